@@ -1,15 +1,38 @@
 use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
 use file_type::FileType;
+use text_encoding::Encoding;
 
 #[derive(Debug, Clone, TypedBuilder, Serialize, Deserialize)]
 pub struct FileInfo {
+    #[serde(with = "path_serde")]
     path: PathBuf,
     r#type: Result<FileType, String>,
+    #[builder(default)]
+    device: Option<u64>,
+    #[builder(default)]
+    mount_point: bool,
+    #[builder(default)]
+    extended_attributes: Option<ExtendedAttributes>,
+    #[builder(default)]
+    modified: Option<SystemTime>,
+    /// The number of bytes in the file if it is a regular file.
+    #[builder(default)]
+    size: Option<u64>,
+    /// The permission bits of the mode of the file (like `0o755`).
+    #[builder(default)]
+    permissions: Option<u32>,
+    /// The encoding of the file if it is a text file.
+    #[builder(default)]
+    encoding: Option<Encoding>,
+    /// Where the file points if it is a symbolic link (and what it points to exists).
+    #[builder(default)]
+    link_target: Option<LinkTarget>,
 }
 
 impl FileInfo {
@@ -24,4 +47,177 @@ impl FileInfo {
     pub fn r#type(&self) -> &Result<FileType, String> {
         &self.r#type
     }
+
+    /// Return the id of the device that the file is on (if it is known).
+    pub fn device(&self) -> Option<u64> {
+        self.device
+    }
+
+    /// Return if the file is a directory on a different device than the directory containing it.
+    pub fn is_mount_point(&self) -> bool {
+        self.mount_point
+    }
+
+    /// Return the data derived from the extended attributes of the file (if it was fetched).
+    pub fn extended_attributes(&self) -> Option<&ExtendedAttributes> {
+        self.extended_attributes.as_ref()
+    }
+
+    /// Set the data derived from the extended attributes of the file.
+    pub fn set_extended_attributes(&mut self, extended_attributes: Option<ExtendedAttributes>) {
+        self.extended_attributes = extended_attributes;
+    }
+
+    /// Return the time the file was last modified at (if it was fetched).
+    pub fn modified(&self) -> Option<SystemTime> {
+        self.modified
+    }
+
+    /// Set the time the file was last modified at.
+    pub fn set_modified(&mut self, modified: Option<SystemTime>) {
+        self.modified = modified;
+    }
+
+    /// Return the number of bytes in the file if it is a regular file (if it was fetched).
+    pub fn size(&self) -> Option<u64> {
+        self.size
+    }
+
+    /// Return the permission bits of the mode of the file (if they were fetched).
+    pub fn permissions(&self) -> Option<u32> {
+        self.permissions
+    }
+
+    /// Return the encoding of the file if it is a text file (if it was fetched).
+    pub fn encoding(&self) -> Option<Encoding> {
+        self.encoding
+    }
+
+    /// Set the encoding of the file.
+    pub fn set_encoding(&mut self, encoding: Option<Encoding>) {
+        self.encoding = encoding;
+    }
+
+    /// Take the details (the extended attributes, the modification time, and the encoding) from
+    /// the info of the same file that was gotten with the details.
+    pub fn take_details(&mut self, details: FileInfo) {
+        self.extended_attributes = details.extended_attributes;
+        self.modified = details.modified;
+        self.encoding = details.encoding;
+    }
+
+    /// Return where the file points if it is a symbolic link (and what it points to exists).
+    pub fn link_target(&self) -> Option<&LinkTarget> {
+        self.link_target.as_ref()
+    }
+
+    /// Return if the file is a directory (or a symbolic link to one).
+    pub fn is_dir(&self) -> bool {
+        self.followed_type() == Some(FileType::Dir)
+    }
+
+    /// Return if the file is a regular file (or a symbolic link to one).
+    pub fn is_file(&self) -> bool {
+        self.followed_type() == Some(FileType::File)
+    }
+
+    /// Return the type of the file with symbolic links followed (if it is known).
+    fn followed_type(&self) -> Option<FileType> {
+        match (&self.r#type, &self.link_target) {
+            (Ok(FileType::Symlink), Some(link_target)) => Some(link_target.r#type),
+            (Ok(FileType::Symlink), None) => None,
+            (Ok(file_type), _) => Some(*file_type),
+            (Err(_), _) => None,
+        }
+    }
+}
+
+/// What a symbolic link points to.
+#[derive(Debug, Clone, TypedBuilder, Serialize, Deserialize)]
+pub struct LinkTarget {
+    /// The physical location of the target (with all of the links resolved).
+    #[serde(with = "path_serde")]
+    path: PathBuf,
+    r#type: FileType,
+}
+
+impl LinkTarget {
+    /// Return the physical location of the target (with all of the links resolved).
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn r#type(&self) -> FileType {
+        self.r#type
+    }
+}
+
+/// Data derived from the extended attributes of a file.
+#[derive(Debug, Clone, Default, TypedBuilder, Serialize, Deserialize)]
+pub struct ExtendedAttributes {
+    /// The tags of the file (Finder tags on macOS and `user.xdg.tags` on Linux).
+    #[builder(default)]
+    tags: Vec<Tag>,
+    /// The names and values of the user extended attributes (`user.*` on Linux).
+    #[builder(default)]
+    attributes: Vec<(String, String)>,
+}
+
+impl ExtendedAttributes {
+    pub fn tags(&self) -> &[Tag] {
+        &self.tags
+    }
+
+    pub fn attributes(&self) -> &[(String, String)] {
+        &self.attributes
+    }
+}
+
+/// A tag of a file.
+#[derive(Debug, Clone, TypedBuilder, Serialize, Deserialize)]
+pub struct Tag {
+    #[builder(setter(into))]
+    name: String,
+    #[builder(default)]
+    color: TagColor,
+}
+
+impl Tag {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn color(&self) -> TagColor {
+        self.color
+    }
+}
+
+/// The color of a tag (the colors are the same as the colors of the Finder tags on macOS).
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TagColor {
+    #[default]
+    None,
+    Gray,
+    Green,
+    Purple,
+    Blue,
+    Yellow,
+    Red,
+    Orange,
+}
+
+impl From<u8> for TagColor {
+    /// Return the tag color for the number that the Finder uses for it.
+    fn from(number: u8) -> Self {
+        match number {
+            1 => Self::Gray,
+            2 => Self::Green,
+            3 => Self::Purple,
+            4 => Self::Blue,
+            5 => Self::Yellow,
+            6 => Self::Red,
+            7 => Self::Orange,
+            _ => Self::None,
+        }
+    }
 }
@@ -1,3 +1,5 @@
+#![allow(clippy::needless_return)]
+
 use serde::{Deserialize, Serialize};
 use std::fs::FileType as StdFileType;
 
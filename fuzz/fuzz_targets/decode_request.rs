@@ -0,0 +1,16 @@
+//! Decode a request (as inshd does with the bytes sent by a client).
+#![no_main]
+
+use insh_api::wire::{decode, decode_length, LENGTH_LEN};
+use insh_api::Request;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|bytes: &[u8]| {
+    if bytes.len() < LENGTH_LEN {
+        return;
+    }
+    let (length, message) = bytes.split_at(LENGTH_LEN);
+    if let Ok(length) = decode_length(length.try_into().unwrap()) {
+        let _ = decode::<Request>(&message[..length.min(message.len())]);
+    }
+});
@@ -0,0 +1,9 @@
+//! Parse terminal input into a terminal event.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use term::TermEvent;
+
+fuzz_target!(|bytes: &[u8]| {
+    let _ = TermEvent::try_from(bytes);
+});
@@ -1,7 +1,11 @@
 #![allow(clippy::needless_return)]
 
+mod path_prefix;
+pub mod wire;
+
 use std::fmt::{Display, Error as FmtError, Formatter};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
@@ -10,6 +14,7 @@ use uuid::Uuid;
 use file_info::FileInfo;
 use file_type::FileType;
 use path_finder::Entry;
+use phrase_searcher::FileHit;
 
 #[derive(Debug, TypedBuilder, Serialize, Deserialize)]
 pub struct Request {
@@ -26,6 +31,12 @@ impl Request {
     pub fn params(&self) -> &RequestParams {
         &self.params
     }
+
+    /// Return the parameters of the request (moving them out so that they can be kept while the
+    /// request is handled).
+    pub fn into_params(self) -> RequestParams {
+        self.params
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -33,23 +44,110 @@ pub enum RequestParams {
     GetFiles(GetFilesRequestParams),
     FindFiles(FindFilesRequestParams),
     CreateFile(CreateFileRequestParams),
+    GetFileDetails(GetFileDetailsRequestParams),
+    ReadFile(ReadFileRequestParams),
+    WriteFile(WriteFileRequestParams),
+    TouchFile(TouchFileRequestParams),
+    GetProcesses(GetProcessesRequestParams),
+    SignalProcess(SignalProcessRequestParams),
+    GetOpenFiles(GetOpenFilesRequestParams),
+    WaitForDevices(WaitForDevicesRequestParams),
+    CancelRequest(CancelRequestRequestParams),
+    SearchPhrase(SearchPhraseRequestParams),
+    DeleteFile(DeleteFileRequestParams),
+    RenameFile(RenameFileRequestParams),
+    CopyFile(TransferFileRequestParams),
+    MoveFile(TransferFileRequestParams),
+}
+
+impl RequestParams {
+    pub fn kind(&self) -> RequestKind {
+        match self {
+            Self::GetFiles(_) => RequestKind::GetFiles,
+            Self::FindFiles(_) => RequestKind::FindFiles,
+            Self::CreateFile(_) => RequestKind::CreateFile,
+            Self::GetFileDetails(_) => RequestKind::GetFileDetails,
+            Self::ReadFile(_) => RequestKind::ReadFile,
+            Self::WriteFile(_) => RequestKind::WriteFile,
+            Self::TouchFile(_) => RequestKind::TouchFile,
+            Self::GetProcesses(_) => RequestKind::GetProcesses,
+            Self::SignalProcess(_) => RequestKind::SignalProcess,
+            Self::GetOpenFiles(_) => RequestKind::GetOpenFiles,
+            Self::WaitForDevices(_) => RequestKind::WaitForDevices,
+            Self::CancelRequest(_) => RequestKind::CancelRequest,
+            Self::SearchPhrase(_) => RequestKind::SearchPhrase,
+            Self::DeleteFile(_) => RequestKind::DeleteFile,
+            Self::RenameFile(_) => RequestKind::RenameFile,
+            Self::CopyFile(_) => RequestKind::CopyFile,
+            Self::MoveFile(_) => RequestKind::MoveFile,
+        }
+    }
 }
 
+/// The kind of a request (and of the responses to it).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RequestKind {
+    GetFiles,
+    FindFiles,
+    CreateFile,
+    GetFileDetails,
+    ReadFile,
+    WriteFile,
+    TouchFile,
+    GetProcesses,
+    SignalProcess,
+    GetOpenFiles,
+    WaitForDevices,
+    CancelRequest,
+    SearchPhrase,
+    DeleteFile,
+    RenameFile,
+    CopyFile,
+    MoveFile,
+}
+
+/// The most levels of subdirectories that a single request to get files descends into.
+pub const MAX_GET_FILES_DEPTH: usize = 8;
+
+/// The most directories that a single request to get files reads (the directory and its
+/// subdirectories).
+pub const MAX_GET_FILES_DIRS: usize = 512;
+
+/// The parameters for getting the files in a directory.
+///
+/// If the depth is more than 0, then the files of the subdirectories are also gotten (breadth
+/// first, one response per directory) down to the depth. The depth is limited to
+/// [`MAX_GET_FILES_DEPTH`] and the number of directories to [`MAX_GET_FILES_DIRS`].
 #[derive(Debug, TypedBuilder, Serialize, Deserialize)]
 pub struct GetFilesRequestParams {
     dir: PathBuf,
+    #[builder(default)]
+    depth: usize,
 }
 
 impl GetFilesRequestParams {
     pub fn dir(&self) -> &Path {
         &self.dir
     }
+
+    /// Return the number of levels of subdirectories whose files are also gotten.
+    pub fn depth(&self) -> usize {
+        self.depth.min(MAX_GET_FILES_DEPTH)
+    }
 }
 
 #[derive(Debug, TypedBuilder, Serialize, Deserialize)]
 pub struct FindFilesRequestParams {
     dir: PathBuf,
     pattern: String,
+    #[builder(default)]
+    same_file_system: bool,
+    /// Whether file names are case folded and normalized before they are matched.
+    #[builder(default)]
+    unicode_matching: bool,
+    /// Whether hidden files and the files ignored by ignore files (like `.gitignore`) are skipped.
+    #[builder(default)]
+    respect_ignore: bool,
 }
 
 impl FindFilesRequestParams {
@@ -60,12 +158,63 @@ impl FindFilesRequestParams {
     pub fn pattern(&self) -> &str {
         &self.pattern
     }
+
+    /// Return if finding files should not cross into other file systems.
+    pub fn same_file_system(&self) -> bool {
+        self.same_file_system
+    }
+
+    /// Return if file names are case folded and normalized before they are matched.
+    pub fn unicode_matching(&self) -> bool {
+        self.unicode_matching
+    }
+
+    /// Return if hidden files and the files ignored by ignore files are skipped.
+    pub fn respect_ignore(&self) -> bool {
+        self.respect_ignore
+    }
+}
+
+/// The parameters for searching the contents of the files in a directory (and its subdirectories)
+/// for a phrase.
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct SearchPhraseRequestParams {
+    dir: PathBuf,
+    phrase: String,
+    #[builder(default)]
+    same_file_system: bool,
+    /// Whether the phrase and the lines are case folded and normalized before they are matched.
+    #[builder(default)]
+    unicode_matching: bool,
+}
+
+impl SearchPhraseRequestParams {
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn phrase(&self) -> &str {
+        &self.phrase
+    }
+
+    /// Return if searching should not cross into other file systems.
+    pub fn same_file_system(&self) -> bool {
+        self.same_file_system
+    }
+
+    /// Return if the phrase and the lines are case folded and normalized before they are matched.
+    pub fn unicode_matching(&self) -> bool {
+        self.unicode_matching
+    }
 }
 
 #[derive(Debug, TypedBuilder, Serialize, Deserialize)]
 pub struct CreateFileRequestParams {
     path: PathBuf,
     file_type: FileType,
+    /// Whether missing parent directories should be created (like `mkdir -p`).
+    #[builder(default)]
+    parents: bool,
 }
 
 impl CreateFileRequestParams {
@@ -76,6 +225,321 @@ impl CreateFileRequestParams {
     pub fn file_type(&self) -> FileType {
         self.file_type
     }
+
+    /// Return whether missing parent directories should be created (like `mkdir -p`).
+    pub fn parents(&self) -> bool {
+        self.parents
+    }
+}
+
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct GetFileDetailsRequestParams {
+    paths: Vec<PathBuf>,
+}
+
+impl GetFileDetailsRequestParams {
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+}
+
+/// The largest chunk (in bytes) that is read by a single request to read a file.
+pub const MAX_READ_FILE_LEN: u64 = 4 * 1024 * 1024;
+
+/// The longest that a single request to read a file waits for the file to grow.
+pub const MAX_READ_FILE_WAIT: Duration = Duration::from_secs(5);
+
+/// The parameters for reading a chunk of a file.
+///
+/// The length of the chunk is limited to [`MAX_READ_FILE_LEN`] bytes.
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct ReadFileRequestParams {
+    path: PathBuf,
+    /// The offset (in bytes) of the start of the chunk.
+    #[builder(default)]
+    offset: u64,
+    /// The length (in bytes) of the chunk.
+    len: u64,
+    /// How long to wait for the file to grow if the offset is the end of the file (for following a
+    /// file that is appended to). The wait is limited to [`MAX_READ_FILE_WAIT`].
+    #[builder(default)]
+    wait: Option<Duration>,
+}
+
+impl ReadFileRequestParams {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Return the offset (in bytes) of the start of the chunk.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Return the length (in bytes) of the chunk.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Return if the chunk is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return how long to wait for the file to grow if the offset is the end of the file.
+    pub fn wait(&self) -> Option<Duration> {
+        self.wait
+    }
+}
+
+/// The parameters for writing a file.
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct WriteFileRequestParams {
+    path: PathBuf,
+    content: WriteFileContent,
+    /// The time the file is expected to have been last modified at (if the write should fail when
+    /// the file was modified since it was read).
+    #[builder(default)]
+    expected_mtime: Option<SystemTime>,
+    /// Whether the line endings and the byte order mark of the file are kept (the content is
+    /// converted to them).
+    ///
+    /// NOTE: This is only handled by inshd for now. insh does not write files yet, so nothing sets
+    /// it or shows the reason in [`WrittenFile::format_not_preserved`].
+    #[builder(default)]
+    preserve_format: bool,
+}
+
+impl WriteFileRequestParams {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn content(&self) -> &WriteFileContent {
+        &self.content
+    }
+
+    /// Return the time the file is expected to have been last modified at.
+    pub fn expected_mtime(&self) -> Option<SystemTime> {
+        self.expected_mtime
+    }
+
+    /// Return whether the line endings and the byte order mark of the file are kept.
+    pub fn preserve_format(&self) -> bool {
+        self.preserve_format
+    }
+}
+
+/// The content to write to a file.
+#[derive(Debug, Serialize, Deserialize)]
+pub enum WriteFileContent {
+    /// The full content of the file.
+    Full(Vec<u8>),
+    /// Patches to apply to the current content of the file (sorted by offset and not overlapping).
+    Patches(Vec<FilePatch>),
+}
+
+/// A patch which replaces a range of the bytes of a file.
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct FilePatch {
+    /// The offset (in bytes) of the start of the range to replace.
+    offset: u64,
+    /// The length (in bytes) of the range to replace.
+    len: u64,
+    /// The bytes to replace the range with.
+    bytes: Vec<u8>,
+}
+
+impl FilePatch {
+    /// Return the offset (in bytes) of the start of the range to replace.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Return the length (in bytes) of the range to replace.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Return if the range to replace is empty (meaning the bytes are inserted).
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the bytes to replace the range with.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+/// The parameters for creating a file if it does not exist or else updating the time it was last
+/// modified at (like `touch`).
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct TouchFileRequestParams {
+    path: PathBuf,
+}
+
+impl TouchFileRequestParams {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// The parameters for deleting a file (or a directory).
+///
+/// A directory is only deleted if it is empty unless the deletion is recursive (which deletes
+/// everything in it too). Symbolic links are deleted rather than what they point to.
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct DeleteFileRequestParams {
+    path: PathBuf,
+    #[builder(default)]
+    recursive: bool,
+}
+
+impl DeleteFileRequestParams {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn recursive(&self) -> bool {
+        self.recursive
+    }
+}
+
+/// The parameters for renaming (or moving) a file.
+///
+/// A file that already exists at the new path is never replaced.
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct RenameFileRequestParams {
+    from: PathBuf,
+    to: PathBuf,
+}
+
+impl RenameFileRequestParams {
+    pub fn from(&self) -> &Path {
+        &self.from
+    }
+
+    pub fn to(&self) -> &Path {
+        &self.to
+    }
+}
+
+/// The parameters for copying or moving a file (and the files in it if it is a directory).
+///
+/// A file that already exists at the new path is never replaced.
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct TransferFileRequestParams {
+    from: PathBuf,
+    to: PathBuf,
+}
+
+impl TransferFileRequestParams {
+    pub fn from(&self) -> &Path {
+        &self.from
+    }
+
+    pub fn to(&self) -> &Path {
+        &self.to
+    }
+}
+
+/// The parameters for getting the processes whose working directory is in a directory (or one of
+/// its descendants).
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct GetProcessesRequestParams {
+    dir: PathBuf,
+}
+
+impl GetProcessesRequestParams {
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+/// The parameters for sending a signal to a process.
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct SignalProcessRequestParams {
+    pid: u32,
+    signal: Signal,
+}
+
+impl SignalProcessRequestParams {
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    pub fn signal(&self) -> Signal {
+        self.signal
+    }
+}
+
+/// The parameters for getting the files in a directory (or one of its descendants) that are open
+/// by processes (like `lsof +D`).
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct GetOpenFilesRequestParams {
+    dir: PathBuf,
+}
+
+impl GetOpenFilesRequestParams {
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+/// The longest that a single request to wait for devices waits for a device to be mounted or
+/// unmounted.
+pub const MAX_WAIT_FOR_DEVICES: Duration = Duration::from_secs(5);
+
+/// The parameters for waiting for a device (or a network share) to be mounted or unmounted.
+///
+/// The wait is limited to [`MAX_WAIT_FOR_DEVICES`].
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct WaitForDevicesRequestParams {
+    wait: Duration,
+}
+
+impl WaitForDevicesRequestParams {
+    pub fn wait(&self) -> Duration {
+        self.wait
+    }
+}
+
+/// The parameters for cancelling a request that is in flight (such as finding files), which stops
+/// the work for it and any more responses to it.
+///
+/// There is no response to cancelling a request.
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct CancelRequestRequestParams {
+    /// The uuid of the request to cancel.
+    request: Uuid,
+}
+
+impl CancelRequestRequestParams {
+    pub fn request(&self) -> &Uuid {
+        &self.request
+    }
+}
+
+/// A signal that can be sent to a process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Signal {
+    /// `SIGINT` (like pressing `<Ctrl>-c`).
+    Interrupt,
+    /// `SIGTERM` (ask the process to exit).
+    Terminate,
+    /// `SIGKILL` (force the process to exit).
+    Kill,
+}
+
+impl Display for Signal {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::Interrupt => write!(formatter, "SIGINT"),
+            Self::Terminate => write!(formatter, "SIGTERM"),
+            Self::Kill => write!(formatter, "SIGKILL"),
+        }
+    }
 }
 
 #[derive(Debug, TypedBuilder, Serialize, Deserialize)]
@@ -95,9 +559,21 @@ impl Response {
         self.last
     }
 
+    /// Change the uuid of the request that this responds to (used for replaying a recorded
+    /// response to a new request).
+    pub fn set_uuid(&mut self, uuid: Uuid) {
+        self.uuid = uuid;
+    }
+
     pub fn params(&self) -> &ResponseParams {
         &self.params
     }
+
+    /// Return the parameters of the response (moving them out so that large results aren't
+    /// copied).
+    pub fn into_params(self) -> ResponseParams {
+        self.params
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -105,6 +581,47 @@ pub enum ResponseParams {
     GetFiles(GetFilesResponseParams),
     FindFiles(FindFilesResponseParams),
     CreateFile(CreateFileResponseParams),
+    GetFileDetails(GetFileDetailsResponseParams),
+    ReadFile(ReadFileResponseParams),
+    WriteFile(WriteFileResponseParams),
+    TouchFile(TouchFileResponseParams),
+    GetProcesses(GetProcessesResponseParams),
+    SignalProcess(SignalProcessResponseParams),
+    GetOpenFiles(GetOpenFilesResponseParams),
+    WaitForDevices(WaitForDevicesResponseParams),
+    BadRequest(BadRequestResponseParams),
+    SearchPhrase(SearchPhraseResponseParams),
+    DeleteFile(DeleteFileResponseParams),
+    RenameFile(RenameFileResponseParams),
+    CopyFile(TransferFileResponseParams),
+    MoveFile(TransferFileResponseParams),
+}
+
+impl ResponseParams {
+    /// Return the kind of the request that this responds to (or `None` if it can respond to any
+    /// kind of request, like a response to a bad request).
+    pub fn kind(&self) -> Option<RequestKind> {
+        let kind: RequestKind = match self {
+            Self::GetFiles(_) => RequestKind::GetFiles,
+            Self::FindFiles(_) => RequestKind::FindFiles,
+            Self::CreateFile(_) => RequestKind::CreateFile,
+            Self::GetFileDetails(_) => RequestKind::GetFileDetails,
+            Self::ReadFile(_) => RequestKind::ReadFile,
+            Self::WriteFile(_) => RequestKind::WriteFile,
+            Self::TouchFile(_) => RequestKind::TouchFile,
+            Self::GetProcesses(_) => RequestKind::GetProcesses,
+            Self::SignalProcess(_) => RequestKind::SignalProcess,
+            Self::GetOpenFiles(_) => RequestKind::GetOpenFiles,
+            Self::WaitForDevices(_) => RequestKind::WaitForDevices,
+            Self::SearchPhrase(_) => RequestKind::SearchPhrase,
+            Self::DeleteFile(_) => RequestKind::DeleteFile,
+            Self::RenameFile(_) => RequestKind::RenameFile,
+            Self::CopyFile(_) => RequestKind::CopyFile,
+            Self::MoveFile(_) => RequestKind::MoveFile,
+            Self::BadRequest(_) => return None,
+        };
+        Some(kind)
+    }
 }
 
 #[derive(Debug, TypedBuilder)]
@@ -115,13 +632,77 @@ pub struct ResponseParamsAndLast {
 
 #[derive(Debug, TypedBuilder, Serialize, Deserialize)]
 pub struct GetFilesResponseParams {
+    /// The directory that the files are in (which is a subdirectory of the requested directory for
+    /// all but the first response if the depth of the request is more than 0).
+    #[builder(default)]
+    dir: PathBuf,
     result: GetFilesResult,
+    #[builder(default)]
+    slow_file_system: bool,
+    /// The numbers of files in the directory (if they were gotten).
+    #[builder(default)]
+    summary: Option<FilesSummary>,
+    /// The physical location of the directory (with all of the symbolic links resolved) if it
+    /// differs from the directory.
+    #[builder(default)]
+    physical_dir: Option<PathBuf>,
 }
 
 impl GetFilesResponseParams {
+    /// Return the directory that the files are in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
     pub fn result(&self) -> &GetFilesResult {
         &self.result
     }
+
+    pub fn into_result(self) -> GetFilesResult {
+        self.result
+    }
+
+    /// Return if the directory is on a slow file system (in which case less information about the
+    /// files is returned).
+    pub fn slow_file_system(&self) -> bool {
+        self.slow_file_system
+    }
+
+    /// Return the numbers of files in the directory (if they were gotten).
+    pub fn summary(&self) -> Option<FilesSummary> {
+        self.summary
+    }
+
+    /// Return the physical location of the directory if it differs from the directory.
+    pub fn physical_dir(&self) -> Option<&Path> {
+        self.physical_dir.as_deref()
+    }
+}
+
+/// The numbers of files in a directory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, TypedBuilder, Serialize, Deserialize)]
+pub struct FilesSummary {
+    /// The number of entries that are not directories.
+    files: usize,
+    dirs: usize,
+    /// The number of entries (files or directories) whose names start with a `.`.
+    hidden: usize,
+}
+
+impl FilesSummary {
+    /// Return the number of entries that are not directories.
+    pub fn files(&self) -> usize {
+        self.files
+    }
+
+    pub fn dirs(&self) -> usize {
+        self.dirs
+    }
+
+    /// Return the number of entries whose names start with a `.`.
+    pub fn hidden(&self) -> usize {
+        self.hidden
+    }
 }
 
 pub type GetFilesResult = Result<Vec<FileInfo>, GetFilesError>;
@@ -129,15 +710,52 @@ pub type GetFilesResult = Result<Vec<FileInfo>, GetFilesError>;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum GetFilesError {
     DirDoesNotExist,
+    /// Permission was denied for an unknown reason.
     PermissionDenied,
+    /// The mode of the directory does not allow reading it.
+    NoReadPermission,
+    /// The mode of the directory `dir` (the directory or one of its ancestors) does not allow
+    /// searching it (it is missing the execute bit).
+    NoSearchPermission {
+        dir: PathBuf,
+    },
+    /// The modes allow reading the directory but a security policy (such as SELinux) denied it.
+    SecurityPolicy,
     OtherErrorReading(String),
 }
 
+impl GetFilesError {
+    /// Return if reading the directory failed because permission was denied.
+    pub fn is_permission_denied(&self) -> bool {
+        matches!(
+            self,
+            Self::PermissionDenied
+                | Self::NoReadPermission
+                | Self::NoSearchPermission { .. }
+                | Self::SecurityPolicy
+        )
+    }
+}
+
 impl Display for GetFilesError {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
         match self {
             Self::DirDoesNotExist => write!(formatter, "The directory does not exist."),
             Self::PermissionDenied => write!(formatter, "Permission denied."),
+            Self::NoReadPermission => write!(
+                formatter,
+                "Permission denied. You do not have read permission for the directory."
+            ),
+            Self::NoSearchPermission { dir } => write!(
+                formatter,
+                "Permission denied. You do not have search (execute) permission for {}.",
+                dir.display()
+            ),
+            Self::SecurityPolicy => write!(
+                formatter,
+                "Permission denied by a security policy (such as SELinux) even though the \
+                 permissions of the directory allow reading it."
+            ),
             Self::OtherErrorReading(string) => write!(formatter, "{}", string),
         }
     }
@@ -145,6 +763,8 @@ impl Display for GetFilesError {
 
 #[derive(Debug, TypedBuilder, Serialize, Deserialize)]
 pub struct FindFilesResponseParams {
+    /// The found files (the paths are prefix compressed in the wire format).
+    #[serde(with = "path_prefix")]
     entries: Vec<Entry>,
 }
 
@@ -153,12 +773,33 @@ impl FindFilesResponseParams {
         &self.entries
     }
 
+    pub fn into_entries(self) -> Vec<Entry> {
+        self.entries
+    }
+
     pub fn is_empty(&self) -> bool {
         return self.entries.is_empty();
     }
 }
 
-pub type CreateFileResult = Result<(), CreateFileError>;
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct SearchPhraseResponseParams {
+    /// The files with lines containing the phrase (in the order that the files were walked in).
+    file_hits: Vec<FileHit>,
+}
+
+impl SearchPhraseResponseParams {
+    pub fn file_hits(&self) -> &Vec<FileHit> {
+        &self.file_hits
+    }
+
+    pub fn into_file_hits(self) -> Vec<FileHit> {
+        self.file_hits
+    }
+}
+
+/// The result of creating a file is the path of the created file.
+pub type CreateFileResult = Result<PathBuf, CreateFileError>;
 
 #[derive(Debug, TypedBuilder, Serialize, Deserialize)]
 pub struct CreateFileResponseParams {
@@ -174,6 +815,7 @@ impl CreateFileResponseParams {
 #[derive(Debug, Serialize, Deserialize)]
 pub enum CreateFileError {
     AlreadyExists(PathBuf),
+    PermissionDenied,
     UnsupportedFileType(FileType),
     Other(String),
 }
@@ -186,6 +828,7 @@ impl Display for CreateFileError {
                 "The file {:?} already exists.",
                 filepath.file_name()
             ),
+            Self::PermissionDenied => write!(formatter, "Permission denied."),
             Self::UnsupportedFileType(file_type) => write!(
                 formatter,
                 "Create a file of the type {:?} is not supported",
@@ -196,3 +839,558 @@ impl Display for CreateFileError {
         }
     }
 }
+
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct GetFileDetailsResponseParams {
+    file_infos: Vec<FileInfo>,
+}
+
+impl GetFileDetailsResponseParams {
+    pub fn file_infos(&self) -> &[FileInfo] {
+        &self.file_infos
+    }
+
+    pub fn into_file_infos(self) -> Vec<FileInfo> {
+        self.file_infos
+    }
+}
+
+pub type ReadFileResult = Result<FileChunk, ReadFileError>;
+
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct ReadFileResponseParams {
+    result: ReadFileResult,
+}
+
+impl ReadFileResponseParams {
+    pub fn result(&self) -> &ReadFileResult {
+        &self.result
+    }
+}
+
+/// A chunk of the contents of a file.
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct FileChunk {
+    /// The offset (in bytes) of the start of the chunk.
+    offset: u64,
+    bytes: Vec<u8>,
+    /// The size (in bytes) of the whole file.
+    file_size: u64,
+}
+
+impl FileChunk {
+    /// Return the offset (in bytes) of the start of the chunk.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Return the size (in bytes) of the whole file.
+    pub fn file_size(&self) -> u64 {
+        self.file_size
+    }
+
+    /// Return if the chunk reaches the end of the file.
+    pub fn eof(&self) -> bool {
+        self.offset + self.bytes.len() as u64 >= self.file_size
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReadFileError {
+    FileDoesNotExist,
+    PermissionDenied,
+    /// The path is not a regular file (such as a directory or a named pipe).
+    NotAFile,
+    /// The offset of the chunk is past the end of the file.
+    OffsetPastEnd {
+        offset: u64,
+        file_size: u64,
+    },
+    Other(String),
+}
+
+impl Display for ReadFileError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::FileDoesNotExist => write!(formatter, "The file does not exist."),
+            Self::PermissionDenied => write!(formatter, "Permission denied."),
+            Self::NotAFile => write!(formatter, "The path is not a regular file."),
+            Self::OffsetPastEnd { offset, file_size } => write!(
+                formatter,
+                "The offset {} is past the end of the file ({} bytes).",
+                offset, file_size
+            ),
+            Self::Other(string) => write!(formatter, "{}", string),
+        }
+    }
+}
+
+pub type WriteFileResult = Result<WrittenFile, WriteFileError>;
+
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct WriteFileResponseParams {
+    result: WriteFileResult,
+}
+
+impl WriteFileResponseParams {
+    pub fn result(&self) -> &WriteFileResult {
+        &self.result
+    }
+}
+
+/// A file that was written.
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct WrittenFile {
+    /// The time the file was modified at by the write.
+    mtime: SystemTime,
+    /// The size (in bytes) of the file after the write.
+    file_size: u64,
+    /// Why the format of the file could not be kept (if it was supposed to be kept).
+    #[builder(default)]
+    format_not_preserved: Option<FormatNotPreserved>,
+}
+
+impl WrittenFile {
+    /// Return the time the file was modified at by the write.
+    pub fn mtime(&self) -> SystemTime {
+        self.mtime
+    }
+
+    /// Return the size (in bytes) of the file after the write.
+    pub fn file_size(&self) -> u64 {
+        self.file_size
+    }
+
+    /// Return why the format of the file could not be kept (if it was supposed to be kept).
+    pub fn format_not_preserved(&self) -> Option<&FormatNotPreserved> {
+        self.format_not_preserved.as_ref()
+    }
+}
+
+/// Why the format of a file could not be kept when it was written (the content was written as it
+/// was given).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FormatNotPreserved {
+    /// The file mixed line endings, so there was no single line ending to convert to.
+    MixedLineEndings,
+    /// The file started with a UTF-16 byte order mark (and the content is not converted to UTF-16).
+    Utf16,
+}
+
+impl Display for FormatNotPreserved {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::MixedLineEndings => write!(
+                formatter,
+                "The file mixed line endings so they were written as given."
+            ),
+            Self::Utf16 => write!(
+                formatter,
+                "The file was UTF-16 so the content was written as given."
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WriteFileError {
+    FileDoesNotExist,
+    PermissionDenied,
+    /// The path is not a regular file (such as a directory or a named pipe).
+    NotAFile,
+    /// The file was modified (or removed) since it was read.
+    Conflict {
+        actual_mtime: Option<SystemTime>,
+    },
+    /// The patches are out of order, overlap, or are out of the range of the file.
+    InvalidPatch(String),
+    Other(String),
+}
+
+impl Display for WriteFileError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::FileDoesNotExist => write!(formatter, "The file does not exist."),
+            Self::PermissionDenied => write!(formatter, "Permission denied."),
+            Self::NotAFile => write!(formatter, "The path is not a regular file."),
+            Self::Conflict { actual_mtime: None } => {
+                write!(formatter, "The file was removed since it was read.")
+            }
+            Self::Conflict { .. } => write!(formatter, "The file was modified since it was read."),
+            Self::InvalidPatch(string) => write!(formatter, "Invalid patch: {}", string),
+            Self::Other(string) => write!(formatter, "{}", string),
+        }
+    }
+}
+
+/// The result of touching a file is the new time the file was last modified at.
+pub type TouchFileResult = Result<SystemTime, TouchFileError>;
+
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct TouchFileResponseParams {
+    path: PathBuf,
+    result: TouchFileResult,
+}
+
+impl TouchFileResponseParams {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn result(&self) -> &TouchFileResult {
+        &self.result
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TouchFileError {
+    PermissionDenied,
+    Other(String),
+}
+
+impl Display for TouchFileError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::PermissionDenied => write!(formatter, "Permission denied."),
+            Self::Other(string) => write!(formatter, "{}", string),
+        }
+    }
+}
+
+pub type DeleteFileResult = Result<(), DeleteFileError>;
+
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct DeleteFileResponseParams {
+    path: PathBuf,
+    recursive: bool,
+    result: DeleteFileResult,
+}
+
+impl DeleteFileResponseParams {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn recursive(&self) -> bool {
+        self.recursive
+    }
+
+    pub fn result(&self) -> &DeleteFileResult {
+        &self.result
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeleteFileError {
+    FileDoesNotExist,
+    PermissionDenied,
+    /// The directory is not empty (and the deletion is not recursive).
+    DirectoryNotEmpty,
+    Other(String),
+}
+
+impl Display for DeleteFileError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::FileDoesNotExist => write!(formatter, "The file does not exist."),
+            Self::PermissionDenied => write!(formatter, "Permission denied."),
+            Self::DirectoryNotEmpty => write!(formatter, "The directory is not empty."),
+            Self::Other(string) => write!(formatter, "{}", string),
+        }
+    }
+}
+
+pub type RenameFileResult = Result<(), RenameFileError>;
+
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct RenameFileResponseParams {
+    from: PathBuf,
+    to: PathBuf,
+    result: RenameFileResult,
+}
+
+impl RenameFileResponseParams {
+    pub fn from(&self) -> &Path {
+        &self.from
+    }
+
+    pub fn to(&self) -> &Path {
+        &self.to
+    }
+
+    pub fn result(&self) -> &RenameFileResult {
+        &self.result
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RenameFileError {
+    FileDoesNotExist,
+    PermissionDenied,
+    /// A file already exists at the new path.
+    AlreadyExists,
+    Other(String),
+}
+
+impl Display for RenameFileError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::FileDoesNotExist => write!(formatter, "The file does not exist."),
+            Self::PermissionDenied => write!(formatter, "Permission denied."),
+            Self::AlreadyExists => write!(formatter, "A file already exists at the new path."),
+            Self::Other(string) => write!(formatter, "{}", string),
+        }
+    }
+}
+
+/// The progress of copying or moving a file (the responses before the last response are sent
+/// while a large file is copied, and the last response is sent once it is done).
+pub type TransferFileResult = Result<TransferProgress, TransferFileError>;
+
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct TransferFileResponseParams {
+    from: PathBuf,
+    to: PathBuf,
+    result: TransferFileResult,
+}
+
+impl TransferFileResponseParams {
+    pub fn from(&self) -> &Path {
+        &self.from
+    }
+
+    pub fn to(&self) -> &Path {
+        &self.to
+    }
+
+    pub fn result(&self) -> &TransferFileResult {
+        &self.result
+    }
+}
+
+/// How many bytes of the files were copied so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TypedBuilder, Serialize, Deserialize)]
+pub struct TransferProgress {
+    copied: u64,
+    total: u64,
+}
+
+impl TransferProgress {
+    pub fn copied(&self) -> u64 {
+        self.copied
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Return the percentage of the bytes that were copied.
+    pub fn percent(&self) -> u64 {
+        match self.total {
+            0 => 100,
+            total => self.copied.min(total) * 100 / total,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransferFileError {
+    FileDoesNotExist,
+    PermissionDenied,
+    /// A file already exists at the new path.
+    AlreadyExists,
+    /// The new path is inside of the directory that is copied or moved.
+    IntoItself,
+    Other(String),
+}
+
+impl Display for TransferFileError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::FileDoesNotExist => write!(formatter, "The file does not exist."),
+            Self::PermissionDenied => write!(formatter, "Permission denied."),
+            Self::AlreadyExists => write!(formatter, "A file already exists at the new path."),
+            Self::IntoItself => write!(formatter, "A directory can't be put inside of itself."),
+            Self::Other(string) => write!(formatter, "{}", string),
+        }
+    }
+}
+
+/// A process whose working directory is in a directory.
+#[derive(Debug, Clone, PartialEq, Eq, TypedBuilder, Serialize, Deserialize)]
+pub struct ProcessInfo {
+    pid: u32,
+    /// The command line of the process (or its name if the command line is not known).
+    command: String,
+    /// The working directory of the process.
+    #[serde(with = "path_serde")]
+    cwd: PathBuf,
+}
+
+impl ProcessInfo {
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Return the command line of the process (or its name if the command line is not known).
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// Return the working directory of the process.
+    pub fn cwd(&self) -> &Path {
+        &self.cwd
+    }
+}
+
+/// The result of getting processes is the processes (ordered by their pids).
+pub type GetProcessesResult = Result<Vec<ProcessInfo>, GetProcessesError>;
+
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct GetProcessesResponseParams {
+    result: GetProcessesResult,
+}
+
+impl GetProcessesResponseParams {
+    pub fn result(&self) -> &GetProcessesResult {
+        &self.result
+    }
+
+    pub fn into_result(self) -> GetProcessesResult {
+        self.result
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GetProcessesError {
+    /// Listing processes is not supported on this platform.
+    Unsupported,
+    Other(String),
+}
+
+impl Display for GetProcessesError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::Unsupported => write!(
+                formatter,
+                "Listing processes is not supported on this platform."
+            ),
+            Self::Other(string) => write!(formatter, "{}", string),
+        }
+    }
+}
+
+pub type SignalProcessResult = Result<(), SignalProcessError>;
+
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct SignalProcessResponseParams {
+    pid: u32,
+    signal: Signal,
+    result: SignalProcessResult,
+}
+
+impl SignalProcessResponseParams {
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    pub fn signal(&self) -> Signal {
+        self.signal
+    }
+
+    pub fn result(&self) -> &SignalProcessResult {
+        &self.result
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignalProcessError {
+    NoSuchProcess,
+    PermissionDenied,
+    Other(String),
+}
+
+impl Display for SignalProcessError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::NoSuchProcess => write!(formatter, "The process no longer exists."),
+            Self::PermissionDenied => write!(formatter, "Permission denied."),
+            Self::Other(string) => write!(formatter, "{}", string),
+        }
+    }
+}
+
+/// A file that is open by a process.
+#[derive(Debug, Clone, PartialEq, Eq, TypedBuilder, Serialize, Deserialize)]
+pub struct OpenFile {
+    #[serde(with = "path_serde")]
+    path: PathBuf,
+    pid: u32,
+    /// The command line of the process.
+    command: String,
+}
+
+impl OpenFile {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Return the command line of the process.
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+}
+
+/// The result of getting open files is the open files (ordered by their paths and then by their
+/// pids).
+pub type GetOpenFilesResult = Result<Vec<OpenFile>, GetProcessesError>;
+
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct GetOpenFilesResponseParams {
+    result: GetOpenFilesResult,
+}
+
+impl GetOpenFilesResponseParams {
+    pub fn result(&self) -> &GetOpenFilesResult {
+        &self.result
+    }
+
+    pub fn into_result(self) -> GetOpenFilesResult {
+        self.result
+    }
+}
+
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct WaitForDevicesResponseParams {
+    /// Whether a device was mounted or unmounted while waiting.
+    changed: bool,
+}
+
+impl WaitForDevicesResponseParams {
+    /// Return whether a device was mounted or unmounted while waiting.
+    pub fn changed(&self) -> bool {
+        self.changed
+    }
+}
+
+/// The parameters of the response to a request that inshd could not decode.
+#[derive(Debug, TypedBuilder, Serialize, Deserialize)]
+pub struct BadRequestResponseParams {
+    /// Why the request could not be decoded.
+    error: String,
+}
+
+impl BadRequestResponseParams {
+    /// Return why the request could not be decoded.
+    pub fn error(&self) -> &str {
+        &self.error
+    }
+}
@@ -0,0 +1,119 @@
+//! Prefix compression of the paths of found files in the wire format.
+//!
+//! The paths of found files repeat long directory prefixes (all of the files in a deep directory
+//! share it). Instead of sending each full path, consecutive paths in the same directory are sent
+//! as the directory (once) followed by the names of the files, and the full paths are reconstructed
+//! when they are decoded.
+//!
+//! The directories and the names are borrowed from the decoded bytes, so each full path is the only
+//! copy that decoding makes.
+use std::borrow::Cow;
+use std::ffi::{OsStr, OsString};
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use path_finder::Entry;
+
+/// A directory (including the trailing separator) and the names of files in it.
+#[derive(Serialize, Deserialize)]
+struct Group<'a> {
+    #[serde(borrow, with = "path_serde::cow")]
+    dir: Cow<'a, Path>,
+    #[serde(borrow)]
+    names: Vec<Name<'a>>,
+}
+
+/// The name of a file in a group.
+#[derive(Serialize, Deserialize)]
+struct Name<'a>(#[serde(borrow, with = "path_serde::cow")] Cow<'a, Path>);
+
+/// Split the bytes of a path after the last separator (so that joining the parts is exactly the
+/// path).
+fn split(path: &Path) -> (&Path, &Path) {
+    let bytes: &[u8] = path.as_os_str().as_bytes();
+    let split: usize = match bytes.iter().rposition(|byte| *byte == b'/') {
+        Some(separator) => separator + 1,
+        None => 0,
+    };
+    let (dir, name) = bytes.split_at(split);
+    (
+        Path::new(OsStr::from_bytes(dir)),
+        Path::new(OsStr::from_bytes(name)),
+    )
+}
+
+/// Serialize entries (grouping consecutive entries in the same directory).
+pub fn serialize<S: Serializer>(entries: &[Entry], serializer: S) -> Result<S::Ok, S::Error> {
+    let mut groups: Vec<Group> = Vec::new();
+    for entry in entries {
+        let (dir, name) = split(entry.path());
+        let name = Name(Cow::Borrowed(name));
+        match groups.last_mut() {
+            Some(group) if group.dir == dir => group.names.push(name),
+            _ => groups.push(Group {
+                dir: Cow::Borrowed(dir),
+                names: vec![name],
+            }),
+        }
+    }
+    serializer.collect_seq(groups)
+}
+
+/// Deserialize entries (reconstructing the full paths).
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Entry>, D::Error> {
+    let groups: Vec<Group> = Vec::deserialize(deserializer)?;
+    let mut entries: Vec<Entry> =
+        Vec::with_capacity(groups.iter().map(|group| group.names.len()).sum());
+    for group in groups {
+        let dir: &[u8] = group.dir.as_os_str().as_bytes();
+        for Name(name) in group.names {
+            let name: &[u8] = name.as_os_str().as_bytes();
+            let mut path: Vec<u8> = Vec::with_capacity(dir.len() + name.len());
+            path.extend_from_slice(dir);
+            path.extend_from_slice(name);
+            entries.push(Entry::from(PathBuf::from(OsString::from_vec(path))));
+        }
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FindFilesResponseParams;
+    use test_case::test_case;
+
+    #[test_case(&["/tmp/a", "/tmp/b", "/tmp/c/d", "/tmp/e"]; "groups")]
+    #[test_case(&["/", "a", "a/", "a//b", ""]; "odd paths")]
+    fn test_round_trip(paths: &[&str]) {
+        let entries: Vec<Entry> = paths
+            .iter()
+            .map(|path| Entry::from(PathBuf::from(path)))
+            .collect();
+        let params = FindFilesResponseParams::builder().entries(entries).build();
+
+        let bytes: Vec<u8> = bincode::serialize(&params).unwrap();
+        let decoded: FindFilesResponseParams = bincode::deserialize(&bytes).unwrap();
+
+        let decoded_paths: Vec<&Path> = decoded.entries().iter().map(Entry::path).collect();
+        let expected_paths: Vec<&Path> = paths.iter().map(Path::new).collect();
+        assert_eq!(decoded_paths, expected_paths);
+    }
+
+    #[test]
+    fn test_smaller() {
+        let dir: String = "/deep".repeat(20);
+        let paths: Vec<PathBuf> = (0..100)
+            .map(|number| PathBuf::from(format!("{}/{}.txt", dir, number)))
+            .collect();
+        let entries: Vec<Entry> = paths.iter().cloned().map(Entry::from).collect();
+        let params = FindFilesResponseParams::builder().entries(entries).build();
+
+        let compressed_len: usize = bincode::serialize(&params).unwrap().len();
+        let uncompressed_len: usize = bincode::serialize(&paths).unwrap().len();
+
+        assert!(compressed_len * 5 < uncompressed_len);
+    }
+}
@@ -0,0 +1,135 @@
+//! Decoding of the messages (requests and responses) sent between insh and inshd.
+//!
+//! Each message is sent as its length (a big endian `u64`) followed by the message serialized with
+//! bincode. The bytes come from another process, so decoding must not panic or allocate without
+//! bound on malformed input.
+use std::fmt::{Display, Error as FmtError, Formatter};
+
+use bincode::Options;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use uuid::Uuid;
+
+/// The length (in bytes) of the length that precedes each message.
+pub const LENGTH_LEN: usize = 8;
+
+/// The largest message (in bytes) that is decoded.
+pub const MAX_MESSAGE_LEN: u64 = 64 * 1024 * 1024;
+
+/// Decode the length that precedes a message.
+pub fn decode_length(bytes: [u8; LENGTH_LEN]) -> Result<usize, DecodeError> {
+    let length: u64 = u64::from_be_bytes(bytes);
+    if length > MAX_MESSAGE_LEN {
+        return Err(DecodeError::TooLong(length));
+    }
+    Ok(length as usize)
+}
+
+/// Decode a message (without the length that precedes it).
+pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, DecodeError> {
+    // NOTE: These are the options used by `bincode::serialize` but with a limit on the size of the
+    // message so that a bogus length inside of the message can not allocate too much memory.
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+        .with_limit(MAX_MESSAGE_LEN)
+        .deserialize(bytes)
+        .map_err(|error| DecodeError::Malformed(error.to_string()))
+}
+
+/// The start of a serialized request (so that the uuid of a malformed request can be decoded).
+#[derive(Deserialize)]
+struct RequestHeader {
+    uuid: Uuid,
+}
+
+/// Decode the uuid of a request from the start of the request (if the request is malformed but the
+/// uuid is not, then the error can still be sent as the response to the request).
+pub fn decode_request_uuid(bytes: &[u8]) -> Option<Uuid> {
+    decode::<RequestHeader>(bytes)
+        .ok()
+        .map(|header| header.uuid)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The length of the message is larger than [`MAX_MESSAGE_LEN`].
+    TooLong(u64),
+    /// The message could not be deserialized.
+    Malformed(String),
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::TooLong(length) => write!(
+                formatter,
+                "The message is {} bytes long which is longer than the maximum of {} bytes.",
+                length, MAX_MESSAGE_LEN
+            ),
+            Self::Malformed(error) => write!(formatter, "The message is malformed: {}", error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GetFilesRequestParams, Request, RequestParams};
+    use std::path::PathBuf;
+    use test_case::test_case;
+
+    #[test_case([0, 0, 0, 0, 0, 0, 0, 5], Ok(5); "short")]
+    #[test_case([0xff; 8], Err(DecodeError::TooLong(u64::MAX)); "too long")]
+    fn test_decode_length(bytes: [u8; LENGTH_LEN], expected: Result<usize, DecodeError>) {
+        assert_eq!(decode_length(bytes), expected);
+    }
+
+    /// Return a request serialized with bincode.
+    fn request_bytes() -> (Request, Vec<u8>) {
+        let request = Request::builder()
+            .params(RequestParams::GetFiles(
+                GetFilesRequestParams::builder()
+                    .dir(PathBuf::from("/tmp"))
+                    .build(),
+            ))
+            .build();
+        let bytes: Vec<u8> = bincode::serialize(&request).unwrap();
+        (request, bytes)
+    }
+
+    #[test]
+    fn test_decode() {
+        let (request, bytes) = request_bytes();
+
+        let decoded: Request = decode(&bytes).unwrap();
+
+        assert_eq!(decoded.uuid(), request.uuid());
+        assert!(decode::<Request>(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_decode_request_uuid() {
+        let (request, mut bytes) = request_bytes();
+        // Make the variant of the parameters invalid.
+        bytes[24..28].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        assert!(decode::<Request>(&bytes).is_err());
+        assert_eq!(decode_request_uuid(&bytes), Some(*request.uuid()));
+        assert_eq!(decode_request_uuid(&bytes[..10]), None);
+    }
+
+    #[test]
+    fn test_decode_bogus_length() {
+        // Make the path of the directory (which is followed by the depth) claim to be 2^62 bytes
+        // long.
+        let (_, mut bytes) = request_bytes();
+        let end: usize = bytes.len() - 8 - "/tmp".len();
+        bytes[end - 8..end].copy_from_slice(&(1u64 << 62).to_le_bytes());
+
+        assert!(matches!(
+            decode::<Request>(&bytes),
+            Err(DecodeError::Malformed(_))
+        ));
+    }
+}
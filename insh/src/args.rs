@@ -6,8 +6,13 @@ use insh_api::Request;
 use term::{Key, KeyEvent, KeyMods, TermEvent};
 use til::SystemEffect;
 
+use crate::choose_mode::ChooseMode;
 use crate::current_dir;
+use crate::expand::expand_path;
+#[cfg(feature = "logging")]
+use crate::logging::LogFormat;
 use crate::programs::{Vim, VimArgs, VimArgsBuilder};
+use crate::shell_init::Shell;
 
 use clap::{Parser, Subcommand};
 #[cfg(feature = "logging")]
@@ -17,22 +22,74 @@ use flexi_logger::{LevelFilter as LogLevelFilter, LogSpecification};
 #[clap(name = "insh", author, version, about)]
 pub struct Args {
     /// Starting directory to run in
-    #[clap(short, long, display_order = 0)]
+    #[clap(short, long, display_order = 0, value_parser = expand_path)]
     dir: Option<PathBuf>,
 
+    /// File to write the browsed directory to on exit
+    #[clap(long = "cd-file", display_order = 1, value_parser = expand_path)]
+    cd_file: Option<PathBuf>,
+
+    /// Choose a file using the browser and write its path to the out file
+    #[clap(long = "choose-file", display_order = 2, requires = "out")]
+    choose_file: bool,
+
+    /// Allow choosing directories as well (when choosing a file)
+    #[clap(long = "choose-dir", display_order = 3, requires = "choose-file")]
+    choose_dir: bool,
+
+    /// File to write the chosen path to (when choosing a file)
+    #[clap(
+        long,
+        display_order = 4,
+        requires = "choose-file",
+        value_parser = expand_path
+    )]
+    out: Option<PathBuf>,
+
+    /// Speak a line-based RPC protocol over stdin and stdout (for editor plugins)
+    #[clap(long, display_order = 5)]
+    rpc: bool,
+
+    /// Disable changing files (files are opened in the editor read-only)
+    #[clap(long = "read-only", display_order = 6)]
+    read_only: bool,
+
+    /// Profile in the configuration file whose options override the other options
+    #[clap(long, display_order = 7, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Record the key presses and responses to a file (to attach to bug reports)
+    #[clap(
+        long,
+        display_order = 8,
+        value_name = "FILE",
+        conflicts_with = "replay",
+        value_parser = expand_path
+    )]
+    record: Option<PathBuf>,
+
+    /// Replay a recording made with --record
+    #[clap(long, display_order = 9, value_name = "FILE", value_parser = expand_path)]
+    replay: Option<PathBuf>,
+
     /// File to write logs to (can be a unix socket)
     #[cfg(feature = "logging")]
-    #[clap(long = "log-file", display_order = 1)]
+    #[clap(long = "log-file", display_order = 10)]
     pub log_file_path: Option<PathBuf>,
 
+    /// Format of the log records (json records include the uuid of the request they are about)
+    #[cfg(feature = "logging")]
+    #[clap(long = "log-format", display_order = 11, arg_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+
     /// Default log level for all modules
     #[cfg(feature = "logging")]
-    #[clap(display_order = 2, long = "log-level", id = "LOG_LEVEL", default_value_t = LogLevelFilter::Info)]
+    #[clap(display_order = 12, long = "log-level", id = "LOG_LEVEL", default_value_t = LogLevelFilter::Info)]
     log_level_filter: LogLevelFilter,
 
     /// Log level for a particular module (<module-name>=<log-level>)
     #[cfg(feature = "logging")]
-    #[clap(display_order = 3, long = "module-log-level", id = "MODULE_LOG_LEVEL")]
+    #[clap(display_order = 13, long = "module-log-level", id = "MODULE_LOG_LEVEL")]
     module_log_level_filters: Vec<ModuleLogLevelFilter>,
 
     #[clap(subcommand)]
@@ -77,11 +134,53 @@ impl Args {
         dir
     }
 
+    pub fn cd_file(&self) -> &Option<PathBuf> {
+        &self.cd_file
+    }
+
+    pub fn choose_mode(&self) -> Option<ChooseMode> {
+        match (self.choose_file, self.choose_dir) {
+            (false, _) => None,
+            (true, false) => Some(ChooseMode::Files),
+            (true, true) => Some(ChooseMode::FilesAndDirs),
+        }
+    }
+
+    pub fn out(&self) -> &Option<PathBuf> {
+        &self.out
+    }
+
+    pub fn rpc(&self) -> bool {
+        self.rpc
+    }
+
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Return the profile whose options override the other options (if one is selected).
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    pub fn record(&self) -> &Option<PathBuf> {
+        &self.record
+    }
+
+    pub fn replay(&self) -> &Option<PathBuf> {
+        &self.replay
+    }
+
     #[cfg(feature = "logging")]
     pub fn log_file_path(&self) -> &Option<PathBuf> {
         &self.log_file_path
     }
 
+    #[cfg(feature = "logging")]
+    pub fn log_format(&self) -> LogFormat {
+        self.log_format
+    }
+
     #[cfg(feature = "logging")]
     pub fn log_specification(&self) -> LogSpecification {
         let mut log_specification_builder = LogSpecification::builder();
@@ -109,13 +208,14 @@ impl Args {
         )
     }
 
-    pub fn starting_effects(&self) -> Option<Vec<SystemEffect<Request>>> {
+    /// Return the effects to start with (files are opened in the editor read-only if `read_only`).
+    pub fn starting_effects(&self, read_only: bool) -> Option<Vec<SystemEffect<Request>>> {
         match &self.command {
             Some(Command::Edit {
                 browse,
                 file_line_column,
             }) => {
-                let mut vim_args_builder = VimArgsBuilder::new();
+                let mut vim_args_builder = VimArgsBuilder::new().read_only(read_only);
                 if let Some(file_line_column) = file_line_column {
                     if let Some(file) = file_line_column.file() {
                         vim_args_builder = vim_args_builder.path(file);
@@ -143,7 +243,11 @@ impl Args {
 
     pub fn starting_term_events(&self) -> Option<Vec<TermEvent>> {
         match &self.command {
-            Some(Command::Find { .. }) => Some(vec![TermEvent::KeyEvent(KeyEvent {
+            Some(Command::Find { .. })
+            | Some(Command::Open { .. })
+            | Some(Command::Search {
+                phrase: Some(_), ..
+            }) => Some(vec![TermEvent::KeyEvent(KeyEvent {
                 key: Key::CarriageReturn,
                 mods: KeyMods::NONE,
             })]),
@@ -185,6 +289,28 @@ pub enum Command {
         #[clap(name = "FILE")]
         file_line_column: Option<FileLineColumn>,
     },
+
+    /// Open a file by name
+    ///
+    /// Find files whose name matches the pattern. If exactly one file matches, then edit it using
+    /// the editor. Otherwise, open the finder with the found files.
+    #[clap(alias = "o", display_order = 5)]
+    Open { pattern: String },
+
+    /// Print shell code for integrating with a shell
+    ///
+    /// The code defines an `insh-cd` function which runs Insh and then changes the working
+    /// directory of the shell to the directory that was being browsed when Insh exited. For
+    /// example, add `eval "$(insh shell-init bash)"` to your `~/.bashrc`.
+    #[clap(display_order = 6)]
+    ShellInit {
+        #[clap(arg_enum)]
+        shell: Shell,
+    },
+
+    /// Preview an image (used by the browser)
+    #[clap(hide = true)]
+    PreviewImage { path: PathBuf },
 }
 
 mod file_line_column {
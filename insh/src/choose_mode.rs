@@ -0,0 +1,19 @@
+/*!
+This module contains the [`ChooseMode`] enum which is used when Insh is run as a file picker.
+*/
+
+/// What can be chosen when Insh is run as a file picker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChooseMode {
+    /// Only files can be chosen.
+    Files,
+    /// Both files and directories can be chosen.
+    FilesAndDirs,
+}
+
+impl ChooseMode {
+    /// Return if directories can be chosen.
+    pub fn dirs(&self) -> bool {
+        matches!(self, Self::FilesAndDirs)
+    }
+}
@@ -0,0 +1,134 @@
+/*!
+This module contains [`Chords`] which recognizes chords (sequences of keys after a leader key, like
+`\ff`) so that commands can be bound to mnemonics without taking the single keys of the browser.
+*/
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use crate::config::KeysConfig;
+
+/// What happened to a key that was given to [`Chords`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum ChordHandling {
+    /// The key is not part of a chord (so it is handled as usual).
+    Passed,
+    /// The key started or continued a chord (and these are the keys after the leader so far).
+    Pending { keys: String },
+    /// The key finished a chord which runs the command.
+    Chosen { command: String },
+    /// The keys after the leader don't start any chord.
+    Unknown { keys: String },
+}
+
+/// Recognizes the chords.
+pub struct Chords {
+    leader: char,
+    /// The commands run by the chords (keyed by the keys after the leader).
+    chords: BTreeMap<String, String>,
+    timeout: Duration,
+    /// The keys pressed after the leader (if a chord is being typed) and when the last key was
+    /// pressed.
+    pending: Option<(String, Instant)>,
+}
+
+impl From<&KeysConfig> for Chords {
+    fn from(config: &KeysConfig) -> Self {
+        Self {
+            leader: config.leader(),
+            chords: config.chords().clone(),
+            timeout: config.chord_timeout(),
+            pending: None,
+        }
+    }
+}
+
+impl Chords {
+    pub fn leader(&self) -> char {
+        self.leader
+    }
+
+    /// Handle a key that was pressed at the time `now`.
+    ///
+    /// A chord is abandoned if its next key isn't pressed before the timeout (and then the key is
+    /// handled as if no chord was being typed).
+    pub fn handle(&mut self, key: char, now: Instant) -> ChordHandling {
+        let mut keys: String = match self.pending.take() {
+            Some((keys, last)) if now.duration_since(last) < self.timeout => keys,
+            _ if key == self.leader => {
+                self.pending = Some((String::new(), now));
+                return ChordHandling::Pending {
+                    keys: String::new(),
+                };
+            }
+            _ => return ChordHandling::Passed,
+        };
+
+        keys.push(key);
+        if let Some(command) = self.chords.get(&keys) {
+            return ChordHandling::Chosen {
+                command: command.clone(),
+            };
+        }
+        if self.chords.keys().any(|chord| chord.starts_with(&keys)) {
+            self.pending = Some((keys.clone(), now));
+            return ChordHandling::Pending { keys };
+        }
+        ChordHandling::Unknown { keys }
+    }
+
+    /// Return the chords that start with the keys (and the commands that they run).
+    pub fn candidates(&self, keys: &str) -> Vec<(&str, &str)> {
+        self.chords
+            .iter()
+            .filter(|(chord, _)| chord.starts_with(keys))
+            .map(|(chord, command)| (chord.as_str(), command.as_str()))
+            .collect()
+    }
+
+    /// Abandon the chord that is being typed. Return whether there was one.
+    pub fn cancel(&mut self) -> bool {
+        self.pending.take().is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn chords() -> Chords {
+        let chords: BTreeMap<String, String> = [("ff", "find"), ("fs", "search"), ("t", "tree")]
+            .into_iter()
+            .map(|(keys, command)| (keys.to_string(), command.to_string()))
+            .collect();
+        Chords {
+            leader: ' ',
+            chords,
+            timeout: Duration::from_secs(1),
+            pending: None,
+        }
+    }
+
+    fn chosen(command: &str) -> ChordHandling {
+        ChordHandling::Chosen {
+            command: command.to_string(),
+        }
+    }
+
+    #[test_case(&[(' ', 0), ('f', 100), ('s', 200)], chosen("search"); "chord")]
+    #[test_case(&[(' ', 0), ('t', 100)], chosen("tree"); "short chord")]
+    #[test_case(&[(' ', 0), ('f', 100)], ChordHandling::Pending { keys: "f".to_string() }; "partial chord")]
+    #[test_case(&[(' ', 0), ('x', 100)], ChordHandling::Unknown { keys: "x".to_string() }; "unknown chord")]
+    #[test_case(&[('f', 0)], ChordHandling::Passed; "not a chord")]
+    #[test_case(&[(' ', 0), ('f', 2000)], ChordHandling::Passed; "timed out")]
+    #[test_case(&[(' ', 0), (' ', 2000)], ChordHandling::Pending { keys: String::new() }; "timed out with the leader")]
+    fn test_handle(keys: &[(char, u64)], expected: ChordHandling) {
+        let mut chords = chords();
+        let start = Instant::now();
+        let mut handling: ChordHandling = ChordHandling::Passed;
+        for (key, millis) in keys {
+            handling = chords.handle(*key, start + Duration::from_millis(*millis));
+        }
+        assert_eq!(handling, expected);
+    }
+}
@@ -1,42 +1,287 @@
+/*!
+This module contains [`Clipboard`] which copies text with the first backend that works here (the
+Wayland or X11 clipboard, `pbcopy`, or the terminal with OSC 52), and keeps the text in the default
+register which is pasted from when there is no system clipboard to paste from. The clipboard also
+holds the named [`Registers`] (which are yanked into and pasted from without the system clipboard).
+
+The clipboard is owned by Insh for as long as it runs (and shared with the components), because the
+X11 clipboard only serves the copied text for as long as its context lives.
+*/
+use std::env;
+use std::io::{self, IsTerminal, Read, Write};
+use std::process::{Command, Stdio};
+
 use copypasta::{ClipboardContext as CopyPastaClipboardContext, ClipboardProvider};
 
+use crate::registers::{self, Registers};
+
+/// A way of copying text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// The Wayland clipboard (with `wl-copy` and `wl-paste`).
+    Wayland,
+    /// The X11 clipboard.
+    X11,
+    /// The macOS pasteboard (with `pbcopy` and `pbpaste`).
+    Pbcopy,
+    /// The clipboard of the terminal (which is set with an OSC 52 escape sequence, so it works
+    /// over SSH, but it can't be pasted from).
+    Osc52,
+    /// The internal register (which can only be pasted from within Insh).
+    Register,
+}
+
+impl Backend {
+    /// All of the backends (in the order that they are tried).
+    const ALL: [Backend; 5] = [
+        Self::Wayland,
+        Self::X11,
+        Self::Pbcopy,
+        Self::Osc52,
+        Self::Register,
+    ];
+
+    /// Return whether the backend can be used here (judging by the environment).
+    fn is_available(&self) -> bool {
+        match self {
+            Self::Wayland => env::var_os("WAYLAND_DISPLAY").is_some(),
+            Self::X11 => cfg!(not(target_os = "macos")) && env::var_os("DISPLAY").is_some(),
+            Self::Pbcopy => cfg!(target_os = "macos"),
+            Self::Osc52 => io::stdout().is_terminal(),
+            Self::Register => true,
+        }
+    }
+}
+
 pub struct Clipboard {
-    context: CopyPastaClipboardContext,
+    /// The backends that are tried in order (a backend is removed once it fails).
+    backends: Vec<Backend>,
+    /// The context of the X11 clipboard (if it was made).
+    x11: Option<CopyPastaClipboardContext>,
+    registers: Registers,
+}
+
+impl Default for Clipboard {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Manages access to the system clipboard.
 impl Clipboard {
-    /// Return a new clipboard.
+    /// Return a new clipboard which uses the backends that can be used here.
     pub fn new() -> Self {
-        let context = CopyPastaClipboardContext::new().unwrap();
-        Self { context }
+        Self {
+            backends: Backend::ALL
+                .into_iter()
+                .filter(Backend::is_available)
+                .collect(),
+            x11: None,
+            registers: Registers::default(),
+        }
+    }
+
+    pub fn registers(&self) -> &Registers {
+        &self.registers
     }
 
-    /// Set the contents of the clipboard.
-    pub fn copy(&mut self, contents: String) {
+    pub fn registers_mut(&mut self) -> &mut Registers {
+        &mut self.registers
+    }
+
+    /// Copy the text. Return the backend that it was copied with (the register if there is no
+    /// system clipboard).
+    pub fn copy(&mut self, text: String) -> Backend {
         #[cfg(feature = "logging")]
-        log::debug!("Setting the clipboard conents to \"{}\"...", contents);
+        log::debug!("Setting the clipboard conents to \"{}\"...", text);
 
-        #[allow(clippy::redundant_clone)]
-        self.context.set_contents(contents.clone()).unwrap();
+        let mut copied_with: Backend = Backend::Register;
+        for backend in self.backends.clone() {
+            match self.copy_with(backend, &text) {
+                Ok(()) => {
+                    copied_with = backend;
+                    break;
+                }
+                #[allow(unused_variables)]
+                Err(error) => {
+                    #[cfg(feature = "logging")]
+                    log::warn!("Failed to copy with {:?}: {}", backend, error);
+                    self.backends.retain(|other| *other != backend);
+                }
+            }
+        }
 
-        // NOTE(ascola): We shouldn't have to do this, but setting contents doesn't seem to work
-        // on my laptop running Ubuntu 22.04 without it?
-        // See https://github.com/alacritty/copypasta/issues/49
-        #[allow(unused_variables)]
-        let actual_contents: String = self.context.get_contents().unwrap();
+        self.registers.set(registers::DEFAULT, text);
+        copied_with
+    }
 
-        #[cfg(feature = "logging")]
-        if actual_contents != contents {
-            log::warn!("Failed to set the clipboard contents.");
-        } else {
-            log::debug!("Successfully set the clipboard contents.");
+    /// Return the contents of the system clipboard, or the text that was copied last if there is
+    /// no system clipboard to paste from.
+    pub fn paste(&mut self) -> Option<String> {
+        for backend in self.backends.clone() {
+            let pasted: Result<String, String> = match backend {
+                Backend::Wayland => output("wl-paste", &["--no-newline"]),
+                Backend::X11 => self
+                    .x11()
+                    .and_then(|context| context.get_contents().map_err(|error| error.to_string())),
+                Backend::Pbcopy => output("pbpaste", &[]),
+                Backend::Osc52 | Backend::Register => continue,
+            };
+            match pasted {
+                Ok(text) => return Some(text),
+                #[allow(unused_variables)]
+                Err(error) => {
+                    #[cfg(feature = "logging")]
+                    log::warn!("Failed to paste with {:?}: {}", backend, error);
+                }
+            }
+        }
+        self.registers.get(registers::DEFAULT).map(str::to_string)
+    }
+
+    fn copy_with(&mut self, backend: Backend, text: &str) -> Result<(), String> {
+        match backend {
+            Backend::Wayland => run_with_input("wl-copy", text),
+            Backend::X11 => {
+                let context: &mut CopyPastaClipboardContext = self.x11()?;
+                context
+                    .set_contents(text.to_string())
+                    .map_err(|error| error.to_string())?;
+
+                // NOTE(ascola): We shouldn't have to do this, but setting contents doesn't seem to
+                // work on my laptop running Ubuntu 22.04 without it?
+                // See https://github.com/alacritty/copypasta/issues/49
+                let actual_contents: String =
+                    context.get_contents().map_err(|error| error.to_string())?;
+                match actual_contents == text {
+                    true => Ok(()),
+                    false => Err("the contents were not set".to_string()),
+                }
+            }
+            Backend::Pbcopy => run_with_input("pbcopy", text),
+            Backend::Osc52 => {
+                let mut stdout = io::stdout();
+                stdout
+                    .write_all(osc52_sequence(text).as_bytes())
+                    .and_then(|()| stdout.flush())
+                    .map_err(|error| error.to_string())
+            }
+            Backend::Register => Ok(()),
+        }
+    }
+
+    /// Return the context of the X11 clipboard (making it the first time).
+    fn x11(&mut self) -> Result<&mut CopyPastaClipboardContext, String> {
+        if self.x11.is_none() {
+            let context = CopyPastaClipboardContext::new().map_err(|error| error.to_string())?;
+            self.x11 = Some(context);
+        }
+        Ok(self.x11.as_mut().unwrap())
+    }
+}
+
+/// Return the message which tells that the text was copied with the backend.
+pub fn copied_message(text: &str, backend: Backend) -> String {
+    match backend {
+        Backend::Register => format!("Copied {} (it can only be pasted in Insh)", text),
+        _ => format!("Copied {}", text),
+    }
+}
+
+/// Run the program with the text as its input.
+fn run_with_input(program: &str, text: &str) -> Result<(), String> {
+    let mut child = Command::new(program)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|error| error.to_string())?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .map_err(|error| error.to_string())?;
+    }
+    let status = child.wait().map_err(|error| error.to_string())?;
+    match status.success() {
+        true => Ok(()),
+        false => Err(format!("{} exited with {}", program, status)),
+    }
+}
+
+/// Run the program and return its output.
+fn output(program: &str, args: &[&str]) -> Result<String, String> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|error| error.to_string())?;
+    let mut text = String::new();
+    if let Some(mut stdout) = child.stdout.take() {
+        stdout
+            .read_to_string(&mut text)
+            .map_err(|error| error.to_string())?;
+    }
+    let status = child.wait().map_err(|error| error.to_string())?;
+    match status.success() {
+        true => Ok(text),
+        false => Err(format!("{} exited with {}", program, status)),
+    }
+}
+
+/// Return the OSC 52 escape sequence which asks the terminal to set its clipboard to the text.
+fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64(text.as_bytes()))
+}
+
+/// Return the bytes encoded with base64 (with padding).
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group: u32 = chunk.iter().enumerate().fold(0, |group, (index, byte)| {
+            group | (*byte as u32) << (16 - 8 * index)
+        });
+        for index in 0..4 {
+            match index <= chunk.len() {
+                true => encoded.push(ALPHABET[(group >> (18 - 6 * index) & 0x3F) as usize] as char),
+                false => encoded.push('='),
+            }
         }
     }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("", ""; "empty")]
+    #[test_case("f", "Zg=="; "one byte")]
+    #[test_case("fo", "Zm8="; "two bytes")]
+    #[test_case("foo", "Zm9v"; "three bytes")]
+    #[test_case("/home/me/a b", "L2hvbWUvbWUvYSBi"; "path")]
+    fn test_base64(text: &str, expected: &str) {
+        assert_eq!(base64(text.as_bytes()), expected);
+    }
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(osc52_sequence("foo"), "\x1b]52;c;Zm9v\x07");
+    }
+
+    #[test]
+    fn test_register() {
+        let mut clipboard = Clipboard {
+            backends: vec![Backend::Register],
+            x11: None,
+            registers: Registers::default(),
+        };
+        assert_eq!(clipboard.paste(), None);
 
-    #[allow(dead_code)]
-    /// Return the contents of the clipboard.
-    pub fn paste(&mut self) -> String {
-        return self.context.get_contents().unwrap();
+        assert_eq!(clipboard.copy("foo".to_string()), Backend::Register);
+        assert_eq!(clipboard.paste().as_deref(), Some("foo"));
     }
 }
@@ -0,0 +1,185 @@
+/*!
+This module contains the enum [`Collation`] (the `sort.collation` configuration option) and the
+[`NameCollator`] which compares file names using it (used to sort the entries of the browser).
+*/
+use std::cmp::Ordering;
+use std::env;
+
+use icu_collator::{Collator, CollatorOptions};
+use icu_locid::Locale;
+use serde::Deserialize;
+
+/// How file names are ordered.
+#[derive(Deserialize, Debug, Default, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Collation {
+    /// The order of the language of the locale (from `LC_ALL`, `LC_COLLATE`, or `LANG`), so that
+    /// for example accented letters are ordered next to the letters without accents.
+    Locale,
+    /// The order of the unicode code points of the names (the order of `ls` with `LC_ALL=C`).
+    #[default]
+    Codepoint,
+    /// The order of the code points except that numbers are ordered by their value (so `file2`
+    /// comes before `file10`).
+    Natural,
+}
+
+impl Collation {
+    /// Return the name of the collation (as it is written in the configuration).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Locale => "locale",
+            Self::Codepoint => "codepoint",
+            Self::Natural => "natural",
+        }
+    }
+}
+
+/// Compares file names using a collation.
+pub enum NameCollator {
+    Locale(Box<Collator>),
+    Codepoint,
+    Natural,
+}
+
+impl NameCollator {
+    /// Return a new collator for the collation.
+    pub fn new(collation: Collation) -> Self {
+        match collation {
+            Collation::Locale => Self::for_locale(&locale_name()),
+            Collation::Codepoint => Self::Codepoint,
+            Collation::Natural => Self::Natural,
+        }
+    }
+
+    /// Return a new collator for the locale (such as `sv_SE.UTF-8`). The root collation (which
+    /// suits most languages) is used if the locale is not known.
+    fn for_locale(name: &str) -> Self {
+        // NOTE: POSIX locale names use an underscore and may end with the encoding and a modifier.
+        let tag: String = name
+            .split(['.', '@'])
+            .next()
+            .unwrap_or_default()
+            .replace('_', "-");
+        let locale: Locale = match tag.as_str() {
+            "C" | "POSIX" => Locale::UND,
+            tag => tag.parse().unwrap_or(Locale::UND),
+        };
+
+        match Collator::try_new(&locale.into(), CollatorOptions::new()) {
+            Ok(collator) => Self::Locale(Box::new(collator)),
+            #[allow(unused_variables)]
+            Err(error) => {
+                #[cfg(feature = "logging")]
+                log::warn!("Failed to create a collator for {}: {}", name, error);
+                Self::Codepoint
+            }
+        }
+    }
+
+    /// Compare two file names.
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        match self {
+            Self::Locale(collator) => collator.compare(a, b).then_with(|| a.cmp(b)),
+            Self::Codepoint => a.cmp(b),
+            Self::Natural => natural_compare(a, b),
+        }
+    }
+}
+
+/// Return the name of the locale used for collation.
+fn locale_name() -> String {
+    ["LC_ALL", "LC_COLLATE", "LANG"]
+        .iter()
+        .filter_map(|variable| env::var(variable).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default()
+}
+
+/// Compare two names by their code points except for runs of digits which are compared by their
+/// value.
+fn natural_compare(a: &str, b: &str) -> Ordering {
+    let mut a_chunks = chunks(a);
+    let mut b_chunks = chunks(b);
+    loop {
+        let ordering: Ordering = match (a_chunks.next(), b_chunks.next()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a_chunk), Some(b_chunk)) => match (is_number(a_chunk), is_number(b_chunk)) {
+                (true, true) => {
+                    let a_number: &str = a_chunk.trim_start_matches('0');
+                    let b_number: &str = b_chunk.trim_start_matches('0');
+                    a_number
+                        .len()
+                        .cmp(&b_number.len())
+                        .then_with(|| a_number.cmp(b_number))
+                }
+                _ => a_chunk.cmp(b_chunk),
+            },
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+/// Return the runs of digits and of other characters of the name.
+fn chunks(name: &str) -> impl Iterator<Item = &str> {
+    let mut rest: &str = name;
+    std::iter::from_fn(move || {
+        let first: char = rest.chars().next()?;
+        let end: usize = rest
+            .find(|character: char| character.is_ascii_digit() != first.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (chunk, after) = rest.split_at(end);
+        rest = after;
+        Some(chunk)
+    })
+}
+
+/// Return if the chunk is a run of digits.
+fn is_number(chunk: &str) -> bool {
+    chunk.starts_with(|character: char| character.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    /// Return the names sorted by the collator.
+    fn sorted<'a>(collator: &NameCollator, names: &[&'a str]) -> Vec<&'a str> {
+        let mut names: Vec<&str> = names.to_vec();
+        names.sort_by(|a, b| collator.compare(a, b));
+        names
+    }
+
+    #[test_case(&["b", "B", "a", "é", "f", "e"], vec!["B", "a", "b", "e", "f", "é"]; "accents")]
+    #[test_case(&["file10", "file2", "file1"], vec!["file1", "file10", "file2"]; "numbers")]
+    fn test_codepoint(names: &[&str], expected: Vec<&str>) {
+        assert_eq!(
+            sorted(&NameCollator::new(Collation::Codepoint), names),
+            expected
+        );
+    }
+
+    #[test_case(&["file10", "file2", "file1"], vec!["file1", "file2", "file10"]; "numbers")]
+    #[test_case(&["a01", "a1", "a001"], vec!["a001", "a01", "a1"]; "leading zeros")]
+    #[test_case(&["2", "a", "10"], vec!["2", "10", "a"]; "only numbers")]
+    #[test_case(&["a1b2", "a1b10", "a1"], vec!["a1", "a1b2", "a1b10"]; "multiple numbers")]
+    fn test_natural(names: &[&str], expected: Vec<&str>) {
+        assert_eq!(
+            sorted(&NameCollator::new(Collation::Natural), names),
+            expected
+        );
+    }
+
+    #[test_case("en_US.UTF-8", &["b", "B", "a", "é", "f", "e"], vec!["a", "b", "B", "e", "é", "f"]; "english")]
+    #[test_case("sv_SE.UTF-8", &["ö", "z", "o"], vec!["o", "z", "ö"]; "swedish")]
+    #[test_case("C", &["b", "é", "a"], vec!["a", "b", "é"]; "posix")]
+    #[test_case("nonsense!", &["b", "a"], vec!["a", "b"]; "unknown")]
+    fn test_locale(locale: &str, names: &[&str], expected: Vec<&str>) {
+        assert_eq!(sorted(&NameCollator::for_locale(locale), names), expected);
+    }
+}
@@ -1,5 +1,7 @@
 use crossterm::style::Color as CrosstermColor;
 
+use file_info::TagColor;
+
 const DARK_GREY: CrosstermColor = CrosstermColor::Rgb {
     r: 96,
     g: 96,
@@ -12,6 +14,18 @@ const LIGHT_GREY: CrosstermColor = CrosstermColor::Rgb {
     b: 159,
 };
 
+const ORANGE: CrosstermColor = CrosstermColor::Rgb {
+    r: 255,
+    g: 165,
+    b: 0,
+};
+
+const SLATE_BLUE: CrosstermColor = CrosstermColor::Rgb {
+    r: 52,
+    g: 70,
+    b: 120,
+};
+
 pub enum Color {
     Highlight,
     GrayedText,
@@ -22,6 +36,24 @@ pub enum Color {
     InvertedBackground,
     BadRegex,
     NotCompiledRegex,
+    MountPoint,
+    Tag(TagColor),
+    Hot,
+    Warm,
+    Cold,
+    MarkdownHeading {
+        level: usize,
+    },
+    MarkdownEmphasis,
+    MarkdownStrong,
+    MarkdownCode,
+    MarkdownListMarker,
+    LogError,
+    LogWarning,
+    LogDebug,
+    Danger,
+    /// The background of the entries that are marked while selecting.
+    Marked,
 }
 
 impl From<Color> for CrosstermColor {
@@ -36,6 +68,33 @@ impl From<Color> for CrosstermColor {
             Color::InvertedBackground => CrosstermColor::White,
             Color::BadRegex => CrosstermColor::Red,
             Color::NotCompiledRegex => DARK_GREY,
+            Color::MountPoint => CrosstermColor::Cyan,
+            Color::Tag(tag_color) => match tag_color {
+                TagColor::None | TagColor::Gray => LIGHT_GREY,
+                TagColor::Green => CrosstermColor::Green,
+                TagColor::Purple => CrosstermColor::Magenta,
+                TagColor::Blue => CrosstermColor::Blue,
+                TagColor::Yellow => CrosstermColor::Yellow,
+                TagColor::Red => CrosstermColor::Red,
+                TagColor::Orange => ORANGE,
+            },
+            Color::Hot => CrosstermColor::Red,
+            Color::Warm => ORANGE,
+            Color::Cold => LIGHT_GREY,
+            Color::MarkdownHeading { level } => match level {
+                1 => CrosstermColor::Yellow,
+                2 => ORANGE,
+                _ => CrosstermColor::Cyan,
+            },
+            Color::MarkdownEmphasis => CrosstermColor::Magenta,
+            Color::MarkdownStrong => CrosstermColor::Red,
+            Color::MarkdownCode => CrosstermColor::Green,
+            Color::MarkdownListMarker => CrosstermColor::Yellow,
+            Color::LogError => CrosstermColor::Red,
+            Color::LogWarning => ORANGE,
+            Color::LogDebug => DARK_GREY,
+            Color::Danger => CrosstermColor::Red,
+            Color::Marked => SLATE_BLUE,
         }
     }
 }
@@ -48,4 +107,15 @@ impl Color {
             Self::InvertedBackground
         }
     }
+
+    /// Return the color for a count relative to the maximum count (for showing a heatmap).
+    pub fn heat(count: usize, max_count: usize) -> Self {
+        if max_count == 0 || count * 3 < max_count {
+            Self::Cold
+        } else if count * 3 < max_count * 2 {
+            Self::Warm
+        } else {
+            Self::Hot
+        }
+    }
 }
@@ -1,4 +1,8 @@
-use std::path::PathBuf;
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use typed_builder::TypedBuilder;
 use uuid::Uuid;
@@ -6,14 +10,34 @@ use uuid::Uuid;
 use file_type::FileType;
 use insh_api::Request;
 use insh_api::Response;
+use insh_api::{
+    RequestKind, RequestParams, ResponseParams, WaitForDevicesRequestParams, MAX_WAIT_FOR_DEVICES,
+};
 use rend::{Fabric, Size};
-use term::TermEvent;
+use term::{Key, KeyEvent, KeyMods, TermEvent};
 use til::Component;
 
-use super::{Contents, ContentsEffect, ContentsEvent, ContentsProps};
-use crate::components::common::{Dir, DirEvent, DirProps};
+use super::{
+    Contents, ContentsEffect, ContentsEvent, ContentsProps, Sidebar, SidebarEffect, SidebarEvent,
+    SidebarProps,
+};
+use crate::choose_mode::ChooseMode;
+use crate::clipboard::Clipboard;
+use crate::components::common::{Dir, DirEvent, DirProps, Prompt};
+use crate::config::Config;
+use crate::data::Data;
+use crate::devices::{self, Device};
+use crate::escalation::Escalation;
+use crate::hooks::{run_hook, HookEvent};
+use crate::inspect::{Inspect, Inspection};
 use crate::programs::VimArgs;
+use crate::script::Script;
 use crate::stateful::Stateful;
+use crate::toasts::ToastLevel;
+
+/// How long after the longest wait for devices that a request for waiting for devices is assumed to
+/// be lost.
+const DEVICES_REQUEST_GRACE: Duration = Duration::from_secs(1);
 
 #[derive(TypedBuilder)]
 pub struct Props {
@@ -21,8 +45,13 @@ pub struct Props {
     size: Size,
     #[builder(default)]
     file: Option<PathBuf>,
+    config: Arc<Config>,
+    /// The script defining custom actions.
+    #[builder(default)]
+    script: Option<Rc<Script>>,
+    clipboard: Rc<RefCell<Clipboard>>,
     #[builder(default)]
-    pending_request: Option<Uuid>,
+    choose_mode: Option<ChooseMode>,
 }
 
 pub struct Browser {
@@ -38,69 +67,136 @@ impl Component<Props, Event, Effect> for Browser {
     fn handle(&mut self, event: Event) -> Option<Effect> {
         let mut effect: Option<Effect> = None;
         match event {
-            Event::Response(response) => match self.state.focus {
-                Focus::Contents => {
-                    let contents_event: ContentsEvent = ContentsEvent::Response(response);
+            Event::Response(response)
+                if self.state.devices_request.map(|(uuid, _)| uuid) == Some(*response.uuid()) =>
+            {
+                self.state.devices_request = None;
+                if let ResponseParams::WaitForDevices(params) = response.params() {
+                    if let (true, Some(sidebar)) = (params.changed(), &mut self.state.sidebar) {
+                        let devices: Vec<Device> = devices::mounted_devices();
+                        sidebar.handle(SidebarEvent::SetDevices { devices });
+                    }
+                }
+                effect = self.state.watch_devices();
+            }
+            Event::Response(response) => {
+                let contents_event: ContentsEvent = ContentsEvent::Response(response);
+                let contents_effect: Option<ContentsEffect> =
                     self.state.contents.handle(contents_event);
+                match contents_effect {
+                    Some(ContentsEffect::GotFiles {
+                        slow_file_system,
+                        get_file_details_request,
+                    }) => {
+                        let dir_event = DirEvent::SetSlowFileSystem { slow_file_system };
+                        self.state.dir.handle(dir_event);
+                        let target: Option<PathBuf> =
+                            self.state.contents.physical_dir().map(Path::to_path_buf);
+                        self.state.dir.handle(DirEvent::SetTarget { target });
+                        effect = get_file_details_request.map(Effect::Request);
+                    }
+                    contents_effect => effect = self.handle_contents_effect(contents_effect),
                 }
-            },
-            Event::TermEvent(term_event) => {
-                match term_event {
-                    TermEvent::Resize(size) => {
-                        let size = Size::new(size.rows - 1, size.columns);
-                        self.state.contents.handle(ContentsEvent::Resize { size });
+            }
+            Event::TimedOut { request, .. }
+                if self.state.devices_request.map(|(uuid, _)| uuid) == Some(request) =>
+            {
+                self.state.devices_request = None;
+                effect = self.state.watch_devices();
+            }
+            Event::TimedOut { request, kind } => {
+                let contents_event = ContentsEvent::TimedOut { request, kind };
+                let contents_effect: Option<ContentsEffect> =
+                    self.state.contents.handle(contents_event);
+                effect = self.handle_contents_effect(contents_effect);
+            }
+            Event::Tick => {
+                let contents_effect: Option<ContentsEffect> =
+                    self.state.contents.handle(ContentsEvent::Tick);
+                effect = self.handle_contents_effect(contents_effect);
+            }
+            Event::ConfirmDelete { index } => {
+                let contents_effect: Option<ContentsEffect> = self
+                    .state
+                    .contents
+                    .handle(ContentsEvent::ConfirmDelete { index });
+                effect = self.handle_contents_effect(contents_effect);
+            }
+            Event::CancelDelete => {
+                let contents_effect: Option<ContentsEffect> =
+                    self.state.contents.handle(ContentsEvent::CancelDelete);
+                effect = self.handle_contents_effect(contents_effect);
+            }
+            Event::TermEvent(term_event) => match term_event {
+                TermEvent::Resize(size) => {
+                    self.state.size = size;
+                    self.state.resize_contents();
+                }
+                TermEvent::KeyEvent(KeyEvent {
+                    key: Key::Char('B'),
+                    mods: KeyMods::SHIFT,
+                }) if !self.state.contents.filtering() => {
+                    self.state.toggle_sidebar();
+                }
+                TermEvent::KeyEvent(KeyEvent {
+                    key: Key::HorizontalTab,
+                    mods: KeyMods::NONE,
+                }) if !self.state.contents.filtering() => {
+                    if self.state.sidebar.is_none() {
+                        return Some(Effect::Bell);
+                    }
+                    let focus = match self.state.focus {
+                        Focus::Contents => Focus::Sidebar,
+                        Focus::Sidebar => Focus::Contents,
+                    };
+                    self.state.set_focus(focus);
+                }
+                _ => match self.state.focus {
+                    Focus::Contents => {
+                        let contents_event: ContentsEvent =
+                            ContentsEvent::Term { event: term_event };
+                        let contents_effect: Option<ContentsEffect> =
+                            self.state.contents.handle(contents_event);
+                        effect = self.handle_contents_effect(contents_effect);
                     }
-                    _ => {
-                        match self.state.focus {
-                            Focus::Contents => {
-                                let contents_event: ContentsEvent =
-                                    ContentsEvent::Term { event: term_event };
+                    Focus::Sidebar => {
+                        let sidebar_event = SidebarEvent::Term { event: term_event };
+                        let sidebar_effect: Option<SidebarEffect> = self
+                            .state
+                            .sidebar
+                            .as_mut()
+                            .and_then(|sidebar| sidebar.handle(sidebar_event));
+                        match sidebar_effect {
+                            Some(SidebarEffect::Browse { dir }) => {
+                                self.state.set_focus(Focus::Contents);
+                                let contents_event = ContentsEvent::Jump { dir };
                                 let contents_effect: Option<ContentsEffect> =
                                     self.state.contents.handle(contents_event);
-
-                                match contents_effect {
-                                    Some(ContentsEffect::SetDir {
-                                        dir,
-                                        get_files_request,
-                                    }) => {
-                                        let dir_event = DirEvent::SetDir { dir };
-                                        self.state.dir.handle(dir_event);
-                                        // TODO: What if the directory returns an effect here? Do we need to loop?
-                                        effect = Some(Effect::Request(get_files_request));
-                                    }
-                                    Some(ContentsEffect::PopDir { get_files_request }) => {
-                                        let dir_event = DirEvent::PopDir;
-                                        self.state.dir.handle(dir_event);
-                                        effect = Some(Effect::Request(get_files_request));
-                                    }
-                                    Some(ContentsEffect::OpenFileCreator { dir, file_type }) => {
-                                        effect = Some(Effect::OpenFileCreator { dir, file_type });
-                                    }
-                                    Some(ContentsEffect::OpenFinder { dir }) => {
-                                        effect = Some(Effect::OpenFinder { dir });
-                                    }
-                                    Some(ContentsEffect::OpenSearcher { dir }) => {
-                                        effect = Some(Effect::OpenSearcher { dir });
-                                    }
-                                    Some(ContentsEffect::OpenVim(vim_args)) => {
-                                        effect = Some(Effect::OpenVim(vim_args));
-                                    }
-                                    Some(ContentsEffect::RunBash { dir }) => {
-                                        effect = Some(Effect::RunBash { dir });
-                                    }
-                                    Some(ContentsEffect::Bell) => {
-                                        effect = Some(Effect::Bell);
-                                    }
-                                    Some(ContentsEffect::Request(request)) => {
-                                        effect = Some(Effect::Request(request))
-                                    }
-                                    None => {}
-                                }
+                                effect = self.handle_contents_effect(contents_effect);
+                            }
+                            Some(SidebarEffect::Refresh) => {
+                                self.state.sidebar = Some(self.state.new_sidebar());
+                                self.state.set_focus(Focus::Sidebar);
+                            }
+                            Some(SidebarEffect::Eject { device }) => {
+                                effect = Some(Effect::Eject { device });
                             }
+                            Some(SidebarEffect::Unfocus) => {
+                                self.state.set_focus(Focus::Contents);
+                            }
+                            Some(SidebarEffect::Bell) => {
+                                effect = Some(Effect::Bell);
+                            }
+                            None => {}
                         }
                     }
-                }
-            }
+                },
+            },
+        }
+        // NOTE: The request for waiting for devices is lost if its response arrives while another
+        // component is shown, so it is sent again once the browser is used.
+        if effect.is_none() {
+            effect = self.state.watch_devices();
         }
         effect
     }
@@ -108,22 +204,156 @@ impl Component<Props, Event, Effect> for Browser {
     fn render(&self, size: Size) -> Fabric {
         match size.rows {
             0 => Fabric::new(size),
-            1 => self.state.dir.render(size),
+            1 => self.render_header(size),
             rows => {
                 let columns = size.columns;
-                let fabric: Fabric = self.state.dir.render(Size::new(1, columns));
-                let contents_fabric: Fabric =
-                    self.state.contents.render(Size::new(rows - 1, columns));
+                let fabric: Fabric = self.render_header(Size::new(1, columns));
+                let sidebar_width: usize = self.state.sidebar_width(columns);
+                let mut contents_fabric: Fabric = self
+                    .state
+                    .contents
+                    .render(Size::new(rows - 1, columns - sidebar_width));
+                if let Some(sidebar) = &self.state.sidebar {
+                    let sidebar_fabric: Fabric = sidebar.render(Size::new(rows - 1, sidebar_width));
+                    contents_fabric = sidebar_fabric.quilt_right(contents_fabric);
+                }
                 fabric.quilt_bottom(contents_fabric)
             }
         }
     }
 }
 
+impl Inspect for Browser {
+    fn inspect(&self) -> Inspection {
+        let mut inspection = Inspection::new("Browser")
+            .field("focus", &self.state.focus)
+            .child(self.state.contents.inspect());
+        if let Some(sidebar) = &self.state.sidebar {
+            inspection = inspection.child(sidebar.inspect());
+        }
+        inspection
+    }
+}
+
+impl Browser {
+    /// Return the directory being browsed.
+    pub fn dir(&self) -> &Path {
+        self.state.contents.dir()
+    }
+
+    /// Render the directory with a summary of its entries.
+    fn render_header(&self, size: Size) -> Fabric {
+        let summary: Option<String> = self.state.contents.summary();
+        self.state.dir.render_with_summary(size, summary.as_deref())
+    }
+
+    /// Return the effect of the browser for an effect of the contents.
+    fn handle_contents_effect(
+        &mut self,
+        contents_effect: Option<ContentsEffect>,
+    ) -> Option<Effect> {
+        let mut effect: Option<Effect> = None;
+        match contents_effect {
+            Some(ContentsEffect::SetDir {
+                dir,
+                get_files_request,
+            }) => {
+                let dir_event = DirEvent::SetDir { dir };
+                self.state.dir.handle(dir_event);
+                self.state.visit();
+                // TODO: What if the directory returns an effect here? Do we need to loop?
+                effect = Some(Effect::List(get_files_request));
+            }
+            Some(ContentsEffect::PopDir { get_files_request }) => {
+                let dir_event = DirEvent::PopDir;
+                self.state.dir.handle(dir_event);
+                self.state.visit();
+                effect = Some(Effect::List(get_files_request));
+            }
+            Some(ContentsEffect::OpenFileCreator { dir, file_type }) => {
+                effect = Some(Effect::OpenFileCreator { dir, file_type });
+            }
+            Some(ContentsEffect::OpenFinder { dir }) => {
+                effect = Some(Effect::OpenFinder { dir });
+            }
+            Some(ContentsEffect::OpenFlatList { dir }) => {
+                effect = Some(Effect::OpenFlatList { dir });
+            }
+            Some(ContentsEffect::OpenSearcher { dir }) => {
+                effect = Some(Effect::OpenSearcher { dir });
+            }
+            Some(ContentsEffect::OpenReader { path }) => {
+                effect = Some(Effect::OpenReader { path });
+            }
+            Some(ContentsEffect::PreviewImage { path }) => {
+                effect = Some(Effect::PreviewImage { path });
+            }
+            Some(ContentsEffect::OpenPager { path }) => {
+                effect = Some(Effect::OpenPager { path });
+            }
+            Some(ContentsEffect::OpenLogViewer { path }) => {
+                effect = Some(Effect::OpenLogViewer { path });
+            }
+            Some(ContentsEffect::OpenProcessList { dir }) => {
+                effect = Some(Effect::OpenProcessList { dir });
+            }
+            Some(ContentsEffect::OpenOpenFiles { dir }) => {
+                effect = Some(Effect::OpenOpenFiles { dir });
+            }
+            Some(ContentsEffect::OpenMounts) => {
+                effect = Some(Effect::OpenMounts);
+            }
+            Some(ContentsEffect::OpenPalette { dir, selection }) => {
+                effect = Some(Effect::OpenPalette { dir, selection });
+            }
+            Some(ContentsEffect::OpenVim(vim_args)) => {
+                effect = Some(Effect::OpenVim(vim_args));
+            }
+            Some(ContentsEffect::Choose { path }) => {
+                effect = Some(Effect::Choose { path });
+            }
+            Some(ContentsEffect::RunBash { dir }) => {
+                effect = Some(Effect::RunBash { dir });
+            }
+            Some(ContentsEffect::Escalate { escalation }) => {
+                effect = Some(Effect::Escalate { escalation });
+            }
+            Some(ContentsEffect::Mark { paths, moving }) => {
+                effect = Some(Effect::Mark { paths, moving });
+            }
+            Some(ContentsEffect::Paste { dir }) => {
+                effect = Some(Effect::Paste { dir });
+            }
+            Some(ContentsEffect::Toast { message, level }) => {
+                effect = Some(Effect::Toast { message, level });
+            }
+            Some(ContentsEffect::Bell) => {
+                effect = Some(Effect::Bell);
+            }
+            Some(ContentsEffect::OpenDeletePrompt(prompt)) => {
+                effect = Some(Effect::OpenDeletePrompt(prompt));
+            }
+            Some(ContentsEffect::Request(request)) => effect = Some(Effect::Request(request)),
+            Some(ContentsEffect::List(request)) => effect = Some(Effect::List(request)),
+            Some(ContentsEffect::Requests(requests)) => effect = Some(Effect::Requests(requests)),
+            Some(ContentsEffect::GotFiles { .. }) => {}
+            None => {}
+        }
+        effect
+    }
+}
+
 struct State {
+    /// The size of the browser.
+    size: Size,
     dir: Dir,
     contents: Contents,
+    /// The sidebar (if it is shown).
+    sidebar: Option<Sidebar>,
+    /// The request for waiting for devices to be mounted or unmounted (and when it was sent).
+    devices_request: Option<(Uuid, Instant)>,
     focus: Focus,
+    config: Arc<Config>,
 }
 
 impl From<Props> for State {
@@ -136,16 +366,127 @@ impl From<Props> for State {
             .dir(props.dir)
             .size(contents_size)
             .file(props.file)
-            .pending_request(props.pending_request)
+            .config(props.config.clone())
+            .script(props.script)
+            .clipboard(props.clipboard)
+            .choose_mode(props.choose_mode)
             .build();
         let contents = Contents::new(contents_props);
 
         let focus = Focus::default();
 
-        State {
+        let mut state = State {
+            size: props.size,
             dir,
             contents,
+            sidebar: None,
+            devices_request: None,
             focus,
+            config: props.config,
+        };
+        state.visit();
+        if state.config.browser().sidebar() {
+            state.toggle_sidebar();
+        }
+        state
+    }
+}
+
+impl State {
+    /// Return the width of the sidebar (or zero if it isn't shown) for the number of columns of the
+    /// browser.
+    fn sidebar_width(&self, columns: usize) -> usize {
+        match self.sidebar {
+            Some(_) => self.config.browser().sidebar_width().min(columns / 2),
+            None => 0,
+        }
+    }
+
+    /// Resize the contents to fit beside the sidebar.
+    fn resize_contents(&mut self) {
+        let columns: usize = self.size.columns - self.sidebar_width(self.size.columns);
+        let size = Size::new(self.size.rows.saturating_sub(1), columns);
+        self.contents.handle(ContentsEvent::Resize { size });
+    }
+
+    /// Return a new sidebar with the bookmarks, the recently browsed directories, and the
+    /// mounted devices.
+    fn new_sidebar(&self) -> Sidebar {
+        let sidebar_props = SidebarProps::builder()
+            .bookmarks(self.config.browser().bookmarks())
+            .recent(self.recent())
+            .devices(devices::mounted_devices())
+            .build();
+        Sidebar::new(sidebar_props)
+    }
+
+    /// Show the sidebar if it is hidden or hide it if it is shown.
+    fn toggle_sidebar(&mut self) {
+        self.sidebar = match self.sidebar {
+            Some(_) => None,
+            None => Some(self.new_sidebar()),
+        };
+        self.set_focus(Focus::Contents);
+        self.resize_contents();
+    }
+
+    fn set_focus(&mut self, focus: Focus) {
+        if let Some(sidebar) = &mut self.sidebar {
+            let focused: bool = matches!(focus, Focus::Sidebar);
+            sidebar.handle(SidebarEvent::Focus { focused });
+        }
+        self.focus = focus;
+    }
+
+    /// Return the request for waiting for devices to be mounted or unmounted (so that the sidebar
+    /// can be refreshed) if the sidebar is shown and a request isn't already pending.
+    fn watch_devices(&mut self) -> Option<Effect> {
+        self.sidebar.as_ref()?;
+        if let Some((_, sent)) = self.devices_request {
+            if sent.elapsed() < MAX_WAIT_FOR_DEVICES + DEVICES_REQUEST_GRACE {
+                return None;
+            }
+        }
+
+        let request = Request::builder()
+            .params(RequestParams::WaitForDevices(
+                WaitForDevicesRequestParams::builder()
+                    .wait(MAX_WAIT_FOR_DEVICES)
+                    .build(),
+            ))
+            .build();
+        self.devices_request = Some((*request.uuid(), Instant::now()));
+        Some(Effect::Request(request))
+    }
+
+    /// Return the recently browsed directories from newest to oldest.
+    fn recent(&self) -> Vec<PathBuf> {
+        if self.config.browser().recent_dirs() == 0 {
+            return Vec::new();
+        }
+        let mut data: Data = Data::read();
+        data.release();
+        data.dirs.recent.into_iter().rev().collect()
+    }
+
+    /// Record that the directory of the contents was browsed (and list it in the sidebar).
+    fn visit(&mut self) {
+        let dir: &Path = self.contents.dir();
+        run_hook(self.config.hooks(), HookEvent::DirEntered { dir });
+
+        let recent_dirs: usize = self.config.browser().recent_dirs();
+        if recent_dirs == 0 {
+            return;
+        }
+
+        let mut data: Data = Data::read();
+        data.dirs.record_visit(self.contents.dir(), recent_dirs);
+        data.write();
+        data.release();
+
+        if let Some(sidebar) = &mut self.sidebar {
+            let recent: Vec<PathBuf> = data.dirs.recent.into_iter().rev().collect();
+            sidebar.handle(SidebarEvent::SetRecent { recent });
         }
     }
 }
@@ -156,25 +497,103 @@ impl Stateful<Action, Effect> for State {
     }
 }
 
-#[derive(Default)]
+#[derive(Debug, Default)]
 enum Focus {
     #[default]
     Contents,
+    Sidebar,
 }
 
 pub enum Event {
     Response(Response),
+    /// The request (of the kind) timed out.
+    TimedOut {
+        request: Uuid,
+        kind: RequestKind,
+    },
+    /// A tick of the app.
+    Tick,
+    /// The choice at the index was chosen in the prompt for deleting entries.
+    ConfirmDelete {
+        index: usize,
+    },
+    /// The prompt for deleting entries was cancelled.
+    CancelDelete,
     TermEvent(TermEvent),
 }
 
 enum Action {}
 
 pub enum Effect {
-    OpenFileCreator { dir: PathBuf, file_type: FileType },
-    OpenFinder { dir: PathBuf },
-    OpenSearcher { dir: PathBuf },
+    OpenFileCreator {
+        dir: PathBuf,
+        file_type: FileType,
+    },
+    OpenFinder {
+        dir: PathBuf,
+    },
+    /// List all of the files under the directory.
+    OpenFlatList {
+        dir: PathBuf,
+    },
+    OpenSearcher {
+        dir: PathBuf,
+    },
+    OpenReader {
+        path: PathBuf,
+    },
+    PreviewImage {
+        path: PathBuf,
+    },
+    OpenPager {
+        path: PathBuf,
+    },
+    OpenLogViewer {
+        path: PathBuf,
+    },
+    OpenProcessList {
+        dir: PathBuf,
+    },
+    OpenOpenFiles {
+        dir: PathBuf,
+    },
+    OpenMounts,
+    OpenPalette {
+        dir: PathBuf,
+        selection: Option<PathBuf>,
+    },
     OpenVim(VimArgs),
-    RunBash { dir: PathBuf },
+    Choose {
+        path: PathBuf,
+    },
+    RunBash {
+        dir: PathBuf,
+    },
+    Eject {
+        device: Device,
+    },
+    Escalate {
+        escalation: Escalation,
+    },
+    /// Mark the entries for copying or moving them into another directory.
+    Mark {
+        paths: Vec<PathBuf>,
+        moving: bool,
+    },
+    /// Paste the marked entries into the directory.
+    Paste {
+        dir: PathBuf,
+    },
+    /// Show a short message (in the corner of the screen).
+    Toast {
+        message: String,
+        level: ToastLevel,
+    },
+    /// Open the prompt for deleting entries (on top of the browser).
+    OpenDeletePrompt(Prompt),
     Bell,
     Request(Request),
+    /// Send the request for the files of the directory (in place of the one sent before).
+    List(Request),
+    Requests(Vec<Request>),
 }
@@ -1,5 +1,10 @@
-use std::cmp::{self, Ordering};
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::ops::RangeInclusive;
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 
 use typed_builder::TypedBuilder;
 use uuid::Uuid;
@@ -7,53 +12,351 @@ use uuid::Uuid;
 use file_info::FileInfo;
 use file_type::FileType;
 use insh_api::{
-    GetFilesRequestParams, GetFilesResponseParams, GetFilesResult, Request, RequestParams,
-    Response, ResponseParams,
+    DeleteFileError, DeleteFileRequestParams, DeleteFileResponseParams, FilesSummary,
+    GetFileDetailsRequestParams, GetFileDetailsResponseParams, GetFilesError,
+    GetFilesRequestParams, GetFilesResponseParams, GetFilesResult, RenameFileError,
+    RenameFileRequestParams, RenameFileResponseParams, Request, RequestKind, RequestParams,
+    Response, ResponseParams, TouchFileError, TouchFileRequestParams, TouchFileResponseParams,
+    TransferFileError, TransferFileResponseParams,
 };
 use rend::{Fabric, Size, Yarn};
 use term::{Key, KeyEvent, KeyMods, TermEvent};
 use til::Component;
 
-use crate::clipboard::Clipboard;
+use crate::choose_mode::ChooseMode;
+use crate::chords::{ChordHandling, Chords};
+use crate::clipboard::{copied_message, Clipboard};
+use crate::collation::{Collation, NameCollator};
 use crate::color::Color;
+use crate::components::common::{
+    Choice, Phrase, PhraseEffect, PhraseEvent, PhraseProps, Prompt, PromptProps,
+};
+use crate::config::{Config, DetailColumn};
+use crate::count::Count;
+use crate::data::Data;
+use crate::escalation::Escalation;
+use crate::external::open_externally;
+use crate::file_metadata::{human_size, permissions};
+use crate::inspect::{Inspect, Inspection};
+use crate::list_filter::ListFilter;
+use crate::list_viewport::ListViewport;
+use crate::path_format::PathFormat;
 use crate::programs::{VimArgs, VimArgsBuilder};
+use crate::registers::Registers;
+use crate::relative_time::relative_time;
+use crate::script::{Script, ScriptEffect};
+use crate::sort_mode::SortMode;
 use crate::stateful::Stateful;
+use crate::symlink;
+use crate::toasts::ToastLevel;
+
+use super::tree::{self, Tree};
+
+/// The width of the column showing the number of times a file has been opened.
+const OPEN_COUNT_WIDTH: usize = 6;
+
+/// The width of the column showing when a file was last modified.
+const MODIFIED_WIDTH: usize = 16;
+
+/// The width of the column showing the encoding of a text file.
+const ENCODING_WIDTH: usize = 9;
+
+/// The width of the column showing the permissions of a file.
+const PERMISSIONS_WIDTH: usize = 10;
+
+/// The width of the column showing the size of a regular file.
+const SIZE_WIDTH: usize = 7;
+
+/// How long the files of the directory are used before they are gotten again in the background
+/// (on the next tick of the app, so the files are fresh even if no key is pressed).
+const REVALIDATE_AFTER: Duration = Duration::from_secs(10);
 
 #[derive(TypedBuilder)]
 pub struct Props {
     dir: PathBuf,
     size: Size,
     file: Option<PathBuf>,
-    pending_request: Option<Uuid>,
+    config: Arc<Config>,
+    /// The script defining custom actions.
+    #[builder(default)]
+    script: Option<Rc<Script>>,
+    clipboard: Rc<RefCell<Clipboard>>,
+    #[builder(default)]
+    choose_mode: Option<ChooseMode>,
 }
 
 pub struct Contents {
     state: State,
+    /// The count prefix for the next motion.
+    count: Count,
 }
 
 impl Component<Props, Event, Effect> for Contents {
     fn new(props: Props) -> Self {
         let state = State::from(props);
-        Self { state }
+        Self {
+            state,
+            count: Count::default(),
+        }
     }
 
     fn handle(&mut self, event: Event) -> Option<Effect> {
-        match self.map(event) {
+        if self.state.renaming.is_some() {
+            if let Event::Term { event } = event {
+                return self.handle_rename(event);
+            }
+        }
+
+        // The register is only yanked into by the next yank.
+        let mut register: Option<char> = None;
+        if let Event::Term {
+            event: TermEvent::KeyEvent(key_event),
+        } = &event
+        {
+            // An escalation is only offered until the next key is pressed.
+            let escalation: Option<Escalation> = self.state.escalation.take();
+            let yanking: bool = std::mem::take(&mut self.state.yanking);
+            let choosing_register: bool = std::mem::take(&mut self.state.choosing_register);
+            register = self.state.register.take();
+            self.state.clear_status();
+            if choosing_register {
+                return match key_event {
+                    KeyEvent {
+                        key: Key::Char(name),
+                        mods: KeyMods::NONE | KeyMods::SHIFT,
+                    } if Registers::is_name(*name) => {
+                        self.state.perform(Action::UseRegister { name: *name })
+                    }
+                    KeyEvent {
+                        key: Key::Escape, ..
+                    } => None,
+                    _ => Some(Effect::Bell),
+                };
+            }
+            if yanking {
+                return match key_event {
+                    KeyEvent {
+                        key: Key::Char(key),
+                        mods: KeyMods::NONE | KeyMods::SHIFT,
+                    } => match PathFormat::from_key(*key) {
+                        Some(format) => self.state.perform(Action::YankAs { format, register }),
+                        None => Some(Effect::Bell),
+                    },
+                    KeyEvent {
+                        key: Key::Escape, ..
+                    } => None,
+                    _ => Some(Effect::Bell),
+                };
+            }
+
+            if !self.state.filtering {
+                if let Some(effect) = self.handle_chord(key_event) {
+                    return effect;
+                }
+            }
+            if let (
+                Some(escalation),
+                KeyEvent {
+                    key: Key::Char('E'),
+                    mods: KeyMods::SHIFT,
+                },
+            ) = (escalation, key_event)
+            {
+                return self.state.perform(Action::Escalate { escalation });
+            }
+        }
+
+        let event: Event = match event {
+            Event::Term { event } if self.state.filtering => return self.handle_filter(event),
+            event => event,
+        };
+
+        let count: usize = match &event {
+            Event::Term {
+                event: TermEvent::KeyEvent(key_event),
+            } => {
+                if self.count.push(key_event) {
+                    return None;
+                }
+                self.count.take()
+            }
+            _ => 1,
+        };
+
+        let action: Option<Action> = match (self.map(event, count), register) {
+            (Some(Action::ReallyYank), Some(_)) => Some(Action::YankAs {
+                format: PathFormat::Absolute,
+                register,
+            }),
+            (action, _) => action,
+        };
+        let effect: Option<Effect> = match action {
             Some(action) => self.state.perform(action),
             None => Some(Effect::Bell),
+        };
+        if self.state.yanking && register.is_some() {
+            // The register is kept for the format that is chosen from the menu.
+            self.state.register = register;
         }
+        effect
     }
 
     fn render(&self, size: Size) -> Fabric {
+        let footer: Option<Fabric> = match (&self.state.status, &self.state.filter) {
+            _ if self.state.renaming.is_some() => {
+                let (phrase, _) = self.state.renaming.as_ref().unwrap();
+                Some(self.render_rename(phrase, size.columns))
+            }
+            (Some(status), _) => Some(self.render_status(status, size.columns)),
+            _ if self.state.selection.is_some() => Some(self.render_status(
+                &format!(
+                    "-- SELECT -- {} marked (y yank, D delete, x cut, X copy, <Esc> stop)",
+                    self.state.marked().len()
+                ),
+                size.columns,
+            )),
+            (None, Some(filter)) => Some(self.render_filter(filter, size.columns)),
+            (None, None) => None,
+        };
+
+        match (footer, size.rows) {
+            (Some(footer), 1) => footer,
+            (Some(footer), rows) if rows > 1 => {
+                let entries: Fabric = self.render_entries(Size::new(rows - 1, size.columns));
+                entries.quilt_bottom(footer)
+            }
+            _ => self.render_entries(size),
+        }
+    }
+}
+
+impl Inspect for Contents {
+    fn inspect(&self) -> Inspection {
+        let state: &State = &self.state;
+        Inspection::new("Contents")
+            .field("dir", &state.dir)
+            .field("entries", state.shown_count())
+            .field("selected", state.viewport.selected())
+            .field("filter", state.filter.as_ref().map(ListFilter::phrase))
+            .field("filtering", state.filtering)
+            .field("detailed", state.detailed)
+            .field("tree", state.tree.as_ref().map(Tree::expanded_count))
+            .field("pending_details_request", state.pending_details_request)
+            .field("revalidating", state.revalidating)
+            .field("status", &state.status)
+            .field("escalation", &state.escalation)
+            .field("yanking", state.yanking)
+            .field(
+                "marked",
+                state.selection.as_ref().map(|_| state.marked().len()),
+            )
+            .field("register", state.register)
+    }
+}
+
+/// Return the hint for fixing an error reading a directory (if there is one).
+fn hint(error: &GetFilesError) -> Option<String> {
+    let escalate: &str = "press E to list it with elevated privileges";
+    match error {
+        GetFilesError::NoReadPermission => Some(format!(
+            "Ask its owner to grant read permission (chmod +r) or {}.",
+            escalate
+        )),
+        GetFilesError::NoSearchPermission { dir } => Some(format!(
+            "Ask the owner of {} to grant search permission (chmod +x) or {}.",
+            dir.display(),
+            escalate
+        )),
+        GetFilesError::SecurityPolicy => Some(format!(
+            "Check the audit log for the denial (ausearch -m avc) or {}.",
+            escalate
+        )),
+        GetFilesError::PermissionDenied => Some(format!("Or {}.", escalate)),
+        _ => None,
+    }
+}
+
+/// Render an error reading a directory (and a hint for fixing it below the error).
+fn render_error(error: &GetFilesError, size: Size) -> Fabric {
+    let hint: String = match hint(error) {
+        Some(hint) if size.rows >= 3 => hint,
+        _ => return Fabric::center(&error.to_string(), size),
+    };
+
+    let rows_before: usize = (size.rows - 2) / 2;
+    let mut yarns: Vec<Yarn> = vec![Yarn::blank(size.columns); rows_before];
+    yarns.push(Yarn::center(&error.to_string(), size.columns));
+    let mut hint = Yarn::center(&hint, size.columns);
+    hint.color(Color::GrayedText.into());
+    yarns.push(hint);
+
+    let mut fabric = Fabric::from(yarns);
+    fabric.pad_bottom(size.rows);
+    fabric
+}
+
+impl Contents {
+    /// Return the directory whose contents are shown.
+    pub fn dir(&self) -> &Path {
+        &self.state.dir
+    }
+
+    /// Return whether the phrase of the filter is being edited.
+    pub fn filtering(&self) -> bool {
+        self.state.filtering
+    }
+
+    /// Return the physical location of the directory if it differs from the directory (as it was
+    /// found by the daemon with the files).
+    pub fn physical_dir(&self) -> Option<&Path> {
+        self.state.physical_dir.as_deref()
+    }
+
+    /// Return a summary of the entries (the numbers of files and directories, the sort order, and
+    /// the filter) if the files of the directory were gotten.
+    pub fn summary(&self) -> Option<String> {
+        let filter: Option<&str> = self
+            .state
+            .filter
+            .as_ref()
+            .map(ListFilter::phrase)
+            .filter(|phrase| !phrase.is_empty());
+        Some(describe_summary(
+            self.state.summary?,
+            self.state.sort_mode,
+            self.state.config.sort().collation(),
+            filter,
+        ))
+    }
+
+    /// Render the entries of the directory.
+    fn render_entries(&self, size: Size) -> Fabric {
         match self.state.file_infos() {
             None => Fabric::new(size),
             Some(file_infos) => match file_infos {
                 Ok(_) => {
                     let visible_file_infos = self.state.visible_file_infos().unwrap();
                     if visible_file_infos.is_empty() {
-                        return Fabric::center("The directory is empty.", size);
+                        let message: &str = match self.state.filter {
+                            Some(_) => "No entries match the filter.",
+                            None => "The directory is empty.",
+                        };
+                        return Fabric::center(message, size);
                     }
 
+                    let max_open_count: usize = self.state.max_open_count();
+                    let now = SystemTime::now();
+                    let detail_columns: &[DetailColumn] = match self.state.detailed {
+                        true => self.state.config.browser().detail_columns(),
+                        false => &[],
+                    };
+                    let marked: HashSet<&Path> = self
+                        .state
+                        .marked()
+                        .into_iter()
+                        .map(FileInfo::path)
+                        .collect();
+
                     let mut yarns: Vec<Yarn> = Vec::new();
                     for (entry, row) in visible_file_infos.iter().zip(0..size.rows) {
                         let mut string: String;
@@ -67,16 +370,70 @@ impl Component<Props, Event, Effect> for Contents {
                         }
 
                         let hidden = string.starts_with('.');
+                        if let Some(tree) = &self.state.tree {
+                            string = format!("{}{}", self.tree_prefix(tree, entry), string);
+                        }
+                        let selected: bool = Some(row) == self.state.viewport.selected_row();
 
                         let mut yarn = Yarn::from(string);
 
-                        if Some(row) == self.state.selected {
+                        if selected {
                             yarn.color(Color::InvertedText.into());
-                            yarn.background(Color::Highlight.into());
+                        } else if entry.is_mount_point() {
+                            yarn.color(Color::MountPoint.into());
                         } else if hidden {
                             yarn.color(Color::LightGrayedText.into());
                         }
-                        yarn.resize(size.columns);
+
+                        if self.state.detailed {
+                            if let Some(extended_attributes) = entry.extended_attributes() {
+                                for tag in extended_attributes.tags() {
+                                    let mut marker = Yarn::from(" \u{25CF}");
+                                    marker.color(Color::Tag(tag.color()).into());
+                                    yarn = yarn.concat(marker);
+                                }
+                            }
+                        }
+
+                        if selected {
+                            yarn.background(Color::Highlight.into());
+                        } else if marked.contains(entry.path()) {
+                            yarn.background(Color::Marked.into());
+                        }
+
+                        let mut columns = Yarn::new();
+                        for column in detail_columns {
+                            let width: usize = detail_column_width(*column);
+                            let mut column = Yarn::from(format!(
+                                "{:>width$}",
+                                detail_column_text(*column, entry, now).unwrap_or_default(),
+                                width = width
+                            ));
+                            column.truncate(width);
+                            column.color(Color::GrayedText.into());
+                            columns = columns.concat(column);
+                        }
+                        if let Some(open_count) = self.state.open_count(entry.path()) {
+                            let mut column = match open_count {
+                                0 => Yarn::blank(OPEN_COUNT_WIDTH),
+                                _ => Yarn::from(format!(
+                                    "{:>width$}",
+                                    open_count,
+                                    width = OPEN_COUNT_WIDTH
+                                )),
+                            };
+                            column.truncate(OPEN_COUNT_WIDTH);
+                            column.color(Color::heat(open_count, max_open_count).into());
+                            columns = columns.concat(column);
+                        }
+
+                        match columns.len() {
+                            len if len > 0 && size.columns > len => {
+                                yarn.resize(size.columns - len);
+                                yarn = yarn.concat(columns);
+                            }
+                            _ => yarn.resize(size.columns),
+                        }
                         yarns.push(yarn);
                     }
 
@@ -85,24 +442,169 @@ impl Component<Props, Event, Effect> for Contents {
 
                     fabric
                 }
-                Err(error) => Fabric::center(&error.to_string(), size),
+                Err(error) => render_error(error, size),
             },
         }
     }
-}
 
-impl Contents {
-    fn map(&self, event: Event) -> Option<Action> {
+    /// Return the indentation (and the marker of whether a directory is expanded) of an entry in
+    /// the tree view.
+    fn tree_prefix(&self, tree: &Tree, entry: &FileInfo) -> String {
+        let indentation: String = "  ".repeat(tree::depth(&self.state.dir, entry.path()));
+        let marker: &str = match (tree::is_expandable(entry), tree.is_expanded(entry.path())) {
+            (true, true) => "\u{25BE} ",
+            (true, false) => "\u{25B8} ",
+            (false, _) => "  ",
+        };
+        format!("{}{}", indentation, marker)
+    }
+
+    /// Render the line with the phrase of the filter and the number of matches.
+    fn render_filter(&self, filter: &ListFilter, columns: usize) -> Fabric {
+        let phrase: &str = match self.state.filtering {
+            true => self.state.phrase.value(),
+            false => filter.phrase(),
+        };
+        let phrase: String = format!("/{}", phrase);
+        let matches: String = format!("{} matches", filter.match_count());
+        let padding: usize = columns.saturating_sub(phrase.chars().count() + matches.len());
+        let mut yarn = Yarn::from(format!("{}{}{}", phrase, " ".repeat(padding), matches));
+        yarn.resize(columns);
+        yarn.color(Color::InvertedText.into());
+        yarn.background(Color::focus_or_important(self.state.filtering).into());
+        Fabric::from(yarn)
+    }
+
+    fn render_rename(&self, phrase: &Phrase, columns: usize) -> Fabric {
+        let mut yarn = Yarn::from(format!("Rename to: {}", phrase.value()));
+        yarn.resize(columns);
+        yarn.color(Color::InvertedText.into());
+        yarn.background(Color::focus_or_important(true).into());
+        Fabric::from(yarn)
+    }
+
+    /// Handle a key as a part of a chord. Return `None` if the key is not a part of a chord (and
+    /// should be handled as usual), or else the effect of the key.
+    fn handle_chord(&mut self, key_event: &KeyEvent) -> Option<Option<Effect>> {
+        let key: char = match key_event {
+            KeyEvent {
+                key: Key::Char(key),
+                mods: KeyMods::NONE | KeyMods::SHIFT,
+            } => *key,
+            KeyEvent {
+                key: Key::Escape, ..
+            } if self.state.chords.cancel() => return Some(None),
+            _ => {
+                self.state.chords.cancel();
+                return None;
+            }
+        };
+
+        match self.state.chords.handle(key, Instant::now()) {
+            ChordHandling::Passed => None,
+            ChordHandling::Pending { keys } => {
+                let leader: char = self.state.chords.leader();
+                let candidates: Vec<String> = self
+                    .state
+                    .chords
+                    .candidates(&keys)
+                    .into_iter()
+                    .map(|(chord, command)| format!("{}{} {}", leader, chord, command))
+                    .collect();
+                self.state
+                    .set_status(format!("{}{}  ({})", leader, keys, candidates.join(", ")));
+                Some(None)
+            }
+            ChordHandling::Chosen { command } => match command_action(&command) {
+                Some(action) => Some(self.state.perform(action)),
+                None => Some(Some(Effect::Toast {
+                    message: format!("Unknown command for a chord: {}", command),
+                    level: ToastLevel::Error,
+                })),
+            },
+            ChordHandling::Unknown { .. } => Some(Some(Effect::Bell)),
+        }
+    }
+
+    /// Render the line with a status message.
+    fn render_status(&self, status: &str, columns: usize) -> Fabric {
+        let mut yarn = Yarn::from(status);
+        yarn.resize(columns);
+        yarn.color(Color::InvertedText.into());
+        yarn.background(Color::InvertedBackground.into());
+        Fabric::from(yarn)
+    }
+
+    /// Handle a terminal event while the phrase of the filter is being edited.
+    fn handle_filter(&mut self, event: TermEvent) -> Option<Effect> {
+        let action: Option<Action> = match event {
+            TermEvent::KeyEvent(KeyEvent {
+                key: Key::Escape, ..
+            }) => Some(Action::ClearFilter),
+            term_event => match self.state.phrase.handle(PhraseEvent::TermEvent(term_event)) {
+                Some(PhraseEffect::Enter { .. }) => Some(Action::AcceptFilter),
+                Some(PhraseEffect::Quit) => Some(Action::ClearFilter),
+                Some(PhraseEffect::Bell) => None,
+                None => Some(Action::UpdateFilter),
+            },
+        };
+
+        match action {
+            Some(action) => self.state.perform(action),
+            None => Some(Effect::Bell),
+        }
+    }
+
+    fn handle_rename(&mut self, event: TermEvent) -> Option<Effect> {
+        let (phrase, _) = self.state.renaming.as_mut()?;
+        let action: Option<Action> = match event {
+            TermEvent::KeyEvent(KeyEvent {
+                key: Key::Escape, ..
+            }) => Some(Action::CloseRename),
+            term_event => match phrase.handle(PhraseEvent::TermEvent(term_event)) {
+                Some(PhraseEffect::Enter { phrase }) => {
+                    Some(Action::ConfirmRename { name: phrase })
+                }
+                Some(PhraseEffect::Quit) => Some(Action::CloseRename),
+                Some(PhraseEffect::Bell) => None,
+                None => return None,
+            },
+        };
+
+        match action {
+            Some(action) => self.state.perform(action),
+            None => Some(Effect::Bell),
+        }
+    }
+
+    fn map(&self, event: Event, count: usize) -> Option<Action> {
         match event {
             Event::Response(response) => Some(Action::HandleResponse(response)),
+            Event::TimedOut { request, kind } => Some(Action::TimeOut { request, kind }),
+            Event::ConfirmDelete { index } => Some(Action::ConfirmDelete { index }),
+            Event::CancelDelete => Some(Action::CloseDeletePrompt),
+            Event::Tick => Some(Action::Revalidate),
             Event::Resize { size } => Some(Action::Resize { size }),
+            Event::Jump { dir } => Some(Action::Jump { dir }),
             Event::Term { event } => {
                 if let TermEvent::KeyEvent(key_event) = event {
                     match key_event {
+                        // NOTE: The custom actions of the script override the built-in commands.
+                        KeyEvent {
+                            key: Key::Char(key),
+                            mods: KeyMods::NONE | KeyMods::SHIFT,
+                        } if self
+                            .state
+                            .script
+                            .as_ref()
+                            .is_some_and(|script| script.binds(key)) =>
+                        {
+                            Some(Action::RunScript { key })
+                        }
                         KeyEvent {
                             key: Key::Char('j'),
                             mods: KeyMods::NONE,
-                        } => Some(Action::Down),
+                        } => Some(Action::Down { count }),
                         KeyEvent {
                             key: Key::Char('J'),
                             mods: KeyMods::SHIFT,
@@ -110,15 +612,75 @@ impl Contents {
                         KeyEvent {
                             key: Key::Char('k'),
                             mods: KeyMods::NONE,
-                        } => Some(Action::Up),
+                        } => Some(Action::Up { count }),
                         KeyEvent {
                             key: Key::Char('K'),
                             mods: KeyMods::SHIFT,
                         } => Some(Action::ReallyUp),
+                        KeyEvent {
+                            key: Key::Char('d'),
+                            mods: KeyMods::CONTROL,
+                        } => Some(Action::HalfPageDown { count }),
+                        KeyEvent {
+                            key: Key::Char('u'),
+                            mods: KeyMods::CONTROL,
+                        } => Some(Action::HalfPageUp { count }),
+                        KeyEvent {
+                            key: Key::Char('f'),
+                            mods: KeyMods::CONTROL,
+                        } => Some(Action::PageDown { count }),
+                        KeyEvent {
+                            key: Key::Char('b'),
+                            mods: KeyMods::CONTROL,
+                        } => Some(Action::PageUp { count }),
                         KeyEvent {
                             key: Key::Char('r'),
                             ..
                         } => Some(Action::Refresh),
+                        KeyEvent {
+                            key: Key::Char('/'),
+                            ..
+                        } => Some(Action::OpenFilter),
+                        KeyEvent {
+                            key: Key::Char('n'),
+                            mods: KeyMods::NONE,
+                        } => Some(Action::NextMatch { count }),
+                        KeyEvent {
+                            key: Key::Char('N'),
+                            mods: KeyMods::SHIFT,
+                        } => Some(Action::PreviousMatch { count }),
+                        KeyEvent {
+                            key: Key::Escape, ..
+                        } if self.state.selection.is_some() => Some(Action::StopSelecting),
+                        KeyEvent {
+                            key: Key::Escape, ..
+                        } if self.state.filter.is_some() => Some(Action::ClearFilter),
+                        KeyEvent {
+                            key: Key::Char('~'),
+                            ..
+                        } => Some(Action::JumpHome),
+                        KeyEvent {
+                            key: Key::Char('^'),
+                            ..
+                        } => Some(Action::JumpRoot),
+                        KeyEvent {
+                            key: Key::Char('-'),
+                            mods: KeyMods::NONE,
+                        } => Some(Action::JumpBack),
+                        KeyEvent {
+                            key: Key::Char('P'),
+                            mods: KeyMods::SHIFT,
+                        } => Some(Action::TogglePhysical),
+                        KeyEvent {
+                            key: Key::Char('E'),
+                            mods: KeyMods::SHIFT,
+                        } if self.state.permission_denied() => Some(Action::Escalate {
+                            escalation: Escalation::list(&self.state.dir),
+                        }),
+                        KeyEvent {
+                            key: Key::CarriageReturn,
+                            ..
+                        } if self.state.choose_mode.is_some() => Some(Action::Choose),
                         KeyEvent {
                             key: Key::Char('l'),
                             ..
@@ -139,10 +701,54 @@ impl Contents {
                             key: Key::Char('y'),
                             mods: KeyMods::NONE,
                         } => Some(Action::Yank),
+                        KeyEvent {
+                            key: Key::Char('T'),
+                            mods: KeyMods::SHIFT,
+                        } => Some(Action::Touch),
+                        KeyEvent {
+                            key: Key::Char('m'),
+                            mods: KeyMods::NONE,
+                        } => Some(Action::OpenRename),
+                        KeyEvent {
+                            key: Key::Char('d'),
+                            mods: KeyMods::NONE,
+                        } => Some(Action::OpenDeletePrompt { recursive: false }),
+                        KeyEvent {
+                            key: Key::Char('D'),
+                            mods: KeyMods::SHIFT,
+                        } => Some(Action::OpenDeletePrompt { recursive: true }),
+                        KeyEvent {
+                            key: Key::Char('x'),
+                            mods: KeyMods::NONE,
+                        } => Some(Action::Mark { moving: true }),
+                        KeyEvent {
+                            key: Key::Char('X'),
+                            mods: KeyMods::SHIFT,
+                        } => Some(Action::Mark { moving: false }),
+                        KeyEvent {
+                            key: Key::Char('V'),
+                            mods: KeyMods::SHIFT,
+                        } => Some(Action::Paste),
+                        KeyEvent {
+                            key: Key::Char('v'),
+                            mods: KeyMods::NONE,
+                        } => Some(Action::ToggleSelecting),
+                        KeyEvent {
+                            key: Key::Char('o'),
+                            mods: KeyMods::NONE,
+                        } => Some(Action::CycleSort),
+                        KeyEvent {
+                            key: Key::Char(' '),
+                            mods: KeyMods::NONE,
+                        } if self.state.selection.is_some() => Some(Action::ToggleMark),
                         KeyEvent {
                             key: Key::Char('Y'),
                             mods: KeyMods::SHIFT,
                         } => Some(Action::ReallyYank),
+                        KeyEvent {
+                            key: Key::Char('"'),
+                            mods: KeyMods::NONE | KeyMods::SHIFT,
+                        } => Some(Action::ChooseRegister),
                         KeyEvent {
                             key: Key::Char('b'),
                             ..
@@ -163,10 +769,60 @@ impl Contents {
                             key: Key::Char('f'),
                             ..
                         } => Some(Action::OpenFinder),
+                        KeyEvent {
+                            key: Key::Char('F'),
+                            mods: KeyMods::SHIFT,
+                        } => Some(Action::OpenFlatList),
                         KeyEvent {
                             key: Key::Char('s'),
                             ..
                         } => Some(Action::OpenSearcher),
+                        KeyEvent {
+                            key: Key::Char('I'),
+                            mods: KeyMods::SHIFT,
+                        } => Some(Action::ToggleDetails),
+                        KeyEvent {
+                            key: Key::Char('t'),
+                            mods: KeyMods::NONE,
+                        } => Some(Action::ToggleTree),
+                        KeyEvent {
+                            key: Key::Char('R'),
+                            mods: KeyMods::SHIFT,
+                        } => Some(Action::OpenReader),
+                        KeyEvent {
+                            key: Key::Char('i'),
+                            mods: KeyMods::NONE,
+                        } => Some(Action::PreviewImage),
+                        KeyEvent {
+                            key: Key::Char(' '),
+                            mods: KeyMods::NONE,
+                        } => Some(Action::OpenPager),
+                        KeyEvent {
+                            key: Key::Char('L'),
+                            mods: KeyMods::SHIFT,
+                        } => Some(Action::OpenLogViewer),
+                        KeyEvent {
+                            key: Key::Char('p'),
+                            mods: KeyMods::NONE,
+                        } => Some(Action::OpenProcessList),
+                        KeyEvent {
+                            key: Key::Char('O'),
+                            mods: KeyMods::SHIFT,
+                        } => Some(Action::OpenExternally),
+                        // NOTE: The files in use are listed with `U` (rather than `O`, which opens
+                        // the selected file externally).
+                        KeyEvent {
+                            key: Key::Char('U'),
+                            mods: KeyMods::SHIFT,
+                        } => Some(Action::OpenOpenFiles),
+                        KeyEvent {
+                            key: Key::Char('M'),
+                            mods: KeyMods::SHIFT,
+                        } => Some(Action::OpenMounts),
+                        KeyEvent {
+                            key: Key::Char(':'),
+                            ..
+                        } => Some(Action::OpenPalette),
                         _ => None,
                     }
                 } else {
@@ -179,22 +835,106 @@ impl Contents {
 
 pub enum Event {
     Response(Response),
-    Resize { size: Size },
-    Term { event: TermEvent },
+    /// The request (of the kind) timed out.
+    TimedOut {
+        request: Uuid,
+        kind: RequestKind,
+    },
+    /// The choice at the index was chosen in the prompt for deleting entries.
+    ConfirmDelete {
+        index: usize,
+    },
+    /// The prompt for deleting entries was cancelled.
+    CancelDelete,
+    /// A tick of the app (which the stale files are gotten again on).
+    Tick,
+    Resize {
+        size: Size,
+    },
+    /// Change the directory (for example to a directory in the sidebar).
+    Jump {
+        dir: PathBuf,
+    },
+    Term {
+        event: TermEvent,
+    },
 }
 
 struct State {
     size: Size,
     dir: PathBuf,
+    /// The directory before the last change of directory (to jump back to).
+    previous_dir: Option<PathBuf>,
+    /// The logical path of the directory (through symbolic links) and its physical location if
+    /// the physical location is shown instead.
+    logical_dir: Option<(PathBuf, PathBuf)>,
+    /// The physical location of the directory if it differs from the directory (found by the
+    /// daemon so that following links never blocks).
+    physical_dir: Option<PathBuf>,
 
     starting_file: Option<PathBuf>,
-    pending_request: Option<Uuid>,
+    /// The selection to restore once the files are refreshed.
+    restore: Option<Restore>,
+    pending_details_request: Option<Uuid>,
+    /// The request for getting the files again in the background (while the stale files are
+    /// still shown).
+    revalidating: Option<Uuid>,
 
     /// The dir entries (if they can be read).
     file_infos: Option<GetFilesResult>,
-
-    selected: Option<usize>,
-    offset: usize,
+    /// When the entries were gotten.
+    fetched_at: Option<Instant>,
+    /// The numbers of files in the directory (counted by the daemon).
+    summary: Option<FilesSummary>,
+
+    viewport: ListViewport,
+
+    /// The filter of the entries (if there is one).
+    filter: Option<ListFilter>,
+    /// Whether the phrase of the filter is being edited.
+    filtering: bool,
+    phrase: Phrase,
+
+    /// A message shown on the last row until the next key is pressed.
+    status: Option<String>,
+    /// An operation that failed because permission was denied (which can be retried with the
+    /// escalation command until the next key is pressed).
+    escalation: Option<Escalation>,
+    /// The entries which the prompt for deleting is open for (if it is open).
+    deleting: Option<Deleting>,
+    /// The input for the new name of the entry that is being renamed (if it is open) and the path
+    /// of the entry.
+    renaming: Option<(Phrase, PathBuf)>,
+    /// Whether the menu of the formats that the path of the selected entry can be copied in is
+    /// shown (until the next key is pressed).
+    yanking: bool,
+    /// Whether the register that the next yank goes into is being chosen (until the next key is
+    /// pressed).
+    choosing_register: bool,
+    /// The register that the next yank goes into instead of the clipboard (if one was chosen).
+    register: Option<char>,
+    /// The entries that are marked (if entries are being selected).
+    selection: Option<Selection>,
+    /// What the entries are ordered by (before their names).
+    sort_mode: SortMode,
+    /// The chords (sequences of keys after the leader key) which run commands.
+    chords: Chords,
+
+    /// Whether the details of the entries (such as tags) are shown.
+    detailed: bool,
+    /// The expanded directories if the entries are shown as a tree.
+    tree: Option<Tree>,
+    /// The number of times files have been opened (loaded when the details are shown, and then
+    /// counted up as the browser opens files so that they aren't read again for every listing).
+    open_counts: Option<HashMap<PathBuf, usize>>,
+
+    config: Arc<Config>,
+    /// The script defining custom actions.
+    script: Option<Rc<Script>>,
+    clipboard: Rc<RefCell<Clipboard>>,
+
+    /// What can be chosen (if Insh is being run as a file picker).
+    choose_mode: Option<ChooseMode>,
 }
 
 impl From<Props> for State {
@@ -205,11 +945,41 @@ impl From<Props> for State {
         State {
             size,
             dir,
+            previous_dir: None,
+            logical_dir: None,
+            physical_dir: None,
             starting_file: props.file,
-            pending_request: props.pending_request,
+            restore: None,
+            pending_details_request: None,
+            revalidating: None,
             file_infos: None,
-            selected: None,
-            offset: 0,
+            fetched_at: None,
+            summary: None,
+            viewport: ListViewport::new(size.rows, props.config.general().scrolloff()),
+            filter: None,
+            filtering: false,
+            phrase: Phrase::new(
+                PhraseProps::builder()
+                    .clipboard(props.clipboard.clone())
+                    .build(),
+            ),
+            status: None,
+            escalation: None,
+            deleting: None,
+            renaming: None,
+            yanking: false,
+            choosing_register: false,
+            register: None,
+            selection: None,
+            sort_mode: props.config.sort().mode(),
+            chords: Chords::from(props.config.keys()),
+            detailed: false,
+            tree: None,
+            open_counts: None,
+            config: props.config,
+            script: props.script,
+            clipboard: props.clipboard,
+            choose_mode: props.choose_mode,
         }
     }
 }
@@ -220,341 +990,1440 @@ impl State {
         &self.file_infos
     }
 
-    fn visible_file_infos(&self) -> Option<&[FileInfo]> {
-        let file_infos: &GetFilesResult = match &self.file_infos {
-            Some(file_infos) => file_infos,
-            None => {
-                return None;
-            }
-        };
-
-        let file_infos: &Vec<FileInfo> = match file_infos {
-            Ok(file_infos) => file_infos,
-            Err(_) => {
-                return None;
-            }
-        };
+    /// Return if reading the directory failed because permission was denied.
+    fn permission_denied(&self) -> bool {
+        matches!(&self.file_infos, Some(Err(error)) if error.is_permission_denied())
+    }
 
-        if file_infos.is_empty() {
-            return Some(&[]);
+    fn visible_file_infos(&self) -> Option<Vec<&FileInfo>> {
+        match &self.file_infos {
+            Some(Ok(_)) => Some(
+                self.viewport
+                    .visible()
+                    .filter_map(|number| self.shown_entry(number))
+                    .collect(),
+            ),
+            _ => None,
         }
-
-        let start = self.offset;
-        let end = cmp::min(self.offset + self.size.rows, file_infos.len());
-        Some(&file_infos[start..end])
     }
 
     fn entry_number(&self) -> Option<usize> {
-        self.selected.map(|selected| self.offset + selected)
+        self.viewport.selected()
     }
 
     fn entry(&self) -> Option<&FileInfo> {
-        let file_infos: &GetFilesResult = match &self.file_infos {
-            Some(file_infos) => file_infos,
-            None => {
-                return None;
-            }
+        self.shown_entry(self.entry_number()?)
+    }
+
+    /// Return the entries that are marked (in the order that they are shown).
+    fn marked(&self) -> Vec<&FileInfo> {
+        let selection: &Selection = match &self.selection {
+            Some(selection) => selection,
+            None => return Vec::new(),
         };
+        let range: Option<RangeInclusive<usize>> =
+            match (self.shown_position(&selection.anchor), self.entry_number()) {
+                (Some(anchor), Some(selected)) => Some(anchor.min(selected)..=anchor.max(selected)),
+                _ => None,
+            };
+        (0..self.shown_count())
+            .filter_map(|number| {
+                let entry: &FileInfo = self.shown_entry(number)?;
+                let in_range: bool = range.as_ref().is_some_and(|range| range.contains(&number));
+                (in_range != selection.toggled.contains(entry.path())).then_some(entry)
+            })
+            .collect()
+    }
 
-        match self.entry_number() {
-            Some(entry_number) => match file_infos {
-                Ok(file_infos) => Some(&file_infos[entry_number]),
-                Err(_) => None,
-            },
-            None => None,
+    /// Return the entries that an operation acts on (the marked entries if entries are being
+    /// selected, otherwise the selected entry).
+    fn targets(&self) -> Vec<&FileInfo> {
+        match &self.selection {
+            Some(_) => self.marked(),
+            None => self.entry().into_iter().collect(),
         }
     }
 
-    fn set_dir(&mut self, dir: &Path) -> Option<Effect> {
-        self.dir = dir.to_path_buf();
-        None
+    /// Return the number of entries that are shown (the entries that match the filter if there is
+    /// one).
+    fn shown_count(&self) -> usize {
+        match (&self.file_infos, &self.filter) {
+            (Some(Ok(_)), Some(filter)) => filter.match_count(),
+            (Some(Ok(file_infos)), None) => file_infos.len(),
+            _ => 0,
+        }
     }
 
-    fn reset_file_infos(&mut self) {
-        self.file_infos = None;
-        self.selected = None;
-        self.offset = 0;
+    /// Return the shown entry with the number `number`.
+    fn shown_entry(&self, number: usize) -> Option<&FileInfo> {
+        let file_infos: &Vec<FileInfo> = match &self.file_infos {
+            Some(Ok(file_infos)) => file_infos,
+            _ => return None,
+        };
+        let index: usize = match &self.filter {
+            Some(filter) => filter.get(number)?,
+            None => number,
+        };
+        file_infos.get(index)
     }
 
-    fn resize(&mut self, new_size: Size) -> Option<Effect> {
-        if let Some(selected) = self.selected {
-            if let Some(Ok(file_infos)) = &self.file_infos {
-                let rows_before = self.size.rows;
-                let entry_count = file_infos.len();
-                let mut visible_file_infos_count = cmp::min(rows_before, entry_count - self.offset);
-                let selected_percent: f64 = selected as f64 / visible_file_infos_count as f64;
-
-                let mut new_selected: usize = (new_size.rows as f64 * selected_percent) as usize;
-                let mut new_offset: usize;
-                let entry_number = self.offset + selected;
-                match entry_number.cmp(&new_selected) {
-                    Ordering::Less | Ordering::Equal => {
-                        new_offset = 0;
-                        new_selected = entry_number;
-                    }
-                    Ordering::Greater => {
-                        new_offset = entry_number - new_selected;
-                        visible_file_infos_count = entry_count - new_offset;
-                        if visible_file_infos_count < new_size.rows {
-                            let bottom_pinned_offset = entry_count.saturating_sub(new_size.rows);
-                            let difference = new_offset - bottom_pinned_offset;
-                            new_selected += difference;
-                            new_offset = bottom_pinned_offset;
-                        }
-                    }
-                }
+    /// Return the number of the shown entry at `path`.
+    fn shown_position(&self, path: &Path) -> Option<usize> {
+        (0..self.shown_count()).find(|number| {
+            self.shown_entry(*number)
+                .is_some_and(|entry| entry.path() == path)
+        })
+    }
 
-                self.offset = new_offset;
-                self.selected = Some(new_selected);
-            }
+    /// Return the number of rows for the entries (the last row shows the input for renaming an
+    /// entry, the status message, or the filter if there is one).
+    fn list_rows(&self) -> usize {
+        match self.status.is_some()
+            || self.selection.is_some()
+            || self.filter.is_some()
+            || self.renaming.is_some()
+        {
+            true => self.size.rows.saturating_sub(1),
+            false => self.size.rows,
         }
+    }
 
-        self.size = new_size;
-
-        None
+    /// Show a status message until the next key is pressed.
+    fn set_status(&mut self, status: String) {
+        self.status = Some(status);
+        self.viewport.resize(self.list_rows());
     }
 
-    fn down(&mut self) -> Option<Effect> {
-        let file_infos: &GetFilesResult = match &self.file_infos {
-            Some(file_infos) => file_infos,
-            None => {
-                return None;
-            }
-        };
+    /// Stop showing the status message (if there is one).
+    fn clear_status(&mut self) {
+        if self.status.take().is_some() {
+            self.viewport.resize(self.list_rows());
+        }
+    }
 
-        let file_infos: &Vec<FileInfo> = match file_infos {
-            Ok(file_infos) => file_infos,
-            Err(_) => {
-                return None;
-            }
-        };
+    /// Offer to retry an operation that failed because permission was denied with the escalation
+    /// command.
+    fn offer_escalation(&mut self, escalation: Escalation) {
+        self.escalation = Some(escalation);
+        self.set_status(String::from(
+            "Permission denied. Press E to retry with elevated privileges.",
+        ));
+    }
 
-        if file_infos.is_empty() {
-            return None;
+    /// Return if files can be changed. If not (in the read-only mode), then show why `what` can't
+    /// be done.
+    fn writable(&mut self, what: &str) -> bool {
+        if self.config.general().read_only() {
+            self.set_status(format!("Can't {} in read-only mode.", what));
+            return false;
         }
+        true
+    }
 
-        let entry_number = self.entry_number().unwrap();
-        if entry_number >= file_infos.len() - 1 {
-            return None;
-        }
-        let selected = self.selected.unwrap();
-        if selected < self.size.rows - 1 {
-            self.selected = Some(selected + 1);
-        } else {
-            self.offset += 1;
+    fn set_dir(&mut self, dir: &Path) -> Option<Effect> {
+        self.previous_dir = Some(self.dir.clone());
+        self.dir = dir.to_path_buf();
+        self.remove_filter();
+        self.stop_selecting();
+        if let Some(tree) = &mut self.tree {
+            tree.reset();
         }
-
         None
     }
 
-    /// Select the last entry and adjust the scroll position if necessary.
-    fn really_down(&mut self) -> Option<Effect> {
-        let file_infos: &GetFilesResult = match &self.file_infos {
-            Some(file_infos) => file_infos,
-            None => {
-                return None;
-            }
+    /// Return a request for the files of the directory (and the files of the expanded directories
+    /// in the tree view).
+    fn get_files_request(&self) -> Request {
+        let depth: usize = match &self.tree {
+            Some(tree) => tree.request_depth(&self.dir, self.config.browser().tree_depth()),
+            None => 0,
         };
-
-        let file_infos: &Vec<FileInfo> = match file_infos {
-            Ok(file_infos) => file_infos,
-            Err(_) => {
-                return None;
-            }
+        Request::builder()
+            .params(RequestParams::GetFiles(
+                GetFilesRequestParams::builder()
+                    .dir(self.dir.clone())
+                    .depth(depth)
+                    .build(),
+            ))
+            .build()
+    }
+
+    fn reset_file_infos(&mut self) {
+        self.file_infos = None;
+        self.fetched_at = None;
+        self.physical_dir = None;
+        self.revalidating = None;
+        self.viewport.set_len(0);
+    }
+
+    fn resize(&mut self, new_size: Size) -> Option<Effect> {
+        self.size = new_size;
+        self.viewport.resize(self.list_rows());
+        None
+    }
+
+    fn down(&mut self, count: usize) -> Option<Effect> {
+        self.viewport.down(count);
+        None
+    }
+
+    /// Select the last entry and adjust the scroll position if necessary.
+    fn really_down(&mut self) -> Option<Effect> {
+        self.viewport.last();
+        None
+    }
+
+    fn up(&mut self, count: usize) -> Option<Effect> {
+        self.viewport.up(count);
+        None
+    }
+
+    /// Select the first entry and adjust the scroll position if necessary.
+    fn really_up(&mut self) -> Option<Effect> {
+        self.viewport.first();
+        None
+    }
+
+    /// Scroll down by `distance` entries (moving the selection along with the entries).
+    fn scroll_down(&mut self, distance: usize) -> Option<Effect> {
+        self.viewport.scroll_down(distance);
+        None
+    }
+
+    /// Scroll up by `distance` entries (moving the selection along with the entries).
+    fn scroll_up(&mut self, distance: usize) -> Option<Effect> {
+        self.viewport.scroll_up(distance);
+        None
+    }
+
+    /// Refresh the contents of the browser to reflect the current state of the file system
+    /// (keeping the expanded directories of the tree view expanded).
+    fn refresh(&mut self) -> Option<Effect> {
+        if let Some(tree) = &mut self.tree {
+            tree.keep_expanded();
+        }
+        self.reload()
+    }
+
+    /// Get the files of the directory again.
+    ///
+    /// The selected entry and its row on the screen are restored once the files are refreshed.
+    fn reload(&mut self) -> Option<Effect> {
+        let request: Request = self.get_files_request();
+        self.restore = self.selection_restore(*request.uuid());
+
+        self.reset_file_infos();
+
+        Some(Effect::List(request))
+    }
+
+    /// Get the files of the directory again in the background if they are stale (the stale files
+    /// are still shown and used until the fresh files arrive).
+    fn revalidate(&mut self, now: Instant) -> Option<Effect> {
+        let fetched_at: Instant = self.fetched_at?;
+        if now.duration_since(fetched_at) < REVALIDATE_AFTER {
+            return None;
+        }
+
+        if let Some(tree) = &mut self.tree {
+            tree.keep_expanded();
+        }
+        let request: Request = self.get_files_request();
+        self.fetched_at = None;
+        self.revalidating = Some(*request.uuid());
+        Some(Effect::List(request))
+    }
+
+    /// Return the selection to restore once the files are gotten by the request.
+    fn selection_restore(&self, request: Uuid) -> Option<Restore> {
+        match (
+            self.entry(),
+            self.entry_number(),
+            self.viewport.selected_row(),
+        ) {
+            (Some(entry), Some(entry_number), Some(row)) => Some(Restore {
+                request,
+                path: entry.path().to_path_buf(),
+                entry_number,
+                row,
+            }),
+            _ => None,
+        }
+    }
+
+    fn push(&mut self) -> Option<Effect> {
+        if let Some(entry) = self.entry() {
+            let mut path: PathBuf = entry.path().to_path_buf();
+            if let Some(tree) = &self.tree {
+                if tree::is_expandable(entry) && !tree.is_expanded(&path) {
+                    return self.expand(path);
+                }
+            }
+            // The cached metadata is used rather than the file system so that a slow mount never
+            // blocks the key.
+            if entry.is_dir() {
+                // Following a link back to the directory (or one of its ancestors) would only
+                // make the path longer, so go to the physical location instead.
+                if let Some(link_target) = entry.link_target() {
+                    let dir: &Path = self.physical_dir.as_deref().unwrap_or(&self.dir);
+                    if symlink::is_cycle(link_target.path(), dir) {
+                        path = link_target.path().to_path_buf();
+                    }
+                }
+
+                self.set_dir(&path);
+
+                let request: Request = self.get_files_request();
+
+                return Some(Effect::SetDir {
+                    dir: path.to_path_buf(),
+                    get_files_request: request,
+                });
+            }
+
+            if entry.is_file() {
+                if self.choose_mode.is_some() {
+                    return Some(Effect::Choose { path });
+                }
+
+                self.count_open(&path);
+                let vim_args: VimArgs = VimArgsBuilder::new().path(&path).build();
+                return Some(Effect::OpenVim(vim_args));
+            }
+        }
+        None
+    }
+
+    /// Toggle showing the entries as a tree (with directories expanded inline).
+    fn toggle_tree(&mut self) -> Option<Effect> {
+        self.tree = match self.tree {
+            Some(_) => None,
+            None => Some(Tree::default()),
         };
+        self.reload()
+    }
+
+    /// Expand a directory in the tree view (its files are inserted once they are gotten).
+    fn expand(&mut self, dir: PathBuf) -> Option<Effect> {
+        let request = Request::builder()
+            .params(RequestParams::GetFiles(
+                GetFilesRequestParams::builder().dir(dir.clone()).build(),
+            ))
+            .build();
+        self.tree.as_mut()?.expand(dir, *request.uuid());
+        Some(Effect::Request(request))
+    }
+
+    /// Collapse a directory in the tree view.
+    fn collapse(&mut self, dir: &Path) {
+        if let Some(tree) = &mut self.tree {
+            tree.collapse(dir);
+        }
+        if let Some(Ok(file_infos)) = &mut self.file_infos {
+            tree::remove_descendants(file_infos, dir);
+        }
+        self.entries_changed(Some(dir.to_path_buf()));
+    }
 
-        if file_infos.is_empty() {
+    /// In the tree view, collapse the selected directory if it is expanded, or else select the
+    /// directory of the selected entry if it is nested. Return `None` if neither applies (and the
+    /// parent directory should be browsed instead).
+    fn collapse_or_select_parent(&mut self) -> Option<Option<Effect>> {
+        let tree: &Tree = self.tree.as_ref()?;
+        let path: PathBuf = self.entry()?.path().to_path_buf();
+        if tree.is_expanded(&path) {
+            self.collapse(&path);
+            return Some(None);
+        }
+        if tree::depth(&self.dir, &path) == 0 {
             return None;
         }
+        match path.parent().and_then(|parent| self.shown_position(parent)) {
+            Some(number) => {
+                self.viewport.select(number);
+                Some(None)
+            }
+            // The directory does not match the filter.
+            None => Some(Some(Effect::Bell)),
+        }
+    }
 
-        if file_infos.len() > self.size.rows {
-            self.offset = file_infos.len() - self.size.rows;
-            self.selected = Some(self.size.rows - 1);
-        } else {
-            self.selected = Some(file_infos.len() - 1);
+    /// Update the filter and the selection after entries were inserted or removed (keeping the
+    /// entry at `path` selected on the same row).
+    fn entries_changed(&mut self, path: Option<PathBuf>) {
+        let row: Option<usize> = self.viewport.selected_row();
+        if let (Some(filter), Some(Ok(file_infos))) = (&mut self.filter, &self.file_infos) {
+            filter.update(file_infos.iter().map(entry_name));
+        }
+        self.viewport.set_len(self.shown_count());
+        if let (Some(number), Some(row)) = (path.and_then(|path| self.shown_position(&path)), row) {
+            self.viewport.select_at_row(number, row);
+        }
+    }
+
+    /// Choose the selected entry (if it can be chosen), otherwise push it.
+    fn choose(&mut self) -> Option<Effect> {
+        let entry: &FileInfo = self.entry()?;
+        let dirs: bool = self
+            .choose_mode
+            .is_some_and(|choose_mode| choose_mode.dirs());
+        if entry.is_file() || (dirs && entry.is_dir()) {
+            return Some(Effect::Choose {
+                path: entry.path().to_path_buf(),
+            });
+        }
+        self.push()
+    }
+
+    fn pop(&mut self) -> Option<Effect> {
+        if let Some(effect) = self.collapse_or_select_parent() {
+            return effect;
+        }
+
+        let dir: PathBuf = self.dir.clone();
+        let popped: bool = self.dir.pop();
+        if popped {
+            self.previous_dir = Some(dir);
+            self.remove_filter();
+            self.reset_file_infos();
+            if let Some(tree) = &mut self.tree {
+                tree.reset();
+            }
+
+            let request: Request = self.get_files_request();
+
+            return Some(Effect::PopDir {
+                get_files_request: request,
+            });
+        }
+        None
+    }
+
+    /// Change the directory to `dir` (the current directory can be jumped back to).
+    fn jump(&mut self, dir: PathBuf) -> Option<Effect> {
+        if dir == self.dir {
+            return None;
+        }
+
+        self.set_dir(&dir);
+        self.reset_file_infos();
+
+        let request: Request = self.get_files_request();
+
+        Some(Effect::SetDir {
+            dir,
+            get_files_request: request,
+        })
+    }
+
+    /// Change the directory to the home directory.
+    fn jump_home(&mut self) -> Option<Effect> {
+        match dirs::home_dir() {
+            Some(home) => self.jump(home),
+            None => Some(Effect::Bell),
+        }
+    }
+
+    /// Change the directory to the root directory.
+    fn jump_root(&mut self) -> Option<Effect> {
+        let root: PathBuf = match self.dir.ancestors().last() {
+            Some(root) => root.to_path_buf(),
+            None => return Some(Effect::Bell),
+        };
+        self.jump(root)
+    }
+
+    /// Change the directory to the previous directory (so that repeating it toggles between the
+    /// two directories).
+    fn jump_back(&mut self) -> Option<Effect> {
+        match self.previous_dir.clone() {
+            Some(previous_dir) => self.jump(previous_dir),
+            None => Some(Effect::Bell),
+        }
+    }
+
+    /// Toggle between the logical path of the directory (through symbolic links) and its physical
+    /// location.
+    fn toggle_physical(&mut self) -> Option<Effect> {
+        if let Some((logical_dir, physical_dir)) = self.logical_dir.take() {
+            if physical_dir == self.dir {
+                return self.jump(logical_dir);
+            }
+        }
+
+        match self.physical_dir.clone() {
+            Some(physical_dir) => {
+                let logical_dir: PathBuf = self.dir.clone();
+                let effect: Option<Effect> = self.jump(physical_dir.clone());
+                self.logical_dir = Some((logical_dir, physical_dir));
+                effect
+            }
+            None => Some(Effect::Bell),
+        }
+    }
+
+    /// Copy the file name of the selected entry to the clipboard.
+    ///
+    /// If the entry is a directory, a trailing slash is added.
+    /// Create the selected file if it is missing or else update the time it was last modified at.
+    fn touch(&mut self) -> Option<Effect> {
+        if !self.writable("touch files") {
+            return None;
+        }
+
+        let path: &Path = match self.entry() {
+            Some(entry) => entry.path(),
+            None => return Some(Effect::Bell),
+        };
+
+        let request = Request::builder()
+            .params(RequestParams::TouchFile(
+                TouchFileRequestParams::builder()
+                    .path(path.to_path_buf())
+                    .build(),
+            ))
+            .build();
+        Some(Effect::Request(request))
+    }
+
+    /// Open the prompt for confirming the deletion of the selected entry (or the marked entries).
+    fn open_delete_prompt(&mut self, recursive: bool) -> Option<Effect> {
+        if !self.writable("delete files") {
+            return None;
+        }
+
+        let file_infos: Vec<FileInfo> = self.targets().into_iter().cloned().collect();
+        let name: String = match file_infos.as_slice() {
+            [] => return Some(Effect::Bell),
+            [file_info] => entry_name(file_info),
+            file_infos => format!("{} entries", file_infos.len()),
+        };
+        let question: String = match (recursive, file_infos.iter().any(FileInfo::is_dir)) {
+            (true, _) => format!("Recursively delete {}?", name),
+            (false, true) if file_infos.len() > 1 => {
+                format!("Delete {} (directories only if empty)?", name)
+            }
+            (false, true) => format!("Delete the directory {} (if empty)?", name),
+            (false, false) => format!("Delete {}?", name),
+        };
+        let choices: Vec<Choice> = vec![Choice::new("Delete", 'd'), Choice::new("Cancel", 'c')];
+        let props = PromptProps::builder()
+            .question(question)
+            .default(choices.len() - 1)
+            .choices(choices)
+            .danger(true)
+            .build();
+        self.deleting = Some(Deleting {
+            file_infos,
+            recursive,
+        });
+        Some(Effect::OpenDeletePrompt(Prompt::new(props)))
+    }
+
+    fn close_delete_prompt(&mut self) -> Option<Effect> {
+        self.deleting = None;
+        None
+    }
+
+    /// Delete the entries as chosen in the prompt (unless the choice is cancelling).
+    fn confirm_delete(&mut self, index: usize) -> Option<Effect> {
+        let Deleting {
+            file_infos,
+            recursive,
+        } = self.deleting.take()?;
+        if index != 0 {
+            return None;
+        }
+        self.stop_selecting();
+
+        let mut requests: Vec<Request> = file_infos
+            .iter()
+            .map(|file_info| {
+                Request::builder()
+                    .params(RequestParams::DeleteFile(
+                        DeleteFileRequestParams::builder()
+                            .path(file_info.path().to_path_buf())
+                            .recursive(recursive)
+                            .build(),
+                    ))
+                    .build()
+            })
+            .collect();
+        match requests.len() {
+            1 => Some(Effect::Request(requests.remove(0))),
+            _ => Some(Effect::Requests(requests)),
+        }
+    }
+
+    fn handle_delete_file_response(&mut self, params: &DeleteFileResponseParams) -> Option<Effect> {
+        let name: String = params
+            .path()
+            .file_name()
+            .unwrap_or(params.path().as_os_str())
+            .to_string_lossy()
+            .to_string();
+        match params.result() {
+            // NOTE: A file that was already deleted (by something else) is gone all the same.
+            Ok(()) | Err(DeleteFileError::FileDoesNotExist) => {}
+            Err(DeleteFileError::PermissionDenied) => {
+                self.offer_escalation(Escalation::delete(params.path(), params.recursive()));
+                return Some(Effect::Bell);
+            }
+            Err(DeleteFileError::DirectoryNotEmpty) => {
+                self.set_status(format!(
+                    "{} is not empty. Press D and then r to delete it recursively.",
+                    name
+                ));
+                return Some(Effect::Bell);
+            }
+            Err(error) => {
+                return Some(Effect::Toast {
+                    message: format!("Failed to delete {}: {}", name, error),
+                    level: ToastLevel::Error,
+                });
+            }
+        }
+
+        // The files are only gotten again if the file was listed (the directory may have changed
+        // since).
+        let listed: bool = params.path().parent().is_some_and(|parent| {
+            parent == self.dir
+                || self
+                    .tree
+                    .as_ref()
+                    .is_some_and(|tree| tree.is_expanded(parent))
+        });
+        if listed {
+            return self.refresh();
+        }
+        Some(Effect::Toast {
+            message: format!("Deleted {}", name),
+            level: ToastLevel::Info,
+        })
+    }
+
+    /// Open the input for the new name of the selected entry (filled with its name).
+    fn open_rename(&mut self) -> Option<Effect> {
+        if !self.writable("rename files") {
+            return None;
+        }
+
+        let file_info: &FileInfo = match self.entry() {
+            Some(entry) => entry,
+            None => return Some(Effect::Bell),
+        };
+        let path: PathBuf = file_info.path().to_path_buf();
+        let phrase = Phrase::new(
+            PhraseProps::builder()
+                .value(entry_name(file_info))
+                .clipboard(self.clipboard.clone())
+                .build(),
+        );
+        self.renaming = Some((phrase, path));
+        self.viewport.resize(self.list_rows());
+        None
+    }
+
+    fn close_rename(&mut self) -> Option<Effect> {
+        self.renaming = None;
+        self.viewport.resize(self.list_rows());
+        None
+    }
+
+    /// Close the input and rename the entry to the name (which is a path relative to the directory
+    /// of the entry, so the entry can be moved too).
+    fn confirm_rename(&mut self, name: String) -> Option<Effect> {
+        let (_, from) = self.renaming.take()?;
+        self.viewport.resize(self.list_rows());
+
+        let to: PathBuf = renamed_path(&from, &name)?;
+        let request = Request::builder()
+            .params(RequestParams::RenameFile(
+                RenameFileRequestParams::builder().from(from).to(to).build(),
+            ))
+            .build();
+        Some(Effect::Request(request))
+    }
+
+    fn handle_rename_file_response(&mut self, params: &RenameFileResponseParams) -> Option<Effect> {
+        let name: String = params
+            .from()
+            .file_name()
+            .unwrap_or(params.from().as_os_str())
+            .to_string_lossy()
+            .to_string();
+        match params.result() {
+            Ok(()) => {}
+            Err(RenameFileError::PermissionDenied) => {
+                self.offer_escalation(Escalation::rename(params.from(), params.to()));
+                return Some(Effect::Bell);
+            }
+            Err(RenameFileError::AlreadyExists) => {
+                self.set_status(format!("{} already exists.", params.to().display()));
+                return Some(Effect::Bell);
+            }
+            Err(error) => {
+                return Some(Effect::Toast {
+                    message: format!("Failed to rename {}: {}", name, error),
+                    level: ToastLevel::Error,
+                });
+            }
+        }
+
+        // The files are only gotten again if the file was listed (the directory may have changed
+        // since).
+        let listed = |path: &Path| {
+            path.parent().is_some_and(|parent| {
+                parent == self.dir
+                    || self
+                        .tree
+                        .as_ref()
+                        .is_some_and(|tree| tree.is_expanded(parent))
+            })
+        };
+        let (from_listed, to_listed): (bool, bool) = (listed(params.from()), listed(params.to()));
+        if from_listed || to_listed {
+            let effect: Option<Effect> = self.refresh();
+            // Select the renamed entry once the files are refreshed (if it is still listed).
+            if let (true, Some(restore)) = (to_listed, &mut self.restore) {
+                restore.path = params.to().to_path_buf();
+            }
+            return effect;
+        }
+        Some(Effect::Toast {
+            message: format!("Renamed {} to {}", name, params.to().display()),
+            level: ToastLevel::Info,
+        })
+    }
+
+    /// Mark the selected entry (or the marked entries) for copying or moving them into another
+    /// directory.
+    fn mark(&mut self, moving: bool) -> Option<Effect> {
+        if moving && !self.writable("move files") {
+            return None;
+        }
+        let paths: Vec<PathBuf> = self
+            .targets()
+            .into_iter()
+            .map(|file_info| file_info.path().to_path_buf())
+            .collect();
+        if paths.is_empty() {
+            return Some(Effect::Bell);
+        }
+        self.stop_selecting();
+        Some(Effect::Mark { paths, moving })
+    }
+
+    /// Paste the marked entry into the directory.
+    fn paste(&mut self) -> Option<Effect> {
+        if !self.writable("paste files") {
+            return None;
+        }
+        Some(Effect::Paste {
+            dir: self.dir.clone(),
+        })
+    }
+
+    /// Handle a response to copying or moving a file (which reports the progress of the copy until
+    /// the last response).
+    fn handle_transfer_file_response(
+        &mut self,
+        params: &TransferFileResponseParams,
+        moving: bool,
+        last: bool,
+    ) -> Option<Effect> {
+        let name: String = params
+            .from()
+            .file_name()
+            .unwrap_or(params.from().as_os_str())
+            .to_string_lossy()
+            .to_string();
+        let (verb, present, past): (&str, &str, &str) = match moving {
+            true => ("move", "Moving", "Moved"),
+            false => ("copy", "Copying", "Copied"),
+        };
+        match params.result() {
+            Ok(progress) if !last => {
+                self.set_status(format!("{} {}... {}%", present, name, progress.percent()));
+                return None;
+            }
+            Ok(_) => self.clear_status(),
+            Err(TransferFileError::PermissionDenied) => {
+                let escalation: Escalation = match moving {
+                    true => Escalation::rename(params.from(), params.to()),
+                    false => Escalation::copy(params.from(), params.to()),
+                };
+                self.offer_escalation(escalation);
+                return Some(Effect::Bell);
+            }
+            Err(error @ (TransferFileError::AlreadyExists | TransferFileError::IntoItself)) => {
+                self.set_status(format!("Can't {} {}: {}.", verb, name, error));
+                return Some(Effect::Bell);
+            }
+            Err(error) => {
+                return Some(Effect::Toast {
+                    message: format!("Failed to {} {}: {}", verb, name, error),
+                    level: ToastLevel::Error,
+                });
+            }
+        }
+
+        // The files are only gotten again if the file was listed (the directory may have changed
+        // since).
+        let listed = |path: &Path| {
+            path.parent().is_some_and(|parent| {
+                parent == self.dir
+                    || self
+                        .tree
+                        .as_ref()
+                        .is_some_and(|tree| tree.is_expanded(parent))
+            })
+        };
+        let (from_listed, to_listed): (bool, bool) =
+            (moving && listed(params.from()), listed(params.to()));
+        if from_listed || to_listed {
+            let effect: Option<Effect> = self.refresh();
+            // Select the pasted entry once the files are refreshed.
+            if let (true, Some(restore)) = (to_listed, &mut self.restore) {
+                restore.path = params.to().to_path_buf();
+            }
+            return effect;
+        }
+        Some(Effect::Toast {
+            message: format!("{} {} to {}", past, name, params.to().display()),
+            level: ToastLevel::Info,
+        })
+    }
+
+    fn handle_touch_file_response(&mut self, params: &TouchFileResponseParams) -> Option<Effect> {
+        let modified: SystemTime = match params.result() {
+            Ok(modified) => *modified,
+            Err(TouchFileError::PermissionDenied) => {
+                self.offer_escalation(Escalation::touch(params.path()));
+                return Some(Effect::Bell);
+            }
+            #[allow(unused_variables)]
+            Err(error) => {
+                #[cfg(feature = "logging")]
+                log::error!("Failed to touch {:?}: {}", params.path(), error);
+                return Some(Effect::Bell);
+            }
+        };
+
+        if let Some(Ok(file_infos)) = &mut self.file_infos {
+            if let Some(file_info) = file_infos
+                .iter_mut()
+                .find(|file_info| file_info.path() == params.path())
+            {
+                file_info.set_modified(Some(modified));
+            }
+        }
+
+        None
+    }
+
+    /// Show the menu of the formats that the path of the selected entry (or the paths of the
+    /// marked entries) can be copied in (which are chosen with the next key).
+    fn yank(&mut self) -> Option<Effect> {
+        if self.targets().is_empty() {
+            return None;
+        }
+        self.yanking = true;
+        let choices: Vec<String> = PathFormat::ALL
+            .iter()
+            .map(|format| format!("{}:{}", format.key(), format.label()))
+            .collect();
+        self.set_status(format!("Copy the path as: {}", choices.join("  ")));
+        None
+    }
+
+    /// Start selecting entries (marking the selected entry), or stop selecting them.
+    fn toggle_selecting(&mut self) -> Option<Effect> {
+        if self.selection.is_some() {
+            return self.stop_selecting();
+        }
+        let anchor: PathBuf = self.entry()?.path().to_path_buf();
+        self.selection = Some(Selection {
+            anchor,
+            toggled: BTreeSet::new(),
+        });
+        self.viewport.resize(self.list_rows());
+        None
+    }
+
+    /// Order the entries by the next sort mode (keeping the selected entry selected).
+    fn cycle_sort(&mut self) -> Option<Effect> {
+        self.sort_mode = self.sort_mode.next();
+        // NOTE: The files are only gotten again if directories of the tree are expanded (so that
+        // the entries of each directory are sorted under it).
+        if self
+            .tree
+            .as_ref()
+            .is_some_and(|tree| tree.expanded_count() > 0)
+        {
+            return self.refresh();
+        }
+        let selected: Option<PathBuf> = self.entry().map(|entry| entry.path().to_path_buf());
+        if let Some(Ok(file_infos)) = &mut self.file_infos {
+            sort_file_infos(
+                file_infos,
+                self.sort_mode,
+                &NameCollator::new(self.config.sort().collation()),
+            );
+        }
+        self.entries_changed(selected);
+        None
+    }
+
+    /// Stop selecting entries (unmarking them).
+    fn stop_selecting(&mut self) -> Option<Effect> {
+        if self.selection.take().is_some() {
+            self.viewport.resize(self.list_rows());
+        }
+        None
+    }
+
+    /// Toggle whether the selected entry is marked.
+    fn toggle_mark(&mut self) -> Option<Effect> {
+        let path: PathBuf = self.entry()?.path().to_path_buf();
+        let selection: &mut Selection = self.selection.as_mut()?;
+        if !selection.toggled.remove(&path) {
+            selection.toggled.insert(path);
+        }
+        None
+    }
+
+    /// Show the registers that hold text (the register that the next yank goes into is chosen
+    /// with the next key).
+    fn choose_register(&mut self) -> Option<Effect> {
+        self.choosing_register = true;
+        let preview: String = self.clipboard.borrow().registers().preview();
+        self.set_status(format!("Yank into the register (\" or a-z): {}", preview));
+        None
+    }
+
+    /// Yank into the register (instead of the clipboard) with the next yank.
+    fn use_register(&mut self, name: char) -> Option<Effect> {
+        self.register = Some(name);
+        self.set_status(format!("Yank into register \"{}", name));
+        None
+    }
+
+    /// Copy the path of the selected entry (or the paths of the marked entries, one per line) to
+    /// the clipboard (or put it in the register) in the format.
+    ///
+    /// If an entry is a directory, a trailing slash is added.
+    fn yank_as(&mut self, format: PathFormat, register: Option<char>) -> Option<Effect> {
+        let targets: Vec<&FileInfo> = self.targets();
+        if targets.is_empty() {
+            return None;
+        }
+        let home: Option<PathBuf> = dirs::home_dir();
+        let mut lines: Vec<String> = Vec::new();
+        for entry in targets {
+            let path: &Path = entry.path();
+            match format.format(path, entry.is_dir(), home.as_deref()) {
+                Some(line) => lines.push(line),
+                None => {
+                    return Some(Effect::Toast {
+                        message: format!("{} is not in a git repository", path.display()),
+                        level: ToastLevel::Error,
+                    });
+                }
+            }
+        }
+        let yanked: String = match lines.len() {
+            1 => lines[0].clone(),
+            count => format!("{} paths", count),
+        };
+        let contents: String = lines.join("\n");
+        self.stop_selecting();
+
+        let mut clipboard = self.clipboard.borrow_mut();
+        let message: String = match register {
+            Some(name) => {
+                let message: String = format!("Yanked {} into register \"{}", yanked, name);
+                clipboard.registers_mut().set(name, contents);
+                message
+            }
+            None => copied_message(&yanked, clipboard.copy(contents)),
+        };
+
+        Some(Effect::Toast {
+            message,
+            level: ToastLevel::Info,
+        })
+    }
+
+    fn open_file_creator(&mut self, file_type: FileType) -> Option<Effect> {
+        let what: &str = match file_type {
+            FileType::Dir => "create directories",
+            _ => "create files",
+        };
+        if !self.writable(what) {
+            return None;
+        }
+
+        Some(Effect::OpenFileCreator {
+            dir: self.dir.clone(),
+            file_type,
+        })
+    }
+
+    fn open_finder(&self) -> Option<Effect> {
+        Some(Effect::OpenFinder {
+            dir: self.dir.clone(),
+        })
+    }
+
+    /// List all of the files under the directory (like a find without a pattern).
+    fn open_flat_list(&self) -> Option<Effect> {
+        Some(Effect::OpenFlatList {
+            dir: self.dir.clone(),
+        })
+    }
+
+    /// Open the selected file in the markdown reader.
+    fn open_reader(&self) -> Option<Effect> {
+        let entry: &FileInfo = self.entry()?;
+        if !entry.is_file() {
+            return Some(Effect::Bell);
+        }
+        let path: &Path = entry.path();
+        Some(Effect::OpenReader {
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Preview the selected file as an image.
+    fn preview_image(&self) -> Option<Effect> {
+        let entry: &FileInfo = self.entry()?;
+        if !entry.is_file() {
+            return Some(Effect::Bell);
+        }
+        let path: &Path = entry.path();
+        Some(Effect::PreviewImage {
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Open the selected file in the pager.
+    fn open_pager(&self) -> Option<Effect> {
+        let entry: &FileInfo = self.entry()?;
+        if !entry.is_file() {
+            return Some(Effect::Bell);
+        }
+        let path: &Path = entry.path();
+        Some(Effect::OpenPager {
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Open the selected file in the log viewer.
+    fn open_log_viewer(&self) -> Option<Effect> {
+        let entry: &FileInfo = self.entry()?;
+        if !entry.is_file() {
+            return Some(Effect::Bell);
+        }
+        let path: &Path = entry.path();
+        Some(Effect::OpenLogViewer {
+            path: path.to_path_buf(),
+        })
+    }
+
+    fn open_process_list(&self) -> Option<Effect> {
+        Some(Effect::OpenProcessList {
+            dir: self.dir.clone(),
+        })
+    }
+
+    /// Open the command palette (with the current directory and the selected entry as the context of
+    /// the commands).
+    fn open_palette(&self) -> Option<Effect> {
+        Some(Effect::OpenPalette {
+            dir: self.dir.clone(),
+            selection: self.entry().map(|entry| entry.path().to_path_buf()),
+        })
+    }
+
+    /// Open the selected entry with the default application of the operating system.
+    fn open_externally(&self) -> Option<Effect> {
+        let entry: &FileInfo = match self.entry() {
+            Some(entry) => entry,
+            None => return Some(Effect::Bell),
+        };
+        match open_externally(entry.path()) {
+            Ok(()) => None,
+            #[allow(unused_variables)]
+            Err(error) => {
+                #[cfg(feature = "logging")]
+                log::error!("Failed to open {:?} externally: {}", entry.path(), error);
+                Some(Effect::Toast {
+                    message: format!("Failed to open {} externally.", entry.path().display()),
+                    level: ToastLevel::Error,
+                })
+            }
+        }
+    }
+
+    /// Open the list of the open files in the selected directory (or in the current directory if
+    /// the selected entry is not a directory).
+    fn open_open_files(&self) -> Option<Effect> {
+        let dir: PathBuf = match self.entry() {
+            Some(entry) if entry.is_dir() => entry.path().to_path_buf(),
+            _ => self.dir.clone(),
+        };
+        Some(Effect::OpenOpenFiles { dir })
+    }
+
+    fn open_searcher(&self) -> Option<Effect> {
+        Some(Effect::OpenSearcher {
+            dir: self.dir.clone(),
+        })
+    }
+
+    fn run_bash(&self) -> Option<Effect> {
+        Some(Effect::RunBash {
+            dir: self.dir.clone(),
+        })
+    }
+
+    /// Run the custom action of the script bound to the key.
+    fn run_script(&mut self, key: char) -> Option<Effect> {
+        let script: Rc<Script> = self.script.clone()?;
+        let selection: Option<PathBuf> = self.entry().map(|entry| entry.path().to_path_buf());
+        let script_effects: Vec<ScriptEffect> =
+            match script.run(key, &self.dir, selection.as_deref()) {
+                Ok(script_effects) => script_effects,
+                Err(error) => {
+                    return Some(Effect::Toast {
+                        message: error,
+                        level: ToastLevel::Error,
+                    });
+                }
+            };
+
+        let mut effect: Option<Effect> = None;
+        for script_effect in script_effects {
+            match script_effect {
+                ScriptEffect::Copy { text } => {
+                    self.clipboard.borrow_mut().copy(text);
+                }
+                ScriptEffect::Browse { dir } => effect = self.jump(dir),
+                ScriptEffect::Bash { dir } => effect = Some(Effect::RunBash { dir }),
+                ScriptEffect::Edit { path } => {
+                    self.count_open(&path);
+                    let vim_args: VimArgs = VimArgsBuilder::new().path(&path).build();
+                    effect = Some(Effect::OpenVim(vim_args));
+                }
+                ScriptEffect::Find { dir } => effect = Some(Effect::OpenFinder { dir }),
+                ScriptEffect::Search { dir } => effect = Some(Effect::OpenSearcher { dir }),
+            }
+        }
+        effect
+    }
+
+    /// Open the prompt for the phrase of the filter (keeping the current phrase).
+    fn open_filter(&mut self) -> Option<Effect> {
+        let phrase: String = self
+            .filter
+            .as_ref()
+            .map(|filter| filter.phrase().to_string())
+            .unwrap_or_default();
+        self.phrase.handle(PhraseEvent::Set { phrase });
+        self.filtering = true;
+        if self.filter.is_none() {
+            self.filter = Some(ListFilter::default());
+            self.update_filter();
+        }
+        None
+    }
+
+    /// Match the phrase that is being edited against the entries.
+    ///
+    /// The selected entry stays selected if it still matches, otherwise the first match is
+    /// selected.
+    fn update_filter(&mut self) -> Option<Effect> {
+        let selected: Option<PathBuf> = self.entry().map(|entry| entry.path().to_path_buf());
+        if let (Some(filter), Some(Ok(file_infos))) = (&mut self.filter, &self.file_infos) {
+            filter.set_phrase(self.phrase.value(), file_infos.iter().map(entry_name));
+        }
+        self.viewport.resize(self.list_rows());
+        self.viewport.set_len(self.shown_count());
+        match selected.and_then(|path| self.shown_position(&path)) {
+            Some(number) => self.viewport.select(number),
+            None => self.viewport.first(),
         }
-
         None
     }
 
-    fn up(&mut self) -> Option<Effect> {
-        if let Some(selected) = self.selected {
-            if selected > 0 {
-                self.selected = Some(selected.saturating_sub(1))
-            } else {
-                self.offset = self.offset.saturating_sub(1);
-            }
+    /// Stop editing the phrase of the filter (removing the filter if the phrase is empty).
+    fn accept_filter(&mut self) -> Option<Effect> {
+        self.filtering = false;
+        if self.phrase.value().is_empty() {
+            return self.clear_filter();
         }
-
         None
     }
 
-    /// Select the first entry and adjust the scroll position if necessary.
-    fn really_up(&mut self) -> Option<Effect> {
-        self.offset = 0;
-        self.selected = Some(0);
+    /// Remove the filter and keep the selected entry selected.
+    fn clear_filter(&mut self) -> Option<Effect> {
+        let selected: Option<PathBuf> = self.entry().map(|entry| entry.path().to_path_buf());
+        self.remove_filter();
+        if let Some(number) = selected.and_then(|path| self.shown_position(&path)) {
+            self.viewport.select(number);
+        }
         None
     }
 
-    /// Refresh the contents of the browser to reflect the current state of the file system.
-    fn refresh(&mut self) -> Option<Effect> {
-        // TODO: Maintain the currently selected entry (if possible) and maintain the currently
-        // selected scroll position (if possible).
-
-        self.reset_file_infos();
-
-        let request = Request::builder()
-            .params(RequestParams::GetFiles(
-                GetFilesRequestParams::builder()
-                    .dir(self.dir.clone())
-                    .build(),
-            ))
-            .build();
-        self.pending_request = Some(*request.uuid());
-        Some(Effect::Request(request))
+    /// Remove the filter (if there is one).
+    fn remove_filter(&mut self) {
+        self.filter = None;
+        self.filtering = false;
+        self.viewport.resize(self.list_rows());
+        self.viewport.set_len(self.shown_count());
     }
 
-    fn push(&mut self) -> Option<Effect> {
-        if let Some(entry) = self.entry() {
-            let path: PathBuf = entry.path().to_path_buf();
-            if path.is_dir() {
-                self.set_dir(&path);
-
-                let request = Request::builder()
-                    .params(RequestParams::GetFiles(
-                        GetFilesRequestParams::builder()
-                            .dir(self.dir.clone())
-                            .build(),
-                    ))
-                    .build();
-                self.pending_request = Some(*request.uuid());
-
-                return Some(Effect::SetDir {
-                    dir: path.to_path_buf(),
-                    get_files_request: request,
-                });
-            }
-
-            if path.is_file() {
-                let vim_args: VimArgs = VimArgsBuilder::new().path(&path).build();
-                return Some(Effect::OpenVim(vim_args));
-            }
-        }
+    /// Select the entry `count` matches of the filter after (or before if not `forward`) the
+    /// selected entry, wrapping around at the ends.
+    fn jump_to_match(&mut self, count: usize, forward: bool) -> Option<Effect> {
+        let shown_count: usize = self.shown_count();
+        let entry_number: usize = match (&self.filter, self.entry_number()) {
+            (Some(_), Some(entry_number)) if shown_count > 0 => entry_number,
+            _ => return Some(Effect::Bell),
+        };
+        let count: usize = count % shown_count;
+        let number: usize = match forward {
+            true => (entry_number + count) % shown_count,
+            false => (entry_number + shown_count - count) % shown_count,
+        };
+        self.viewport.select(number);
         None
     }
 
-    fn pop(&mut self) -> Option<Effect> {
-        let popped: bool = self.dir.pop();
-        if popped {
-            self.reset_file_infos();
-
-            let request = Request::builder()
-                .params(RequestParams::GetFiles(
-                    GetFilesRequestParams::builder()
-                        .dir(self.dir.clone())
-                        .build(),
-                ))
-                .build();
-            self.pending_request = Some(*request.uuid());
-
-            return Some(Effect::PopDir {
-                get_files_request: request,
-            });
+    /// Toggle showing the details of the entries.
+    fn toggle_details(&mut self) -> Option<Effect> {
+        self.detailed = !self.detailed;
+        self.load_open_counts();
+        if self.detailed {
+            if let Some(request) = self.get_file_details_request() {
+                return Some(Effect::Request(request));
+            }
         }
         None
     }
 
-    /// Copy the file name of the selected entry to the clipboard.
-    ///
-    /// If the entry is a directory, a trailing slash is added.
-    fn yank(&self) -> Option<Effect> {
-        let entry: &FileInfo = match self.entry() {
-            Some(entry) => entry,
-            None => {
-                return None;
+    /// Load the number of times files have been opened if they are shown.
+    fn load_open_counts(&mut self) {
+        self.open_counts = match self.detailed && self.config.browser().open_counts() {
+            true => {
+                let mut data: Data = Data::read();
+                data.release();
+                Some(data.files.open_counts)
             }
+            false => None,
         };
+    }
 
-        let mut contents: String = entry.name().unwrap().to_string_lossy().to_string();
-        if entry.path().is_dir() {
-            contents.push('/');
+    /// Count that the browser opens a file (if the counts are shown).
+    fn count_open(&mut self, path: &Path) {
+        if let Some(open_counts) = &mut self.open_counts {
+            *open_counts.entry(path.to_path_buf()).or_default() += 1;
         }
+    }
 
-        let mut clipboard = Clipboard::new();
-        clipboard.copy(contents);
+    /// Return the number of times a file has been opened (if the counts are shown).
+    fn open_count(&self, path: &Path) -> Option<usize> {
+        self.open_counts
+            .as_ref()
+            .map(|open_counts| open_counts.get(path).copied().unwrap_or(0))
+    }
 
-        None
+    /// Return the largest number of times any of the entries has been opened.
+    fn max_open_count(&self) -> usize {
+        match &self.file_infos {
+            Some(Ok(file_infos)) => file_infos
+                .iter()
+                .filter_map(|file_info| self.open_count(file_info.path()))
+                .max()
+                .unwrap_or(0),
+            _ => 0,
+        }
     }
 
-    /// Copy the path of the selected entry to the clipboard.
-    ///
-    /// If the entry is a directory, a trailing slash is added.
-    fn really_yank(&self) -> Option<Effect> {
-        let entry: &FileInfo = match self.entry() {
-            Some(entry) => entry,
-            None => {
+    /// Return a request for the details of the entries (if there are any entries).
+    fn get_file_details_request(&mut self) -> Option<Request> {
+        let paths: Vec<PathBuf> = match &self.file_infos {
+            Some(Ok(file_infos)) if !file_infos.is_empty() => file_infos
+                .iter()
+                .map(|file_info| file_info.path().to_path_buf())
+                .collect(),
+            _ => {
                 return None;
             }
         };
 
-        let path: PathBuf = entry.path().to_path_buf();
-        let mut contents: String = path.to_string_lossy().to_string();
-        if path.is_dir() {
-            contents.push('/');
-        }
+        let request = Request::builder()
+            .params(RequestParams::GetFileDetails(
+                GetFileDetailsRequestParams::builder().paths(paths).build(),
+            ))
+            .build();
+        self.pending_details_request = Some(*request.uuid());
+        Some(request)
+    }
 
-        let mut clipboard = Clipboard::new();
-        clipboard.copy(contents);
+    fn handle_file_details_response(
+        &mut self,
+        uuid: Uuid,
+        params: GetFileDetailsResponseParams,
+    ) -> Option<Effect> {
+        if self.pending_details_request != Some(uuid) {
+            #[cfg(feature = "logging")]
+            log::debug!("The response is not for the pending details request.");
+            return None;
+        }
+        self.pending_details_request = None;
+
+        let mut details: HashMap<PathBuf, FileInfo> = params
+            .into_file_infos()
+            .into_iter()
+            .map(|file_info| (file_info.path().to_path_buf(), file_info))
+            .collect();
+
+        if let Some(Ok(file_infos)) = &mut self.file_infos {
+            for file_info in file_infos {
+                if let Some(details) = details.remove(file_info.path()) {
+                    file_info.take_details(details);
+                }
+            }
+        }
 
         None
     }
 
-    fn open_file_creator(&self, file_type: FileType) -> Option<Effect> {
-        Some(Effect::OpenFileCreator {
-            dir: self.dir.clone(),
-            file_type,
-        })
-    }
+    /// Insert the files of a directory that is expanded in the tree view. If getting the files
+    /// failed, then the directory is collapsed (and the error is shown if the directory was
+    /// expanded by itself).
+    fn handle_expanded_files(
+        &mut self,
+        dir: &Path,
+        params: GetFilesResponseParams,
+        by_itself: bool,
+    ) -> Option<Effect> {
+        let slow_file_system: bool = params.slow_file_system();
+        let mut children: Vec<FileInfo> = match params.into_result() {
+            Ok(children) => children,
+            Err(error) => {
+                self.collapse(dir);
+                return match by_itself {
+                    true => Some(Effect::Toast {
+                        message: format!("Can't expand {}: {}", dir.display(), error),
+                        level: ToastLevel::Error,
+                    }),
+                    false => None,
+                };
+            }
+        };
+        sort_file_infos(
+            &mut children,
+            self.sort_mode,
+            &NameCollator::new(self.config.sort().collation()),
+        );
+
+        let selected: Option<PathBuf> = self.entry().map(|entry| entry.path().to_path_buf());
+        let inserted: bool = match &mut self.file_infos {
+            Some(Ok(file_infos)) => tree::insert_children(file_infos, dir, children),
+            _ => false,
+        };
+        if !inserted {
+            return None;
+        }
+        self.entries_changed(selected);
+
+        // The entry that was selected before refreshing might be in the directory.
+        if let Some(number) = self
+            .restore
+            .as_ref()
+            .and_then(|restore| self.shown_position(&restore.path))
+        {
+            let restore: Restore = self.restore.take().unwrap();
+            self.viewport.select_at_row(number, restore.row);
+        }
 
-    fn open_finder(&self) -> Option<Effect> {
-        Some(Effect::OpenFinder {
-            dir: self.dir.clone(),
+        let get_file_details_request: Option<Request> = match self.detailed {
+            true => self.get_file_details_request(),
+            false => None,
+        };
+        Some(Effect::GotFiles {
+            slow_file_system,
+            get_file_details_request,
         })
     }
 
-    fn open_searcher(&self) -> Option<Effect> {
-        Some(Effect::OpenSearcher {
-            dir: self.dir.clone(),
-        })
-    }
+    /// Stop waiting for the request that timed out.
+    fn time_out(&mut self, request: Uuid, kind: RequestKind) -> Option<Effect> {
+        if let Some(dir) = self
+            .tree
+            .as_mut()
+            .and_then(|tree| tree.take_pending(&request))
+        {
+            self.collapse(&dir);
+            return Some(Effect::Toast {
+                message: format!("Expanding {} timed out", dir.display()),
+                level: ToastLevel::Error,
+            });
+        }
+        if self.pending_details_request == Some(request) {
+            self.pending_details_request = None;
+            return None;
+        }
+        if kind != RequestKind::GetFiles {
+            return Some(Effect::Toast {
+                message: String::from("inshd did not respond in time"),
+                level: ToastLevel::Error,
+            });
+        }
 
-    fn run_bash(&self) -> Option<Effect> {
-        Some(Effect::RunBash {
-            dir: self.dir.clone(),
-        })
+        // The stale files are kept (and gotten again later) if they were being revalidated.
+        if self.revalidating == Some(request) {
+            self.revalidating = None;
+            self.fetched_at = Some(Instant::now());
+            return None;
+        }
+        if self.file_infos.is_none() {
+            let error =
+                GetFilesError::OtherErrorReading(String::from("Getting the files timed out."));
+            self.file_infos = Some(Err(error));
+        }
+        None
     }
 
     fn handle_response(&mut self, response: Response) -> Option<Effect> {
         #[cfg(feature = "logging")]
         log::debug!("Handling response...");
 
-        let pending_request: Uuid = match self.pending_request {
-            Some(pending_request) => pending_request,
-            None => {
-                #[cfg(feature = "logging")]
-                log::debug!("There is no pending request.");
-                return None;
+        let uuid: Uuid = *response.uuid();
+        let last: bool = response.last();
+        // The parameters are moved out of the response so that the files aren't copied.
+        let params: GetFilesResponseParams = match response.into_params() {
+            ResponseParams::GetFiles(params) => {
+                if let Some(dir) = self.tree.as_mut().and_then(|tree| tree.take_pending(&uuid)) {
+                    return self.handle_expanded_files(&dir, params, true);
+                }
+                params
+            }
+            ResponseParams::GetFileDetails(params) => {
+                return self.handle_file_details_response(uuid, params);
+            }
+            ResponseParams::TouchFile(params) => return self.handle_touch_file_response(&params),
+            ResponseParams::DeleteFile(params) => return self.handle_delete_file_response(&params),
+            ResponseParams::RenameFile(params) => return self.handle_rename_file_response(&params),
+            ResponseParams::CopyFile(params) => {
+                return self.handle_transfer_file_response(&params, false, last);
+            }
+            ResponseParams::MoveFile(params) => {
+                return self.handle_transfer_file_response(&params, true, last);
             }
-        };
-
-        if response.uuid() != &pending_request {
-            #[cfg(feature = "logging")]
-            log::debug!("The response is not for the pending request.");
-            return None;
-        }
-
-        let params: &GetFilesResponseParams = match response.params() {
-            ResponseParams::GetFiles(params) => params,
             _ => {
                 #[cfg(feature = "logging")]
                 log::error!("Unexpected response parameters.");
@@ -562,50 +2431,90 @@ impl State {
             }
         };
 
-        self.file_infos = Some(params.result().clone());
+        // NOTE: Only the responses to the latest request for the files of the directory are routed
+        // here (the requests for the files replace the ones before).
+        // The files of the subdirectories (in the tree view) follow the files of the directory.
+        if !params.dir().as_os_str().is_empty() && params.dir() != self.dir {
+            let dir: PathBuf = params.dir().to_path_buf();
+            let accepted: bool = self.tree.as_mut().is_some_and(|tree| tree.accept(&dir));
+            return match accepted {
+                true => self.handle_expanded_files(&dir, params, false),
+                false => None,
+            };
+        }
+
+        // Keep the entry that is selected now (which may have changed since the files were
+        // requested) selected once the stale files are replaced.
+        if self.revalidating == Some(uuid) {
+            self.revalidating = None;
+            self.restore = self.selection_restore(uuid);
+        }
+
+        self.physical_dir = params.physical_dir().map(Path::to_path_buf);
+        self.summary = params.summary();
+        let slow_file_system: bool = params.slow_file_system();
+        let mut result: GetFilesResult = params.into_result();
+        if let Ok(file_infos) = &mut result {
+            sort_file_infos(
+                file_infos,
+                self.sort_mode,
+                &NameCollator::new(self.config.sort().collation()),
+            );
+        }
+        self.file_infos = Some(result);
+        self.fetched_at = Some(Instant::now());
+        self.pending_details_request = None;
+
+        let restore: Option<Restore> = self
+            .restore
+            .take()
+            .filter(|restore| restore.request == uuid);
+
+        if let (Some(filter), Some(Ok(file_infos))) = (&mut self.filter, &self.file_infos) {
+            filter.update(file_infos.iter().map(entry_name));
+        }
 
         // Adjust the selected entry and offset.
-        let selected;
-        let offset;
-        if let Some(Ok(file_infos)) = &self.file_infos {
-            if file_infos.is_empty() {
-                selected = None;
-                offset = 0;
-            } else if let Some(file) = &self.starting_file {
-                let index = file_infos.iter().position(|entry| entry.path() == file);
-                match index {
-                    Some(index) => {
-                        if index < self.size.rows {
-                            selected = Some(index);
-                            offset = 0;
-                        } else {
-                            selected = Some(0);
-                            offset = index;
-                        }
-                    }
-                    None => {
-                        selected = Some(0);
-                        offset = 0;
+        self.viewport.set_len(self.shown_count());
+        if let Some(file) = &self.starting_file {
+            match self.shown_position(file) {
+                // Keep the first rows in place if the file is on them, otherwise scroll the file
+                // to the top.
+                Some(number) if number < self.list_rows() => {
+                    self.viewport.select_at_row(number, number)
+                }
+                Some(number) => self.viewport.select_at_row(number, 0),
+                None => self.viewport.first(),
+            }
+        } else if let Some(restore) = restore {
+            // Select the same entry (or the entry that took its place if it disappeared) on the
+            // same row.
+            match self.shown_position(&restore.path) {
+                Some(number) => self.viewport.select_at_row(number, restore.row),
+                None => {
+                    self.viewport
+                        .select_at_row(restore.entry_number, restore.row);
+                    // The entry might be in an expanded directory whose files follow.
+                    if !last {
+                        self.restore = Some(restore);
                     }
                 }
-            } else {
-                selected = if !file_infos.is_empty() {
-                    Some(0)
-                } else {
-                    None
-                };
-                offset = 0;
             }
         } else {
-            selected = Some(0);
-            offset = 0;
+            self.viewport.first();
         }
-        self.selected = selected;
-        self.offset = offset;
 
         self.starting_file = None;
 
-        None
+        let get_file_details_request: Option<Request> = match self.detailed {
+            true => self.get_file_details_request(),
+            false => None,
+        };
+
+        Some(Effect::GotFiles {
+            slow_file_system,
+            get_file_details_request,
+        })
     }
 }
 
@@ -613,43 +2522,345 @@ impl Stateful<Action, Effect> for State {
     fn perform(&mut self, action: Action) -> Option<Effect> {
         match action {
             Action::Resize { size } => self.resize(size),
-            Action::Down => self.down(),
+            Action::Down { count } => self.down(count),
             Action::ReallyDown => self.really_down(),
-            Action::Up => self.up(),
+            Action::Up { count } => self.up(count),
             Action::ReallyUp => self.really_up(),
+            Action::HalfPageDown { count } => self.scroll_down(self.viewport.half_page() * count),
+            Action::HalfPageUp { count } => self.scroll_up(self.viewport.half_page() * count),
+            Action::PageDown { count } => self.scroll_down(self.viewport.page() * count),
+            Action::PageUp { count } => self.scroll_up(self.viewport.page() * count),
             Action::Refresh => self.refresh(),
             Action::Push => self.push(),
+            Action::Choose => self.choose(),
             Action::Pop => self.pop(),
+            Action::Jump { dir } => self.jump(dir),
+            Action::JumpHome => self.jump_home(),
+            Action::JumpRoot => self.jump_root(),
+            Action::JumpBack => self.jump_back(),
+            Action::TogglePhysical => self.toggle_physical(),
+            Action::Escalate { escalation } => Some(Effect::Escalate { escalation }),
+            Action::Touch => self.touch(),
+            Action::OpenDeletePrompt { recursive } => self.open_delete_prompt(recursive),
+            Action::CloseDeletePrompt => self.close_delete_prompt(),
+            Action::ConfirmDelete { index } => self.confirm_delete(index),
+            Action::OpenRename => self.open_rename(),
+            Action::CloseRename => self.close_rename(),
+            Action::ConfirmRename { name } => self.confirm_rename(name),
+            Action::Mark { moving } => self.mark(moving),
+            Action::Paste => self.paste(),
+            Action::CycleSort => self.cycle_sort(),
+            Action::ToggleSelecting => self.toggle_selecting(),
+            Action::StopSelecting => self.stop_selecting(),
+            Action::ToggleMark => self.toggle_mark(),
             Action::Yank => self.yank(),
-            Action::ReallyYank => self.really_yank(),
+            Action::YankAs { format, register } => self.yank_as(format, register),
+            Action::ReallyYank => self.yank_as(PathFormat::Absolute, None),
+            Action::ChooseRegister => self.choose_register(),
+            Action::UseRegister { name } => self.use_register(name),
             Action::OpenFileCreator { file_type } => self.open_file_creator(file_type),
             Action::OpenFinder => self.open_finder(),
+            Action::OpenFlatList => self.open_flat_list(),
             Action::OpenSearcher => self.open_searcher(),
+            Action::OpenReader => self.open_reader(),
+            Action::PreviewImage => self.preview_image(),
+            Action::OpenPager => self.open_pager(),
+            Action::OpenLogViewer => self.open_log_viewer(),
+            Action::OpenProcessList => self.open_process_list(),
+            Action::OpenExternally => self.open_externally(),
+            Action::OpenOpenFiles => self.open_open_files(),
+            Action::OpenMounts => Some(Effect::OpenMounts),
+            Action::OpenPalette => self.open_palette(),
             Action::RunBash => self.run_bash(),
+            Action::RunScript { key } => self.run_script(key),
             Action::HandleResponse(response) => self.handle_response(response),
+            Action::TimeOut { request, kind } => self.time_out(request, kind),
+            Action::Revalidate => self.revalidate(Instant::now()),
+            Action::ToggleDetails => self.toggle_details(),
+            Action::ToggleTree => self.toggle_tree(),
+            Action::OpenFilter => self.open_filter(),
+            Action::UpdateFilter => self.update_filter(),
+            Action::AcceptFilter => self.accept_filter(),
+            Action::ClearFilter => self.clear_filter(),
+            Action::NextMatch { count } => self.jump_to_match(count, true),
+            Action::PreviousMatch { count } => self.jump_to_match(count, false),
         }
     }
 }
 
+/// Return the action of a command that can be run by a chord (if the command exists).
+fn command_action(command: &str) -> Option<Action> {
+    let action: Action = match command {
+        "find" => Action::OpenFinder,
+        "flat_list" => Action::OpenFlatList,
+        "search" => Action::OpenSearcher,
+        "tree" => Action::ToggleTree,
+        "details" => Action::ToggleDetails,
+        "filter" => Action::OpenFilter,
+        "refresh" => Action::Refresh,
+        "pager" => Action::OpenPager,
+        "reader" => Action::OpenReader,
+        "image" => Action::PreviewImage,
+        "log_viewer" => Action::OpenLogViewer,
+        "processes" => Action::OpenProcessList,
+        "open_files" => Action::OpenOpenFiles,
+        "mounts" => Action::OpenMounts,
+        "palette" => Action::OpenPalette,
+        "open_externally" => Action::OpenExternally,
+        "bash" => Action::RunBash,
+        "yank" => Action::Yank,
+        "touch" => Action::Touch,
+        "delete" => Action::OpenDeletePrompt { recursive: false },
+        "delete_recursively" => Action::OpenDeletePrompt { recursive: true },
+        "rename" => Action::OpenRename,
+        "cut" => Action::Mark { moving: true },
+        "copy" => Action::Mark { moving: false },
+        "paste" => Action::Paste,
+        "select" => Action::ToggleSelecting,
+        "sort" => Action::CycleSort,
+        "create_file" => Action::OpenFileCreator {
+            file_type: FileType::File,
+        },
+        "create_dir" => Action::OpenFileCreator {
+            file_type: FileType::Dir,
+        },
+        "home" => Action::JumpHome,
+        "root" => Action::JumpRoot,
+        "back" => Action::JumpBack,
+        _ => return None,
+    };
+    Some(action)
+}
+
+/// The entries that are marked while selecting.
+struct Selection {
+    /// The entry that selecting started at (the entries from it to the selected entry are marked).
+    anchor: PathBuf,
+    /// The entries whose marks were toggled (a toggled entry in the range is unmarked).
+    toggled: BTreeSet<PathBuf>,
+}
+
+/// The entries which the prompt for deleting is open for.
+struct Deleting {
+    file_infos: Vec<FileInfo>,
+    /// Whether directories are deleted with their contents (else only if they are empty).
+    recursive: bool,
+}
+
+/// Return the path that the entry at `from` is renamed to by the name (a path relative to the
+/// directory of the entry, or an absolute path), or `None` if the name is empty or unchanged.
+fn renamed_path(from: &Path, name: &str) -> Option<PathBuf> {
+    if name.is_empty() {
+        return None;
+    }
+    let to: PathBuf = from.parent()?.join(name);
+    match to == from {
+        true => None,
+        false => Some(to),
+    }
+}
+
+/// Return the name of an entry that is matched against the phrase of the filter.
+fn entry_name(file_info: &FileInfo) -> String {
+    file_info
+        .name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Return a description of the numbers of files in a directory, the sort order, and the filter (like
+/// `3 files, 1 dir (2 hidden), sorted by codepoint`). The collation is named when the entries are
+/// only sorted by name.
+fn describe_summary(
+    summary: FilesSummary,
+    sort_mode: SortMode,
+    collation: Collation,
+    filter: Option<&str>,
+) -> String {
+    let order: &str = match sort_mode {
+        SortMode::Name => collation.name(),
+        sort_mode => sort_mode.name(),
+    };
+    let count = |count: usize, noun: &str| match count {
+        1 => format!("1 {}", noun),
+        count => format!("{} {}s", count, noun),
+    };
+    let mut description: String = format!(
+        "{}, {} ({} hidden), sorted by {}",
+        count(summary.files(), "file"),
+        count(summary.dirs(), "dir"),
+        summary.hidden(),
+        order
+    );
+    if let Some(filter) = filter {
+        description.push_str(&format!(", filtered by /{}", filter));
+    }
+    description
+}
+
+/// Return the width of the column of the detailed view.
+fn detail_column_width(column: DetailColumn) -> usize {
+    match column {
+        DetailColumn::Encoding => ENCODING_WIDTH,
+        DetailColumn::Permissions => PERMISSIONS_WIDTH,
+        DetailColumn::Size => SIZE_WIDTH,
+        DetailColumn::Modified => MODIFIED_WIDTH,
+    }
+}
+
+/// Return the text of the column of the detailed view for the entry (if it is known).
+fn detail_column_text(column: DetailColumn, entry: &FileInfo, now: SystemTime) -> Option<String> {
+    match column {
+        DetailColumn::Encoding => entry.encoding().map(|encoding| encoding.to_string()),
+        DetailColumn::Permissions => entry.permissions().map(permissions),
+        DetailColumn::Size => entry.size().map(human_size),
+        DetailColumn::Modified => entry
+            .modified()
+            .map(|modified| relative_time(modified, now)),
+    }
+}
+
+/// Sort the entries by the sort mode and then by their names.
+fn sort_file_infos(file_infos: &mut Vec<FileInfo>, sort_mode: SortMode, collator: &NameCollator) {
+    // NOTE: The names are only computed once (instead of for each comparison).
+    let mut named: Vec<(String, FileInfo)> = file_infos
+        .drain(..)
+        .map(|file_info| (entry_name(&file_info), file_info))
+        .collect();
+    named.sort_by(|(a_name, a), (b_name, b)| {
+        sort_mode
+            .compare(a, b)
+            .then_with(|| collator.compare(a_name, b_name))
+    });
+    file_infos.extend(named.into_iter().map(|(_, file_info)| file_info));
+}
+
+/// A selection to restore once the files are refreshed.
+struct Restore {
+    /// The request for refreshing the files.
+    request: Uuid,
+    /// The path of the selected entry.
+    path: PathBuf,
+    /// The number of the selected entry (used if the entry disappeared).
+    entry_number: usize,
+    /// The row of the selected entry on the screen.
+    row: usize,
+}
+
 enum Action {
-    Resize { size: Size },
-    Down,
+    Resize {
+        size: Size,
+    },
+    Down {
+        count: usize,
+    },
     ReallyDown,
-    Up,
+    Up {
+        count: usize,
+    },
     ReallyUp,
+    HalfPageDown {
+        count: usize,
+    },
+    HalfPageUp {
+        count: usize,
+    },
+    PageDown {
+        count: usize,
+    },
+    PageUp {
+        count: usize,
+    },
     Refresh,
     Push,
+    Choose,
     Pop,
+    Jump {
+        dir: PathBuf,
+    },
+    JumpHome,
+    JumpRoot,
+    JumpBack,
+    TogglePhysical,
+    Escalate {
+        escalation: Escalation,
+    },
+    Touch,
+    OpenDeletePrompt {
+        recursive: bool,
+    },
+    CloseDeletePrompt,
+    ConfirmDelete {
+        index: usize,
+    },
+    OpenRename,
+    CloseRename,
+    ConfirmRename {
+        name: String,
+    },
+    /// Mark the selected entry for copying or moving it.
+    Mark {
+        moving: bool,
+    },
+    Paste,
+    /// Order the entries by the next sort mode.
+    CycleSort,
+    ToggleSelecting,
+    StopSelecting,
+    ToggleMark,
     Yank,
+    YankAs {
+        format: PathFormat,
+        register: Option<char>,
+    },
     ReallyYank,
-    OpenFileCreator { file_type: FileType },
+    ChooseRegister,
+    UseRegister {
+        name: char,
+    },
+    OpenFileCreator {
+        file_type: FileType,
+    },
     OpenFinder,
+    OpenFlatList,
     OpenSearcher,
+    OpenReader,
+    PreviewImage,
+    OpenPager,
+    OpenLogViewer,
+    OpenProcessList,
+    OpenExternally,
+    OpenOpenFiles,
+    OpenMounts,
+    OpenPalette,
     RunBash,
+    RunScript {
+        key: char,
+    },
     HandleResponse(Response),
+    TimeOut {
+        request: Uuid,
+        kind: RequestKind,
+    },
+    /// Get the files again in the background if they are stale.
+    Revalidate,
+    ToggleDetails,
+    ToggleTree,
+    OpenFilter,
+    UpdateFilter,
+    AcceptFilter,
+    ClearFilter,
+    NextMatch {
+        count: usize,
+    },
+    PreviousMatch {
+        count: usize,
+    },
 }
 
 pub enum Effect {
+    /// Open the prompt for deleting the entries (whose choice comes back as an event).
+    OpenDeletePrompt(Prompt),
     SetDir {
         dir: PathBuf,
         // NOTE: We only jam this in here for now because we can only emit a single effect right
@@ -668,13 +2879,343 @@ pub enum Effect {
     OpenFinder {
         dir: PathBuf,
     },
+    OpenFlatList {
+        dir: PathBuf,
+    },
     OpenSearcher {
         dir: PathBuf,
     },
+    OpenReader {
+        path: PathBuf,
+    },
+    PreviewImage {
+        path: PathBuf,
+    },
+    OpenPager {
+        path: PathBuf,
+    },
+    OpenLogViewer {
+        path: PathBuf,
+    },
+    OpenProcessList {
+        dir: PathBuf,
+    },
+    OpenOpenFiles {
+        dir: PathBuf,
+    },
+    OpenMounts,
+    OpenPalette {
+        dir: PathBuf,
+        selection: Option<PathBuf>,
+    },
     OpenVim(VimArgs),
+    Choose {
+        path: PathBuf,
+    },
     RunBash {
         dir: PathBuf,
     },
+    Escalate {
+        escalation: Escalation,
+    },
+    Mark {
+        paths: Vec<PathBuf>,
+        moving: bool,
+    },
+    Paste {
+        dir: PathBuf,
+    },
+    GotFiles {
+        slow_file_system: bool,
+        // NOTE: We only jam this in here for now because we can only emit a single effect right
+        // now.
+        get_file_details_request: Option<Request>,
+    },
+    /// Show a short message (in the corner of the screen).
+    Toast {
+        message: String,
+        level: ToastLevel,
+    },
     Bell,
     Request(Request),
+    /// Send the request for the files of the directory (in place of the one sent before).
+    List(Request),
+    /// Send several requests (like for deleting the marked entries).
+    Requests(Vec<Request>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::common::{PromptEffect, PromptEvent};
+    use crate::requester::send_over_socket;
+
+    use test_case::test_case;
+
+    /// Return contents of the directory `/r` which has the entries (a trailing slash marks a
+    /// directory).
+    fn contents(entries: &[&str]) -> Contents {
+        let uuid: Uuid = Uuid::new_v4();
+        let props = Props::builder()
+            .dir(PathBuf::from("/r"))
+            .size(Size::new(10, 80))
+            .file(None)
+            .config(Arc::new(Config::default()))
+            .clipboard(Rc::new(RefCell::new(Clipboard::new())))
+            .build();
+        let mut contents = Contents::new(props);
+
+        let file_infos: Vec<FileInfo> = entries
+            .iter()
+            .map(|entry| {
+                let file_type: FileType = match entry.ends_with('/') {
+                    true => FileType::Dir,
+                    false => FileType::File,
+                };
+                FileInfo::builder()
+                    .path(Path::new("/r").join(entry.trim_end_matches('/')))
+                    .r#type(Ok(file_type))
+                    .build()
+            })
+            .collect();
+        let response = Response::builder()
+            .uuid(uuid)
+            .last(true)
+            .params(ResponseParams::GetFiles(
+                GetFilesResponseParams::builder()
+                    .dir(PathBuf::from("/r"))
+                    .result(Ok(file_infos))
+                    .build(),
+            ))
+            .build();
+        contents.handle(Event::Response(response));
+        contents
+    }
+
+    /// Press the keys (with `\r` for enter, `\x7f` for delete, and `\x1b` for escape) and return
+    /// the requests that are sent (over a socket) described like `GetFiles /r 0`.
+    fn requests(contents: &mut Contents, keys: &str) -> Vec<String> {
+        // The prompt for deleting entries is on top of the browser (like the modal stack of insh).
+        let mut prompt: Option<Prompt> = None;
+        let requests: Vec<Request> = keys
+            .chars()
+            .filter_map(|key| {
+                let mods: KeyMods = match key.is_ascii_uppercase() {
+                    true => KeyMods::SHIFT,
+                    false => KeyMods::NONE,
+                };
+                let key: Key = match key {
+                    '\r' => Key::CarriageReturn,
+                    '\x7f' => Key::Delete,
+                    '\x1b' => Key::Escape,
+                    key => Key::Char(key),
+                };
+                let term_event = TermEvent::KeyEvent(KeyEvent { key, mods });
+                let event: Event = match &mut prompt {
+                    Some(open_prompt) => {
+                        match open_prompt.handle(PromptEvent::TermEvent(term_event))? {
+                            PromptEffect::Choose { index } => {
+                                prompt = None;
+                                Event::ConfirmDelete { index }
+                            }
+                            PromptEffect::Cancel => {
+                                prompt = None;
+                                Event::CancelDelete
+                            }
+                            PromptEffect::Bell => return None,
+                        }
+                    }
+                    None => Event::Term { event: term_event },
+                };
+                match contents.handle(event) {
+                    Some(Effect::Request(request) | Effect::List(request)) => Some(vec![request]),
+                    Some(Effect::Requests(requests)) => Some(requests),
+                    Some(Effect::OpenDeletePrompt(open_prompt)) => {
+                        prompt = Some(open_prompt);
+                        None
+                    }
+                    _ => None,
+                }
+            })
+            .flatten()
+            .collect();
+        send_over_socket(requests)
+            .iter()
+            .map(|request| match request.params() {
+                RequestParams::GetFiles(params) => {
+                    format!("GetFiles {} {}", params.dir().display(), params.depth())
+                }
+                RequestParams::TouchFile(params) => {
+                    format!("TouchFile {}", params.path().display())
+                }
+                RequestParams::DeleteFile(params) => format!(
+                    "DeleteFile {} {}",
+                    params.path().display(),
+                    params.recursive()
+                ),
+                RequestParams::RenameFile(params) => format!(
+                    "RenameFile {} {}",
+                    params.from().display(),
+                    params.to().display()
+                ),
+                params => format!("{:?}", params),
+            })
+            .collect()
+    }
+
+    #[test_case(&["a"], "r", &["GetFiles /r 0"]; "refresh")]
+    #[test_case(&["a"], "T", &["TouchFile /r/a"]; "touch")]
+    #[test_case(&["a"], "dd", &["DeleteFile /r/a false"]; "delete")]
+    #[test_case(&["a"], "dc", &[]; "delete cancelled")]
+    #[test_case(&["d/"], "dd", &["DeleteFile /r/d false"]; "delete empty directory")]
+    #[test_case(&["d/"], "Dd", &["DeleteFile /r/d true"]; "delete recursively")]
+    #[test_case(&["d/"], "Dc", &[]; "delete recursively cancelled")]
+    #[test_case(&[], "d", &[]; "delete nothing")]
+    #[test_case(&["a"], "m\x7fb\r", &["RenameFile /r/a /r/b"]; "rename")]
+    #[test_case(&["a", "d/"], "m\x7fd/a\r", &["RenameFile /r/a /r/d/a"]; "rename into a directory")]
+    #[test_case(&["a"], "m\r", &[]; "rename unchanged")]
+    #[test_case(&["a"], "mb\x1b", &[]; "rename cancelled")]
+    #[test_case(&["a", "b", "c"], "vjdd", &["DeleteFile /r/a false", "DeleteFile /r/b false"]; "delete marked")]
+    #[test_case(&["a", "b", "c"], "vj jdd", &["DeleteFile /r/a false", "DeleteFile /r/c false"]; "delete toggled")]
+    #[test_case(&["a", "d/"], "vjDd", &["DeleteFile /r/a true", "DeleteFile /r/d true"]; "delete marked recursively")]
+    #[test_case(&["a", "b"], "vj\x1bdd", &["DeleteFile /r/b false"]; "stop selecting")]
+    fn test_requests(entries: &[&str], keys: &str, expected: &[&str]) {
+        let mut contents = contents(entries);
+        assert_eq!(requests(&mut contents, keys), expected);
+    }
+
+    #[test]
+    fn test_cycle_sort() {
+        let mut contents = contents(&["a", "c", "d/"]);
+        assert!(requests(&mut contents, "jooo").is_empty());
+        assert_eq!(contents.state.sort_mode, SortMode::Type);
+
+        let paths: Vec<&Path> = match &contents.state.file_infos {
+            Some(Ok(file_infos)) => file_infos.iter().map(FileInfo::path).collect(),
+            _ => Vec::new(),
+        };
+        assert_eq!(
+            paths,
+            [Path::new("/r/d"), Path::new("/r/a"), Path::new("/r/c")]
+        );
+        assert_eq!(contents.state.entry().unwrap().path(), Path::new("/r/c"));
+    }
+
+    #[test]
+    fn test_count_open() {
+        let mut contents = contents(&["a", "b"]);
+        contents.state.detailed = true;
+        contents.state.open_counts = Some(HashMap::from([(PathBuf::from("/r/a"), 2)]));
+
+        assert!(requests(&mut contents, "\rj\r").is_empty());
+        assert_eq!(contents.state.open_count(Path::new("/r/a")), Some(3));
+        assert_eq!(contents.state.open_count(Path::new("/r/b")), Some(1));
+        assert_eq!(contents.state.max_open_count(), 3);
+    }
+
+    #[test_case(&["a"], "x", Some("Mark /r/a true"); "cut")]
+    #[test_case(&["a"], "X", Some("Mark /r/a false"); "copy")]
+    #[test_case(&[], "x", None; "cut nothing")]
+    #[test_case(&["a"], "V", Some("Paste /r"); "paste")]
+    #[test_case(&["a", "b", "c"], "vjjX", Some("Mark /r/a /r/b /r/c false"); "copy marked")]
+    #[test_case(&["a", "b", "c"], "v jx", Some("Mark /r/b true"); "cut toggled")]
+    fn test_mark(entries: &[&str], keys: &str, expected: Option<&str>) {
+        let mut contents = contents(entries);
+        let mut described: Option<String> = None;
+        for key in keys.chars() {
+            let mods: KeyMods = match key.is_ascii_uppercase() {
+                true => KeyMods::SHIFT,
+                false => KeyMods::NONE,
+            };
+            let event = Event::Term {
+                event: TermEvent::KeyEvent(KeyEvent {
+                    key: Key::Char(key),
+                    mods,
+                }),
+            };
+            described = match contents.handle(event) {
+                Some(Effect::Mark { paths, moving }) => {
+                    let paths: Vec<String> = paths
+                        .iter()
+                        .map(|path| path.display().to_string())
+                        .collect();
+                    Some(format!("Mark {} {}", paths.join(" "), moving))
+                }
+                Some(Effect::Paste { dir }) => Some(format!("Paste {}", dir.display())),
+                _ => None,
+            };
+        }
+        assert_eq!(described.as_deref(), expected);
+    }
+
+    #[test_case("b", Some("/r/b"); "name")]
+    #[test_case("d/a", Some("/r/d/a"); "relative path")]
+    #[test_case("/tmp/a", Some("/tmp/a"); "absolute path")]
+    #[test_case("a", None; "unchanged")]
+    #[test_case("", None; "empty")]
+    fn test_renamed_path(name: &str, expected: Option<&str>) {
+        assert_eq!(
+            renamed_path(Path::new("/r/a"), name),
+            expected.map(PathBuf::from)
+        );
+    }
+
+    #[test_case(3, 1, SortMode::Name, None, "3 files, 1 dir (2 hidden), sorted by natural"; "no filter")]
+    #[test_case(1, 0, SortMode::Name, Some("foo"), "1 file, 0 dirs (2 hidden), sorted by natural, filtered by /foo"; "filter")]
+    #[test_case(3, 1, SortMode::Modified, None, "3 files, 1 dir (2 hidden), sorted by modified"; "sort mode")]
+    fn test_describe_summary(
+        files: usize,
+        dirs: usize,
+        sort_mode: SortMode,
+        filter: Option<&str>,
+        expected: &str,
+    ) {
+        let summary = FilesSummary::builder()
+            .files(files)
+            .dirs(dirs)
+            .hidden(2)
+            .build();
+        assert_eq!(
+            describe_summary(summary, sort_mode, Collation::Natural, filter),
+            expected
+        );
+    }
+
+    #[test_case(DetailColumn::Permissions, Some("rw-r--r--"); "permissions")]
+    #[test_case(DetailColumn::Size, Some("1.5K"); "size")]
+    #[test_case(DetailColumn::Modified, Some("2 hours ago"); "modified")]
+    #[test_case(DetailColumn::Encoding, None; "unknown")]
+    fn test_detail_column_text(column: DetailColumn, expected: Option<&str>) {
+        let now: SystemTime = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        let entry: FileInfo = FileInfo::builder()
+            .path("/r/a".into())
+            .r#type(Ok(FileType::File))
+            .modified(Some(now - Duration::from_secs(2 * 60 * 60)))
+            .size(Some(1536))
+            .permissions(Some(0o644))
+            .build();
+
+        assert_eq!(detail_column_text(column, &entry, now).as_deref(), expected);
+    }
+
+    #[test_case(0, false; "fresh")]
+    #[test_case(REVALIDATE_AFTER.as_secs(), true; "stale")]
+    fn test_revalidate(age: u64, expected_request: bool) {
+        let mut contents: Contents = contents(&["a"]);
+        let fetched_at: Instant = contents.state.fetched_at.unwrap();
+        let now: Instant = fetched_at + Duration::from_secs(age);
+
+        match contents.state.revalidate(now) {
+            Some(Effect::List(request)) => {
+                assert!(expected_request);
+                assert_eq!(contents.state.revalidating, Some(*request.uuid()));
+                // The files aren't gotten again until the fresh files arrive.
+                assert!(contents.state.revalidate(now).is_none());
+            }
+            None => assert!(!expected_request),
+            _ => panic!("Unexpected effect."),
+        }
+        // The stale files are still shown.
+        assert_eq!(contents.state.shown_count(), 1);
+    }
 }
@@ -1,7 +1,10 @@
 mod browser;
 mod contents;
+mod sidebar;
+mod tree;
 
 pub use browser::{Browser, Effect as BrowserEffect, Event as BrowserEvent, Props as BrowserProps};
 use contents::{
     Contents, Effect as ContentsEffect, Event as ContentsEvent, Props as ContentsProps,
 };
+use sidebar::{Effect as SidebarEffect, Event as SidebarEvent, Props as SidebarProps, Sidebar};
@@ -0,0 +1,454 @@
+use std::path::{Path, PathBuf};
+
+use typed_builder::TypedBuilder;
+
+use rend::{Fabric, Size, Yarn};
+use term::{Key, KeyEvent, KeyMods, TermEvent};
+use til::Component;
+
+use crate::color::Color;
+use crate::devices::Device;
+use crate::inspect::{Inspect, Inspection};
+use crate::stateful::Stateful;
+
+#[derive(TypedBuilder)]
+pub struct Props {
+    /// The bookmarked directories.
+    #[builder(default)]
+    bookmarks: Vec<PathBuf>,
+    /// The recently browsed directories from newest to oldest.
+    #[builder(default)]
+    recent: Vec<PathBuf>,
+    /// The mounted devices.
+    #[builder(default)]
+    devices: Vec<Device>,
+}
+
+/// A list of bookmarks, recently browsed directories, and mounted devices shown beside the
+/// contents of the browser.
+pub struct Sidebar {
+    state: State,
+}
+
+impl Component<Props, Event, Effect> for Sidebar {
+    fn new(props: Props) -> Self {
+        let state = State::from(props);
+        Self { state }
+    }
+
+    fn handle(&mut self, event: Event) -> Option<Effect> {
+        match self.map(event) {
+            Some(action) => self.state.perform(action),
+            None => Some(Effect::Bell),
+        }
+    }
+
+    fn render(&self, size: Size) -> Fabric {
+        if size.columns == 0 {
+            return Fabric::new(size);
+        }
+        let width: usize = size.columns - 1;
+
+        let rows: Vec<Row> = self.state.rows();
+        if rows.is_empty() {
+            let fabric = Fabric::center("No bookmarks.", Size::new(size.rows, width));
+            return fabric.quilt_right(render_border(size.rows));
+        }
+
+        let selected_row: Option<usize> = rows.iter().position(
+            |row| matches!(row, Row::Entry { index, .. } if *index == self.state.selected),
+        );
+        // Scroll just far enough that the selected entry is visible.
+        let offset: usize = match selected_row {
+            Some(selected_row) if selected_row >= size.rows => selected_row + 1 - size.rows,
+            _ => 0,
+        };
+
+        let mut yarns: Vec<Yarn> = rows
+            .iter()
+            .skip(offset)
+            .take(size.rows)
+            .map(|row| match row {
+                Row::Header(title) => {
+                    let mut yarn = Yarn::from(*title);
+                    yarn.resize(width);
+                    yarn.color(Color::GrayedText.into());
+                    yarn
+                }
+                Row::Entry { index, path } => {
+                    let mut string: String = format!(" {}", name(path));
+                    if self.state.device(*index).is_some_and(Device::removable) {
+                        string.push_str(" ⏏");
+                    }
+                    let mut yarn = Yarn::from(string);
+                    yarn.resize(width);
+                    if self.state.focused && *index == self.state.selected {
+                        yarn.color(Color::InvertedText.into());
+                        yarn.background(Color::Highlight.into());
+                    }
+                    yarn
+                }
+            })
+            .collect();
+        yarns.resize(size.rows, Yarn::blank(width));
+
+        Fabric::from(yarns).quilt_right(render_border(size.rows))
+    }
+}
+
+impl Inspect for Sidebar {
+    fn inspect(&self) -> Inspection {
+        Inspection::new("Sidebar")
+            .field("bookmarks", self.state.bookmarks.len())
+            .field("recent", self.state.recent.len())
+            .field("devices", self.state.devices.len())
+            .field("selected", self.state.selected)
+            .field("focused", self.state.focused)
+    }
+}
+
+impl Sidebar {
+    fn map(&self, event: Event) -> Option<Action> {
+        match event {
+            Event::Focus { focused } => Some(Action::Focus { focused }),
+            Event::SetRecent { recent } => Some(Action::SetRecent { recent }),
+            Event::SetDevices { devices } => Some(Action::SetDevices { devices }),
+            Event::Term {
+                event: TermEvent::KeyEvent(key_event),
+            } => match key_event {
+                KeyEvent {
+                    key: Key::Char('j'),
+                    mods: KeyMods::NONE,
+                } => Some(Action::Down),
+                KeyEvent {
+                    key: Key::Char('J'),
+                    mods: KeyMods::SHIFT,
+                } => Some(Action::ReallyDown),
+                KeyEvent {
+                    key: Key::Char('k'),
+                    mods: KeyMods::NONE,
+                } => Some(Action::Up),
+                KeyEvent {
+                    key: Key::Char('K'),
+                    mods: KeyMods::SHIFT,
+                } => Some(Action::ReallyUp),
+                KeyEvent {
+                    key: Key::Char('r'),
+                    mods: KeyMods::NONE,
+                } => Some(Action::Refresh),
+                KeyEvent {
+                    key: Key::Char('e'),
+                    mods: KeyMods::NONE,
+                } => Some(Action::Eject),
+                KeyEvent {
+                    key: Key::Char('l'),
+                    ..
+                }
+                | KeyEvent {
+                    key: Key::CarriageReturn,
+                    ..
+                } => Some(Action::Browse),
+                KeyEvent {
+                    key: Key::Char('h'),
+                    ..
+                }
+                | KeyEvent {
+                    key: Key::Escape, ..
+                } => Some(Action::Unfocus),
+                _ => None,
+            },
+            Event::Term { .. } => None,
+        }
+    }
+}
+
+/// Render the border between the sidebar and the contents.
+fn render_border(rows: usize) -> Fabric {
+    let yarns: Vec<Yarn> = (0..rows)
+        .map(|_| {
+            let mut yarn = Yarn::from("│");
+            yarn.color(Color::GrayedText.into());
+            yarn
+        })
+        .collect();
+    Fabric::from(yarns)
+}
+
+/// Return the name that a directory is listed as.
+fn name(dir: &Path) -> String {
+    if dirs::home_dir().as_deref() == Some(dir) {
+        return String::from("~");
+    }
+    match dir.file_name() {
+        Some(name) => name.to_string_lossy().to_string(),
+        None => dir.to_string_lossy().to_string(),
+    }
+}
+
+/// A row of the sidebar.
+enum Row<'a> {
+    /// The title of a section.
+    Header(&'a str),
+    /// A directory (with its index in all of the directories).
+    Entry { index: usize, path: &'a Path },
+}
+
+pub enum Event {
+    Term { event: TermEvent },
+    Focus { focused: bool },
+    SetRecent { recent: Vec<PathBuf> },
+    SetDevices { devices: Vec<Device> },
+}
+
+struct State {
+    bookmarks: Vec<PathBuf>,
+    recent: Vec<PathBuf>,
+    devices: Vec<Device>,
+    /// The index of the selected directory (in all of the directories).
+    selected: usize,
+    focused: bool,
+}
+
+impl From<Props> for State {
+    fn from(props: Props) -> Self {
+        Self {
+            bookmarks: props.bookmarks,
+            recent: props.recent,
+            devices: props.devices,
+            selected: 0,
+            focused: false,
+        }
+    }
+}
+
+impl State {
+    /// Return the sections of the sidebar (that have directories) with their titles.
+    fn sections(&self) -> Vec<(&str, Vec<&Path>)> {
+        let sections: [(&str, Vec<&Path>); 3] = [
+            (
+                "Bookmarks",
+                self.bookmarks.iter().map(PathBuf::as_path).collect(),
+            ),
+            ("Recent", self.recent.iter().map(PathBuf::as_path).collect()),
+            (
+                "Devices",
+                self.devices.iter().map(Device::mount_point).collect(),
+            ),
+        ];
+        sections
+            .into_iter()
+            .filter(|(_, dirs)| !dirs.is_empty())
+            .collect()
+    }
+
+    /// Return the rows of the sidebar.
+    fn rows(&self) -> Vec<Row<'_>> {
+        let mut rows: Vec<Row> = Vec::new();
+        let mut index: usize = 0;
+        for (title, dirs) in self.sections() {
+            rows.push(Row::Header(title));
+            for path in dirs {
+                rows.push(Row::Entry { index, path });
+                index += 1;
+            }
+        }
+        rows
+    }
+
+    /// Return all of the directories in the order that they are listed.
+    fn dirs(&self) -> Vec<&Path> {
+        self.sections()
+            .into_iter()
+            .flat_map(|(_, dirs)| dirs)
+            .collect()
+    }
+
+    fn focus(&mut self, focused: bool) -> Option<Effect> {
+        self.focused = focused;
+        None
+    }
+
+    /// Return the device at the index (in all of the directories) if there is one.
+    fn device(&self, index: usize) -> Option<&Device> {
+        index
+            .checked_sub(self.bookmarks.len() + self.recent.len())
+            .and_then(|index| self.devices.get(index))
+    }
+
+    fn set_recent(&mut self, recent: Vec<PathBuf>) -> Option<Effect> {
+        self.recent = recent;
+        self.clamp_selection();
+        None
+    }
+
+    fn set_devices(&mut self, devices: Vec<Device>) -> Option<Effect> {
+        self.devices = devices;
+        self.clamp_selection();
+        None
+    }
+
+    /// Select the last directory if the selected directory was removed.
+    fn clamp_selection(&mut self) {
+        let len: usize = self.dirs().len();
+        self.selected = self.selected.min(len.saturating_sub(1));
+    }
+
+    fn down(&mut self) -> Option<Effect> {
+        if self.selected + 1 >= self.dirs().len() {
+            return Some(Effect::Bell);
+        }
+        self.selected += 1;
+        None
+    }
+
+    fn really_down(&mut self) -> Option<Effect> {
+        self.selected = self.dirs().len().saturating_sub(1);
+        None
+    }
+
+    fn up(&mut self) -> Option<Effect> {
+        if self.selected == 0 {
+            return Some(Effect::Bell);
+        }
+        self.selected -= 1;
+        None
+    }
+
+    fn really_up(&mut self) -> Option<Effect> {
+        self.selected = 0;
+        None
+    }
+
+    /// Eject the selected device (if it is removable media).
+    fn eject(&mut self) -> Option<Effect> {
+        match self.device(self.selected) {
+            Some(device) if device.removable() => Some(Effect::Eject {
+                device: device.clone(),
+            }),
+            _ => Some(Effect::Bell),
+        }
+    }
+
+    fn browse(&mut self) -> Option<Effect> {
+        match self.dirs().get(self.selected) {
+            Some(dir) => Some(Effect::Browse {
+                dir: dir.to_path_buf(),
+            }),
+            None => Some(Effect::Bell),
+        }
+    }
+}
+
+impl Stateful<Action, Effect> for State {
+    fn perform(&mut self, action: Action) -> Option<Effect> {
+        match action {
+            Action::Focus { focused } => self.focus(focused),
+            Action::SetRecent { recent } => self.set_recent(recent),
+            Action::SetDevices { devices } => self.set_devices(devices),
+            Action::Down => self.down(),
+            Action::ReallyDown => self.really_down(),
+            Action::Up => self.up(),
+            Action::ReallyUp => self.really_up(),
+            Action::Refresh => Some(Effect::Refresh),
+            Action::Browse => self.browse(),
+            Action::Eject => self.eject(),
+            Action::Unfocus => Some(Effect::Unfocus),
+        }
+    }
+}
+
+enum Action {
+    Focus { focused: bool },
+    SetRecent { recent: Vec<PathBuf> },
+    SetDevices { devices: Vec<Device> },
+    Down,
+    ReallyDown,
+    Up,
+    ReallyUp,
+    Refresh,
+    Browse,
+    Eject,
+    Unfocus,
+}
+
+pub enum Effect {
+    Browse { dir: PathBuf },
+    Eject { device: Device },
+    Refresh,
+    Unfocus,
+    Bell,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    fn sidebar() -> Sidebar {
+        let props = Props::builder()
+            .bookmarks(vec![PathBuf::from("/foo")])
+            .recent(vec![PathBuf::from("/bar"), PathBuf::from("/baz")])
+            .build();
+        Sidebar::new(props)
+    }
+
+    fn key(key: Key, mods: KeyMods) -> Event {
+        Event::Term {
+            event: TermEvent::KeyEvent(KeyEvent { key, mods }),
+        }
+    }
+
+    #[test_case(0, "/foo"; "bookmark")]
+    #[test_case(2, "/baz"; "recent directory after the bookmarks")]
+    fn test_browse(downs: usize, expected: &str) {
+        let mut sidebar = sidebar();
+        for _ in 0..downs {
+            sidebar.handle(key(Key::Char('j'), KeyMods::NONE));
+        }
+
+        let effect = sidebar.handle(key(Key::CarriageReturn, KeyMods::NONE));
+
+        assert!(matches!(effect, Some(Effect::Browse { dir }) if dir == Path::new(expected)));
+    }
+
+    #[test_case(false, false; "not removable")]
+    #[test_case(true, true; "removable")]
+    fn test_eject(removable: bool, ejects: bool) {
+        let device = Device::new(
+            String::from("/dev/sdb1"),
+            PathBuf::from("/media/usb"),
+            removable,
+        );
+        let props = Props::builder().devices(vec![device.clone()]).build();
+        let mut sidebar = Sidebar::new(props);
+
+        let effect = sidebar.handle(key(Key::Char('e'), KeyMods::NONE));
+
+        match effect {
+            Some(Effect::Eject { device: ejected }) => {
+                assert!(ejects);
+                assert_eq!(ejected, device);
+            }
+            _ => assert!(!ejects),
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let mut sidebar = sidebar();
+        sidebar.handle(Event::Focus { focused: true });
+
+        let fabric: Fabric = sidebar.render(Size::new(6, 6));
+
+        let rows: Vec<String> = fabric
+            .characters()
+            .iter()
+            .map(|row| row.iter().collect())
+            .collect();
+        assert_eq!(
+            rows,
+            vec!["Bookm│", " foo │", "Recen│", " bar │", " baz │", "     │"]
+        );
+    }
+}
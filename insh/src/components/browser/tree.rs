@@ -0,0 +1,230 @@
+/*!
+This module contains [`Tree`] which tracks the directories that are expanded inline in the tree view
+of the browser (like `tree`), and the functions for inserting and removing the entries of expanded
+directories in the list of entries.
+
+The entries of an expanded directory follow the entry of the directory in the list, so the list can
+be filtered, selected, and scrolled like the list of a single directory.
+*/
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use file_info::FileInfo;
+use file_type::FileType;
+use uuid::Uuid;
+
+/// The directories that are expanded in the tree view.
+pub struct Tree {
+    /// The expanded directories.
+    expanded: HashSet<PathBuf>,
+    /// Whether the directories whose files arrive for the pending request are expanded (when the
+    /// tree is first shown for a directory) rather than only the directories that were already
+    /// expanded (when the tree is refreshed).
+    expand_all: bool,
+    /// The requests for the files of directories that are being expanded one at a time.
+    pending: HashMap<Uuid, PathBuf>,
+}
+
+impl Default for Tree {
+    fn default() -> Self {
+        Self {
+            expanded: HashSet::new(),
+            expand_all: true,
+            pending: HashMap::new(),
+        }
+    }
+}
+
+impl Tree {
+    /// Collapse all of the directories (when the directory of the browser changes).
+    pub fn reset(&mut self) {
+        self.expanded.clear();
+        self.expand_all = true;
+        self.pending.clear();
+    }
+
+    pub fn is_expanded(&self, dir: &Path) -> bool {
+        self.expanded.contains(dir)
+    }
+
+    /// Return the number of expanded directories.
+    pub fn expanded_count(&self) -> usize {
+        self.expanded.len()
+    }
+
+    /// Return the depth of the request for the files of `root` (expanding the directories to
+    /// `depth` the first time the tree is shown, and otherwise only reading deep enough to refresh
+    /// the expanded directories).
+    pub fn request_depth(&self, root: &Path, depth: usize) -> usize {
+        match self.expand_all {
+            true => depth,
+            false => self
+                .expanded
+                .iter()
+                .map(|dir| self::depth(root, dir) + 1)
+                .max()
+                .unwrap_or(0),
+        }
+    }
+
+    /// Keep the expanded directories when the tree is refreshed (instead of expanding all of
+    /// them).
+    pub fn keep_expanded(&mut self) {
+        self.expand_all = false;
+    }
+
+    /// Expand a directory whose files are gotten by the request.
+    pub fn expand(&mut self, dir: PathBuf, request: Uuid) {
+        self.expanded.insert(dir.clone());
+        self.pending.insert(request, dir);
+    }
+
+    /// Return the directory that the request is getting the files of (and stop waiting for it).
+    pub fn take_pending(&mut self, request: &Uuid) -> Option<PathBuf> {
+        self.pending.remove(request)
+    }
+
+    /// Return whether the files of the directory that arrived for the pending request of the
+    /// browser should be shown (expanding the directory if all directories are being expanded).
+    pub fn accept(&mut self, dir: &Path) -> bool {
+        if self.expand_all {
+            self.expanded.insert(dir.to_path_buf());
+        }
+        self.expanded.contains(dir)
+    }
+
+    /// Collapse a directory (and the directories under it).
+    pub fn collapse(&mut self, dir: &Path) {
+        self.expanded.retain(|expanded| !expanded.starts_with(dir));
+        self.pending.retain(|_, pending| !pending.starts_with(dir));
+    }
+}
+
+/// Return the depth of an entry under `root` (0 for the entries of the root itself).
+pub fn depth(root: &Path, path: &Path) -> usize {
+    match path.strip_prefix(root) {
+        Ok(relative) => relative.components().count().saturating_sub(1),
+        Err(_) => 0,
+    }
+}
+
+/// Return whether an entry is a directory that can be expanded.
+pub fn is_expandable(file_info: &FileInfo) -> bool {
+    matches!(file_info.r#type(), Ok(FileType::Dir))
+}
+
+/// Insert the entries of `dir` after the entry of `dir` (unless they are already there). Return
+/// whether the entries were inserted.
+pub fn insert_children(
+    file_infos: &mut Vec<FileInfo>,
+    dir: &Path,
+    children: Vec<FileInfo>,
+) -> bool {
+    let index: usize = match file_infos
+        .iter()
+        .position(|file_info| file_info.path() == dir)
+    {
+        Some(index) => index,
+        None => return false,
+    };
+    if file_infos
+        .get(index + 1)
+        .is_some_and(|next| next.path().parent() == Some(dir))
+    {
+        return false;
+    }
+    file_infos.splice(index + 1..index + 1, children);
+    true
+}
+
+/// Remove the entries under `dir` (which follow the entry of `dir`).
+pub fn remove_descendants(file_infos: &mut Vec<FileInfo>, dir: &Path) {
+    let index: usize = match file_infos
+        .iter()
+        .position(|file_info| file_info.path() == dir)
+    {
+        Some(index) => index,
+        None => return,
+    };
+    let len: usize = file_infos[index + 1..]
+        .iter()
+        .take_while(|file_info| file_info.path().starts_with(dir))
+        .count();
+    file_infos.drain(index + 1..index + 1 + len);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    fn file_infos(paths: &[&str]) -> Vec<FileInfo> {
+        paths
+            .iter()
+            .map(|path| {
+                let file_type: FileType = match path.ends_with('/') {
+                    true => FileType::Dir,
+                    false => FileType::File,
+                };
+                FileInfo::builder()
+                    .path(PathBuf::from(path.trim_end_matches('/')))
+                    .r#type(Ok(file_type))
+                    .build()
+            })
+            .collect()
+    }
+
+    fn paths(file_infos: &[FileInfo]) -> Vec<String> {
+        file_infos
+            .iter()
+            .map(|file_info| file_info.path().to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test_case("/root/a", 0; "entry")]
+    #[test_case("/root/a/b/c", 2; "nested entry")]
+    #[test_case("/other/a", 0; "outside of the root")]
+    fn test_depth(path: &str, expected: usize) {
+        assert_eq!(depth(Path::new("/root"), Path::new(path)), expected);
+    }
+
+    #[test_case(&["/r/a/", "/r/b"], "/r/a", &["/r/a", "/r/a/x", "/r/a/y", "/r/b"]; "inserted")]
+    #[test_case(&["/r/a/", "/r/a/x", "/r/b"], "/r/a", &["/r/a", "/r/a/x", "/r/b"]; "already inserted")]
+    #[test_case(&["/r/b"], "/r/a", &["/r/b"]; "missing directory")]
+    fn test_insert_children(entries: &[&str], dir: &str, expected: &[&str]) {
+        let mut entries: Vec<FileInfo> = file_infos(entries);
+        let children: Vec<FileInfo> = file_infos(&[&format!("{}/x", dir), &format!("{}/y", dir)]);
+        insert_children(&mut entries, Path::new(dir), children);
+
+        assert_eq!(paths(&entries), expected);
+    }
+
+    #[test_case("/r/a", &["/r/a", "/r/ab"]; "directory")]
+    #[test_case("/r/a/x", &["/r/a", "/r/a/x", "/r/a/y", "/r/ab"]; "nested directory")]
+    #[test_case("/r/ab", &["/r/a", "/r/a/x", "/r/a/x/1", "/r/a/y", "/r/ab"]; "no entries")]
+    fn test_remove_descendants(dir: &str, expected: &[&str]) {
+        let mut entries: Vec<FileInfo> =
+            file_infos(&["/r/a/", "/r/a/x/", "/r/a/x/1", "/r/a/y", "/r/ab/"]);
+        remove_descendants(&mut entries, Path::new(dir));
+
+        assert_eq!(paths(&entries), expected);
+    }
+
+    #[test]
+    fn test_request_depth() {
+        let root = Path::new("/r");
+        let mut tree = Tree::default();
+        assert_eq!(tree.request_depth(root, 2), 2);
+
+        tree.keep_expanded();
+        assert_eq!(tree.request_depth(root, 2), 0);
+
+        tree.expand(PathBuf::from("/r/a/b"), Uuid::new_v4());
+        tree.expand(PathBuf::from("/r/c"), Uuid::new_v4());
+        assert_eq!(tree.request_depth(root, 2), 2);
+
+        tree.collapse(Path::new("/r/a"));
+        assert_eq!(tree.request_depth(root, 2), 1);
+    }
+}
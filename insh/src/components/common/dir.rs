@@ -39,21 +39,45 @@ mod dir {
         }
 
         fn render(&self, size: Size) -> Fabric {
-            let string = self.state.dir_string();
+            self.render_with_summary(size, None)
+        }
+    }
+
+    impl Dir {
+        /// Render the directory with a summary (such as the number of files) at the right end
+        /// (if there is room for it after the directory).
+        pub fn render_with_summary(&self, size: Size, summary: Option<&str>) -> Fabric {
+            let mut string = self.state.dir_string();
+            if let Some(target) = self.state.target() {
+                string.push_str(" → ");
+                string.push_str(&self.state.path_string(target));
+            }
+            if self.state.slow_file_system() {
+                string.push_str(" (slow file system)");
+            }
             let mut yarn = Yarn::from(string);
+            if let Some(summary) = summary {
+                let summary: Yarn = Yarn::from(format!(" {}", summary));
+                if yarn.len() + summary.len() <= size.columns {
+                    yarn.resize(size.columns - summary.len());
+                    yarn = yarn.concat(summary);
+                }
+            }
             yarn.resize(size.columns);
             yarn.color(Color::InvertedText.into());
             yarn.background(Color::InvertedBackground.into());
 
             Fabric::from(yarn)
         }
-    }
 
-    impl Dir {
         fn map(&self, event: Event) -> Option<Action> {
             match event {
                 Event::SetDir { dir } => Some(Action::SetDir { dir }),
                 Event::PopDir => Some(Action::PopDir),
+                Event::SetSlowFileSystem { slow_file_system } => {
+                    Some(Action::SetSlowFileSystem { slow_file_system })
+                }
+                Event::SetTarget { target } => Some(Action::SetTarget { target }),
             }
         }
     }
@@ -71,8 +95,17 @@ mod event {
     use std::path::PathBuf;
 
     pub enum Event {
-        SetDir { dir: PathBuf },
+        SetDir {
+            dir: PathBuf,
+        },
         PopDir,
+        SetSlowFileSystem {
+            slow_file_system: bool,
+        },
+        /// Set the physical location of the directory (as it was found by the daemon).
+        SetTarget {
+            target: Option<PathBuf>,
+        },
     }
 }
 pub use event::Event;
@@ -82,17 +115,37 @@ mod state {
     use crate::current_dir;
     use crate::stateful::Stateful;
 
-    use std::path::{PathBuf, MAIN_SEPARATOR as PATH_SEPARATOR};
+    use std::path::{Path, PathBuf, MAIN_SEPARATOR as PATH_SEPARATOR};
 
     pub struct State {
         dir: PathBuf,
+        /// The physical location of the directory (if it is reached through a symbolic link).
+        ///
+        /// NOTE: The location is found by the daemon with the files of the directory (so that
+        /// following the links never blocks), and isn't known until they arrive.
+        target: Option<PathBuf>,
         home: Option<PathBuf>,
+        /// If the directory is on a slow file system.
+        slow_file_system: bool,
     }
 
     impl State {
+        pub fn slow_file_system(&self) -> bool {
+            self.slow_file_system
+        }
+
+        pub fn target(&self) -> Option<&Path> {
+            self.target.as_deref()
+        }
+
         pub fn dir_string(&self) -> String {
+            self.path_string(&self.dir)
+        }
+
+        /// Return the path as a string (relative to the home directory if it is in it).
+        pub fn path_string(&self, dir: &Path) -> String {
             if let Some(home) = &self.home {
-                if let Ok(path) = self.dir.strip_prefix(home) {
+                if let Ok(path) = dir.strip_prefix(home) {
                     let mut string = String::from("~");
                     string.push(PATH_SEPARATOR);
 
@@ -106,27 +159,27 @@ mod state {
                 }
             }
 
-            let mut string = self.dir.to_str().unwrap().to_string();
-            if self.dir.parent().is_some() {
+            let mut string = dir.to_str().unwrap().to_string();
+            if dir.parent().is_some() {
                 string.push(PATH_SEPARATOR);
             }
             string
         }
 
         fn set_dir(&mut self, dir: PathBuf) {
+            self.target = None;
             self.dir = dir;
         }
 
         fn pop_dir(&mut self) {
             self.dir.pop();
+            self.target = None;
         }
     }
 
     impl Default for State {
         fn default() -> Self {
-            let dir: PathBuf = current_dir::current_dir();
-            let home: Option<PathBuf> = dirs::home_dir();
-            State { dir, home }
+            Self::from(Props::new(current_dir::current_dir()))
         }
     }
 
@@ -134,7 +187,9 @@ mod state {
         fn from(props: Props) -> Self {
             Self {
                 dir: props.dir,
-                ..Default::default()
+                target: None,
+                home: dirs::home_dir(),
+                slow_file_system: false,
             }
         }
     }
@@ -148,6 +203,12 @@ mod state {
                 Action::PopDir => {
                     self.pop_dir();
                 }
+                Action::SetSlowFileSystem { slow_file_system } => {
+                    self.slow_file_system = slow_file_system;
+                }
+                Action::SetTarget { target } => {
+                    self.target = target;
+                }
             }
             None
         }
@@ -161,6 +222,8 @@ mod action {
     pub enum Action {
         SetDir { dir: PathBuf },
         PopDir,
+        SetSlowFileSystem { slow_file_system: bool },
+        SetTarget { target: Option<PathBuf> },
     }
 }
 use action::Action;
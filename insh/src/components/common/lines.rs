@@ -0,0 +1,152 @@
+/*!
+This module contains the struct [`Lines`] which splits the chunks of a file that are read by the
+pager and the log viewer into lines.
+*/
+
+/// A line of a file.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Line {
+    /// The offset (in bytes) of the start of the line in the file.
+    start: u64,
+    /// The text of the line (without the line ending).
+    text: String,
+}
+
+impl Line {
+    /// Return the offset (in bytes) of the start of the line in the file.
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    /// Return the text of the line (without the line ending).
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+/// The lines of the part of a file that has been read so far.
+///
+/// Chunks of the file are pushed in order. Bytes after the last newline are kept until the next
+/// chunk (or the end of the file) so that lines and multi-byte characters that span chunks are not
+/// split.
+#[derive(Default)]
+pub struct Lines {
+    lines: Vec<Line>,
+    /// The bytes of the line that is not complete yet.
+    partial: Vec<u8>,
+    /// The offset (in bytes) of the start of the line that is not complete yet.
+    partial_start: u64,
+}
+
+impl Lines {
+    /// Return the number of complete lines.
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Return the line at `index` (if it is complete).
+    pub fn get(&self, index: usize) -> Option<&Line> {
+        self.lines.get(index)
+    }
+
+    /// Return the complete lines in the range `start..end` (clamped to the complete lines).
+    pub fn range(&self, start: usize, end: usize) -> &[Line] {
+        let end: usize = end.min(self.lines.len());
+        &self.lines[start.min(end)..end]
+    }
+
+    /// Return the number of bytes of the file that have been pushed.
+    pub fn loaded_bytes(&self) -> u64 {
+        self.partial_start + self.partial.len() as u64
+    }
+
+    /// Push the next chunk of the file.
+    pub fn push(&mut self, bytes: &[u8]) {
+        let mut rest: &[u8] = bytes;
+        while let Some(newline) = rest.iter().position(|byte| *byte == b'\n') {
+            self.partial.extend_from_slice(&rest[..newline]);
+            self.complete_line();
+            self.partial_start += 1;
+            rest = &rest[newline + 1..];
+        }
+        self.partial.extend_from_slice(rest);
+    }
+
+    /// Finish the lines once the end of the file is reached (so that a last line without a
+    /// newline is complete).
+    pub fn finish(&mut self) {
+        if !self.partial.is_empty() {
+            self.complete_line();
+        }
+    }
+
+    /// Return the index of the first line containing `phrase` at or after `from` (when searching
+    /// forward) or before `from` (when searching backward).
+    pub fn find(&self, phrase: &str, from: usize, forward: bool) -> Option<usize> {
+        let contains = |index: &usize| self.lines[*index].text.contains(phrase);
+        match forward {
+            true => (from.min(self.lines.len())..self.lines.len()).find(contains),
+            false => (0..from.min(self.lines.len())).rev().find(contains),
+        }
+    }
+
+    /// Complete the partial line.
+    fn complete_line(&mut self) {
+        let bytes: Vec<u8> = std::mem::take(&mut self.partial);
+        let text: &[u8] = bytes.strip_suffix(b"\r").unwrap_or(&bytes);
+        self.lines.push(Line {
+            start: self.partial_start,
+            text: String::from_utf8_lossy(text).to_string(),
+        });
+        self.partial_start += bytes.len() as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(&[b"one\ntwo\n"], false, vec![(0, "one"), (4, "two")], 8; "complete lines")]
+    #[test_case(&[b"one\ntw", b"o\nthree"], false, vec![(0, "one"), (4, "two")], 13; "line spanning chunks")]
+    #[test_case(&[b"one\ntw", b"o\nthree"], true, vec![(0, "one"), (4, "two"), (8, "three")], 13; "last line without newline")]
+    #[test_case(&[b"one\r\ntwo\r\n"], true, vec![(0, "one"), (5, "two")], 10; "crlf")]
+    #[test_case(&[b"caf\xc3", b"\xa9\n"], true, vec![(0, "caf\u{e9}")], 6; "character spanning chunks")]
+    #[test_case(&[b"\n\n"], true, vec![(0, ""), (1, "")], 2; "empty lines")]
+    fn test_push(
+        chunks: &[&[u8]],
+        finish: bool,
+        expected_lines: Vec<(u64, &str)>,
+        expected_loaded_bytes: u64,
+    ) {
+        let mut lines = Lines::default();
+        for chunk in chunks {
+            lines.push(chunk);
+        }
+        if finish {
+            lines.finish();
+        }
+
+        let pushed_lines: Vec<(u64, &str)> = lines
+            .range(0, lines.len())
+            .iter()
+            .map(|line| (line.start(), line.text()))
+            .collect();
+        assert_eq!(pushed_lines, expected_lines);
+        assert_eq!(lines.loaded_bytes(), expected_loaded_bytes);
+    }
+
+    #[test_case("b", 0, true, Some(1); "forward")]
+    #[test_case("b", 2, true, Some(3); "forward from")]
+    #[test_case("b", 4, true, None; "forward not found")]
+    #[test_case("b", 3, false, Some(1); "backward")]
+    #[test_case("b", 1, false, None; "backward not found")]
+    fn test_find(phrase: &str, from: usize, forward: bool, expected_index: Option<usize>) {
+        let mut lines = Lines::default();
+        lines.push(b"a\nb\nc\nb\n");
+
+        let index: Option<usize> = lines.find(phrase, from, forward);
+
+        assert_eq!(index, expected_index);
+    }
+}
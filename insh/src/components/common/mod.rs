@@ -1,5 +1,13 @@
 mod dir;
 pub use dir::{Dir, Event as DirEvent, Props as DirProps};
 
+mod lines;
+pub use lines::Lines;
+
 mod phrase;
 pub use phrase::{Effect as PhraseEffect, Event as PhraseEvent, Phrase, Props as PhraseProps};
+
+mod prompt;
+pub use prompt::{
+    Choice, Effect as PromptEffect, Event as PromptEvent, Prompt, Props as PromptProps,
+};
@@ -1,5 +1,9 @@
 mod props {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
     use crate::auto_completer::AutoCompleter;
+    use crate::clipboard::Clipboard;
 
     use typed_builder::TypedBuilder;
 
@@ -9,14 +13,23 @@ mod props {
         pub auto_completer: Option<Box<dyn AutoCompleter<String, String>>>,
         #[builder(default, setter(into))]
         pub value: Option<String>,
+        /// The clipboard that is pasted from with `Ctrl-V` (and whose registers are pasted from
+        /// with `Ctrl-R` and the name of the register).
+        #[builder(default, setter(into))]
+        pub clipboard: Option<Rc<RefCell<Clipboard>>>,
     }
 }
 pub use props::Props;
 
 mod phrase {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
     use super::{Action, Effect, Event, Props, State};
     use crate::auto_completer::AutoCompleter;
+    use crate::clipboard::Clipboard;
     use crate::color::Color;
+    use crate::registers::Registers;
     use crate::stateful::Stateful;
 
     use rend::{Fabric, Size, Yarn};
@@ -28,6 +41,9 @@ mod phrase {
     pub struct Phrase {
         state: State,
         auto_completer: Option<Box<dyn AutoCompleter<String, String>>>,
+        clipboard: Option<Rc<RefCell<Clipboard>>>,
+        /// Whether the register to paste from is being chosen (with the next key).
+        choosing_register: bool,
     }
 
     impl Component<Props, Event, Effect> for Phrase {
@@ -37,20 +53,56 @@ mod phrase {
                     .value(props.value.unwrap_or_default())
                     .build(),
                 auto_completer: props.auto_completer,
+                clipboard: props.clipboard,
+                choosing_register: false,
             }
         }
 
         fn handle(&mut self, event: Event) -> Option<Effect> {
             let action: Option<Action> = match event {
                 Event::Focus => Some(Action::Focus),
-                Event::Unfocus => Some(Action::Unfocus),
                 Event::Set { phrase } => Some(Action::Set { phrase }),
+                Event::TermEvent(TermEvent::KeyEvent(KeyEvent {
+                    key: Key::Char(name),
+                    mods: KeyMods::NONE | KeyMods::SHIFT,
+                })) if self.choosing_register => {
+                    self.choosing_register = false;
+                    match Registers::is_name(name) {
+                        true => self.register(name).map(|text| Action::Paste {
+                            text,
+                            auto_completer: &mut self.auto_completer,
+                        }),
+                        false => None,
+                    }
+                }
+                Event::TermEvent(TermEvent::KeyEvent(_)) if self.choosing_register => {
+                    self.choosing_register = false;
+                    None
+                }
                 Event::TermEvent(key_event) => match key_event {
+                    TermEvent::KeyEvent(KeyEvent {
+                        key: Key::Char('r'),
+                        mods: KeyMods::CONTROL,
+                    }) if self.clipboard.is_some() => {
+                        self.choosing_register = true;
+                        return None;
+                    }
                     TermEvent::KeyEvent(KeyEvent {
                         key: Key::Char('q'),
                         mods: KeyMods::CONTROL,
                         ..
                     }) => Some(Action::Quit),
+                    TermEvent::KeyEvent(KeyEvent {
+                        key: Key::Char('v'),
+                        mods: KeyMods::CONTROL,
+                    }) => self
+                        .clipboard
+                        .as_ref()
+                        .and_then(|clipboard| clipboard.borrow_mut().paste())
+                        .map(|text| Action::Paste {
+                            text,
+                            auto_completer: &mut self.auto_completer,
+                        }),
                     TermEvent::KeyEvent(KeyEvent {
                         key: Key::Delete, ..
                     }) => Some(Action::Pop {
@@ -87,7 +139,11 @@ mod phrase {
             let mut yarn = Yarn::from(string);
             yarn.color(Color::InvertedText.into());
 
-            if self.state.is_focused() {
+            if self.choosing_register {
+                let mut register_yarn: Yarn = Yarn::from("\"");
+                register_yarn.color(Color::InvertedGrayedText.into());
+                yarn = yarn.concat(register_yarn);
+            } else if self.state.is_focused() {
                 if let Some(completion) = self.state.completion() {
                     if let Some(rest) = completion.strip_prefix(self.state.value()) {
                         let mut rest_yarn: Yarn = Yarn::from(rest);
@@ -103,6 +159,19 @@ mod phrase {
             Fabric::from(yarn)
         }
     }
+
+    impl Phrase {
+        /// Return the current value of the phrase.
+        pub fn value(&self) -> &str {
+            self.state.value()
+        }
+
+        /// Return the text in the register.
+        fn register(&self, name: char) -> Option<String> {
+            let clipboard = self.clipboard.as_ref()?.borrow();
+            clipboard.registers().get(name).map(str::to_string)
+        }
+    }
 }
 pub use phrase::Phrase;
 
@@ -112,7 +181,6 @@ mod event {
     #[allow(clippy::enum_variant_names)]
     pub enum Event {
         Focus,
-        Unfocus,
         Set { phrase: String },
         TermEvent(TermEvent),
     }
@@ -164,11 +232,6 @@ mod state {
             None
         }
 
-        pub fn unfocus(&mut self) -> Option<Effect> {
-            self.focus = false;
-            None
-        }
-
         pub fn set(&mut self, value: String) -> Option<Effect> {
             self.value = value;
             None
@@ -189,6 +252,24 @@ mod state {
             None
         }
 
+        /// Push the first line of the text (without control characters).
+        fn paste(
+            &mut self,
+            text: String,
+            auto_completer: &mut Option<Box<dyn AutoCompleter<String, String>>>,
+        ) -> Option<Effect> {
+            let line: &str = text.lines().next().unwrap_or_default();
+            self.value
+                .extend(line.chars().filter(|character| !character.is_control()));
+
+            if let Some(auto_completer) = auto_completer {
+                // TODO: Make auto completion non-blocking.
+                self.completion = auto_completer.complete(self.value.clone());
+            }
+
+            None
+        }
+
         fn pop(
             &mut self,
             auto_completer: &mut Option<Box<dyn AutoCompleter<String, String>>>,
@@ -230,12 +311,15 @@ mod state {
         fn perform(&mut self, action: Action) -> Option<Effect> {
             match action {
                 Action::Focus => self.focus(),
-                Action::Unfocus => self.unfocus(),
                 Action::Set { phrase } => self.set(phrase),
                 Action::Push {
                     character,
                     auto_completer,
                 } => self.push(character, auto_completer),
+                Action::Paste {
+                    text,
+                    auto_completer,
+                } => self.paste(text, auto_completer),
                 Action::Pop { auto_completer } => self.pop(auto_completer),
                 Action::Complete => self.complete(),
                 Action::Enter => self.find(),
@@ -251,7 +335,6 @@ mod action {
 
     pub enum Action<'a> {
         Focus,
-        Unfocus,
         Set {
             phrase: String,
         },
@@ -259,6 +342,10 @@ mod action {
             character: char,
             auto_completer: &'a mut Option<Box<dyn AutoCompleter<String, String>>>,
         },
+        Paste {
+            text: String,
+            auto_completer: &'a mut Option<Box<dyn AutoCompleter<String, String>>>,
+        },
         Pop {
             auto_completer: &'a mut Option<Box<dyn AutoCompleter<String, String>>>,
         },
@@ -0,0 +1,331 @@
+mod props {
+    use super::Choice;
+
+    use typed_builder::TypedBuilder;
+
+    #[derive(TypedBuilder)]
+    pub struct Props {
+        /// The question that is asked.
+        #[builder(setter(into))]
+        pub question: String,
+        pub choices: Vec<Choice>,
+        /// The index of the choice that is highlighted at the start (and chosen by `<Enter>`).
+        #[builder(default)]
+        pub default: usize,
+        /// Whether the choice is dangerous (which is shown in red).
+        #[builder(default)]
+        pub danger: bool,
+    }
+}
+pub use props::Props;
+
+mod choice {
+    /// A choice of a prompt.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Choice {
+        label: String,
+        /// The key which chooses the choice (its accelerator).
+        key: char,
+    }
+
+    impl Choice {
+        pub fn new(label: &str, key: char) -> Self {
+            Self {
+                label: label.to_string(),
+                key,
+            }
+        }
+
+        pub fn key(&self) -> char {
+            self.key
+        }
+
+        /// Return the label with the key marked (like `[O]verwrite`, or `Skip (k)` if the label
+        /// doesn't contain the key).
+        pub fn marked_label(&self) -> String {
+            match self
+                .label
+                .char_indices()
+                .find(|(_, character)| character.eq_ignore_ascii_case(&self.key))
+            {
+                Some((index, character)) => {
+                    let rest: &str = &self.label[index + character.len_utf8()..];
+                    format!("{}[{}]{}", &self.label[..index], character, rest)
+                }
+                None => format!("{} ({})", self.label, self.key),
+            }
+        }
+    }
+}
+pub use choice::Choice;
+
+mod prompt {
+    use super::{Action, Effect, Event, Props, State};
+    use crate::color::Color;
+    use crate::stateful::Stateful;
+
+    use rend::{Fabric, Size, Yarn};
+    use term::{Key, KeyEvent, KeyMods, TermEvent};
+    use til::Component;
+
+    /// A prompt which asks a question with choices that are chosen by their keys, by their numbers,
+    /// or by moving the highlight and pressing `<Enter>`.
+    pub struct Prompt {
+        state: State,
+    }
+
+    impl Component<Props, Event, Effect> for Prompt {
+        fn new(props: Props) -> Self {
+            Self {
+                state: State::from(props),
+            }
+        }
+
+        fn handle(&mut self, event: Event) -> Option<Effect> {
+            let action: Option<Action> = match event {
+                Event::TermEvent(TermEvent::KeyEvent(key_event)) => match key_event {
+                    KeyEvent {
+                        key: Key::Escape, ..
+                    }
+                    | KeyEvent {
+                        key: Key::Char('q'),
+                        mods: KeyMods::CONTROL,
+                    } => Some(Action::Cancel),
+                    KeyEvent {
+                        key: Key::HorizontalTab,
+                        mods: KeyMods::NONE,
+                    }
+                    | KeyEvent {
+                        key: Key::Char('n'),
+                        mods: KeyMods::CONTROL,
+                    } => Some(Action::Next),
+                    KeyEvent {
+                        key: Key::Char('p'),
+                        mods: KeyMods::CONTROL,
+                    } => Some(Action::Previous),
+                    KeyEvent {
+                        key: Key::CarriageReturn,
+                        ..
+                    } => Some(Action::ChooseHighlighted),
+                    KeyEvent {
+                        key: Key::Char(character),
+                        mods: KeyMods::NONE | KeyMods::SHIFT,
+                    } => Some(Action::Press { character }),
+                    _ => None,
+                },
+                Event::TermEvent(_) => None,
+            };
+
+            if let Some(action) = action {
+                self.state.perform(action)
+            } else {
+                Some(Effect::Bell)
+            }
+        }
+
+        fn render(&self, size: Size) -> Fabric {
+            let background = || match self.state.danger() {
+                true => Color::Danger,
+                false => Color::InvertedBackground,
+            };
+
+            let mut yarn = Yarn::from(format!("{} ", self.state.question()));
+            yarn.color(Color::InvertedText.into());
+            yarn.background(background().into());
+            for (index, choice) in self.state.choices().iter().enumerate() {
+                let mut choice = Yarn::from(format!(" {}:{} ", index + 1, choice.marked_label()));
+                choice.color(Color::InvertedText.into());
+                if index == self.state.highlighted() {
+                    choice.background(Color::Highlight.into());
+                } else {
+                    choice.background(background().into());
+                }
+                yarn = yarn.concat(choice);
+            }
+            yarn.resize(size.columns);
+            yarn.background(background().into());
+
+            let mut fabric = Fabric::from(yarn);
+            fabric.pad_bottom(size.rows);
+            fabric
+        }
+    }
+}
+pub use prompt::Prompt;
+
+mod event {
+    use term::TermEvent;
+
+    pub enum Event {
+        TermEvent(TermEvent),
+    }
+}
+pub use event::Event;
+
+mod state {
+    use super::{Action, Choice, Effect, Props};
+    use crate::stateful::Stateful;
+
+    pub struct State {
+        question: String,
+        choices: Vec<Choice>,
+        /// The index of the highlighted choice.
+        highlighted: usize,
+        danger: bool,
+    }
+
+    impl From<Props> for State {
+        fn from(props: Props) -> Self {
+            let highlighted: usize = props.default.min(props.choices.len().saturating_sub(1));
+            Self {
+                question: props.question,
+                choices: props.choices,
+                highlighted,
+                danger: props.danger,
+            }
+        }
+    }
+
+    impl State {
+        pub fn question(&self) -> &str {
+            &self.question
+        }
+
+        pub fn choices(&self) -> &[Choice] {
+            &self.choices
+        }
+
+        pub fn highlighted(&self) -> usize {
+            self.highlighted
+        }
+
+        pub fn danger(&self) -> bool {
+            self.danger
+        }
+
+        fn next(&mut self) -> Option<Effect> {
+            if self.choices.is_empty() {
+                return Some(Effect::Bell);
+            }
+            self.highlighted = (self.highlighted + 1) % self.choices.len();
+            None
+        }
+
+        fn previous(&mut self) -> Option<Effect> {
+            if self.choices.is_empty() {
+                return Some(Effect::Bell);
+            }
+            self.highlighted = (self.highlighted + self.choices.len() - 1) % self.choices.len();
+            None
+        }
+
+        fn choose(&self, index: usize) -> Option<Effect> {
+            match index < self.choices.len() {
+                true => Some(Effect::Choose { index }),
+                false => Some(Effect::Bell),
+            }
+        }
+
+        /// Choose the choice whose key is the character (ignoring case), or whose number is the
+        /// digit.
+        fn press(&self, character: char) -> Option<Effect> {
+            if let Some(index) = self
+                .choices
+                .iter()
+                .position(|choice| choice.key().eq_ignore_ascii_case(&character))
+            {
+                return self.choose(index);
+            }
+            match character.to_digit(10) {
+                Some(number) if number > 0 => self.choose(number as usize - 1),
+                _ => Some(Effect::Bell),
+            }
+        }
+    }
+
+    impl Stateful<Action, Effect> for State {
+        fn perform(&mut self, action: Action) -> Option<Effect> {
+            match action {
+                Action::Next => self.next(),
+                Action::Previous => self.previous(),
+                Action::ChooseHighlighted => self.choose(self.highlighted),
+                Action::Press { character } => self.press(character),
+                Action::Cancel => Some(Effect::Cancel),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        use test_case::test_case;
+
+        fn new_state() -> State {
+            let props = Props::builder()
+                .question("Overwrite foo?")
+                .choices(vec![
+                    Choice::new("Overwrite", 'o'),
+                    Choice::new("Rename", 'r'),
+                    Choice::new("Skip", 'k'),
+                    Choice::new("Cancel", 'c'),
+                ])
+                .default(2)
+                .build();
+            State::from(props)
+        }
+
+        #[test_case(vec![Action::ChooseHighlighted], Some(2); "default")]
+        #[test_case(vec![Action::Next, Action::ChooseHighlighted], Some(3); "next")]
+        #[test_case(vec![Action::Next, Action::Next, Action::ChooseHighlighted], Some(0); "next wraps")]
+        #[test_case(vec![Action::Previous, Action::ChooseHighlighted], Some(1); "previous")]
+        #[test_case(vec![Action::Press { character: 'R' }], Some(1); "key ignoring case")]
+        #[test_case(vec![Action::Press { character: '4' }], Some(3); "number")]
+        #[test_case(vec![Action::Press { character: '5' }], None; "number out of range")]
+        #[test_case(vec![Action::Press { character: 'x' }], None; "unknown key")]
+        fn test_choose(actions: Vec<Action>, expected: Option<usize>) {
+            let mut state = new_state();
+            let mut effect: Option<Effect> = None;
+            for action in actions {
+                effect = state.perform(action);
+            }
+
+            match (effect, expected) {
+                (Some(Effect::Choose { index }), Some(expected)) => assert_eq!(index, expected),
+                (Some(Effect::Bell), None) => {}
+                _ => panic!("Unexpected effect."),
+            }
+        }
+
+        #[test_case("Overwrite", 'o', "[O]verwrite"; "first character")]
+        #[test_case("Skip", 'k', "S[k]ip"; "inner character")]
+        #[test_case("Skip", 'x', "Skip (x)"; "missing character")]
+        fn test_marked_label(label: &str, key: char, expected: &str) {
+            assert_eq!(Choice::new(label, key).marked_label(), expected);
+        }
+    }
+}
+use state::State;
+
+mod action {
+    pub enum Action {
+        Next,
+        Previous,
+        ChooseHighlighted,
+        Press { character: char },
+        Cancel,
+    }
+}
+use action::Action;
+
+mod effect {
+    pub enum Effect {
+        /// Choose the choice at the index.
+        Choose {
+            index: usize,
+        },
+        Cancel,
+        Bell,
+    }
+}
+pub use effect::Effect;
@@ -24,6 +24,7 @@ mod props {
 pub use props::Props;
 
 mod file_creator {
+    use crate::inspect::{Inspect, Inspection};
     use rend::{Fabric, Size};
     use til::Component;
 
@@ -32,6 +33,8 @@ mod file_creator {
     use crate::components::common::{PhraseEffect, PhraseEvent};
     use crate::Stateful;
 
+    use term::{Key, KeyEvent, KeyMods, TermEvent};
+
     pub struct FileCreator {
         state: State,
     }
@@ -47,6 +50,12 @@ mod file_creator {
             let mut action: Option<Action> = None;
 
             match event {
+                Event::TermEvent(TermEvent::KeyEvent(KeyEvent {
+                    key: Key::Char('e'),
+                    mods: KeyMods::CONTROL,
+                })) if self.state.escalation().is_some() => {
+                    action = Some(Action::Escalate);
+                }
                 Event::TermEvent(term_event) => {
                     let phrase_event = PhraseEvent::TermEvent(term_event);
                     let phrase_effect = self.state.phrase.handle(phrase_event);
@@ -108,6 +117,12 @@ mod file_creator {
             }
         }
     }
+
+    impl Inspect for FileCreator {
+        fn inspect(&self) -> Inspection {
+            self.state.inspect()
+        }
+    }
 }
 pub use file_creator::FileCreator;
 
@@ -123,20 +138,21 @@ mod event {
 pub use event::Event;
 
 mod state {
+    use crate::inspect::{Inspect, Inspection};
     use std::path::PathBuf;
 
-    use uuid::Uuid;
-
     use file_type::FileType;
     use insh_api::{
-        CreateFileRequestParams, CreateFileResponseParams, Request, RequestParams, Response,
-        ResponseParams,
+        CreateFileError, CreateFileRequestParams, CreateFileResponseParams, Request, RequestParams,
+        Response, ResponseParams,
     };
     use til::Component;
 
     use super::{Action, Effect, Props};
     use crate::components::common::PhraseEvent;
     use crate::components::common::{Dir, DirProps, Phrase};
+    use crate::escalation::Escalation;
+    use crate::expand::expand;
     use crate::Stateful;
 
     pub struct State {
@@ -145,10 +161,15 @@ mod state {
         pub phrase: Phrase,
         file_type: FileType,
 
-        pending_request: Option<Uuid>,
-        pending_file: Option<PathBuf>,
+        /// The path of the file that is being created.
+        pending_path: Option<PathBuf>,
+        /// The type of the file that is being created.
+        pending_file_type: Option<FileType>,
 
         error: Option<String>,
+        /// Creating the file failed because permission was denied (so it can be retried with the
+        /// escalation command).
+        escalation: Option<Escalation>,
     }
 
     impl From<Props> for State {
@@ -161,9 +182,10 @@ mod state {
                 dir_component,
                 phrase: Phrase::default(),
                 file_type: props.file_type(),
-                pending_request: None,
-                pending_file: None,
+                pending_path: None,
+                pending_file_type: None,
                 error: None,
+                escalation: None,
             }
         }
     }
@@ -173,6 +195,7 @@ mod state {
             match action {
                 Action::CreateFile { filename } => self.create_file(&filename),
                 Action::HandleResponse(response) => self.handle_response(response),
+                Action::Escalate => self.escalate(),
                 Action::Bell => self.bell(),
                 Action::Quit => self.quit(),
             }
@@ -188,20 +211,41 @@ mod state {
             &self.error
         }
 
+        pub fn escalation(&self) -> &Option<Escalation> {
+            &self.escalation
+        }
+
         fn create_file(&mut self, filename: &str) -> Option<Effect> {
+            let filename: String = match expand(filename) {
+                Ok(filename) => filename,
+                Err(error) => {
+                    self.error = Some(error.to_string());
+                    self.phrase.handle(PhraseEvent::Focus);
+                    return None;
+                }
+            };
+
+            // NOTE: A trailing slash means a directory should be created (even in file mode).
+            let file_type: FileType = match filename.ends_with('/') {
+                true => FileType::Dir,
+                false => self.file_type,
+            };
+
             let mut path = self.dir.clone();
-            path.push(filename);
+            path.push(filename.trim_end_matches('/'));
+            self.pending_path = Some(path.clone());
+            self.escalation = None;
 
             let request = Request::builder()
                 .params(RequestParams::CreateFile(
                     CreateFileRequestParams::builder()
-                        .path(path.clone())
-                        .file_type(self.file_type)
+                        .path(path)
+                        .file_type(file_type)
+                        .parents(true)
                         .build(),
                 ))
                 .build();
-            self.pending_request = Some(*request.uuid());
-            self.pending_file = Some(path);
+            self.pending_file_type = Some(file_type);
 
             Some(Effect::Request(request))
         }
@@ -210,21 +254,6 @@ mod state {
             #[cfg(feature = "logging")]
             log::debug!("Handling response...");
 
-            let pending_request: Uuid = match self.pending_request {
-                Some(pending_request) => pending_request,
-                None => {
-                    #[cfg(feature = "logging")]
-                    log::debug!("There is no pending request.");
-                    return None;
-                }
-            };
-
-            if response.uuid() != &pending_request {
-                #[cfg(feature = "logging")]
-                log::debug!("The response is not for the pending request.");
-                return None;
-            }
-
             let params: &CreateFileResponseParams = match response.params() {
                 ResponseParams::CreateFile(params) => params,
                 _ => {
@@ -234,16 +263,46 @@ mod state {
                 }
             };
 
-            if let Err(error) = params.result() {
-                self.error = Some(error.to_string());
-                self.phrase.handle(PhraseEvent::Focus);
-                return None;
+            let path: PathBuf = match params.result() {
+                Ok(path) => path.clone(),
+                Err(CreateFileError::PermissionDenied) => {
+                    if let (Some(path), Some(file_type)) =
+                        (self.pending_path.take(), self.pending_file_type.take())
+                    {
+                        self.escalation = Some(Escalation::create(&path, file_type));
+                    }
+                    self.error = Some(String::from(
+                        "Permission denied. Press <Ctrl>-e to retry with elevated privileges.",
+                    ));
+                    self.phrase.handle(PhraseEvent::Focus);
+                    return None;
+                }
+                Err(error) => {
+                    self.error = Some(error.to_string());
+                    self.phrase.handle(PhraseEvent::Focus);
+                    return None;
+                }
+            };
+
+            // Drill down into created directories and select created files (using the path that
+            // was created so that it matches the path of the entry in the refreshed listing).
+            match self.pending_file_type.take().unwrap_or(self.file_type) {
+                FileType::Dir => Some(Effect::Browse {
+                    dir: path,
+                    file: None,
+                }),
+                _ => Some(Effect::Browse {
+                    dir: path.parent().unwrap_or(&self.dir).to_path_buf(),
+                    file: Some(path),
+                }),
             }
+        }
 
-            Some(Effect::Browse {
-                dir: self.dir.clone(),
-                file: Some(self.pending_file.clone().unwrap()),
-            })
+        /// Retry creating the file with the escalation command.
+        fn escalate(&mut self) -> Option<Effect> {
+            let escalation: Escalation = self.escalation.take()?;
+            self.error = None;
+            Some(Effect::Escalate { escalation })
         }
 
         fn bell(&mut self) -> Option<Effect> {
@@ -254,6 +313,59 @@ mod state {
             Some(Effect::Quit)
         }
     }
+
+    impl Inspect for State {
+        fn inspect(&self) -> Inspection {
+            Inspection::new("FileCreator")
+                .field("dir", &self.dir)
+                .field("file_type", self.file_type)
+                .field("phrase", self.phrase.value())
+                .field("pending_path", &self.pending_path)
+                .field("error", &self.error)
+                .field("escalation", &self.escalation)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::requester::send_over_socket;
+        use std::path::Path;
+        use test_case::test_case;
+
+        #[test_case(FileType::File, "notes.txt", "/r/notes.txt", FileType::File; "file")]
+        #[test_case(FileType::File, "src/", "/r/src", FileType::Dir; "trailing slash")]
+        #[test_case(FileType::Dir, "a/b", "/r/a/b", FileType::Dir; "nested directory")]
+        fn test_create_file(
+            file_type: FileType,
+            filename: &str,
+            expected_path: &str,
+            expected_file_type: FileType,
+        ) {
+            let props = Props::builder()
+                .dir(PathBuf::from("/r"))
+                .file_type(file_type)
+                .build();
+            let mut state = State::from(props);
+
+            let request: Request = match state.perform(Action::CreateFile {
+                filename: filename.to_string(),
+            }) {
+                Some(Effect::Request(request)) => request,
+                _ => panic!("Expected a request."),
+            };
+            let requests: Vec<Request> = send_over_socket(vec![request]);
+
+            match requests[0].params() {
+                RequestParams::CreateFile(params) => {
+                    assert_eq!(params.path(), Path::new(expected_path));
+                    assert_eq!(params.file_type(), expected_file_type);
+                    assert!(params.parents());
+                }
+                _ => panic!("Expected a request to create a file."),
+            }
+        }
+    }
 }
 use state::State;
 
@@ -262,9 +374,12 @@ mod effect {
 
     use insh_api::Request;
 
+    use crate::escalation::Escalation;
+
     pub enum Effect {
         Request(Request),
         Browse { dir: PathBuf, file: Option<PathBuf> },
+        Escalate { escalation: Escalation },
         Bell,
         Quit,
     }
@@ -277,6 +392,7 @@ mod action {
     pub enum Action {
         CreateFile { filename: String },
         HandleResponse(Response),
+        Escalate,
         Bell,
         Quit,
     }
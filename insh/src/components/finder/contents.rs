@@ -1,5 +1,9 @@
 mod props {
+    use std::cell::RefCell;
     use std::path::PathBuf;
+    use std::rc::Rc;
+
+    use crate::clipboard::Clipboard;
 
     use rend::Size;
     use typed_builder::TypedBuilder;
@@ -8,6 +12,18 @@ mod props {
     pub struct Props {
         pub dir: PathBuf,
         pub size: Size,
+        /// The number of rows of context to keep above and below the selection.
+        #[builder(default)]
+        pub scrolloff: usize,
+        /// Whether the hits are a flat list of the files under the directory (which skips the
+        /// hidden and ignored files).
+        #[builder(default)]
+        pub flat: bool,
+        /// Whether the request for the hits was already sent (when the contents start focussed
+        /// with hits instead of waiting for a phrase).
+        #[builder(default)]
+        pub finding: bool,
+        pub clipboard: Rc<RefCell<Clipboard>>,
     }
 }
 pub use props::Props;
@@ -15,6 +31,10 @@ pub use props::Props;
 mod contents {
     use super::{Action, Effect, Event, Props, State};
     use crate::color::Color;
+    use crate::components::common::{PhraseEffect, PhraseEvent};
+    use crate::count::Count;
+    use crate::inspect::{Inspect, Inspection};
+    use crate::list_filter::ListFilter;
     use crate::stateful::Stateful;
 
     use rend::{Fabric, Size, Yarn};
@@ -25,19 +45,42 @@ mod contents {
 
     pub struct Contents {
         state: State,
+        /// The count prefix for the next motion.
+        count: Count,
     }
 
     impl Component<Props, Event, Effect> for Contents {
         fn new(props: Props) -> Self {
             let state = State::from(props);
-            Self { state }
+            Self {
+                state,
+                count: Count::default(),
+            }
         }
 
         fn handle(&mut self, event: Event) -> Option<Effect> {
+            let event: Event = match event {
+                Event::TermEvent(term_event) if self.state.filtering() => {
+                    return self.handle_filter(term_event)
+                }
+                event => event,
+            };
+
+            let count: usize = match &event {
+                Event::TermEvent(TermEvent::KeyEvent(key_event)) => {
+                    if self.count.push(key_event) {
+                        return None;
+                    }
+                    self.count.take()
+                }
+                _ => 1,
+            };
+
             let action: Option<Action> = match event {
                 Event::Find { phrase } => Some(Action::Find { phrase }),
                 Event::TermEvent(term_event) => match term_event {
                     TermEvent::Resize(size) => Some(Action::Resize { size }),
+                    TermEvent::FocusGained | TermEvent::FocusLost => None,
                     TermEvent::KeyEvent(key_event) => match key_event {
                         KeyEvent {
                             key: Key::Char('q'),
@@ -47,7 +90,7 @@ mod contents {
                         KeyEvent {
                             key: Key::Char('j'),
                             mods: KeyMods::NONE,
-                        } => Some(Action::Down),
+                        } => Some(Action::Down { count }),
                         KeyEvent {
                             key: Key::Char('J'),
                             mods: KeyMods::SHIFT,
@@ -55,15 +98,49 @@ mod contents {
                         KeyEvent {
                             key: Key::Char('k'),
                             mods: KeyMods::NONE,
-                        } => Some(Action::Up),
+                        } => Some(Action::Up { count }),
                         KeyEvent {
                             key: Key::Char('K'),
                             mods: KeyMods::SHIFT,
                         } => Some(Action::ReallyUp),
+                        KeyEvent {
+                            key: Key::Char('d'),
+                            mods: KeyMods::CONTROL,
+                        } => Some(Action::HalfPageDown { count }),
+                        KeyEvent {
+                            key: Key::Char('u'),
+                            mods: KeyMods::CONTROL,
+                        } => Some(Action::HalfPageUp { count }),
+                        KeyEvent {
+                            key: Key::Char('f'),
+                            mods: KeyMods::CONTROL,
+                        } => Some(Action::PageDown { count }),
+                        KeyEvent {
+                            key: Key::Char('b'),
+                            mods: KeyMods::CONTROL,
+                        } => Some(Action::PageUp { count }),
                         KeyEvent {
                             key: Key::Char('r'),
                             mods: KeyMods::NONE,
                         } => Some(Action::Refresh),
+                        KeyEvent {
+                            key: Key::Char('/'),
+                            ..
+                        } => Some(Action::OpenFilter),
+                        KeyEvent {
+                            key: Key::Char('n'),
+                            mods: KeyMods::NONE,
+                        } => Some(Action::NextMatch { count }),
+                        KeyEvent {
+                            key: Key::Char('N'),
+                            mods: KeyMods::SHIFT,
+                        } => Some(Action::PreviousMatch { count }),
+                        KeyEvent {
+                            key: Key::Escape, ..
+                        } if self.state.filter().is_some() => Some(Action::ClearFilter),
+                        KeyEvent {
+                            key: Key::Escape, ..
+                        } if self.state.finding() => Some(Action::Cancel),
                         KeyEvent {
                             key: Key::Char('l'),
                             ..
@@ -104,12 +181,31 @@ mod contents {
         }
 
         fn render(&self, size: Size) -> Fabric {
+            match (self.state.hits(), self.state.filter(), size.rows) {
+                (Some(true), Some(filter), 1) => self.render_filter(filter, size.columns),
+                (Some(true), Some(filter), rows) if rows > 1 => {
+                    let entries: Fabric = self.render_entries(Size::new(rows - 1, size.columns));
+                    entries.quilt_bottom(self.render_filter(filter, size.columns))
+                }
+                _ => self.render_entries(size),
+            }
+        }
+    }
+
+    impl Contents {
+        /// Render the hits.
+        fn render_entries(&self, size: Size) -> Fabric {
             match self.state.hits() {
                 Some(true) => {
                     let dir: &str = &self.state.dir().to_string_lossy();
 
+                    let visible_entries = self.state.visible_entries();
+                    if visible_entries.is_empty() {
+                        return Fabric::center("No hits match the filter.", size);
+                    }
+
                     let mut yarns: Vec<Yarn> = Vec::new();
-                    for (entry, row) in self.state.visible_entries().iter().zip(0..size.rows) {
+                    for (entry, row) in visible_entries.into_iter().zip(0..size.rows) {
                         let path: &Path = entry.path();
                         let mut string: &str = &path.to_string_lossy();
                         string = string.strip_prefix(dir).unwrap();
@@ -146,6 +242,53 @@ mod contents {
                 None => Fabric::new(size),
             }
         }
+
+        /// Render the line with the phrase of the filter and the number of matches.
+        fn render_filter(&self, filter: &ListFilter, columns: usize) -> Fabric {
+            let phrase: &str = match self.state.filtering() {
+                true => self.state.filter_phrase.value(),
+                false => filter.phrase(),
+            };
+            let phrase: String = format!("/{}", phrase);
+            let matches: String = format!("{} matches", filter.match_count());
+            let padding: usize = columns.saturating_sub(phrase.chars().count() + matches.len());
+            let mut yarn = Yarn::from(format!("{}{}{}", phrase, " ".repeat(padding), matches));
+            yarn.resize(columns);
+            yarn.color(Color::InvertedText.into());
+            yarn.background(Color::focus_or_important(self.state.filtering()).into());
+            Fabric::from(yarn)
+        }
+
+        /// Handle a terminal event while the phrase of the filter is being edited.
+        fn handle_filter(&mut self, event: TermEvent) -> Option<Effect> {
+            let action: Option<Action> = match event {
+                TermEvent::Resize(size) => Some(Action::Resize { size }),
+                TermEvent::KeyEvent(KeyEvent {
+                    key: Key::Escape, ..
+                }) => Some(Action::ClearFilter),
+                term_event => match self
+                    .state
+                    .filter_phrase
+                    .handle(PhraseEvent::TermEvent(term_event))
+                {
+                    Some(PhraseEffect::Enter { .. }) => Some(Action::AcceptFilter),
+                    Some(PhraseEffect::Quit) => Some(Action::ClearFilter),
+                    Some(PhraseEffect::Bell) => None,
+                    None => Some(Action::UpdateFilter),
+                },
+            };
+
+            match action {
+                Some(action) => self.state.perform(action),
+                None => Some(Effect::Bell),
+            }
+        }
+    }
+
+    impl Inspect for Contents {
+        fn inspect(&self) -> Inspection {
+            self.state.inspect()
+        }
     }
 }
 pub use contents::Contents;
@@ -166,42 +309,65 @@ pub use event::Event;
 mod state {
     use super::{Action, Effect, Props};
     use crate::clipboard::Clipboard;
+    use crate::components::common::{Phrase, PhraseEvent, PhraseProps};
+    use crate::inspect::{Inspect, Inspection};
+    use crate::list_filter::ListFilter;
+    use crate::list_viewport::ListViewport;
     use crate::programs::{VimArgs, VimArgsBuilder};
     use crate::stateful::Stateful;
 
     use insh_api::{FindFilesResponseParams, Response, ResponseParams};
     use path_finder::Entry;
     use rend::Size;
+    use til::Component;
 
-    use std::cmp::{self, Ordering};
+    use std::cell::RefCell;
     use std::path::{Path, PathBuf, MAIN_SEPARATOR as PATH_SEPARATOR};
-
-    use uuid::Uuid;
+    use std::rc::Rc;
 
     pub struct State {
         size: Size,
         dir: PathBuf,
         phrase: Option<String>,
+        /// Whether hidden files and the files ignored by ignore files are skipped.
+        flat: bool,
         focussed: bool,
         hits: Option<bool>,
         entries: Vec<Entry>,
-        selected: Option<usize>,
-        offset: usize,
-        pending_request: Option<Uuid>,
+        viewport: ListViewport,
+        /// The filter of the hits (if there is one).
+        filter: Option<ListFilter>,
+        /// Whether the phrase of the filter is being edited.
+        filtering: bool,
+        pub filter_phrase: Phrase,
+        /// Whether the hits are still being found.
+        ///
+        /// NOTE: Only the responses to the latest request for the hits are routed to the contents.
+        finding: bool,
+        clipboard: Rc<RefCell<Clipboard>>,
     }
 
     impl From<Props> for State {
         fn from(props: Props) -> Self {
+            let started: bool = props.finding;
             Self {
                 size: props.size,
                 dir: props.dir,
-                phrase: None,
-                focussed: false,
+                phrase: started.then(String::new),
+                flat: props.flat,
+                focussed: started,
                 hits: None,
                 entries: Vec::new(),
-                selected: None,
-                offset: 0,
-                pending_request: None,
+                viewport: ListViewport::new(props.size.rows, props.scrolloff),
+                filter: None,
+                filtering: false,
+                filter_phrase: Phrase::new(
+                    PhraseProps::builder()
+                        .clipboard(props.clipboard.clone())
+                        .build(),
+                ),
+                finding: props.finding,
+                clipboard: props.clipboard,
             }
         }
     }
@@ -219,62 +385,77 @@ mod state {
             self.hits
         }
 
-        pub fn visible_entries(&self) -> &[Entry] {
-            if self.entries.is_empty() {
-                return &[];
-            }
-            let start = self.offset;
-            let end = cmp::min(self.offset + self.size.rows, self.entries.len());
-            &self.entries[start..end]
+        pub fn filter(&self) -> Option<&ListFilter> {
+            self.filter.as_ref()
+        }
+
+        pub fn filtering(&self) -> bool {
+            self.filtering
+        }
+
+        pub fn finding(&self) -> bool {
+            self.finding
+        }
+
+        pub fn visible_entries(&self) -> Vec<&Entry> {
+            self.viewport
+                .visible()
+                .filter_map(|number| self.shown_entry(number))
+                .collect()
         }
 
         pub fn selected(&self) -> Option<usize> {
-            self.selected
+            self.viewport.selected_row()
         }
 
         fn entry_number(&self) -> Option<usize> {
-            self.selected.map(|selected| self.offset + selected)
+            self.viewport.selected()
         }
 
         fn entry_path(&self) -> Option<&Path> {
-            match self.entry_number() {
-                Some(entry_number) => Some(self.entries[entry_number].path()),
-                None => None,
+            Some(self.shown_entry(self.entry_number()?)?.path())
+        }
+
+        /// Return the number of hits that are shown (the hits that match the filter if there is
+        /// one).
+        fn shown_count(&self) -> usize {
+            match &self.filter {
+                Some(filter) => filter.match_count(),
+                None => self.entries.len(),
             }
         }
 
-        fn resize(&mut self, new_size: Size) -> Option<Effect> {
-            if let Some(selected) = self.selected {
-                let rows_before = self.size.rows;
-                let entry_count = self.entries.len();
-                let mut visible_entries_count = cmp::min(rows_before, entry_count - self.offset);
-                let selected_percent: f64 = selected as f64 / visible_entries_count as f64;
-
-                let mut new_selected: usize = (new_size.rows as f64 * selected_percent) as usize;
-                let mut new_offset: usize;
-                let entry_number = self.offset + selected;
-                match entry_number.cmp(&new_selected) {
-                    Ordering::Less | Ordering::Equal => {
-                        new_offset = 0;
-                        new_selected = entry_number;
-                    }
-                    Ordering::Greater => {
-                        new_offset = entry_number - new_selected;
-                        visible_entries_count = entry_count - new_offset;
-                        if visible_entries_count < new_size.rows {
-                            let bottom_pinned_offset = entry_count.saturating_sub(new_size.rows);
-                            let difference = new_offset - bottom_pinned_offset;
-                            new_selected += difference;
-                            new_offset = bottom_pinned_offset;
-                        }
-                    }
-                }
+        /// Return the shown hit with the number `number`.
+        fn shown_entry(&self, number: usize) -> Option<&Entry> {
+            let index: usize = match &self.filter {
+                Some(filter) => filter.get(number)?,
+                None => number,
+            };
+            self.entries.get(index)
+        }
 
-                self.offset = new_offset;
-                self.selected = Some(new_selected);
+        /// Return the path of a hit relative to the directory (which is matched against the
+        /// phrase of the filter).
+        fn entry_name(&self, entry: &Entry) -> String {
+            let path: String = entry.path().to_string_lossy().to_string();
+            let dir: String = self.dir.to_string_lossy().to_string();
+            let name: &str = path.strip_prefix(&dir).unwrap_or(&path);
+            name.strip_prefix(PATH_SEPARATOR)
+                .unwrap_or(name)
+                .to_string()
+        }
+
+        /// Return the number of rows for the hits (the last row shows the filter if there is one).
+        fn list_rows(&self) -> usize {
+            match self.filter {
+                Some(_) => self.size.rows.saturating_sub(1),
+                None => self.size.rows,
             }
+        }
 
+        fn resize(&mut self, new_size: Size) -> Option<Effect> {
             self.size = new_size;
+            self.viewport.resize(self.list_rows());
             None
         }
 
@@ -290,64 +471,55 @@ mod state {
         fn find(&mut self, phrase: &str) -> Option<Effect> {
             self.focus();
             self.phrase = Some(phrase.to_string());
-            let uuid: Uuid = Uuid::new_v4();
-            self.pending_request = Some(uuid);
+            self.finding = true;
             Some(Effect::SendFindFilesRequest {
-                uuid,
                 dir: self.dir.clone(),
                 pattern: phrase.to_string(),
+                respect_ignore: self.flat,
             })
         }
 
-        fn down(&mut self) -> Option<Effect> {
-            if self.entries.is_empty() {
+        /// Stop finding files (keeping the hits that were already found).
+        fn cancel(&mut self) -> Option<Effect> {
+            if !self.finding {
                 return None;
             }
+            self.finding = false;
+            self.hits = Some(!self.entries.is_empty());
+            Some(Effect::CancelRequest)
+        }
 
-            let entry_number = self.entry_number().unwrap();
-            if entry_number >= self.entries.len() - 1 {
-                return None;
-            }
-            let selected = self.selected.unwrap();
-            if selected < self.size.rows - 1 {
-                self.selected = Some(selected + 1);
-            } else {
-                self.offset += 1;
-            }
+        fn down(&mut self, count: usize) -> Option<Effect> {
+            self.viewport.down(count);
             None
         }
 
         /// Select the last hit and adjust the scroll position if necessary.
         fn really_down(&mut self) -> Option<Effect> {
-            if self.entries.is_empty() {
-                return None;
-            }
-
-            if self.entries.len() > self.size.rows {
-                self.offset = self.entries.len() - self.size.rows;
-                self.selected = Some(self.size.rows - 1);
-            } else {
-                self.selected = Some(self.entries.len() - 1);
-            }
-
+            self.viewport.last();
             None
         }
 
-        fn up(&mut self) -> Option<Effect> {
-            if let Some(selected) = self.selected {
-                if selected > 0 {
-                    self.selected = Some(selected.saturating_sub(1))
-                } else {
-                    self.offset = self.offset.saturating_sub(1);
-                }
-            }
+        fn up(&mut self, count: usize) -> Option<Effect> {
+            self.viewport.up(count);
             None
         }
 
         /// Select the first hit and adjust the scroll position if necessary.
         fn really_up(&mut self) -> Option<Effect> {
-            self.offset = 0;
-            self.selected = Some(0);
+            self.viewport.first();
+            None
+        }
+
+        /// Scroll down by `distance` hits (moving the selection along with the hits).
+        fn scroll_down(&mut self, distance: usize) -> Option<Effect> {
+            self.viewport.scroll_down(distance);
+            None
+        }
+
+        /// Scroll up by `distance` hits (moving the selection along with the hits).
+        fn scroll_up(&mut self, distance: usize) -> Option<Effect> {
+            self.viewport.scroll_up(distance);
             None
         }
 
@@ -405,7 +577,7 @@ mod state {
 
         fn _yank(&mut self, really: bool) -> Option<Effect> {
             if let Some(entry) = self.entry_path() {
-                let mut path: String = entry.to_path_buf().to_string_lossy().to_string();
+                let mut path: String = entry.to_string_lossy().to_string();
                 if !really {
                     let dir_string: String = self.dir().to_string_lossy().to_string();
                     path = path.strip_prefix(&dir_string).unwrap().to_string();
@@ -413,32 +585,117 @@ mod state {
                         path = path.strip_prefix(PATH_SEPARATOR).unwrap().to_string();
                     }
                 }
-                let mut clipboard = Clipboard::new();
-                clipboard.copy(path);
+                self.clipboard.borrow_mut().copy(path);
+            }
+            None
+        }
+
+        /// Open the prompt for the phrase of the filter (keeping the current phrase).
+        fn open_filter(&mut self) -> Option<Effect> {
+            if self.hits != Some(true) {
+                return Some(Effect::Bell);
+            }
+            let phrase: String = self
+                .filter
+                .as_ref()
+                .map(|filter| filter.phrase().to_string())
+                .unwrap_or_default();
+            self.filter_phrase.handle(PhraseEvent::Set { phrase });
+            self.filtering = true;
+            if self.filter.is_none() {
+                self.filter = Some(ListFilter::default());
+                self.update_filter();
+            }
+            None
+        }
+
+        /// Match the phrase that is being edited against the hits.
+        ///
+        /// The selected hit stays selected if it still matches, otherwise the first match is
+        /// selected.
+        fn update_filter(&mut self) -> Option<Effect> {
+            let selected: Option<PathBuf> = self.entry_path().map(Path::to_path_buf);
+            let names: Vec<String> = self
+                .entries
+                .iter()
+                .map(|entry| self.entry_name(entry))
+                .collect();
+            if let Some(filter) = &mut self.filter {
+                filter.set_phrase(self.filter_phrase.value(), names.iter());
+            }
+            self.viewport.resize(self.list_rows());
+            self.viewport.set_len(self.shown_count());
+            match selected.and_then(|path| self.shown_position(&path)) {
+                Some(number) => self.viewport.select(number),
+                None => self.viewport.first(),
+            }
+            None
+        }
+
+        /// Stop editing the phrase of the filter (removing the filter if the phrase is empty).
+        fn accept_filter(&mut self) -> Option<Effect> {
+            self.filtering = false;
+            if self.filter_phrase.value().is_empty() {
+                return self.clear_filter();
+            }
+            None
+        }
+
+        /// Remove the filter and keep the selected hit selected.
+        fn clear_filter(&mut self) -> Option<Effect> {
+            let selected: Option<PathBuf> = self.entry_path().map(Path::to_path_buf);
+            self.remove_filter();
+            if let Some(number) = selected.and_then(|path| self.shown_position(&path)) {
+                self.viewport.select(number);
             }
             None
         }
 
+        /// Remove the filter (if there is one).
+        fn remove_filter(&mut self) {
+            self.filter = None;
+            self.filtering = false;
+            self.viewport.resize(self.list_rows());
+            self.viewport.set_len(self.shown_count());
+        }
+
+        /// Return the number of the shown hit at `path`.
+        fn shown_position(&self, path: &Path) -> Option<usize> {
+            (0..self.shown_count()).find(|number| {
+                self.shown_entry(*number)
+                    .is_some_and(|entry| entry.path() == path)
+            })
+        }
+
+        /// Select the hit `count` matches of the filter after (or before if not `forward`) the
+        /// selected hit, wrapping around at the ends.
+        fn jump_to_match(&mut self, count: usize, forward: bool) -> Option<Effect> {
+            let shown_count: usize = self.shown_count();
+            let entry_number: usize = match (&self.filter, self.entry_number()) {
+                (Some(_), Some(entry_number)) if shown_count > 0 => entry_number,
+                _ => return Some(Effect::Bell),
+            };
+            let count: usize = count % shown_count;
+            let number: usize = match forward {
+                true => (entry_number + count) % shown_count,
+                false => (entry_number + shown_count - count) % shown_count,
+            };
+            self.viewport.select(number);
+            None
+        }
+
         fn handle_response(&mut self, response: Response) -> Option<Effect> {
             #[cfg(feature = "logging")]
             log::debug!("Handling response...");
 
-            let pending_request: Uuid = match self.pending_request {
-                Some(pending_request) => pending_request,
-                None => {
-                    #[cfg(feature = "logging")]
-                    log::debug!("There is no pending request.");
-                    return None;
-                }
-            };
-
-            if response.uuid() != &pending_request {
+            if !self.finding {
                 #[cfg(feature = "logging")]
-                log::debug!("The response is not for the pending request.");
+                log::debug!("The hits are not being found.");
                 return None;
             }
 
-            let params: &FindFilesResponseParams = match response.params() {
+            let last: bool = response.last();
+            let params: FindFilesResponseParams = match response.into_params() {
                 ResponseParams::FindFiles(params) => params,
                 _ => {
                     #[cfg(feature = "logging")]
@@ -447,21 +704,33 @@ mod state {
                 }
             };
 
-            self.entries.extend_from_slice(params.entries());
+            // NOTE: Only the names of the new entries are matched against the filter so that a huge
+            // tree (which arrives in many batches) doesn't match every entry again for each batch.
+            let start: usize = self.entries.len();
+            self.entries.extend(params.into_entries());
+            if self.filter.is_some() {
+                let names: Vec<String> = self.entries[start..]
+                    .iter()
+                    .map(|entry| self.entry_name(entry))
+                    .collect();
+                if let Some(filter) = &mut self.filter {
+                    filter.extend(start, names.iter());
+                }
+            }
+            self.viewport.set_len(self.shown_count());
 
-            if self.entries.is_empty() && response.last() {
+            if self.entries.is_empty() && last {
                 self.hits = Some(false);
-                self.selected = None;
                 return Some(Effect::Unfocus);
             }
 
             self.hits = Some(true);
-            if self.selected.is_none() {
-                self.selected = Some(0);
+            if self.viewport.selected().is_none() {
+                self.viewport.first();
             }
 
-            if response.last() {
-                self.pending_request = None;
+            if last {
+                self.finding = false;
             }
 
             None
@@ -473,11 +742,18 @@ mod state {
             match action {
                 Action::Unfocus => self.unfocus(),
                 Action::Find { phrase } => self.find(&phrase),
+                Action::Cancel => self.cancel(),
                 Action::Resize { size } => self.resize(size),
-                Action::Down => self.down(),
+                Action::Down { count } => self.down(count),
                 Action::ReallyDown => self.really_down(),
-                Action::Up => self.up(),
+                Action::Up { count } => self.up(count),
                 Action::ReallyUp => self.really_up(),
+                Action::HalfPageDown { count } => {
+                    self.scroll_down(self.viewport.half_page() * count)
+                }
+                Action::HalfPageUp { count } => self.scroll_up(self.viewport.half_page() * count),
+                Action::PageDown { count } => self.scroll_down(self.viewport.page() * count),
+                Action::PageUp { count } => self.scroll_up(self.viewport.page() * count),
                 Action::Refresh => self.refresh(),
                 Action::Edit => self.edit(),
                 Action::Goto => self.goto(),
@@ -485,9 +761,29 @@ mod state {
                 Action::Yank => self.yank(),
                 Action::ReallyYank => self.really_yank(),
                 Action::HandleResponse(response) => self.handle_response(response),
+                Action::OpenFilter => self.open_filter(),
+                Action::UpdateFilter => self.update_filter(),
+                Action::AcceptFilter => self.accept_filter(),
+                Action::ClearFilter => self.clear_filter(),
+                Action::NextMatch { count } => self.jump_to_match(count, true),
+                Action::PreviousMatch { count } => self.jump_to_match(count, false),
             }
         }
     }
+
+    impl Inspect for State {
+        fn inspect(&self) -> Inspection {
+            Inspection::new("Contents")
+                .field("dir", &self.dir)
+                .field("phrase", &self.phrase)
+                .field("flat", self.flat)
+                .field("hits", self.entries.len())
+                .field("selected", self.viewport.selected())
+                .field("filter", self.filter.as_ref().map(ListFilter::phrase))
+                .field("filtering", self.filtering)
+                .field("finding", self.finding)
+        }
+    }
 }
 use state::State;
 
@@ -497,12 +793,34 @@ mod action {
 
     pub enum Action {
         Unfocus,
-        Find { phrase: String },
-        Resize { size: Size },
-        Down,
+        Find {
+            phrase: String,
+        },
+        /// Stop finding files.
+        Cancel,
+        Resize {
+            size: Size,
+        },
+        Down {
+            count: usize,
+        },
         ReallyDown,
-        Up,
+        Up {
+            count: usize,
+        },
         ReallyUp,
+        HalfPageDown {
+            count: usize,
+        },
+        HalfPageUp {
+            count: usize,
+        },
+        PageDown {
+            count: usize,
+        },
+        PageUp {
+            count: usize,
+        },
         Refresh,
         Edit,
         Goto,
@@ -510,6 +828,16 @@ mod action {
         Yank,
         ReallyYank,
         HandleResponse(Response),
+        OpenFilter,
+        UpdateFilter,
+        AcceptFilter,
+        ClearFilter,
+        NextMatch {
+            count: usize,
+        },
+        PreviousMatch {
+            count: usize,
+        },
     }
 }
 use action::Action;
@@ -519,15 +847,15 @@ mod effect {
 
     use std::path::PathBuf;
 
-    use uuid::Uuid;
-
     pub enum Effect {
         Unfocus,
         SendFindFilesRequest {
-            uuid: Uuid,
             dir: PathBuf,
             pattern: String,
+            /// Whether hidden files and the files ignored by ignore files are skipped.
+            respect_ignore: bool,
         },
+        CancelRequest,
         Goto {
             dir: PathBuf,
             file: Option<PathBuf>,
@@ -1,7 +1,11 @@
 mod props {
+    use crate::clipboard::Clipboard;
+
     use rend::Size;
 
+    use std::cell::RefCell;
     use std::path::PathBuf;
+    use std::rc::Rc;
 
     use typed_builder::TypedBuilder;
 
@@ -12,6 +16,18 @@ mod props {
         pub size: Size,
         #[builder(setter(into))]
         pub phrase: Option<String>,
+        /// The number of rows of context to keep above and below the selection.
+        #[builder(default)]
+        pub scrolloff: usize,
+        /// Whether the hits are a flat list of the files under the directory (which skips the
+        /// hidden and ignored files).
+        #[builder(default)]
+        pub flat: bool,
+        /// Whether the request for the hits was already sent (when the finder starts with the hits
+        /// focussed instead of the phrase).
+        #[builder(default)]
+        pub finding: bool,
+        pub clipboard: Rc<RefCell<Clipboard>>,
     }
 }
 pub use props::Props;
@@ -20,13 +36,14 @@ mod finder {
     use super::super::{ContentsEffect, ContentsEvent};
     use super::{Action, Effect, Focus, Props, State};
     use crate::components::common::{PhraseEffect, PhraseEvent};
+    use crate::inspect::{Inspect, Inspection};
     use crate::stateful::Stateful;
 
     use insh_api::Response;
     use rend::{Fabric, Size};
     use til::{Component, Event};
 
-    use term::TermEvent;
+    use term::{Key, KeyEvent, KeyMods, TermEvent};
 
     pub struct Finder {
         state: State,
@@ -41,11 +58,7 @@ mod finder {
         fn handle(&mut self, event: Event<Response>) -> Option<Effect> {
             match event {
                 Event::TermEvent(TermEvent::Resize(size)) => {
-                    let contents_size = Size::new(size.rows.saturating_sub(2), size.columns);
-                    self.state
-                        .contents
-                        .handle(ContentsEvent::TermEvent(TermEvent::Resize(contents_size)));
-                    None
+                    self.state.perform(Action::Resize { size })
                 }
                 _ => match self.state.focus() {
                     Focus::Phrase => {
@@ -58,25 +71,40 @@ mod finder {
                             }
                         };
 
+                        if let TermEvent::KeyEvent(KeyEvent {
+                            key: Key::Char('g'),
+                            mods: KeyMods::CONTROL,
+                        }) = event
+                        {
+                            return self.state.perform(Action::ToggleGlob);
+                        }
+
                         let mut action: Option<Action> = None;
 
                         let phrase_event = PhraseEvent::TermEvent(event);
                         let phrase_effect = self.state.phrase.handle(phrase_event);
+                        self.state.perform(Action::ValidatePattern);
                         match phrase_effect {
                             Some(PhraseEffect::Enter { phrase }) => {
+                                if self.state.pattern_state().is_invalid() {
+                                    self.state.phrase.handle(PhraseEvent::Focus);
+                                    return Some(Effect::Bell);
+                                }
+
+                                let phrase: String = self.state.pattern(&phrase);
                                 self.state.perform(Action::FocusContents);
                                 let contents_effect =
                                     self.state.contents.handle(ContentsEvent::Find { phrase });
                                 match contents_effect {
                                     Some(ContentsEffect::SendFindFilesRequest {
-                                        uuid,
                                         dir,
                                         pattern,
+                                        respect_ignore,
                                     }) => {
                                         return Some(Effect::SendFindFilesRequest {
-                                            uuid,
                                             dir,
                                             pattern,
+                                            respect_ignore,
                                         })
                                     }
                                     _ => {}
@@ -109,9 +137,16 @@ mod finder {
                                 self.state.phrase.handle(PhraseEvent::Focus);
                                 None
                             }
-                            Some(ContentsEffect::SendFindFilesRequest { uuid, dir, pattern }) => {
-                                Some(Effect::SendFindFilesRequest { uuid, dir, pattern })
-                            }
+                            Some(ContentsEffect::SendFindFilesRequest {
+                                dir,
+                                pattern,
+                                respect_ignore,
+                            }) => Some(Effect::SendFindFilesRequest {
+                                dir,
+                                pattern,
+                                respect_ignore,
+                            }),
+                            Some(ContentsEffect::CancelRequest) => Some(Effect::CancelRequest),
                             Some(ContentsEffect::Goto { dir, file }) => {
                                 Some(Effect::Browse { dir, file })
                             }
@@ -144,30 +179,52 @@ mod finder {
                     let phrase_fabric = self.state.phrase.render(Size::new(1, columns));
                     fabric = fabric.quilt_bottom(phrase_fabric);
 
-                    let contents_fabric =
-                        self.state.contents().render(Size::new(rows - 2, columns));
+                    let mut contents_rows: usize = rows - 2;
+                    if let Some(message) = self.state.message(columns) {
+                        fabric = fabric.quilt_bottom(Fabric::from(message));
+                        contents_rows -= 1;
+                    }
+
+                    let contents_fabric = self
+                        .state
+                        .contents()
+                        .render(Size::new(contents_rows, columns));
                     fabric.quilt_bottom(contents_fabric)
                 }
             }
         }
     }
+
+    impl Inspect for Finder {
+        fn inspect(&self) -> Inspection {
+            self.state.inspect()
+        }
+    }
 }
 pub use finder::Finder;
 
 mod state {
-    use super::super::{Contents, ContentsProps};
+    use super::super::{Contents, ContentsEvent, ContentsProps};
     use super::{Action, Effect, Focus, Props};
+    use crate::color::Color;
     use crate::components::common::{Dir, DirProps, Phrase, PhraseProps};
+    use crate::inspect::{Inspect, Inspection};
+    use crate::pattern::{glob_to_regex, PatternState};
     use crate::stateful::Stateful;
 
-    use rend::Size;
+    use rend::{Size, Yarn};
+    use term::TermEvent;
     use til::Component;
 
     pub struct State {
+        size: Size,
         dir: Dir,
         pub phrase: Phrase,
         pub contents: Contents,
         focus: Focus,
+        /// Whether the phrase is a simple glob pattern instead of a regex.
+        glob: bool,
+        pattern_state: PatternState,
     }
 
     impl From<Props> for State {
@@ -175,23 +232,40 @@ mod state {
             let dir_props = DirProps::new(props.dir.clone());
             let dir = Dir::new(dir_props);
 
-            let phrase = Phrase::new(PhraseProps::builder().value(props.phrase).build());
+            let phrase = Phrase::new(
+                PhraseProps::builder()
+                    .value(props.phrase)
+                    .clipboard(props.clipboard.clone())
+                    .build(),
+            );
 
             let contents_size = Size::new(props.size.rows.saturating_sub(2), props.size.columns);
             let contents_props = ContentsProps::builder()
                 .dir(props.dir)
                 .size(contents_size)
+                .scrolloff(props.scrolloff)
+                .flat(props.flat)
+                .finding(props.finding)
+                .clipboard(props.clipboard)
                 .build();
             let contents = Contents::new(contents_props);
 
-            let focus = Focus::default();
+            let focus = match props.finding {
+                true => Focus::Contents,
+                false => Focus::default(),
+            };
 
-            Self {
+            let mut state = Self {
+                size: props.size,
                 dir,
                 phrase,
                 contents,
                 focus,
-            }
+                glob: false,
+                pattern_state: PatternState::default(),
+            };
+            state.validate_pattern();
+            state
         }
     }
 
@@ -208,6 +282,77 @@ mod state {
             &self.focus
         }
 
+        pub fn pattern_state(&self) -> &PatternState {
+            &self.pattern_state
+        }
+
+        /// Return the regex pattern for the phrase.
+        pub fn pattern(&self, phrase: &str) -> String {
+            match self.glob {
+                true => glob_to_regex(phrase),
+                false => phrase.to_string(),
+            }
+        }
+
+        /// Return the message to show under the phrase (if there is one).
+        ///
+        /// If the pattern is invalid, then the message is the error. Else, if the phrase is a glob
+        /// pattern, then the message is the regex that the glob is converted to.
+        pub fn message(&self, columns: usize) -> Option<Yarn> {
+            let mut yarn: Yarn = match (&self.pattern_state, self.glob) {
+                (PatternState::Invalid { error }, _) => {
+                    let mut yarn = Yarn::from(error.as_str());
+                    yarn.color(Color::BadRegex.into());
+                    yarn
+                }
+                (_, true) => {
+                    let regex: String = self.pattern(self.phrase.value());
+                    let mut yarn = Yarn::from(format!("Glob pattern as a regex: {}", regex));
+                    yarn.color(Color::GrayedText.into());
+                    yarn
+                }
+                (_, false) => {
+                    return None;
+                }
+            };
+            yarn.resize(columns);
+            Some(yarn)
+        }
+
+        fn resize(&mut self, size: Size) -> Option<Effect> {
+            self.size = size;
+            self.resize_contents();
+            None
+        }
+
+        /// Resize the contents to fit in the rows not used by the directory, the phrase, and the
+        /// message.
+        fn resize_contents(&mut self) {
+            let mut rows: usize = self.size.rows.saturating_sub(2);
+            if self.message(self.size.columns).is_some() {
+                rows = rows.saturating_sub(1);
+            }
+            let contents_size = Size::new(rows, self.size.columns);
+            self.contents
+                .handle(ContentsEvent::TermEvent(TermEvent::Resize(contents_size)));
+        }
+
+        fn validate_pattern(&mut self) -> Option<Effect> {
+            let had_message: bool = self.message(self.size.columns).is_some();
+            self.pattern_state = PatternState::new(&self.pattern(self.phrase.value()));
+            if had_message != self.message(self.size.columns).is_some() {
+                self.resize_contents();
+            }
+            None
+        }
+
+        fn toggle_glob(&mut self) -> Option<Effect> {
+            self.glob = !self.glob;
+            self.validate_pattern();
+            self.resize_contents();
+            None
+        }
+
         fn focus_contents(&mut self) -> Option<Effect> {
             self.focus = Focus::Contents;
             None
@@ -228,15 +373,28 @@ mod state {
             match action {
                 Action::FocusContents => self.focus_contents(),
                 Action::FocusPhrase => self.focus_phrase(),
+                Action::Resize { size } => self.resize(size),
+                Action::ValidatePattern => self.validate_pattern(),
+                Action::ToggleGlob => self.toggle_glob(),
                 Action::Quit => self.quit(),
             }
         }
     }
+
+    impl Inspect for State {
+        fn inspect(&self) -> Inspection {
+            Inspection::new("Finder")
+                .field("focus", &self.focus)
+                .field("phrase", self.phrase.value())
+                .field("glob", self.glob)
+                .child(self.contents.inspect())
+        }
+    }
 }
 use state::State;
 
 mod focus {
-    #[derive(Default)]
+    #[derive(Debug, Default)]
     pub enum Focus {
         #[default]
         Phrase,
@@ -246,9 +404,14 @@ mod focus {
 use focus::Focus;
 
 mod action {
+    use rend::Size;
+
     pub enum Action {
         FocusContents,
         FocusPhrase,
+        Resize { size: Size },
+        ValidatePattern,
+        ToggleGlob,
         Quit,
     }
 }
@@ -259,14 +422,15 @@ mod effect {
 
     use std::path::PathBuf;
 
-    use uuid::Uuid;
-
     pub enum Effect {
         SendFindFilesRequest {
-            uuid: Uuid,
             dir: PathBuf,
             pattern: String,
+            /// Whether hidden files and the files ignored by ignore files are skipped.
+            respect_ignore: bool,
         },
+        /// Cancel the request for the hits (which is still in flight).
+        CancelRequest,
         Browse {
             dir: PathBuf,
             file: Option<PathBuf>,
@@ -1,58 +1,103 @@
+use crate::choose_mode::ChooseMode;
+use crate::clipboard::Clipboard;
+use crate::color::Color;
 use crate::components::browser::{Browser, BrowserEffect, BrowserEvent, BrowserProps};
+use crate::components::common::{Choice, Prompt, PromptEffect, PromptEvent, PromptProps};
 use crate::components::file_creator::{
     FileCreator, FileCreatorEffect, FileCreatorEvent, FileCreatorProps,
 };
 use crate::components::finder::{Finder, FinderEffect, FinderProps};
+use crate::components::jobs::{Jobs, JobsEffect, JobsEvent, JobsProps};
+use crate::components::log_viewer::{
+    read_log_request, LogViewer, LogViewerEffect, LogViewerEvent, LogViewerProps,
+};
+use crate::components::messages::{Messages, MessagesEffect, MessagesEvent, MessagesProps};
+use crate::components::mounts::{Mounts, MountsEffect, MountsEvent, MountsProps};
+use crate::components::open_files::{
+    get_open_files_request, OpenFiles, OpenFilesEffect, OpenFilesEvent, OpenFilesProps,
+};
+use crate::components::pager::{read_chunk_request, Pager, PagerEffect, PagerEvent, PagerProps};
+use crate::components::palette::{Palette, PaletteEffect, PaletteEvent, PaletteProps};
+use crate::components::process_list::{
+    get_processes_request, ProcessList, ProcessListEffect, ProcessListEvent, ProcessListProps,
+};
+use crate::components::reader::{Reader, ReaderEffect, ReaderProps};
 use crate::components::searcher::{Searcher, SearcherEffect, SearcherProps};
 use crate::config::Config;
 use crate::current_dir;
-use crate::programs::{Bash, Vim};
+use crate::data::Data;
+use crate::escalation::Escalation;
+use crate::hooks::{run_hook, HookEvent};
+use crate::inspect::{Inspect, Inspection};
+use crate::jobs::{Job, JobStatus};
+#[cfg(feature = "logging")]
+use crate::logging::correlate;
+use crate::open_policy::open;
+use crate::plugins::{self, PluginCommand, PluginContext};
+use crate::programs::{Bash, Eject, Escalated, ImagePreview, Mount, Plugin, VimArgs};
+use crate::request_registry::{RequestRegistry, Resolution};
+use crate::script::Script;
 use crate::stateful::Stateful;
+use crate::toasts::{ToastLevel, Toasts};
 
 use file_type::FileType;
-use insh_api::{FindFilesRequestParams, GetFilesRequestParams, Request, RequestParams, Response};
-use rend::{Fabric, Size};
+use insh_api::{
+    CancelRequestRequestParams, FindFilesRequestParams, GetFilesRequestParams, Request,
+    RequestKind, RequestParams, Response, ResponseParams, SearchPhraseRequestParams,
+    TransferFileRequestParams,
+};
+use rend::{Fabric, Size, Yarn};
 use term::{Key, KeyEvent, KeyMods, TermEvent};
-use til::{Component, Event, SystemEffect};
+use til::{
+    has_id, BackgroundDone, Component, ComponentId, Event, Identified, Modal, ModalHandling,
+    ModalStack, SystemEffect, Urgency,
+};
 
-use std::path::PathBuf;
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::fs;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Instant, SystemTime};
 
 use crossterm::terminal;
+use uuid::Uuid;
 
 mod props {
     use std::path::PathBuf;
+    use std::rc::Rc;
+    use std::sync::Arc;
 
     use typed_builder::TypedBuilder;
     use uuid::Uuid;
 
     use crate::args::Command;
+    use crate::choose_mode::ChooseMode;
     use crate::config::Config;
+    use crate::script::Script;
 
     #[derive(TypedBuilder)]
     pub struct Props {
-        start: Start,
-        dir: Option<PathBuf>,
+        pub start: Start,
+        pub dir: Option<PathBuf>,
         #[builder(default)]
-        pending_browser_request: Option<Uuid>,
-        config: Config,
-    }
-
-    impl Props {
-        pub fn start(&self) -> &Start {
-            &self.start
-        }
-
-        pub fn dir(&self) -> &Option<PathBuf> {
-            &self.dir
-        }
-
-        pub fn pending_browser_request(&self) -> &Option<Uuid> {
-            &self.pending_browser_request
-        }
-
-        pub fn config(&self) -> &Config {
-            &self.config
-        }
+        pub pending_browser_request: Option<Uuid>,
+        pub config: Arc<Config>,
+        /// The script defining custom actions of the browser.
+        #[builder(default)]
+        pub script: Option<Rc<Script>>,
+        /// The file to write the browsed directory to on exit.
+        #[builder(default)]
+        pub cd_file: Option<PathBuf>,
+        /// What can be chosen (if Insh is being run as a file picker).
+        #[builder(default)]
+        pub choose_mode: Option<ChooseMode>,
+        /// The file to write the chosen path to.
+        #[builder(default)]
+        pub choose_out: Option<PathBuf>,
     }
 
     pub enum Start {
@@ -68,10 +113,16 @@ mod props {
                 Some(Command::Browse) | None => Start::Browser,
                 Some(Command::Search { phrase }) => Start::Searcher { phrase },
                 Some(Command::Find { phrase }) => Start::Finder { phrase },
+                Some(Command::Open { pattern }) => Start::Finder {
+                    phrase: Some(pattern),
+                },
                 Some(Command::Edit { browse, .. }) => match browse {
                     true => Start::Browser,
                     false => Start::Nothing,
                 },
+                Some(Command::ShellInit { .. }) | Some(Command::PreviewImage { .. }) => {
+                    Start::Nothing
+                }
             }
         }
     }
@@ -90,24 +141,193 @@ impl Component<Props, Event<Response>, SystemEffect<Request>> for Insh {
     }
 
     fn handle(&mut self, event: Event<Response>) -> Option<SystemEffect<Request>> {
+        match event {
+            Event::TermEvent(TermEvent::KeyEvent(KeyEvent {
+                key: Key::FileSep,
+                mods: KeyMods::NONE,
+            })) => {
+                self.state.debug = !self.state.debug;
+                return None;
+            }
+            Event::TermEvent(TermEvent::FocusGained) => {
+                self.state.focused = true;
+                return None;
+            }
+            Event::TermEvent(TermEvent::FocusLost) => {
+                self.state.focused = false;
+                return None;
+            }
+            _ => {}
+        }
+
+        let notification: Option<SystemEffect<Request>> = self.notification(&event);
+        let mut effect: Option<SystemEffect<Request>> =
+            chain_effects(self.handle_event(event), notification);
+        // Insh quits once the operations that it was waiting for are done.
+        if self.state.exiting == Some(Exiting::Waiting)
+            && self.state.requests.operations().is_empty()
+        {
+            effect = self.state.exit();
+        }
+        if let Some(effect) = &effect {
+            self.state.last_effect = Some(describe(effect));
+        }
+        effect
+    }
+
+    fn render(&self, size: Size) -> Fabric {
+        let mut fabric: Fabric = self.state.modals.render(self.render_component(size), size);
+        self.state.toasts.render(&mut fabric, SystemTime::now());
+        if self.state.debug && size.rows >= 2 {
+            // The debug overlay covers the bottom half of the component.
+            let overlay_rows: usize = size.rows / 2;
+            fabric.truncate_bottom(size.rows - overlay_rows);
+            fabric = fabric.quilt_bottom(self.render_debug(Size::new(overlay_rows, size.columns)));
+        }
+        fabric
+    }
+
+    fn working_dir(&self) -> Option<PathBuf> {
+        self.state
+            .browser
+            .as_ref()
+            .map(|browser| browser.dir().to_path_buf())
+    }
+
+    fn handle_program_stderr(&mut self, stderr: String) -> Option<SystemEffect<Request>> {
+        self.state.perform(Action::ShowStderr { stderr })
+    }
+
+    fn handle_tick(&mut self) -> Option<SystemEffect<Request>> {
+        let timed_out: Vec<(Mode, Uuid, RequestKind)> = self.state.sweep();
+        let mut effects: Vec<SystemEffect<Request>> = timed_out
+            .into_iter()
+            .filter_map(|(mode, request, kind)| {
+                self.dispatch(mode, Input::TimedOut { request, kind })
+            })
+            .collect();
+        // The browser gets its stale files again on the ticks.
+        if matches!(self.state.mode, Mode::Browse) && self.state.browser.is_some() {
+            effects.extend(self.dispatch(Mode::Browse, Input::Browser(BrowserEvent::Tick)));
+        }
+        // The toasts are hidden after a while, so a frame is drawn even without input.
+        if self.state.toasts.tick(SystemTime::now()) {
+            effects.push(SystemEffect::Redraw);
+        }
+        merge_effects(effects)
+    }
+
+    fn handle_background_done(&mut self, done: BackgroundDone) -> Option<SystemEffect<Request>> {
+        let notification: Option<SystemEffect<Request>> = self.background_notification(&done.uuid);
+        let effect: Option<SystemEffect<Request>> = self.state.perform(Action::FinishBackground {
+            uuid: done.uuid,
+            status: done.status,
+            stdout: done.stdout,
+            stderr: done.stderr,
+        });
+        chain_effects(effect, notification)
+    }
+}
+
+impl Insh {
+    /// Return the desktop notification for an event if one should be shown (when a long running
+    /// operation like finding files, searching for a phrase, copying, moving, or deleting finishes
+    /// while the terminal is unfocused).
+    fn notification(&self, event: &Event<Response>) -> Option<SystemEffect<Request>> {
+        if self.state.focused || !self.state.config.general().notifications() {
+            return None;
+        }
+        match event {
+            Event::Response(response) if response.last() => {
+                let (body, urgency) = notification_body(response.params())?;
+                Some(SystemEffect::Notify {
+                    title: String::from("Insh"),
+                    body,
+                    urgency,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Return the desktop notification for a program that finished running in the background if
+    /// one should be shown (when the terminal is unfocused).
+    fn background_notification(&self, uuid: &Uuid) -> Option<SystemEffect<Request>> {
+        if self.state.focused || !self.state.config.general().notifications() {
+            return None;
+        }
+        let jobs = self.state.jobs.borrow();
+        let job: &Job = jobs.get(uuid)?;
+        Some(SystemEffect::Notify {
+            title: String::from("Insh"),
+            body: format!("{} finished.", job.name()),
+            urgency: Urgency::Normal,
+        })
+    }
+
+    /// Handle an event (other than toggling the debug overlay or a change of focus).
+    fn handle_event(&mut self, event: Event<Response>) -> Option<SystemEffect<Request>> {
         if let Event::TermEvent(TermEvent::KeyEvent(KeyEvent {
             key: Key::Char('x'),
             mods: KeyMods::CONTROL,
         })) = event
         {
-            return Some(SystemEffect::Exit);
+            return self.state.perform(Action::Quit);
         }
 
+        // Correlate what is logged while handling a response with its request.
+        #[cfg(feature = "logging")]
+        let _correlation = match &event {
+            Event::Response(response) => Some(correlate(*response.uuid())),
+            _ => None,
+        };
+
+        // The top modal captures the input (but what is under the modals is also resized).
+        let event: Event<Response> = match event {
+            Event::TermEvent(TermEvent::Resize(size)) => {
+                let _ = self.state.modals.handle(TermEvent::Resize(size));
+                Event::TermEvent(TermEvent::Resize(size))
+            }
+            Event::TermEvent(term_event) => match self.state.modals.handle(term_event) {
+                ModalHandling::Captured(effect) => return self.handle_modal_effect(effect),
+                ModalHandling::Passed(term_event) => Event::TermEvent(term_event),
+            },
+            event => event,
+        };
+
+        // A response goes to the component that sent the request (even if it isn't shown).
+        let mode: Mode = match &event {
+            Event::Response(response) => match self.state.route(response) {
+                Some(mode) => mode,
+                None => {
+                    #[cfg(feature = "logging")]
+                    log::debug!("There is no component for the response.");
+                    return None;
+                }
+            },
+            Event::TermEvent(_) => self.state.mode,
+        };
+
+        self.dispatch(mode, Input::Event(event))
+    }
+
+    /// Handle the input with the component of the mode.
+    fn dispatch(&mut self, mode: Mode, input: Input) -> Option<SystemEffect<Request>> {
         let mut action: Option<Action> = None;
 
-        match self.state.mode {
+        match mode {
             Mode::Browse => {
-                let event: BrowserEvent = match event {
-                    Event::TermEvent(term_event) => BrowserEvent::TermEvent(term_event),
-                    Event::Response(response) => BrowserEvent::Response(response),
+                let event: BrowserEvent = match input {
+                    Input::Event(Event::TermEvent(term_event)) => {
+                        BrowserEvent::TermEvent(term_event)
+                    }
+                    Input::Event(Event::Response(response)) => BrowserEvent::Response(response),
+                    Input::TimedOut { request, kind } => BrowserEvent::TimedOut { request, kind },
+                    Input::Browser(event) => event,
                 };
 
                 let browser = self.state.browser.as_mut().unwrap();
+                let target = Target::Browser(browser.id());
                 let browser_effect: Option<BrowserEffect> = browser.handle(event);
                 match browser_effect {
                     Some(BrowserEffect::OpenFileCreator { dir, file_type }) => {
@@ -116,42 +336,108 @@ impl Component<Props, Event<Response>, SystemEffect<Request>> for Insh {
                     Some(BrowserEffect::OpenFinder { dir }) => {
                         action = Some(Action::Find { dir });
                     }
+                    Some(BrowserEffect::OpenFlatList { dir }) => {
+                        action = Some(Action::ListAllFiles { dir });
+                    }
                     Some(BrowserEffect::OpenSearcher { dir }) => {
                         action = Some(Action::Search { dir });
                     }
+                    Some(BrowserEffect::OpenReader { path }) => {
+                        action = Some(Action::Read { path });
+                    }
                     Some(BrowserEffect::OpenVim(vim_args)) => {
-                        let program = Box::new(Vim::new(vim_args));
+                        action = Some(Action::OpenVim(vim_args));
+                    }
+                    Some(BrowserEffect::Choose { path }) => {
+                        action = Some(Action::Choose { path });
+                    }
+                    Some(BrowserEffect::OpenPager { path }) => {
+                        action = Some(Action::Page { path });
+                    }
+                    Some(BrowserEffect::OpenLogViewer { path }) => {
+                        action = Some(Action::ViewLog { path });
+                    }
+                    Some(BrowserEffect::OpenProcessList { dir }) => {
+                        action = Some(Action::ListProcesses { dir });
+                    }
+                    Some(BrowserEffect::OpenOpenFiles { dir }) => {
+                        action = Some(Action::ListOpenFiles { dir });
+                    }
+                    Some(BrowserEffect::OpenMounts) => {
+                        action = Some(Action::ListMounts);
+                    }
+                    Some(BrowserEffect::OpenPalette { dir, selection }) => {
+                        let context = PluginContext::new(dir, selection);
+                        action = Some(Action::OpenPalette { context });
+                    }
+                    Some(BrowserEffect::PreviewImage { path }) => {
+                        let program = Box::new(ImagePreview::new(path));
                         return Some(SystemEffect::RunProgram { program });
                     }
                     Some(BrowserEffect::RunBash { dir }) => {
                         let program = Box::new(Bash::new(dir));
                         return Some(SystemEffect::RunProgram { program });
                     }
+                    Some(BrowserEffect::Eject { device }) => {
+                        let program = Box::new(Eject::new(device));
+                        return Some(SystemEffect::RunProgram { program });
+                    }
+                    Some(BrowserEffect::Escalate { escalation }) => {
+                        action = Some(Action::Escalate { escalation });
+                    }
+                    Some(BrowserEffect::Mark { paths, moving }) => {
+                        action = Some(Action::Mark { paths, moving });
+                    }
+                    Some(BrowserEffect::Paste { dir }) => {
+                        action = Some(Action::Paste { dir });
+                    }
+                    Some(BrowserEffect::Toast { message, level }) => {
+                        action = Some(Action::Toast { message, level });
+                    }
+                    Some(BrowserEffect::OpenDeletePrompt(prompt)) => {
+                        self.state.modals.push(Box::new(DeletePrompt(prompt)));
+                    }
                     Some(BrowserEffect::Bell) => {
                         action = Some(Action::Bell);
                     }
                     Some(BrowserEffect::Request(request)) => {
-                        return Some(SystemEffect::Request(request));
+                        return self.state.send(target, request);
+                    }
+                    Some(BrowserEffect::Requests(requests)) => {
+                        return self.state.send_all(target, requests);
+                    }
+                    Some(BrowserEffect::List(request)) => {
+                        return self.state.send_replacing(target, request);
                     }
                     None => {}
                 }
             }
             Mode::FileCreator => {
-                let file_creator_event: FileCreatorEvent = match event {
-                    Event::TermEvent(term_event) => FileCreatorEvent::TermEvent(term_event),
-                    Event::Response(response) => FileCreatorEvent::Response(response),
+                let file_creator_event: FileCreatorEvent = match input {
+                    Input::Event(Event::TermEvent(term_event)) => {
+                        FileCreatorEvent::TermEvent(term_event)
+                    }
+                    Input::Event(Event::Response(response)) => FileCreatorEvent::Response(response),
+                    Input::TimedOut { .. } => return self.state.request_timed_out(),
+                    Input::Browser(_) => return None,
                 };
 
                 let file_creator = self.state.file_creator.as_mut().unwrap();
+                let target = Target::FileCreator(file_creator.id());
                 let file_creator_effect: Option<FileCreatorEffect> =
                     file_creator.handle(file_creator_event);
                 match file_creator_effect {
                     Some(FileCreatorEffect::Request(request)) => {
-                        return Some(SystemEffect::Request(request));
+                        return self.state.send(target, request);
                     }
                     Some(FileCreatorEffect::Browse { dir, file }) => {
+                        let path: &Path = file.as_deref().unwrap_or(&dir);
+                        run_hook(self.state.config.hooks(), HookEvent::FileCreated { path });
                         action = Some(Action::Browse { dir, file });
                     }
+                    Some(FileCreatorEffect::Escalate { escalation }) => {
+                        action = Some(Action::Escalate { escalation });
+                    }
                     Some(FileCreatorEffect::Bell) => {
                         action = Some(Action::Bell);
                     }
@@ -162,25 +448,32 @@ impl Component<Props, Event<Response>, SystemEffect<Request>> for Insh {
                 }
             }
             Mode::Finder => {
+                let event: Event<Response> = match input {
+                    Input::Event(event) => event,
+                    Input::TimedOut { .. } => return self.state.request_timed_out(),
+                    Input::Browser(_) => return None,
+                };
                 let finder = self.state.finder.as_mut().unwrap();
+                let target = Target::Finder(finder.id());
                 let finder_effect: Option<FinderEffect> = finder.handle(event);
                 match finder_effect {
-                    Some(FinderEffect::SendFindFilesRequest { uuid, dir, pattern }) => {
-                        let params: RequestParams = RequestParams::FindFiles(
-                            FindFilesRequestParams::builder()
-                                .dir(dir)
-                                .pattern(pattern)
-                                .build(),
-                        );
-                        let request: Request = Request::builder().uuid(uuid).params(params).build();
-                        return Some(SystemEffect::Request(request));
+                    Some(FinderEffect::SendFindFilesRequest {
+                        dir,
+                        pattern,
+                        respect_ignore,
+                    }) => {
+                        let request: Request =
+                            find_files_request(&self.state.config, dir, pattern, respect_ignore);
+                        return self.state.send(target, request);
+                    }
+                    Some(FinderEffect::CancelRequest) => {
+                        return self.state.cancel_target(target);
                     }
                     Some(FinderEffect::Browse { dir, file }) => {
                         action = Some(Action::Browse { dir, file });
                     }
                     Some(FinderEffect::OpenVim(vim_args)) => {
-                        let program = Box::new(Vim::new(vim_args));
-                        return Some(SystemEffect::RunProgram { program });
+                        action = Some(Action::OpenVim(vim_args));
                     }
                     Some(FinderEffect::Quit) => {
                         action = Some(Action::QuitFinder);
@@ -192,18 +485,23 @@ impl Component<Props, Event<Response>, SystemEffect<Request>> for Insh {
                 }
             }
             Mode::Searcher => {
-                let event = match event {
-                    Event::TermEvent(event) => event,
-                    Event::Response(_) => {
-                        #[cfg(feature = "logging")]
-                        log::warn!("Searcher doesn't handle responses yet.");
-                        return None;
-                    }
+                let event: Event<Response> = match input {
+                    Input::Event(event) => event,
+                    Input::TimedOut { .. } => return self.state.request_timed_out(),
+                    Input::Browser(_) => return None,
                 };
-
                 let searcher = self.state.searcher.as_mut().unwrap();
+                let target = Target::Searcher(searcher.id());
                 let searcher_effect: Option<SearcherEffect> = searcher.handle(event);
                 match searcher_effect {
+                    Some(SearcherEffect::SendSearchPhraseRequest { dir, phrase }) => {
+                        let request: Request =
+                            search_phrase_request(&self.state.config, dir, phrase);
+                        return self.state.send(target, request);
+                    }
+                    Some(SearcherEffect::CancelRequest) => {
+                        return self.state.cancel_target(target);
+                    }
                     Some(SearcherEffect::Goto { dir, file }) => {
                         action = Some(Action::Browse { dir, file });
                     }
@@ -211,8 +509,7 @@ impl Component<Props, Event<Response>, SystemEffect<Request>> for Insh {
                         action = Some(Action::QuitSearcher);
                     }
                     Some(SearcherEffect::OpenVim(vim_args)) => {
-                        let program = Box::new(Vim::new(vim_args));
-                        return Some(SystemEffect::RunProgram { program });
+                        action = Some(Action::OpenVim(vim_args));
                     }
                     Some(SearcherEffect::Bell) => {
                         action = Some(Action::Bell);
@@ -220,8 +517,171 @@ impl Component<Props, Event<Response>, SystemEffect<Request>> for Insh {
                     None => {}
                 }
             }
+            Mode::Reader => {
+                let event = match input {
+                    Input::Event(Event::TermEvent(event)) => event,
+                    Input::Event(Event::Response(_))
+                    | Input::TimedOut { .. }
+                    | Input::Browser(_) => {
+                        #[cfg(feature = "logging")]
+                        log::warn!("Reader doesn't handle responses.");
+                        return None;
+                    }
+                };
+
+                let reader = self.state.reader.as_mut().unwrap();
+                let reader_effect: Option<ReaderEffect> = reader.handle(event);
+                match reader_effect {
+                    Some(ReaderEffect::Quit) => {
+                        action = Some(Action::QuitReader);
+                    }
+                    Some(ReaderEffect::Bell) => {
+                        action = Some(Action::Bell);
+                    }
+                    None => {}
+                }
+            }
+            Mode::Pager => {
+                let event: PagerEvent = match input {
+                    Input::Event(Event::TermEvent(term_event)) => PagerEvent::TermEvent(term_event),
+                    Input::Event(Event::Response(response)) => PagerEvent::Response(response),
+                    Input::TimedOut { .. } => PagerEvent::TimedOut,
+                    Input::Browser(_) => return None,
+                };
+
+                let pager = self.state.pager.as_mut().unwrap();
+                let target = Target::Pager(pager.id());
+                let pager_effect: Option<PagerEffect> = pager.handle(event);
+                match pager_effect {
+                    Some(PagerEffect::Request(request)) => {
+                        return self.state.send(target, request);
+                    }
+                    Some(PagerEffect::Quit) => {
+                        action = Some(Action::QuitPager);
+                    }
+                    Some(PagerEffect::Bell) => {
+                        action = Some(Action::Bell);
+                    }
+                    None => {}
+                }
+            }
+            Mode::LogViewer => {
+                let event: LogViewerEvent = match input {
+                    Input::Event(Event::TermEvent(term_event)) => {
+                        LogViewerEvent::TermEvent(term_event)
+                    }
+                    Input::Event(Event::Response(response)) => LogViewerEvent::Response(response),
+                    Input::TimedOut { .. } => LogViewerEvent::TimedOut,
+                    Input::Browser(_) => return None,
+                };
+
+                let log_viewer = self.state.log_viewer.as_mut().unwrap();
+                let target = Target::LogViewer(log_viewer.id());
+                let log_viewer_effect: Option<LogViewerEffect> = log_viewer.handle(event);
+                match log_viewer_effect {
+                    Some(LogViewerEffect::Request(request)) => {
+                        return self.state.send(target, request);
+                    }
+                    Some(LogViewerEffect::Quit) => {
+                        action = Some(Action::QuitLogViewer);
+                    }
+                    Some(LogViewerEffect::Bell) => {
+                        action = Some(Action::Bell);
+                    }
+                    None => {}
+                }
+            }
+            Mode::ProcessList => {
+                let event: ProcessListEvent = match input {
+                    Input::Event(Event::TermEvent(term_event)) => {
+                        ProcessListEvent::TermEvent(term_event)
+                    }
+                    Input::Event(Event::Response(response)) => ProcessListEvent::Response(response),
+                    Input::TimedOut { .. } => return self.state.request_timed_out(),
+                    Input::Browser(_) => return None,
+                };
+
+                let process_list = self.state.process_list.as_mut().unwrap();
+                let target = Target::ProcessList(process_list.id());
+                let process_list_effect: Option<ProcessListEffect> = process_list.handle(event);
+                match process_list_effect {
+                    Some(ProcessListEffect::Request(request)) => {
+                        return self.state.send(target, request);
+                    }
+                    Some(ProcessListEffect::Quit) => {
+                        action = Some(Action::QuitProcessList);
+                    }
+                    Some(ProcessListEffect::Bell) => {
+                        action = Some(Action::Bell);
+                    }
+                    None => {}
+                }
+            }
+            Mode::OpenFiles => {
+                let event: OpenFilesEvent = match input {
+                    Input::Event(Event::TermEvent(term_event)) => {
+                        OpenFilesEvent::TermEvent(term_event)
+                    }
+                    Input::Event(Event::Response(response)) => OpenFilesEvent::Response(response),
+                    Input::TimedOut { .. } => return self.state.request_timed_out(),
+                    Input::Browser(_) => return None,
+                };
+
+                let open_files = self.state.open_files.as_mut().unwrap();
+                let target = Target::OpenFiles(open_files.id());
+                let open_files_effect: Option<OpenFilesEffect> = open_files.handle(event);
+                match open_files_effect {
+                    Some(OpenFilesEffect::Request(request)) => {
+                        return self.state.send(target, request);
+                    }
+                    Some(OpenFilesEffect::Quit) => {
+                        action = Some(Action::QuitOpenFiles);
+                    }
+                    Some(OpenFilesEffect::Bell) => {
+                        action = Some(Action::Bell);
+                    }
+                    None => {}
+                }
+            }
+            Mode::Mounts => {
+                let event: MountsEvent = match input {
+                    Input::Event(Event::TermEvent(term_event)) => {
+                        MountsEvent::TermEvent(term_event)
+                    }
+                    Input::Event(Event::Response(_))
+                    | Input::TimedOut { .. }
+                    | Input::Browser(_) => return None,
+                };
+
+                let mounts = self.state.mounts.as_mut().unwrap();
+                let mounts_effect: Option<MountsEffect> = mounts.handle(event);
+                match mounts_effect {
+                    Some(MountsEffect::Mount { mount }) => {
+                        let command: String =
+                            self.state.config.general().escalation_command().to_string();
+                        let program = Box::new(Mount::new(mount, command));
+                        return Some(SystemEffect::RunProgram { program });
+                    }
+                    Some(MountsEffect::Unmount { mount }) => {
+                        let command: String =
+                            self.state.config.general().escalation_command().to_string();
+                        let program = Box::new(Mount::unmount(mount, command));
+                        return Some(SystemEffect::RunProgram { program });
+                    }
+                    Some(MountsEffect::Browse { dir }) => {
+                        action = Some(Action::BrowseMount { dir });
+                    }
+                    Some(MountsEffect::Quit) => {
+                        action = Some(Action::QuitMounts);
+                    }
+                    Some(MountsEffect::Bell) => {
+                        action = Some(Action::Bell);
+                    }
+                    None => {}
+                }
+            }
             Mode::Nothing => {
-                return Some(SystemEffect::Exit);
+                return self.state.exit();
             }
         }
 
@@ -233,117 +693,594 @@ impl Component<Props, Event<Response>, SystemEffect<Request>> for Insh {
         None
     }
 
-    fn render(&self, size: Size) -> Fabric {
+    /// Handle the effect of the top modal.
+    fn handle_modal_effect(
+        &mut self,
+        effect: Option<ModalEffect>,
+    ) -> Option<SystemEffect<Request>> {
+        let action: Action = match effect {
+            Some(ModalEffect::Palette(PaletteEffect::Run { command, context })) => {
+                self.state.add_to_palette_history(command.name());
+                self.state.perform(Action::QuitPalette);
+                if command.is_builtin() {
+                    return match command.name() {
+                        "jobs" => self.state.perform(Action::OpenJobs),
+                        _ => self.state.perform(Action::OpenMessages),
+                    };
+                }
+                if command.background() {
+                    return self
+                        .state
+                        .perform(Action::RunInBackground { command, context });
+                }
+                let program = Box::new(Plugin::new(command, context));
+                return Some(SystemEffect::RunProgram { program });
+            }
+            Some(ModalEffect::Palette(PaletteEffect::Quit)) => Action::QuitPalette,
+            Some(ModalEffect::Palette(PaletteEffect::Bell)) => Action::Bell,
+            Some(ModalEffect::Messages(MessagesEffect::Quit)) => Action::QuitMessages,
+            Some(ModalEffect::Messages(MessagesEffect::Bell)) => Action::Bell,
+            Some(ModalEffect::Jobs(JobsEffect::Kill { uuid })) => Action::KillJob { uuid },
+            Some(ModalEffect::Jobs(JobsEffect::Quit)) => Action::QuitJobs,
+            Some(ModalEffect::Jobs(JobsEffect::Bell)) => Action::Bell,
+            Some(ModalEffect::ExitPrompt(PromptEffect::Choose { index })) => match index {
+                0 => Action::WaitToExit,
+                1 => Action::CancelOperations,
+                _ => Action::Exit,
+            },
+            Some(ModalEffect::ExitPrompt(PromptEffect::Cancel)) => Action::QuitExitPrompt,
+            Some(ModalEffect::ExitPrompt(PromptEffect::Bell)) => Action::Bell,
+            Some(ModalEffect::DeletePrompt(PromptEffect::Choose { index })) => {
+                self.state.modals.pop();
+                let event = BrowserEvent::ConfirmDelete { index };
+                return self.dispatch(Mode::Browse, Input::Browser(event));
+            }
+            Some(ModalEffect::DeletePrompt(PromptEffect::Cancel)) => {
+                self.state.modals.pop();
+                return self.dispatch(Mode::Browse, Input::Browser(BrowserEvent::CancelDelete));
+            }
+            Some(ModalEffect::DeletePrompt(PromptEffect::Bell)) => Action::Bell,
+            None => return None,
+        };
+        self.state.perform(action)
+    }
+
+    /// Render the debug overlay (the component tree with the key state of each component).
+    fn render_debug(&self, size: Size) -> Fabric {
+        let mut title = Yarn::from("Debug (<Ctrl>-\\ to close)");
+        title.resize(size.columns);
+        title.color(Color::InvertedText.into());
+        title.background(Color::InvertedBackground.into());
+
+        let mut yarns: Vec<Yarn> = vec![title];
+        for line in self.inspect().lines().into_iter().take(size.rows - 1) {
+            let mut yarn = Yarn::from(line);
+            yarn.resize(size.columns);
+            yarns.push(yarn);
+        }
+
+        let mut fabric = Fabric::from(yarns);
+        fabric.pad_bottom(size.rows);
+        fabric
+    }
+
+    /// Render the component for the current mode.
+    fn render_component(&self, size: Size) -> Fabric {
         match self.state.mode {
             Mode::Browse => self.state.browser.as_ref().unwrap().render(size),
             Mode::FileCreator => self.state.file_creator.as_ref().unwrap().render(size),
             Mode::Finder => self.state.finder.as_ref().unwrap().render(size),
             Mode::Searcher => self.state.searcher.as_ref().unwrap().render(size),
+            Mode::Reader => self.state.reader.as_ref().unwrap().render(size),
+            Mode::Pager => self.state.pager.as_ref().unwrap().render(size),
+            Mode::LogViewer => self.state.log_viewer.as_ref().unwrap().render(size),
+            Mode::ProcessList => self.state.process_list.as_ref().unwrap().render(size),
+            Mode::OpenFiles => self.state.open_files.as_ref().unwrap().render(size),
+            Mode::Mounts => self.state.mounts.as_ref().unwrap().render(size),
             Mode::Nothing => Fabric::new(size),
         }
     }
 }
 
+impl Inspect for Insh {
+    fn inspect(&self) -> Inspection {
+        let mut inspection = Inspection::new("Insh")
+            .field("mode", self.state.mode)
+            .field("read_only", self.state.config.general().read_only())
+            .field("focused", self.state.focused)
+            .field("modals", self.state.modals.len())
+            .field("toasts", self.state.toasts.history().len())
+            .field("running_jobs", self.state.jobs.borrow().running_count())
+            .field("pending_requests", self.state.requests.len())
+            .field("marked", &self.state.marked)
+            .field("exiting", self.state.exiting)
+            .field("last_effect", &self.state.last_effect);
+        if let Some(browser) = &self.state.browser {
+            inspection = inspection.child(browser.inspect());
+        }
+        if let Some(file_creator) = &self.state.file_creator {
+            inspection = inspection.child(file_creator.inspect());
+        }
+        if let Some(finder) = &self.state.finder {
+            inspection = inspection.child(finder.inspect());
+        }
+        if let Some(searcher) = &self.state.searcher {
+            inspection = inspection.child(searcher.inspect());
+        }
+        if let Some(reader) = &self.state.reader {
+            inspection = inspection.child(reader.inspect());
+        }
+        if let Some(pager) = &self.state.pager {
+            inspection = inspection.child(pager.inspect());
+        }
+        if let Some(log_viewer) = &self.state.log_viewer {
+            inspection = inspection.child(log_viewer.inspect());
+        }
+        if let Some(process_list) = &self.state.process_list {
+            inspection = inspection.child(process_list.inspect());
+        }
+        if let Some(open_files) = &self.state.open_files {
+            inspection = inspection.child(open_files.inspect());
+        }
+        if let Some(mounts) = &self.state.mounts {
+            inspection = inspection.child(mounts.inspect());
+        }
+        inspection
+    }
+}
+
+/// Return the body and the urgency of the desktop notification for the last response to a long
+/// running operation (or `None` if the response isn't for one).
+fn notification_body(params: &ResponseParams) -> Option<(String, Urgency)> {
+    let (doing, path, succeeded): (&str, &Path, bool) = match params {
+        ResponseParams::FindFiles(_) => {
+            return Some((String::from("Finding files finished."), Urgency::Normal))
+        }
+        ResponseParams::SearchPhrase(_) => {
+            return Some((
+                String::from("Searching for the phrase finished."),
+                Urgency::Normal,
+            ))
+        }
+        ResponseParams::DeleteFile(params) => ("Deleting", params.path(), params.result().is_ok()),
+        ResponseParams::CopyFile(params) => ("Copying", params.from(), params.result().is_ok()),
+        ResponseParams::MoveFile(params) => ("Moving", params.from(), params.result().is_ok()),
+        _ => return None,
+    };
+    let name = path
+        .file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy();
+    Some(match succeeded {
+        true => (format!("{} {} finished.", doing, name), Urgency::Normal),
+        false => (format!("{} {} failed.", doing, name), Urgency::Critical),
+    })
+}
+
+/// Return an effect which has both of the effects (in order) if there are both.
+fn chain_effects(
+    first: Option<SystemEffect<Request>>,
+    second: Option<SystemEffect<Request>>,
+) -> Option<SystemEffect<Request>> {
+    match (first, second) {
+        (Some(first), Some(second)) => Some(SystemEffect::Batch(vec![first, second])),
+        (first, second) => first.or(second),
+    }
+}
+
+/// Return a short description of an effect (for the debug overlay).
+fn describe(effect: &SystemEffect<Request>) -> String {
+    match effect {
+        SystemEffect::RunProgram { .. } => String::from("RunProgram"),
+        SystemEffect::SpawnBackground { uuid, .. } => format!("SpawnBackground {}", uuid),
+        SystemEffect::KillBackground { uuid } => format!("KillBackground {}", uuid),
+        SystemEffect::Request(request) => {
+            format!("Request {} {:?}", request.uuid(), request.params())
+        }
+        SystemEffect::Requests(requests) => format!("Requests ({})", requests.len()),
+        SystemEffect::Bell => String::from("Bell"),
+        SystemEffect::Redraw => String::from("Redraw"),
+        SystemEffect::Notify { title, .. } => format!("Notify {}", title),
+        SystemEffect::Exit => String::from("Exit"),
+        SystemEffect::Batch(effects) => format!("Batch ({})", effects.len()),
+    }
+}
+
+/// Return a request for finding the files under the directory whose names match the pattern.
+fn find_files_request(
+    config: &Config,
+    dir: PathBuf,
+    pattern: String,
+    respect_ignore: bool,
+) -> Request {
+    let params: RequestParams = RequestParams::FindFiles(
+        FindFilesRequestParams::builder()
+            .dir(dir)
+            .pattern(pattern)
+            .same_file_system(config.general().same_file_system())
+            .unicode_matching(config.general().unicode_matching())
+            .respect_ignore(respect_ignore)
+            .build(),
+    );
+    Request::builder().params(params).build()
+}
+
+/// Return a request for searching the contents of the files under the directory for the phrase.
+fn search_phrase_request(config: &Config, dir: PathBuf, phrase: String) -> Request {
+    let params: RequestParams = RequestParams::SearchPhrase(
+        SearchPhraseRequestParams::builder()
+            .dir(dir)
+            .phrase(phrase)
+            .same_file_system(config.general().same_file_system())
+            .unicode_matching(config.general().unicode_matching())
+            .build(),
+    );
+    Request::builder().params(params).build()
+}
+
+/// Return a request for pasting the marked entry at the path into the directory (copying or moving
+/// it there), or why it can't be pasted there.
+fn paste_request(path: &Path, moving: bool, dir: &Path) -> Result<Request, String> {
+    let name = match path.file_name() {
+        Some(name) => name,
+        None => return Err(format!("Can't paste {}", path.display())),
+    };
+    if path.parent() == Some(dir) {
+        return Err(format!("{} is already here", name.to_string_lossy()));
+    }
+
+    let params = TransferFileRequestParams::builder()
+        .from(path.to_path_buf())
+        .to(dir.join(name))
+        .build();
+    let params: RequestParams = match moving {
+        true => RequestParams::MoveFile(params),
+        false => RequestParams::CopyFile(params),
+    };
+    Ok(Request::builder().params(params).build())
+}
+
+/// Return the question which asks whether to quit while the operations (of the kinds) are running.
+fn exit_question(kinds: &[RequestKind]) -> String {
+    let doing: BTreeSet<&str> = kinds
+        .iter()
+        .map(|kind| match kind {
+            RequestKind::CreateFile => "creating",
+            RequestKind::WriteFile => "writing",
+            RequestKind::TouchFile => "touching",
+            RequestKind::DeleteFile => "deleting",
+            RequestKind::RenameFile => "renaming",
+            RequestKind::CopyFile => "copying",
+            RequestKind::MoveFile => "moving",
+            RequestKind::FindFiles => "finding files",
+            RequestKind::SearchPhrase => "searching",
+            _ => "requesting",
+        })
+        .collect();
+    let doing: Vec<&str> = doing.into_iter().collect();
+    match kinds.len() {
+        1 => format!("1 operation is still running ({}). Quit?", doing.join(", ")),
+        count => format!(
+            "{} operations are still running ({}). Quit?",
+            count,
+            doing.join(", ")
+        ),
+    }
+}
+
+/// Return a request which cancels the request with the uuid.
+fn cancel_request(uuid: Uuid) -> Request {
+    Request::builder()
+        .params(RequestParams::CancelRequest(
+            CancelRequestRequestParams::builder().request(uuid).build(),
+        ))
+        .build()
+}
+
 struct State {
     mode: Mode,
-    browser: Option<Browser>,
-    file_creator: Option<FileCreator>,
-    finder: Option<Finder>,
-    searcher: Option<Searcher>,
-    config: Config,
+    browser: Option<Identified<Browser>>,
+    file_creator: Option<Identified<FileCreator>>,
+    finder: Option<Identified<Finder>>,
+    searcher: Option<Identified<Searcher>>,
+    reader: Option<Reader>,
+    pager: Option<Identified<Pager>>,
+    log_viewer: Option<Identified<LogViewer>>,
+    process_list: Option<Identified<ProcessList>>,
+    open_files: Option<Identified<OpenFiles>>,
+    mounts: Option<Mounts>,
+    /// The requests that are pending (and the components that their responses go to).
+    requests: RequestRegistry<Target>,
+    /// The entries that were marked for copying or moving (which are pasted into another
+    /// directory).
+    marked: Option<Marked>,
+    /// Whether quitting is being asked about or waits for the running operations.
+    exiting: Option<Exiting>,
+    /// The overlays that capture the input (like the command palette).
+    modals: ModalStack<TermEvent, ModalEffect>,
+    /// The short messages that are shown in the corner (and their history).
+    toasts: Toasts,
+    config: Arc<Config>,
+    /// The script defining custom actions of the browser.
+    script: Option<Rc<Script>>,
+    /// The clipboard (shared with the components that copy and paste).
+    clipboard: Rc<RefCell<Clipboard>>,
+    /// The file to write the browsed directory to on exit.
+    cd_file: Option<PathBuf>,
+    /// What can be chosen (if Insh is being run as a file picker).
+    choose_mode: Option<ChooseMode>,
+    /// The file to write the chosen path to.
+    choose_out: Option<PathBuf>,
+    /// The commands that were run in the background (shared with the jobs list).
+    jobs: Rc<RefCell<crate::jobs::Jobs>>,
+    /// Whether the debug overlay is shown.
+    debug: bool,
+    /// Whether the terminal is focused (it is assumed to be until the terminal reports otherwise).
+    focused: bool,
+    /// A description of the last effect (for the debug overlay).
+    last_effect: Option<String>,
 }
 
 impl From<Props> for State {
     fn from(props: Props) -> Self {
-        let dir: PathBuf = props.dir().clone().unwrap_or_else(current_dir::current_dir);
+        // NOTE: The props are moved into the state (and the components) instead of being cloned.
+        let Props {
+            start,
+            dir,
+            pending_browser_request,
+            config,
+            script,
+            cd_file,
+            choose_mode,
+            choose_out,
+        } = props;
+        let dir: PathBuf = dir.unwrap_or_else(current_dir::current_dir);
         let size: Size = Size::from(terminal::size().unwrap());
+        let clipboard: Rc<RefCell<Clipboard>> = Rc::new(RefCell::new(Clipboard::new()));
 
-        let browser_props = BrowserProps::builder()
-            .dir(dir.clone())
-            .size(size)
-            .pending_request(*props.pending_browser_request())
-            .build();
-        let browser = Some(Browser::new(browser_props));
-        match props.start() {
-            Start::Browser => Self {
-                mode: Mode::Browse,
-                browser,
-                file_creator: None,
-                finder: None,
-                searcher: None,
-                config: props.config().clone(),
-            },
+        let (mode, finder, searcher): (
+            Mode,
+            Option<Identified<Finder>>,
+            Option<Identified<Searcher>>,
+        ) = match start {
+            Start::Browser => (Mode::Browse, None, None),
             Start::Finder { phrase } => {
                 let finder_props = FinderProps::builder()
-                    .dir(dir)
+                    .dir(dir.clone())
                     .size(size)
-                    .phrase(phrase.clone())
+                    .phrase(phrase)
+                    .scrolloff(config.general().scrolloff())
+                    .clipboard(clipboard.clone())
                     .build();
-                let finder = Some(Finder::new(finder_props));
-                Self {
-                    mode: Mode::Finder,
-                    browser,
-                    file_creator: None,
-                    finder,
-                    searcher: None,
-                    config: props.config().clone(),
-                }
+                (
+                    Mode::Finder,
+                    Some(Identified::new(Finder::new(finder_props))),
+                    None,
+                )
             }
             Start::Searcher { phrase } => {
-                let searcher_props =
-                    SearcherProps::new(props.config().clone(), dir, size, phrase.clone());
-                let searcher = Some(Searcher::new(searcher_props));
-                Self {
-                    mode: Mode::Searcher,
-                    browser,
-                    file_creator: None,
-                    finder: None,
-                    searcher,
-                    config: props.config().clone(),
-                }
+                let searcher_props = SearcherProps::new(
+                    config.clone(),
+                    clipboard.clone(),
+                    dir.clone(),
+                    size,
+                    phrase,
+                );
+                (
+                    Mode::Searcher,
+                    None,
+                    Some(Identified::new(Searcher::new(searcher_props))),
+                )
             }
-            Start::Nothing => Self {
-                mode: Mode::Nothing,
-                browser: None,
-                file_creator: None,
-                finder: None,
-                searcher: None,
-                config: props.config().clone(),
-            },
+            Start::Nothing => (Mode::Nothing, None, None),
+        };
+
+        // The browser is made last so that the directory can be moved into it.
+        let browser: Option<Identified<Browser>> = match mode {
+            Mode::Nothing => None,
+            _ => {
+                let browser_props = BrowserProps::builder()
+                    .dir(dir)
+                    .size(size)
+                    .config(config.clone())
+                    .script(script.clone())
+                    .clipboard(clipboard.clone())
+                    .choose_mode(choose_mode)
+                    .build();
+                Some(Identified::new(Browser::new(browser_props)))
+            }
+        };
+
+        // The first request for the files of the browser is sent before it is made.
+        let mut requests: RequestRegistry<Target> = RequestRegistry::default();
+        if let (Some(browser), Some(uuid)) = (&browser, pending_browser_request) {
+            let target = Target::Browser(browser.id());
+            requests.register(uuid, RequestKind::GetFiles, target, Instant::now());
+        }
+
+        Self {
+            mode,
+            browser,
+            file_creator: None,
+            finder,
+            searcher,
+            reader: None,
+            pager: None,
+            log_viewer: None,
+            process_list: None,
+            open_files: None,
+            mounts: None,
+            requests,
+            marked: None,
+            exiting: None,
+            modals: ModalStack::default(),
+            toasts: Toasts::default(),
+            config,
+            script,
+            clipboard,
+            cd_file,
+            choose_mode,
+            choose_out,
+            jobs: Rc::default(),
+            debug: false,
+            focused: true,
+            last_effect: None,
         }
     }
 }
 
 impl State {
-    fn browse(&mut self, dir: PathBuf, file: Option<PathBuf>) -> Option<SystemEffect<Request>> {
-        // Create a request for getting the files in the dir.
-        let request = Request::builder()
-            .params(RequestParams::GetFiles(
-                GetFilesRequestParams::builder().dir(dir.clone()).build(),
-            ))
-            .build();
-
-        self.mode = Mode::Browse;
-        let size: Size = Size::from(terminal::size().unwrap());
-        let browser_props = BrowserProps::builder()
-            .dir(dir)
-            .size(size)
-            .file(file)
-            .pending_request(Some(*request.uuid()))
-            .build();
-        self.browser = Some(Browser::new(browser_props));
-
+    /// Send a request whose responses are routed to the target.
+    fn send(&mut self, target: Target, request: Request) -> Option<SystemEffect<Request>> {
+        self.track(target, &request, target.replaces());
         Some(SystemEffect::Request(request))
     }
 
-    fn create_file(&mut self, dir: PathBuf, file_type: FileType) -> Option<SystemEffect<Request>> {
-        self.mode = Mode::FileCreator;
-        let file_creator_props = FileCreatorProps::builder()
-            .dir(dir)
-            .file_type(file_type)
-            .build();
-        self.file_creator = Some(FileCreator::new(file_creator_props));
-        None
+    /// Send a request whose responses are routed to the target in place of the requests of the
+    /// same kind that the target sent before.
+    fn send_replacing(
+        &mut self,
+        target: Target,
+        request: Request,
+    ) -> Option<SystemEffect<Request>> {
+        self.track(target, &request, true);
+        Some(SystemEffect::Request(request))
+    }
+
+    /// Send several requests whose responses are routed to the target.
+    fn send_all(
+        &mut self,
+        target: Target,
+        requests: Vec<Request>,
+    ) -> Option<SystemEffect<Request>> {
+        for request in &requests {
+            self.track(target, request, target.replaces());
+        }
+        Some(SystemEffect::Requests(requests))
+    }
+
+    /// Register the request that is sent (so that its responses are routed to the target), in
+    /// place of the requests of the same kind that the target sent before if it is replacing them.
+    fn track(&mut self, target: Target, request: &Request, replacing: bool) {
+        let now = Instant::now();
+        let kind: RequestKind = request.params().kind();
+        match replacing {
+            true => {
+                #[allow(unused_variables)]
+                let replaced: Vec<Uuid> = self.requests.replace(*request.uuid(), kind, target, now);
+                #[cfg(feature = "logging")]
+                if !replaced.is_empty() {
+                    log::debug!("The requests {:?} were replaced.", replaced);
+                }
+            }
+            false => {
+                self.requests.register(*request.uuid(), kind, target, now);
+            }
+        }
+    }
+
+    /// Cancel the requests that the target sent (dropping the responses to them that are already
+    /// on their way).
+    fn cancel_target(&mut self, target: Target) -> Option<SystemEffect<Request>> {
+        let requests: Vec<Request> = self
+            .requests
+            .remove_target(target)
+            .into_iter()
+            .map(cancel_request)
+            .collect();
+        match requests.is_empty() {
+            true => None,
+            false => Some(SystemEffect::Requests(requests)),
+        }
+    }
+
+    /// Forget the requests that timed out. Return the modes of the components that sent them
+    /// (which are told that they timed out) with the requests and their kinds.
+    fn sweep(&mut self) -> Vec<(Mode, Uuid, RequestKind)> {
+        let swept: Vec<(Uuid, RequestKind, Target)> = self.requests.sweep(Instant::now());
+        #[cfg(feature = "logging")]
+        if !swept.is_empty() {
+            log::warn!("The requests {:?} timed out.", swept);
+        }
+        swept
+            .into_iter()
+            .filter_map(|(uuid, kind, target)| Some((self.mode_of(target)?, uuid, kind)))
+            .collect()
+    }
+
+    /// Show that inshd did not respond to a request in time (for the components which don't wait
+    /// for their requests).
+    fn request_timed_out(&mut self) -> Option<SystemEffect<Request>> {
+        self.toast(
+            String::from("inshd did not respond in time"),
+            ToastLevel::Error,
+        )
+    }
+
+    /// Return the mode of the component that sent the request which the response is for (or
+    /// `None` if the response is dropped or the component is gone).
+    fn route(&mut self, response: &Response) -> Option<Mode> {
+        let target: Target = match self.requests.resolve(response, Instant::now()) {
+            Resolution::Route(target) => target,
+            #[allow(unused_variables)]
+            resolution => {
+                #[cfg(feature = "logging")]
+                log::debug!("Dropping the response: {:?}", resolution);
+                return None;
+            }
+        };
+        self.mode_of(target)
+    }
+
+    /// Return the mode of the target (or `None` if the component is gone).
+    fn mode_of(&self, target: Target) -> Option<Mode> {
+        let (mode, present): (Mode, bool) = match target {
+            Target::Browser(id) => (Mode::Browse, has_id(&self.browser, id)),
+            Target::FileCreator(id) => (Mode::FileCreator, has_id(&self.file_creator, id)),
+            Target::Finder(id) => (Mode::Finder, has_id(&self.finder, id)),
+            Target::Searcher(id) => (Mode::Searcher, has_id(&self.searcher, id)),
+            Target::Pager(id) => (Mode::Pager, has_id(&self.pager, id)),
+            Target::LogViewer(id) => (Mode::LogViewer, has_id(&self.log_viewer, id)),
+            Target::ProcessList(id) => (Mode::ProcessList, has_id(&self.process_list, id)),
+            Target::OpenFiles(id) => (Mode::OpenFiles, has_id(&self.open_files, id)),
+        };
+        present.then_some(mode)
+    }
+
+    fn browse(&mut self, dir: PathBuf, file: Option<PathBuf>) -> Option<SystemEffect<Request>> {
+        // Create a request for getting the files in the dir.
+        let request = Request::builder()
+            .params(RequestParams::GetFiles(
+                GetFilesRequestParams::builder().dir(dir.clone()).build(),
+            ))
+            .build();
+
+        self.mode = Mode::Browse;
+        let size: Size = Size::from(terminal::size().unwrap());
+        let browser_props = BrowserProps::builder()
+            .dir(dir)
+            .size(size)
+            .file(file)
+            .config(self.config.clone())
+            .script(self.script.clone())
+            .clipboard(self.clipboard.clone())
+            .choose_mode(self.choose_mode)
+            .build();
+        let browser = Identified::new(Browser::new(browser_props));
+        let target = Target::Browser(browser.id());
+        self.browser = Some(browser);
+
+        self.send(target, request)
+    }
+
+    fn create_file(&mut self, dir: PathBuf, file_type: FileType) -> Option<SystemEffect<Request>> {
+        self.mode = Mode::FileCreator;
+        let file_creator_props = FileCreatorProps::builder()
+            .dir(dir)
+            .file_type(file_type)
+            .build();
+        self.file_creator = Some(Identified::new(FileCreator::new(file_creator_props)));
+        None
     }
 
     fn find(&mut self, dir: PathBuf) -> Option<SystemEffect<Request>> {
@@ -354,17 +1291,328 @@ impl State {
             .dir(dir)
             .size(size)
             .phrase(phrase)
+            .scrolloff(self.config.general().scrolloff())
+            .clipboard(self.clipboard.clone())
             .build();
-        self.finder = Some(Finder::new(finder_props));
+        self.finder = Some(Identified::new(Finder::new(finder_props)));
         None
     }
 
+    /// Open the finder with a flat list of all of the files under the directory (skipping the
+    /// hidden and ignored files).
+    fn list_all_files(&mut self, dir: PathBuf) -> Option<SystemEffect<Request>> {
+        self.mode = Mode::Finder;
+        let size: Size = Size::from(terminal::size().unwrap());
+        let finder_props = FinderProps::builder()
+            .dir(dir.clone())
+            .size(size)
+            .phrase(None)
+            .scrolloff(self.config.general().scrolloff())
+            .clipboard(self.clipboard.clone())
+            .flat(true)
+            .finding(true)
+            .build();
+        let finder = Identified::new(Finder::new(finder_props));
+        let target = Target::Finder(finder.id());
+        self.finder = Some(finder);
+        let request: Request = find_files_request(&self.config, dir, String::new(), true);
+        self.send(target, request)
+    }
+
     fn search(&mut self, dir: PathBuf) -> Option<SystemEffect<Request>> {
         self.mode = Mode::Searcher;
         let size: Size = Size::from(terminal::size().unwrap());
         let phrase = None;
-        let searcher_props = SearcherProps::new(self.config.clone(), dir, size, phrase);
-        self.searcher = Some(Searcher::new(searcher_props));
+        let searcher_props = SearcherProps::new(
+            self.config.clone(),
+            self.clipboard.clone(),
+            dir,
+            size,
+            phrase,
+        );
+        self.searcher = Some(Identified::new(Searcher::new(searcher_props)));
+        None
+    }
+
+    fn read(&mut self, path: PathBuf) -> Option<SystemEffect<Request>> {
+        self.mode = Mode::Reader;
+        let size: Size = Size::from(terminal::size().unwrap());
+        let reader_props = ReaderProps::builder().path(path).size(size).build();
+        self.reader = Some(Reader::new(reader_props));
+        None
+    }
+
+    fn page(&mut self, path: PathBuf) -> Option<SystemEffect<Request>> {
+        let request: Request = read_chunk_request(&path, 0);
+
+        self.mode = Mode::Pager;
+        let size: Size = Size::from(terminal::size().unwrap());
+        let pager_props = PagerProps::builder()
+            .path(path)
+            .size(size)
+            .config(self.config.clone())
+            .build();
+        let pager = Identified::new(Pager::new(pager_props));
+        let target = Target::Pager(pager.id());
+        self.pager = Some(pager);
+
+        self.send(target, request)
+    }
+
+    fn quit_pager(&mut self) -> Option<SystemEffect<Request>> {
+        self.mode = Mode::Browse;
+        self.pager = None;
+        None
+    }
+
+    fn view_log(&mut self, path: PathBuf) -> Option<SystemEffect<Request>> {
+        let request: Request = read_log_request(&path, 0, None);
+
+        self.mode = Mode::LogViewer;
+        let size: Size = Size::from(terminal::size().unwrap());
+        let log_viewer_props = LogViewerProps::builder()
+            .path(path)
+            .size(size)
+            .config(self.config.clone())
+            .build();
+        let log_viewer = Identified::new(LogViewer::new(log_viewer_props));
+        let target = Target::LogViewer(log_viewer.id());
+        self.log_viewer = Some(log_viewer);
+
+        self.send(target, request)
+    }
+
+    fn quit_log_viewer(&mut self) -> Option<SystemEffect<Request>> {
+        self.mode = Mode::Browse;
+        self.log_viewer = None;
+        None
+    }
+
+    fn list_processes(&mut self, dir: PathBuf) -> Option<SystemEffect<Request>> {
+        let request: Request = get_processes_request(&dir);
+
+        self.mode = Mode::ProcessList;
+        let size: Size = Size::from(terminal::size().unwrap());
+        let process_list_props = ProcessListProps::builder().dir(dir).size(size).build();
+        let process_list = Identified::new(ProcessList::new(process_list_props));
+        let target = Target::ProcessList(process_list.id());
+        self.process_list = Some(process_list);
+
+        self.send(target, request)
+    }
+
+    fn quit_process_list(&mut self) -> Option<SystemEffect<Request>> {
+        self.mode = Mode::Browse;
+        self.process_list = None;
+        None
+    }
+
+    fn list_open_files(&mut self, dir: PathBuf) -> Option<SystemEffect<Request>> {
+        let request: Request = get_open_files_request(&dir);
+
+        self.mode = Mode::OpenFiles;
+        let size: Size = Size::from(terminal::size().unwrap());
+        let open_files_props = OpenFilesProps::builder().dir(dir).size(size).build();
+        let open_files = Identified::new(OpenFiles::new(open_files_props));
+        let target = Target::OpenFiles(open_files.id());
+        self.open_files = Some(open_files);
+
+        self.send(target, request)
+    }
+
+    fn quit_open_files(&mut self) -> Option<SystemEffect<Request>> {
+        self.mode = Mode::Browse;
+        self.open_files = None;
+        None
+    }
+
+    fn list_mounts(&mut self) -> Option<SystemEffect<Request>> {
+        self.mode = Mode::Mounts;
+        let size: Size = Size::from(terminal::size().unwrap());
+        let mounts_props = MountsProps::builder()
+            .mounts(self.config.mounts().to_vec())
+            .size(size)
+            .build();
+        self.mounts = Some(Mounts::new(mounts_props));
+        None
+    }
+
+    /// Browse the directory that a network location is mounted on.
+    fn browse_mount(&mut self, dir: PathBuf) -> Option<SystemEffect<Request>> {
+        self.mounts = None;
+        self.browse(dir, None)
+    }
+
+    fn quit_mounts(&mut self) -> Option<SystemEffect<Request>> {
+        self.mode = Mode::Browse;
+        self.mounts = None;
+        None
+    }
+
+    /// Open the command palette with the commands that the plugins advertise.
+    fn open_palette(&mut self, context: PluginContext) -> Option<SystemEffect<Request>> {
+        let size: Size = Palette::modal_size(Size::from(terminal::size().unwrap()));
+        let (plugin_commands, errors) = plugins::discover(&self.config.plugins());
+        for error in &errors {
+            self.toasts.push(error.clone(), ToastLevel::Error);
+        }
+        let mut commands: Vec<PluginCommand> = vec![
+            PluginCommand::builtin("messages", "List the messages that were shown"),
+            PluginCommand::builtin("jobs", "List the commands that were run in the background"),
+        ];
+        commands.extend(plugin_commands);
+        let mut data: Data = Data::read();
+        let history: Vec<String> = data.palette.history.iter().cloned().collect();
+        data.release();
+        let palette_props = PaletteProps::builder()
+            .commands(commands)
+            .errors(errors)
+            .history(history)
+            .context(context)
+            .size(size)
+            .build();
+        self.modals.push(Box::new(Palette::new(palette_props)));
+        None
+    }
+
+    fn add_to_palette_history(&self, name: &str) {
+        let mut data: Data = Data::read();
+        data.palette
+            .add_to_history(name, self.config.palette().history().length());
+        data.write();
+        data.release();
+    }
+
+    fn quit_palette(&mut self) -> Option<SystemEffect<Request>> {
+        self.modals.pop();
+        None
+    }
+
+    /// Open the list of the messages that were shown.
+    fn open_messages(&mut self) -> Option<SystemEffect<Request>> {
+        let size: Size = Messages::modal_size(Size::from(terminal::size().unwrap()));
+        let messages_props = MessagesProps::builder()
+            .toasts(self.toasts.history().iter().cloned().collect())
+            .size(size)
+            .build();
+        self.modals.push(Box::new(Messages::new(messages_props)));
+        None
+    }
+
+    fn quit_messages(&mut self) -> Option<SystemEffect<Request>> {
+        self.modals.pop();
+        None
+    }
+
+    fn open_jobs(&mut self) -> Option<SystemEffect<Request>> {
+        let size: Size = Jobs::modal_size(Size::from(terminal::size().unwrap()));
+        let jobs_props = JobsProps::builder()
+            .jobs(self.jobs.clone())
+            .size(size)
+            .build();
+        self.modals.push(Box::new(Jobs::new(jobs_props)));
+        None
+    }
+
+    fn quit_jobs(&mut self) -> Option<SystemEffect<Request>> {
+        self.modals.pop();
+        None
+    }
+
+    /// Kill a job that is running in the background.
+    fn kill_job(&mut self, uuid: Uuid) -> Option<SystemEffect<Request>> {
+        if !self.jobs.borrow_mut().kill(&uuid) {
+            return self.bell();
+        }
+        Some(SystemEffect::KillBackground { uuid })
+    }
+
+    /// Show the lines of the stderr of a program that exited as error toasts (so that they can
+    /// still be read with the messages command after the screen is restored).
+    fn show_stderr(&mut self, stderr: String) -> Option<SystemEffect<Request>> {
+        let lines = stderr
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.is_empty());
+        for line in lines {
+            self.toasts.push(line.to_string(), ToastLevel::Error);
+        }
+        self.bell()
+    }
+
+    /// Run the command of a plugin in the background.
+    fn run_in_background(
+        &mut self,
+        command: PluginCommand,
+        context: PluginContext,
+    ) -> Option<SystemEffect<Request>> {
+        let uuid = Uuid::new_v4();
+        let name: String = command.name().to_string();
+        self.toasts.push(
+            format!("Running {} in the background", name),
+            ToastLevel::Info,
+        );
+        self.jobs.borrow_mut().start(uuid, name, Instant::now());
+        let program = Box::new(Plugin::new(command, context));
+        Some(SystemEffect::SpawnBackground { uuid, program })
+    }
+
+    /// Record how a command that was running in the background finished and show whether it
+    /// succeeded (and the stderr of the command if it failed).
+    fn finish_background(
+        &mut self,
+        uuid: Uuid,
+        status: Result<ExitStatus, String>,
+        stdout: String,
+        stderr: String,
+    ) -> Option<SystemEffect<Request>> {
+        let status: JobStatus = match status {
+            Ok(status) => JobStatus::Exited(status),
+            Err(error) => JobStatus::Failed(error),
+        };
+        let mut jobs = self.jobs.borrow_mut();
+        let (name, killed): (String, bool) = match jobs.finish(
+            &uuid,
+            status.clone(),
+            stdout,
+            stderr.clone(),
+            Instant::now(),
+        ) {
+            Some(job) => (job.name().to_string(), job.killed()),
+            None => (String::from("A command"), false),
+        };
+        drop(jobs);
+        match status {
+            JobStatus::Exited(_) if killed => {
+                self.toast(format!("{} was killed", name), ToastLevel::Info)
+            }
+            JobStatus::Exited(status) if status.success() => {
+                self.toast(format!("{} finished", name), ToastLevel::Info)
+            }
+            JobStatus::Exited(status) => {
+                self.show_stderr(stderr);
+                self.toast(format!("{} failed ({})", name, status), ToastLevel::Error)
+            }
+            JobStatus::Failed(error) => self.toast(
+                format!("{} failed to run: {}", name, error),
+                ToastLevel::Error,
+            ),
+            JobStatus::Running => None,
+        }
+    }
+
+    /// Show a short message in the corner (and make the bell sound if it is an error).
+    fn toast(&mut self, message: String, level: ToastLevel) -> Option<SystemEffect<Request>> {
+        self.toasts.push(message, level);
+        match level {
+            ToastLevel::Error => self.bell(),
+            ToastLevel::Info => None,
+        }
+    }
+
+    fn quit_reader(&mut self) -> Option<SystemEffect<Request>> {
+        self.mode = Mode::Browse;
+        self.reader = None;
         None
     }
 
@@ -378,6 +1626,178 @@ impl State {
         None
     }
 
+    /// Record that the file is opened and return the effect for opening it in vim (or in the viewer
+    /// if the file is too large or is binary).
+    fn open_vim(&mut self, vim_args: VimArgs) -> Option<SystemEffect<Request>> {
+        let program = open(vim_args, &self.config);
+        Some(SystemEffect::RunProgram { program })
+    }
+
+    /// Return the effect for retrying an operation with the escalation command.
+    fn escalate(&self, escalation: Escalation) -> Option<SystemEffect<Request>> {
+        let command: String = self.config.general().escalation_command().to_string();
+        let program = Box::new(Escalated::new(command, escalation));
+        Some(SystemEffect::RunProgram { program })
+    }
+
+    /// Mark the entries for copying or moving them (once they are pasted).
+    fn mark(&mut self, paths: Vec<PathBuf>, moving: bool) -> Option<SystemEffect<Request>> {
+        let (name, it): (String, &str) = match paths.as_slice() {
+            [path] => (
+                path.file_name()
+                    .unwrap_or(path.as_os_str())
+                    .to_string_lossy()
+                    .to_string(),
+                "it",
+            ),
+            paths => (format!("{} entries", paths.len()), "them"),
+        };
+        let verb: &str = match moving {
+            true => "Cut",
+            false => "Copied",
+        };
+        self.toasts.push(
+            format!(
+                "{} {} (press V in another directory to paste {})",
+                verb, name, it
+            ),
+            ToastLevel::Info,
+        );
+        self.marked = Some(Marked { paths, moving });
+        None
+    }
+
+    /// Paste the marked entries into the directory (the entries are only moved once).
+    fn paste(&mut self, dir: PathBuf) -> Option<SystemEffect<Request>> {
+        let marked: &Marked = match &self.marked {
+            Some(marked) => marked,
+            None => {
+                self.toasts
+                    .push(String::from("Nothing to paste"), ToastLevel::Info);
+                return self.bell();
+            }
+        };
+        let mut requests: Vec<Request> = match marked
+            .paths
+            .iter()
+            .map(|path| paste_request(path, marked.moving, &dir))
+            .collect()
+        {
+            Ok(requests) => requests,
+            Err(message) => {
+                self.toasts.push(message, ToastLevel::Info);
+                return self.bell();
+            }
+        };
+        if marked.moving {
+            self.marked = None;
+        }
+
+        let target = Target::Browser(self.browser.as_ref()?.id());
+        match requests.len() {
+            1 => self.send(target, requests.remove(0)),
+            _ => self.send_all(target, requests),
+        }
+    }
+
+    /// Quit, or ask whether to wait for, cancel, or abandon the operations that are still running
+    /// (quitting anyway if it is asked again).
+    fn quit(&mut self) -> Option<SystemEffect<Request>> {
+        let kinds: Vec<RequestKind> = self
+            .requests
+            .operations()
+            .into_iter()
+            .map(|(_, kind)| kind)
+            .collect();
+        if kinds.is_empty() || self.exiting == Some(Exiting::Prompting) {
+            return self.exit();
+        }
+
+        let props = PromptProps::builder()
+            .question(exit_question(&kinds))
+            .choices(vec![
+                Choice::new("Wait", 'w'),
+                Choice::new("Cancel them", 'c'),
+                Choice::new("Force quit", 'f'),
+            ])
+            .danger(true)
+            .build();
+        self.modals.push(Box::new(Prompt::new(props)));
+        self.exiting = Some(Exiting::Prompting);
+        None
+    }
+
+    fn quit_exit_prompt(&mut self) -> Option<SystemEffect<Request>> {
+        self.modals.pop();
+        self.exiting = None;
+        None
+    }
+
+    /// Quit once the operations that are running are done.
+    fn wait_to_exit(&mut self) -> Option<SystemEffect<Request>> {
+        self.modals.pop();
+        self.exiting = Some(Exiting::Waiting);
+        self.toast(
+            String::from("Insh will quit once the operations are done"),
+            ToastLevel::Info,
+        )
+    }
+
+    /// Cancel the operations that are running (and keep running).
+    fn cancel_operations(&mut self) -> Option<SystemEffect<Request>> {
+        self.quit_exit_prompt();
+        let uuids: Vec<Uuid> = self
+            .requests
+            .operations()
+            .into_iter()
+            .map(|(uuid, _)| uuid)
+            .collect();
+        for uuid in &uuids {
+            self.requests.remove(uuid);
+        }
+        let message: String = match uuids.len() {
+            1 => String::from("Cancelled 1 operation"),
+            count => format!("Cancelled {} operations", count),
+        };
+        self.toast(message, ToastLevel::Info);
+        Some(SystemEffect::Requests(
+            uuids.into_iter().map(cancel_request).collect(),
+        ))
+    }
+
+    /// Write the chosen path to the choose out file and return the effect for exiting.
+    fn choose(&self, path: PathBuf) -> Option<SystemEffect<Request>> {
+        if let Some(choose_out) = &self.choose_out {
+            let mut bytes: Vec<u8> = path.as_os_str().as_bytes().to_vec();
+            bytes.push(b'\n');
+            #[allow(unused_variables)]
+            if let Err(error) = fs::write(choose_out, bytes) {
+                #[cfg(feature = "logging")]
+                log::error!(
+                    "Failed to write the choose out file {:?}: {}",
+                    choose_out,
+                    error
+                );
+            }
+        }
+
+        self.exit()
+    }
+
+    /// Write the browsed directory to the cd file (if there is one) and return the effect for
+    /// exiting.
+    fn exit(&self) -> Option<SystemEffect<Request>> {
+        if let (Some(cd_file), Some(browser)) = (&self.cd_file, &self.browser) {
+            #[allow(unused_variables)]
+            if let Err(error) = fs::write(cd_file, browser.dir().as_os_str().as_bytes()) {
+                #[cfg(feature = "logging")]
+                log::error!("Failed to write the cd file {:?}: {}", cd_file, error);
+            }
+        }
+
+        Some(SystemEffect::Exit)
+    }
+
     /// If the bell sound is configured to be made, then return the effect for making the bell
     /// sound.
     fn bell(&self) -> Option<SystemEffect<Request>> {
@@ -394,30 +1814,607 @@ impl Stateful<Action, SystemEffect<Request>> for State {
             Action::Browse { dir, file } => self.browse(dir, file),
             Action::CreateFile { dir, file_type } => self.create_file(dir, file_type),
             Action::Find { dir } => self.find(dir),
+            Action::ListAllFiles { dir } => self.list_all_files(dir),
             Action::Search { dir } => self.search(dir),
+            Action::Read { path } => self.read(path),
             Action::QuitFinder => self.quit_finder(),
             Action::QuitSearcher => self.quit_searcher(),
+            Action::Page { path } => self.page(path),
+            Action::QuitReader => self.quit_reader(),
+            Action::QuitPager => self.quit_pager(),
+            Action::ViewLog { path } => self.view_log(path),
+            Action::QuitLogViewer => self.quit_log_viewer(),
+            Action::ListProcesses { dir } => self.list_processes(dir),
+            Action::QuitProcessList => self.quit_process_list(),
+            Action::ListOpenFiles { dir } => self.list_open_files(dir),
+            Action::QuitOpenFiles => self.quit_open_files(),
+            Action::ListMounts => self.list_mounts(),
+            Action::BrowseMount { dir } => self.browse_mount(dir),
+            Action::QuitMounts => self.quit_mounts(),
+            Action::OpenPalette { context } => self.open_palette(context),
+            Action::QuitPalette => self.quit_palette(),
+            Action::OpenMessages => self.open_messages(),
+            Action::QuitMessages => self.quit_messages(),
+            Action::Toast { message, level } => self.toast(message, level),
+            Action::ShowStderr { stderr } => self.show_stderr(stderr),
+            Action::RunInBackground { command, context } => {
+                self.run_in_background(command, context)
+            }
+            Action::FinishBackground {
+                uuid,
+                status,
+                stdout,
+                stderr,
+            } => self.finish_background(uuid, status, stdout, stderr),
+            Action::OpenJobs => self.open_jobs(),
+            Action::QuitJobs => self.quit_jobs(),
+            Action::KillJob { uuid } => self.kill_job(uuid),
+            Action::OpenVim(vim_args) => self.open_vim(vim_args),
+            Action::Escalate { escalation } => self.escalate(escalation),
+            Action::Mark { paths, moving } => self.mark(paths, moving),
+            Action::Paste { dir } => self.paste(dir),
+            Action::Choose { path } => self.choose(path),
+            Action::Quit => self.quit(),
+            Action::QuitExitPrompt => self.quit_exit_prompt(),
+            Action::WaitToExit => self.wait_to_exit(),
+            Action::CancelOperations => self.cancel_operations(),
+            Action::Exit => self.exit(),
             Action::Bell => self.bell(),
         }
     }
 }
 
-#[derive(Default)]
+#[derive(Debug, Default, Clone, Copy)]
 enum Mode {
     #[default]
     Browse,
     FileCreator,
     Finder,
     Searcher,
+    Reader,
+    Pager,
+    LogViewer,
+    ProcessList,
+    OpenFiles,
+    Mounts,
     Nothing,
 }
 
+/// The component that sent a request (which the responses to the request are routed to).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Target {
+    Browser(ComponentId<Browser>),
+    FileCreator(ComponentId<FileCreator>),
+    Finder(ComponentId<Finder>),
+    Searcher(ComponentId<Searcher>),
+    Pager(ComponentId<Pager>),
+    LogViewer(ComponentId<LogViewer>),
+    ProcessList(ComponentId<ProcessList>),
+    OpenFiles(ComponentId<OpenFiles>),
+}
+
+impl Target {
+    /// Return whether a request of the target replaces its pending requests of the same kind (the
+    /// browser sends several requests of the same kind at once, like for the directories of a
+    /// tree, so only its requests for the files of its directory replace the ones before).
+    fn replaces(&self) -> bool {
+        !matches!(self, Self::Browser(_))
+    }
+}
+
+/// What the component of a mode handles.
+enum Input {
+    Event(Event<Response>),
+    /// A request that the component sent timed out.
+    TimedOut {
+        request: Uuid,
+        kind: RequestKind,
+    },
+    /// An event that only the browser handles (like the ticks of the app).
+    Browser(BrowserEvent),
+}
+
+/// Return the effects as one effect (the requests are sent together, and otherwise only the first
+/// of the other effects is kept).
+fn merge_effects(effects: Vec<SystemEffect<Request>>) -> Option<SystemEffect<Request>> {
+    let mut requests: Vec<Request> = Vec::new();
+    let mut other: Option<SystemEffect<Request>> = None;
+    for effect in effects {
+        match effect {
+            SystemEffect::Request(request) => requests.push(request),
+            SystemEffect::Requests(more) => requests.extend(more),
+            effect => {
+                other.get_or_insert(effect);
+            }
+        }
+    }
+    match requests.is_empty() {
+        true => other,
+        false => Some(SystemEffect::Requests(requests)),
+    }
+}
+
+/// The entries that were marked for copying or moving.
+#[derive(Debug)]
+struct Marked {
+    paths: Vec<PathBuf>,
+    /// Whether the entries are moved (rather than copied) when they are pasted.
+    moving: bool,
+}
+
+/// What happens to quitting while operations are running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Exiting {
+    /// Whether to wait for the operations, cancel them, or quit anyway is being asked.
+    Prompting,
+    /// Insh quits once the operations are done.
+    Waiting,
+}
+
+/// The effect of a modal.
+enum ModalEffect {
+    Palette(PaletteEffect),
+    Messages(MessagesEffect),
+    Jobs(JobsEffect),
+    ExitPrompt(PromptEffect),
+    DeletePrompt(PromptEffect),
+}
+
+impl Modal<TermEvent, ModalEffect> for Prompt {
+    fn handle(&mut self, event: TermEvent) -> Option<ModalEffect> {
+        Component::handle(self, PromptEvent::TermEvent(event)).map(ModalEffect::ExitPrompt)
+    }
+
+    fn render(&self, size: Size) -> Fabric {
+        Component::render(self, size)
+    }
+
+    fn size(&self, screen: Size) -> Size {
+        Size::new(screen.rows.min(1), screen.columns)
+    }
+}
+
+/// The prompt for deleting the entries of the browser.
+struct DeletePrompt(Prompt);
+
+impl Modal<TermEvent, ModalEffect> for DeletePrompt {
+    fn handle(&mut self, event: TermEvent) -> Option<ModalEffect> {
+        Component::handle(&mut self.0, PromptEvent::TermEvent(event)).map(ModalEffect::DeletePrompt)
+    }
+
+    fn render(&self, size: Size) -> Fabric {
+        Component::render(&self.0, size)
+    }
+
+    fn size(&self, screen: Size) -> Size {
+        Size::new(screen.rows.min(1), screen.columns)
+    }
+}
+
+impl Modal<TermEvent, ModalEffect> for Palette {
+    fn handle(&mut self, event: TermEvent) -> Option<ModalEffect> {
+        let event: TermEvent = match event {
+            TermEvent::Resize(size) => TermEvent::Resize(Palette::modal_size(size)),
+            event => event,
+        };
+        Component::handle(self, PaletteEvent::TermEvent(event)).map(ModalEffect::Palette)
+    }
+
+    fn render(&self, size: Size) -> Fabric {
+        Component::render(self, size)
+    }
+
+    fn size(&self, screen: Size) -> Size {
+        Palette::modal_size(screen)
+    }
+}
+
+impl Modal<TermEvent, ModalEffect> for Jobs {
+    fn handle(&mut self, event: TermEvent) -> Option<ModalEffect> {
+        let event: TermEvent = match event {
+            TermEvent::Resize(size) => TermEvent::Resize(Jobs::modal_size(size)),
+            event => event,
+        };
+        Component::handle(self, JobsEvent::TermEvent(event)).map(ModalEffect::Jobs)
+    }
+
+    fn render(&self, size: Size) -> Fabric {
+        Component::render(self, size)
+    }
+
+    fn size(&self, screen: Size) -> Size {
+        Jobs::modal_size(screen)
+    }
+}
+
+impl Modal<TermEvent, ModalEffect> for Messages {
+    fn handle(&mut self, event: TermEvent) -> Option<ModalEffect> {
+        let event: TermEvent = match event {
+            TermEvent::Resize(size) => TermEvent::Resize(Messages::modal_size(size)),
+            event => event,
+        };
+        Component::handle(self, MessagesEvent::TermEvent(event)).map(ModalEffect::Messages)
+    }
+
+    fn render(&self, size: Size) -> Fabric {
+        Component::render(self, size)
+    }
+
+    fn size(&self, screen: Size) -> Size {
+        Messages::modal_size(screen)
+    }
+}
+
 enum Action {
-    Browse { dir: PathBuf, file: Option<PathBuf> },
-    CreateFile { dir: PathBuf, file_type: FileType },
-    Find { dir: PathBuf },
-    Search { dir: PathBuf },
+    Browse {
+        dir: PathBuf,
+        file: Option<PathBuf>,
+    },
+    CreateFile {
+        dir: PathBuf,
+        file_type: FileType,
+    },
+    Find {
+        dir: PathBuf,
+    },
+    ListAllFiles {
+        dir: PathBuf,
+    },
+    Search {
+        dir: PathBuf,
+    },
+    Read {
+        path: PathBuf,
+    },
+    Page {
+        path: PathBuf,
+    },
+    ViewLog {
+        path: PathBuf,
+    },
+    ListProcesses {
+        dir: PathBuf,
+    },
+    ListOpenFiles {
+        dir: PathBuf,
+    },
+    ListMounts,
+    BrowseMount {
+        dir: PathBuf,
+    },
+    OpenPalette {
+        context: PluginContext,
+    },
+    OpenVim(VimArgs),
+    Escalate {
+        escalation: Escalation,
+    },
+    Mark {
+        paths: Vec<PathBuf>,
+        moving: bool,
+    },
+    Paste {
+        dir: PathBuf,
+    },
+    Choose {
+        path: PathBuf,
+    },
+    Toast {
+        message: String,
+        level: ToastLevel,
+    },
+    ShowStderr {
+        stderr: String,
+    },
+    RunInBackground {
+        command: PluginCommand,
+        context: PluginContext,
+    },
+    FinishBackground {
+        uuid: Uuid,
+        status: Result<ExitStatus, String>,
+        stdout: String,
+        stderr: String,
+    },
+    OpenJobs,
+    QuitJobs,
+    KillJob {
+        uuid: Uuid,
+    },
     Bell,
     QuitFinder,
     QuitSearcher,
+    QuitReader,
+    QuitPager,
+    QuitLogViewer,
+    QuitProcessList,
+    QuitOpenFiles,
+    QuitMounts,
+    QuitPalette,
+    OpenMessages,
+    QuitMessages,
+    Quit,
+    QuitExitPrompt,
+    WaitToExit,
+    CancelOperations,
+    Exit,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::requester::send_over_socket;
+    use insh_api::{
+        DeleteFileResponseParams, TouchFileResponseParams, TransferFileError,
+        TransferFileResponseParams, TransferFileResult, TransferProgress,
+    };
+
+    use test_case::test_case;
+
+    /// Return the key event of pressing a key (where `\r` is enter and `\x1b` is escape).
+    fn key_event(key: char) -> KeyEvent {
+        match key {
+            '\r' => KeyEvent {
+                key: Key::CarriageReturn,
+                mods: KeyMods::NONE,
+            },
+            '\x1b' => KeyEvent {
+                key: Key::Escape,
+                mods: KeyMods::NONE,
+            },
+            key => KeyEvent {
+                key: Key::Char(key),
+                mods: KeyMods::NONE,
+            },
+        }
+    }
+
+    /// Press the keys in a finder of the directory `/r` and return the requests that the effects
+    /// of the finder are mapped to (the requests for the hits are registered like they are for
+    /// the finder, and are sent over a socket) described like `FindFiles /r foo`.
+    fn finder_requests(keys: &str) -> Vec<String> {
+        let config = Config::default();
+        let props = FinderProps::builder()
+            .dir("/r")
+            .size(Size::new(10, 80))
+            .phrase(None)
+            .clipboard(Rc::new(RefCell::new(Clipboard::new())))
+            .build();
+        let mut finder = Finder::new(props);
+        let mut registry: RequestRegistry<()> = RequestRegistry::default();
+
+        let requests: Vec<Request> = keys
+            .chars()
+            .flat_map(|key| {
+                match finder.handle(Event::TermEvent(TermEvent::KeyEvent(key_event(key)))) {
+                    Some(FinderEffect::SendFindFilesRequest {
+                        dir,
+                        pattern,
+                        respect_ignore,
+                    }) => {
+                        let request: Request =
+                            find_files_request(&config, dir, pattern, respect_ignore);
+                        let kind: RequestKind = request.params().kind();
+                        registry.replace(*request.uuid(), kind, (), Instant::now());
+                        vec![request]
+                    }
+                    Some(FinderEffect::CancelRequest) => registry
+                        .remove_target(())
+                        .into_iter()
+                        .map(cancel_request)
+                        .collect(),
+                    _ => Vec::new(),
+                }
+            })
+            .collect();
+
+        let requests: Vec<Request> = send_over_socket(requests);
+        requests
+            .iter()
+            .map(|request| match request.params() {
+                RequestParams::FindFiles(params) => {
+                    format!("FindFiles {} {}", params.dir().display(), params.pattern())
+                }
+                RequestParams::CancelRequest(params) => {
+                    let canceled: bool =
+                        requests.first().map(Request::uuid) == Some(params.request());
+                    format!("CancelRequest of the first request: {}", canceled)
+                }
+                params => format!("{:?}", params),
+            })
+            .collect()
+    }
+
+    /// Type the phrase and press the keys in a searcher of the directory `/r` (which stores no
+    /// history) and return the requests that the effects of the searcher are mapped to (like for
+    /// the finder) described like `SearchPhrase /r foo`.
+    fn searcher_requests(keys: &str) -> Vec<String> {
+        let config: Config =
+            serde_yaml::from_str("searcher:\n  history:\n    length: 0\n").unwrap();
+        let props = SearcherProps::new(
+            Arc::new(config.clone()),
+            Rc::new(RefCell::new(Clipboard::new())),
+            PathBuf::from("/r"),
+            Size::new(10, 80),
+            None,
+        );
+        let mut searcher = Searcher::new(props);
+        let mut registry: RequestRegistry<()> = RequestRegistry::default();
+
+        let requests: Vec<Request> = keys
+            .chars()
+            .flat_map(|key| {
+                match searcher.handle(Event::TermEvent(TermEvent::KeyEvent(key_event(key)))) {
+                    Some(SearcherEffect::SendSearchPhraseRequest { dir, phrase }) => {
+                        let request: Request = search_phrase_request(&config, dir, phrase);
+                        let kind: RequestKind = request.params().kind();
+                        registry.replace(*request.uuid(), kind, (), Instant::now());
+                        vec![request]
+                    }
+                    Some(SearcherEffect::CancelRequest) => registry
+                        .remove_target(())
+                        .into_iter()
+                        .map(cancel_request)
+                        .collect(),
+                    _ => Vec::new(),
+                }
+            })
+            .collect();
+
+        let requests: Vec<Request> = send_over_socket(requests);
+        requests
+            .iter()
+            .map(|request| match request.params() {
+                RequestParams::SearchPhrase(params) => {
+                    format!(
+                        "SearchPhrase {} {}",
+                        params.dir().display(),
+                        params.phrase()
+                    )
+                }
+                RequestParams::CancelRequest(params) => {
+                    let canceled: bool =
+                        requests.first().map(Request::uuid) == Some(params.request());
+                    format!("CancelRequest of the first request: {}", canceled)
+                }
+                params => format!("{:?}", params),
+            })
+            .collect()
+    }
+
+    #[test_case("foo\r", &["SearchPhrase /r foo"]; "search")]
+    #[test_case("foo\r\x1b", &["SearchPhrase /r foo", "CancelRequest of the first request: true"]; "cancel")]
+    #[test_case("foo", &[]; "typing")]
+    fn test_searcher_requests(keys: &str, expected: &[&str]) {
+        assert_eq!(searcher_requests(keys), expected);
+    }
+
+    #[test_case("foo\r", &["FindFiles /r foo"]; "find")]
+    #[test_case("foo\r\x1b", &["FindFiles /r foo", "CancelRequest of the first request: true"]; "cancel")]
+    #[test_case("foo", &[]; "typing")]
+    fn test_finder_requests(keys: &str, expected: &[&str]) {
+        assert_eq!(finder_requests(keys), expected);
+    }
+
+    #[test_case("/a/b", false, "/c", Ok("CopyFile /a/b /c/b"); "copy")]
+    #[test_case("/a/b", true, "/c", Ok("MoveFile /a/b /c/b"); "cut")]
+    #[test_case("/a/b", false, "/a", Err("b is already here"); "same directory")]
+    fn test_paste_request(path: &str, moving: bool, dir: &str, expected: Result<&str, &str>) {
+        let described: Result<String, String> =
+            paste_request(Path::new(path), moving, Path::new(dir)).map(|request| {
+                match request.params() {
+                    RequestParams::CopyFile(params) => format!(
+                        "CopyFile {} {}",
+                        params.from().display(),
+                        params.to().display()
+                    ),
+                    RequestParams::MoveFile(params) => format!(
+                        "MoveFile {} {}",
+                        params.from().display(),
+                        params.to().display()
+                    ),
+                    params => format!("{:?}", params),
+                }
+            });
+        assert_eq!(
+            described,
+            expected.map(str::to_string).map_err(str::to_string)
+        );
+    }
+
+    #[test]
+    fn test_search_phrase_request() {
+        let request: Request =
+            search_phrase_request(&Config::default(), "/r".into(), "foo".to_string());
+        let uuid: Uuid = *request.uuid();
+        let requests: Vec<Request> = send_over_socket(vec![request]);
+
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].uuid(), &uuid);
+        match requests[0].params() {
+            RequestParams::SearchPhrase(params) => {
+                assert_eq!(params.dir(), Path::new("/r"));
+                assert_eq!(params.phrase(), "foo");
+            }
+            params => panic!("Unexpected request parameters: {:?}", params),
+        }
+    }
+
+    #[test]
+    fn test_merge_effects() {
+        let first: Request = cancel_request(Uuid::new_v4());
+        let second: Request = cancel_request(Uuid::new_v4());
+        let uuids: Vec<Uuid> = vec![*first.uuid(), *second.uuid()];
+        let effects: Vec<SystemEffect<Request>> = vec![
+            SystemEffect::Bell,
+            SystemEffect::Request(first),
+            SystemEffect::Requests(vec![second]),
+        ];
+        match merge_effects(effects) {
+            Some(SystemEffect::Requests(requests)) => {
+                let merged: Vec<Uuid> = requests.iter().map(|request| *request.uuid()).collect();
+                assert_eq!(merged, uuids);
+            }
+            _ => panic!("The requests were not merged."),
+        }
+
+        assert!(matches!(
+            merge_effects(vec![SystemEffect::Bell, SystemEffect::Exit]),
+            Some(SystemEffect::Bell)
+        ));
+        assert!(merge_effects(Vec::new()).is_none());
+    }
+
+    /// Return the params of the last response to copying (or moving) `/r/a` to `/s/a`.
+    fn transfer_params(result: TransferFileResult) -> TransferFileResponseParams {
+        TransferFileResponseParams::builder()
+            .from(PathBuf::from("/r/a"))
+            .to(PathBuf::from("/s/a"))
+            .result(result)
+            .build()
+    }
+
+    #[test_case(ResponseParams::CopyFile(transfer_params(Ok(TransferProgress::builder().copied(1).total(1).build()))), Some(("Copying a finished.", Urgency::Normal)); "copied")]
+    #[test_case(ResponseParams::MoveFile(transfer_params(Err(TransferFileError::AlreadyExists))), Some(("Moving a failed.", Urgency::Critical)); "moving failed")]
+    #[test_case(ResponseParams::DeleteFile(DeleteFileResponseParams::builder().path(PathBuf::from("/r/b")).recursive(true).result(Ok(())).build()), Some(("Deleting b finished.", Urgency::Normal)); "deleted")]
+    #[test_case(ResponseParams::TouchFile(TouchFileResponseParams::builder().path(PathBuf::from("/r/b")).result(Ok(SystemTime::UNIX_EPOCH)).build()), None; "not long running")]
+    fn test_notification_body(params: ResponseParams, expected: Option<(&str, Urgency)>) {
+        let body: Option<(String, Urgency)> = notification_body(&params);
+
+        assert_eq!(
+            body.as_ref()
+                .map(|(body, urgency)| (body.as_str(), *urgency)),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_chain_effects() {
+        let request: Request = cancel_request(Uuid::new_v4());
+        let uuid: Uuid = *request.uuid();
+
+        match chain_effects(
+            Some(SystemEffect::Request(request)),
+            Some(SystemEffect::Bell),
+        ) {
+            Some(SystemEffect::Batch(effects)) => match effects.as_slice() {
+                [SystemEffect::Request(request), SystemEffect::Bell] => {
+                    assert_eq!(*request.uuid(), uuid)
+                }
+                _ => panic!("The effects were not chained in order."),
+            },
+            _ => panic!("The effects were not chained."),
+        }
+        assert!(matches!(
+            chain_effects(None, Some(SystemEffect::Bell)),
+            Some(SystemEffect::Bell)
+        ));
+        assert!(chain_effects(None, None).is_none());
+    }
+
+    #[test_case(&[RequestKind::CopyFile], "1 operation is still running (copying). Quit?"; "one")]
+    #[test_case(&[RequestKind::SearchPhrase, RequestKind::CopyFile, RequestKind::CopyFile], "3 operations are still running (copying, searching). Quit?"; "several")]
+    fn test_exit_question(kinds: &[RequestKind], expected: &str) {
+        assert_eq!(exit_question(kinds), expected);
+    }
 }
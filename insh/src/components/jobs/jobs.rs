@@ -0,0 +1,426 @@
+mod props {
+    use crate::jobs::Jobs;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use rend::Size;
+
+    use typed_builder::TypedBuilder;
+
+    #[derive(TypedBuilder)]
+    pub struct Props {
+        /// The jobs (shared with Insh, which updates them when they finish).
+        pub jobs: Rc<RefCell<Jobs>>,
+        pub size: Size,
+    }
+}
+pub use props::Props;
+
+mod jobs {
+    use super::{Action, Effect, Event, State};
+    use crate::color::Color;
+    use crate::inspect::{Inspect, Inspection};
+    use crate::jobs::{format_runtime, Job, JobStatus};
+    use crate::stateful::Stateful;
+
+    use rend::{Fabric, Size, Yarn};
+    use term::{Key, KeyEvent, KeyMods, TermEvent};
+    use til::Component;
+
+    use std::time::Instant;
+
+    use super::Props;
+
+    /// A list of the commands that were run in the background (from newest to oldest) with their
+    /// status and runtime, which can show the output of a job or kill it.
+    pub struct Jobs {
+        state: State,
+    }
+
+    impl Component<Props, Event, Effect> for Jobs {
+        fn new(props: Props) -> Self {
+            let state = State::from(props);
+            Self { state }
+        }
+
+        fn handle(&mut self, event: Event) -> Option<Effect> {
+            let action: Option<Action> = match event {
+                Event::TermEvent(TermEvent::Resize(size)) => Some(Action::Resize { size }),
+                Event::TermEvent(TermEvent::FocusGained | TermEvent::FocusLost) => None,
+                Event::TermEvent(TermEvent::KeyEvent(key_event)) => match key_event {
+                    KeyEvent {
+                        key: Key::Char('q'),
+                        mods: KeyMods::CONTROL | KeyMods::NONE,
+                    }
+                    | KeyEvent {
+                        key: Key::Escape, ..
+                    } if self.state.showing_output() => Some(Action::HideOutput),
+                    KeyEvent {
+                        key: Key::Char('q'),
+                        mods: KeyMods::CONTROL | KeyMods::NONE,
+                    }
+                    | KeyEvent {
+                        key: Key::Escape, ..
+                    } => Some(Action::Quit),
+                    KeyEvent {
+                        key: Key::Char('j'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Down),
+                    KeyEvent {
+                        key: Key::Char('k'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Up),
+                    KeyEvent {
+                        key: Key::CarriageReturn,
+                        mods: KeyMods::NONE,
+                    } if !self.state.showing_output() => Some(Action::ShowOutput),
+                    KeyEvent {
+                        key: Key::Char('x'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Kill),
+                    _ => None,
+                },
+            };
+
+            if let Some(action) = action {
+                self.state.perform(action)
+            } else {
+                Some(Effect::Bell)
+            }
+        }
+
+        fn render(&self, size: Size) -> Fabric {
+            if size.rows == 0 {
+                return Fabric::new(size);
+            }
+
+            let jobs = self.state.jobs().borrow();
+            let selected: Option<&Job> = self.state.selected_job(&jobs);
+            let title: String = match selected {
+                Some(job) if self.state.showing_output() => {
+                    format!("Output of {} ({})", job.name(), job.describe_status())
+                }
+                _ => format!("Jobs ({} running)", jobs.running_count()),
+            };
+            let mut title = Yarn::from(title);
+            title.resize(size.columns);
+            title.color(Color::InvertedText.into());
+            title.background(Color::InvertedBackground.into());
+            let mut fabric = Fabric::from(title);
+            if size.rows == 1 {
+                return fabric;
+            }
+
+            let rows: usize = size.rows - 1;
+            let body: Fabric = match selected {
+                Some(job) if self.state.showing_output() => {
+                    self.render_output(job, Size::new(rows, size.columns))
+                }
+                _ if jobs.jobs().is_empty() => {
+                    let message = "No commands were run in the background yet.";
+                    Fabric::center(message, Size::new(rows, size.columns))
+                }
+                _ => self.render_jobs(jobs.jobs(), selected, Size::new(rows, size.columns)),
+            };
+            fabric = fabric.quilt_bottom(body);
+            fabric.pad_bottom(size.rows);
+            fabric
+        }
+    }
+
+    impl Jobs {
+        /// Return the size of the list on a screen of the size (it is shown over the browser).
+        pub fn modal_size(screen: Size) -> Size {
+            Size::new(
+                (screen.rows * 2 / 3).max(screen.rows.min(5)),
+                (screen.columns * 4 / 5).max(screen.columns.min(40)),
+            )
+        }
+
+        /// Render the jobs (from newest to oldest) scrolled so that the selected job is visible.
+        fn render_jobs(&self, jobs: &[Job], selected: Option<&Job>, size: Size) -> Fabric {
+            let now = Instant::now();
+            let selected_number: usize = selected
+                .and_then(|selected| {
+                    jobs.iter()
+                        .rev()
+                        .position(|job| job.uuid() == selected.uuid())
+                })
+                .unwrap_or(0);
+            let top: usize = (selected_number + 1).saturating_sub(size.rows);
+            let yarns: Vec<Yarn> = jobs
+                .iter()
+                .rev()
+                .enumerate()
+                .skip(top)
+                .take(size.rows)
+                .map(|(number, job)| {
+                    let mut yarn: Yarn = self.render_job(job, now);
+                    yarn.resize(size.columns);
+                    if number == selected_number {
+                        yarn.color(Color::InvertedText.into());
+                        yarn.background(Color::Highlight.into());
+                    }
+                    yarn
+                })
+                .collect();
+            let mut fabric = Fabric::from(yarns);
+            fabric.pad_bottom(size.rows);
+            fabric
+        }
+
+        /// Return the yarn for a job (its runtime, its status, and its name).
+        fn render_job(&self, job: &Job, now: Instant) -> Yarn {
+            let mut runtime = Yarn::from(format!("{:>8}  ", format_runtime(job.runtime(now))));
+            runtime.color(Color::GrayedText.into());
+            let mut status = Yarn::from(format!("{:<16}  ", job.describe_status()));
+            match job.status() {
+                JobStatus::Running => status.color(Color::Highlight.into()),
+                JobStatus::Exited(exit_status) if exit_status.success() => {}
+                _ => status.color(Color::Danger.into()),
+            }
+            runtime.concat(status).concat(Yarn::from(job.name()))
+        }
+
+        /// Render the output of a job (its stdout and then its stderr).
+        fn render_output(&self, job: &Job, size: Size) -> Fabric {
+            if job.is_running() {
+                return Fabric::center("The job is still running.", size);
+            }
+            let stdout = job.stdout().lines().map(|line| (line, false));
+            let stderr = job.stderr().lines().map(|line| (line, true));
+            let yarns: Vec<Yarn> = stdout
+                .chain(stderr)
+                .skip(self.state.output_top())
+                .take(size.rows)
+                .map(|(line, is_stderr)| {
+                    let mut yarn = Yarn::from(line);
+                    yarn.resize(size.columns);
+                    if is_stderr {
+                        yarn.color(Color::Danger.into());
+                    }
+                    yarn
+                })
+                .collect();
+            if yarns.is_empty() {
+                return Fabric::center("The job had no output.", size);
+            }
+            let mut fabric = Fabric::from(yarns);
+            fabric.pad_bottom(size.rows);
+            fabric
+        }
+    }
+
+    impl Inspect for Jobs {
+        fn inspect(&self) -> Inspection {
+            self.state.inspect()
+        }
+    }
+}
+pub use jobs::Jobs;
+
+mod event {
+    use term::TermEvent;
+
+    pub enum Event {
+        TermEvent(TermEvent),
+    }
+}
+pub use event::Event;
+
+mod state {
+    use super::{Action, Effect, Props};
+    use crate::inspect::{Inspect, Inspection};
+    use crate::jobs::{Job, Jobs};
+    use crate::stateful::Stateful;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use rend::Size;
+    use uuid::Uuid;
+
+    pub struct State {
+        jobs: Rc<RefCell<Jobs>>,
+        size: Size,
+        /// The selected job (the newest job if it is not set or the job was forgotten).
+        selected: Option<Uuid>,
+        /// Whether the output of the selected job is shown (instead of the list).
+        showing_output: bool,
+        /// The number of the first visible line of the output.
+        output_top: usize,
+    }
+
+    impl From<Props> for State {
+        fn from(props: Props) -> Self {
+            Self {
+                jobs: props.jobs,
+                size: props.size,
+                selected: None,
+                showing_output: false,
+                output_top: 0,
+            }
+        }
+    }
+
+    impl State {
+        pub fn jobs(&self) -> &RefCell<Jobs> {
+            &self.jobs
+        }
+
+        pub fn showing_output(&self) -> bool {
+            self.showing_output
+        }
+
+        pub fn output_top(&self) -> usize {
+            self.output_top
+        }
+
+        /// Return the selected job of the jobs.
+        pub fn selected_job<'a>(&self, jobs: &'a Jobs) -> Option<&'a Job> {
+            self.selected
+                .and_then(|uuid| jobs.get(&uuid))
+                .or_else(|| jobs.jobs().last())
+        }
+
+        /// Return the number of rows available for showing the jobs or the output.
+        fn rows(&self) -> usize {
+            self.size.rows.saturating_sub(1)
+        }
+
+        fn resize(&mut self, size: Size) -> Option<Effect> {
+            self.size = size;
+            None
+        }
+
+        /// Select the job that is `offset` jobs older than the selected job (or newer if it is
+        /// negative).
+        fn select_by(&mut self, offset: isize) -> Option<Effect> {
+            let jobs = self.jobs.borrow();
+            let uuids: Vec<Uuid> = jobs.jobs().iter().rev().map(Job::uuid).collect();
+            let number: usize = self
+                .selected_job(&jobs)
+                .and_then(|job| uuids.iter().position(|uuid| *uuid == job.uuid()))
+                .unwrap_or(0);
+            match number
+                .checked_add_signed(offset)
+                .and_then(|number| uuids.get(number))
+            {
+                Some(uuid) => {
+                    self.selected = Some(*uuid);
+                    None
+                }
+                None => Some(Effect::Bell),
+            }
+        }
+
+        fn down(&mut self) -> Option<Effect> {
+            if !self.showing_output {
+                return self.select_by(1);
+            }
+            let jobs = self.jobs.borrow();
+            let lines: usize = self.selected_job(&jobs).map_or(0, |job| {
+                job.stdout().lines().count() + job.stderr().lines().count()
+            });
+            if self.output_top + self.rows() >= lines {
+                return Some(Effect::Bell);
+            }
+            self.output_top += 1;
+            None
+        }
+
+        fn up(&mut self) -> Option<Effect> {
+            if !self.showing_output {
+                return self.select_by(-1);
+            }
+            if self.output_top == 0 {
+                return Some(Effect::Bell);
+            }
+            self.output_top -= 1;
+            None
+        }
+
+        fn show_output(&mut self) -> Option<Effect> {
+            let jobs = self.jobs.borrow();
+            let uuid: Uuid = match self.selected_job(&jobs) {
+                Some(job) => job.uuid(),
+                None => return Some(Effect::Bell),
+            };
+            drop(jobs);
+            self.selected = Some(uuid);
+            self.showing_output = true;
+            self.output_top = 0;
+            None
+        }
+
+        fn hide_output(&mut self) -> Option<Effect> {
+            self.showing_output = false;
+            None
+        }
+
+        fn kill(&mut self) -> Option<Effect> {
+            let jobs = self.jobs.borrow();
+            match self.selected_job(&jobs) {
+                Some(job) if job.is_running() && !job.killed() => {
+                    Some(Effect::Kill { uuid: job.uuid() })
+                }
+                _ => Some(Effect::Bell),
+            }
+        }
+    }
+
+    impl Stateful<Action, Effect> for State {
+        fn perform(&mut self, action: Action) -> Option<Effect> {
+            match action {
+                Action::Resize { size } => self.resize(size),
+                Action::Down => self.down(),
+                Action::Up => self.up(),
+                Action::ShowOutput => self.show_output(),
+                Action::HideOutput => self.hide_output(),
+                Action::Kill => self.kill(),
+                Action::Quit => Some(Effect::Quit),
+            }
+        }
+    }
+
+    impl Inspect for State {
+        fn inspect(&self) -> Inspection {
+            Inspection::new("Jobs")
+                .field("jobs", self.jobs.borrow().jobs().len())
+                .field("selected", self.selected)
+                .field("showing_output", self.showing_output)
+                .field("output_top", self.output_top)
+        }
+    }
+}
+use state::State;
+
+mod action {
+    use rend::Size;
+
+    pub enum Action {
+        Resize { size: Size },
+        Down,
+        Up,
+        ShowOutput,
+        HideOutput,
+        Kill,
+        Quit,
+    }
+}
+use action::Action;
+
+mod effect {
+    use uuid::Uuid;
+
+    pub enum Effect {
+        /// Kill the job.
+        Kill {
+            uuid: Uuid,
+        },
+        Quit,
+        Bell,
+    }
+}
+pub use effect::Effect;
@@ -0,0 +1,3 @@
+mod jobs;
+
+pub use jobs::{Effect as JobsEffect, Event as JobsEvent, Jobs, Props as JobsProps};
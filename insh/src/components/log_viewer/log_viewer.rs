@@ -0,0 +1,957 @@
+mod props {
+    use rend::Size;
+
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use typed_builder::TypedBuilder;
+
+    use crate::config::Config;
+
+    #[derive(TypedBuilder)]
+    pub struct Props {
+        pub path: PathBuf,
+        pub size: Size,
+        pub config: Arc<Config>,
+    }
+}
+pub use props::Props;
+
+mod log_viewer {
+    use super::{level, Action, Effect, Event, Level, State};
+    use crate::color::Color;
+    use crate::components::common::{PhraseEffect, PhraseEvent};
+    use crate::inspect::{Inspect, Inspection};
+    use crate::stateful::Stateful;
+    use crate::string::DetabExt;
+
+    use crossterm::style::Color as CrosstermColor;
+    use rend::{Fabric, Size, Yarn};
+    use term::{Key, KeyEvent, KeyMods, TermEvent};
+    use til::Component;
+
+    use super::Props;
+
+    /// A full-screen viewer for log files which follows the file as it is appended to (like
+    /// `tail -f`), colors the lines by their level, and highlights the lines that match a filter.
+    pub struct LogViewer {
+        state: State,
+    }
+
+    impl Component<Props, Event, Effect> for LogViewer {
+        fn new(props: Props) -> Self {
+            let state = State::from(props);
+            Self { state }
+        }
+
+        fn handle(&mut self, event: Event) -> Option<Effect> {
+            let action: Option<Action> = match event {
+                Event::Response(response) => Some(Action::HandleResponse(response)),
+                Event::TimedOut => Some(Action::TimeOut),
+                Event::TermEvent(TermEvent::Resize(size)) => Some(Action::Resize { size }),
+                Event::TermEvent(TermEvent::FocusGained | TermEvent::FocusLost) => None,
+                Event::TermEvent(TermEvent::KeyEvent(KeyEvent {
+                    key: Key::Escape, ..
+                })) if self.state.prompt_open() => Some(Action::ClosePrompt),
+                Event::TermEvent(term_event) if self.state.prompt_open() => {
+                    let phrase_event = PhraseEvent::TermEvent(term_event);
+                    match self.state.phrase.handle(phrase_event) {
+                        Some(PhraseEffect::Enter { phrase }) => Some(Action::Filter { phrase }),
+                        Some(PhraseEffect::Quit) => Some(Action::ClosePrompt),
+                        Some(PhraseEffect::Bell) => Some(Action::Bell),
+                        None => return None,
+                    }
+                }
+                Event::TermEvent(TermEvent::KeyEvent(key_event)) => match key_event {
+                    KeyEvent {
+                        key: Key::Char('q'),
+                        mods: KeyMods::CONTROL,
+                    }
+                    | KeyEvent {
+                        key: Key::Char('q'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Quit),
+                    KeyEvent {
+                        key: Key::Char('j'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Down),
+                    KeyEvent {
+                        key: Key::Char('J'),
+                        mods: KeyMods::SHIFT,
+                    } => Some(Action::ReallyDown),
+                    KeyEvent {
+                        key: Key::Char('k'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Up),
+                    KeyEvent {
+                        key: Key::Char('K'),
+                        mods: KeyMods::SHIFT,
+                    } => Some(Action::ReallyUp),
+                    KeyEvent {
+                        key: Key::Char('F'),
+                        mods: KeyMods::SHIFT,
+                    } => Some(Action::ToggleFollow),
+                    KeyEvent {
+                        key: Key::Char('/'),
+                        ..
+                    } => Some(Action::OpenPrompt),
+                    KeyEvent {
+                        key: Key::Char('n'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::NextMatch),
+                    KeyEvent {
+                        key: Key::Char('N'),
+                        mods: KeyMods::SHIFT,
+                    } => Some(Action::PreviousMatch),
+                    KeyEvent {
+                        key: Key::Char('f'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::ToggleOnlyMatches),
+                    _ => None,
+                },
+            };
+
+            if let Some(action) = action {
+                self.state.perform(action)
+            } else {
+                Some(Effect::Bell)
+            }
+        }
+
+        fn render(&self, size: Size) -> Fabric {
+            if size.rows == 0 {
+                return Fabric::new(size);
+            }
+
+            let mut title = Yarn::from(self.state.path().to_string_lossy().to_string());
+            title.resize(size.columns);
+            title.color(Color::InvertedText.into());
+            title.background(Color::InvertedBackground.into());
+            let mut fabric = Fabric::from(title);
+            if size.rows == 1 {
+                return fabric;
+            }
+
+            let rows: usize = size.rows - 2;
+            if let Some(error) = self.state.error() {
+                fabric = fabric.quilt_bottom(Fabric::center(error, Size::new(rows, size.columns)));
+                return fabric.quilt_bottom(self.render_status(size.columns));
+            }
+
+            let top: usize = self.state.top();
+            let mut yarns: Vec<Yarn> = Vec::with_capacity(rows);
+            for shown in top..top + rows {
+                let line_index: usize = match self.state.line_index(shown) {
+                    Some(line_index) => line_index,
+                    None => break,
+                };
+                let text: &str = self.state.lines().get(line_index).unwrap().text();
+                let mut yarn: Yarn = self.render_line(&text.detab(self.state.tab_width()));
+                yarn.resize(size.columns);
+                yarns.push(yarn);
+            }
+            if !yarns.is_empty() {
+                fabric = fabric.quilt_bottom(Fabric::from(yarns));
+            }
+            fabric.pad_bottom(size.rows - 1);
+
+            fabric.quilt_bottom(self.render_status(size.columns))
+        }
+    }
+
+    impl LogViewer {
+        /// Return the yarn for a line colored by its level with the matches of the filter
+        /// highlighted.
+        fn render_line(&self, text: &str) -> Yarn {
+            let color: Option<CrosstermColor> = match level(text) {
+                Some(Level::Error) => Some(Color::LogError.into()),
+                Some(Level::Warning) => Some(Color::LogWarning.into()),
+                Some(Level::Debug) => Some(Color::LogDebug.into()),
+                Some(Level::Info) | None => None,
+            };
+            let plain = |text: &str| {
+                let mut yarn = Yarn::from(text);
+                if let Some(color) = color {
+                    yarn.color(color);
+                }
+                yarn
+            };
+
+            let filter = match self.state.filter() {
+                Some(filter) => filter,
+                None => return plain(text),
+            };
+            let mut yarn = Yarn::new();
+            let mut position: usize = 0;
+            for found in filter
+                .find_iter(text)
+                .filter(|found| found.start() < found.end())
+            {
+                yarn = yarn.concat(plain(&text[position..found.start()]));
+                let mut match_yarn = Yarn::from(found.as_str());
+                match_yarn.color(Color::InvertedText.into());
+                match_yarn.background(Color::Highlight.into());
+                yarn = yarn.concat(match_yarn);
+                position = found.end();
+            }
+            yarn.concat(plain(&text[position..]))
+        }
+
+        /// Return the fabric for the status line (or the filter prompt if it is open).
+        fn render_status(&self, columns: usize) -> Fabric {
+            if self.state.prompt_open() {
+                let mut yarn = Yarn::from(format!("/{}", self.state.phrase.value()));
+                yarn.resize(columns);
+                yarn.color(Color::InvertedText.into());
+                yarn.background(Color::focus_or_important(true).into());
+                return Fabric::from(yarn);
+            }
+
+            let mut parts: Vec<String> = Vec::new();
+            if let Some(filter) = self.state.filter() {
+                parts.push(format!(
+                    "/{}/ {} matches",
+                    filter.as_str(),
+                    self.state.match_count()
+                ));
+            }
+            if self.state.only_matches() {
+                parts.push(String::from("only matches"));
+            }
+            parts.push(format!(
+                "line {}/{}",
+                (self.state.top() + 1).min(self.state.shown_len()),
+                self.state.shown_len()
+            ));
+            parts.push(String::from(match self.state.follow() {
+                true => "following",
+                false => "paused",
+            }));
+            let position: String = parts.join("  ");
+
+            let message: &str = self.state.message().as_deref().unwrap_or_default();
+            let padding: usize =
+                columns.saturating_sub(message.chars().count() + position.chars().count());
+            let mut status = Yarn::from(format!("{}{}{}", message, " ".repeat(padding), position));
+            status.resize(columns);
+            status.color(Color::InvertedText.into());
+            status.background(Color::InvertedBackground.into());
+            Fabric::from(status)
+        }
+    }
+
+    impl Inspect for LogViewer {
+        fn inspect(&self) -> Inspection {
+            self.state.inspect()
+        }
+    }
+}
+pub use log_viewer::LogViewer;
+
+mod event {
+    use insh_api::Response;
+    use term::TermEvent;
+
+    pub enum Event {
+        Response(Response),
+        /// The request for reading a chunk of the file timed out.
+        TimedOut,
+        TermEvent(TermEvent),
+    }
+}
+pub use event::Event;
+
+mod level {
+    /// The level of a log line.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Level {
+        Error,
+        Warning,
+        Info,
+        Debug,
+    }
+
+    /// Return the level of a log line (from the first word that is the name of a level in upper
+    /// case, such as `ERROR`, or the value of a `level=` field).
+    pub fn level(line: &str) -> Option<Level> {
+        line.split(|character: char| !character.is_ascii_alphanumeric() && character != '=')
+            .find_map(|word| {
+                let upper: String;
+                let name: &str = match word.split_once('=') {
+                    Some((key, value))
+                        if key.eq_ignore_ascii_case("level") || key.eq_ignore_ascii_case("lvl") =>
+                    {
+                        upper = value.to_ascii_uppercase();
+                        &upper
+                    }
+                    Some(_) => return None,
+                    None => word,
+                };
+                match name {
+                    "FATAL" | "CRIT" | "CRITICAL" | "ERROR" | "ERR" => Some(Level::Error),
+                    "WARN" | "WARNING" => Some(Level::Warning),
+                    "INFO" => Some(Level::Info),
+                    "DEBUG" | "TRACE" => Some(Level::Debug),
+                    _ => None,
+                }
+            })
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use test_case::test_case;
+
+        #[test_case("2023-01-01 12:00:00 ERROR Failed to connect", Some(Level::Error); "error")]
+        #[test_case("[WARN] Disk is almost full", Some(Level::Warning); "bracketed warning")]
+        #[test_case("ts=1 level=debug msg=hello", Some(Level::Debug); "level field")]
+        #[test_case("INFO Retrying after ERROR", Some(Level::Info); "first level wins")]
+        #[test_case("an error in lower case", None; "lower case word")]
+        #[test_case("ERRORS were found", None; "part of a word")]
+        fn test_level(line: &str, expected: Option<Level>) {
+            assert_eq!(level(line), expected);
+        }
+    }
+}
+use level::{level, Level};
+
+mod state {
+    use super::{Action, Effect, Props};
+    use crate::components::common::{Lines, Phrase};
+    use crate::inspect::{Inspect, Inspection};
+    use crate::stateful::Stateful;
+
+    use insh_api::{
+        ReadFileError, ReadFileRequestParams, ReadFileResponseParams, Request, RequestParams,
+        Response, ResponseParams, MAX_READ_FILE_WAIT,
+    };
+    use regex::Regex;
+    use rend::Size;
+
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    /// The number of bytes that are read at a time.
+    const CHUNK_LEN: u64 = 64 * 1024;
+
+    /// The number of bytes at the end of a log file that are read (so that large logs open
+    /// quickly).
+    const TAIL_LEN: u64 = 1024 * 1024;
+
+    /// Return the request for reading the chunk of the log file at `path` which starts at `offset`
+    /// (waiting up to `wait` for the file to grow if the offset is the end of the file).
+    pub fn read_log_request(path: &Path, offset: u64, wait: Option<Duration>) -> Request {
+        Request::builder()
+            .params(RequestParams::ReadFile(
+                ReadFileRequestParams::builder()
+                    .path(path.to_path_buf())
+                    .offset(offset)
+                    .len(CHUNK_LEN)
+                    .wait(wait)
+                    .build(),
+            ))
+            .build()
+    }
+
+    pub struct State {
+        path: PathBuf,
+        size: Size,
+        tab_width: usize,
+        lines: Lines,
+        /// The offset (in bytes) of the next chunk of the file to read.
+        offset: u64,
+        /// Whether the next chunk starts in the middle of a line (whose start is skipped).
+        skip_partial_line: bool,
+        /// Whether a chunk of the file is being read (the first chunk is requested before the log
+        /// viewer is made).
+        reading: bool,
+        /// An error reading the file.
+        error: Option<String>,
+        /// The index of the first visible line (of the lines that are shown).
+        top: usize,
+        /// Whether the view stays at the end of the file as lines are appended.
+        follow: bool,
+        /// Whether the filter prompt is open.
+        prompt_open: bool,
+        pub phrase: Phrase,
+        /// The expression that the highlighted lines match.
+        filter: Option<Regex>,
+        /// The indices of the lines that match the filter.
+        matches: Vec<usize>,
+        /// The number of lines that have been checked against the filter.
+        checked: usize,
+        /// Whether only the lines that match the filter are shown.
+        only_matches: bool,
+        /// A message shown in the status line.
+        message: Option<String>,
+    }
+
+    impl From<Props> for State {
+        fn from(props: Props) -> Self {
+            Self {
+                tab_width: props.config.general().tab_width_for(&props.path),
+                path: props.path,
+                size: props.size,
+                lines: Lines::default(),
+                offset: 0,
+                skip_partial_line: false,
+                reading: true,
+                error: None,
+                top: 0,
+                follow: true,
+                prompt_open: false,
+                phrase: Phrase::default(),
+                filter: None,
+                matches: Vec::new(),
+                checked: 0,
+                only_matches: false,
+                message: None,
+            }
+        }
+    }
+
+    impl State {
+        pub fn path(&self) -> &Path {
+            &self.path
+        }
+
+        pub fn tab_width(&self) -> usize {
+            self.tab_width
+        }
+
+        pub fn lines(&self) -> &Lines {
+            &self.lines
+        }
+
+        pub fn error(&self) -> &Option<String> {
+            &self.error
+        }
+
+        pub fn top(&self) -> usize {
+            self.top
+        }
+
+        pub fn follow(&self) -> bool {
+            self.follow
+        }
+
+        pub fn prompt_open(&self) -> bool {
+            self.prompt_open
+        }
+
+        pub fn filter(&self) -> Option<&Regex> {
+            self.filter.as_ref()
+        }
+
+        /// Return the number of lines that match the filter.
+        pub fn match_count(&self) -> usize {
+            self.matches.len()
+        }
+
+        pub fn only_matches(&self) -> bool {
+            self.only_matches
+        }
+
+        pub fn message(&self) -> &Option<String> {
+            &self.message
+        }
+
+        /// Return the number of lines that are shown (all of the lines or only the matches).
+        pub fn shown_len(&self) -> usize {
+            match self.only_matches {
+                true => self.matches.len(),
+                false => self.lines.len(),
+            }
+        }
+
+        /// Return the index of the line that is shown at the index `shown`.
+        pub fn line_index(&self, shown: usize) -> Option<usize> {
+            match self.only_matches {
+                true => self.matches.get(shown).copied(),
+                false => Some(shown).filter(|shown| *shown < self.lines.len()),
+            }
+        }
+
+        /// Return the index of the shown line for the line at `line_index` (or of the next shown
+        /// line if it is not shown).
+        fn shown_index(&self, line_index: usize) -> usize {
+            match self.only_matches {
+                true => self.matches.partition_point(|index| *index < line_index),
+                false => line_index,
+            }
+        }
+
+        /// Return the number of rows available for showing the lines of the file.
+        fn rows(&self) -> usize {
+            self.size.rows.saturating_sub(2)
+        }
+
+        /// Return the largest index of the first visible line.
+        fn max_top(&self) -> usize {
+            self.shown_len().saturating_sub(self.rows())
+        }
+
+        /// Keep the view at the end of the file if it is following the file.
+        fn keep_following(&mut self) {
+            if self.follow {
+                self.top = self.max_top();
+            }
+        }
+
+        /// Return the effect for reading the next chunk of the file (waiting for it to grow if
+        /// `wait` is true).
+        fn read(&mut self, wait: bool) -> Option<Effect> {
+            if self.error.is_some() || self.reading {
+                return None;
+            }
+            let wait: Option<Duration> = match wait {
+                true => Some(MAX_READ_FILE_WAIT),
+                false => None,
+            };
+            let request: Request = read_log_request(&self.path, self.offset, wait);
+            self.reading = true;
+            Some(Effect::Request(request))
+        }
+
+        /// Check the lines that have not been checked against the filter yet.
+        fn check_matches(&mut self) {
+            if let Some(filter) = &self.filter {
+                for index in self.checked..self.lines.len() {
+                    if filter.is_match(self.lines.get(index).unwrap().text()) {
+                        self.matches.push(index);
+                    }
+                }
+            }
+            self.checked = self.lines.len();
+        }
+
+        /// Forget the lines that are read (when the file is truncated) and read it again.
+        fn restart(&mut self) -> Option<Effect> {
+            self.lines = Lines::default();
+            self.offset = 0;
+            self.skip_partial_line = false;
+            self.matches.clear();
+            self.checked = 0;
+            self.top = 0;
+            self.message = Some(String::from("The file was truncated."));
+            self.read(false)
+        }
+
+        fn handle_response(&mut self, response: Response) -> Option<Effect> {
+            self.reading = false;
+
+            let params: &ReadFileResponseParams = match response.params() {
+                ResponseParams::ReadFile(params) => params,
+                _ => {
+                    #[cfg(feature = "logging")]
+                    log::error!("Unexpected response parameters.");
+                    return None;
+                }
+            };
+
+            let chunk = match params.result() {
+                Ok(chunk) => chunk,
+                Err(ReadFileError::OffsetPastEnd { .. }) => return self.restart(),
+                Err(error) => {
+                    self.error = Some(format!("Failed to read the file: {}", error));
+                    return None;
+                }
+            };
+            if chunk.file_size() < self.offset {
+                return self.restart();
+            }
+
+            // Only the end of large files is read.
+            if self.offset == 0 && chunk.file_size() > TAIL_LEN {
+                self.offset = chunk.file_size() - TAIL_LEN;
+                self.skip_partial_line = true;
+                self.message = Some(format!(
+                    "Showing the last {} KiB of the file.",
+                    TAIL_LEN / 1024
+                ));
+                return self.read(false);
+            }
+
+            let mut bytes: &[u8] = chunk.bytes();
+            self.offset += bytes.len() as u64;
+            if self.skip_partial_line {
+                match bytes.iter().position(|byte| *byte == b'\n') {
+                    Some(newline) => {
+                        bytes = &bytes[newline + 1..];
+                        self.skip_partial_line = false;
+                    }
+                    None => bytes = &[],
+                }
+            }
+            self.lines.push(bytes);
+            self.check_matches();
+            self.keep_following();
+
+            // NOTE: The last line is not finished at the end of the file since it may still be
+            // being written.
+            self.read(chunk.eof())
+        }
+
+        /// Stop waiting for the chunk that is being read and read it again (so that the file is
+        /// still followed).
+        fn time_out(&mut self) -> Option<Effect> {
+            self.reading = false;
+            self.message = Some(String::from("Reading the file timed out."));
+            self.read(true)
+        }
+
+        fn resize(&mut self, size: Size) -> Option<Effect> {
+            self.size = size;
+            self.top = self.top.min(self.max_top());
+            self.keep_following();
+            None
+        }
+
+        fn down(&mut self) -> Option<Effect> {
+            if self.top >= self.max_top() {
+                return Some(Effect::Bell);
+            }
+            self.top += 1;
+            None
+        }
+
+        fn really_down(&mut self) -> Option<Effect> {
+            self.follow = true;
+            self.keep_following();
+            None
+        }
+
+        fn up(&mut self) -> Option<Effect> {
+            if self.top == 0 {
+                return Some(Effect::Bell);
+            }
+            self.top -= 1;
+            self.follow = false;
+            None
+        }
+
+        fn really_up(&mut self) -> Option<Effect> {
+            self.top = 0;
+            self.follow = false;
+            None
+        }
+
+        fn toggle_follow(&mut self) -> Option<Effect> {
+            self.follow = !self.follow;
+            self.keep_following();
+            None
+        }
+
+        fn open_prompt(&mut self) -> Option<Effect> {
+            self.prompt_open = true;
+            self.phrase = Phrase::default();
+            None
+        }
+
+        fn close_prompt(&mut self) -> Option<Effect> {
+            self.prompt_open = false;
+            None
+        }
+
+        /// Set the filter to the expression (or clear it if the expression is empty).
+        fn set_filter(&mut self, expression: String) -> Option<Effect> {
+            self.prompt_open = false;
+            let line_index: Option<usize> = self.line_index(self.top);
+
+            let filter: Option<Regex> = match expression.is_empty() {
+                true => None,
+                false => match Regex::new(&expression) {
+                    Ok(filter) => Some(filter),
+                    Err(error) => {
+                        self.message = Some(format!("Invalid filter: {}", error));
+                        return Some(Effect::Bell);
+                    }
+                },
+            };
+            if filter.is_none() {
+                self.only_matches = false;
+            }
+            self.filter = filter;
+            self.matches.clear();
+            self.checked = 0;
+            self.check_matches();
+            self.message = None;
+
+            self.top = self
+                .shown_index(line_index.unwrap_or_default())
+                .min(self.max_top());
+            self.keep_following();
+            None
+        }
+
+        /// Move the view to the next (or previous) line that matches the filter.
+        fn jump_to_match(&mut self, forward: bool) -> Option<Effect> {
+            if self.filter.is_none() {
+                return Some(Effect::Bell);
+            }
+            let line_index: usize = self.line_index(self.top).unwrap_or_default();
+            let next: Option<usize> = match forward {
+                true => self
+                    .matches
+                    .get(self.matches.partition_point(|index| *index <= line_index))
+                    .copied(),
+                false => self
+                    .matches
+                    .partition_point(|index| *index < line_index)
+                    .checked_sub(1)
+                    .map(|position| self.matches[position]),
+            };
+            match next {
+                Some(next) => {
+                    self.top = self.shown_index(next).min(self.max_top());
+                    self.follow = false;
+                    None
+                }
+                None => {
+                    self.message = Some(String::from("No more matches."));
+                    Some(Effect::Bell)
+                }
+            }
+        }
+
+        fn toggle_only_matches(&mut self) -> Option<Effect> {
+            if self.filter.is_none() {
+                return Some(Effect::Bell);
+            }
+            let line_index: usize = self.line_index(self.top).unwrap_or_default();
+            self.only_matches = !self.only_matches;
+            self.top = self.shown_index(line_index).min(self.max_top());
+            self.keep_following();
+            None
+        }
+    }
+
+    impl Stateful<Action, Effect> for State {
+        fn perform(&mut self, action: Action) -> Option<Effect> {
+            if !matches!(action, Action::HandleResponse(_) | Action::Resize { .. }) {
+                self.message = None;
+            }
+            match action {
+                Action::HandleResponse(response) => self.handle_response(response),
+                Action::TimeOut => self.time_out(),
+                Action::Resize { size } => self.resize(size),
+                Action::Down => self.down(),
+                Action::ReallyDown => self.really_down(),
+                Action::Up => self.up(),
+                Action::ReallyUp => self.really_up(),
+                Action::ToggleFollow => self.toggle_follow(),
+                Action::OpenPrompt => self.open_prompt(),
+                Action::ClosePrompt => self.close_prompt(),
+                Action::Filter { phrase } => self.set_filter(phrase),
+                Action::NextMatch => self.jump_to_match(true),
+                Action::PreviousMatch => self.jump_to_match(false),
+                Action::ToggleOnlyMatches => self.toggle_only_matches(),
+                Action::Bell => Some(Effect::Bell),
+                Action::Quit => Some(Effect::Quit),
+            }
+        }
+    }
+
+    impl Inspect for State {
+        fn inspect(&self) -> Inspection {
+            Inspection::new("LogViewer")
+                .field("path", &self.path)
+                .field("offset", self.offset)
+                .field("lines", self.lines.len())
+                .field("top", self.top)
+                .field("follow", self.follow)
+                .field(
+                    "filter",
+                    self.filter.as_ref().map(|filter| filter.to_string()),
+                )
+                .field("matches", self.matches.len())
+                .field("only_matches", self.only_matches)
+                .field("reading", self.reading)
+                .field("error", &self.error)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::requester::send_over_socket;
+        use insh_api::FileChunk;
+        use std::sync::Arc;
+        use uuid::Uuid;
+
+        /// Return a new log viewer state for a terminal with `rows` rows (and the uuid of the
+        /// request for the first chunk).
+        fn state(rows: usize) -> (State, Uuid) {
+            let props = Props::builder()
+                .path(PathBuf::from("/var/log/test.log"))
+                .size(Size::new(rows, 80))
+                .config(Arc::default())
+                .build();
+            (State::from(props), Uuid::new_v4())
+        }
+
+        /// Respond to the pending request with a chunk and return the next request (as it is read
+        /// from a socket).
+        fn respond(
+            state: &mut State,
+            uuid: Uuid,
+            offset: u64,
+            bytes: &[u8],
+            file_size: u64,
+        ) -> Request {
+            let response = Response::builder()
+                .uuid(uuid)
+                .params(ResponseParams::ReadFile(
+                    ReadFileResponseParams::builder()
+                        .result(Ok(FileChunk::builder()
+                            .offset(offset)
+                            .bytes(bytes.to_vec())
+                            .file_size(file_size)
+                            .build()))
+                        .build(),
+                ))
+                .build();
+            match state.perform(Action::HandleResponse(response)) {
+                Some(Effect::Request(request)) => send_over_socket(vec![request]).remove(0),
+                _ => panic!("Expected a request."),
+            }
+        }
+
+        /// Return the offset and the wait of a request to read a file.
+        fn read_params(request: &Request) -> (u64, Option<Duration>) {
+            match request.params() {
+                RequestParams::ReadFile(params) => (params.offset(), params.wait()),
+                _ => panic!("Expected a request to read a file."),
+            }
+        }
+
+        #[test]
+        fn test_follow() {
+            let (mut state, uuid) = state(4);
+
+            let request: Request = respond(&mut state, uuid, 0, b"a\nb\nc\nd", 7);
+
+            assert_eq!(read_params(&request), (7, Some(MAX_READ_FILE_WAIT)));
+            assert_eq!(state.lines().len(), 3);
+            assert_eq!(state.top(), 1);
+
+            respond(&mut state, *request.uuid(), 7, b"\ne\n", 10);
+
+            assert_eq!(state.lines().len(), 5);
+            assert_eq!(state.top(), 3);
+        }
+
+        #[test]
+        fn test_time_out() {
+            let (mut state, uuid) = state(4);
+            let request: Request = respond(&mut state, uuid, 0, b"a\nb\n", 4);
+
+            let retry: Option<Effect> = state.perform(Action::TimeOut);
+
+            match retry {
+                Some(Effect::Request(retry)) => {
+                    assert_eq!(read_params(&retry), read_params(&request));
+                    assert_ne!(retry.uuid(), request.uuid());
+                }
+                _ => panic!("Expected the chunk to be read again."),
+            }
+            assert_eq!(state.lines().len(), 2);
+        }
+
+        #[test]
+        fn test_tail() {
+            let (mut state, uuid) = state(10);
+
+            let request: Request = respond(&mut state, uuid, 0, b"", TAIL_LEN + 5);
+
+            assert_eq!(read_params(&request), (5, None));
+
+            respond(
+                &mut state,
+                *request.uuid(),
+                5,
+                b"rtial\nwhole\n",
+                TAIL_LEN + 5,
+            );
+
+            assert_eq!(state.lines().len(), 1);
+            assert_eq!(state.lines().get(0).unwrap().text(), "whole");
+        }
+
+        #[test]
+        fn test_truncated() {
+            let (mut state, uuid) = state(10);
+            let request: Request = respond(&mut state, uuid, 0, b"a\nb\n", 4);
+
+            let request: Request = respond(&mut state, *request.uuid(), 4, b"", 2);
+
+            assert_eq!(read_params(&request), (0, None));
+            assert_eq!(state.lines().len(), 0);
+        }
+
+        #[test]
+        fn test_filter() {
+            let (mut state, uuid) = state(10);
+            respond(
+                &mut state,
+                uuid,
+                0,
+                b"INFO a\nERROR b\nINFO c\nERROR d\n",
+                30,
+            );
+
+            state.perform(Action::Filter {
+                phrase: String::from("ERROR"),
+            });
+            assert_eq!(state.match_count(), 2);
+
+            state.perform(Action::ToggleOnlyMatches);
+            assert_eq!(state.shown_len(), 2);
+            assert_eq!(state.line_index(1), Some(3));
+
+            state.perform(Action::Filter {
+                phrase: String::new(),
+            });
+            assert!(!state.only_matches());
+            assert_eq!(state.shown_len(), 4);
+        }
+    }
+}
+pub use state::read_log_request;
+use state::State;
+
+mod action {
+    use insh_api::Response;
+    use rend::Size;
+
+    pub enum Action {
+        HandleResponse(Response),
+        TimeOut,
+        Resize { size: Size },
+        Down,
+        ReallyDown,
+        Up,
+        ReallyUp,
+        ToggleFollow,
+        OpenPrompt,
+        ClosePrompt,
+        Filter { phrase: String },
+        NextMatch,
+        PreviousMatch,
+        ToggleOnlyMatches,
+        Bell,
+        Quit,
+    }
+}
+use action::Action;
+
+mod effect {
+    use insh_api::Request;
+
+    pub enum Effect {
+        Request(Request),
+        Quit,
+        Bell,
+    }
+}
+pub use effect::Effect;
@@ -0,0 +1,6 @@
+mod log_viewer;
+
+pub use log_viewer::{
+    read_log_request, Effect as LogViewerEffect, Event as LogViewerEvent, LogViewer,
+    Props as LogViewerProps,
+};
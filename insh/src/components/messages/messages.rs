@@ -0,0 +1,256 @@
+mod props {
+    use crate::toasts::Toast;
+
+    use rend::Size;
+
+    use typed_builder::TypedBuilder;
+
+    #[derive(TypedBuilder)]
+    pub struct Props {
+        /// The toasts that were made (from oldest to newest).
+        pub toasts: Vec<Toast>,
+        pub size: Size,
+    }
+}
+pub use props::Props;
+
+mod messages {
+    use super::{Action, Effect, Event, State};
+    use crate::color::Color;
+    use crate::inspect::{Inspect, Inspection};
+    use crate::relative_time::relative_time;
+    use crate::stateful::Stateful;
+    use crate::toasts::{Toast, ToastLevel};
+
+    use rend::{Fabric, Size, Yarn};
+    use term::{Key, KeyEvent, KeyMods, TermEvent};
+    use til::Component;
+
+    use std::time::SystemTime;
+
+    use super::Props;
+
+    /// A list of the past toasts (from newest to oldest) so that messages which were only shown
+    /// for a moment can be read.
+    pub struct Messages {
+        state: State,
+    }
+
+    impl Component<Props, Event, Effect> for Messages {
+        fn new(props: Props) -> Self {
+            let state = State::from(props);
+            Self { state }
+        }
+
+        fn handle(&mut self, event: Event) -> Option<Effect> {
+            let action: Option<Action> = match event {
+                Event::TermEvent(TermEvent::Resize(size)) => Some(Action::Resize { size }),
+                Event::TermEvent(TermEvent::FocusGained | TermEvent::FocusLost) => None,
+                Event::TermEvent(TermEvent::KeyEvent(key_event)) => match key_event {
+                    KeyEvent {
+                        key: Key::Char('q'),
+                        mods: KeyMods::CONTROL | KeyMods::NONE,
+                    }
+                    | KeyEvent {
+                        key: Key::Escape, ..
+                    } => Some(Action::Quit),
+                    KeyEvent {
+                        key: Key::Char('j'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Down),
+                    KeyEvent {
+                        key: Key::Char('k'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Up),
+                    _ => None,
+                },
+            };
+
+            if let Some(action) = action {
+                self.state.perform(action)
+            } else {
+                Some(Effect::Bell)
+            }
+        }
+
+        fn render(&self, size: Size) -> Fabric {
+            if size.rows == 0 {
+                return Fabric::new(size);
+            }
+
+            let mut title = Yarn::from(format!("Messages ({})", self.state.toasts().len()));
+            title.resize(size.columns);
+            title.color(Color::InvertedText.into());
+            title.background(Color::InvertedBackground.into());
+            let mut fabric = Fabric::from(title);
+            if size.rows == 1 {
+                return fabric;
+            }
+
+            let rows: usize = size.rows - 1;
+            if self.state.toasts().is_empty() {
+                let message = "There are no messages yet.";
+                return fabric.quilt_bottom(Fabric::center(message, Size::new(rows, size.columns)));
+            }
+
+            let now = SystemTime::now();
+            let yarns: Vec<Yarn> = self
+                .state
+                .toasts()
+                .iter()
+                .rev()
+                .skip(self.state.top())
+                .take(rows)
+                .map(|toast| {
+                    let mut yarn: Yarn = self.render_toast(toast, now);
+                    yarn.resize(size.columns);
+                    yarn
+                })
+                .collect();
+            if !yarns.is_empty() {
+                fabric = fabric.quilt_bottom(Fabric::from(yarns));
+            }
+            fabric.pad_bottom(size.rows);
+            fabric
+        }
+    }
+
+    impl Messages {
+        /// Return the size of the list on a screen of the size (it is shown over the browser).
+        pub fn modal_size(screen: Size) -> Size {
+            Size::new(
+                (screen.rows * 2 / 3).max(screen.rows.min(5)),
+                (screen.columns * 4 / 5).max(screen.columns.min(40)),
+            )
+        }
+
+        /// Return the yarn for a toast (when it was made and its message).
+        fn render_toast(&self, toast: &Toast, now: SystemTime) -> Yarn {
+            let mut time = Yarn::from(format!("{:>14}  ", relative_time(toast.time(), now)));
+            time.color(Color::GrayedText.into());
+            let mut message = Yarn::from(toast.message());
+            if toast.level() == ToastLevel::Error {
+                message.color(Color::Danger.into());
+            }
+            time.concat(message)
+        }
+    }
+
+    impl Inspect for Messages {
+        fn inspect(&self) -> Inspection {
+            self.state.inspect()
+        }
+    }
+}
+pub use messages::Messages;
+
+mod event {
+    use term::TermEvent;
+
+    pub enum Event {
+        TermEvent(TermEvent),
+    }
+}
+pub use event::Event;
+
+mod state {
+    use super::{Action, Effect, Props};
+    use crate::inspect::{Inspect, Inspection};
+    use crate::stateful::Stateful;
+    use crate::toasts::Toast;
+
+    use rend::Size;
+
+    pub struct State {
+        toasts: Vec<Toast>,
+        size: Size,
+        /// The number of the first visible toast (counting from the newest).
+        top: usize,
+    }
+
+    impl From<Props> for State {
+        fn from(props: Props) -> Self {
+            Self {
+                toasts: props.toasts,
+                size: props.size,
+                top: 0,
+            }
+        }
+    }
+
+    impl State {
+        pub fn toasts(&self) -> &[Toast] {
+            &self.toasts
+        }
+
+        pub fn top(&self) -> usize {
+            self.top
+        }
+
+        /// Return the number of rows available for showing the toasts.
+        fn rows(&self) -> usize {
+            self.size.rows.saturating_sub(1)
+        }
+
+        fn resize(&mut self, size: Size) -> Option<Effect> {
+            self.size = size;
+            None
+        }
+
+        fn down(&mut self) -> Option<Effect> {
+            if self.top + self.rows() >= self.toasts.len() {
+                return Some(Effect::Bell);
+            }
+            self.top += 1;
+            None
+        }
+
+        fn up(&mut self) -> Option<Effect> {
+            if self.top == 0 {
+                return Some(Effect::Bell);
+            }
+            self.top -= 1;
+            None
+        }
+    }
+
+    impl Stateful<Action, Effect> for State {
+        fn perform(&mut self, action: Action) -> Option<Effect> {
+            match action {
+                Action::Resize { size } => self.resize(size),
+                Action::Down => self.down(),
+                Action::Up => self.up(),
+                Action::Quit => Some(Effect::Quit),
+            }
+        }
+    }
+
+    impl Inspect for State {
+        fn inspect(&self) -> Inspection {
+            Inspection::new("Messages")
+                .field("toasts", self.toasts.len())
+                .field("top", self.top)
+        }
+    }
+}
+use state::State;
+
+mod action {
+    use rend::Size;
+
+    pub enum Action {
+        Resize { size: Size },
+        Down,
+        Up,
+        Quit,
+    }
+}
+use action::Action;
+
+mod effect {
+    pub enum Effect {
+        Quit,
+        Bell,
+    }
+}
+pub use effect::Effect;
@@ -0,0 +1,5 @@
+mod messages;
+
+pub use messages::{
+    Effect as MessagesEffect, Event as MessagesEvent, Messages, Props as MessagesProps,
+};
@@ -3,6 +3,15 @@ mod common;
 mod file_creator;
 mod finder;
 mod insh;
+mod jobs;
+mod log_viewer;
+mod messages;
+mod mounts;
+mod open_files;
+mod pager;
+mod palette;
+mod process_list;
+mod reader;
 mod searcher;
 
-pub use insh::{Insh, Props as InshProps};
+pub use insh::{Insh, Props as InshProps, Start};
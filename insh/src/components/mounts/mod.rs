@@ -0,0 +1,3 @@
+mod mounts;
+
+pub use mounts::{Effect as MountsEffect, Event as MountsEvent, Mounts, Props as MountsProps};
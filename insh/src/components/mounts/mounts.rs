@@ -0,0 +1,492 @@
+mod props {
+    use rend::Size;
+
+    use typed_builder::TypedBuilder;
+
+    use crate::config::MountConfig;
+
+    #[derive(TypedBuilder)]
+    pub struct Props {
+        pub mounts: Vec<MountConfig>,
+        pub size: Size,
+    }
+}
+pub use props::Props;
+
+mod mounts {
+    use super::{Action, Effect, Event, State};
+    use crate::color::Color;
+    use crate::config::{MountConfig, MountKind};
+    use crate::inspect::{Inspect, Inspection};
+    use crate::stateful::Stateful;
+
+    use rend::{Fabric, Size, Yarn};
+    use term::{Key, KeyEvent, KeyMods, TermEvent};
+    use til::Component;
+
+    use super::Props;
+
+    /// A list of the configured network locations which can mount and unmount them.
+    pub struct Mounts {
+        state: State,
+    }
+
+    impl Component<Props, Event, Effect> for Mounts {
+        fn new(props: Props) -> Self {
+            let state = State::from(props);
+            Self { state }
+        }
+
+        fn handle(&mut self, event: Event) -> Option<Effect> {
+            let action: Option<Action> = match event {
+                Event::TermEvent(TermEvent::Resize(size)) => Some(Action::Resize { size }),
+                Event::TermEvent(TermEvent::FocusGained | TermEvent::FocusLost) => None,
+                Event::TermEvent(TermEvent::KeyEvent(key_event)) => match key_event {
+                    KeyEvent {
+                        key: Key::Char('q'),
+                        mods: KeyMods::CONTROL,
+                    }
+                    | KeyEvent {
+                        key: Key::Char('q'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Quit),
+                    KeyEvent {
+                        key: Key::Char('j'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Down),
+                    KeyEvent {
+                        key: Key::Char('J'),
+                        mods: KeyMods::SHIFT,
+                    } => Some(Action::ReallyDown),
+                    KeyEvent {
+                        key: Key::Char('k'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Up),
+                    KeyEvent {
+                        key: Key::Char('K'),
+                        mods: KeyMods::SHIFT,
+                    } => Some(Action::ReallyUp),
+                    KeyEvent {
+                        key: Key::Char('r'),
+                        ..
+                    } => Some(Action::Refresh),
+                    KeyEvent {
+                        key: Key::Char('l'),
+                        ..
+                    }
+                    | KeyEvent {
+                        key: Key::CarriageReturn,
+                        ..
+                    } => Some(Action::Open),
+                    KeyEvent {
+                        key: Key::Char('u'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Unmount),
+                    _ => None,
+                },
+            };
+
+            if let Some(action) = action {
+                self.state.perform(action)
+            } else {
+                Some(Effect::Bell)
+            }
+        }
+
+        fn render(&self, size: Size) -> Fabric {
+            if size.rows == 0 {
+                return Fabric::new(size);
+            }
+
+            let mut title = Yarn::from("Network locations");
+            title.resize(size.columns);
+            title.color(Color::InvertedText.into());
+            title.background(Color::InvertedBackground.into());
+            let mut fabric = Fabric::from(title);
+            if size.rows == 1 {
+                return fabric;
+            }
+
+            let rows: usize = size.rows - 2;
+            if self.state.mounts().is_empty() {
+                let message = "No network locations are configured (see `mounts` in the README).";
+                fabric =
+                    fabric.quilt_bottom(Fabric::center(message, Size::new(rows, size.columns)));
+                return fabric.quilt_bottom(self.render_status(size.columns));
+            }
+
+            let top: usize = self.state.top();
+            let yarns: Vec<Yarn> = self
+                .state
+                .mounts()
+                .iter()
+                .enumerate()
+                .skip(top)
+                .take(rows)
+                .map(|(index, mount)| {
+                    let mut yarn: Yarn = self.render_mount(mount, self.state.is_mounted(index));
+                    yarn.resize(size.columns);
+                    if index == self.state.selected() {
+                        yarn.color(Color::InvertedText.into());
+                        yarn.background(Color::Highlight.into());
+                    }
+                    yarn
+                })
+                .collect();
+            fabric = fabric.quilt_bottom(Fabric::from(yarns));
+            fabric.pad_bottom(size.rows - 1);
+
+            fabric.quilt_bottom(self.render_status(size.columns))
+        }
+    }
+
+    impl Mounts {
+        /// Return the yarn for a network location (whether it is mounted, its name, its kind, its
+        /// source, and its mount point).
+        fn render_mount(&self, mount: &MountConfig, mounted: bool) -> Yarn {
+            let mut marker = Yarn::from(match mounted {
+                true => "● ",
+                false => "○ ",
+            });
+            if mounted {
+                marker.color(Color::MountPoint.into());
+            }
+            let kind: &str = match mount.kind() {
+                MountKind::Sshfs => "sshfs",
+                MountKind::Smb => "smb",
+            };
+            let mut details = Yarn::from(format!(
+                "  {} {} → {}",
+                kind,
+                mount.source(),
+                mount.mount_point().to_string_lossy()
+            ));
+            details.color(Color::GrayedText.into());
+            marker.concat(Yarn::from(mount.name())).concat(details)
+        }
+
+        /// Return the fabric for the status line.
+        fn render_status(&self, columns: usize) -> Fabric {
+            let mut status = Yarn::from(self.state.message().as_deref().unwrap_or_default());
+            status.resize(columns);
+            status.color(Color::InvertedText.into());
+            status.background(Color::InvertedBackground.into());
+            Fabric::from(status)
+        }
+    }
+
+    impl Inspect for Mounts {
+        fn inspect(&self) -> Inspection {
+            self.state.inspect()
+        }
+    }
+}
+pub use mounts::Mounts;
+
+mod event {
+    use term::TermEvent;
+
+    pub enum Event {
+        TermEvent(TermEvent),
+    }
+}
+pub use event::Event;
+
+mod state {
+    use super::{Action, Effect, Props};
+    use crate::config::MountConfig;
+    use crate::inspect::{Inspect, Inspection};
+    use crate::stateful::Stateful;
+
+    use rend::Size;
+
+    use std::fs;
+    use std::os::unix::fs::MetadataExt;
+    use std::path::Path;
+
+    /// Return if a file system is mounted on the directory (if its device is different than the
+    /// device of its parent).
+    fn is_mount_point(dir: &Path) -> bool {
+        let parent: &Path = match dir.parent() {
+            Some(parent) => parent,
+            None => return true,
+        };
+        match (fs::metadata(dir), fs::metadata(parent)) {
+            (Ok(metadata), Ok(parent_metadata)) => metadata.dev() != parent_metadata.dev(),
+            _ => false,
+        }
+    }
+
+    pub struct State {
+        mounts: Vec<MountConfig>,
+        /// Whether each location is mounted.
+        mounted: Vec<bool>,
+        size: Size,
+        /// The index of the selected location.
+        selected: usize,
+        /// The index of the first visible location.
+        top: usize,
+        /// The index of the location that is being mounted (which is browsed once it is mounted).
+        mounting: Option<usize>,
+        /// A message shown in the status line.
+        message: Option<String>,
+    }
+
+    impl From<Props> for State {
+        fn from(props: Props) -> Self {
+            let mut state = Self {
+                mounted: Vec::new(),
+                mounts: props.mounts,
+                size: props.size,
+                selected: 0,
+                top: 0,
+                mounting: None,
+                message: None,
+            };
+            state.check_mounted();
+            state
+        }
+    }
+
+    impl State {
+        pub fn mounts(&self) -> &[MountConfig] {
+            &self.mounts
+        }
+
+        /// Return whether the location at `index` is mounted.
+        pub fn is_mounted(&self, index: usize) -> bool {
+            self.mounted.get(index).copied().unwrap_or_default()
+        }
+
+        pub fn selected(&self) -> usize {
+            self.selected
+        }
+
+        pub fn top(&self) -> usize {
+            self.top
+        }
+
+        pub fn message(&self) -> &Option<String> {
+            &self.message
+        }
+
+        /// Check which of the locations are mounted.
+        fn check_mounted(&mut self) {
+            self.mounted = self
+                .mounts
+                .iter()
+                .map(|mount| is_mount_point(&mount.mount_point()))
+                .collect();
+        }
+
+        /// Return the number of rows available for showing the locations.
+        fn rows(&self) -> usize {
+            self.size.rows.saturating_sub(2)
+        }
+
+        /// Move the selection to the location at `selected` (clamped to the locations).
+        fn select(&mut self, selected: usize) {
+            self.selected = selected.min(self.mounts.len().saturating_sub(1));
+            let rows: usize = self.rows().max(1);
+            if self.selected < self.top {
+                self.top = self.selected;
+            } else if self.selected >= self.top + rows {
+                self.top = self.selected + 1 - rows;
+            }
+        }
+
+        /// Handle a resize (which also happens after the mount command is run).
+        fn resize(&mut self, size: Size) -> Option<Effect> {
+            self.size = size;
+            self.select(self.selected);
+            self.check_mounted();
+
+            let index: usize = self.mounting.take()?;
+            let mount: &MountConfig = &self.mounts[index];
+            match self.mounted[index] {
+                true => Some(Effect::Browse {
+                    dir: mount.mount_point(),
+                }),
+                false => {
+                    self.message = Some(format!("Failed to mount {}.", mount.name()));
+                    Some(Effect::Bell)
+                }
+            }
+        }
+
+        fn down(&mut self) -> Option<Effect> {
+            if self.selected + 1 >= self.mounts.len() {
+                return Some(Effect::Bell);
+            }
+            self.select(self.selected + 1);
+            None
+        }
+
+        fn really_down(&mut self) -> Option<Effect> {
+            self.select(self.mounts.len());
+            None
+        }
+
+        fn up(&mut self) -> Option<Effect> {
+            if self.selected == 0 {
+                return Some(Effect::Bell);
+            }
+            self.select(self.selected - 1);
+            None
+        }
+
+        fn really_up(&mut self) -> Option<Effect> {
+            self.select(0);
+            None
+        }
+
+        fn refresh(&mut self) -> Option<Effect> {
+            self.check_mounted();
+            None
+        }
+
+        /// Browse the selected location (mounting it first if it is not mounted).
+        fn open(&mut self) -> Option<Effect> {
+            let mount: &MountConfig = match self.mounts.get(self.selected) {
+                Some(mount) => mount,
+                None => return Some(Effect::Bell),
+            };
+            if self.is_mounted(self.selected) {
+                return Some(Effect::Browse {
+                    dir: mount.mount_point(),
+                });
+            }
+            let mount: MountConfig = mount.clone();
+            self.mounting = Some(self.selected);
+            Some(Effect::Mount { mount })
+        }
+
+        /// Unmount the selected location.
+        fn unmount(&mut self) -> Option<Effect> {
+            if !self.is_mounted(self.selected) {
+                return Some(Effect::Bell);
+            }
+            let mount: MountConfig = self.mounts[self.selected].clone();
+            Some(Effect::Unmount { mount })
+        }
+    }
+
+    impl Stateful<Action, Effect> for State {
+        fn perform(&mut self, action: Action) -> Option<Effect> {
+            if !matches!(action, Action::Resize { .. }) {
+                self.message = None;
+            }
+            match action {
+                Action::Resize { size } => self.resize(size),
+                Action::Down => self.down(),
+                Action::ReallyDown => self.really_down(),
+                Action::Up => self.up(),
+                Action::ReallyUp => self.really_up(),
+                Action::Refresh => self.refresh(),
+                Action::Open => self.open(),
+                Action::Unmount => self.unmount(),
+                Action::Quit => Some(Effect::Quit),
+            }
+        }
+    }
+
+    impl Inspect for State {
+        fn inspect(&self) -> Inspection {
+            Inspection::new("Mounts")
+                .field("mounts", self.mounts.len())
+                .field(
+                    "mounted",
+                    self.mounted.iter().filter(|mounted| **mounted).count(),
+                )
+                .field("selected", self.selected)
+                .field("mounting", self.mounting)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::env;
+        use std::path::PathBuf;
+
+        #[test]
+        fn test_is_mount_point() {
+            assert!(is_mount_point(Path::new("/")));
+            assert!(!is_mount_point(&env::current_dir().unwrap().join("src")));
+        }
+
+        #[test]
+        fn test_open_unmounted() {
+            let mount: MountConfig = serde_yaml::from_str(
+                "{name: work, kind: sshfs, source: 'host:/', mount_point: /nonexistent/work}",
+            )
+            .unwrap();
+            let props = Props::builder()
+                .mounts(vec![mount])
+                .size(Size::new(10, 80))
+                .build();
+            let mut state = State::from(props);
+
+            assert!(matches!(
+                state.perform(Action::Open),
+                Some(Effect::Mount { .. })
+            ));
+            assert!(matches!(
+                state.perform(Action::Resize {
+                    size: Size::new(10, 80)
+                }),
+                Some(Effect::Bell)
+            ));
+            assert_eq!(
+                state.message(),
+                &Some(String::from("Failed to mount work."))
+            );
+            assert_eq!(
+                state.mounts()[0].mount_point(),
+                PathBuf::from("/nonexistent/work")
+            );
+        }
+    }
+}
+use state::State;
+
+mod action {
+    use rend::Size;
+
+    pub enum Action {
+        Resize { size: Size },
+        Down,
+        ReallyDown,
+        Up,
+        ReallyUp,
+        Refresh,
+        Open,
+        Unmount,
+        Quit,
+    }
+}
+use action::Action;
+
+mod effect {
+    use std::path::PathBuf;
+
+    use crate::config::MountConfig;
+
+    pub enum Effect {
+        /// Run the command for mounting the location.
+        Mount {
+            mount: MountConfig,
+        },
+        /// Run the command for unmounting the location.
+        Unmount {
+            mount: MountConfig,
+        },
+        /// Browse the directory that a location is mounted on.
+        Browse {
+            dir: PathBuf,
+        },
+        Quit,
+        Bell,
+    }
+}
+pub use effect::Effect;
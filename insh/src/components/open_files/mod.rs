@@ -0,0 +1,6 @@
+mod open_files;
+
+pub use open_files::{
+    get_open_files_request, Effect as OpenFilesEffect, Event as OpenFilesEvent, OpenFiles,
+    Props as OpenFilesProps,
+};
@@ -0,0 +1,556 @@
+mod props {
+    use rend::Size;
+
+    use std::path::PathBuf;
+
+    use typed_builder::TypedBuilder;
+
+    #[derive(TypedBuilder)]
+    pub struct Props {
+        pub dir: PathBuf,
+        pub size: Size,
+    }
+}
+pub use props::Props;
+
+mod open_files {
+    use super::{Action, Effect, Event, State};
+    use crate::color::Color;
+    use crate::components::common::{PhraseEffect, PhraseEvent};
+    use crate::inspect::{Inspect, Inspection};
+    use crate::stateful::Stateful;
+
+    use insh_api::OpenFile;
+    use rend::{Fabric, Size, Yarn};
+    use term::{Key, KeyEvent, KeyMods, TermEvent};
+    use til::Component;
+
+    use super::Props;
+
+    /// A list of the files in a directory that are open by processes (like `lsof +D`), which is
+    /// useful to check before unmounting or deleting the directory.
+    pub struct OpenFiles {
+        state: State,
+    }
+
+    impl Component<Props, Event, Effect> for OpenFiles {
+        fn new(props: Props) -> Self {
+            let state = State::from(props);
+            Self { state }
+        }
+
+        fn handle(&mut self, event: Event) -> Option<Effect> {
+            let action: Option<Action> = match event {
+                Event::Response(response) => Some(Action::HandleResponse(response)),
+                Event::TermEvent(TermEvent::Resize(size)) => Some(Action::Resize { size }),
+                Event::TermEvent(TermEvent::FocusGained | TermEvent::FocusLost) => None,
+                Event::TermEvent(TermEvent::KeyEvent(KeyEvent {
+                    key: Key::Escape, ..
+                })) if self.state.filtering() => Some(Action::ClearFilter),
+                Event::TermEvent(term_event) if self.state.filtering() => {
+                    let phrase_event = PhraseEvent::TermEvent(term_event);
+                    match self.state.phrase.handle(phrase_event) {
+                        Some(PhraseEffect::Enter { .. }) => Some(Action::AcceptFilter),
+                        Some(PhraseEffect::Quit) => Some(Action::ClearFilter),
+                        Some(PhraseEffect::Bell) => None,
+                        None => Some(Action::UpdateFilter),
+                    }
+                }
+                Event::TermEvent(TermEvent::KeyEvent(key_event)) => match key_event {
+                    KeyEvent {
+                        key: Key::Char('q'),
+                        mods: KeyMods::CONTROL,
+                    }
+                    | KeyEvent {
+                        key: Key::Char('q'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Quit),
+                    KeyEvent {
+                        key: Key::Char('j'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Down),
+                    KeyEvent {
+                        key: Key::Char('J'),
+                        mods: KeyMods::SHIFT,
+                    } => Some(Action::ReallyDown),
+                    KeyEvent {
+                        key: Key::Char('k'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Up),
+                    KeyEvent {
+                        key: Key::Char('K'),
+                        mods: KeyMods::SHIFT,
+                    } => Some(Action::ReallyUp),
+                    KeyEvent {
+                        key: Key::Char('r'),
+                        ..
+                    } => Some(Action::Refresh),
+                    KeyEvent {
+                        key: Key::Char('/'),
+                        ..
+                    } => Some(Action::OpenFilter),
+                    KeyEvent {
+                        key: Key::Escape, ..
+                    } if !self.state.phrase.value().is_empty() => Some(Action::ClearFilter),
+                    _ => None,
+                },
+            };
+
+            if let Some(action) = action {
+                self.state.perform(action)
+            } else {
+                Some(Effect::Bell)
+            }
+        }
+
+        fn render(&self, size: Size) -> Fabric {
+            if size.rows == 0 {
+                return Fabric::new(size);
+            }
+
+            let mut title = Yarn::from(format!(
+                "Open files in {}",
+                self.state.dir().to_string_lossy()
+            ));
+            title.resize(size.columns);
+            title.color(Color::InvertedText.into());
+            title.background(Color::InvertedBackground.into());
+            let mut fabric = Fabric::from(title);
+            if size.rows == 1 {
+                return fabric;
+            }
+
+            let rows: usize = size.rows - 2;
+            if let Some(error) = self.state.error() {
+                fabric = fabric.quilt_bottom(Fabric::center(error, Size::new(rows, size.columns)));
+                return fabric.quilt_bottom(self.render_status(size.columns));
+            }
+
+            let top: usize = self.state.top();
+            let yarns: Vec<Yarn> = self
+                .state
+                .visible()
+                .iter()
+                .enumerate()
+                .skip(top)
+                .take(rows)
+                .map(|(index, open_file)| {
+                    let mut yarn: Yarn = self.render_open_file(open_file);
+                    yarn.resize(size.columns);
+                    if index == self.state.selected() {
+                        yarn.color(Color::InvertedText.into());
+                        yarn.background(Color::Highlight.into());
+                    }
+                    yarn
+                })
+                .collect();
+            if !yarns.is_empty() {
+                fabric = fabric.quilt_bottom(Fabric::from(yarns));
+            }
+            fabric.pad_bottom(size.rows - 1);
+
+            fabric.quilt_bottom(self.render_status(size.columns))
+        }
+    }
+
+    impl OpenFiles {
+        /// Return the yarn for an open file (its path relative to the directory, and the pid and
+        /// the command line of the process that has it open).
+        fn render_open_file(&self, open_file: &OpenFile) -> Yarn {
+            let mut path = Yarn::from(format!("{}  ", self.state.relative_path(open_file)));
+            path.color(Color::LightGrayedText.into());
+            let mut pid = Yarn::from(format!("{:>7}  ", open_file.pid()));
+            pid.color(Color::GrayedText.into());
+            path.concat(pid).concat(Yarn::from(open_file.command()))
+        }
+
+        /// Return the fabric for the status line (or the filter if it is being edited).
+        fn render_status(&self, columns: usize) -> Fabric {
+            if self.state.filtering() {
+                let mut yarn = Yarn::from(format!("/{}", self.state.phrase.value()));
+                yarn.resize(columns);
+                yarn.color(Color::InvertedText.into());
+                yarn.background(Color::focus_or_important(true).into());
+                return Fabric::from(yarn);
+            }
+
+            let count: String = match self.state.phrase.value().is_empty() {
+                true => format!("{} open files", self.state.visible().len()),
+                false => format!(
+                    "/{}/ {} of {} open files",
+                    self.state.phrase.value(),
+                    self.state.visible().len(),
+                    self.state.open_files().len()
+                ),
+            };
+            let padding: usize = columns.saturating_sub(count.chars().count());
+            let mut status = Yarn::from(format!("{}{}", " ".repeat(padding), count));
+            status.resize(columns);
+            status.color(Color::InvertedText.into());
+            status.background(Color::InvertedBackground.into());
+            Fabric::from(status)
+        }
+    }
+
+    impl Inspect for OpenFiles {
+        fn inspect(&self) -> Inspection {
+            self.state.inspect()
+        }
+    }
+}
+pub use open_files::OpenFiles;
+
+mod event {
+    use insh_api::Response;
+    use term::TermEvent;
+
+    pub enum Event {
+        Response(Response),
+        TermEvent(TermEvent),
+    }
+}
+pub use event::Event;
+
+mod state {
+    use super::{Action, Effect, Props};
+    use crate::components::common::Phrase;
+    use crate::inspect::{Inspect, Inspection};
+    use crate::stateful::Stateful;
+
+    use insh_api::{
+        GetOpenFilesRequestParams, OpenFile, Request, RequestParams, Response, ResponseParams,
+    };
+    use rend::Size;
+
+    use std::path::{Path, PathBuf};
+
+    /// Return the request for the files in `dir` that are open by processes.
+    pub fn get_open_files_request(dir: &Path) -> Request {
+        Request::builder()
+            .params(RequestParams::GetOpenFiles(
+                GetOpenFilesRequestParams::builder()
+                    .dir(dir.to_path_buf())
+                    .build(),
+            ))
+            .build()
+    }
+
+    pub struct State {
+        dir: PathBuf,
+        size: Size,
+        open_files: Vec<OpenFile>,
+        /// The open files that match the filter.
+        visible: Vec<OpenFile>,
+        /// The index of the selected open file (of the visible open files).
+        selected: usize,
+        /// The index of the first visible open file.
+        top: usize,
+        /// An error getting the open files.
+        error: Option<String>,
+        /// Whether the filter is being edited.
+        filtering: bool,
+        /// The filter (the open files whose path or command contains it are shown).
+        pub phrase: Phrase,
+    }
+
+    impl From<Props> for State {
+        fn from(props: Props) -> Self {
+            Self {
+                dir: props.dir,
+                size: props.size,
+                open_files: Vec::new(),
+                visible: Vec::new(),
+                selected: 0,
+                top: 0,
+                error: None,
+                filtering: false,
+                phrase: Phrase::default(),
+            }
+        }
+    }
+
+    impl State {
+        pub fn dir(&self) -> &Path {
+            &self.dir
+        }
+
+        pub fn open_files(&self) -> &[OpenFile] {
+            &self.open_files
+        }
+
+        /// Return the open files that match the filter.
+        pub fn visible(&self) -> &[OpenFile] {
+            &self.visible
+        }
+
+        pub fn selected(&self) -> usize {
+            self.selected
+        }
+
+        pub fn top(&self) -> usize {
+            self.top
+        }
+
+        pub fn error(&self) -> &Option<String> {
+            &self.error
+        }
+
+        pub fn filtering(&self) -> bool {
+            self.filtering
+        }
+
+        /// Return the path of an open file relative to the directory.
+        pub fn relative_path(&self, open_file: &OpenFile) -> String {
+            let path: &Path = open_file
+                .path()
+                .strip_prefix(&self.dir)
+                .unwrap_or(open_file.path());
+            match path.as_os_str().is_empty() {
+                true => String::from("."),
+                false => path.to_string_lossy().to_string(),
+            }
+        }
+
+        /// Return the number of rows available for showing the open files.
+        fn rows(&self) -> usize {
+            self.size.rows.saturating_sub(2)
+        }
+
+        /// Keep the selected open file visible.
+        fn scroll_to_selected(&mut self) {
+            let rows: usize = self.rows().max(1);
+            if self.selected < self.top {
+                self.top = self.selected;
+            } else if self.selected >= self.top + rows {
+                self.top = self.selected + 1 - rows;
+            }
+        }
+
+        /// Move the selection to the open file at `selected` (clamped to the visible open files).
+        fn select(&mut self, selected: usize) {
+            self.selected = selected.min(self.visible.len().saturating_sub(1));
+            self.scroll_to_selected();
+        }
+
+        /// Update the visible open files for the filter (ignoring case).
+        fn apply_filter(&mut self) {
+            let filter: String = self.phrase.value().to_lowercase();
+            self.visible = self
+                .open_files
+                .iter()
+                .filter(|open_file| {
+                    self.relative_path(open_file)
+                        .to_lowercase()
+                        .contains(&filter)
+                        || open_file.command().to_lowercase().contains(&filter)
+                })
+                .cloned()
+                .collect();
+            self.top = 0;
+            self.select(self.selected);
+        }
+
+        fn handle_response(&mut self, response: Response) -> Option<Effect> {
+            let params = match response.into_params() {
+                ResponseParams::GetOpenFiles(params) => params,
+                _ => {
+                    #[cfg(feature = "logging")]
+                    log::error!("Unexpected response parameters.");
+                    return None;
+                }
+            };
+            match params.into_result() {
+                Ok(open_files) => {
+                    self.open_files = open_files;
+                    self.error = None;
+                    self.apply_filter();
+                }
+                Err(error) => {
+                    self.error = Some(format!("Failed to get the open files: {}", error));
+                }
+            }
+            None
+        }
+
+        fn resize(&mut self, size: Size) -> Option<Effect> {
+            self.size = size;
+            self.scroll_to_selected();
+            None
+        }
+
+        fn down(&mut self) -> Option<Effect> {
+            if self.selected + 1 >= self.visible.len() {
+                return Some(Effect::Bell);
+            }
+            self.select(self.selected + 1);
+            None
+        }
+
+        fn really_down(&mut self) -> Option<Effect> {
+            self.select(self.visible.len());
+            None
+        }
+
+        fn up(&mut self) -> Option<Effect> {
+            if self.selected == 0 {
+                return Some(Effect::Bell);
+            }
+            self.select(self.selected - 1);
+            None
+        }
+
+        fn really_up(&mut self) -> Option<Effect> {
+            self.select(0);
+            None
+        }
+
+        fn refresh(&mut self) -> Option<Effect> {
+            let request: Request = get_open_files_request(&self.dir);
+            Some(Effect::Request(request))
+        }
+
+        fn open_filter(&mut self) -> Option<Effect> {
+            self.filtering = true;
+            None
+        }
+
+        fn update_filter(&mut self) -> Option<Effect> {
+            self.apply_filter();
+            None
+        }
+
+        fn accept_filter(&mut self) -> Option<Effect> {
+            self.filtering = false;
+            None
+        }
+
+        fn clear_filter(&mut self) -> Option<Effect> {
+            self.filtering = false;
+            self.phrase = Phrase::default();
+            self.apply_filter();
+            None
+        }
+    }
+
+    impl Stateful<Action, Effect> for State {
+        fn perform(&mut self, action: Action) -> Option<Effect> {
+            match action {
+                Action::HandleResponse(response) => self.handle_response(response),
+                Action::Resize { size } => self.resize(size),
+                Action::Down => self.down(),
+                Action::ReallyDown => self.really_down(),
+                Action::Up => self.up(),
+                Action::ReallyUp => self.really_up(),
+                Action::Refresh => self.refresh(),
+                Action::OpenFilter => self.open_filter(),
+                Action::UpdateFilter => self.update_filter(),
+                Action::AcceptFilter => self.accept_filter(),
+                Action::ClearFilter => self.clear_filter(),
+                Action::Quit => Some(Effect::Quit),
+            }
+        }
+    }
+
+    impl Inspect for State {
+        fn inspect(&self) -> Inspection {
+            Inspection::new("OpenFiles")
+                .field("dir", &self.dir)
+                .field("open_files", self.open_files.len())
+                .field("visible", self.visible.len())
+                .field("selected", self.selected)
+                .field("top", self.top)
+                .field("filter", self.phrase.value())
+                .field("error", &self.error)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::components::common::PhraseProps;
+        use insh_api::GetOpenFilesResponseParams;
+        use til::Component;
+        use uuid::Uuid;
+
+        /// Return a file in `/mnt/usb` open by a process.
+        fn open_file(path: &str, pid: u32, command: &str) -> OpenFile {
+            OpenFile::builder()
+                .path(Path::new("/mnt/usb").join(path))
+                .pid(pid)
+                .command(String::from(command))
+                .build()
+        }
+
+        #[test]
+        fn test_filter() {
+            let props = Props::builder()
+                .dir(PathBuf::from("/mnt/usb"))
+                .size(Size::new(10, 80))
+                .build();
+            let mut state = State::from(props);
+            let response = Response::builder()
+                .uuid(Uuid::new_v4())
+                .params(ResponseParams::GetOpenFiles(
+                    GetOpenFilesResponseParams::builder()
+                        .result(Ok(vec![
+                            open_file("music/song.mp3", 10, "mpv song.mp3"),
+                            open_file("notes.txt", 20, "vim notes.txt"),
+                            open_file("photos/cat.png", 30, "feh cat.png"),
+                        ]))
+                        .build(),
+                ))
+                .build();
+            state.perform(Action::HandleResponse(response));
+            state.perform(Action::ReallyDown);
+
+            state.phrase = Phrase::new(
+                PhraseProps::builder()
+                    .value(Some(String::from("VIM")))
+                    .build(),
+            );
+            state.perform(Action::UpdateFilter);
+
+            assert_eq!(state.visible().len(), 1);
+            assert_eq!(state.visible()[0].pid(), 20);
+            assert_eq!(state.selected(), 0);
+
+            state.perform(Action::ClearFilter);
+
+            assert_eq!(state.visible().len(), 3);
+        }
+    }
+}
+pub use state::get_open_files_request;
+use state::State;
+
+mod action {
+    use insh_api::Response;
+    use rend::Size;
+
+    pub enum Action {
+        HandleResponse(Response),
+        Resize { size: Size },
+        Down,
+        ReallyDown,
+        Up,
+        ReallyUp,
+        Refresh,
+        OpenFilter,
+        UpdateFilter,
+        AcceptFilter,
+        ClearFilter,
+        Quit,
+    }
+}
+use action::Action;
+
+mod effect {
+    use insh_api::Request;
+
+    pub enum Effect {
+        Request(Request),
+        Quit,
+        Bell,
+    }
+}
+pub use effect::Effect;
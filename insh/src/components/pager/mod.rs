@@ -0,0 +1,5 @@
+mod pager;
+
+pub use pager::{
+    read_chunk_request, Effect as PagerEffect, Event as PagerEvent, Pager, Props as PagerProps,
+};
@@ -0,0 +1,909 @@
+mod props {
+    use rend::Size;
+
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use typed_builder::TypedBuilder;
+
+    use crate::config::Config;
+
+    #[derive(TypedBuilder)]
+    pub struct Props {
+        pub path: PathBuf,
+        pub size: Size,
+        pub config: Arc<Config>,
+    }
+}
+pub use props::Props;
+
+mod pager {
+    use super::{Action, Effect, Event, Prompt, State};
+    use crate::color::Color;
+    use crate::components::common::{PhraseEffect, PhraseEvent};
+    use crate::inspect::{Inspect, Inspection};
+    use crate::stateful::Stateful;
+    use crate::string::DetabExt;
+
+    use rend::{Fabric, Size, Yarn};
+    use term::{Key, KeyEvent, KeyMods, TermEvent};
+    use text_encoding::Encoding;
+    use til::Component;
+
+    use super::Props;
+
+    /// A full-screen pager (like `less`) which reads the file in chunks as it is scrolled.
+    pub struct Pager {
+        state: State,
+    }
+
+    impl Component<Props, Event, Effect> for Pager {
+        fn new(props: Props) -> Self {
+            let state = State::from(props);
+            Self { state }
+        }
+
+        fn handle(&mut self, event: Event) -> Option<Effect> {
+            let action: Option<Action> = match event {
+                Event::Response(response) => Some(Action::HandleResponse(response)),
+                Event::TimedOut => Some(Action::TimeOut),
+                Event::TermEvent(TermEvent::Resize(size)) => Some(Action::Resize { size }),
+                Event::TermEvent(TermEvent::FocusGained | TermEvent::FocusLost) => None,
+                Event::TermEvent(TermEvent::KeyEvent(KeyEvent {
+                    key: Key::Escape, ..
+                })) if self.state.prompt().is_some() => Some(Action::ClosePrompt),
+                Event::TermEvent(term_event) if self.state.prompt().is_some() => {
+                    let phrase_event = PhraseEvent::TermEvent(term_event);
+                    match self.state.phrase.handle(phrase_event) {
+                        Some(PhraseEffect::Enter { phrase }) => Some(Action::Submit { phrase }),
+                        Some(PhraseEffect::Quit) => Some(Action::ClosePrompt),
+                        Some(PhraseEffect::Bell) => Some(Action::Bell),
+                        None => return None,
+                    }
+                }
+                Event::TermEvent(TermEvent::KeyEvent(key_event)) => match key_event {
+                    KeyEvent {
+                        key: Key::Char('q'),
+                        mods: KeyMods::CONTROL,
+                    }
+                    | KeyEvent {
+                        key: Key::Char('q'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Quit),
+                    KeyEvent {
+                        key: Key::Char('j'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Down),
+                    KeyEvent {
+                        key: Key::Char('J'),
+                        mods: KeyMods::SHIFT,
+                    } => Some(Action::ReallyDown),
+                    KeyEvent {
+                        key: Key::Char('k'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Up),
+                    KeyEvent {
+                        key: Key::Char('K'),
+                        mods: KeyMods::SHIFT,
+                    } => Some(Action::ReallyUp),
+                    KeyEvent {
+                        key: Key::Char('/'),
+                        ..
+                    } => Some(Action::OpenPrompt {
+                        prompt: Prompt::Search,
+                    }),
+                    KeyEvent {
+                        key: Key::Char(':'),
+                        ..
+                    } => Some(Action::OpenPrompt {
+                        prompt: Prompt::Goto,
+                    }),
+                    KeyEvent {
+                        key: Key::Char('n'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::NextMatch),
+                    KeyEvent {
+                        key: Key::Char('N'),
+                        mods: KeyMods::SHIFT,
+                    } => Some(Action::PreviousMatch),
+                    KeyEvent {
+                        key: Key::Char('w'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::ToggleWrap),
+                    KeyEvent {
+                        key: Key::Char('h'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Left),
+                    KeyEvent {
+                        key: Key::Char('l'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Right),
+                    _ => None,
+                },
+            };
+
+            if let Some(action) = action {
+                self.state.perform(action)
+            } else {
+                Some(Effect::Bell)
+            }
+        }
+
+        fn render(&self, size: Size) -> Fabric {
+            if size.rows == 0 {
+                return Fabric::new(size);
+            }
+
+            let mut title = Yarn::from(self.state.path().to_string_lossy().to_string());
+            title.resize(size.columns);
+            title.color(Color::InvertedText.into());
+            title.background(Color::InvertedBackground.into());
+            let mut fabric = Fabric::from(title);
+            if size.rows == 1 {
+                return fabric;
+            }
+
+            let rows: usize = size.rows - 2;
+            if let Some(error) = self.state.error() {
+                fabric = fabric.quilt_bottom(Fabric::center(error, Size::new(rows, size.columns)));
+                return fabric.quilt_bottom(self.render_status(size.columns));
+            }
+
+            let top: usize = self.state.top();
+            let gutter_width: usize = (top + rows).to_string().len();
+            let mut yarns: Vec<Yarn> = Vec::with_capacity(rows);
+            for (index, line) in self.state.lines().range(top, top + rows).iter().enumerate() {
+                let line_number: usize = top + index + 1;
+                let mut gutter = Yarn::from(format!("{:>gutter_width$} ", line_number));
+                match self.state.match_line() == Some(top + index) {
+                    true => gutter.color(Color::Highlight.into()),
+                    false => gutter.color(Color::GrayedText.into()),
+                }
+
+                let text: String = line.text().detab(self.state.tab_width());
+                let shown: String = match self.state.show_whitespace() {
+                    true => line.text().detab_showing_whitespace(self.state.tab_width()),
+                    false => text.clone(),
+                };
+                let highlighted: Yarn = self.highlight_matches(&text, &shown);
+                match self.state.wrap() {
+                    true => {
+                        let yarn: Yarn = gutter.concat(highlighted);
+                        for mut row in yarn.wrap(size.columns, gutter_width + 1) {
+                            if yarns.len() == rows {
+                                break;
+                            }
+                            row.resize(size.columns);
+                            yarns.push(row);
+                        }
+                    }
+                    false => {
+                        let width: usize = size.columns.saturating_sub(gutter.len());
+                        let mut yarn: Yarn =
+                            gutter.concat(highlighted.window(self.state.column_offset(), width));
+                        yarn.resize(size.columns);
+                        yarns.push(yarn);
+                    }
+                }
+            }
+            if !yarns.is_empty() {
+                fabric = fabric.quilt_bottom(Fabric::from(yarns));
+            }
+            fabric.pad_bottom(size.rows - 1);
+
+            fabric.quilt_bottom(self.render_status(size.columns))
+        }
+    }
+
+    impl Pager {
+        /// Return the yarn for the text of a line with the matches of the search highlighted.
+        ///
+        /// The matches are found in `text` and the characters of `shown` (the text as it is shown,
+        /// which has the same number of characters) are highlighted.
+        fn highlight_matches(&self, text: &str, shown: &str) -> Yarn {
+            let search: &str = match self.state.search() {
+                Some(search) if !search.is_empty() => search,
+                _ => return Yarn::from(shown),
+            };
+
+            let shown: Vec<char> = shown.chars().collect();
+            let slice = |start: usize, end: usize| Yarn::from(shown[start..end].to_vec());
+            let search_len: usize = search.chars().count();
+
+            let mut yarn = Yarn::new();
+            let mut rest: &str = text;
+            let mut position: usize = 0;
+            while let Some(start) = rest.find(search) {
+                let match_position: usize = position + rest[..start].chars().count();
+                yarn = yarn.concat(slice(position, match_position));
+                position = match_position + search_len;
+                let mut match_yarn = slice(match_position, position);
+                match_yarn.color(Color::InvertedText.into());
+                match_yarn.background(Color::Highlight.into());
+                yarn = yarn.concat(match_yarn);
+                rest = &rest[start + search.len()..];
+            }
+            yarn.concat(slice(position, shown.len()))
+        }
+
+        /// Return the fabric for the status line (or the prompt if it is open).
+        fn render_status(&self, columns: usize) -> Fabric {
+            if let Some(prompt) = self.state.prompt() {
+                let mut yarn =
+                    Yarn::from(format!("{}{}", prompt.prefix(), self.state.phrase.value()));
+                yarn.resize(columns);
+                yarn.color(Color::InvertedText.into());
+                yarn.background(Color::focus_or_important(true).into());
+                return Fabric::from(yarn);
+            }
+
+            let mut position: String = match self.state.percentage() {
+                Some(percentage) => format!("line {} {}%", self.state.top() + 1, percentage),
+                None => "Loading...".to_string(),
+            };
+            // The encoding is only shown if the file is decoded.
+            if let Some(encoding) = self
+                .state
+                .encoding()
+                .filter(|encoding| *encoding != Encoding::Utf8)
+            {
+                position = format!("{} {}", encoding, position);
+            }
+            let message: &str = self.state.message().as_deref().unwrap_or_default();
+            let padding: usize = columns.saturating_sub(message.chars().count() + position.len());
+            let mut status = Yarn::from(format!("{}{}{}", message, " ".repeat(padding), position));
+            status.resize(columns);
+            status.color(Color::InvertedText.into());
+            status.background(Color::InvertedBackground.into());
+            Fabric::from(status)
+        }
+    }
+
+    impl Inspect for Pager {
+        fn inspect(&self) -> Inspection {
+            self.state.inspect()
+        }
+    }
+}
+pub use pager::Pager;
+
+mod event {
+    use insh_api::Response;
+    use term::TermEvent;
+
+    pub enum Event {
+        Response(Response),
+        /// The request for reading a chunk of the file timed out.
+        TimedOut,
+        TermEvent(TermEvent),
+    }
+}
+pub use event::Event;
+
+mod prompt {
+    /// A prompt that is shown in place of the status line.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Prompt {
+        /// A prompt for a phrase to search for.
+        Search,
+        /// A prompt for a line number to go to.
+        Goto,
+    }
+
+    impl Prompt {
+        /// Return the prefix that is shown before the input of the prompt.
+        pub fn prefix(&self) -> &'static str {
+            match self {
+                Self::Search => "/",
+                Self::Goto => ":",
+            }
+        }
+    }
+}
+use prompt::Prompt;
+
+mod state {
+    use super::{Action, Effect, Prompt, Props};
+    use crate::components::common::{Lines, Phrase};
+    use crate::inspect::{Inspect, Inspection};
+    use crate::stateful::Stateful;
+    use crate::string::DetabExt;
+
+    use insh_api::{
+        ReadFileRequestParams, ReadFileResponseParams, Request, RequestParams, Response,
+        ResponseParams,
+    };
+    use rend::{Size, Yarn};
+    use text_encoding::{Encoding, Transcoder, SAMPLE_LEN};
+
+    use std::path::{Path, PathBuf};
+
+    /// The number of bytes that are read at a time.
+    const CHUNK_LEN: u64 = 64 * 1024;
+
+    /// Return the request for reading the chunk of the file at `path` which starts at `offset`.
+    pub fn read_chunk_request(path: &Path, offset: u64) -> Request {
+        Request::builder()
+            .params(RequestParams::ReadFile(
+                ReadFileRequestParams::builder()
+                    .path(path.to_path_buf())
+                    .offset(offset)
+                    .len(CHUNK_LEN)
+                    .build(),
+            ))
+            .build()
+    }
+
+    /// A position to jump to once enough of the file is read.
+    enum Jump {
+        /// The end of the file.
+        End,
+        /// A line (starting at zero).
+        Line(usize),
+        /// The next line at or after a line containing the search phrase.
+        Search { from: usize },
+    }
+
+    pub struct State {
+        path: PathBuf,
+        size: Size,
+        tab_width: usize,
+        /// Whether spaces and tabs are shown.
+        show_whitespace: bool,
+        /// Whether long lines are wrapped.
+        wrap: bool,
+        /// The number of columns that the lines are scrolled to the right by (when they are not
+        /// wrapped).
+        column_offset: usize,
+        lines: Lines,
+        /// The number of bytes of the file that are read.
+        read_bytes: u64,
+        /// The encoding of the file (detected from the first chunk, nothing if it looks binary).
+        encoding: Option<Encoding>,
+        /// Decodes the chunks of files that are not UTF-8.
+        transcoder: Option<Transcoder>,
+        /// The size of the file (once the first chunk is read).
+        file_size: Option<u64>,
+        /// Whether or not the whole file is read.
+        eof: bool,
+        /// Whether a chunk of the file is being read (the first chunk is requested before the pager
+        /// is made).
+        reading: bool,
+        /// An error reading the file.
+        error: Option<String>,
+        /// The index of the first visible line.
+        top: usize,
+        /// The open prompt (if any).
+        prompt: Option<Prompt>,
+        pub phrase: Phrase,
+        /// The last phrase that was searched for.
+        search: Option<String>,
+        /// The index of the line of the current match of the search.
+        match_line: Option<usize>,
+        /// A position to jump to once enough of the file is read.
+        jump: Option<Jump>,
+        /// A message shown in the status line.
+        message: Option<String>,
+    }
+
+    impl From<Props> for State {
+        fn from(props: Props) -> Self {
+            Self {
+                tab_width: props.config.general().tab_width_for(&props.path),
+                path: props.path,
+                size: props.size,
+                show_whitespace: props.config.general().show_whitespace(),
+                wrap: props.config.general().wrap(),
+                column_offset: 0,
+                lines: Lines::default(),
+                read_bytes: 0,
+                encoding: None,
+                transcoder: None,
+                file_size: None,
+                eof: false,
+                reading: true,
+                error: None,
+                top: 0,
+                prompt: None,
+                phrase: Phrase::default(),
+                search: None,
+                match_line: None,
+                jump: None,
+                message: None,
+            }
+        }
+    }
+
+    impl State {
+        pub fn path(&self) -> &Path {
+            &self.path
+        }
+
+        pub fn tab_width(&self) -> usize {
+            self.tab_width
+        }
+
+        pub fn show_whitespace(&self) -> bool {
+            self.show_whitespace
+        }
+
+        pub fn wrap(&self) -> bool {
+            self.wrap
+        }
+
+        pub fn column_offset(&self) -> usize {
+            self.column_offset
+        }
+
+        pub fn lines(&self) -> &Lines {
+            &self.lines
+        }
+
+        pub fn error(&self) -> &Option<String> {
+            &self.error
+        }
+
+        pub fn top(&self) -> usize {
+            self.top
+        }
+
+        pub fn prompt(&self) -> Option<Prompt> {
+            self.prompt
+        }
+
+        pub fn search(&self) -> Option<&str> {
+            self.search.as_deref()
+        }
+
+        pub fn match_line(&self) -> Option<usize> {
+            self.match_line
+        }
+
+        pub fn message(&self) -> &Option<String> {
+            &self.message
+        }
+
+        /// Return the encoding of the file (once the first chunk is read).
+        pub fn encoding(&self) -> Option<Encoding> {
+            self.encoding
+        }
+
+        /// Return the percentage of the file that is at or above the bottom of the screen (once
+        /// the size of the file is known).
+        pub fn percentage(&self) -> Option<u64> {
+            let file_size: u64 = self.file_size?;
+            if file_size == 0 {
+                return Some(100);
+            }
+            let mut end: u64 = match self.lines.get(self.top + self.rows()) {
+                Some(line) => line.start(),
+                None => self.lines.loaded_bytes(),
+            };
+            // The lines of transcoded files are measured in UTF-8, so scale them to the file.
+            if self.transcoder.is_some() && self.lines.loaded_bytes() > 0 {
+                end = end * self.read_bytes / self.lines.loaded_bytes();
+            }
+            Some(end.min(file_size) * 100 / file_size)
+        }
+
+        /// Return the number of rows available for showing the lines of the file.
+        fn rows(&self) -> usize {
+            self.size.rows.saturating_sub(2)
+        }
+
+        /// Return the largest index of the first visible line (where the last line that is read
+        /// is at the bottom).
+        fn max_top(&self) -> usize {
+            if !self.wrap {
+                return self.lines.len().saturating_sub(self.rows());
+            }
+
+            // NOTE: The gutter is at most as wide as the largest line number that can be visible.
+            let indent: usize = (self.lines.len() + self.rows()).to_string().len() + 1;
+            let mut rows: usize = 0;
+            let mut top: usize = self.lines.len();
+            while let Some(line) = top.checked_sub(1).and_then(|index| self.lines.get(index)) {
+                let len: usize = indent + line.text().detab(self.tab_width).chars().count();
+                rows += Yarn::wrapped_rows(len, self.size.columns, indent);
+                if rows > self.rows() {
+                    break;
+                }
+                top -= 1;
+            }
+            top.min(self.lines.len().saturating_sub(1))
+        }
+
+        /// Return the effect for reading the next chunk of the file (unless the whole file is
+        /// read or a chunk is already being read).
+        fn read_more(&mut self) -> Option<Effect> {
+            if self.eof || self.error.is_some() || self.reading {
+                return None;
+            }
+            let request: Request = read_chunk_request(&self.path, self.read_bytes);
+            self.reading = true;
+            Some(Effect::Request(request))
+        }
+
+        /// Read the next chunk of the file if the lines that are read are close to running out.
+        fn prefetch(&mut self) -> Option<Effect> {
+            if self.top + 2 * self.rows() >= self.lines.len() {
+                return self.read_more();
+            }
+            None
+        }
+
+        /// Jump to the position if enough of the file is read, otherwise read more of the file.
+        fn jump(&mut self) -> Option<Effect> {
+            let jump: Jump = match self.jump.take() {
+                Some(jump) => jump,
+                None => return self.prefetch(),
+            };
+
+            match jump {
+                Jump::End => {
+                    if !self.eof {
+                        self.jump = Some(jump);
+                        return self.read_more();
+                    }
+                    self.top = self.max_top();
+                }
+                Jump::Line(line) => {
+                    if line >= self.lines.len() && !self.eof {
+                        self.jump = Some(jump);
+                        return self.read_more();
+                    }
+                    if line >= self.lines.len() {
+                        self.message = Some(format!("The file has {} lines.", self.lines.len()));
+                    }
+                    self.top = line.min(self.max_top());
+                }
+                Jump::Search { from } => {
+                    let search: &str = self.search.as_deref().unwrap_or_default();
+                    match self.lines.find(search, from, true) {
+                        Some(line) => {
+                            self.match_line = Some(line);
+                            self.top = line.min(self.max_top());
+                        }
+                        None if !self.eof => {
+                            self.jump = Some(Jump::Search {
+                                from: self.lines.len(),
+                            });
+                            return self.read_more();
+                        }
+                        None => {
+                            self.message = Some("Pattern not found.".to_string());
+                            return Some(Effect::Bell);
+                        }
+                    }
+                }
+            }
+
+            self.prefetch()
+        }
+
+        fn handle_response(&mut self, response: Response) -> Option<Effect> {
+            self.reading = false;
+
+            let params: &ReadFileResponseParams = match response.params() {
+                ResponseParams::ReadFile(params) => params,
+                _ => {
+                    #[cfg(feature = "logging")]
+                    log::error!("Unexpected response parameters.");
+                    return None;
+                }
+            };
+
+            match params.result() {
+                Ok(chunk) => {
+                    let bytes: &[u8] = chunk.bytes();
+                    if self.read_bytes == 0 {
+                        self.encoding =
+                            text_encoding::detect(&bytes[..bytes.len().min(SAMPLE_LEN)]);
+                        self.transcoder = match self.encoding {
+                            Some(Encoding::Utf8) | None => None,
+                            Some(encoding) => Some(Transcoder::new(encoding)),
+                        };
+                    }
+                    self.read_bytes += bytes.len() as u64;
+                    // NOTE: An empty chunk means the file shrunk while it was being read.
+                    let eof: bool = chunk.eof() || bytes.is_empty();
+
+                    match &mut self.transcoder {
+                        Some(transcoder) => {
+                            self.lines.push(transcoder.transcode(bytes, eof).as_bytes())
+                        }
+                        None => self.lines.push(bytes),
+                    }
+                    self.file_size = Some(chunk.file_size());
+                    if eof {
+                        self.eof = true;
+                        self.lines.finish();
+                    }
+                }
+                Err(error) => {
+                    self.error = Some(format!("Failed to read the file: {}", error));
+                    return None;
+                }
+            }
+
+            self.jump()
+        }
+
+        /// Stop waiting for the chunk that is being read (it is read again once it is needed).
+        fn time_out(&mut self) -> Option<Effect> {
+            self.reading = false;
+            self.message = Some(String::from("Reading the file timed out."));
+            None
+        }
+
+        fn resize(&mut self, size: Size) -> Option<Effect> {
+            self.size = size;
+            self.top = self.top.min(self.max_top());
+            self.prefetch()
+        }
+
+        fn down(&mut self) -> Option<Effect> {
+            if self.top >= self.max_top() {
+                return match self.eof {
+                    true => Some(Effect::Bell),
+                    false => self.read_more(),
+                };
+            }
+            self.top += 1;
+            self.prefetch()
+        }
+
+        fn really_down(&mut self) -> Option<Effect> {
+            self.jump = Some(Jump::End);
+            self.jump()
+        }
+
+        fn up(&mut self) -> Option<Effect> {
+            if self.top == 0 {
+                return Some(Effect::Bell);
+            }
+            self.top -= 1;
+            None
+        }
+
+        fn really_up(&mut self) -> Option<Effect> {
+            self.top = 0;
+            None
+        }
+
+        /// Return the number of columns that the lines are scrolled by horizontally at a time
+        /// (half of the width of the text).
+        fn column_step(&self) -> usize {
+            let gutter_width: usize = (self.top + self.rows()).to_string().len() + 1;
+            (self.size.columns.saturating_sub(gutter_width) / 2).max(1)
+        }
+
+        fn left(&mut self) -> Option<Effect> {
+            if self.wrap || self.column_offset == 0 {
+                return Some(Effect::Bell);
+            }
+            self.column_offset = self.column_offset.saturating_sub(self.column_step());
+            None
+        }
+
+        fn right(&mut self) -> Option<Effect> {
+            let longest: usize = self
+                .lines
+                .range(self.top, self.top + self.rows())
+                .iter()
+                .map(|line| line.text().detab(self.tab_width).chars().count())
+                .max()
+                .unwrap_or(0);
+            if self.wrap || self.column_offset + self.column_step() >= longest {
+                return Some(Effect::Bell);
+            }
+            self.column_offset += self.column_step();
+            None
+        }
+
+        fn toggle_wrap(&mut self) -> Option<Effect> {
+            self.wrap = !self.wrap;
+            self.column_offset = 0;
+            self.top = self.top.min(self.max_top());
+            self.prefetch()
+        }
+
+        fn open_prompt(&mut self, prompt: Prompt) -> Option<Effect> {
+            self.prompt = Some(prompt);
+            self.phrase = Phrase::default();
+            None
+        }
+
+        fn close_prompt(&mut self) -> Option<Effect> {
+            self.prompt = None;
+            None
+        }
+
+        fn submit(&mut self, phrase: String) -> Option<Effect> {
+            let prompt: Prompt = self.prompt.take()?;
+            match prompt {
+                Prompt::Search => {
+                    if !phrase.is_empty() {
+                        self.search = Some(phrase);
+                    }
+                    if self.search.is_none() {
+                        return Some(Effect::Bell);
+                    }
+                    self.jump = Some(Jump::Search { from: self.top });
+                    self.jump()
+                }
+                Prompt::Goto => match phrase.trim().parse::<usize>() {
+                    Ok(line) if line > 0 => {
+                        self.jump = Some(Jump::Line(line - 1));
+                        self.jump()
+                    }
+                    _ => {
+                        self.message = Some(format!("Invalid line number {:?}.", phrase));
+                        Some(Effect::Bell)
+                    }
+                },
+            }
+        }
+
+        fn next_match(&mut self) -> Option<Effect> {
+            if self.search.is_none() {
+                return Some(Effect::Bell);
+            }
+            let from: usize = match self.match_line {
+                Some(line) => line + 1,
+                None => self.top,
+            };
+            self.jump = Some(Jump::Search { from });
+            self.jump()
+        }
+
+        fn previous_match(&mut self) -> Option<Effect> {
+            let search: &str = match &self.search {
+                Some(search) => search,
+                None => return Some(Effect::Bell),
+            };
+            let from: usize = self.match_line.unwrap_or(self.top);
+            match self.lines.find(search, from, false) {
+                Some(line) => {
+                    self.match_line = Some(line);
+                    self.top = line.min(self.max_top());
+                    None
+                }
+                None => {
+                    self.message = Some("Pattern not found.".to_string());
+                    Some(Effect::Bell)
+                }
+            }
+        }
+    }
+
+    impl Stateful<Action, Effect> for State {
+        fn perform(&mut self, action: Action) -> Option<Effect> {
+            if !matches!(action, Action::HandleResponse(_) | Action::Resize { .. }) {
+                self.message = None;
+            }
+
+            match action {
+                Action::HandleResponse(response) => self.handle_response(response),
+                Action::TimeOut => self.time_out(),
+                Action::Resize { size } => self.resize(size),
+                Action::Down => self.down(),
+                Action::ReallyDown => self.really_down(),
+                Action::Up => self.up(),
+                Action::ReallyUp => self.really_up(),
+                Action::OpenPrompt { prompt } => self.open_prompt(prompt),
+                Action::ClosePrompt => self.close_prompt(),
+                Action::Submit { phrase } => self.submit(phrase),
+                Action::NextMatch => self.next_match(),
+                Action::PreviousMatch => self.previous_match(),
+                Action::ToggleWrap => self.toggle_wrap(),
+                Action::Left => self.left(),
+                Action::Right => self.right(),
+                Action::Bell => Some(Effect::Bell),
+                Action::Quit => Some(Effect::Quit),
+            }
+        }
+    }
+
+    impl Inspect for State {
+        fn inspect(&self) -> Inspection {
+            Inspection::new("Pager")
+                .field("path", &self.path)
+                .field("top", self.top)
+                .field("file_size", self.file_size)
+                .field("encoding", self.encoding)
+                .field("wrap", self.wrap)
+                .field("column_offset", self.column_offset)
+                .field("eof", self.eof)
+                .field("reading", self.reading)
+                .field("error", &self.error)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::requester::send_over_socket;
+        use insh_api::FileChunk;
+        use std::sync::Arc;
+        use uuid::Uuid;
+
+        #[test]
+        fn test_read_more() {
+            let props = Props::builder()
+                .path(PathBuf::from("/r/notes.txt"))
+                .size(Size::new(10, 80))
+                .config(Arc::default())
+                .build();
+            let mut state = State::from(props);
+
+            let response = Response::builder()
+                .uuid(Uuid::new_v4())
+                .params(ResponseParams::ReadFile(
+                    ReadFileResponseParams::builder()
+                        .result(Ok(FileChunk::builder()
+                            .offset(0)
+                            .bytes(b"a\nb\n".to_vec())
+                            .file_size(CHUNK_LEN + 4)
+                            .build()))
+                        .build(),
+                ))
+                .build();
+            let request: Request = match state.perform(Action::HandleResponse(response)) {
+                Some(Effect::Request(request)) => request,
+                _ => panic!("Expected a request."),
+            };
+            let requests: Vec<Request> = send_over_socket(vec![request]);
+
+            match requests[0].params() {
+                RequestParams::ReadFile(params) => {
+                    assert_eq!(params.path(), Path::new("/r/notes.txt"));
+                    assert_eq!(params.offset(), 4);
+                    assert_eq!(params.len(), CHUNK_LEN);
+                }
+                _ => panic!("Expected a request to read a file."),
+            }
+        }
+    }
+}
+pub use state::read_chunk_request;
+use state::State;
+
+mod action {
+    use super::Prompt;
+
+    use insh_api::Response;
+    use rend::Size;
+
+    pub enum Action {
+        HandleResponse(Response),
+        TimeOut,
+        Resize { size: Size },
+        Down,
+        ReallyDown,
+        Up,
+        ReallyUp,
+        OpenPrompt { prompt: Prompt },
+        ClosePrompt,
+        Submit { phrase: String },
+        NextMatch,
+        PreviousMatch,
+        ToggleWrap,
+        Left,
+        Right,
+        Bell,
+        Quit,
+    }
+}
+use action::Action;
+
+mod effect {
+    use insh_api::Request;
+
+    pub enum Effect {
+        Request(Request),
+        Quit,
+        Bell,
+    }
+}
+pub use effect::Effect;
@@ -0,0 +1,3 @@
+mod palette;
+
+pub use palette::{Effect as PaletteEffect, Event as PaletteEvent, Palette, Props as PaletteProps};
@@ -0,0 +1,542 @@
+mod props {
+    use rend::Size;
+
+    use typed_builder::TypedBuilder;
+
+    use crate::plugins::{PluginCommand, PluginContext};
+
+    #[derive(TypedBuilder)]
+    pub struct Props {
+        pub commands: Vec<PluginCommand>,
+        /// The errors of the plugins that failed to advertise their commands.
+        #[builder(default)]
+        pub errors: Vec<String>,
+        /// The names of the commands that were run from oldest to newest.
+        #[builder(default)]
+        pub history: Vec<String>,
+        pub context: PluginContext,
+        pub size: Size,
+    }
+}
+pub use props::Props;
+
+mod palette {
+    use super::{Action, Effect, Event, State};
+    use crate::color::Color;
+    use crate::inspect::{Inspect, Inspection};
+    use crate::plugins::PluginCommand;
+    use crate::stateful::Stateful;
+
+    use rend::{Fabric, Size, Yarn};
+    use term::{Key, KeyEvent, KeyMods, TermEvent};
+    use til::Component;
+
+    use super::Props;
+
+    /// A command palette listing the commands of the plugins, which are filtered by typing.
+    pub struct Palette {
+        state: State,
+    }
+
+    impl Component<Props, Event, Effect> for Palette {
+        fn new(props: Props) -> Self {
+            let state = State::from(props);
+            Self { state }
+        }
+
+        fn handle(&mut self, event: Event) -> Option<Effect> {
+            let action: Option<Action> = match event {
+                Event::TermEvent(TermEvent::Resize(size)) => Some(Action::Resize { size }),
+                Event::TermEvent(TermEvent::FocusGained | TermEvent::FocusLost) => None,
+                Event::TermEvent(TermEvent::KeyEvent(key_event)) => match key_event {
+                    KeyEvent {
+                        key: Key::Char('q'),
+                        mods: KeyMods::CONTROL,
+                    }
+                    | KeyEvent {
+                        key: Key::Escape, ..
+                    } => Some(Action::Quit),
+                    KeyEvent {
+                        key: Key::Char('j') | Key::Char('n'),
+                        mods: KeyMods::CONTROL,
+                    } => Some(Action::Down),
+                    KeyEvent {
+                        key: Key::Char('k') | Key::Char('p'),
+                        mods: KeyMods::CONTROL,
+                    } => Some(Action::Up),
+                    KeyEvent { key: Key::Up, .. } => Some(Action::RecallPrevious),
+                    KeyEvent { key: Key::Down, .. } => Some(Action::RecallNext),
+                    KeyEvent {
+                        key: Key::Delete | Key::Backspace,
+                        ..
+                    } => Some(Action::Pop),
+                    KeyEvent {
+                        key: Key::CarriageReturn,
+                        ..
+                    } => Some(Action::Run),
+                    KeyEvent {
+                        key: Key::Char(character),
+                        mods: KeyMods::NONE | KeyMods::SHIFT,
+                    } => Some(Action::Push { character }),
+                    _ => None,
+                },
+            };
+
+            if let Some(action) = action {
+                self.state.perform(action)
+            } else {
+                Some(Effect::Bell)
+            }
+        }
+
+        fn render(&self, size: Size) -> Fabric {
+            if size.rows == 0 {
+                return Fabric::new(size);
+            }
+
+            let mut prompt = Yarn::from(format!(":{}", self.state.phrase()));
+            prompt.resize(size.columns);
+            prompt.color(Color::InvertedText.into());
+            prompt.background(Color::focus_or_important(true).into());
+            let mut fabric = Fabric::from(prompt);
+            if size.rows == 1 {
+                return fabric;
+            }
+
+            let rows: usize = size.rows - 2;
+            if self.state.commands().is_empty() {
+                let message = "No plugins advertise commands (see `plugins` in the README).";
+                fabric =
+                    fabric.quilt_bottom(Fabric::center(message, Size::new(rows, size.columns)));
+                return fabric.quilt_bottom(self.render_status(size.columns));
+            }
+
+            let top: usize = self.state.top();
+            let yarns: Vec<Yarn> = (top..self.state.match_count())
+                .take(rows)
+                .map(|number| {
+                    let command: &PluginCommand = self.state.command(number).unwrap();
+                    let mut yarn: Yarn = self.render_command(command);
+                    yarn.resize(size.columns);
+                    if number == self.state.selected() {
+                        yarn.color(Color::InvertedText.into());
+                        yarn.background(Color::Highlight.into());
+                    }
+                    yarn
+                })
+                .collect();
+            if !yarns.is_empty() {
+                fabric = fabric.quilt_bottom(Fabric::from(yarns));
+            }
+            fabric.pad_bottom(size.rows - 1);
+
+            fabric.quilt_bottom(self.render_status(size.columns))
+        }
+    }
+
+    impl Palette {
+        /// Return the size of the palette on a screen of the size (it is shown over the browser).
+        pub fn modal_size(screen: Size) -> Size {
+            Size::new(
+                (screen.rows * 2 / 3).max(screen.rows.min(5)),
+                (screen.columns * 4 / 5).max(screen.columns.min(40)),
+            )
+        }
+
+        /// Return the yarn for a command (its name and its description).
+        fn render_command(&self, command: &PluginCommand) -> Yarn {
+            let mut description = Yarn::from(format!("  {}", command.description()));
+            description.color(Color::GrayedText.into());
+            Yarn::from(command.name()).concat(description)
+        }
+
+        /// Return the fabric for the status line (the number of matching commands and the first
+        /// error of the plugins).
+        fn render_status(&self, columns: usize) -> Fabric {
+            let mut status: String = format!(
+                "{}/{} commands",
+                self.state.match_count(),
+                self.state.commands().len()
+            );
+            if let Some(error) = self.state.errors().first() {
+                status.push_str(&format!(" | {}", error));
+            }
+            let mut status = Yarn::from(status);
+            status.resize(columns);
+            status.color(Color::InvertedText.into());
+            status.background(Color::InvertedBackground.into());
+            Fabric::from(status)
+        }
+    }
+
+    impl Inspect for Palette {
+        fn inspect(&self) -> Inspection {
+            self.state.inspect()
+        }
+    }
+}
+pub use palette::Palette;
+
+mod event {
+    use term::TermEvent;
+
+    pub enum Event {
+        TermEvent(TermEvent),
+    }
+}
+pub use event::Event;
+
+mod state {
+    use super::{Action, Effect, Props};
+    use crate::inspect::{Inspect, Inspection};
+    use crate::list_filter::ListFilter;
+    use crate::plugins::{PluginCommand, PluginContext};
+    use crate::stateful::Stateful;
+
+    use rend::Size;
+
+    pub struct State {
+        commands: Vec<PluginCommand>,
+        errors: Vec<String>,
+        /// The names of the commands that were run from oldest to newest.
+        history: Vec<String>,
+        /// The index in the history of the recalled command (if one is recalled).
+        recalled: Option<usize>,
+        /// The phrase that was typed before recalling (which the recalled commands start with).
+        prefix: String,
+        context: PluginContext,
+        size: Size,
+        /// The commands whose names match the typed phrase.
+        filter: ListFilter,
+        /// The number of the selected match.
+        selected: usize,
+        /// The number of the first visible match.
+        top: usize,
+    }
+
+    impl From<Props> for State {
+        fn from(props: Props) -> Self {
+            let mut filter = ListFilter::default();
+            filter.set_phrase("", props.commands.iter().map(PluginCommand::name));
+            Self {
+                commands: props.commands,
+                errors: props.errors,
+                history: props.history,
+                recalled: None,
+                prefix: String::new(),
+                context: props.context,
+                size: props.size,
+                filter,
+                selected: 0,
+                top: 0,
+            }
+        }
+    }
+
+    impl State {
+        pub fn commands(&self) -> &[PluginCommand] {
+            &self.commands
+        }
+
+        pub fn errors(&self) -> &[String] {
+            &self.errors
+        }
+
+        pub fn phrase(&self) -> &str {
+            self.filter.phrase()
+        }
+
+        /// Return the number of commands that match the phrase.
+        pub fn match_count(&self) -> usize {
+            self.filter.match_count()
+        }
+
+        /// Return the `number`th command that matches the phrase.
+        pub fn command(&self, number: usize) -> Option<&PluginCommand> {
+            self.filter
+                .get(number)
+                .and_then(|index| self.commands.get(index))
+        }
+
+        pub fn selected(&self) -> usize {
+            self.selected
+        }
+
+        pub fn top(&self) -> usize {
+            self.top
+        }
+
+        /// Return the number of rows available for showing the commands.
+        fn rows(&self) -> usize {
+            self.size.rows.saturating_sub(2)
+        }
+
+        /// Move the selection to the match at `selected` (clamped to the matches).
+        fn select(&mut self, selected: usize) {
+            self.selected = selected.min(self.match_count().saturating_sub(1));
+            let rows: usize = self.rows().max(1);
+            if self.selected < self.top {
+                self.top = self.selected;
+            } else if self.selected >= self.top + rows {
+                self.top = self.selected + 1 - rows;
+            }
+        }
+
+        /// Match the phrase against the names of the commands (and select the first match).
+        fn set_phrase(&mut self, phrase: &str) {
+            self.filter
+                .set_phrase(phrase, self.commands.iter().map(PluginCommand::name));
+            self.top = 0;
+            self.select(0);
+        }
+
+        /// Select the command with the name if it matches the phrase.
+        fn select_named(&mut self, name: &str) {
+            let number: Option<usize> = (0..self.match_count())
+                .find(|number| self.command(*number).map(PluginCommand::name) == Some(name));
+            if let Some(number) = number {
+                self.select(number);
+            }
+        }
+
+        /// Show the recalled command of the history (or the typed phrase if none is recalled).
+        fn recall(&mut self, recalled: Option<usize>) {
+            self.recalled = recalled;
+            let phrase: String = match recalled {
+                Some(index) => self.history[index].clone(),
+                None => self.prefix.clone(),
+            };
+            self.set_phrase(&phrase);
+            self.select_named(&phrase);
+        }
+
+        /// Return whether the entry of the history at the index can be recalled (it starts with
+        /// the typed phrase and isn't what is shown already).
+        fn recallable(&self, index: usize) -> bool {
+            let name: &str = &self.history[index];
+            name.starts_with(&self.prefix) && name != self.phrase()
+        }
+
+        /// Recall the previous command of the history which starts with the typed phrase.
+        fn recall_previous(&mut self) -> Option<Effect> {
+            let end: usize = match self.recalled {
+                Some(index) => index,
+                None => {
+                    self.prefix = self.phrase().to_string();
+                    self.history.len()
+                }
+            };
+            match (0..end).rev().find(|index| self.recallable(*index)) {
+                Some(index) => {
+                    self.recall(Some(index));
+                    None
+                }
+                None => Some(Effect::Bell),
+            }
+        }
+
+        /// Recall the next command of the history which starts with the typed phrase (or go back
+        /// to the typed phrase after the newest one).
+        fn recall_next(&mut self) -> Option<Effect> {
+            let start: usize = match self.recalled {
+                Some(index) => index + 1,
+                None => return Some(Effect::Bell),
+            };
+            let next: Option<usize> =
+                (start..self.history.len()).find(|index| self.recallable(*index));
+            self.recall(next);
+            None
+        }
+
+        fn resize(&mut self, size: Size) -> Option<Effect> {
+            self.size = size;
+            self.select(self.selected);
+            None
+        }
+
+        fn down(&mut self) -> Option<Effect> {
+            if self.selected + 1 >= self.match_count() {
+                return Some(Effect::Bell);
+            }
+            self.select(self.selected + 1);
+            None
+        }
+
+        fn up(&mut self) -> Option<Effect> {
+            if self.selected == 0 {
+                return Some(Effect::Bell);
+            }
+            self.select(self.selected - 1);
+            None
+        }
+
+        fn push(&mut self, character: char) -> Option<Effect> {
+            let mut phrase: String = self.phrase().to_string();
+            phrase.push(character);
+            self.recalled = None;
+            self.set_phrase(&phrase);
+            None
+        }
+
+        fn pop(&mut self) -> Option<Effect> {
+            let mut phrase: String = self.phrase().to_string();
+            if phrase.pop().is_none() {
+                return Some(Effect::Bell);
+            }
+            self.recalled = None;
+            self.set_phrase(&phrase);
+            None
+        }
+
+        /// Run the selected command.
+        fn run(&mut self) -> Option<Effect> {
+            match self.command(self.selected) {
+                Some(command) => Some(Effect::Run {
+                    command: command.clone(),
+                    context: self.context.clone(),
+                }),
+                None => Some(Effect::Bell),
+            }
+        }
+    }
+
+    impl Stateful<Action, Effect> for State {
+        fn perform(&mut self, action: Action) -> Option<Effect> {
+            match action {
+                Action::Resize { size } => self.resize(size),
+                Action::Down => self.down(),
+                Action::Up => self.up(),
+                Action::RecallPrevious => self.recall_previous(),
+                Action::RecallNext => self.recall_next(),
+                Action::Push { character } => self.push(character),
+                Action::Pop => self.pop(),
+                Action::Run => self.run(),
+                Action::Quit => Some(Effect::Quit),
+            }
+        }
+    }
+
+    impl Inspect for State {
+        fn inspect(&self) -> Inspection {
+            Inspection::new("Palette")
+                .field("commands", self.commands.len())
+                .field("errors", self.errors.len())
+                .field("history", self.history.len())
+                .field("phrase", self.phrase())
+                .field("matches", self.match_count())
+                .field("selected", self.selected)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        use std::path::PathBuf;
+
+        #[test]
+        fn test_filter_and_run() {
+            let commands: Vec<PluginCommand> =
+                serde_json::from_str(r#"[{"name": "compress"}, {"name": "upload"}]"#).unwrap();
+            let context = PluginContext::new(PathBuf::from("/foo"), None);
+            let props = Props::builder()
+                .commands(commands)
+                .context(context.clone())
+                .size(Size::new(10, 80))
+                .build();
+            let mut state = State::from(props);
+            assert_eq!(state.match_count(), 2);
+
+            assert!(state.perform(Action::Push { character: 'u' }).is_none());
+            assert!(state.perform(Action::Push { character: 'p' }).is_none());
+            assert_eq!(state.match_count(), 1);
+            assert!(matches!(state.perform(Action::Down), Some(Effect::Bell)));
+
+            match state.perform(Action::Run) {
+                Some(Effect::Run {
+                    command,
+                    context: run_context,
+                }) => {
+                    assert_eq!(command.name(), "upload");
+                    assert_eq!(run_context, context);
+                }
+                _ => panic!("The selected command should be run."),
+            }
+
+            assert!(state.perform(Action::Push { character: 'x' }).is_none());
+            assert!(matches!(state.perform(Action::Run), Some(Effect::Bell)));
+        }
+
+        #[test]
+        fn test_recall() {
+            let commands: Vec<PluginCommand> = serde_json::from_str(
+                r#"[{"name": "compress"}, {"name": "upload"}, {"name": "unzip"}]"#,
+            )
+            .unwrap();
+            let history: Vec<String> = ["unzip", "compress", "upload", "upload"]
+                .iter()
+                .map(|name| name.to_string())
+                .collect();
+            let props = Props::builder()
+                .commands(commands)
+                .history(history)
+                .context(PluginContext::new(PathBuf::from("/foo"), None))
+                .size(Size::new(10, 80))
+                .build();
+            let mut state = State::from(props);
+
+            assert!(matches!(
+                state.perform(Action::RecallNext),
+                Some(Effect::Bell)
+            ));
+            assert!(state.perform(Action::Push { character: 'u' }).is_none());
+            assert!(state.perform(Action::RecallPrevious).is_none());
+            assert_eq!(state.phrase(), "upload");
+            assert_eq!(state.command(state.selected()).unwrap().name(), "upload");
+            assert!(state.perform(Action::RecallPrevious).is_none());
+            assert_eq!(state.phrase(), "unzip");
+            assert!(matches!(
+                state.perform(Action::RecallPrevious),
+                Some(Effect::Bell)
+            ));
+
+            assert!(state.perform(Action::RecallNext).is_none());
+            assert_eq!(state.phrase(), "upload");
+            assert!(state.perform(Action::RecallNext).is_none());
+            assert_eq!(state.phrase(), "u");
+        }
+    }
+}
+use state::State;
+
+mod action {
+    use rend::Size;
+
+    pub enum Action {
+        Resize { size: Size },
+        Down,
+        Up,
+        RecallPrevious,
+        RecallNext,
+        Push { character: char },
+        Pop,
+        Run,
+        Quit,
+    }
+}
+use action::Action;
+
+mod effect {
+    use crate::plugins::{PluginCommand, PluginContext};
+
+    pub enum Effect {
+        /// Run the command of a plugin.
+        Run {
+            command: PluginCommand,
+            context: PluginContext,
+        },
+        Quit,
+        Bell,
+    }
+}
+pub use effect::Effect;
@@ -0,0 +1,6 @@
+mod process_list;
+
+pub use process_list::{
+    get_processes_request, Effect as ProcessListEffect, Event as ProcessListEvent, ProcessList,
+    Props as ProcessListProps,
+};
@@ -0,0 +1,654 @@
+mod props {
+    use rend::Size;
+
+    use std::path::PathBuf;
+
+    use typed_builder::TypedBuilder;
+
+    #[derive(TypedBuilder)]
+    pub struct Props {
+        pub dir: PathBuf,
+        pub size: Size,
+    }
+}
+pub use props::Props;
+
+mod process_list {
+    use super::{Action, Effect, Event, State};
+    use crate::color::Color;
+    use crate::components::common::{PromptEffect, PromptEvent};
+    use crate::inspect::{Inspect, Inspection};
+    use crate::stateful::Stateful;
+
+    use insh_api::{ProcessInfo, Signal};
+    use rend::{Fabric, Size, Yarn};
+    use term::{Key, KeyEvent, KeyMods, TermEvent};
+    use til::Component;
+
+    use std::path::Path;
+
+    use super::Props;
+
+    /// A list of the processes whose working directory is in a directory (which are likely to be
+    /// holding files in it open) which can send signals to them.
+    pub struct ProcessList {
+        state: State,
+    }
+
+    impl Component<Props, Event, Effect> for ProcessList {
+        fn new(props: Props) -> Self {
+            let state = State::from(props);
+            Self { state }
+        }
+
+        fn handle(&mut self, event: Event) -> Option<Effect> {
+            let action: Option<Action> = match event {
+                Event::Response(response) => Some(Action::HandleResponse(response)),
+                Event::TermEvent(TermEvent::Resize(size)) => Some(Action::Resize { size }),
+                Event::TermEvent(TermEvent::FocusGained | TermEvent::FocusLost) => None,
+                Event::TermEvent(term_event) if self.state.prompt.is_some() => {
+                    let prompt_event = PromptEvent::TermEvent(term_event);
+                    match self.state.prompt.as_mut().unwrap().handle(prompt_event) {
+                        Some(PromptEffect::Choose { index }) => Some(Action::Confirm { index }),
+                        Some(PromptEffect::Cancel) => Some(Action::ClosePrompt),
+                        Some(PromptEffect::Bell) => return Some(Effect::Bell),
+                        None => return None,
+                    }
+                }
+                Event::TermEvent(TermEvent::KeyEvent(key_event)) => match key_event {
+                    KeyEvent {
+                        key: Key::Char('q'),
+                        mods: KeyMods::CONTROL,
+                    }
+                    | KeyEvent {
+                        key: Key::Char('q'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Quit),
+                    KeyEvent {
+                        key: Key::Char('j'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Down),
+                    KeyEvent {
+                        key: Key::Char('J'),
+                        mods: KeyMods::SHIFT,
+                    } => Some(Action::ReallyDown),
+                    KeyEvent {
+                        key: Key::Char('k'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Up),
+                    KeyEvent {
+                        key: Key::Char('K'),
+                        mods: KeyMods::SHIFT,
+                    } => Some(Action::ReallyUp),
+                    KeyEvent {
+                        key: Key::Char('r'),
+                        ..
+                    } => Some(Action::Refresh),
+                    KeyEvent {
+                        key: Key::Char('i'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Signal {
+                        signal: Signal::Interrupt,
+                    }),
+                    KeyEvent {
+                        key: Key::Char('t'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Signal {
+                        signal: Signal::Terminate,
+                    }),
+                    KeyEvent {
+                        key: Key::Char('X'),
+                        mods: KeyMods::SHIFT,
+                    } => Some(Action::OpenStopPrompt),
+                    _ => None,
+                },
+            };
+
+            if let Some(action) = action {
+                self.state.perform(action)
+            } else {
+                Some(Effect::Bell)
+            }
+        }
+
+        fn render(&self, size: Size) -> Fabric {
+            if size.rows == 0 {
+                return Fabric::new(size);
+            }
+
+            let mut title = Yarn::from(format!(
+                "Processes in {}",
+                self.state.dir().to_string_lossy()
+            ));
+            title.resize(size.columns);
+            title.color(Color::InvertedText.into());
+            title.background(Color::InvertedBackground.into());
+            let mut fabric = Fabric::from(title);
+            if size.rows == 1 {
+                return fabric;
+            }
+
+            let rows: usize = size.rows - 2;
+            if let Some(error) = self.state.error() {
+                fabric = fabric.quilt_bottom(Fabric::center(error, Size::new(rows, size.columns)));
+                return fabric.quilt_bottom(self.render_status(size.columns));
+            }
+
+            let top: usize = self.state.top();
+            let yarns: Vec<Yarn> = self
+                .state
+                .processes()
+                .iter()
+                .enumerate()
+                .skip(top)
+                .take(rows)
+                .map(|(index, process)| {
+                    let mut yarn: Yarn = self.render_process(process);
+                    yarn.resize(size.columns);
+                    if index == self.state.selected() {
+                        yarn.color(Color::InvertedText.into());
+                        yarn.background(Color::Highlight.into());
+                    }
+                    yarn
+                })
+                .collect();
+            if !yarns.is_empty() {
+                fabric = fabric.quilt_bottom(Fabric::from(yarns));
+            }
+            fabric.pad_bottom(size.rows - 1);
+
+            fabric.quilt_bottom(self.render_status(size.columns))
+        }
+    }
+
+    impl ProcessList {
+        /// Return the yarn for a process (its pid, its working directory relative to the
+        /// directory, and its command line).
+        fn render_process(&self, process: &ProcessInfo) -> Yarn {
+            let cwd: &Path = process
+                .cwd()
+                .strip_prefix(self.state.dir())
+                .unwrap_or(process.cwd());
+            let cwd: String = match cwd.as_os_str().is_empty() {
+                true => String::from("."),
+                false => cwd.to_string_lossy().to_string(),
+            };
+
+            let mut pid = Yarn::from(format!("{:>7}  ", process.pid()));
+            pid.color(Color::GrayedText.into());
+            let mut cwd = Yarn::from(format!("{}  ", cwd));
+            cwd.color(Color::LightGrayedText.into());
+            pid.concat(cwd).concat(Yarn::from(process.command()))
+        }
+
+        /// Return the fabric for the status line (or the prompt if it is open).
+        fn render_status(&self, columns: usize) -> Fabric {
+            if let Some(prompt) = &self.state.prompt {
+                return prompt.render(Size::new(1, columns));
+            }
+
+            let count: String = match self.state.processes().len() {
+                1 => String::from("1 process"),
+                count => format!("{} processes", count),
+            };
+            let message: &str = self.state.message().as_deref().unwrap_or_default();
+            let padding: usize =
+                columns.saturating_sub(message.chars().count() + count.chars().count());
+            let mut status = Yarn::from(format!("{}{}{}", message, " ".repeat(padding), count));
+            status.resize(columns);
+            status.color(Color::InvertedText.into());
+            status.background(Color::InvertedBackground.into());
+            Fabric::from(status)
+        }
+    }
+
+    impl Inspect for ProcessList {
+        fn inspect(&self) -> Inspection {
+            self.state.inspect()
+        }
+    }
+}
+pub use process_list::ProcessList;
+
+mod event {
+    use insh_api::Response;
+    use term::TermEvent;
+
+    pub enum Event {
+        Response(Response),
+        TermEvent(TermEvent),
+    }
+}
+pub use event::Event;
+
+mod state {
+    use super::{Action, Effect, Props};
+    use crate::components::common::{Choice, Prompt, PromptProps};
+    use crate::inspect::{Inspect, Inspection};
+    use crate::stateful::Stateful;
+
+    use insh_api::{
+        GetProcessesRequestParams, ProcessInfo, Request, RequestParams, Response, ResponseParams,
+        Signal, SignalProcessRequestParams,
+    };
+    use rend::Size;
+
+    use std::path::{Path, PathBuf};
+
+    use til::Component;
+
+    /// The signals of the choices of the prompt for stopping a process (the last choice cancels).
+    const STOP_SIGNALS: [Signal; 2] = [Signal::Terminate, Signal::Kill];
+
+    /// Return the request for the processes whose working directory is in `dir`.
+    pub fn get_processes_request(dir: &Path) -> Request {
+        Request::builder()
+            .params(RequestParams::GetProcesses(
+                GetProcessesRequestParams::builder()
+                    .dir(dir.to_path_buf())
+                    .build(),
+            ))
+            .build()
+    }
+
+    pub struct State {
+        dir: PathBuf,
+        size: Size,
+        processes: Vec<ProcessInfo>,
+        /// The index of the selected process.
+        selected: usize,
+        /// The index of the first visible process.
+        top: usize,
+        /// An error getting the processes.
+        error: Option<String>,
+        /// A message shown in the status line.
+        message: Option<String>,
+        /// The prompt for how to stop the selected process (if it is open).
+        pub prompt: Option<Prompt>,
+    }
+
+    impl From<Props> for State {
+        fn from(props: Props) -> Self {
+            Self {
+                dir: props.dir,
+                size: props.size,
+                processes: Vec::new(),
+                selected: 0,
+                top: 0,
+                error: None,
+                message: None,
+                prompt: None,
+            }
+        }
+    }
+
+    impl State {
+        pub fn dir(&self) -> &Path {
+            &self.dir
+        }
+
+        pub fn processes(&self) -> &[ProcessInfo] {
+            &self.processes
+        }
+
+        pub fn selected(&self) -> usize {
+            self.selected
+        }
+
+        pub fn top(&self) -> usize {
+            self.top
+        }
+
+        pub fn error(&self) -> &Option<String> {
+            &self.error
+        }
+
+        pub fn message(&self) -> &Option<String> {
+            &self.message
+        }
+
+        /// Return the number of rows available for showing the processes.
+        fn rows(&self) -> usize {
+            self.size.rows.saturating_sub(2)
+        }
+
+        /// Keep the selected process visible.
+        fn scroll_to_selected(&mut self) {
+            let rows: usize = self.rows().max(1);
+            if self.selected < self.top {
+                self.top = self.selected;
+            } else if self.selected >= self.top + rows {
+                self.top = self.selected + 1 - rows;
+            }
+        }
+
+        /// Move the selection to the process at `selected` (clamped to the processes).
+        fn select(&mut self, selected: usize) {
+            self.selected = selected.min(self.processes.len().saturating_sub(1));
+            self.scroll_to_selected();
+        }
+
+        fn handle_response(&mut self, response: Response) -> Option<Effect> {
+            match response.into_params() {
+                ResponseParams::GetProcesses(params) => match params.into_result() {
+                    Ok(processes) => {
+                        // Keep the same process selected if it is still running.
+                        let selected_pid: Option<u32> =
+                            self.processes.get(self.selected).map(ProcessInfo::pid);
+                        self.processes = processes;
+                        self.error = None;
+                        let selected: usize = selected_pid
+                            .and_then(|pid| {
+                                self.processes
+                                    .iter()
+                                    .position(|process| process.pid() >= pid)
+                            })
+                            .unwrap_or(self.selected);
+                        self.select(selected);
+                        None
+                    }
+                    Err(error) => {
+                        self.error = Some(format!("Failed to get the processes: {}", error));
+                        None
+                    }
+                },
+                ResponseParams::SignalProcess(params) => {
+                    match params.result() {
+                        Ok(()) => {
+                            self.message =
+                                Some(format!("Sent {} to {}.", params.signal(), params.pid()))
+                        }
+                        Err(error) => {
+                            self.message = Some(format!(
+                                "Failed to send {} to {}: {}",
+                                params.signal(),
+                                params.pid(),
+                                error
+                            ))
+                        }
+                    }
+                    self.refresh()
+                }
+                _ => {
+                    #[cfg(feature = "logging")]
+                    log::error!("Unexpected response parameters.");
+                    None
+                }
+            }
+        }
+
+        fn resize(&mut self, size: Size) -> Option<Effect> {
+            self.size = size;
+            self.scroll_to_selected();
+            None
+        }
+
+        fn down(&mut self) -> Option<Effect> {
+            if self.selected + 1 >= self.processes.len() {
+                return Some(Effect::Bell);
+            }
+            self.select(self.selected + 1);
+            None
+        }
+
+        fn really_down(&mut self) -> Option<Effect> {
+            self.select(self.processes.len());
+            None
+        }
+
+        fn up(&mut self) -> Option<Effect> {
+            if self.selected == 0 {
+                return Some(Effect::Bell);
+            }
+            self.select(self.selected - 1);
+            None
+        }
+
+        fn really_up(&mut self) -> Option<Effect> {
+            self.select(0);
+            None
+        }
+
+        fn refresh(&mut self) -> Option<Effect> {
+            let request: Request = get_processes_request(&self.dir);
+            Some(Effect::Request(request))
+        }
+
+        /// Open the prompt for how to stop the selected process.
+        fn open_stop_prompt(&mut self) -> Option<Effect> {
+            let process: &ProcessInfo = match self.processes.get(self.selected) {
+                Some(process) => process,
+                None => return Some(Effect::Bell),
+            };
+            let props = PromptProps::builder()
+                .question(format!("Stop {} ({})?", process.pid(), process.command()))
+                .choices(vec![
+                    Choice::new("Terminate", 't'),
+                    Choice::new("Kill", 'k'),
+                    Choice::new("Cancel", 'c'),
+                ])
+                .danger(true)
+                .build();
+            self.prompt = Some(Prompt::new(props));
+            None
+        }
+
+        /// Close the prompt and send the signal of the choice (if it isn't cancelling).
+        fn confirm(&mut self, index: usize) -> Option<Effect> {
+            self.prompt = None;
+            match STOP_SIGNALS.get(index) {
+                Some(signal) => self.signal(*signal),
+                None => None,
+            }
+        }
+
+        /// Send the signal to the selected process.
+        fn signal(&mut self, signal: Signal) -> Option<Effect> {
+            let pid: u32 = match self.processes.get(self.selected) {
+                Some(process) => process.pid(),
+                None => return Some(Effect::Bell),
+            };
+            let request: Request = Request::builder()
+                .params(RequestParams::SignalProcess(
+                    SignalProcessRequestParams::builder()
+                        .pid(pid)
+                        .signal(signal)
+                        .build(),
+                ))
+                .build();
+            Some(Effect::Request(request))
+        }
+    }
+
+    impl Stateful<Action, Effect> for State {
+        fn perform(&mut self, action: Action) -> Option<Effect> {
+            if !matches!(action, Action::HandleResponse(_) | Action::Resize { .. }) {
+                self.message = None;
+            }
+            match action {
+                Action::HandleResponse(response) => self.handle_response(response),
+                Action::Resize { size } => self.resize(size),
+                Action::Down => self.down(),
+                Action::ReallyDown => self.really_down(),
+                Action::Up => self.up(),
+                Action::ReallyUp => self.really_up(),
+                Action::Refresh => self.refresh(),
+                Action::Signal { signal } => self.signal(signal),
+                Action::OpenStopPrompt => self.open_stop_prompt(),
+                Action::Confirm { index } => self.confirm(index),
+                Action::ClosePrompt => {
+                    self.prompt = None;
+                    None
+                }
+                Action::Quit => Some(Effect::Quit),
+            }
+        }
+    }
+
+    impl Inspect for State {
+        fn inspect(&self) -> Inspection {
+            Inspection::new("ProcessList")
+                .field("dir", &self.dir)
+                .field("processes", self.processes.len())
+                .field("selected", self.selected)
+                .field("top", self.top)
+                .field("error", &self.error)
+                .field("prompt_open", self.prompt.is_some())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::requester::send_over_socket;
+        use insh_api::{GetProcessesResponseParams, SignalProcessResponseParams};
+        use uuid::Uuid;
+
+        /// Return a process with the pid `pid` in the directory `/project`.
+        fn process(pid: u32) -> ProcessInfo {
+            ProcessInfo::builder()
+                .pid(pid)
+                .command(String::from("vim notes.txt"))
+                .cwd(PathBuf::from("/project"))
+                .build()
+        }
+
+        /// Respond to the request of the state with the parameters.
+        fn respond(state: &mut State, params: ResponseParams) -> Option<Effect> {
+            let response = Response::builder()
+                .uuid(Uuid::new_v4())
+                .params(params)
+                .build();
+            state.perform(Action::HandleResponse(response))
+        }
+
+        /// Respond to the pending request of the state with the processes with the pids.
+        fn respond_processes(state: &mut State, pids: &[u32]) {
+            let processes: Vec<ProcessInfo> = pids.iter().copied().map(process).collect();
+            respond(
+                state,
+                ResponseParams::GetProcesses(
+                    GetProcessesResponseParams::builder()
+                        .result(Ok(processes))
+                        .build(),
+                ),
+            );
+        }
+
+        /// Return a new process list state with the processes with the pids.
+        fn state(pids: &[u32]) -> State {
+            let props = Props::builder()
+                .dir(PathBuf::from("/project"))
+                .size(Size::new(10, 80))
+                .build();
+            let mut state = State::from(props);
+            respond_processes(&mut state, pids);
+            state
+        }
+
+        #[test]
+        fn test_signal() {
+            let mut state = state(&[10, 20, 30]);
+            state.perform(Action::Down);
+
+            let request: Request = match state.perform(Action::Signal {
+                signal: Signal::Terminate,
+            }) {
+                Some(Effect::Request(request)) => send_over_socket(vec![request]).remove(0),
+                _ => panic!("Expected a request."),
+            };
+            match request.params() {
+                RequestParams::SignalProcess(params) => {
+                    assert_eq!(params.pid(), 20);
+                    assert_eq!(params.signal(), Signal::Terminate);
+                }
+                _ => panic!("Expected a request to signal a process."),
+            }
+
+            let effect: Option<Effect> = respond(
+                &mut state,
+                ResponseParams::SignalProcess(
+                    SignalProcessResponseParams::builder()
+                        .pid(20)
+                        .signal(Signal::Terminate)
+                        .result(Ok(()))
+                        .build(),
+                ),
+            );
+            assert!(matches!(effect, Some(Effect::Request(_))));
+            assert_eq!(state.message(), &Some(String::from("Sent SIGTERM to 20.")));
+
+            respond_processes(&mut state, &[10, 30]);
+            assert_eq!(state.processes()[state.selected()].pid(), 30);
+        }
+
+        #[test]
+        fn test_stop_prompt() {
+            let mut state = state(&[10, 20]);
+
+            assert!(state.perform(Action::OpenStopPrompt).is_none());
+            assert!(state.prompt.is_some());
+            let request: Request = match state.perform(Action::Confirm { index: 1 }) {
+                Some(Effect::Request(request)) => send_over_socket(vec![request]).remove(0),
+                _ => panic!("Expected a request."),
+            };
+            assert!(state.prompt.is_none());
+            match request.params() {
+                RequestParams::SignalProcess(params) => {
+                    assert_eq!(params.pid(), 10);
+                    assert_eq!(params.signal(), Signal::Kill);
+                }
+                _ => panic!("Expected a request to signal a process."),
+            }
+
+            state.perform(Action::OpenStopPrompt);
+            assert!(state.perform(Action::Confirm { index: 2 }).is_none());
+            assert!(state.prompt.is_none());
+        }
+
+        #[test]
+        fn test_refresh_keeps_selection() {
+            let mut state = state(&[10, 20, 30]);
+            state.perform(Action::ReallyDown);
+
+            state.perform(Action::Refresh);
+            respond_processes(&mut state, &[5, 10, 20, 30]);
+
+            assert_eq!(state.selected(), 3);
+        }
+    }
+}
+pub use state::get_processes_request;
+use state::State;
+
+mod action {
+    use insh_api::{Response, Signal};
+    use rend::Size;
+
+    pub enum Action {
+        HandleResponse(Response),
+        Resize { size: Size },
+        Down,
+        ReallyDown,
+        Up,
+        ReallyUp,
+        Refresh,
+        Signal { signal: Signal },
+        OpenStopPrompt,
+        Confirm { index: usize },
+        ClosePrompt,
+        Quit,
+    }
+}
+use action::Action;
+
+mod effect {
+    use insh_api::Request;
+
+    pub enum Effect {
+        Request(Request),
+        Quit,
+        Bell,
+    }
+}
+pub use effect::Effect;
@@ -0,0 +1,3 @@
+mod reader;
+
+pub use reader::{Effect as ReaderEffect, Props as ReaderProps, Reader};
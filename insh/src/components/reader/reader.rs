@@ -0,0 +1,261 @@
+mod props {
+    use rend::Size;
+
+    use std::path::PathBuf;
+
+    use typed_builder::TypedBuilder;
+
+    #[derive(TypedBuilder)]
+    pub struct Props {
+        pub path: PathBuf,
+        pub size: Size,
+    }
+}
+pub use props::Props;
+
+mod reader {
+    use super::{Action, Effect, Props, State};
+    use crate::color::Color;
+    use crate::inspect::{Inspect, Inspection};
+    use crate::stateful::Stateful;
+
+    use rend::{Fabric, Size, Yarn};
+    use term::{Key, KeyEvent, KeyMods, TermEvent};
+    use til::Component;
+
+    /// A full-screen reader for markdown files.
+    pub struct Reader {
+        state: State,
+    }
+
+    impl Component<Props, TermEvent, Effect> for Reader {
+        fn new(props: Props) -> Self {
+            let state = State::from(props);
+            Self { state }
+        }
+
+        fn handle(&mut self, event: TermEvent) -> Option<Effect> {
+            let action: Option<Action> = match event {
+                TermEvent::Resize(size) => Some(Action::Resize { size }),
+                TermEvent::FocusGained | TermEvent::FocusLost => None,
+                TermEvent::KeyEvent(key_event) => match key_event {
+                    KeyEvent {
+                        key: Key::Char('q'),
+                        mods: KeyMods::CONTROL,
+                    }
+                    | KeyEvent {
+                        key: Key::Char('q'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Quit),
+                    KeyEvent {
+                        key: Key::Char('j'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Down),
+                    KeyEvent {
+                        key: Key::Char('J'),
+                        mods: KeyMods::SHIFT,
+                    } => Some(Action::ReallyDown),
+                    KeyEvent {
+                        key: Key::Char('k'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::Up),
+                    KeyEvent {
+                        key: Key::Char('K'),
+                        mods: KeyMods::SHIFT,
+                    } => Some(Action::ReallyUp),
+                    _ => None,
+                },
+            };
+
+            if let Some(action) = action {
+                self.state.perform(action)
+            } else {
+                Some(Effect::Bell)
+            }
+        }
+
+        fn render(&self, size: Size) -> Fabric {
+            if size.rows == 0 {
+                return Fabric::new(size);
+            }
+
+            let mut title = Yarn::from(self.state.path().to_string_lossy().to_string());
+            title.resize(size.columns);
+            title.color(Color::InvertedText.into());
+            title.background(Color::InvertedBackground.into());
+            let mut yarns: Vec<Yarn> = vec![title];
+
+            let rows: usize = size.rows - 1;
+            match self.state.error() {
+                Some(error) => {
+                    let fabric = Fabric::from(yarns);
+                    return fabric
+                        .quilt_bottom(Fabric::center(error, Size::new(rows, size.columns)));
+                }
+                None => {
+                    for yarn in self.state.visible_yarns(rows) {
+                        let mut yarn: Yarn = yarn.clone();
+                        yarn.resize(size.columns);
+                        yarns.push(yarn);
+                    }
+                }
+            }
+
+            let mut fabric = Fabric::from(yarns);
+            if fabric.size().rows < size.rows {
+                fabric.pad_bottom(size.rows);
+            }
+            fabric
+        }
+    }
+
+    impl Inspect for Reader {
+        fn inspect(&self) -> Inspection {
+            self.state.inspect()
+        }
+    }
+}
+pub use reader::Reader;
+
+mod state {
+    use super::{Action, Effect, Props};
+    use crate::inspect::{Inspect, Inspection};
+    use crate::markdown::markdown_to_yarns;
+    use crate::stateful::Stateful;
+
+    use rend::{Size, Yarn};
+
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    pub struct State {
+        path: PathBuf,
+        size: Size,
+        /// The rendered lines of the file.
+        yarns: Vec<Yarn>,
+        /// An error reading the file.
+        error: Option<String>,
+        /// The index of the first visible line.
+        offset: usize,
+    }
+
+    impl From<Props> for State {
+        fn from(props: Props) -> Self {
+            let (yarns, error) = match fs::read_to_string(&props.path) {
+                Ok(text) => (markdown_to_yarns(&text), None),
+                Err(error) => (vec![], Some(format!("Failed to read the file: {}", error))),
+            };
+
+            Self {
+                path: props.path,
+                size: props.size,
+                yarns,
+                error,
+                offset: 0,
+            }
+        }
+    }
+
+    impl State {
+        pub fn path(&self) -> &Path {
+            &self.path
+        }
+
+        pub fn error(&self) -> &Option<String> {
+            &self.error
+        }
+
+        pub fn visible_yarns(&self, rows: usize) -> &[Yarn] {
+            let end: usize = (self.offset + rows).min(self.yarns.len());
+            &self.yarns[self.offset..end]
+        }
+
+        /// Return the number of rows available for showing the lines of the file.
+        fn rows(&self) -> usize {
+            self.size.rows.saturating_sub(1)
+        }
+
+        /// Return the largest offset (where the last line of the file is at the bottom).
+        fn max_offset(&self) -> usize {
+            self.yarns.len().saturating_sub(self.rows())
+        }
+
+        fn resize(&mut self, size: Size) -> Option<Effect> {
+            self.size = size;
+            self.offset = self.offset.min(self.max_offset());
+            None
+        }
+
+        fn down(&mut self) -> Option<Effect> {
+            if self.offset >= self.max_offset() {
+                return Some(Effect::Bell);
+            }
+            self.offset += 1;
+            None
+        }
+
+        fn really_down(&mut self) -> Option<Effect> {
+            self.offset = self.max_offset();
+            None
+        }
+
+        fn up(&mut self) -> Option<Effect> {
+            if self.offset == 0 {
+                return Some(Effect::Bell);
+            }
+            self.offset -= 1;
+            None
+        }
+
+        fn really_up(&mut self) -> Option<Effect> {
+            self.offset = 0;
+            None
+        }
+    }
+
+    impl Stateful<Action, Effect> for State {
+        fn perform(&mut self, action: Action) -> Option<Effect> {
+            match action {
+                Action::Resize { size } => self.resize(size),
+                Action::Down => self.down(),
+                Action::ReallyDown => self.really_down(),
+                Action::Up => self.up(),
+                Action::ReallyUp => self.really_up(),
+                Action::Quit => Some(Effect::Quit),
+            }
+        }
+    }
+
+    impl Inspect for State {
+        fn inspect(&self) -> Inspection {
+            Inspection::new("Reader")
+                .field("path", &self.path)
+                .field("lines", self.yarns.len())
+                .field("offset", self.offset)
+                .field("error", &self.error)
+        }
+    }
+}
+use state::State;
+
+mod action {
+    use rend::Size;
+
+    pub enum Action {
+        Resize { size: Size },
+        Down,
+        ReallyDown,
+        Up,
+        ReallyUp,
+        Quit,
+    }
+}
+use action::Action;
+
+mod effect {
+    pub enum Effect {
+        Quit,
+        Bell,
+    }
+}
+pub use effect::Effect;
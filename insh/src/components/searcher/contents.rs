@@ -1,19 +1,34 @@
 mod props {
+    use crate::clipboard::Clipboard;
     use crate::config::Config;
 
     use rend::Size;
 
+    use std::cell::RefCell;
     use std::path::PathBuf;
+    use std::rc::Rc;
+    use std::sync::Arc;
 
     pub struct Props {
-        pub config: Config,
+        pub config: Arc<Config>,
+        pub clipboard: Rc<RefCell<Clipboard>>,
         pub dir: PathBuf,
         pub size: Size,
     }
 
     impl Props {
-        pub fn new(config: Config, dir: PathBuf, size: Size) -> Self {
-            Self { config, dir, size }
+        pub fn new(
+            config: Arc<Config>,
+            clipboard: Rc<RefCell<Clipboard>>,
+            dir: PathBuf,
+            size: Size,
+        ) -> Self {
+            Self {
+                config,
+                clipboard,
+                dir,
+                size,
+            }
         }
     }
 }
@@ -21,49 +36,113 @@ pub use props::Props;
 
 mod contents {
     use super::{Action, Effect, Event, Props, State};
+    use crate::clipboard::Clipboard;
     use crate::color::Color;
-    use crate::phrase_searcher::{FileHit, LineHit};
+    use crate::count::Count;
+    use crate::hits::Hits;
+    use crate::inspect::{Inspect, Inspection};
     use crate::string::DetabExt;
     use crate::Config;
     use crate::Stateful;
+    use phrase_searcher::{FileHit, LineHit};
 
     use rend::{Fabric, Size, Yarn};
     use term::{Key, KeyEvent, KeyMods, TermEvent};
     use til::Component;
 
+    use std::borrow::Cow;
+    use std::cell::RefCell;
     use std::path::MAIN_SEPARATOR as PATH_SEPARATOR;
+    use std::rc::Rc;
+    use std::sync::Arc;
 
     pub struct Contents {
-        config: Config,
+        config: Arc<Config>,
+        clipboard: Rc<RefCell<Clipboard>>,
         state: State,
+        /// The count prefix for the next motion.
+        count: Count,
+        /// The count before `z` if it was pressed (the start of a fold or scroll command).
+        z_pending: Option<usize>,
     }
 
     impl Component<Props, Event, Effect> for Contents {
         fn new(props: Props) -> Self {
-            let state: State = State::from(&props);
+            let config: Arc<Config> = props.config.clone();
+            let clipboard: Rc<RefCell<Clipboard>> = props.clipboard.clone();
+            let state: State = State::from(props);
             Self {
-                config: props.config,
+                config,
+                clipboard,
                 state,
+                count: Count::default(),
+                z_pending: None,
             }
         }
 
         fn handle(&mut self, event: Event) -> Option<Effect> {
+            if let Event::TermEvent(TermEvent::KeyEvent(key_event)) = &event {
+                if let Some(count) = self.z_pending.take() {
+                    return match key_event {
+                        KeyEvent {
+                            key: Key::Char('a'),
+                            mods: KeyMods::NONE,
+                        } => self.state.perform(Action::ToggleFold),
+                        KeyEvent {
+                            key: Key::Char('h'),
+                            mods: KeyMods::NONE,
+                        } => self.state.perform(Action::ScrollLeft { columns: count }),
+                        KeyEvent {
+                            key: Key::Char('l'),
+                            mods: KeyMods::NONE,
+                        } => self.state.perform(Action::ScrollRight { columns: count }),
+                        KeyEvent {
+                            key: Key::Char('H'),
+                            mods: KeyMods::SHIFT,
+                        } => self.state.perform(Action::ScrollLeft {
+                            columns: count * self.state.half_width(),
+                        }),
+                        KeyEvent {
+                            key: Key::Char('L'),
+                            mods: KeyMods::SHIFT,
+                        } => self.state.perform(Action::ScrollRight {
+                            columns: count * self.state.half_width(),
+                        }),
+                        _ => Some(Effect::Bell),
+                    };
+                }
+            }
+
+            let count: usize = match &event {
+                Event::TermEvent(TermEvent::KeyEvent(key_event)) => {
+                    if self.count.push(key_event) {
+                        return None;
+                    }
+                    self.count.take()
+                }
+                _ => 1,
+            };
+
             let action: Option<Action> = match event {
                 Event::Search { phrase } => Some(Action::Search {
                     phrase,
                     max_history_length: self.config.searcher().history().length(),
                 }),
                 Event::TermEvent(TermEvent::Resize(size)) => Some(Action::Resize { size }),
+                Event::TermEvent(TermEvent::FocusGained | TermEvent::FocusLost) => None,
                 Event::TermEvent(TermEvent::KeyEvent(key_event)) => match key_event {
                     KeyEvent {
                         key: Key::Char('q'),
                         mods: KeyMods::CONTROL,
                         ..
                     } => Some(Action::Unfocus),
+                    KeyEvent {
+                        key: Key::Escape, ..
+                    } if self.state.searching() => Some(Action::Cancel),
                     KeyEvent {
                         key: Key::Char('j'),
                         mods: KeyMods::NONE,
-                    } => Some(Action::Down),
+                    } => Some(Action::Down { count }),
                     KeyEvent {
                         key: Key::Char('J'),
                         mods: KeyMods::SHIFT,
@@ -71,11 +150,11 @@ mod contents {
                     KeyEvent {
                         key: Key::Char('j'),
                         mods: KeyMods::CONTROL,
-                    } => Some(Action::ScrollDown),
+                    } => Some(Action::ScrollDown { count }),
                     KeyEvent {
                         key: Key::Char('k'),
                         mods: KeyMods::NONE,
-                    } => Some(Action::Up),
+                    } => Some(Action::Up { count }),
                     KeyEvent {
                         key: Key::Char('K'),
                         mods: KeyMods::SHIFT,
@@ -83,7 +162,31 @@ mod contents {
                     KeyEvent {
                         key: Key::Char('k'),
                         mods: KeyMods::CONTROL,
-                    } => Some(Action::ScrollUp),
+                    } => Some(Action::ScrollUp { count }),
+                    KeyEvent {
+                        key: Key::Char('d'),
+                        mods: KeyMods::CONTROL,
+                    } => Some(Action::HalfPageDown { count }),
+                    KeyEvent {
+                        key: Key::Char('u'),
+                        mods: KeyMods::CONTROL,
+                    } => Some(Action::HalfPageUp { count }),
+                    KeyEvent {
+                        key: Key::Char('f'),
+                        mods: KeyMods::CONTROL,
+                    } => Some(Action::PageDown { count }),
+                    KeyEvent {
+                        key: Key::Char('b'),
+                        mods: KeyMods::CONTROL,
+                    } => Some(Action::PageUp { count }),
+                    KeyEvent {
+                        key: Key::Char('n'),
+                        mods: KeyMods::CONTROL,
+                    } => Some(Action::NextFile { count }),
+                    KeyEvent {
+                        key: Key::Char('p'),
+                        mods: KeyMods::CONTROL,
+                    } => Some(Action::PreviousFile { count }),
                     KeyEvent {
                         key: Key::Char('r'),
                         mods: KeyMods::NONE,
@@ -98,6 +201,10 @@ mod contents {
                         key: Key::CarriageReturn,
                         ..
                     } => Some(Action::Edit),
+                    KeyEvent {
+                        key: Key::Char('L'),
+                        mods: KeyMods::SHIFT,
+                    } => Some(Action::EditAll),
                     KeyEvent {
                         key: Key::Char('g'),
                         mods: KeyMods::NONE,
@@ -110,14 +217,30 @@ mod contents {
                         key: Key::Char('y'),
                         mods: KeyMods::NONE,
                         ..
-                    } => Some(Action::Yank),
+                    } => Some(Action::Yank {
+                        clipboard: self.clipboard.clone(),
+                    }),
                     KeyEvent {
                         key: Key::Char('Y'),
                         mods: KeyMods::SHIFT,
                         ..
-                    } => Some(Action::ReallyYank),
+                    } => Some(Action::ReallyYank {
+                        clipboard: self.clipboard.clone(),
+                    }),
+                    KeyEvent {
+                        key: Key::Char('w'),
+                        mods: KeyMods::NONE,
+                    } => Some(Action::ToggleWrap),
+                    KeyEvent {
+                        key: Key::Char('z'),
+                        mods: KeyMods::NONE,
+                    } => {
+                        self.z_pending = Some(count);
+                        return None;
+                    }
                     _ => None,
                 },
+                Event::Response(response) => Some(Action::HandleResponse(response)),
             };
 
             if let Some(action) = action {
@@ -131,17 +254,30 @@ mod contents {
             match self.state.searched() {
                 false => Fabric::new(size),
                 true => {
-                    let file_hits: &Vec<FileHit> = self.state.hits();
+                    let file_hits: &Hits = self.state.hits();
                     if self.state.hits().is_empty() {
-                        Fabric::center("No matches.", size)
+                        match self.state.searching() {
+                            true => Fabric::center("Searching...", size),
+                            false => Fabric::center("No matches.", size),
+                        }
                     } else {
                         let rows = size.rows;
                         let columns = size.columns;
                         let mut yarns: Vec<Yarn> = Vec::new();
-
-                        let file_hits = file_hits.iter().enumerate().skip(self.state.file_offset());
+                        // The number of rows that the state accounts for (wrapped lines take more
+                        // rows than that).
+                        let mut entries: usize = 0;
+                        // The row after the last row of the selection.
+                        let mut selection_end: Option<usize> = None;
+
+                        // Only the visible file hits are read (in case their line hits were spilled).
+                        let file_hits = (self.state.file_offset()..file_hits.len()).filter_map(
+                            |file_hit_number| {
+                                Some((file_hit_number, file_hits.get(file_hit_number)?))
+                            },
+                        );
                         for (file_hit_number, file_hit) in file_hits {
-                            if yarns.len() == rows {
+                            if entries == rows {
                                 break;
                             }
 
@@ -149,18 +285,23 @@ mod contents {
                             let file_hit_is_focused: bool =
                                 self.state.hit_number().unwrap() == file_hit_number;
 
+                            let collapsed: bool = self.state.is_collapsed(&file_hit);
+
                             let draw_path = !(first_hit && self.state.line_offset().is_some());
                             if draw_path {
-                                let mut path: String =
-                                    file_hit.path().to_string_lossy().to_string();
-                                let dir_string: String =
-                                    self.state.dir().to_string_lossy().to_string();
-                                path = path.strip_prefix(&dir_string).unwrap().to_string();
-                                if path.starts_with(PATH_SEPARATOR) {
-                                    path = path.strip_prefix(PATH_SEPARATOR).unwrap().to_string();
+                                let mut path: String = self.relative_path(&file_hit);
+                                if collapsed {
+                                    path.push_str(&format!(
+                                        " ({} hits)",
+                                        file_hit.line_hits().len()
+                                    ));
                                 }
-
-                                let mut yarn = Yarn::from(path);
+                                let mut yarn = match self.state.wrap() {
+                                    true => Yarn::from(path),
+                                    false => {
+                                        Yarn::from(path).window(self.state.column_offset(), columns)
+                                    }
+                                };
                                 yarn.resize(columns);
 
                                 if self.state.focussed()
@@ -169,49 +310,87 @@ mod contents {
                                 {
                                     yarn.background(Color::Highlight.into());
                                     yarn.color(Color::InvertedText.into());
+                                    selection_end = Some(yarns.len() + 1);
                                 }
 
                                 yarns.push(yarn);
+                                entries += 1;
                             }
 
-                            let mut line_hits: Vec<(usize, &LineHit)> =
-                                file_hit.line_hits().iter().enumerate().collect();
+                            let mut line_hits: Vec<(usize, &LineHit)> = match collapsed {
+                                true => Vec::new(),
+                                false => file_hit.line_hits().iter().enumerate().collect(),
+                            };
                             if first_hit {
                                 if let Some(line_offset) = self.state.line_offset() {
                                     line_hits = line_hits.into_iter().skip(line_offset).collect();
                                 }
                             }
                             for (line_hit_number, line_hit) in line_hits {
-                                if yarns.len() == rows {
+                                if entries == rows {
                                     break;
                                 }
 
-                                let mut string: String = line_hit.line_number().to_string();
-                                string.push_str(": ");
-                                string.push_str(
-                                    &line_hit.line().detab(self.config.general().tab_width()),
-                                );
-
-                                let mut yarn = Yarn::from(string);
-                                yarn.resize(columns);
-                                if self.state.focussed()
+                                let mut prefix: String = line_hit.line_number().to_string();
+                                prefix.push_str(": ");
+                                let indent: usize = prefix.len();
+                                let tab_width: usize =
+                                    self.config.general().tab_width_for(file_hit.path());
+                                let line: Yarn =
+                                    Yarn::from(match self.config.general().show_whitespace() {
+                                        true => line_hit.line().detab_showing_whitespace(tab_width),
+                                        false => line_hit.line().detab(tab_width),
+                                    });
+
+                                // NOTE: The line number stays put when scrolling horizontally.
+                                let yarn: Yarn = match self.state.wrap() {
+                                    true => Yarn::from(prefix).concat(line),
+                                    false => Yarn::from(prefix).concat(line.window(
+                                        self.state.column_offset(),
+                                        columns.saturating_sub(indent),
+                                    )),
+                                };
+                                let selected: bool = self.state.focussed()
                                     && file_hit_is_focused
                                     && self.state.is_line_selected()
-                                    && self.state.line_hit_number().unwrap() == line_hit_number
-                                {
-                                    yarn.background(Color::Highlight.into());
-                                    yarn.color(Color::InvertedText.into());
+                                    && self.state.line_hit_number().unwrap() == line_hit_number;
+                                let line_rows: Vec<Yarn> = match self.state.wrap() {
+                                    true => yarn.wrap(columns, indent),
+                                    false => vec![yarn],
+                                };
+                                for mut row in line_rows {
+                                    row.resize(columns);
+                                    if selected {
+                                        row.background(Color::Highlight.into());
+                                        row.color(Color::InvertedText.into());
+                                    }
+                                    yarns.push(row);
                                 }
-                                yarns.push(yarn);
+                                if selected {
+                                    selection_end = Some(yarns.len());
+                                }
+                                entries += 1;
                             }
 
-                            if yarns.len() == rows {
+                            if entries == rows {
                                 break;
                             }
                             let yarn = Yarn::blank(columns);
                             yarns.push(yarn);
+                            entries += 1;
                         }
 
+                        // Wrapped lines can push the selection off the bottom, so drop rows from
+                        // the top to keep it visible.
+                        if let Some(selection_end) = selection_end {
+                            if selection_end > rows {
+                                yarns.drain(..selection_end - rows);
+                            }
+                        }
+                        yarns.truncate(rows);
+
+                        self.pin_header(&mut yarns, rows, columns);
+
                         let mut fabric = Fabric::from(yarns);
 
                         if fabric.size().rows < size.rows {
@@ -224,15 +403,67 @@ mod contents {
             }
         }
     }
+
+    impl Contents {
+        /// Return the path of a file hit relative to the directory that was searched.
+        fn relative_path(&self, file_hit: &FileHit) -> String {
+            let mut path: String = file_hit.path().to_string_lossy().to_string();
+            let dir_string: String = self.state.dir().to_string_lossy().to_string();
+            path = path.strip_prefix(&dir_string).unwrap().to_string();
+            if path.starts_with(PATH_SEPARATOR) {
+                path = path.strip_prefix(PATH_SEPARATOR).unwrap().to_string();
+            }
+            path
+        }
+
+        /// If the path of the first visible file hit has scrolled past the top, then pin it as a
+        /// header on the first row so that it is clear which file the visible line hits belong to.
+        ///
+        /// The header covers the first row, unless the selection is on the first row in which case
+        /// the rows are pushed down by one instead (so that the selection is always visible).
+        fn pin_header(&self, yarns: &mut Vec<Yarn>, rows: usize, columns: usize) {
+            let file_hit: Cow<FileHit> = match (self.state.line_offset(), self.state.first_hit()) {
+                (Some(line_offset), Some(file_hit))
+                    if line_offset < self.state.line_count(self.state.file_offset()) =>
+                {
+                    file_hit
+                }
+                _ => return,
+            };
+            if rows < 2 {
+                return;
+            }
+
+            let mut header = Yarn::from(self.relative_path(&file_hit));
+            header.resize(columns);
+            header.color(Color::InvertedText.into());
+            header.background(Color::InvertedBackground.into());
+
+            if self.state.focussed() && self.state.selected_row_number() == 0 {
+                yarns.insert(0, header);
+                yarns.truncate(rows);
+            } else {
+                yarns[0] = header;
+            }
+        }
+    }
+
+    impl Inspect for Contents {
+        fn inspect(&self) -> Inspection {
+            self.state.inspect().field("z_pending", self.z_pending)
+        }
+    }
 }
 pub use contents::Contents;
 
 mod event {
+    use insh_api::Response;
     use term::TermEvent;
 
     pub enum Event {
         TermEvent(TermEvent),
         Search { phrase: String },
+        Response(Response),
     }
 }
 pub use event::Event;
@@ -240,15 +471,38 @@ pub use event::Event;
 mod state {
     use super::{Action, Effect, Props};
     use crate::clipboard::Clipboard;
+    use crate::config::Config;
     use crate::data::Data;
-    use crate::phrase_searcher::{FileHit, LineHit, PhraseSearcher};
+    use crate::hits::Hits;
+    use crate::hooks::{run_hook, HookEvent};
+    use crate::inspect::{Inspect, Inspection};
+    use crate::list_viewport::ListViewport;
     use crate::programs::{VimArgs, VimArgsBuilder};
     use crate::Stateful;
+    use insh_api::{Response, ResponseParams, SearchPhraseResponseParams};
+    use phrase_searcher::{FileHit, LineHit};
 
     use rend::Size;
 
-    use std::cmp::Ordering;
+    use std::borrow::Cow;
+    use std::cell::RefCell;
+    use std::collections::HashSet;
+    use std::env;
+    use std::fs;
     use std::path::{Path, PathBuf, MAIN_SEPARATOR as PATH_SEPARATOR};
+    use std::process;
+    use std::sync::Arc;
+
+    /// A row of the hits.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Row {
+        /// The path of the file hit.
+        Path(usize),
+        /// A line hit of the file hit.
+        Line(usize, usize),
+        /// The blank row after the file hit.
+        Gap(usize),
+    }
 
     #[derive(Debug, PartialEq, Eq, Default)]
     pub struct State {
@@ -257,28 +511,43 @@ mod state {
         phrase: Option<String>,
         focussed: bool,
         searched: bool,
-        hits: Vec<FileHit>,
-        file_offset: usize,
-        line_offset: Option<usize>,
-        file_selected: usize,
-        line_selected: Option<usize>,
+        hits: Hits,
+        /// Whether the hits are still arriving.
+        ///
+        /// NOTE: Only the responses to the latest request for the hits are routed to the contents.
+        searching: bool,
+        /// The number of the first row of each file hit (the rows of a file hit are its path, its
+        /// line hits, and a gap before the next file hit).
+        row_starts: Vec<usize>,
+        /// The selected row and the visible rows.
+        viewport: ListViewport,
+        /// The paths of the file hits that are collapsed to a single row.
+        collapsed: HashSet<PathBuf>,
+        /// Whether long lines are wrapped.
+        wrap: bool,
+        /// The number of columns that the paths and lines are scrolled to the right by (when
+        /// lines are not wrapped).
+        column_offset: usize,
+        /// The config (for the shell commands that are run when events happen).
+        config: Arc<Config>,
     }
 
-    impl From<&Props> for State {
-        fn from(props: &Props) -> Self {
+    impl From<Props> for State {
+        fn from(props: Props) -> Self {
             Self {
                 size: props.size,
-                // Would be nice to not have to clone this. Maybe use the builder pattern instead
-                // of using From &Props?
-                dir: props.dir.clone(),
+                dir: props.dir,
                 phrase: None,
                 focussed: false,
                 searched: false,
-                hits: Vec::new(),
-                file_offset: 0,
-                line_offset: None,
-                file_selected: 0,
-                line_selected: None,
+                hits: Hits::default(),
+                searching: false,
+                row_starts: Vec::new(),
+                viewport: ListViewport::new(props.size.rows, props.config.general().scrolloff()),
+                collapsed: HashSet::new(),
+                wrap: props.config.general().wrap(),
+                column_offset: 0,
+                config: props.config,
             }
         }
     }
@@ -288,6 +557,21 @@ mod state {
             &self.dir
         }
 
+        /// Return whether long lines are wrapped.
+        pub fn wrap(&self) -> bool {
+            self.wrap
+        }
+
+        /// Return the number of columns that the paths and lines are scrolled to the right by.
+        pub fn column_offset(&self) -> usize {
+            self.column_offset
+        }
+
+        /// Return half of the number of columns.
+        pub fn half_width(&self) -> usize {
+            (self.size.columns / 2).max(1)
+        }
+
         /// Return if the search contents are currently foccused on.
         pub fn focussed(&self) -> bool {
             self.focussed
@@ -297,109 +581,146 @@ mod state {
             self.searched
         }
 
+        pub fn searching(&self) -> bool {
+            self.searching
+        }
+
         /// The number of the currently selected file hit.
         pub fn hit_number(&self) -> Option<usize> {
-            let number: usize = self.file_offset + self.file_selected;
-            if number < self.hits().len() {
-                Some(number)
-            } else {
-                None
+            match self.row(self.viewport.selected()?)? {
+                Row::Path(hit_number) | Row::Line(hit_number, _) => Some(hit_number),
+                Row::Gap(_) => None,
             }
         }
 
+        /// Return the number of the first file hit that is (at least partly) visible.
         pub fn file_offset(&self) -> usize {
-            self.file_offset
+            match self.row(self.viewport.visible().start) {
+                Some(Row::Path(hit_number) | Row::Line(hit_number, _) | Row::Gap(hit_number)) => {
+                    hit_number
+                }
+                None => 0,
+            }
         }
 
+        /// Return the number of rows of the first visible file hit that are scrolled past the top
+        /// after its path (or `None` if its path is visible).
         pub fn line_offset(&self) -> Option<usize> {
-            self.line_offset
+            match self.row(self.viewport.visible().start)? {
+                Row::Path(_) => None,
+                Row::Line(_, line_hit_number) => Some(line_hit_number),
+                Row::Gap(hit_number) => Some(self.line_count(hit_number)),
+            }
+        }
+
+        /// Return the first file hit that is (at least partly) visible.
+        pub fn first_hit(&self) -> Option<Cow<'_, FileHit>> {
+            self.hits.get(self.file_offset())
         }
 
         pub fn line_hit_number(&self) -> Option<usize> {
-            match self.line_selected {
-                Some(line_selected) => match self.file_selected {
-                    0 => match self.line_offset {
-                        None => Some(line_selected),
-                        Some(line_offset) => Some(line_offset + line_selected),
-                    },
-                    _ => Some(line_selected),
-                },
-                None => None,
+            match self.row(self.viewport.selected()?)? {
+                Row::Line(_, line_hit_number) => Some(line_hit_number),
+                Row::Path(_) | Row::Gap(_) => None,
             }
         }
 
         /// Return the currently selected file hit.
-        pub fn hit(&self) -> Option<&FileHit> {
-            match self.hit_number() {
-                Some(hit_number) => Some(&self.hits[hit_number]),
-                None => None,
-            }
+        pub fn hit(&self) -> Option<Cow<'_, FileHit>> {
+            self.hits.get(self.hit_number()?)
         }
 
-        pub fn hits(&self) -> &Vec<FileHit> {
+        pub fn hits(&self) -> &Hits {
             &self.hits
         }
 
+        /// Return if the file hit is collapsed to a single row.
+        pub fn is_collapsed(&self, file_hit: &FileHit) -> bool {
+            self.collapsed.contains(file_hit.path())
+        }
+
+        /// Return the number of line hits that are shown for the file hit with the number
+        /// `hit_number` (none if it is collapsed).
+        pub fn line_count(&self, hit_number: usize) -> usize {
+            match self.hits.path(hit_number) {
+                Some(path) if !self.collapsed.contains(path) => {
+                    self.hits.line_count(hit_number).unwrap_or(0)
+                }
+                _ => 0,
+            }
+        }
+
         /// Return if a line is selected or not.
         pub fn is_line_selected(&self) -> bool {
-            self.line_selected.is_some()
+            self.line_hit_number().is_some()
         }
 
         /// Return the row number that is selected.
-        fn selected_row_number(&self) -> usize {
-            match self.file_selected {
-                0 => match self.line_selected {
-                    None => 0,
-                    Some(line_selected) => match self.line_offset {
-                        None => line_selected + 1,
-                        Some(_) => line_selected,
-                    },
-                },
-                _ => {
-                    let mut selected_row_number = 0;
-
-                    let first_hit = &self.hits[self.file_offset];
-                    selected_row_number += (first_hit.line_hits().len() + 1)
-                        - match self.line_offset {
-                            None => 0,
-                            Some(line_offset) => line_offset + 1,
-                        };
-
-                    for hit_number in
-                        (self.file_offset + 1)..(self.file_offset + self.file_selected)
-                    {
-                        selected_row_number += self.hits[hit_number].line_hits().len() + 2;
-                    }
-
-                    selected_row_number += match self.line_selected {
-                        None => 1,
-                        Some(line_selected) => line_selected + 2,
-                    };
+        pub fn selected_row_number(&self) -> usize {
+            self.viewport.selected_row().unwrap_or(0)
+        }
 
-                    selected_row_number
-                }
+        /// Return the row with the number `number`.
+        fn row(&self, number: usize) -> Option<Row> {
+            if number >= self.row_count() {
+                return None;
             }
+            let hit_number: usize = self
+                .row_starts
+                .partition_point(|start| *start <= number)
+                .checked_sub(1)?;
+            let row: Row = match number - self.row_starts[hit_number] {
+                0 => Row::Path(hit_number),
+                line if line <= self.line_count(hit_number) => Row::Line(hit_number, line - 1),
+                _ => Row::Gap(hit_number),
+            };
+            Some(row)
         }
 
-        fn resize(&mut self, new_size: Size) -> Option<Effect> {
-            let rows_before = self.size.rows;
-            let selected_row_number = self.selected_row_number();
-            let position_percent: f64 = selected_row_number as f64 / rows_before as f64;
+        /// Return the number of the row of the line hit (or of the path if there is no line hit)
+        /// of the file hit.
+        fn row_number(&self, hit_number: usize, line_hit_number: Option<usize>) -> usize {
+            self.row_starts[hit_number] + line_hit_number.map_or(0, |number| number + 1)
+        }
 
-            let new_selected_row_number = (new_size.rows as f64 * position_percent) as usize;
+        /// Return the number of rows of the hits (there is no gap after the last file hit).
+        fn row_count(&self) -> usize {
+            match self.row_starts.last() {
+                Some(start) => start + self.line_count(self.row_starts.len() - 1) + 1,
+                None => 0,
+            }
+        }
 
-            self.size = new_size;
+        /// Count the rows of the file hits from the file hit with the number `from` on (after
+        /// file hits are added or folded).
+        fn update_rows(&mut self, from: usize) {
+            self.row_starts.truncate(from);
+            let mut start: usize = match from.checked_sub(1) {
+                Some(previous) => self.row_starts[previous] + self.line_count(previous) + 2,
+                None => 0,
+            };
+            for hit_number in from..self.hits.len() {
+                self.row_starts.push(start);
+                start += self.line_count(hit_number) + 2;
+            }
+            self.viewport.set_len(self.row_count());
+        }
 
-            match new_selected_row_number.cmp(&selected_row_number) {
-                Ordering::Less => {
-                    self.scroll_down(selected_row_number - new_selected_row_number);
-                }
-                Ordering::Greater => {
-                    self.scroll_up(new_selected_row_number - selected_row_number);
-                }
-                _ => {}
+        /// Move the selection off of the gap between two file hits (down if `down` is true,
+        /// otherwise up).
+        fn skip_gap(&mut self, down: bool) {
+            if let Some(Row::Gap(_)) = self.viewport.selected().and_then(|number| self.row(number))
+            {
+                match down {
+                    true => self.viewport.down(1),
+                    false => self.viewport.up(1),
+                };
             }
+        }
 
+        fn resize(&mut self, new_size: Size) -> Option<Effect> {
+            self.size = new_size;
+            self.viewport.resize(new_size.rows);
             None
         }
 
@@ -414,27 +735,92 @@ mod state {
 
         fn search(&mut self, phrase: &str, max_history_length: usize) -> Option<Effect> {
             self.focus();
+            // Keep the folds when refreshing the hits.
+            if self.phrase.as_deref() != Some(phrase) {
+                self.collapsed.clear();
+            }
             self.phrase = Some(phrase.to_string());
 
-            let phrase_searcher = PhraseSearcher::new(&self.dir, phrase);
-            self.hits = phrase_searcher.collect();
+            self.hits = Hits::default();
             self.searched = true;
 
             self.add_to_history(phrase, max_history_length);
 
-            self.file_offset = 0;
-            self.line_offset = None;
-            self.file_selected = 0;
-            self.line_selected = None;
+            self.row_starts.clear();
+            self.viewport.set_len(0);
+
+            self.searching = true;
+            Some(Effect::SendSearchPhraseRequest {
+                dir: self.dir.clone(),
+                phrase: phrase.to_string(),
+            })
+        }
+
+        /// Stop searching for the phrase (keeping the hits that were already found).
+        fn cancel(&mut self) -> Option<Effect> {
+            if !self.searching {
+                return None;
+            }
+            self.searching = false;
+            Some(Effect::CancelRequest)
+        }
+
+        fn handle_response(&mut self, response: Response) -> Option<Effect> {
+            #[cfg(feature = "logging")]
+            log::debug!("Handling response...");
+
+            if !self.searching {
+                #[cfg(feature = "logging")]
+                log::debug!("The hits are not being searched for.");
+                return None;
+            }
+
+            let last: bool = response.last();
+            let params: SearchPhraseResponseParams = match response.into_params() {
+                ResponseParams::SearchPhrase(params) => params,
+                _ => {
+                    #[cfg(feature = "logging")]
+                    log::error!("Unexpected response parameters.");
+                    return None;
+                }
+            };
+
+            let from: usize = self.hits.len();
+            for file_hit in params.into_file_hits() {
+                self.hits.push(file_hit);
+            }
+            self.update_rows(from);
+            if self.viewport.selected().is_none() {
+                self.viewport.first();
+            }
+
+            if !last {
+                return None;
+            }
+
+            self.searching = false;
+            run_hook(
+                self.config.hooks(),
+                HookEvent::SearchFinished {
+                    dir: &self.dir,
+                    phrase: self.phrase.as_deref().unwrap_or_default(),
+                    files: self.hits.len(),
+                },
+            );
 
             if self.hits.is_empty() {
+                self.focussed = false;
                 Some(Effect::Unfocus)
             } else {
                 None
             }
         }
 
+        /// Store the phrase in the history of searches (unless no searches are stored).
         fn add_to_history(&self, phrase: &str, max_length: usize) {
+            if max_length == 0 {
+                return;
+            }
             let mut data: Data = Data::read();
             data.searcher.add_to_history(phrase, max_length);
             data.write();
@@ -442,203 +828,136 @@ mod state {
         }
 
         fn down(&mut self) -> Option<Effect> {
-            match self.line_selected {
-                None => {
-                    self.line_selected = Some(0);
-                }
-                Some(line_selected) => {
-                    if self.line_hit_number().unwrap() < self.hit().unwrap().line_hits().len() - 1 {
-                        self.line_selected = Some(line_selected + 1);
-                    } else if self.hit_number().unwrap() < self.hits().len() - 1 {
-                        self.line_selected = None;
-                        self.file_selected += 1;
-                    }
-                }
-            }
-
-            let down_adjustment: usize =
-                (self.selected_row_number() + 1).saturating_sub(self.size.rows);
-            self.scroll_down(down_adjustment);
-
+            self.viewport.down(1);
+            self.skip_gap(true);
             None
         }
 
-        /// Select the last file hit and adjust the scroll if necessary.
+        /// Select the last file hit (with as many of its line hits visible as fit).
         fn really_down(&mut self) -> Option<Effect> {
             if self.hits.is_empty() {
                 return None;
             }
-
-            self.file_offset = self.hits.len() - 1;
-            self.line_offset = None;
-            self.file_selected = 0;
-            self.line_selected = None;
-
-            let up_adjustment: usize;
-            {
-                let last_file_hit: &FileHit = self.hits.last().unwrap();
-                let number_of_line_hits: usize = last_file_hit.line_hits().len();
-                up_adjustment = self.size.rows - (number_of_line_hits + 1);
-            }
-            // For now, scroll up one line at a time b/c there seems to be a bug w/ scrolling too
-            // many lines at a time
-            for _ in 0..up_adjustment {
-                self.scroll_up(1);
-            }
-
+            self.viewport.last();
+            self.viewport
+                .select(self.row_number(self.hits.len() - 1, None));
             None
         }
 
+        /// Scroll down by `rows` rows (keeping the selection unless it is scrolled past the top).
         fn scroll_down(&mut self, rows: usize) -> Option<Effect> {
-            for _ in 0..rows {
-                match self.line_offset {
-                    None => {
-                        self.line_offset = Some(0);
-                    }
-                    Some(line_offset) => {
-                        let first_visible_hit = &self.hits[self.file_offset];
-                        if line_offset < first_visible_hit.line_hits().len() {
-                            self.line_offset = Some(line_offset + 1);
-                            if self.file_selected == 0 {
-                                if let Some(line_selected) = self.line_selected {
-                                    self.line_selected = Some(line_selected.saturating_sub(1));
-                                }
-                            }
-                        } else if self.file_offset < self.hits.len() - 1 {
-                            self.file_offset += 1;
-                            self.file_selected = self.file_selected.saturating_sub(1);
-                            self.line_offset = None;
-                        }
-                    }
-                }
-            }
+            self.viewport.scroll_view_down(rows);
+            self.skip_gap(true);
             None
         }
 
         fn up(&mut self) -> Option<Effect> {
-            match self.line_selected {
-                None => match self.file_selected {
-                    0 => {
-                        if self.file_offset > 0 {
-                            self.file_offset -= 1;
-                            self.line_offset = Some(self.hit().unwrap().line_hits().len() - 1);
-                            self.line_selected = Some(0);
-                        }
-                    }
-                    1 => {
-                        self.file_selected = 0;
-                        match self.line_offset {
-                            None => {
-                                self.line_selected =
-                                    Some(self.hit().unwrap().line_hits().len() - 1);
-                            }
-                            Some(line_offset) => {
-                                if line_offset == self.hit().unwrap().line_hits().len() {
-                                    self.line_offset = Some(line_offset - 1);
-                                    self.line_selected = Some(0);
-                                } else {
-                                    self.line_selected = Some(
-                                        self.hit().unwrap().line_hits().len() - 1 - line_offset,
-                                    );
-                                }
-                            }
-                        }
-                    }
-                    _ => {
-                        self.file_selected -= 1;
-                        self.line_selected = Some(self.hit().unwrap().line_hits().len() - 1);
-                    }
-                },
-                Some(0) => match self.file_selected.cmp(&0) {
-                    Ordering::Equal => {
-                        match self.line_offset {
-                            None => {
-                                self.line_offset = None;
-                                self.line_selected = None;
-                            }
-                            Some(0) => {
-                                self.line_offset = None;
-                                self.line_selected = None;
-                            }
-                            Some(line_offset) => {
-                                self.line_offset = Some(line_offset - 1);
-                            }
-                        };
-                    }
-                    Ordering::Greater => {
-                        self.line_selected = None;
-                    }
-                    _ => {}
-                },
-                Some(line_selected) => {
-                    self.line_selected = Some(line_selected - 1);
-                }
-            }
-
+            self.viewport.up(1);
+            self.skip_gap(false);
             None
         }
 
-        /// Select the first file hit and adjust the scroll position if necessary.
+        /// Select the first file hit.
         fn really_up(&mut self) -> Option<Effect> {
             if self.hits.is_empty() {
                 return None;
             }
+            self.viewport.first();
+            None
+        }
 
-            self.file_offset = 0;
-            self.line_offset = None;
-            self.file_selected = 0;
-            self.line_selected = None;
+        /// Scroll up by `rows` rows (keeping the selection unless it is scrolled past the bottom).
+        fn scroll_up(&mut self, rows: usize) -> Option<Effect> {
+            self.viewport.scroll_view_up(rows);
+            self.skip_gap(false);
+            None
+        }
 
+        /// Select the path of the next file hit (skipping over the line hits).
+        fn next_file(&mut self) -> Option<Effect> {
+            let hit_number: Option<usize> = match self.hit_number() {
+                Some(hit_number) if hit_number + 1 < self.hits.len() => Some(hit_number),
+                _ => return None,
+            };
+            while self.hit_number() == hit_number || self.is_line_selected() {
+                let selection = (self.hit_number(), self.line_hit_number());
+                self.down();
+                if (self.hit_number(), self.line_hit_number()) == selection {
+                    break;
+                }
+            }
             None
         }
 
-        fn scroll_up(&mut self, mut rows: usize) -> Option<Effect> {
-            while rows > 0 {
-                match self.line_offset {
-                    Some(line_offset) => {
-                        if rows <= line_offset {
-                            self.line_offset = Some(line_offset - rows);
-                            if self.file_selected == 0 {
-                                if let Some(line_selected) = self.line_selected {
-                                    self.line_selected = Some(line_selected + rows);
-                                }
-                            }
-                            break;
-                        }
+        /// Select the path of the selected file hit if a line hit is selected, else select the
+        /// path of the previous file hit (skipping over the line hits).
+        fn previous_file(&mut self) -> Option<Effect> {
+            let hit_number: Option<usize> = match self.is_line_selected() {
+                true => None,
+                false => self.hit_number(),
+            };
+            while self.hit_number() == hit_number || self.is_line_selected() {
+                let selection = (self.hit_number(), self.line_hit_number());
+                self.up();
+                if (self.hit_number(), self.line_hit_number()) == selection {
+                    break;
+                }
+            }
+            None
+        }
 
-                        if rows == line_offset + 1 {
-                            self.line_offset = None;
-                            if self.file_selected == 0 {
-                                if let Some(line_selected) = self.line_selected {
-                                    self.line_selected = Some(line_selected + rows + 1);
-                                }
-                            }
-                            break;
-                        }
+        /// Collapse the selected file hit to a single row (or expand it if it is collapsed).
+        fn toggle_fold(&mut self) -> Option<Effect> {
+            let hit_number: usize = match self.hit_number() {
+                Some(hit_number) => hit_number,
+                None => return Some(Effect::Bell),
+            };
+            // The path of the file hit stays where it is (or comes back to the top).
+            let row: usize = self
+                .selected_row_number()
+                .saturating_sub(self.line_hit_number().map_or(0, |number| number + 1));
+
+            let path: PathBuf = self.hits.path(hit_number).unwrap().to_path_buf();
+            if !self.collapsed.remove(&path) {
+                self.collapsed.insert(path);
+            }
+            self.update_rows(hit_number);
+            self.viewport
+                .select_at_row(self.row_number(hit_number, None), row);
+            None
+        }
 
-                        if self.file_offset == 0 {
-                            break;
-                        }
+        fn toggle_wrap(&mut self) -> Option<Effect> {
+            self.wrap = !self.wrap;
+            self.column_offset = 0;
+            None
+        }
 
-                        if self.file_selected == 0 {
-                            if let Some(line_selected) = self.line_selected {
-                                self.line_selected = Some(line_selected + line_offset);
-                            }
-                        }
-                        rows -= line_offset + 1;
-                        self.file_offset -= 1;
-                        self.line_offset = Some(self.hits[self.file_offset].line_hits().len());
-                    }
-                    None => {
-                        if self.file_offset == 0 {
-                            break;
-                        }
+        fn scroll_left(&mut self, columns: usize) -> Option<Effect> {
+            if self.wrap || self.column_offset == 0 {
+                return Some(Effect::Bell);
+            }
+            self.column_offset = self.column_offset.saturating_sub(columns);
+            None
+        }
 
-                        rows -= 1;
-                        self.file_offset -= 1;
-                        self.file_selected += 1;
-                        self.line_offset = Some(self.hits[self.file_offset].line_hits().len());
-                    }
+        fn scroll_right(&mut self, columns: usize) -> Option<Effect> {
+            if self.wrap || !self.searched || self.hits.is_empty() {
+                return Some(Effect::Bell);
+            }
+            self.column_offset += columns;
+            None
+        }
+
+        /// Perform a motion `count` times (stopping early if it has an effect).
+        fn repeat(
+            &mut self,
+            count: usize,
+            motion: fn(&mut Self) -> Option<Effect>,
+        ) -> Option<Effect> {
+            for _ in 0..count {
+                if let Some(effect) = motion(self) {
+                    return Some(effect);
                 }
             }
             None
@@ -653,7 +972,7 @@ mod state {
         }
 
         fn edit(&mut self) -> Option<Effect> {
-            let file_hit: &FileHit = self.hit().unwrap();
+            let file_hit: Cow<FileHit> = self.hit().unwrap();
             let path: &Path = file_hit.path();
 
             let mut vim_args_builder = VimArgsBuilder::new().path(path);
@@ -668,6 +987,41 @@ mod state {
             Some(Effect::OpenVim(vim_args))
         }
 
+        /// Open vim with all of the line hits of the selected file hit loaded as the quickfix list.
+        fn edit_all(&mut self) -> Option<Effect> {
+            let (file_hit, phrase) = match (self.hit(), &self.phrase) {
+                (Some(file_hit), Some(phrase)) => (file_hit, phrase),
+                _ => return Some(Effect::Bell),
+            };
+
+            let path: String = file_hit.path().to_string_lossy().to_string();
+            let locations: String = file_hit
+                .line_hits()
+                .iter()
+                .map(|line_hit| {
+                    let column: usize = line_hit.line().find(phrase.as_str()).unwrap_or(0) + 1;
+                    format!(
+                        "{}:{}:{}:{}\n",
+                        path,
+                        line_hit.line_number(),
+                        column,
+                        line_hit.line()
+                    )
+                })
+                .collect();
+
+            let quickfix: PathBuf =
+                env::temp_dir().join(format!("insh-{}-hits.txt", process::id()));
+            if let Err(_error) = fs::write(&quickfix, locations) {
+                #[cfg(feature = "logging")]
+                log::error!("Failed to write the locations of the hits: {}", _error);
+                return Some(Effect::Bell);
+            }
+
+            let vim_args: VimArgs = VimArgsBuilder::new().quickfix(&quickfix).build();
+            Some(Effect::OpenVim(vim_args))
+        }
+
         fn goto(&mut self) -> Option<Effect> {
             self._goto(false)
         }
@@ -692,16 +1046,16 @@ mod state {
         }
 
         /// If a file path is selected, copy it to the system clipboard. Else if the line of a file is selected, then copy it.
-        fn yank(&mut self) -> Option<Effect> {
-            self._yank(false)
+        fn yank(&mut self, clipboard: &RefCell<Clipboard>) -> Option<Effect> {
+            self._yank(false, clipboard)
         }
 
         /// If a file path is selected, copy the absolute file path to the system clipboard. Else if the line of a file is selected, then copy it.
-        fn really_yank(&mut self) -> Option<Effect> {
-            self._yank(true)
+        fn really_yank(&mut self, clipboard: &RefCell<Clipboard>) -> Option<Effect> {
+            self._yank(true, clipboard)
         }
 
-        fn _yank(&mut self, really: bool) -> Option<Effect> {
+        fn _yank(&mut self, really: bool, clipboard: &RefCell<Clipboard>) -> Option<Effect> {
             if let Some(file_hit) = self.hit() {
                 let contents: String = match self.line_hit_number() {
                     Some(line_hit_number) => {
@@ -709,8 +1063,7 @@ mod state {
                         line_hit.line().to_string()
                     }
                     None => {
-                        let mut path: String =
-                            file_hit.path().to_path_buf().to_string_lossy().to_string();
+                        let mut path: String = file_hit.path().to_string_lossy().to_string();
                         if !really {
                             let dir_string: String = self.dir().to_string_lossy().to_string();
                             path = path.strip_prefix(&dir_string).unwrap().to_string();
@@ -721,8 +1074,7 @@ mod state {
                         path
                     }
                 };
-                let mut clipboard = Clipboard::new();
-                clipboard.copy(contents);
+                clipboard.borrow_mut().copy(contents);
             }
             None
         }
@@ -737,81 +1089,263 @@ mod state {
                     phrase,
                     max_history_length,
                 } => self.search(&phrase, max_history_length),
-                Action::Down => self.down(),
+                Action::Cancel => self.cancel(),
+                Action::HandleResponse(response) => self.handle_response(response),
+                Action::Down { count } => self.repeat(count, Self::down),
                 Action::ReallyDown => self.really_down(),
-                Action::ScrollDown => self.scroll_down(1),
-                Action::Up => self.up(),
+                Action::ScrollDown { count } => self.scroll_down(count),
+                Action::Up { count } => self.repeat(count, Self::up),
                 Action::ReallyUp => self.really_up(),
-                Action::ScrollUp => self.scroll_up(1),
+                Action::ScrollUp { count } => self.scroll_up(count),
+                Action::HalfPageDown { count } => {
+                    self.repeat(self.viewport.half_page() * count, Self::down)
+                }
+                Action::HalfPageUp { count } => {
+                    self.repeat(self.viewport.half_page() * count, Self::up)
+                }
+                Action::PageDown { count } => self.repeat(self.viewport.page() * count, Self::down),
+                Action::PageUp { count } => self.repeat(self.viewport.page() * count, Self::up),
                 Action::Refresh { max_history_length } => self.refresh(max_history_length),
                 Action::Edit => self.edit(),
+                Action::EditAll => self.edit_all(),
                 Action::Goto => self.goto(),
                 Action::ReallyGoto => self.really_goto(),
-                Action::Yank => self.yank(),
-                Action::ReallyYank => self.really_yank(),
+                Action::Yank { clipboard } => self.yank(&clipboard),
+                Action::ReallyYank { clipboard } => self.really_yank(&clipboard),
+                Action::ToggleFold => self.toggle_fold(),
+                Action::ToggleWrap => self.toggle_wrap(),
+                Action::ScrollLeft { columns } => self.scroll_left(columns),
+                Action::ScrollRight { columns } => self.scroll_right(columns),
+                Action::NextFile { count } => self.repeat(count, Self::next_file),
+                Action::PreviousFile { count } => self.repeat(count, Self::previous_file),
             }
         }
     }
 
+    impl Inspect for State {
+        fn inspect(&self) -> Inspection {
+            Inspection::new("Contents")
+                .field("dir", &self.dir)
+                .field("phrase", &self.phrase)
+                .field("searched", self.searched)
+                .field("searching", self.searching)
+                .field("file_hits", self.hits.len())
+                .field("spilled", self.hits.spilled())
+                .field("hit", self.hit_number())
+                .field("line_hit", self.line_hit_number())
+                .field("collapsed", self.collapsed.len())
+                .field("wrap", self.wrap)
+                .field("column_offset", self.column_offset)
+        }
+    }
+
     #[cfg(test)]
     mod tests {
         use super::*;
 
         use test_case::test_case;
 
-        #[test_case(&mut State::default(), 0, State::default();)]
-        #[test_case(
-            &mut State{
-                size: Size{rows: 1, columns: 2},
-                hits: vec![FileHit::new(Path::new(""), vec![LineHit::new(0, "")])],
-                ..Default::default()
-            },
-            1,
-            State{
-                size: Size{rows: 1, columns: 2},
-                hits: vec![FileHit::new(Path::new(""), vec![LineHit::new(0, "")])],
+        /// Return two file hits (the first with two line hits and the second with one).
+        fn two_file_hits() -> Vec<FileHit> {
+            vec![
+                FileHit::new(
+                    Path::new("a"),
+                    vec![LineHit::new(0, ""), LineHit::new(1, "")],
+                ),
+                FileHit::new(Path::new("b"), vec![LineHit::new(0, "")]),
+            ]
+        }
+
+        /// Return the state of two file hits (see [`two_file_hits`]) that are shown in `rows` rows
+        /// with the first file hit selected.
+        fn state(rows: usize, collapsed: HashSet<PathBuf>) -> State {
+            let mut state = State {
+                size: Size::new(rows, 5),
+                hits: two_file_hits().into(),
+                collapsed,
+                viewport: ListViewport::new(rows, 0),
                 ..Default::default()
             };
-        )]
-        #[test_case(
-            &mut State{
-                size: Size{rows: 2, columns: 5},
-                hits: vec![
-                    FileHit::new(Path::new(""), vec![LineHit::new(0, ""), LineHit::new(1, "")]),
-                    FileHit::new(Path::new(""), vec![LineHit::new(0, "")]),
-                ],
-                file_offset: 1,
-                line_offset: None,
-                file_selected: 0,
-                line_selected: None,
-                ..Default::default()
-            },
-            1,
-            State{
-                size: Size{rows: 2, columns: 5},
-                hits: vec![
-                    FileHit::new(Path::new(""), vec![LineHit::new(0, ""), LineHit::new(1, "")]),
-                    FileHit::new(Path::new(""), vec![LineHit::new(0, "")]),
-                ],
-                file_offset: 0,
-                line_offset: Some(2),
-                file_selected: 1,
-                line_selected: None,
+            state.update_rows(0);
+            state.viewport.first();
+            state
+        }
+
+        /// Select the line hit (or the path if there is no line hit) of the file hit on the row.
+        fn select(
+            state: &mut State,
+            hit_number: usize,
+            line_hit_number: Option<usize>,
+            row: usize,
+        ) {
+            let number: usize = state.row_number(hit_number, line_hit_number);
+            state.viewport.select_at_row(number, row);
+        }
+
+        #[test_case(HashSet::new(), &[Row::Path(0), Row::Line(0, 0), Row::Line(0, 1), Row::Gap(0), Row::Path(1), Row::Line(1, 0)]; "expanded")]
+        #[test_case(HashSet::from([PathBuf::from("a")]), &[Row::Path(0), Row::Gap(0), Row::Path(1), Row::Line(1, 0)]; "collapsed")]
+        fn test_row(collapsed: HashSet<PathBuf>, expected_rows: &[Row]) {
+            let state: State = state(10, collapsed);
+
+            let rows: Vec<Row> = (0..state.row_count())
+                .map(|number| state.row(number).unwrap())
+                .collect();
+            assert_eq!(rows, expected_rows);
+            assert_eq!(state.row(state.row_count()), None);
+        }
+
+        #[test_case(1, 0, Some(2), 1, None; "to the gap")]
+        #[test_case(2, 0, Some(1), 0, Some(1); "selection moves off of the gap")]
+        #[test_case(5, 0, None, 0, Some(0); "past the first row")]
+        fn test_scroll_up(
+            rows: usize,
+            expected_file_offset: usize,
+            expected_line_offset: Option<usize>,
+            expected_hit_number: usize,
+            expected_line_hit_number: Option<usize>,
+        ) {
+            let mut state: State = state(2, HashSet::new());
+            select(&mut state, 1, None, 0);
+
+            state.scroll_up(rows);
+
+            assert_eq!(state.file_offset(), expected_file_offset);
+            assert_eq!(state.line_offset(), expected_line_offset);
+            assert_eq!(state.hit_number(), Some(expected_hit_number));
+            assert_eq!(state.line_hit_number(), expected_line_hit_number);
+        }
+
+        #[test_case(HashSet::new(), 0, Some(0); "expanded")]
+        #[test_case(HashSet::from([PathBuf::from("a")]), 1, None; "collapsed")]
+        fn test_down(
+            collapsed: HashSet<PathBuf>,
+            expected_hit_number: usize,
+            expected_line_hit_number: Option<usize>,
+        ) {
+            let mut state: State = state(10, collapsed);
+
+            state.down();
+
+            assert_eq!(state.hit_number(), Some(expected_hit_number));
+            assert_eq!(state.line_hit_number(), expected_line_hit_number);
+        }
+
+        #[test_case(HashSet::new(), Some(1); "expanded")]
+        #[test_case(HashSet::from([PathBuf::from("a")]), None; "collapsed")]
+        fn test_up(collapsed: HashSet<PathBuf>, expected_line_hit_number: Option<usize>) {
+            let mut state: State = state(10, collapsed);
+            select(&mut state, 1, None, 0);
+
+            state.up();
+
+            assert_eq!(state.hit_number(), Some(0));
+            assert_eq!(state.line_hit_number(), expected_line_hit_number);
+        }
+
+        #[test]
+        fn test_really_down() {
+            let mut state: State = state(4, HashSet::new());
+
+            state.really_down();
+
+            assert_eq!(state.hit_number(), Some(1));
+            assert_eq!(state.line_hit_number(), None);
+            assert_eq!(state.selected_row_number(), 2);
+        }
+
+        #[test_case(0, None, 1, 1, None; "from a path")]
+        #[test_case(0, Some(1), 1, 1, None; "from a line")]
+        #[test_case(0, None, 2, 1, None; "from the last file")]
+        fn test_next_file(
+            hit_number: usize,
+            line_hit_number: Option<usize>,
+            count: usize,
+            expected_hit_number: usize,
+            expected_line_hit_number: Option<usize>,
+        ) {
+            let mut state: State = state(10, HashSet::new());
+            select(&mut state, hit_number, line_hit_number, 0);
+
+            state.perform(Action::NextFile { count });
+
+            assert_eq!(state.hit_number(), Some(expected_hit_number));
+            assert_eq!(state.line_hit_number(), expected_line_hit_number);
+        }
+
+        #[test_case(1, None, 1, 0, None; "from a path")]
+        #[test_case(1, Some(0), 1, 1, None; "from a line")]
+        #[test_case(1, Some(0), 2, 0, None; "with a count")]
+        #[test_case(0, None, 1, 0, None; "from the first file")]
+        fn test_previous_file(
+            hit_number: usize,
+            line_hit_number: Option<usize>,
+            count: usize,
+            expected_hit_number: usize,
+            expected_line_hit_number: Option<usize>,
+        ) {
+            let mut state: State = state(10, HashSet::new());
+            select(&mut state, hit_number, line_hit_number, 0);
+
+            state.perform(Action::PreviousFile { count });
+
+            assert_eq!(state.hit_number(), Some(expected_hit_number));
+            assert_eq!(state.line_hit_number(), expected_line_hit_number);
+        }
+
+        #[test]
+        fn test_toggle_fold() {
+            let mut state: State = state(3, HashSet::new());
+            select(&mut state, 0, Some(1), 1);
+
+            state.toggle_fold();
+
+            assert!(state.is_collapsed(&state.hits.get(0).unwrap()));
+            assert_eq!(state.line_offset(), None);
+            assert_eq!(state.hit_number(), Some(0));
+            assert_eq!(state.line_hit_number(), None);
+            assert_eq!(state.selected_row_number(), 0);
+            assert_eq!(state.line_count(0), 0);
+            assert_eq!(state.row_count(), 4);
+
+            state.toggle_fold();
+
+            assert!(!state.is_collapsed(&state.hits.get(0).unwrap()));
+            assert_eq!(state.line_count(0), 2);
+            assert_eq!(state.row_count(), 6);
+        }
+
+        #[test]
+        fn test_scroll_horizontally() {
+            let mut state = State {
+                size: Size::new(10, 5),
+                searched: true,
+                hits: two_file_hits().into(),
                 ..Default::default()
             };
-        )]
-        fn test_scroll_up(state: &mut State, rows: usize, expected_state: State) {
-            state.scroll_up(rows);
 
-            assert_eq!(*state, expected_state);
+            assert!(state.scroll_left(1).is_some());
+            assert!(state.scroll_right(3).is_none());
+            assert!(state.scroll_left(1).is_none());
+            assert_eq!(state.column_offset(), 2);
+
+            state.toggle_wrap();
+
+            assert_eq!(state.column_offset(), 0);
+            assert!(state.scroll_right(1).is_some());
         }
     }
 }
 use state::State;
 
 mod action {
+    use crate::clipboard::Clipboard;
+
+    use insh_api::Response;
     use rend::Size;
 
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
     pub enum Action {
         Resize {
             size: Size,
@@ -821,20 +1355,62 @@ mod action {
             phrase: String,
             max_history_length: usize,
         },
-        Down,
+        /// Stop searching for the phrase.
+        Cancel,
+        Down {
+            count: usize,
+        },
         ReallyDown,
-        ScrollDown,
-        Up,
+        ScrollDown {
+            count: usize,
+        },
+        Up {
+            count: usize,
+        },
         ReallyUp,
-        ScrollUp,
+        ScrollUp {
+            count: usize,
+        },
+        HalfPageDown {
+            count: usize,
+        },
+        HalfPageUp {
+            count: usize,
+        },
+        PageDown {
+            count: usize,
+        },
+        PageUp {
+            count: usize,
+        },
         Refresh {
             max_history_length: usize,
         },
         Edit,
+        EditAll,
         Goto,
         ReallyGoto,
-        Yank,
-        ReallyYank,
+        Yank {
+            clipboard: Rc<RefCell<Clipboard>>,
+        },
+        ReallyYank {
+            clipboard: Rc<RefCell<Clipboard>>,
+        },
+        ToggleFold,
+        ToggleWrap,
+        ScrollLeft {
+            columns: usize,
+        },
+        ScrollRight {
+            columns: usize,
+        },
+        NextFile {
+            count: usize,
+        },
+        PreviousFile {
+            count: usize,
+        },
+        HandleResponse(Response),
     }
 }
 use action::Action;
@@ -846,6 +1422,8 @@ mod effect {
 
     pub enum Effect {
         Unfocus,
+        SendSearchPhraseRequest { dir: PathBuf, phrase: String },
+        CancelRequest,
         Goto { dir: PathBuf, file: Option<PathBuf> },
         OpenVim(VimArgs),
         Bell,
@@ -1,21 +1,33 @@
 mod props {
+    use crate::clipboard::Clipboard;
     use crate::config::Config;
 
     use rend::Size;
 
+    use std::cell::RefCell;
     use std::path::PathBuf;
+    use std::rc::Rc;
+    use std::sync::Arc;
 
     pub struct Props {
-        pub config: Config,
+        pub config: Arc<Config>,
+        pub clipboard: Rc<RefCell<Clipboard>>,
         pub dir: PathBuf,
         pub size: Size,
         pub phrase: Option<String>,
     }
 
     impl Props {
-        pub fn new(config: Config, dir: PathBuf, size: Size, phrase: Option<String>) -> Self {
+        pub fn new(
+            config: Arc<Config>,
+            clipboard: Rc<RefCell<Clipboard>>,
+            dir: PathBuf,
+            size: Size,
+            phrase: Option<String>,
+        ) -> Self {
             Self {
                 config,
+                clipboard,
                 dir,
                 size,
                 phrase,
@@ -28,47 +40,54 @@ pub use props::Props;
 mod searcher {
     use super::super::{ContentsEffect, ContentsEvent};
     use super::{Action, Effect, Focus, Props, State};
+    use crate::inspect::{Inspect, Inspection};
 
     use crate::components::common::{PhraseEffect, PhraseEvent};
     use crate::Stateful;
 
+    use insh_api::Response;
     use rend::{Fabric, Size};
     use term::TermEvent;
-    use til::Component;
+    use til::{Component, Event};
 
     pub struct Searcher {
         state: State,
     }
 
-    impl Component<Props, TermEvent, Effect> for Searcher {
+    impl Component<Props, Event<Response>, Effect> for Searcher {
         fn new(props: Props) -> Self {
             let state = State::from(props);
             Self { state }
         }
 
-        fn handle(&mut self, event: TermEvent) -> Option<Effect> {
+        fn handle(&mut self, event: Event<Response>) -> Option<Effect> {
             match event {
-                TermEvent::Resize(size) => {
+                Event::TermEvent(TermEvent::Resize(size)) => {
                     let contents_size = Size::new(size.rows.saturating_sub(2), size.columns);
                     self.state
                         .contents
                         .handle(ContentsEvent::TermEvent(TermEvent::Resize(contents_size)));
                     None
                 }
-                _ => match self.state.focus() {
+                // NOTE: The responses go to the contents even if the phrase is focussed so that the
+                // hits keep arriving after the contents are unfocussed.
+                Event::Response(response) => {
+                    let contents_effect = self
+                        .state
+                        .contents
+                        .handle(ContentsEvent::Response(response));
+                    self.handle_contents_effect(contents_effect)
+                }
+                Event::TermEvent(event) => match self.state.focus() {
                     Focus::Phrase => {
                         let phrase_event = PhraseEvent::TermEvent(event);
                         let phrase_effect = self.state.phrase.handle(phrase_event);
                         let action: Option<Action> = match phrase_effect {
                             Some(PhraseEffect::Enter { phrase }) => {
+                                self.state.perform(Action::FocusContents);
                                 let contents_event = ContentsEvent::Search { phrase };
                                 let contents_effect = self.state.contents.handle(contents_event);
-                                if let Some(ContentsEffect::Unfocus) = contents_effect {
-                                    self.state.phrase.handle(PhraseEvent::Focus);
-                                    Some(Action::FocusPhrase)
-                                } else {
-                                    Some(Action::FocusContents)
-                                }
+                                return self.handle_contents_effect(contents_effect);
                             }
                             Some(PhraseEffect::Bell) => {
                                 return Some(Effect::Bell);
@@ -86,28 +105,7 @@ mod searcher {
                     Focus::Contents => {
                         let contents_event = ContentsEvent::TermEvent(event);
                         let contents_effect = self.state.contents.handle(contents_event);
-                        let action: Option<Action> = match contents_effect {
-                            Some(ContentsEffect::Unfocus) => {
-                                self.state.phrase.handle(PhraseEvent::Focus);
-                                Some(Action::FocusPhrase)
-                            }
-                            Some(ContentsEffect::Goto { dir, file }) => {
-                                Some(Action::Goto { dir, file })
-                            }
-                            Some(ContentsEffect::OpenVim(vim_args)) => {
-                                Some(Action::OpenVim(vim_args))
-                            }
-                            Some(ContentsEffect::Bell) => {
-                                return Some(Effect::Bell);
-                            }
-                            None => None,
-                        };
-
-                        if let Some(action) = action {
-                            self.state.perform(action)
-                        } else {
-                            None
-                        }
+                        self.handle_contents_effect(contents_effect)
                     }
                 },
             }
@@ -139,6 +137,45 @@ mod searcher {
             }
         }
     }
+
+    impl Searcher {
+        /// Handle an effect of the contents.
+        fn handle_contents_effect(
+            &mut self,
+            contents_effect: Option<ContentsEffect>,
+        ) -> Option<Effect> {
+            let action: Option<Action> = match contents_effect {
+                Some(ContentsEffect::Unfocus) => {
+                    self.state.phrase.handle(PhraseEvent::Focus);
+                    Some(Action::FocusPhrase)
+                }
+                Some(ContentsEffect::SendSearchPhraseRequest { dir, phrase }) => {
+                    return Some(Effect::SendSearchPhraseRequest { dir, phrase });
+                }
+                Some(ContentsEffect::CancelRequest) => {
+                    return Some(Effect::CancelRequest);
+                }
+                Some(ContentsEffect::Goto { dir, file }) => Some(Action::Goto { dir, file }),
+                Some(ContentsEffect::OpenVim(vim_args)) => Some(Action::OpenVim(vim_args)),
+                Some(ContentsEffect::Bell) => {
+                    return Some(Effect::Bell);
+                }
+                None => None,
+            };
+
+            if let Some(action) = action {
+                self.state.perform(action)
+            } else {
+                None
+            }
+        }
+    }
+
+    impl Inspect for Searcher {
+        fn inspect(&self) -> Inspection {
+            self.state.inspect()
+        }
+    }
 }
 pub use searcher::Searcher;
 
@@ -148,7 +185,16 @@ mod effect {
     use std::path::PathBuf;
 
     pub enum Effect {
-        Goto { dir: PathBuf, file: Option<PathBuf> },
+        SendSearchPhraseRequest {
+            dir: PathBuf,
+            phrase: String,
+        },
+        /// Cancel the request for the hits (which is still in flight).
+        CancelRequest,
+        Goto {
+            dir: PathBuf,
+            file: Option<PathBuf>,
+        },
         OpenVim(VimArgs),
         Bell,
         Quit,
@@ -157,11 +203,12 @@ mod effect {
 pub use effect::Effect;
 
 mod state {
-    use super::super::{Contents, ContentsEffect, ContentsEvent, ContentsProps};
+    use super::super::{Contents, ContentsProps};
     use super::{Action, Effect, Props};
     use crate::auto_completer::AutoCompleter;
     use crate::auto_completers::SearchCompleter;
     use crate::components::common::{Dir, DirProps, Phrase, PhraseEvent, PhraseProps};
+    use crate::inspect::{Inspect, Inspection};
     use crate::programs::VimArgs;
     use crate::Stateful;
 
@@ -239,11 +286,13 @@ mod state {
                 Some(Box::new(SearchCompleter::new()));
             let phrase_props = PhraseProps::builder()
                 .auto_completer(search_completer)
+                .clipboard(props.clipboard.clone())
                 .build();
             let phrase = Phrase::new(phrase_props);
 
             let contents_size = Size::new(props.size.rows.saturating_sub(2), props.size.columns);
-            let contents_props = ContentsProps::new(props.config, props.dir, contents_size);
+            let contents_props =
+                ContentsProps::new(props.config, props.clipboard, props.dir, contents_size);
             let contents = Contents::new(contents_props);
 
             let mut state = Self {
@@ -253,34 +302,28 @@ mod state {
                 contents,
             };
 
+            // NOTE: The search for the starting phrase is started by the starting enter key.
             if let Some(phrase) = props.phrase {
-                state.phrase.handle(PhraseEvent::Set {
-                    phrase: phrase.clone(),
-                });
-
-                let contents_event = ContentsEvent::Search { phrase };
-                let contents_effect = state.contents.handle(contents_event);
-                if let Some(ContentsEffect::Unfocus) = contents_effect {
-                    state.phrase.handle(PhraseEvent::Focus);
-                    state.focus = Focus::Phrase;
-                } else {
-                    state.phrase.handle(PhraseEvent::Unfocus);
-                    state.focus = Focus::Contents;
-                }
+                state.phrase.handle(PhraseEvent::Set { phrase });
             }
 
             state
         }
     }
 
+    #[derive(Debug, Default)]
     pub enum Focus {
+        #[default]
         Phrase,
         Contents,
     }
 
-    impl Default for Focus {
-        fn default() -> Self {
-            Self::Phrase
+    impl Inspect for State {
+        fn inspect(&self) -> Inspection {
+            Inspection::new("Searcher")
+                .field("focus", &self.focus)
+                .field("phrase", self.phrase.value())
+                .child(self.contents.inspect())
         }
     }
 }
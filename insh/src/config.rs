@@ -4,7 +4,11 @@ Configuration options loaded from the YAML file `~/.insh-config` if it exists.
 
 /// Configuration options.
 mod config {
-    use super::{GeneralConfig, SearcherConfig};
+    use super::{
+        BrowserConfig, EditorConfig, GeneralConfig, HooksConfig, KeysConfig, MountConfig,
+        PaletteConfig, SearcherConfig, SortConfig,
+    };
+    use crate::expand::expand_path;
 
     use std::fmt::{Display, Formatter, Result as FormatResult};
     use std::fs::File;
@@ -12,7 +16,10 @@ mod config {
     use std::path::PathBuf;
 
     use serde::Deserialize;
-    use serde_yaml::Error as YamlParseError;
+    use serde_yaml::{Error as YamlParseError, Value};
+
+    /// The key of the named profiles (which override the other options when they are selected).
+    const PROFILES_KEY: &str = "profiles";
 
     /// Configuration options.
     #[derive(Deserialize, Debug, Default, Clone, Eq, PartialEq)]
@@ -20,9 +27,33 @@ mod config {
         /// General configuration.
         #[serde(default)]
         general: GeneralConfig,
+        /// Configuration of the Browser.
+        #[serde(default)]
+        browser: BrowserConfig,
         /// Configuration of the Searcher.
         #[serde(default)]
         searcher: SearcherConfig,
+        /// Configuration of the command palette.
+        #[serde(default)]
+        palette: PaletteConfig,
+        /// Configuration of opening files.
+        #[serde(default)]
+        editor: EditorConfig,
+        /// Configuration of sorting files.
+        #[serde(default)]
+        sort: SortConfig,
+        /// Configuration of the leader key and the chords.
+        #[serde(default)]
+        keys: KeysConfig,
+        /// The network locations that can be mounted.
+        #[serde(default)]
+        mounts: Vec<MountConfig>,
+        /// The shell commands that are run when events happen.
+        #[serde(default)]
+        hooks: HooksConfig,
+        /// The executables of the plugins (which advertise commands for the command palette).
+        #[serde(default)]
+        plugins: Vec<String>,
     }
 
     impl Config {
@@ -40,7 +71,9 @@ mod config {
 
         /// Return the `Config` loaded from the default file if it exists or the default config if the
         /// file does not exist. If there is an error then return a `ConfigLoadError`.
-        pub fn load() -> ConfigLoadResult {
+        ///
+        /// If a profile is given, then the options of the profile override the other options.
+        pub fn load(profile: Option<&str>) -> ConfigLoadResult {
             let path: PathBuf = match Self::default_path() {
                 Ok(path) => path,
                 Err(error) => {
@@ -51,9 +84,14 @@ mod config {
             let file: File = match File::open(path.clone()) {
                 Ok(file) => file,
                 Err(error) => match error.kind() {
-                    IOErrorKind::NotFound => {
+                    IOErrorKind::NotFound if profile.is_none() => {
                         return Ok(Config::default());
                     }
+                    IOErrorKind::NotFound => {
+                        // NOTE: Without a configuration file there are no profiles.
+                        let profile: String = profile.unwrap().to_string();
+                        return Err(ConfigLoadError::UnknownProfile { path, profile });
+                    }
                     IOErrorKind::PermissionDenied => {
                         return Err(ConfigLoadError::PermissionDeniedError(path));
                     }
@@ -63,7 +101,16 @@ mod config {
                 },
             };
 
-            match serde_yaml::from_reader(file) {
+            let mut value: Value = match serde_yaml::from_reader(file) {
+                Ok(value) => value,
+                Err(error) => return Err(ConfigLoadError::ParseError { path, error }),
+            };
+            if !apply_profile(&mut value, profile) {
+                let profile: String = profile.unwrap().to_string();
+                return Err(ConfigLoadError::UnknownProfile { path, profile });
+            }
+
+            match serde_yaml::from_value(value) {
                 Ok(config) => Ok(config),
                 Err(error) => Err(ConfigLoadError::ParseError { path, error }),
             }
@@ -74,10 +121,105 @@ mod config {
             &self.general
         }
 
+        /// Return the browser configuration.
+        pub fn browser(&self) -> &BrowserConfig {
+            &self.browser
+        }
+
         /// Return the searcher configuration.
         pub fn searcher(&self) -> &SearcherConfig {
             &self.searcher
         }
+
+        /// Return the command palette configuration.
+        pub fn palette(&self) -> &PaletteConfig {
+            &self.palette
+        }
+
+        /// Return the editor configuration.
+        pub fn editor(&self) -> &EditorConfig {
+            &self.editor
+        }
+
+        /// Return the sorting configuration.
+        pub fn sort(&self) -> &SortConfig {
+            &self.sort
+        }
+
+        /// Return the configuration of the leader key and the chords.
+        pub fn keys(&self) -> &KeysConfig {
+            &self.keys
+        }
+
+        /// Return the network locations that can be mounted.
+        pub fn mounts(&self) -> &[MountConfig] {
+            &self.mounts
+        }
+
+        /// Return the shell commands that are run when events happen.
+        pub fn hooks(&self) -> &HooksConfig {
+            &self.hooks
+        }
+
+        /// Return the executables of the plugins (with a leading `~` and environment variables
+        /// expanded).
+        pub fn plugins(&self) -> Vec<PathBuf> {
+            self.plugins
+                .iter()
+                .map(|plugin| match expand_path(plugin) {
+                    Ok(plugin) => plugin,
+                    Err(_) => PathBuf::from(plugin),
+                })
+                .collect()
+        }
+
+        /// Turn on the read-only mode (for example if it is requested on the command line).
+        pub fn set_read_only(&mut self) {
+            self.general.read_only = true;
+        }
+    }
+
+    /// Remove the profiles from the options and override the other options with the options of the
+    /// profile (if one is given). Return false if the profile doesn't exist.
+    fn apply_profile(value: &mut Value, profile: Option<&str>) -> bool {
+        let profiles: Option<Value> = match value {
+            Value::Mapping(mapping) => mapping.remove(Value::from(PROFILES_KEY)),
+            _ => None,
+        };
+
+        let profile: &str = match profile {
+            Some(profile) => profile,
+            None => return true,
+        };
+        let overrides: Value = match profiles {
+            Some(Value::Mapping(mut profiles)) => match profiles.remove(Value::from(profile)) {
+                Some(overrides) => overrides,
+                None => return false,
+            },
+            _ => return false,
+        };
+
+        merge(value, overrides);
+        true
+    }
+
+    /// Merge the overrides into the options (replacing the options that aren't mappings).
+    fn merge(value: &mut Value, overrides: Value) {
+        match (value, overrides) {
+            (Value::Mapping(mapping), Value::Mapping(overrides)) => {
+                for (key, override_value) in overrides {
+                    match mapping.get_mut(&key) {
+                        Some(value) => merge(value, override_value),
+                        None => {
+                            mapping.insert(key, override_value);
+                        }
+                    }
+                }
+            }
+            // NOTE: An empty profile (`work:` without any options) is null.
+            (_, Value::Null) => {}
+            (value, overrides) => *value = overrides,
+        }
     }
 
     /// The result of trying to determine a default path.
@@ -115,6 +257,13 @@ mod config {
             /// An error parsing the configuration file as YAML.
             error: YamlParseError,
         },
+        /// An error when the selected profile is not in the configuration file.
+        UnknownProfile {
+            /// The path of the configuration file.
+            path: PathBuf,
+            /// The name of the profile.
+            profile: String,
+        },
     }
 
     impl Display for ConfigLoadError {
@@ -148,14 +297,84 @@ mod config {
                         error
                     )
                 }
+                Self::UnknownProfile { path, profile } => {
+                    write!(
+                        f,
+                        "The profile \"{}\" is not in the configuration file \"{}\".",
+                        profile,
+                        path.display()
+                    )
+                }
             }
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        use std::path::Path;
+
+        use test_case::test_case;
+
+        const CONFIG: &str = "
+general:
+  bell: false
+  tab_width: 2
+profiles:
+  work:
+    general:
+      tab_width: 8
+      dir: /work
+  empty:
+";
+
+        #[test_case(None, false, 2, None; "no profile")]
+        #[test_case(Some("work"), false, 8, Some("/work"); "profile overrides options")]
+        #[test_case(Some("empty"), false, 2, None; "empty profile")]
+        fn test_apply_profile(
+            profile: Option<&str>,
+            bell: bool,
+            tab_width: usize,
+            dir: Option<&str>,
+        ) {
+            let mut value: Value = serde_yaml::from_str(CONFIG).unwrap();
+
+            assert!(apply_profile(&mut value, profile));
+
+            let config: Config = serde_yaml::from_value(value).unwrap();
+            assert_eq!(config.general().bell(), bell);
+            assert_eq!(config.general().tab_width_for(Path::new("foo")), tab_width);
+            assert_eq!(config.general().dir(), dir.map(PathBuf::from));
+        }
+
+        #[test]
+        fn test_apply_unknown_profile() {
+            let mut value: Value = serde_yaml::from_str(CONFIG).unwrap();
+
+            assert!(!apply_profile(&mut value, Some("ops")));
+        }
+
+        #[test]
+        fn test_keys_defaults() {
+            let config: Config = serde_yaml::from_str("keys:\n  chords:\n    x: find\n").unwrap();
+
+            assert_eq!(config.keys().leader(), '\\');
+            assert_eq!(config.keys().chords().len(), 1);
+        }
+    }
 }
 pub use config::Config;
 
 /// Contains general configuration.
 mod general {
+    use crate::expand::expand_path;
+
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
+
+    use common::paths::INSHD_SOCKET;
+
     use serde::Deserialize;
 
     /// General configuration options.
@@ -165,34 +384,343 @@ mod general {
         #[serde(default)]
         tab_width: usize,
 
+        /// The widths of tab characters of files with a name or an extension (overriding the
+        /// width of tab characters).
+        #[serde(default)]
+        tab_widths: HashMap<String, usize>,
+
+        /// Whether spaces and tabs are shown as `·` and `→` in the pager and the searcher.
+        #[serde(default)]
+        show_whitespace: bool,
+
+        /// Whether long lines are wrapped (instead of cut off) in the pager and the searcher at
+        /// the start.
+        #[serde(default)]
+        wrap: bool,
+
         /// Whether the bell sound should be made or not.
         #[serde(default)]
         bell: bool,
+
+        /// Whether finding and searching should stay on the file system of the directory they
+        /// start in.
+        #[serde(default)]
+        same_file_system: bool,
+
+        /// Whether finding and searching case fold and normalize the text that they match (so
+        /// that for example `CAFÉ` matches `café` however the accent is encoded).
+        #[serde(default)]
+        unicode_matching: bool,
+
+        /// The number of rows of context to keep above and below the selection in lists.
+        #[serde(default)]
+        scrolloff: usize,
+
+        /// Whether changing files is disabled.
+        #[serde(default)]
+        pub(super) read_only: bool,
+
+        /// The shell command for retrying operations that failed because permission was denied
+        /// (the command of the operation is passed as the arguments).
+        #[serde(default)]
+        escalation_command: String,
+
+        /// The directory to start in (if one isn't passed as an argument). A leading `~` and
+        /// environment variables are expanded.
+        #[serde(default)]
+        dir: Option<String>,
+
+        /// The path of the socket that inshd is connected to. A leading `~` and environment
+        /// variables are expanded.
+        #[serde(default)]
+        inshd_socket: Option<String>,
+
+        /// Whether a desktop notification is shown when a long running operation (like finding
+        /// files or copying) finishes while the terminal is unfocused.
+        #[serde(default)]
+        notifications: bool,
+
+        /// Whether notifications are shown by the terminal (with OSC 9) instead of as desktop
+        /// notifications.
+        #[serde(default)]
+        terminal_notifications: bool,
+
+        /// Whether the directory of the browser is reported to the terminal (with OSC 7) when it
+        /// changes.
+        #[serde(default)]
+        report_dir: bool,
     }
 
     impl Default for GeneralConfig {
         fn default() -> Self {
             Self {
                 tab_width: 4,
+                tab_widths: HashMap::new(),
+                show_whitespace: false,
+                wrap: false,
                 bell: true,
+                same_file_system: false,
+                unicode_matching: false,
+                scrolloff: 0,
+                read_only: false,
+                escalation_command: String::from("sudo"),
+                dir: None,
+                inshd_socket: None,
+                notifications: false,
+                terminal_notifications: false,
+                report_dir: false,
             }
         }
     }
 
     impl GeneralConfig {
-        /// Return the width of tab characters.
-        pub fn tab_width(&self) -> usize {
-            self.tab_width
+        /// Return the width of tab characters of the file at the path (by its name, then by its
+        /// extension, then the width of tab characters).
+        pub fn tab_width_for(&self, path: &Path) -> usize {
+            let name = path.file_name().and_then(|name| name.to_str());
+            let extension = path.extension().and_then(|extension| extension.to_str());
+            [name, extension]
+                .iter()
+                .flatten()
+                .find_map(|key| self.tab_widths.get(*key))
+                .copied()
+                .unwrap_or(self.tab_width)
+        }
+
+        /// Return whether spaces and tabs are shown as `·` and `→` in the pager and the searcher.
+        pub fn show_whitespace(&self) -> bool {
+            self.show_whitespace
+        }
+
+        /// Return whether long lines are wrapped in the pager and the searcher at the start.
+        pub fn wrap(&self) -> bool {
+            self.wrap
         }
 
         /// Return whether the bell sound should be made or not.
         pub fn bell(&self) -> bool {
             self.bell
         }
+
+        /// Return whether finding and searching should stay on the file system of the directory
+        /// they start in.
+        pub fn same_file_system(&self) -> bool {
+            self.same_file_system
+        }
+
+        /// Return whether finding and searching case fold and normalize the text that they match.
+        pub fn unicode_matching(&self) -> bool {
+            self.unicode_matching
+        }
+
+        /// Return the number of rows of context to keep above and below the selection in lists.
+        pub fn scrolloff(&self) -> usize {
+            self.scrolloff
+        }
+
+        /// Return whether changing files is disabled.
+        pub fn read_only(&self) -> bool {
+            self.read_only
+        }
+
+        /// Return the shell command for retrying operations that failed because permission was
+        /// denied.
+        pub fn escalation_command(&self) -> &str {
+            &self.escalation_command
+        }
+
+        /// Return the directory to start in (with a leading `~` and environment variables
+        /// expanded) if there is one.
+        pub fn dir(&self) -> Option<PathBuf> {
+            self.dir.as_deref().map(expand)
+        }
+
+        /// Return the path of the socket that inshd is connected to (with a leading `~` and
+        /// environment variables expanded).
+        pub fn inshd_socket(&self) -> PathBuf {
+            match &self.inshd_socket {
+                Some(inshd_socket) => expand(inshd_socket),
+                None => INSHD_SOCKET.clone(),
+            }
+        }
+
+        /// Return whether a desktop notification is shown when a long running operation (like
+        /// finding files or copying) finishes while the terminal is unfocused.
+        pub fn notifications(&self) -> bool {
+            self.notifications
+        }
+
+        /// Return whether notifications are shown by the terminal instead of as desktop
+        /// notifications.
+        pub fn terminal_notifications(&self) -> bool {
+            self.terminal_notifications
+        }
+
+        /// Return whether the directory of the browser is reported to the terminal when it
+        /// changes.
+        pub fn report_dir(&self) -> bool {
+            self.report_dir
+        }
+    }
+
+    /// Return the path expanded (or as is if it can't be expanded).
+    fn expand(path: &str) -> PathBuf {
+        match expand_path(path) {
+            Ok(path) => path,
+            Err(_) => PathBuf::from(path),
+        }
     }
 }
 pub use general::GeneralConfig;
 
+/// Contains browser configuration.
+mod browser {
+    use crate::expand::expand_path;
+
+    use std::path::PathBuf;
+
+    use serde::Deserialize;
+
+    /// Configuration for the Browser.
+    #[derive(Deserialize, Debug, Clone, Eq, PartialEq)]
+    pub struct BrowserConfig {
+        /// Whether the detailed view shows the number of times each file was opened.
+        #[serde(default)]
+        open_counts: bool,
+
+        /// Whether the sidebar is shown at the start.
+        #[serde(default)]
+        sidebar: bool,
+
+        /// The width of the sidebar.
+        #[serde(default)]
+        sidebar_width: usize,
+
+        /// The directories listed in the sidebar. A leading `~` and environment variables are
+        /// expanded.
+        #[serde(default)]
+        bookmarks: Vec<String>,
+
+        /// The number of recently browsed directories that are listed in the sidebar.
+        #[serde(default)]
+        recent_dirs: usize,
+
+        /// The Rhai script defining custom actions which are bound to keys. A leading `~` and
+        /// environment variables are expanded.
+        #[serde(default)]
+        script: Option<String>,
+
+        /// The number of levels of subdirectories that are expanded when showing the tree view.
+        #[serde(default)]
+        tree_depth: usize,
+
+        /// The columns of the detailed view (in order).
+        #[serde(default)]
+        detail_columns: DetailColumns,
+    }
+
+    /// A column of the detailed view of the browser.
+    #[derive(Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+    #[serde(rename_all = "snake_case")]
+    pub enum DetailColumn {
+        /// The encoding of text files.
+        Encoding,
+        /// The permission bits (like `rwxr-xr-x`).
+        Permissions,
+        /// The size of regular files (like `1.5K`).
+        Size,
+        /// When the files were last modified (like `5 minutes ago`).
+        Modified,
+    }
+
+    /// The columns of the detailed view of the browser (in order).
+    #[derive(Deserialize, Debug, Clone, Eq, PartialEq)]
+    #[serde(transparent)]
+    pub struct DetailColumns(Vec<DetailColumn>);
+
+    impl Default for DetailColumns {
+        fn default() -> Self {
+            Self(vec![
+                DetailColumn::Encoding,
+                DetailColumn::Size,
+                DetailColumn::Modified,
+            ])
+        }
+    }
+
+    impl Default for BrowserConfig {
+        fn default() -> Self {
+            Self {
+                open_counts: true,
+                sidebar: false,
+                sidebar_width: 24,
+                bookmarks: Vec::new(),
+                recent_dirs: 10,
+                script: None,
+                tree_depth: 1,
+                detail_columns: DetailColumns::default(),
+            }
+        }
+    }
+
+    impl BrowserConfig {
+        /// Return whether the detailed view shows the number of times each file was opened.
+        pub fn open_counts(&self) -> bool {
+            self.open_counts
+        }
+
+        /// Return whether the sidebar is shown at the start.
+        pub fn sidebar(&self) -> bool {
+            self.sidebar
+        }
+
+        /// Return the width of the sidebar.
+        pub fn sidebar_width(&self) -> usize {
+            self.sidebar_width
+        }
+
+        /// Return the directories listed in the sidebar (with a leading `~` and environment
+        /// variables expanded).
+        pub fn bookmarks(&self) -> Vec<PathBuf> {
+            self.bookmarks
+                .iter()
+                .map(|bookmark| match expand_path(bookmark) {
+                    Ok(bookmark) => bookmark,
+                    Err(_) => PathBuf::from(bookmark),
+                })
+                .collect()
+        }
+
+        /// Return the number of recently browsed directories that are listed in the sidebar.
+        pub fn recent_dirs(&self) -> usize {
+            self.recent_dirs
+        }
+
+        /// Return the number of levels of subdirectories that are expanded when showing the tree
+        /// view.
+        pub fn tree_depth(&self) -> usize {
+            self.tree_depth
+        }
+
+        /// Return the columns of the detailed view (in order).
+        pub fn detail_columns(&self) -> &[DetailColumn] {
+            &self.detail_columns.0
+        }
+
+        /// Return the path of the script defining custom actions (with a leading `~` and
+        /// environment variables expanded).
+        pub fn script(&self) -> Option<PathBuf> {
+            self.script
+                .as_deref()
+                .map(|script| match expand_path(script) {
+                    Ok(script) => script,
+                    Err(_) => PathBuf::from(script),
+                })
+        }
+    }
+}
+pub use browser::{BrowserConfig, DetailColumn};
+
 /// Contains search configuration.
 mod search {
     use serde::Deserialize;
@@ -234,3 +762,319 @@ mod search {
     }
 }
 pub use search::SearcherConfig;
+
+/// Contains command palette configuration.
+mod palette {
+    use serde::Deserialize;
+
+    /// Configuration for the command palette.
+    #[derive(Deserialize, Debug, Default, Clone, Eq, PartialEq)]
+    pub struct PaletteConfig {
+        /// Configuration for the command palette history.
+        #[serde(default)]
+        history: PaletteHistoryConfig,
+    }
+
+    impl PaletteConfig {
+        /// Return the command palette history configuration.
+        pub fn history(&self) -> &PaletteHistoryConfig {
+            &self.history
+        }
+    }
+
+    /// Configuration for the command palette history.
+    #[derive(Deserialize, Debug, Clone, Eq, PartialEq)]
+    pub struct PaletteHistoryConfig {
+        /// The maximum length of the command palette history.
+        #[serde(default)]
+        length: usize,
+    }
+
+    impl Default for PaletteHistoryConfig {
+        fn default() -> Self {
+            Self { length: 1000 }
+        }
+    }
+
+    impl PaletteHistoryConfig {
+        /// Return the maximum length of the command palette history.
+        pub fn length(&self) -> usize {
+            self.length
+        }
+    }
+}
+pub use palette::PaletteConfig;
+
+/// Contains editor configuration.
+mod editor {
+    use serde::Deserialize;
+
+    /// Configuration for opening files.
+    #[derive(Deserialize, Debug, Clone, Eq, PartialEq)]
+    pub struct EditorConfig {
+        /// The size (in bytes) above which files are opened using the viewer instead of the editor.
+        #[serde(default)]
+        max_file_size: u64,
+
+        /// Whether binary files are opened using the viewer instead of the editor.
+        #[serde(default)]
+        view_binary_files: bool,
+
+        /// The shell command for viewing files (the path of the file is passed as `$1`).
+        #[serde(default)]
+        viewer: String,
+    }
+
+    impl Default for EditorConfig {
+        fn default() -> Self {
+            Self {
+                max_file_size: 50 * 1024 * 1024,
+                view_binary_files: true,
+                viewer: String::from("xxd \"$1\" | less"),
+            }
+        }
+    }
+
+    impl EditorConfig {
+        /// Return the size (in bytes) above which files are opened using the viewer instead of the
+        /// editor.
+        pub fn max_file_size(&self) -> u64 {
+            self.max_file_size
+        }
+
+        /// Return whether binary files are opened using the viewer instead of the editor.
+        pub fn view_binary_files(&self) -> bool {
+            self.view_binary_files
+        }
+
+        /// Return the shell command for viewing files.
+        pub fn viewer(&self) -> &str {
+            &self.viewer
+        }
+    }
+}
+pub use editor::EditorConfig;
+
+/// Contains sorting configuration.
+mod sort {
+    use crate::collation::Collation;
+    use crate::sort_mode::SortMode;
+
+    use serde::Deserialize;
+
+    /// Configuration for sorting files.
+    #[derive(Deserialize, Debug, Default, Clone, Eq, PartialEq)]
+    pub struct SortConfig {
+        /// How the names of files are ordered.
+        #[serde(default)]
+        collation: Collation,
+        /// What the files are ordered by (before their names) when the browser starts.
+        #[serde(default)]
+        mode: SortMode,
+    }
+
+    impl SortConfig {
+        /// Return how the names of files are ordered.
+        pub fn collation(&self) -> Collation {
+            self.collation
+        }
+
+        /// Return what the files are ordered by (before their names) when the browser starts.
+        pub fn mode(&self) -> SortMode {
+            self.mode
+        }
+    }
+}
+pub use sort::SortConfig;
+
+/// Contains the configuration of the leader key and the chords.
+mod keys {
+    use std::collections::BTreeMap;
+    use std::time::Duration;
+
+    use serde::Deserialize;
+
+    /// Configuration for the leader key and the chords (sequences of keys after the leader which
+    /// run commands of the browser).
+    ///
+    /// NOTE: The missing options are taken from the default configuration (so that for example
+    /// setting only the chords keeps the leader).
+    #[derive(Deserialize, Debug, Clone, Eq, PartialEq)]
+    #[serde(default)]
+    pub struct KeysConfig {
+        /// The key which starts a chord.
+        leader: char,
+
+        /// How long (in milliseconds) to wait for the next key of a chord.
+        chord_timeout: u64,
+
+        /// The commands of the browser run by the chords (keyed by the keys after the leader).
+        chords: BTreeMap<String, String>,
+    }
+
+    impl Default for KeysConfig {
+        fn default() -> Self {
+            let chords: BTreeMap<String, String> = [
+                ("ff", "find"),
+                ("fl", "flat_list"),
+                ("fs", "search"),
+                ("vt", "tree"),
+                ("vd", "details"),
+                ("op", "processes"),
+                ("oo", "open_files"),
+                ("om", "mounts"),
+                ("gh", "home"),
+                ("gr", "root"),
+                ("gb", "back"),
+            ]
+            .into_iter()
+            .map(|(keys, command)| (keys.to_string(), command.to_string()))
+            .collect();
+            Self {
+                leader: '\\',
+                chord_timeout: 1000,
+                chords,
+            }
+        }
+    }
+
+    impl KeysConfig {
+        /// Return the key which starts a chord.
+        pub fn leader(&self) -> char {
+            self.leader
+        }
+
+        /// Return how long to wait for the next key of a chord.
+        pub fn chord_timeout(&self) -> Duration {
+            Duration::from_millis(self.chord_timeout)
+        }
+
+        /// Return the commands of the browser run by the chords (keyed by the keys after the
+        /// leader).
+        pub fn chords(&self) -> &BTreeMap<String, String> {
+            &self.chords
+        }
+    }
+}
+pub use keys::KeysConfig;
+
+/// Contains the configuration of network locations.
+mod mount {
+    use crate::expand::expand_path;
+
+    use std::path::PathBuf;
+
+    use serde::Deserialize;
+
+    /// How a network location is mounted.
+    #[derive(Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    pub enum MountKind {
+        /// A directory on a host that is reachable with ssh (mounted with `sshfs`).
+        Sshfs,
+        /// An SMB (or CIFS) share (mounted with `mount -t cifs`).
+        Smb,
+    }
+
+    /// A network location that can be mounted.
+    #[derive(Deserialize, Debug, Clone, Eq, PartialEq)]
+    pub struct MountConfig {
+        /// The name that the location is listed as.
+        name: String,
+
+        /// How the location is mounted.
+        kind: MountKind,
+
+        /// The location (such as `user@host:/home/user` or `//host/share`).
+        source: String,
+
+        /// The directory that the location is mounted on (created if it is missing). A leading `~`
+        /// and environment variables are expanded.
+        mount_point: String,
+
+        /// Extra options for the mount command (such as `-o reconnect`).
+        #[serde(default)]
+        options: String,
+    }
+
+    impl MountConfig {
+        /// Return the name that the location is listed as.
+        pub fn name(&self) -> &str {
+            &self.name
+        }
+
+        /// Return how the location is mounted.
+        pub fn kind(&self) -> MountKind {
+            self.kind
+        }
+
+        /// Return the location.
+        pub fn source(&self) -> &str {
+            &self.source
+        }
+
+        /// Return the directory that the location is mounted on (with a leading `~` and
+        /// environment variables expanded).
+        pub fn mount_point(&self) -> PathBuf {
+            match expand_path(&self.mount_point) {
+                Ok(mount_point) => mount_point,
+                Err(_) => PathBuf::from(&self.mount_point),
+            }
+        }
+
+        /// Return the extra options for the mount command.
+        pub fn options(&self) -> &str {
+            &self.options
+        }
+    }
+}
+pub use mount::{MountConfig, MountKind};
+
+/// Contains the configuration of hooks.
+mod hooks {
+    use serde::Deserialize;
+
+    /// The shell commands that are run when events happen (with environment variables describing
+    /// the event).
+    #[derive(Deserialize, Debug, Default, Clone, Eq, PartialEq)]
+    pub struct HooksConfig {
+        /// The command run when a directory is entered in the browser.
+        #[serde(default)]
+        dir_entered: Option<String>,
+
+        /// The command run when a file (or a directory) is created.
+        #[serde(default)]
+        file_created: Option<String>,
+
+        /// The command run when a file is opened.
+        #[serde(default)]
+        file_opened: Option<String>,
+
+        /// The command run when a search finishes.
+        #[serde(default)]
+        search_finished: Option<String>,
+    }
+
+    impl HooksConfig {
+        /// Return the command run when a directory is entered in the browser.
+        pub fn dir_entered(&self) -> Option<&str> {
+            self.dir_entered.as_deref()
+        }
+
+        /// Return the command run when a file (or a directory) is created.
+        pub fn file_created(&self) -> Option<&str> {
+            self.file_created.as_deref()
+        }
+
+        /// Return the command run when a file is opened.
+        pub fn file_opened(&self) -> Option<&str> {
+            self.file_opened.as_deref()
+        }
+
+        /// Return the command run when a search finishes.
+        pub fn search_finished(&self) -> Option<&str> {
+            self.search_finished.as_deref()
+        }
+    }
+}
+pub use hooks::HooksConfig;
@@ -0,0 +1,86 @@
+/*!
+This module contains the struct [`Count`] which accumulates a vim-style count prefix (such as the
+`5` in `5j`) for the motion that follows it.
+*/
+use term::{Key, KeyEvent, KeyMods};
+
+/// The largest count (larger counts are clamped to it).
+const MAX_COUNT: usize = 9999;
+
+/// A pending count prefix.
+#[derive(Debug, Default)]
+pub struct Count {
+    pending: Option<usize>,
+}
+
+impl Count {
+    /// Add the key to the pending count and return if the key was consumed by the count.
+    ///
+    /// A count can not start with a zero and escape cancels a pending count.
+    pub fn push(&mut self, key_event: &KeyEvent) -> bool {
+        match (key_event, self.pending) {
+            (
+                KeyEvent {
+                    key: Key::Char(digit @ '0'..='9'),
+                    mods: KeyMods::NONE,
+                },
+                pending,
+            ) if *digit != '0' || pending.is_some() => {
+                let digit: usize = digit.to_digit(10).unwrap() as usize;
+                let count: usize = pending.unwrap_or(0).saturating_mul(10) + digit;
+                self.pending = Some(count.min(MAX_COUNT));
+                true
+            }
+            (
+                KeyEvent {
+                    key: Key::Escape, ..
+                },
+                Some(_),
+            ) => {
+                self.pending = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Return the pending count (or one if there is no count) and reset it.
+    pub fn take(&mut self) -> usize {
+        self.pending.take().unwrap_or(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    /// Return the key event for pressing `character` without modifiers.
+    fn char(character: char) -> KeyEvent {
+        KeyEvent {
+            key: Key::Char(character),
+            mods: KeyMods::NONE,
+        }
+    }
+
+    #[test_case(vec![], vec![], 1; "no count")]
+    #[test_case(vec![char('5')], vec![true], 5; "one digit")]
+    #[test_case(vec![char('1'), char('0')], vec![true, true], 10; "zero after a digit")]
+    #[test_case(vec![char('0')], vec![false], 1; "leading zero")]
+    #[test_case(vec![char('9'); 6], vec![true; 6], MAX_COUNT; "clamped")]
+    #[test_case(vec![char('3'), KeyEvent { key: Key::Escape, mods: KeyMods::NONE }], vec![true, true], 1; "cancelled")]
+    #[test_case(vec![KeyEvent { key: Key::Escape, mods: KeyMods::NONE }], vec![false], 1; "escape without a count")]
+    #[test_case(vec![KeyEvent { key: Key::Char('5'), mods: KeyMods::CONTROL }], vec![false], 1; "modifier")]
+    fn test_push(key_events: Vec<KeyEvent>, expected_consumed: Vec<bool>, expected_count: usize) {
+        let mut count = Count::default();
+
+        let consumed: Vec<bool> = key_events
+            .iter()
+            .map(|key_event| count.push(key_event))
+            .collect();
+
+        assert_eq!(consumed, expected_consumed);
+        assert_eq!(count.take(), expected_count);
+        assert_eq!(count.take(), 1);
+    }
+}
@@ -2,11 +2,11 @@
 This module contains the [`Data`] struct which is used to access persistent data stored in the file
 system.
 */
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::fs::{File, OpenOptions};
 use std::io::ErrorKind as IOErrorKind;
 use std::os::unix::fs::OpenOptionsExt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use common::paths::{ensure_insh_dir_exists, INSH_DIR, INSH_FILES_PERMS};
 
@@ -40,6 +40,18 @@ pub struct Data {
 
     /// Data related to searching for text in files.
     pub searcher: SearcherData,
+
+    /// Data related to files.
+    #[serde(default)]
+    pub files: FilesData,
+
+    /// Data related to directories.
+    #[serde(default)]
+    pub dirs: DirsData,
+
+    /// Data related to the command palette.
+    #[serde(default)]
+    pub palette: PaletteData,
 }
 
 impl Default for Data {
@@ -47,6 +59,9 @@ impl Default for Data {
         Self {
             lock: get_lock_file(),
             searcher: SearcherData::default(),
+            files: FilesData::default(),
+            dirs: DirsData::default(),
+            palette: PaletteData::default(),
         }
     }
 }
@@ -125,6 +140,7 @@ impl Data {
         let file: File = OpenOptions::new()
             .write(true)
             .create(true)
+            .truncate(true)
             .mode(INSH_FILES_PERMS)
             .open(&*DATA_FILE_PATH)
             .expect("Cannot write persistent data because the data file could not be opened or created.");
@@ -149,3 +165,88 @@ impl SearcherData {
         }
     }
 }
+
+/// Data about the command palette.
+#[derive(Serialize, Deserialize, Default)]
+pub struct PaletteData {
+    /// The history of the names of the commands that were run from oldest to newest.
+    pub history: VecDeque<String>,
+}
+
+impl PaletteData {
+    /// Add an entry to the history.
+    pub fn add_to_history(&mut self, name: &str, max_length: usize) {
+        self.history.push_back(name.to_string());
+        if self.history.len() > max_length {
+            self.history.pop_front();
+        }
+    }
+}
+
+/// Data about files.
+#[derive(Serialize, Deserialize, Default)]
+pub struct FilesData {
+    /// The number of times that each file has been opened from insh.
+    pub open_counts: HashMap<PathBuf, usize>,
+}
+
+impl FilesData {
+    /// Record that a file was opened, forgetting the least opened other files while more than
+    /// `max_length` files are counted.
+    pub fn record_open(&mut self, path: &Path, max_length: usize) {
+        *self.open_counts.entry(path.to_path_buf()).or_default() += 1;
+        while self.open_counts.len() > max_length {
+            let least_opened: Option<PathBuf> = self
+                .open_counts
+                .iter()
+                .filter(|(counted, _)| counted.as_path() != path)
+                .min_by_key(|(_, count)| **count)
+                .map(|(counted, _)| counted.clone());
+            match least_opened {
+                Some(least_opened) => self.open_counts.remove(&least_opened),
+                None => break,
+            };
+        }
+    }
+}
+
+/// Data about directories.
+#[derive(Serialize, Deserialize, Default)]
+pub struct DirsData {
+    /// The directories that were browsed most recently from oldest to newest.
+    pub recent: VecDeque<PathBuf>,
+}
+
+impl DirsData {
+    /// Record that a directory was browsed (moving it to the end if it was browsed before).
+    pub fn record_visit(&mut self, dir: &Path, max_length: usize) {
+        self.recent.retain(|recent| recent != dir);
+        self.recent.push_back(dir.to_path_buf());
+        while self.recent.len() > max_length {
+            self.recent.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_open_forgets_least_opened() {
+        let mut files = FilesData::default();
+        files.record_open(Path::new("/a"), 2);
+        files.record_open(Path::new("/a"), 2);
+        files.record_open(Path::new("/b"), 2);
+
+        files.record_open(Path::new("/c"), 2);
+
+        let mut counted: Vec<(&Path, usize)> = files
+            .open_counts
+            .iter()
+            .map(|(path, count)| (path.as_path(), *count))
+            .collect();
+        counted.sort();
+        assert_eq!(counted, [(Path::new("/a"), 2), (Path::new("/c"), 1)]);
+    }
+}
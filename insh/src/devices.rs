@@ -0,0 +1,228 @@
+/*!
+This module contains [`mounted_devices`] for listing the devices that are mounted (such as disks and
+network shares) so that they can be browsed and removable media can be ejected.
+*/
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The types of network file systems (which are listed along with the devices).
+#[cfg(any(target_os = "linux", test))]
+const NETWORK_FILE_SYSTEM_TYPES: [&str; 5] = ["cifs", "smb3", "nfs", "nfs4", "fuse.sshfs"];
+
+/// The directories that udisks mounts removable media in.
+#[cfg(any(target_os = "linux", test))]
+const MEDIA_DIRS: [&str; 2] = ["/media", "/run/media"];
+
+/// A mounted device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Device {
+    /// The device (or the network location) that is mounted.
+    source: String,
+    /// The directory that the device is mounted on.
+    mount_point: PathBuf,
+    /// Whether the device is removable media (such as a USB drive) which can be ejected.
+    removable: bool,
+}
+
+impl Device {
+    /// Return a new device.
+    pub fn new(source: String, mount_point: PathBuf, removable: bool) -> Self {
+        Self {
+            source,
+            mount_point,
+            removable,
+        }
+    }
+
+    /// Return the device (or the network location) that is mounted.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Return the directory that the device is mounted on.
+    pub fn mount_point(&self) -> &Path {
+        &self.mount_point
+    }
+
+    /// Return whether the device is removable media which can be ejected.
+    pub fn removable(&self) -> bool {
+        self.removable
+    }
+}
+
+/// Return the mounted devices and network shares (but not the root file system, the boot
+/// partitions, or virtual file systems).
+#[cfg(target_os = "linux")]
+pub fn mounted_devices() -> Vec<Device> {
+    match fs::read_to_string("/proc/self/mounts") {
+        Ok(mounts) => parse_mounts(&mounts, removable_block_device),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Return whether the block device (such as `/dev/sdb1`) is removable or is connected by USB
+/// (according to sysfs).
+#[cfg(target_os = "linux")]
+fn removable_block_device(source: &str) -> bool {
+    let source: PathBuf = fs::canonicalize(source).unwrap_or_else(|_| PathBuf::from(source));
+    let name = match source.file_name() {
+        Some(name) => name,
+        None => return false,
+    };
+    let mut block: PathBuf = match fs::canonicalize(Path::new("/sys/class/block").join(name)) {
+        Ok(block) => block,
+        Err(_) => return false,
+    };
+    // NOTE: Only the disk of a partition says whether it is removable.
+    if block.join("partition").exists() {
+        block.pop();
+    }
+    let removable: bool = fs::read_to_string(block.join("removable"))
+        .map(|removable| removable.trim() == "1")
+        .unwrap_or(false);
+    removable || block.to_string_lossy().contains("/usb")
+}
+
+/// Return the mounted volumes (other than the volume of the root file system).
+#[cfg(target_os = "macos")]
+pub fn mounted_devices() -> Vec<Device> {
+    let entries = match fs::read_dir("/Volumes") {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let mut devices: Vec<Device> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        // NOTE: The volume of the root file system is a symbolic link to `/`.
+        .filter(|path| !path.is_symlink())
+        .map(|mount_point| {
+            let source: String = mount_point.to_string_lossy().to_string();
+            Device::new(source, mount_point, true)
+        })
+        .collect();
+    devices.sort_by(|a, b| a.mount_point.cmp(&b.mount_point));
+    devices
+}
+
+/// Return no devices (because listing them is not supported on this platform).
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn mounted_devices() -> Vec<Device> {
+    Vec::new()
+}
+
+/// Return the devices that are listed from the contents of a mounts file (like `/proc/mounts`),
+/// where `removable_block_device` returns whether a block device is removable.
+#[cfg(any(target_os = "linux", test))]
+fn parse_mounts<F>(mounts: &str, removable_block_device: F) -> Vec<Device>
+where
+    F: Fn(&str) -> bool,
+{
+    let mut devices: Vec<Device> = Vec::new();
+    for line in mounts.lines() {
+        let mut fields = line.split(' ');
+        let (source, mount_point, file_system_type) =
+            match (fields.next(), fields.next(), fields.next()) {
+                (Some(source), Some(mount_point), Some(file_system_type)) => {
+                    (source, mount_point, file_system_type)
+                }
+                _ => continue,
+            };
+
+        let mount_point = PathBuf::from(unescape(mount_point));
+        let network: bool = NETWORK_FILE_SYSTEM_TYPES.contains(&file_system_type);
+        let disk: bool = source.starts_with("/dev/")
+            && !source.starts_with("/dev/loop")
+            && mount_point != Path::new("/")
+            && !mount_point.starts_with("/boot");
+        if !(network || disk) || devices.iter().any(|d| d.mount_point == mount_point) {
+            continue;
+        }
+
+        let source: String = unescape(source);
+        let removable: bool = disk
+            && (MEDIA_DIRS.iter().any(|dir| mount_point.starts_with(dir))
+                || removable_block_device(&source));
+        devices.push(Device::new(source, mount_point, removable));
+    }
+    devices
+}
+
+/// Return the field of a mounts file with the octal escapes (such as `\040` for a space) replaced.
+#[cfg(any(target_os = "linux", test))]
+fn unescape(field: &str) -> String {
+    let mut unescaped = String::with_capacity(field.len());
+    let mut rest: &str = field;
+    while let Some(index) = rest.find('\\') {
+        unescaped.push_str(&rest[..index]);
+        let code: Option<u8> = rest
+            .get(index + 1..index + 4)
+            .and_then(|digits| u8::from_str_radix(digits, 8).ok());
+        match code {
+            Some(code) => {
+                unescaped.push(code as char);
+                rest = &rest[index + 4..];
+            }
+            None => {
+                unescaped.push('\\');
+                rest = &rest[index + 1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    #[test_case("", vec![]; "empty")]
+    #[test_case("/dev/sda1 / ext4 rw 0 0", vec![]; "root file system")]
+    #[test_case("/dev/sda2 /boot/efi vfat rw 0 0", vec![]; "boot partition")]
+    #[test_case("proc /proc proc rw 0 0", vec![]; "virtual file system")]
+    #[test_case("/dev/loop3 /snap/core/1 squashfs ro 0 0", vec![]; "loop device")]
+    #[test_case(
+        "/dev/sdb1 /media/me/My\\040Disk vfat rw 0 0",
+        vec![("/dev/sdb1", "/media/me/My Disk", true)];
+        "removable media with a space"
+    )]
+    #[test_case(
+        "/dev/sdc1 /srv/data ext4 rw 0 0",
+        vec![("/dev/sdc1", "/srv/data", true)];
+        "removable block device"
+    )]
+    #[test_case(
+        "me@host:/home/me /home/me/host fuse.sshfs rw 0 0",
+        vec![("me@host:/home/me", "/home/me/host", false)];
+        "network share"
+    )]
+    #[test_case(
+        "/dev/sdb1 /mnt ext4 rw 0 0\n/dev/sdb1 /mnt ext4 rw 0 0",
+        vec![("/dev/sdb1", "/mnt", false)];
+        "mounted over"
+    )]
+    fn test_parse_mounts(mounts: &str, expected: Vec<(&str, &str, bool)>) {
+        let expected: Vec<Device> = expected
+            .into_iter()
+            .map(|(source, mount_point, removable)| {
+                Device::new(source.to_string(), PathBuf::from(mount_point), removable)
+            })
+            .collect();
+
+        assert_eq!(
+            parse_mounts(mounts, |source| source == "/dev/sdc1"),
+            expected
+        );
+    }
+
+    #[test_case("foo", "foo"; "nothing escaped")]
+    #[test_case("a\\040b", "a b"; "space")]
+    #[test_case("a\\134b", "a\\b"; "backslash")]
+    #[test_case("a\\b", "a\\b"; "not an escape")]
+    fn test_unescape(field: &str, expected: &str) {
+        assert_eq!(unescape(field), expected);
+    }
+}
@@ -0,0 +1,128 @@
+/*!
+This module contains the struct [`Escalation`] which is an operation that failed because permission
+was denied and that can be retried as a command run with the escalation command (such as `sudo`).
+
+The daemon is never given more privileges. Instead the command is run in the terminal (so that the
+escalation command can prompt for a password).
+*/
+use file_type::FileType;
+
+use std::ffi::OsString;
+use std::path::Path;
+
+/// The shell script for listing a directory (`$1` is the path) and waiting for enter to be pressed
+/// (so that the listing can be read before returning to insh).
+const LIST_DIR_SCRIPT: &str = "ls -la -- \"$1\"; printf 'Press enter to continue.'; read -r _";
+
+/// The shell script for creating a file and its missing parent directories (`$1` is the path).
+const CREATE_FILE_SCRIPT: &str = "mkdir -p -- \"$(dirname -- \"$1\")\" && touch -- \"$1\"";
+
+/// An operation to retry with the escalation command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Escalation {
+    /// The command (and its arguments) that performs the operation.
+    args: Vec<OsString>,
+}
+
+impl Escalation {
+    /// Return the escalation for touching the file at `path`.
+    pub fn touch(path: &Path) -> Self {
+        Self {
+            args: vec!["touch".into(), "--".into(), path.into()],
+        }
+    }
+
+    /// Return the escalation for deleting the file at `path` (and everything in it if it is a
+    /// directory and the deletion is recursive).
+    pub fn delete(path: &Path, recursive: bool) -> Self {
+        let flag: &str = match recursive {
+            true => "-r",
+            false => "-d",
+        };
+        Self {
+            args: vec!["rm".into(), flag.into(), "--".into(), path.into()],
+        }
+    }
+
+    /// Return the escalation for copying the file at `from` (and everything in it if it is a
+    /// directory) to `to` (without replacing a file at `to`).
+    pub fn copy(from: &Path, to: &Path) -> Self {
+        Self {
+            args: vec![
+                "cp".into(),
+                "-R".into(),
+                "-n".into(),
+                "--".into(),
+                from.into(),
+                to.into(),
+            ],
+        }
+    }
+
+    /// Return the escalation for renaming the file at `from` to `to` (without replacing a file at
+    /// `to`).
+    pub fn rename(from: &Path, to: &Path) -> Self {
+        Self {
+            args: vec![
+                "mv".into(),
+                "-n".into(),
+                "--".into(),
+                from.into(),
+                to.into(),
+            ],
+        }
+    }
+
+    /// Return the escalation for listing the directory at `path`.
+    pub fn list(path: &Path) -> Self {
+        Self::script(LIST_DIR_SCRIPT, path)
+    }
+
+    /// Return the escalation for creating a file of the type `file_type` at `path` (and its missing
+    /// parent directories).
+    pub fn create(path: &Path, file_type: FileType) -> Self {
+        let args: Vec<OsString> = match file_type {
+            FileType::Dir => vec!["mkdir".into(), "-p".into(), "--".into(), path.into()],
+            _ => return Self::script(CREATE_FILE_SCRIPT, path),
+        };
+        Self { args }
+    }
+
+    /// Return the escalation for running the shell `script` with the argument `path`.
+    fn script(script: &str, path: &Path) -> Self {
+        Self {
+            args: vec![
+                "sh".into(),
+                "-c".into(),
+                script.into(),
+                // NOTE: This is `$0` for the script.
+                "sh".into(),
+                path.into(),
+            ],
+        }
+    }
+
+    /// Return the command (and its arguments) that performs the operation.
+    pub fn args(&self) -> &[OsString] {
+        &self.args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(Escalation::touch(Path::new("/etc/foo")), vec!["touch", "--", "/etc/foo"]; "touch")]
+    #[test_case(Escalation::create(Path::new("/etc/foo"), FileType::Dir), vec!["mkdir", "-p", "--", "/etc/foo"]; "create directory")]
+    #[test_case(Escalation::create(Path::new("/etc/foo"), FileType::File), vec!["sh", "-c", CREATE_FILE_SCRIPT, "sh", "/etc/foo"]; "create file")]
+    #[test_case(Escalation::delete(Path::new("/etc/foo"), false), vec!["rm", "-d", "--", "/etc/foo"]; "delete")]
+    #[test_case(Escalation::delete(Path::new("/etc/foo"), true), vec!["rm", "-r", "--", "/etc/foo"]; "delete recursively")]
+    #[test_case(Escalation::rename(Path::new("/etc/foo"), Path::new("/etc/bar")), vec!["mv", "-n", "--", "/etc/foo", "/etc/bar"]; "rename")]
+    #[test_case(Escalation::copy(Path::new("/etc/foo"), Path::new("/etc/bar")), vec!["cp", "-R", "-n", "--", "/etc/foo", "/etc/bar"]; "copy")]
+    #[test_case(Escalation::list(Path::new("/root")), vec!["sh", "-c", LIST_DIR_SCRIPT, "sh", "/root"]; "list")]
+    fn test_args(escalation: Escalation, expected_args: Vec<&str>) {
+        let expected_args: Vec<OsString> = expected_args.into_iter().map(OsString::from).collect();
+        assert_eq!(escalation.args(), expected_args);
+    }
+}
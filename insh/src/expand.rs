@@ -0,0 +1,149 @@
+/*!
+This module contains the function [`expand`] for expanding `~` and environment variables (such as
+`$HOME` and `${HOME}`) in paths and other inputs.
+*/
+use std::env;
+use std::error::Error;
+use std::fmt::{Display, Error as FmtError, Formatter};
+use std::path::{Path, PathBuf};
+
+/// Return the string with a leading `~` expanded to the home directory and with environment
+/// variables (of the form `$NAME` or `${NAME}`) expanded to their values.
+pub fn expand(string: &str) -> Result<String, ExpandError> {
+    expand_with(string, dirs::home_dir().as_deref(), |name| {
+        env::var(name).ok()
+    })
+}
+
+/// Return the path parsed from the string after expanding it (see [`expand`]).
+///
+/// This can be used as a clap value parser.
+pub fn expand_path(string: &str) -> Result<PathBuf, ExpandError> {
+    expand(string).map(PathBuf::from)
+}
+
+/// Return the string expanded using the given home directory and the given function for looking up
+/// the values of variables.
+fn expand_with<F>(string: &str, home: Option<&Path>, lookup: F) -> Result<String, ExpandError>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut expanded = String::with_capacity(string.len());
+
+    // Expand a leading tilde (but not a tilde followed by a user name).
+    let mut rest: &str = string;
+    if rest == "~" || rest.starts_with("~/") {
+        let home: &Path = home.ok_or(ExpandError::UnknownHomeDir)?;
+        expanded.push_str(&home.to_string_lossy());
+        rest = &rest[1..];
+    }
+
+    let mut characters = rest.char_indices().peekable();
+    while let Some((index, character)) = characters.next() {
+        if character != '$' {
+            expanded.push(character);
+            continue;
+        }
+
+        let name: &str = match characters.peek() {
+            Some((_, '{')) => {
+                let start: usize = index + 2;
+                let length: usize = rest[start..].find('}').ok_or(ExpandError::UnclosedBrace)?;
+                let name: &str = &rest[start..start + length];
+                // Skip past the closing brace (`take_while` also consumes the brace).
+                characters
+                    .by_ref()
+                    .take_while(|(index, _)| *index < start + length)
+                    .for_each(drop);
+                name
+            }
+            Some((_, next)) if is_name_character(*next) => {
+                let start: usize = index + 1;
+                let mut end: usize = start;
+                while let Some((index, character)) =
+                    characters.next_if(|(_, character)| is_name_character(*character))
+                {
+                    end = index + character.len_utf8();
+                }
+                &rest[start..end]
+            }
+            _ => {
+                // A dollar sign that is not followed by a variable name is kept as is.
+                expanded.push(character);
+                continue;
+            }
+        };
+
+        match lookup(name) {
+            Some(value) => expanded.push_str(&value),
+            None => return Err(ExpandError::UndefinedVariable(name.to_string())),
+        }
+    }
+
+    Ok(expanded)
+}
+
+/// Return if the character can be part of a variable name.
+fn is_name_character(character: char) -> bool {
+    character.is_ascii_alphanumeric() || character == '_'
+}
+
+/// An error expanding a string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpandError {
+    /// The home directory could not be determined.
+    UnknownHomeDir,
+    /// A variable is not defined.
+    UndefinedVariable(String),
+    /// A `${` is not followed by a closing brace.
+    UnclosedBrace,
+}
+
+impl Display for ExpandError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::UnknownHomeDir => write!(
+                formatter,
+                "Cannot expand \"~\" because the home directory could not be determined."
+            ),
+            Self::UndefinedVariable(name) => {
+                write!(formatter, "The variable \"{}\" is not defined.", name)
+            }
+            Self::UnclosedBrace => write!(formatter, "Missing a closing brace after \"${{\"."),
+        }
+    }
+}
+
+impl Error for ExpandError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "HOME" => Some(String::from("/home/foo")),
+            "BAR" => Some(String::from("bar")),
+            _ => None,
+        }
+    }
+
+    #[test_case("foo/bar", Ok("foo/bar".into()); "nothing to expand")]
+    #[test_case("~", Ok("/home/foo".into()); "tilde")]
+    #[test_case("~/baz", Ok("/home/foo/baz".into()); "tilde slash")]
+    #[test_case("~baz", Ok("~baz".into()); "tilde user is not expanded")]
+    #[test_case("a/~/b", Ok("a/~/b".into()); "tilde not at the start")]
+    #[test_case("$HOME/baz", Ok("/home/foo/baz".into()); "variable")]
+    #[test_case("${BAR}baz", Ok("barbaz".into()); "braced variable")]
+    #[test_case("$BAR.txt", Ok("bar.txt".into()); "variable followed by a dot")]
+    #[test_case("a$ b$", Ok("a$ b$".into()); "lone dollar signs")]
+    #[test_case("$QUX", Err(ExpandError::UndefinedVariable("QUX".into())); "undefined variable")]
+    #[test_case("${BAR", Err(ExpandError::UnclosedBrace); "unclosed brace")]
+    fn test_expand_with(string: &str, expected_result: Result<String, ExpandError>) {
+        let result: Result<String, ExpandError> =
+            expand_with(string, Some(Path::new("/home/foo")), lookup);
+
+        assert_eq!(result, expected_result);
+    }
+}
@@ -0,0 +1,32 @@
+/*!
+This module contains [`open_externally`] for opening files with the default application of the
+operating system (with `xdg-open`, or `open` on macOS).
+*/
+use std::io::Result as IOResult;
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// The command which opens a file with its default application.
+#[cfg(target_os = "macos")]
+const OPENER: &str = "open";
+#[cfg(not(target_os = "macos"))]
+const OPENER: &str = "xdg-open";
+
+/// Open the file at the path with its default application.
+///
+/// The opener is detached from the terminal (it gets no input or output and runs in its own process
+/// group) so that it can't draw over insh or be stopped by the terminal, and insh keeps running.
+pub fn open_externally(path: &Path) -> IOResult<()> {
+    let mut child = Command::new(OPENER)
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .process_group(0)
+        .spawn()?;
+    // Wait for the opener in another thread so that it doesn't become a zombie.
+    thread::spawn(move || child.wait());
+    Ok(())
+}
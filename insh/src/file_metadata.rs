@@ -0,0 +1,63 @@
+/*!
+This module contains the functions [`human_size`] and [`permissions`] which format the metadata of
+files (such as `1.5K` and `rwxr-xr-x`) for the columns of the detailed view of the browser.
+*/
+
+/// The suffixes of the units of sizes (each 1024 times the one before).
+const UNITS: [&str; 6] = ["K", "M", "G", "T", "P", "E"];
+
+/// Return the number of bytes in a human readable form (like `ls -lh`): bytes below 1024 are shown
+/// as is, and larger sizes in the largest unit with one decimal while they are below ten (for
+/// example `1.5K` or `23M`).
+pub fn human_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        return bytes.to_string();
+    }
+    let mut size: f64 = bytes as f64;
+    let mut unit: &str = UNITS[0];
+    for next_unit in UNITS {
+        size /= 1024.0;
+        unit = next_unit;
+        if size < 1024.0 {
+            break;
+        }
+    }
+    match size < 10.0 {
+        true => format!("{:.1}{}", size, unit),
+        false => format!("{:.0}{}", size, unit),
+    }
+}
+
+/// Return the permission bits of the mode of a file (like `rwxr-xr-x`).
+pub fn permissions(mode: u32) -> String {
+    let mut string = String::with_capacity(9);
+    for shift in [6, 3, 0] {
+        let bits: u32 = mode >> shift;
+        string.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+        string.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+        string.push(if bits & 0o1 != 0 { 'x' } else { '-' });
+    }
+    string
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(0, "0"; "empty")]
+    #[test_case(1023, "1023"; "bytes")]
+    #[test_case(1536, "1.5K"; "kibibytes")]
+    #[test_case(23 * 1024 * 1024, "23M"; "mebibytes")]
+    #[test_case(3 * 1024 * 1024 * 1024 * 1024, "3.0T"; "tebibytes")]
+    fn test_human_size(bytes: u64, expected: &str) {
+        assert_eq!(human_size(bytes), expected);
+    }
+
+    #[test_case(0o755, "rwxr-xr-x"; "executable")]
+    #[test_case(0o640, "rw-r-----"; "private")]
+    #[test_case(0o100644, "rw-r--r--"; "with the file type")]
+    fn test_permissions(mode: u32, expected: &str) {
+        assert_eq!(permissions(mode), expected);
+    }
+}
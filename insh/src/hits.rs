@@ -0,0 +1,254 @@
+/*!
+This module contains the struct [`Hits`] which stores the file hits of a phrase search.
+
+Only a bounded number of line hits are kept in memory. The line hits of the file hits after those
+are spilled to a temporary file and read back when they are needed (only the visible file hits are
+needed at a time), so that searching for a phrase with a huge number of hits does not run out of
+memory.
+*/
+use std::borrow::Cow;
+use std::env;
+use std::fmt::{Debug, Error as FmtError, Formatter};
+use std::fs::{self, File, OpenOptions};
+use std::io::Error as IOError;
+use std::os::unix::fs::FileExt;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use uuid::Uuid;
+
+use phrase_searcher::{FileHit, LineHit};
+
+/// The number of line hits that are kept in memory.
+pub const MAX_IN_MEMORY_LINE_HITS: usize = 100_000;
+
+/// The file hits of a phrase search.
+pub struct Hits {
+    /// The file hits (in order).
+    file_hits: Vec<StoredFileHit>,
+    /// The number of line hits that are in memory.
+    in_memory_line_hits: usize,
+    /// The number of line hits that can be kept in memory.
+    max_in_memory_line_hits: usize,
+    /// The file that line hits are spilled to (created when the first file hit is spilled).
+    spill: Option<File>,
+    /// The length of the spill file.
+    spill_len: u64,
+}
+
+/// A file hit whose line hits are either in memory or in the spill file.
+enum StoredFileHit {
+    InMemory(FileHit),
+    Spilled {
+        path: PathBuf,
+        /// The number of line hits.
+        line_count: usize,
+        /// The offset of the serialized line hits in the spill file.
+        offset: u64,
+        /// The length of the serialized line hits.
+        len: usize,
+    },
+}
+
+impl Hits {
+    /// Return no hits which keep at most `max_in_memory_line_hits` line hits in memory.
+    pub fn with_limit(max_in_memory_line_hits: usize) -> Self {
+        Self {
+            file_hits: Vec::new(),
+            in_memory_line_hits: 0,
+            max_in_memory_line_hits,
+            spill: None,
+            spill_len: 0,
+        }
+    }
+
+    /// Add a file hit (spilling its line hits if there are too many line hits in memory).
+    pub fn push(&mut self, file_hit: FileHit) {
+        let line_count: usize = file_hit.line_hits().len();
+        if self.in_memory_line_hits + line_count > self.max_in_memory_line_hits {
+            match self.spill(&file_hit) {
+                Ok(stored_file_hit) => {
+                    self.file_hits.push(stored_file_hit);
+                    return;
+                }
+                #[allow(unused_variables)]
+                Err(error) => {
+                    #[cfg(feature = "logging")]
+                    log::error!("Failed to spill the hits for a file: {}", error);
+                }
+            }
+        }
+
+        self.in_memory_line_hits += line_count;
+        self.file_hits.push(StoredFileHit::InMemory(file_hit));
+    }
+
+    /// Write the line hits of a file hit to the spill file.
+    fn spill(&mut self, file_hit: &FileHit) -> Result<StoredFileHit, IOError> {
+        let bytes: Vec<u8> = bincode::serialize(file_hit.line_hits()).map_err(IOError::other)?;
+
+        if self.spill.is_none() {
+            self.spill = Some(create_spill_file()?);
+        }
+        let spill: &File = self.spill.as_ref().unwrap();
+        spill.write_all_at(&bytes, self.spill_len)?;
+
+        let offset: u64 = self.spill_len;
+        self.spill_len += bytes.len() as u64;
+        Ok(StoredFileHit::Spilled {
+            path: file_hit.path().to_path_buf(),
+            line_count: file_hit.line_hits().len(),
+            offset,
+            len: bytes.len(),
+        })
+    }
+
+    /// Return the number of file hits.
+    pub fn len(&self) -> usize {
+        self.file_hits.len()
+    }
+
+    /// Return if there are no file hits.
+    pub fn is_empty(&self) -> bool {
+        self.file_hits.is_empty()
+    }
+
+    /// Return the number of file hits whose line hits are spilled.
+    pub fn spilled(&self) -> usize {
+        self.file_hits
+            .iter()
+            .filter(|file_hit| matches!(file_hit, StoredFileHit::Spilled { .. }))
+            .count()
+    }
+
+    /// Return the path of the file hit with the number `number`.
+    pub fn path(&self, number: usize) -> Option<&Path> {
+        match self.file_hits.get(number)? {
+            StoredFileHit::InMemory(file_hit) => Some(file_hit.path()),
+            StoredFileHit::Spilled { path, .. } => Some(path),
+        }
+    }
+
+    /// Return the number of line hits of the file hit with the number `number`.
+    pub fn line_count(&self, number: usize) -> Option<usize> {
+        match self.file_hits.get(number)? {
+            StoredFileHit::InMemory(file_hit) => Some(file_hit.line_hits().len()),
+            StoredFileHit::Spilled { line_count, .. } => Some(*line_count),
+        }
+    }
+
+    /// Return the file hit with the number `number` (reading its line hits back if they were
+    /// spilled).
+    pub fn get(&self, number: usize) -> Option<Cow<'_, FileHit>> {
+        match self.file_hits.get(number)? {
+            StoredFileHit::InMemory(file_hit) => Some(Cow::Borrowed(file_hit)),
+            StoredFileHit::Spilled {
+                path, offset, len, ..
+            } => {
+                let spill: &File = self.spill.as_ref()?;
+                let mut bytes: Vec<u8> = vec![0; *len];
+                let line_hits: Vec<LineHit> = match spill.read_exact_at(&mut bytes, *offset) {
+                    Ok(()) => bincode::deserialize(&bytes).ok()?,
+                    #[allow(unused_variables)]
+                    Err(error) => {
+                        #[cfg(feature = "logging")]
+                        log::error!("Failed to read spilled hits: {}", error);
+                        return None;
+                    }
+                };
+                Some(Cow::Owned(FileHit::new(path, line_hits)))
+            }
+        }
+    }
+}
+
+/// Create a file to spill line hits to.
+///
+/// The file is removed right away so that it is cleaned up when it is closed (even if insh
+/// crashes).
+fn create_spill_file() -> Result<File, IOError> {
+    let path: PathBuf = env::temp_dir().join(format!(
+        "insh-{}-hits-{}.spill",
+        process::id(),
+        Uuid::new_v4()
+    ));
+    let file: File = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .open(&path)?;
+    fs::remove_file(&path)?;
+    Ok(file)
+}
+
+impl Default for Hits {
+    fn default() -> Self {
+        Self::with_limit(MAX_IN_MEMORY_LINE_HITS)
+    }
+}
+
+impl FromIterator<FileHit> for Hits {
+    fn from_iter<I: IntoIterator<Item = FileHit>>(file_hits: I) -> Self {
+        let mut hits = Self::default();
+        for file_hit in file_hits {
+            hits.push(file_hit);
+        }
+        hits
+    }
+}
+
+impl From<Vec<FileHit>> for Hits {
+    fn from(file_hits: Vec<FileHit>) -> Self {
+        file_hits.into_iter().collect()
+    }
+}
+
+impl PartialEq for Hits {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && (0..self.len()).all(|number| self.get(number) == other.get(number))
+    }
+}
+
+impl Eq for Hits {}
+
+impl Debug for Hits {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        formatter
+            .debug_list()
+            .entries((0..self.len()).filter_map(|number| self.get(number)))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Return a file hit with `line_count` line hits.
+    fn file_hit(name: &str, line_count: usize) -> FileHit {
+        let line_hits: Vec<LineHit> = (1..=line_count)
+            .map(|line_number| LineHit::new(line_number, &format!("{} {}", name, line_number)))
+            .collect();
+        FileHit::new(Path::new(name), line_hits)
+    }
+
+    #[test]
+    fn test_spill() {
+        let file_hits: Vec<FileHit> = vec![file_hit("a", 2), file_hit("b", 2), file_hit("c", 1)];
+
+        let mut hits = Hits::with_limit(3);
+        for file_hit in file_hits.iter().cloned() {
+            hits.push(file_hit);
+        }
+
+        assert_eq!(hits.len(), 3);
+        assert_eq!(hits.spilled(), 1);
+        assert_eq!(hits.path(1), Some(Path::new("b")));
+        assert_eq!(hits.line_count(1), Some(2));
+        for (number, file_hit) in file_hits.iter().enumerate() {
+            assert_eq!(hits.get(number).as_deref(), Some(file_hit));
+        }
+        assert_eq!(hits.get(3), None);
+    }
+}
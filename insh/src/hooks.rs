@@ -0,0 +1,137 @@
+/*!
+This module contains [`run_hook`] for running the shell commands that are configured to run when
+events happen (such as when a directory is entered), so that other tools can be notified.
+*/
+use std::ffi::OsString;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+
+use crate::config::HooksConfig;
+
+/// An event that a hook can run on.
+pub enum HookEvent<'a> {
+    /// A directory was entered in the browser.
+    DirEntered { dir: &'a Path },
+    /// A file (or a directory) was created.
+    FileCreated { path: &'a Path },
+    /// A file was opened in the editor (or the viewer).
+    FileOpened { path: &'a Path },
+    /// A search for a phrase finished.
+    SearchFinished {
+        dir: &'a Path,
+        phrase: &'a str,
+        /// The number of files that contain the phrase.
+        files: usize,
+    },
+}
+
+impl HookEvent<'_> {
+    /// Return the name of the event (which is the name of its option in the config).
+    fn name(&self) -> &'static str {
+        match self {
+            Self::DirEntered { .. } => "dir_entered",
+            Self::FileCreated { .. } => "file_created",
+            Self::FileOpened { .. } => "file_opened",
+            Self::SearchFinished { .. } => "search_finished",
+        }
+    }
+
+    /// Return the environment variables describing the event.
+    fn env(&self) -> Vec<(&'static str, OsString)> {
+        let mut env: Vec<(&'static str, OsString)> = vec![("INSH_EVENT", self.name().into())];
+        match self {
+            Self::DirEntered { dir } => {
+                env.push(("INSH_DIR", dir.into()));
+            }
+            Self::FileCreated { path } | Self::FileOpened { path } => {
+                env.push(("INSH_PATH", path.into()));
+            }
+            Self::SearchFinished { dir, phrase, files } => {
+                env.push(("INSH_DIR", dir.into()));
+                env.push(("INSH_PHRASE", phrase.into()));
+                env.push(("INSH_FILES", files.to_string().into()));
+            }
+        }
+        env
+    }
+
+    /// Return the shell command of the hook for the event (if one is configured).
+    fn command<'a>(&self, hooks: &'a HooksConfig) -> Option<&'a str> {
+        match self {
+            Self::DirEntered { .. } => hooks.dir_entered(),
+            Self::FileCreated { .. } => hooks.file_created(),
+            Self::FileOpened { .. } => hooks.file_opened(),
+            Self::SearchFinished { .. } => hooks.search_finished(),
+        }
+    }
+}
+
+/// Run the hook for the event in the background (if one is configured).
+///
+/// The output of the hook is discarded (so that it doesn't draw over insh) and insh doesn't wait for
+/// the hook to finish.
+pub fn run_hook(hooks: &HooksConfig, event: HookEvent) {
+    let command: &str = match event.command(hooks) {
+        Some(command) => command,
+        None => return,
+    };
+
+    #[cfg(feature = "logging")]
+    log::debug!("Running the {} hook...", event.name());
+    let spawned = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .envs(event.env())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    match spawned {
+        Ok(mut child) => {
+            // Wait for the hook in another thread so that it doesn't become a zombie.
+            thread::spawn(move || child.wait());
+        }
+        #[allow(unused_variables)]
+        Err(error) => {
+            #[cfg(feature = "logging")]
+            log::error!("Failed to run the {} hook: {}", event.name(), error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    #[test_case(
+        HookEvent::DirEntered { dir: Path::new("/foo") },
+        vec![("INSH_EVENT", "dir_entered"), ("INSH_DIR", "/foo")];
+        "dir entered"
+    )]
+    #[test_case(
+        HookEvent::FileOpened { path: Path::new("/foo/bar") },
+        vec![("INSH_EVENT", "file_opened"), ("INSH_PATH", "/foo/bar")];
+        "file opened"
+    )]
+    #[test_case(
+        HookEvent::SearchFinished { dir: Path::new("/foo"), phrase: "bar", files: 2 },
+        vec![
+            ("INSH_EVENT", "search_finished"),
+            ("INSH_DIR", "/foo"),
+            ("INSH_PHRASE", "bar"),
+            ("INSH_FILES", "2"),
+        ];
+        "search finished"
+    )]
+    fn test_env(event: HookEvent, expected: Vec<(&str, &str)>) {
+        let expected: Vec<(&str, OsString)> = expected
+            .into_iter()
+            .map(|(name, value)| (name, OsString::from(value)))
+            .collect();
+
+        assert_eq!(event.env(), expected);
+    }
+}
@@ -0,0 +1,245 @@
+/*!
+This module contains the function [`preview_image`] which shows a preview of an image in the
+terminal (used by the hidden `preview-image` command which is run as a program by the browser).
+
+Images are shown using the kitty graphics protocol or the iTerm2 inline images protocol when the
+terminal supports one of them. Otherwise (or if the format of the image is not supported by the
+protocol), the metadata of the image (the format and the dimensions) is shown instead.
+*/
+use std::env;
+use std::fmt::{Display, Error as FmtError, Formatter};
+use std::fs::File;
+use std::io::{self, BufRead, Read, Write};
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+
+/// The number of bytes at the start of an image that are read to determine its metadata.
+const METADATA_LENGTH: u64 = 256 * 1024;
+
+/// The largest image (in bytes) that is sent to the terminal using the iTerm2 protocol.
+const MAX_INLINE_IMAGE_SIZE: u64 = 20 * 1024 * 1024;
+
+/// The approximate ratio of the height of a terminal cell to its width.
+const CELL_ASPECT_RATIO: usize = 2;
+
+/// A protocol for showing images in the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    /// The kitty graphics protocol.
+    Kitty,
+    /// The iTerm2 inline images protocol (also supported by WezTerm).
+    ITerm2,
+}
+
+impl GraphicsProtocol {
+    /// Return the graphics protocol supported by the terminal (if any) based on its capabilities
+    /// and the environment.
+    pub fn detect() -> Option<Self> {
+        if term::capabilities().kitty() {
+            return Some(Self::Kitty);
+        }
+        let term_program: String = env::var("TERM_PROGRAM").unwrap_or_default();
+        if term_program == "iTerm.app" || term_program == "WezTerm" {
+            return Some(Self::ITerm2);
+        }
+        None
+    }
+}
+
+/// The format of an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Gif,
+    Bmp,
+}
+
+impl Display for ImageFormat {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let name: &str = match self {
+            Self::Png => "PNG",
+            Self::Jpeg => "JPEG",
+            Self::Gif => "GIF",
+            Self::Bmp => "BMP",
+        };
+        write!(formatter, "{}", name)
+    }
+}
+
+/// The metadata of an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageInfo {
+    format: ImageFormat,
+    width: usize,
+    height: usize,
+}
+
+impl Display for ImageInfo {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        write!(
+            formatter,
+            "{} image, {} × {} pixels",
+            self.format, self.width, self.height
+        )
+    }
+}
+
+/// Show a preview of the image at `path` and wait for the enter key to be pressed.
+pub fn preview_image(path: &Path) {
+    let mut stdout = io::stdout();
+
+    let info: Option<ImageInfo> = read_image_info(path);
+    match info {
+        Some(info) => {
+            let (columns, rows) = crossterm::terminal::size().unwrap_or((80, 24));
+            // Leave room for the metadata and the prompt.
+            let size: (usize, usize) = (columns.into(), usize::from(rows).saturating_sub(3));
+            if let Some(escape_sequence) = image_escape_sequence(path, &info, size) {
+                let _ = write!(stdout, "{}", escape_sequence);
+            }
+            let _ = writeln!(stdout, "\r\n{}", info);
+        }
+        None => {
+            let _ = writeln!(stdout, "{} is not a supported image.", path.display());
+        }
+    }
+
+    let _ = write!(stdout, "Press enter to return.");
+    let _ = stdout.flush();
+    let _ = io::stdin().lock().read_line(&mut String::new());
+}
+
+/// Return the escape sequence for showing the image using the graphics protocol of the terminal
+/// (if the terminal supports one that supports the format of the image).
+///
+/// The image is scaled to fit within `size` (the number of columns and rows) while keeping its
+/// aspect ratio.
+fn image_escape_sequence(path: &Path, info: &ImageInfo, size: (usize, usize)) -> Option<String> {
+    let (columns, rows) = size;
+    if columns == 0 || rows == 0 || info.width == 0 || info.height == 0 {
+        return None;
+    }
+
+    // Determine if the image is limited by the number of rows or the number of columns.
+    let columns_for_all_rows: usize = rows * CELL_ASPECT_RATIO * info.width / info.height;
+    let (columns, rows): (Option<usize>, Option<usize>) = match columns_for_all_rows <= columns {
+        true => (None, Some(rows)),
+        false => (Some(columns), None),
+    };
+
+    match GraphicsProtocol::detect()? {
+        GraphicsProtocol::Kitty => {
+            // NOTE: The kitty graphics protocol can only read PNG images from files directly.
+            if info.format != ImageFormat::Png {
+                return None;
+            }
+            let path: String = BASE64.encode(path.as_os_str().to_string_lossy().as_bytes());
+            let size: String = match (columns, rows) {
+                (Some(columns), _) => format!("c={}", columns),
+                (_, Some(rows)) => format!("r={}", rows),
+                (None, None) => String::new(),
+            };
+            Some(format!("\x1b_Ga=T,f=100,t=f,{};{}\x1b\\", size, path))
+        }
+        GraphicsProtocol::ITerm2 => {
+            let file: File = File::open(path).ok()?;
+            let mut bytes: Vec<u8> = Vec::new();
+            file.take(MAX_INLINE_IMAGE_SIZE)
+                .read_to_end(&mut bytes)
+                .ok()?;
+            let size: String = match (columns, rows) {
+                (Some(columns), _) => format!("width={}", columns),
+                (_, Some(rows)) => format!("height={}", rows),
+                (None, None) => String::new(),
+            };
+            Some(format!(
+                "\x1b]1337;File=inline=1;size={};{};preserveAspectRatio=1:{}\x07",
+                bytes.len(),
+                size,
+                BASE64.encode(&bytes)
+            ))
+        }
+    }
+}
+
+/// Return the metadata of the image at `path` (if it is an image in a supported format).
+fn read_image_info(path: &Path) -> Option<ImageInfo> {
+    let file: File = File::open(path).ok()?;
+    let mut bytes: Vec<u8> = Vec::new();
+    file.take(METADATA_LENGTH).read_to_end(&mut bytes).ok()?;
+    image_info(&bytes)
+}
+
+/// Return the metadata of an image from the bytes at the start of the image.
+fn image_info(bytes: &[u8]) -> Option<ImageInfo> {
+    let u16_le = |index: usize| -> Option<usize> {
+        Some(u16::from_le_bytes(bytes.get(index..index + 2)?.try_into().ok()?).into())
+    };
+    let u16_be = |index: usize| -> Option<usize> {
+        Some(u16::from_be_bytes(bytes.get(index..index + 2)?.try_into().ok()?).into())
+    };
+    let u32_be = |index: usize| -> Option<usize> {
+        u32::from_be_bytes(bytes.get(index..index + 4)?.try_into().ok()?)
+            .try_into()
+            .ok()
+    };
+    let i32_le = |index: usize| -> Option<usize> {
+        i32::from_le_bytes(bytes.get(index..index + 4)?.try_into().ok()?)
+            .unsigned_abs()
+            .try_into()
+            .ok()
+    };
+
+    let (format, width, height): (ImageFormat, usize, usize) =
+        if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            (ImageFormat::Png, u32_be(16)?, u32_be(20)?)
+        } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            (ImageFormat::Gif, u16_le(6)?, u16_le(8)?)
+        } else if bytes.starts_with(b"BM") {
+            (ImageFormat::Bmp, i32_le(18)?, i32_le(22)?)
+        } else if bytes.starts_with(b"\xff\xd8") {
+            // Find the start of frame segment which contains the dimensions.
+            let mut index: usize = 2;
+            loop {
+                if *bytes.get(index)? != 0xff {
+                    return None;
+                }
+                let marker: u8 = *bytes.get(index + 1)?;
+                let is_start_of_frame: bool =
+                    (0xc0..=0xcf).contains(&marker) && ![0xc4, 0xc8, 0xcc].contains(&marker);
+                if is_start_of_frame {
+                    break (ImageFormat::Jpeg, u16_be(index + 7)?, u16_be(index + 5)?);
+                }
+                index += 2 + u16_be(index + 2)?;
+            }
+        } else {
+            return None;
+        };
+
+    Some(ImageInfo {
+        format,
+        width,
+        height,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR\x00\x00\x02\x80\x00\x00\x01\xe0", Some(ImageInfo { format: ImageFormat::Png, width: 640, height: 480 }); "png")]
+    #[test_case(b"GIF89a\x10\x00\x20\x00", Some(ImageInfo { format: ImageFormat::Gif, width: 16, height: 32 }); "gif")]
+    #[test_case(b"BM\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x03\x00\x00\x00\xfe\xff\xff\xff", Some(ImageInfo { format: ImageFormat::Bmp, width: 3, height: 2 }); "top down bmp")]
+    #[test_case(b"\xff\xd8\xff\xe0\x00\x04\x00\x00\xff\xc0\x00\x11\x08\x00\x02\x00\x03", Some(ImageInfo { format: ImageFormat::Jpeg, width: 3, height: 2 }); "jpeg")]
+    #[test_case(b"\x89PNG\r\n\x1a\n", None; "truncated png")]
+    #[test_case(b"fn main() {}", None; "not an image")]
+    fn test_image_info(bytes: &[u8], expected_info: Option<ImageInfo>) {
+        let info: Option<ImageInfo> = image_info(bytes);
+
+        assert_eq!(info, expected_info);
+    }
+}
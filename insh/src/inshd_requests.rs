@@ -0,0 +1,161 @@
+/*!
+This module contains the functions [`find_files`] and [`search_phrase`] for sending requests to
+inshd and handling each of the responses as it arrives outside of the app (used by the `open`
+command and the `--rpc` mode).
+*/
+use std::error::Error;
+use std::fmt::{Display, Error as FmtError, Formatter};
+use std::io::{Error as IOError, Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+
+use insh_api::wire::{decode, decode_length, DecodeError, LENGTH_LEN};
+use insh_api::{
+    FindFilesRequestParams, Request, RequestParams, Response, ResponseParams,
+    SearchPhraseRequestParams,
+};
+use path_finder::Entry;
+use phrase_searcher::FileHit;
+
+/// Find the files in `dir` (recursively) whose name matches `pattern` and handle each batch of
+/// their entries as it arrives.
+pub fn find_files(
+    inshd_socket: &Path,
+    dir: PathBuf,
+    pattern: &str,
+    same_file_system: bool,
+    unicode_matching: bool,
+    mut handle: impl FnMut(Vec<Entry>) -> Result<(), IOError>,
+) -> Result<(), InshdRequestError> {
+    let params = RequestParams::FindFiles(
+        FindFilesRequestParams::builder()
+            .dir(dir)
+            .pattern(pattern.to_string())
+            .same_file_system(same_file_system)
+            .unicode_matching(unicode_matching)
+            .build(),
+    );
+
+    send_request(inshd_socket, params, |params| match params {
+        ResponseParams::FindFiles(params) => {
+            handle(params.into_entries()).map_err(InshdRequestError::Handle)
+        }
+        _ => Err(InshdRequestError::UnexpectedResponse),
+    })
+}
+
+/// Search the files in `dir` (recursively) for lines containing `phrase` and handle each batch of
+/// the hits as it arrives.
+pub fn search_phrase(
+    inshd_socket: &Path,
+    dir: PathBuf,
+    phrase: &str,
+    same_file_system: bool,
+    unicode_matching: bool,
+    mut handle: impl FnMut(Vec<FileHit>) -> Result<(), IOError>,
+) -> Result<(), InshdRequestError> {
+    let params = RequestParams::SearchPhrase(
+        SearchPhraseRequestParams::builder()
+            .dir(dir)
+            .phrase(phrase.to_string())
+            .same_file_system(same_file_system)
+            .unicode_matching(unicode_matching)
+            .build(),
+    );
+
+    send_request(inshd_socket, params, |params| match params {
+        ResponseParams::SearchPhrase(params) => {
+            handle(params.into_file_hits()).map_err(InshdRequestError::Handle)
+        }
+        _ => Err(InshdRequestError::UnexpectedResponse),
+    })
+}
+
+/// Send a request with the params to inshd (at the socket `inshd_socket`) and handle the params of
+/// each of the responses to it until the last one (or until handling them fails, which closes the
+/// socket so that inshd cancels the request).
+fn send_request(
+    inshd_socket: &Path,
+    params: RequestParams,
+    mut handle: impl FnMut(ResponseParams) -> Result<(), InshdRequestError>,
+) -> Result<(), InshdRequestError> {
+    let mut socket = UnixStream::connect(inshd_socket).map_err(InshdRequestError::Connect)?;
+
+    let request = Request::builder().params(params).build();
+
+    // Write the length of the request followed by the serialized request.
+    let bytes: Vec<u8> = bincode::serialize(&request).map_err(InshdRequestError::Serialize)?;
+    let length: u64 = bytes.len().try_into().unwrap();
+    socket
+        .write_all(&length.to_be_bytes())
+        .map_err(InshdRequestError::Write)?;
+    socket.write_all(&bytes).map_err(InshdRequestError::Write)?;
+
+    let mut length_buffer: [u8; LENGTH_LEN] = [0; LENGTH_LEN];
+    loop {
+        // Read the length of the response followed by the response.
+        socket
+            .read_exact(&mut length_buffer)
+            .map_err(InshdRequestError::Read)?;
+        let length: usize = decode_length(length_buffer).map_err(InshdRequestError::Decode)?;
+        let mut response_buffer: Vec<u8> = vec![0; length];
+        socket
+            .read_exact(&mut response_buffer)
+            .map_err(InshdRequestError::Read)?;
+        let response: Response = decode(&response_buffer).map_err(InshdRequestError::Decode)?;
+
+        if response.uuid() != request.uuid() {
+            continue;
+        }
+
+        let last: bool = response.last();
+        handle(response.into_params())?;
+
+        if last {
+            return Ok(());
+        }
+    }
+}
+
+/// An error sending a request to inshd.
+#[derive(Debug)]
+pub enum InshdRequestError {
+    /// Failed to connect to the inshd socket.
+    Connect(IOError),
+    /// Failed to serialize the request.
+    Serialize(bincode::Error),
+    /// Failed to write the request to the socket.
+    Write(IOError),
+    /// Failed to read a response from the socket.
+    Read(IOError),
+    /// Failed to decode a response.
+    Decode(DecodeError),
+    /// Received a response that is not for the request.
+    UnexpectedResponse,
+    /// Failed to handle the results of a response (like writing them out).
+    Handle(IOError),
+}
+
+impl Display for InshdRequestError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::Connect(error) => {
+                write!(
+                    formatter,
+                    "Failed to connect to the inshd socket: {}",
+                    error
+                )
+            }
+            Self::Serialize(error) => write!(formatter, "Failed to serialize a request: {}", error),
+            Self::Write(error) => write!(formatter, "Failed to send a request to inshd: {}", error),
+            Self::Read(error) => {
+                write!(formatter, "Failed to read a response from inshd: {}", error)
+            }
+            Self::Decode(error) => write!(formatter, "Failed to decode a response: {}", error),
+            Self::UnexpectedResponse => write!(formatter, "Received an unexpected response."),
+            Self::Handle(error) => write!(formatter, "Failed to handle the results: {}", error),
+        }
+    }
+}
+
+impl Error for InshdRequestError {}
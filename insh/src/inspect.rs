@@ -0,0 +1,82 @@
+/*!
+This module contains the trait [`Inspect`] for describing a component and its children (for the
+debug overlay).
+*/
+use std::fmt::Debug;
+
+/// A description of a component, its key state, and its children.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Inspection {
+    name: &'static str,
+    fields: Vec<(&'static str, String)>,
+    children: Vec<Inspection>,
+}
+
+impl Inspection {
+    /// Return a new inspection of the component `name`.
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            fields: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Add a field of the state of the component.
+    pub fn field<T: Debug>(mut self, name: &'static str, value: T) -> Self {
+        self.fields.push((name, format!("{:?}", value)));
+        self
+    }
+
+    /// Add a child component.
+    pub fn child(mut self, child: Inspection) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Return the lines describing the component tree (children are indented below their parent).
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = Vec::new();
+        self.push_lines(0, &mut lines);
+        lines
+    }
+
+    /// Push the lines describing the component at the indentation `depth`.
+    fn push_lines(&self, depth: usize, lines: &mut Vec<String>) {
+        let indent: String = "  ".repeat(depth);
+        lines.push(format!("{}{}", indent, self.name));
+        for (name, value) in &self.fields {
+            lines.push(format!("{}  {}: {}", indent, name, value));
+        }
+        for child in &self.children {
+            child.push_lines(depth + 1, lines);
+        }
+    }
+}
+
+/// Describe a component (for the debug overlay).
+pub trait Inspect {
+    fn inspect(&self) -> Inspection;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines() {
+        let inspection = Inspection::new("Browser")
+            .field("focus", "Contents")
+            .child(Inspection::new("Contents").field("pending_request", None::<u8>));
+
+        assert_eq!(
+            inspection.lines(),
+            vec![
+                "Browser",
+                "  focus: \"Contents\"",
+                "  Contents",
+                "    pending_request: None",
+            ]
+        );
+    }
+}
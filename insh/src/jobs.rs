@@ -0,0 +1,235 @@
+/*!
+This module contains [`Jobs`] which are the commands that were run in the background (with
+`"background": true` plugin commands), whether they are still running, and what they output once
+they finished. The jobs are listed by the `jobs` command of the command palette.
+*/
+use std::process::ExitStatus;
+use std::time::{Duration, Instant};
+
+use uuid::Uuid;
+
+/// The maximum number of finished jobs that are kept.
+const MAX_FINISHED: usize = 50;
+
+/// Whether a job is running or how it finished.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JobStatus {
+    Running,
+    /// The job exited (successfully or not).
+    Exited(ExitStatus),
+    /// The job could not be run.
+    Failed(String),
+}
+
+/// A command that was run in the background.
+#[derive(Debug, Clone)]
+pub struct Job {
+    uuid: Uuid,
+    name: String,
+    status: JobStatus,
+    /// When the job started.
+    started: Instant,
+    /// When the job finished (if it did).
+    finished: Option<Instant>,
+    /// Whether the job was asked to stop.
+    killed: bool,
+    /// The end of the stdout of the job (once it finished).
+    stdout: String,
+    /// The end of the stderr of the job (once it finished).
+    stderr: String,
+}
+
+impl Job {
+    pub fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn status(&self) -> &JobStatus {
+        &self.status
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.status == JobStatus::Running
+    }
+
+    pub fn killed(&self) -> bool {
+        self.killed
+    }
+
+    pub fn stdout(&self) -> &str {
+        &self.stdout
+    }
+
+    pub fn stderr(&self) -> &str {
+        &self.stderr
+    }
+
+    /// Return how long the job ran for (or has been running for at the time `now`).
+    pub fn runtime(&self, now: Instant) -> Duration {
+        self.finished
+            .unwrap_or(now)
+            .saturating_duration_since(self.started)
+    }
+
+    /// Return a short description of the status (like `running` or `exit status: 1`).
+    pub fn describe_status(&self) -> String {
+        match &self.status {
+            JobStatus::Running if self.killed => String::from("stopping"),
+            JobStatus::Running => String::from("running"),
+            JobStatus::Exited(status) if status.success() => String::from("done"),
+            JobStatus::Exited(status) => status.to_string(),
+            JobStatus::Failed(error) => format!("failed to run: {}", error),
+        }
+    }
+}
+
+/// The jobs (from oldest to newest).
+#[derive(Debug, Default)]
+pub struct Jobs {
+    jobs: Vec<Job>,
+}
+
+impl Jobs {
+    pub fn jobs(&self) -> &[Job] {
+        &self.jobs
+    }
+
+    pub fn get(&self, uuid: &Uuid) -> Option<&Job> {
+        self.jobs.iter().find(|job| &job.uuid == uuid)
+    }
+
+    /// Return the number of jobs that are running.
+    pub fn running_count(&self) -> usize {
+        self.jobs.iter().filter(|job| job.is_running()).count()
+    }
+
+    /// Add a job that started at the time `now`.
+    pub fn start(&mut self, uuid: Uuid, name: String, now: Instant) {
+        self.jobs.push(Job {
+            uuid,
+            name,
+            status: JobStatus::Running,
+            started: now,
+            finished: None,
+            killed: false,
+            stdout: String::new(),
+            stderr: String::new(),
+        });
+    }
+
+    /// Remember that the job was asked to stop. Return whether it is running.
+    pub fn kill(&mut self, uuid: &Uuid) -> bool {
+        match self.jobs.iter_mut().find(|job| &job.uuid == uuid) {
+            Some(job) if job.is_running() => {
+                job.killed = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Record that the job finished at the time `now` (forgetting the oldest finished jobs if there
+    /// are too many). Return the job.
+    pub fn finish(
+        &mut self,
+        uuid: &Uuid,
+        status: JobStatus,
+        stdout: String,
+        stderr: String,
+        now: Instant,
+    ) -> Option<&Job> {
+        let index: usize = self.jobs.iter().position(|job| &job.uuid == uuid)?;
+        let job: &mut Job = &mut self.jobs[index];
+        job.status = status;
+        job.finished = Some(now);
+        job.stdout = stdout;
+        job.stderr = stderr;
+
+        let finished: usize = self.jobs.len() - self.running_count();
+        let mut excess: usize = finished.saturating_sub(MAX_FINISHED);
+        // NOTE: The job that just finished is never forgotten (so that it can be returned).
+        self.jobs.retain(|job| {
+            if excess > 0 && !job.is_running() && &job.uuid != uuid {
+                excess -= 1;
+                return false;
+            }
+            true
+        });
+        self.get(uuid)
+    }
+}
+
+/// Return the duration in its two largest units (like `3m 5s`).
+pub fn format_runtime(runtime: Duration) -> String {
+    let seconds: u64 = runtime.as_secs();
+    match seconds {
+        0..=59 => format!("{}s", seconds),
+        60..=3599 => format!("{}m {}s", seconds / 60, seconds % 60),
+        _ => format!("{}h {}m", seconds / 3600, seconds % 3600 / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::process::ExitStatusExt;
+    use test_case::test_case;
+
+    #[test_case(0, "0s"; "zero")]
+    #[test_case(59, "59s"; "seconds")]
+    #[test_case(185, "3m 5s"; "minutes")]
+    #[test_case(7500, "2h 5m"; "hours")]
+    fn test_format_runtime(seconds: u64, expected: &str) {
+        assert_eq!(format_runtime(Duration::from_secs(seconds)), expected);
+    }
+
+    #[test]
+    fn test_finish() {
+        let start = Instant::now();
+        let mut jobs = Jobs::default();
+        let uuid = Uuid::new_v4();
+        jobs.start(uuid, "fetch".to_string(), start);
+        assert_eq!(jobs.running_count(), 1);
+
+        let job: &Job = jobs
+            .finish(
+                &uuid,
+                JobStatus::Exited(ExitStatus::from_raw(0)),
+                "out".to_string(),
+                String::new(),
+                start + Duration::from_secs(3),
+            )
+            .unwrap();
+
+        assert_eq!(job.describe_status(), "done");
+        assert_eq!(job.stdout(), "out");
+        assert_eq!(
+            job.runtime(start + Duration::from_secs(10)),
+            Duration::from_secs(3)
+        );
+        assert_eq!(jobs.running_count(), 0);
+    }
+
+    #[test]
+    fn test_finished_jobs_are_forgotten() {
+        let now = Instant::now();
+        let mut jobs = Jobs::default();
+        let running = Uuid::new_v4();
+        jobs.start(running, "watch".to_string(), now);
+        for _ in 0..MAX_FINISHED + 2 {
+            let uuid = Uuid::new_v4();
+            jobs.start(uuid, "build".to_string(), now);
+            let status = JobStatus::Failed("nope".to_string());
+            assert!(jobs
+                .finish(&uuid, status, String::new(), String::new(), now)
+                .is_some());
+        }
+
+        assert_eq!(jobs.jobs().len(), MAX_FINISHED + 1);
+        assert!(jobs.get(&running).is_some());
+    }
+}
@@ -0,0 +1,111 @@
+/*!
+This module contains the struct [`ListFilter`] which narrows a list to the entries whose names
+match a phrase (used by the `/` filter of the browser and the finder).
+*/
+
+/// Return if `name` matches `phrase`, that is if the characters of the phrase appear in the name in
+/// order (so a substring always matches).
+///
+/// Matching ignores case unless the phrase contains an uppercase character.
+pub fn fuzzy_match(name: &str, phrase: &str) -> bool {
+    let case_sensitive: bool = phrase.chars().any(char::is_uppercase);
+    let mut name_characters = name.chars();
+    phrase.chars().all(|phrase_character| {
+        name_characters.any(|name_character| match case_sensitive {
+            true => name_character == phrase_character,
+            false => name_character
+                .to_lowercase()
+                .eq(phrase_character.to_lowercase()),
+        })
+    })
+}
+
+/// A filter of a list by the names of its entries.
+#[derive(Debug, Default)]
+pub struct ListFilter {
+    phrase: String,
+    /// The indices of the entries that match the phrase (in order).
+    matches: Vec<usize>,
+}
+
+impl ListFilter {
+    /// Return the phrase that the names are matched against.
+    pub fn phrase(&self) -> &str {
+        &self.phrase
+    }
+
+    /// Return the number of entries that match the phrase.
+    pub fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    /// Return the index (in the whole list) of the `number`th match.
+    pub fn get(&self, number: usize) -> Option<usize> {
+        self.matches.get(number).copied()
+    }
+
+    /// Change the phrase and match it against the names of the entries of the list.
+    pub fn set_phrase<S: AsRef<str>>(&mut self, phrase: &str, names: impl Iterator<Item = S>) {
+        self.phrase = phrase.to_string();
+        self.update(names);
+    }
+
+    /// Match the phrase against the names of the entries of the list (after the list changed).
+    pub fn update<S: AsRef<str>>(&mut self, names: impl Iterator<Item = S>) {
+        self.matches = names
+            .enumerate()
+            .filter(|(_, name)| fuzzy_match(name.as_ref(), &self.phrase))
+            .map(|(index, _)| index)
+            .collect();
+    }
+
+    /// Match the phrase against the names of the entries that were appended to the list (starting
+    /// at the index `start`), without matching the entries before them again.
+    pub fn extend<S: AsRef<str>>(&mut self, start: usize, names: impl Iterator<Item = S>) {
+        let matches = names
+            .enumerate()
+            .filter(|(_, name)| fuzzy_match(name.as_ref(), &self.phrase))
+            .map(|(index, _)| start + index);
+        self.matches.extend(matches);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("Cargo.toml", "toml", true; "substring")]
+    #[test_case("Cargo.toml", "ctml", true; "fuzzy")]
+    #[test_case("Cargo.toml", "CARGO", false; "case sensitive")]
+    #[test_case("Cargo.toml", "cargo", true; "case insensitive")]
+    #[test_case("Cargo.toml", "tomlc", false; "out of order")]
+    #[test_case("Cargo.toml", "", true; "empty phrase")]
+    #[test_case("", "a", false; "empty name")]
+    fn test_fuzzy_match(name: &str, phrase: &str, expected_match: bool) {
+        assert_eq!(fuzzy_match(name, phrase), expected_match);
+    }
+
+    #[test_case("", vec![0, 1, 2, 3]; "empty phrase")]
+    #[test_case("rs", vec![0, 2]; "some")]
+    #[test_case("zzz", vec![]; "none")]
+    fn test_set_phrase(phrase: &str, expected_matches: Vec<usize>) {
+        let names = ["main.rs", "README.md", "lib.rs", "Cargo.toml"];
+        let mut filter = ListFilter::default();
+
+        filter.set_phrase(phrase, names.iter());
+
+        assert_eq!(filter.phrase(), phrase);
+        assert_eq!(filter.matches, expected_matches);
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut filter = ListFilter::default();
+        filter.set_phrase("rs", ["main.rs", "README.md"].iter());
+
+        filter.extend(2, ["lib.rs", "Cargo.toml", "mod.rs"].iter());
+
+        assert_eq!(filter.matches, vec![0, 2, 4]);
+    }
+}
@@ -0,0 +1,441 @@
+/*!
+This module contains the struct [`ListViewport`] which keeps track of the selected entry of a list
+and the part of the list that is visible (used by the browser, the finder, and the searcher).
+*/
+use std::ops::Range;
+
+use crate::scrolloff::{margin, offset_for};
+
+/// The selection and scroll position of a list that is shown in a number of rows.
+///
+/// The viewport never scrolls past the first or the last entry and the selection (if there is one)
+/// is always visible.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ListViewport {
+    /// The number of visible rows.
+    rows: usize,
+    /// The number of entries in the list.
+    len: usize,
+    /// The number of rows of context to keep above and below the selection.
+    scrolloff: usize,
+    /// The row of the selected entry.
+    selected: Option<usize>,
+    /// The number of the first visible entry.
+    offset: usize,
+}
+
+impl ListViewport {
+    /// Return a new viewport for an empty list that is shown in `rows` rows.
+    pub fn new(rows: usize, scrolloff: usize) -> Self {
+        Self {
+            rows,
+            scrolloff,
+            ..Default::default()
+        }
+    }
+
+    /// Return the number of the selected entry.
+    pub fn selected(&self) -> Option<usize> {
+        self.selected.map(|selected| self.offset + selected)
+    }
+
+    /// Return the row of the selected entry.
+    pub fn selected_row(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Return the range of the numbers of the visible entries.
+    pub fn visible(&self) -> Range<usize> {
+        let end: usize = (self.offset + self.rows).min(self.len);
+        self.offset.min(end)..end
+    }
+
+    /// Set the number of entries in the list.
+    ///
+    /// The selection is kept on the same row (or moved to the last entry if the selected entry is
+    /// gone) but nothing is selected if there was no selection.
+    pub fn set_len(&mut self, len: usize) {
+        self.len = len;
+        if len == 0 {
+            self.clear();
+            return;
+        }
+        match (self.selected(), self.selected) {
+            (Some(index), Some(row)) => self.select_at_row(index.min(len - 1), row),
+            _ => self.offset = self.offset.min(self.max_offset()),
+        }
+    }
+
+    /// Remove the selection and scroll to the top.
+    pub fn clear(&mut self) {
+        self.selected = None;
+        self.offset = 0;
+    }
+
+    /// Select the entry at `index` and scroll as little as possible to keep the rows of context
+    /// around it.
+    pub fn select(&mut self, index: usize) {
+        if self.len == 0 {
+            return;
+        }
+        let index: usize = index.min(self.len - 1);
+        if self.rows == 0 {
+            self.select_at_row(index, 0);
+            return;
+        }
+        self.offset = offset_for(index, self.offset, self.len, self.rows, self.scrolloff);
+        self.selected = Some(index - self.offset);
+    }
+
+    /// Select the entry at `index` on `row` (or as close to it as possible without scrolling past
+    /// the first or the last entry).
+    pub fn select_at_row(&mut self, index: usize, row: usize) {
+        if self.len == 0 {
+            return;
+        }
+        let index: usize = index.min(self.len - 1);
+        let rows: usize = self.rows.max(1);
+        self.offset = index
+            .saturating_sub(row.min(rows - 1))
+            .min(self.max_offset());
+        self.selected = Some(index - self.offset);
+    }
+
+    /// Select the entry `count` entries down (or the last entry) and return if the selection
+    /// moved.
+    pub fn down(&mut self, count: usize) -> bool {
+        match self.selected() {
+            Some(index) if index + 1 < self.len => {
+                self.select(index.saturating_add(count));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Select the entry `count` entries up (or the first entry) and return if the selection moved.
+    pub fn up(&mut self, count: usize) -> bool {
+        match self.selected() {
+            Some(index) if index > 0 => {
+                self.select(index.saturating_sub(count));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Scroll down by `distance` entries and move the selection along with the list so that it
+    /// stays on the same row (or on a lower row once the last entry is visible). Return if the
+    /// selection moved.
+    pub fn scroll_down(&mut self, distance: usize) -> bool {
+        match (self.selected(), self.selected) {
+            (Some(index), Some(row)) if index + 1 < self.len => {
+                let index: usize = index.saturating_add(distance);
+                self.select_at_row(index, row);
+                self.select(index);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Scroll up by `distance` entries and move the selection along with the list so that it stays
+    /// on the same row (or on a higher row once the first entry is visible). Return if the
+    /// selection moved.
+    pub fn scroll_up(&mut self, distance: usize) -> bool {
+        match (self.selected(), self.selected) {
+            (Some(index), Some(row)) if index > 0 => {
+                let index: usize = index.saturating_sub(distance);
+                self.select_at_row(index, row);
+                self.select(index);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Scroll down by `distance` entries without moving the selection (unless it would be scrolled
+    /// into the rows of context at the top, then it moves down with the list).
+    pub fn scroll_view_down(&mut self, distance: usize) {
+        let offset: usize = (self.offset + distance).min(self.max_offset());
+        if offset == self.offset {
+            return;
+        }
+        if let Some(index) = self.selected() {
+            let index: usize = index
+                .max(offset + margin(self.scrolloff, self.rows))
+                .min(self.len - 1);
+            self.selected = Some(index - offset);
+        }
+        self.offset = offset;
+    }
+
+    /// Scroll up by `distance` entries without moving the selection (unless it would be scrolled
+    /// into the rows of context at the bottom, then it moves up with the list).
+    pub fn scroll_view_up(&mut self, distance: usize) {
+        let offset: usize = self.offset.saturating_sub(distance);
+        if offset == self.offset {
+            return;
+        }
+        if let Some(index) = self.selected() {
+            let last: usize = offset + self.rows.max(1) - 1;
+            let index: usize = index.min(last.saturating_sub(margin(self.scrolloff, self.rows)));
+            self.selected = Some(index - offset);
+        }
+        self.offset = offset;
+    }
+
+    /// Return the number of entries in half of a page.
+    pub fn half_page(&self) -> usize {
+        (self.rows / 2).max(1)
+    }
+
+    /// Return the number of entries in a page.
+    pub fn page(&self) -> usize {
+        self.rows.max(1)
+    }
+
+    /// Select the first entry.
+    pub fn first(&mut self) {
+        self.select_at_row(0, 0);
+    }
+
+    /// Select the last entry.
+    pub fn last(&mut self) {
+        self.select_at_row(self.len.saturating_sub(1), self.rows.saturating_sub(1));
+    }
+
+    /// Change the number of visible rows.
+    ///
+    /// The selected entry stays at the same relative position on the screen (limited so that the
+    /// rows of context stay visible).
+    pub fn resize(&mut self, rows: usize) {
+        let rows_before: usize = self.rows;
+        self.rows = rows;
+        match (self.selected(), self.selected) {
+            (Some(index), Some(row)) => {
+                let row: usize = match rows_before {
+                    0 => 0,
+                    _ => row * rows / rows_before,
+                };
+                self.select_at_row(index, row);
+                self.select(index);
+            }
+            _ => self.offset = self.offset.min(self.max_offset()),
+        }
+    }
+
+    /// Return the largest offset (where the last entry is on the last row).
+    fn max_offset(&self) -> usize {
+        self.len.saturating_sub(self.rows.max(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    /// Return a viewport of a list of `len` entries with the entry at `index` selected on `row`.
+    fn viewport(
+        rows: usize,
+        len: usize,
+        scrolloff: usize,
+        selected: (usize, usize),
+    ) -> ListViewport {
+        let mut viewport = ListViewport::new(rows, scrolloff);
+        viewport.set_len(len);
+        viewport.select_at_row(selected.0, selected.1);
+        viewport
+    }
+
+    #[test_case(10, 100, 0, 5, 2, 3; "same row")]
+    #[test_case(10, 100, 0, 1, 5, 0; "near the first entry")]
+    #[test_case(10, 100, 0, 98, 2, 90; "near the last entry")]
+    #[test_case(10, 100, 0, 3, 12, 0; "row past the last row")]
+    #[test_case(10, 100, 0, 50, 20, 41; "row past the last row when scrolled")]
+    #[test_case(10, 4, 0, 3, 0, 0; "fewer entries than rows")]
+    #[test_case(0, 5, 0, 3, 2, 3; "no rows")]
+    fn test_select_at_row(
+        rows: usize,
+        len: usize,
+        scrolloff: usize,
+        index: usize,
+        row: usize,
+        expected_offset: usize,
+    ) {
+        let viewport: ListViewport = viewport(rows, len, scrolloff, (index, row));
+
+        assert_eq!(viewport.selected(), Some(index));
+        assert_eq!(viewport.offset, expected_offset);
+    }
+
+    #[test_case(viewport(10, 100, 0, (9, 9)), 1, true, Some(10), 1; "scroll at the last row")]
+    #[test_case(viewport(10, 100, 2, (7, 7)), 1, true, Some(8), 1; "scroll within the margin")]
+    #[test_case(viewport(10, 100, 2, (97, 7)), 1, true, Some(98), 90; "margin near the last entry")]
+    #[test_case(viewport(10, 5, 0, (4, 4)), 1, false, Some(4), 0; "last entry")]
+    #[test_case(viewport(10, 100, 0, (2, 2)), 5, true, Some(7), 0; "count")]
+    #[test_case(viewport(10, 100, 0, (2, 2)), 20, true, Some(22), 13; "count past the last row")]
+    #[test_case(viewport(10, 100, 0, (95, 5)), 20, true, Some(99), 90; "count past the last entry")]
+    #[test_case(ListViewport::new(10, 0), 1, false, None, 0; "empty")]
+    fn test_down(
+        mut viewport: ListViewport,
+        count: usize,
+        expected_moved: bool,
+        expected_selected: Option<usize>,
+        expected_offset: usize,
+    ) {
+        let moved: bool = viewport.down(count);
+
+        assert_eq!(moved, expected_moved);
+        assert_eq!(viewport.selected(), expected_selected);
+        assert_eq!(viewport.offset, expected_offset);
+    }
+
+    #[test_case(viewport(10, 100, 0, (10, 0)), 1, true, Some(9), 9; "scroll at the first row")]
+    #[test_case(viewport(10, 100, 2, (12, 2)), 1, true, Some(11), 9; "scroll within the margin")]
+    #[test_case(viewport(10, 100, 2, (2, 2)), 1, true, Some(1), 0; "margin near the first entry")]
+    #[test_case(viewport(10, 100, 0, (0, 0)), 1, false, Some(0), 0; "first entry")]
+    #[test_case(viewport(10, 100, 0, (50, 9)), 5, true, Some(45), 41; "count")]
+    #[test_case(viewport(10, 100, 0, (5, 5)), 20, true, Some(0), 0; "count past the first entry")]
+    fn test_up(
+        mut viewport: ListViewport,
+        count: usize,
+        expected_moved: bool,
+        expected_selected: Option<usize>,
+        expected_offset: usize,
+    ) {
+        let moved: bool = viewport.up(count);
+
+        assert_eq!(moved, expected_moved);
+        assert_eq!(viewport.selected(), expected_selected);
+        assert_eq!(viewport.offset, expected_offset);
+    }
+
+    #[test_case(viewport(10, 100, 0, (50, 5)), 5, Some(55), 50; "half page")]
+    #[test_case(viewport(10, 100, 0, (50, 5)), 10, Some(60), 55; "page")]
+    #[test_case(viewport(10, 100, 0, (93, 3)), 5, Some(98), 90; "near the last entry")]
+    #[test_case(viewport(10, 100, 0, (97, 7)), 5, Some(99), 90; "past the last entry")]
+    #[test_case(viewport(10, 5, 0, (1, 1)), 5, Some(4), 0; "short list")]
+    #[test_case(viewport(10, 100, 3, (2, 2)), 5, Some(7), 4; "keep the margin")]
+    fn test_scroll_down(
+        mut viewport: ListViewport,
+        distance: usize,
+        expected_selected: Option<usize>,
+        expected_offset: usize,
+    ) {
+        viewport.scroll_down(distance);
+
+        assert_eq!(viewport.selected(), expected_selected);
+        assert_eq!(viewport.offset, expected_offset);
+    }
+
+    #[test_case(viewport(10, 100, 0, (50, 5)), 5, Some(45), 40; "half page")]
+    #[test_case(viewport(10, 100, 0, (50, 5)), 10, Some(40), 35; "page")]
+    #[test_case(viewport(10, 100, 0, (6, 6)), 5, Some(1), 0; "near the first entry")]
+    #[test_case(viewport(10, 100, 0, (2, 2)), 5, Some(0), 0; "past the first entry")]
+    #[test_case(viewport(10, 100, 3, (97, 7)), 5, Some(92), 86; "keep the margin")]
+    fn test_scroll_up(
+        mut viewport: ListViewport,
+        distance: usize,
+        expected_selected: Option<usize>,
+        expected_offset: usize,
+    ) {
+        viewport.scroll_up(distance);
+
+        assert_eq!(viewport.selected(), expected_selected);
+        assert_eq!(viewport.offset, expected_offset);
+    }
+
+    #[test_case(viewport(10, 100, 0, (50, 5)), 3, Some(50), 48; "selection stays")]
+    #[test_case(viewport(10, 100, 0, (50, 1)), 3, Some(52), 52; "selection at the top")]
+    #[test_case(viewport(10, 100, 2, (50, 3)), 3, Some(52), 50; "keep the margin")]
+    #[test_case(viewport(10, 100, 0, (95, 5)), 3, Some(95), 90; "last entry visible")]
+    fn test_scroll_view_down(
+        mut viewport: ListViewport,
+        distance: usize,
+        expected_selected: Option<usize>,
+        expected_offset: usize,
+    ) {
+        viewport.scroll_view_down(distance);
+
+        assert_eq!(viewport.selected(), expected_selected);
+        assert_eq!(viewport.offset, expected_offset);
+    }
+
+    #[test_case(viewport(10, 100, 0, (50, 5)), 3, Some(50), 42; "selection stays")]
+    #[test_case(viewport(10, 100, 0, (50, 8)), 3, Some(48), 39; "selection at the bottom")]
+    #[test_case(viewport(10, 100, 2, (50, 6)), 3, Some(48), 41; "keep the margin")]
+    #[test_case(viewport(10, 100, 0, (2, 2)), 3, Some(2), 0; "first entry visible")]
+    fn test_scroll_view_up(
+        mut viewport: ListViewport,
+        distance: usize,
+        expected_selected: Option<usize>,
+        expected_offset: usize,
+    ) {
+        viewport.scroll_view_up(distance);
+
+        assert_eq!(viewport.selected(), expected_selected);
+        assert_eq!(viewport.offset, expected_offset);
+    }
+
+    #[test_case(viewport(10, 100, 0, (50, 5)), 20, Some(10), 40; "grow")]
+    #[test_case(viewport(10, 100, 0, (50, 5)), 4, Some(2), 48; "shrink")]
+    #[test_case(viewport(10, 100, 0, (95, 5)), 20, Some(15), 80; "grow near the last entry")]
+    #[test_case(viewport(10, 100, 0, (2, 2)), 20, Some(2), 0; "grow near the first entry")]
+    #[test_case(viewport(10, 5, 0, (4, 4)), 3, Some(2), 2; "shrink a short list")]
+    #[test_case(viewport(10, 100, 3, (50, 9)), 10, Some(6), 44; "keep the margin")]
+    #[test_case(viewport(10, 100, 0, (50, 5)), 0, Some(0), 50; "no rows")]
+    #[test_case(viewport(0, 100, 0, (50, 0)), 10, Some(0), 50; "from no rows")]
+    #[test_case(ListViewport::new(10, 0), 20, None, 0; "empty")]
+    fn test_resize(
+        mut viewport: ListViewport,
+        rows: usize,
+        expected_row: Option<usize>,
+        expected_offset: usize,
+    ) {
+        let selected: Option<usize> = viewport.selected();
+
+        viewport.resize(rows);
+
+        assert_eq!(viewport.selected(), selected);
+        assert_eq!(viewport.selected_row(), expected_row);
+        assert_eq!(viewport.offset, expected_offset);
+    }
+
+    #[test_case(viewport(10, 100, 0, (95, 5)), 50, Some(49), 40; "selected entry is gone")]
+    #[test_case(viewport(10, 100, 0, (95, 5)), 98, Some(95), 88; "scrolled past the last entry")]
+    #[test_case(viewport(10, 100, 0, (5, 5)), 0, None, 0; "empty")]
+    fn test_set_len(
+        mut viewport: ListViewport,
+        len: usize,
+        expected_selected: Option<usize>,
+        expected_offset: usize,
+    ) {
+        viewport.set_len(len);
+
+        assert_eq!(viewport.selected(), expected_selected);
+        assert_eq!(viewport.offset, expected_offset);
+    }
+
+    #[test_case(viewport(10, 100, 0, (50, 5)), Some(99), 90; "long list")]
+    #[test_case(viewport(10, 5, 0, (0, 0)), Some(4), 0; "short list")]
+    #[test_case(ListViewport::new(10, 0), None, 0; "empty")]
+    fn test_last(
+        mut viewport: ListViewport,
+        expected_selected: Option<usize>,
+        expected_offset: usize,
+    ) {
+        viewport.last();
+
+        assert_eq!(viewport.selected(), expected_selected);
+        assert_eq!(viewport.offset, expected_offset);
+    }
+
+    #[test_case(viewport(10, 100, 0, (50, 5)), 45..55; "middle")]
+    #[test_case(viewport(10, 5, 0, (2, 2)), 0..5; "short list")]
+    #[test_case(ListViewport::new(10, 0), 0..0; "empty")]
+    fn test_visible(viewport: ListViewport, expected_visible: Range<usize>) {
+        assert_eq!(viewport.visible(), expected_visible);
+    }
+}
@@ -1,18 +1,56 @@
 /// Logging for debug purposes.
-use std::fmt::{Display, Error as FormatError, Formatter};
+///
+/// Log records can be correlated with the request that they are about (see [`correlate`]). The
+/// uuid of the request is included in each record so that the logs of insh can be matched up with
+/// the logs of inshd (which log the uuids of the requests that they handle).
+use std::cell::Cell;
+use std::fmt::{Display, Error as FormatError, Formatter, Write as FormatWrite};
+use std::io::{Error as IOError, Write};
 use std::path::PathBuf;
+use std::thread;
 
+use clap::ValueEnum;
 use flexi_logger::writers::FileLogWriter;
-use flexi_logger::{FileSpec, LogSpecification, Logger, LoggerHandle};
+use flexi_logger::{
+    DeferredNow, FileSpec, FormatFunction, LogSpecification, Logger, LoggerHandle, Record,
+};
+use uuid::Uuid;
+
+thread_local! {
+    /// The request that the records logged by the thread are about.
+    static REQUEST: Cell<Option<Uuid>> = const { Cell::new(None) };
+}
+
+/// The format of log records.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// One line of text per record.
+    #[default]
+    Text,
+    /// One JSON object per line per record.
+    Json,
+}
+
+impl LogFormat {
+    /// Return the function which formats records.
+    fn format_function(&self) -> FormatFunction {
+        match self {
+            Self::Text => text_format,
+            Self::Json => json_format,
+        }
+    }
+}
 
 /// Configure logging.
 pub fn configure_logging(
     path: PathBuf,
     log_specification: LogSpecification,
+    log_format: LogFormat,
 ) -> ConfigureLoggingResult {
     let mut logger = Logger::with(log_specification);
 
     let file_log_writer = FileLogWriter::builder(FileSpec::try_from(path).unwrap())
+        .format(log_format.format_function())
         .try_build()
         .unwrap();
     logger = logger.log_to_writer(Box::new(file_log_writer));
@@ -31,3 +69,121 @@ impl Display for ConfigureLoggingError {
         todo!();
     }
 }
+
+/// Records logged by the current thread are about a request until this is dropped.
+pub struct Correlation {
+    /// The request that records were about before.
+    previous: Option<Uuid>,
+}
+
+impl Drop for Correlation {
+    fn drop(&mut self) {
+        REQUEST.with(|request| request.set(self.previous));
+    }
+}
+
+/// Correlate the records logged by the current thread with the request `uuid` (until the returned
+/// correlation is dropped).
+pub fn correlate(uuid: Uuid) -> Correlation {
+    let previous: Option<Uuid> = REQUEST.with(|request| request.replace(Some(uuid)));
+    Correlation { previous }
+}
+
+/// Return the request that the records logged by the current thread are about.
+fn request() -> Option<Uuid> {
+    REQUEST.with(Cell::get)
+}
+
+/// Format a record as a line of text (with the uuid of the request it is about).
+fn text_format(
+    writer: &mut dyn Write,
+    _now: &mut DeferredNow,
+    record: &Record,
+) -> Result<(), IOError> {
+    write!(
+        writer,
+        "{} [{}] ",
+        record.level(),
+        record.module_path().unwrap_or("<unnamed>")
+    )?;
+    if let Some(request) = request() {
+        write!(writer, "[request {}] ", request)?;
+    }
+    write!(writer, "{}", record.args())
+}
+
+/// Format a record as a JSON object.
+fn json_format(
+    writer: &mut dyn Write,
+    now: &mut DeferredNow,
+    record: &Record,
+) -> Result<(), IOError> {
+    let request: String = match request() {
+        Some(request) => json_string(&request.to_string()),
+        None => String::from("null"),
+    };
+    write!(
+        writer,
+        "{{\"time\":{},\"level\":{},\"module\":{},\"thread\":{},\"request\":{},\"message\":{}}}",
+        json_string(&now.format_rfc3339()),
+        json_string(record.level().as_str()),
+        json_string(record.module_path().unwrap_or("<unnamed>")),
+        json_string(thread::current().name().unwrap_or("<unnamed>")),
+        request,
+        json_string(&record.args().to_string()),
+    )
+}
+
+/// Return `string` as a JSON string (quoted and escaped).
+fn json_string(string: &str) -> String {
+    let mut json = String::with_capacity(string.len() + 2);
+    json.push('"');
+    for character in string.chars() {
+        match character {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            character if character.is_control() => {
+                write!(json, "\\u{:04x}", character as u32).unwrap();
+            }
+            character => json.push(character),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("", "\"\""; "empty")]
+    #[test_case("Received request.", "\"Received request.\""; "plain")]
+    #[test_case("a \"b\" \\c", "\"a \\\"b\\\" \\\\c\""; "quotes and backslashes")]
+    #[test_case("a\nb\tc", "\"a\\nb\\tc\""; "whitespace")]
+    #[test_case("\u{1b}[0m", "\"\\u001b[0m\""; "control")]
+    fn test_json_string(string: &str, expected_json: &str) {
+        assert_eq!(json_string(string), expected_json);
+    }
+
+    #[test]
+    fn test_correlate() {
+        let outer = Uuid::new_v4();
+        let inner = Uuid::new_v4();
+
+        assert_eq!(request(), None);
+        {
+            let _correlation = correlate(outer);
+            assert_eq!(request(), Some(outer));
+            {
+                let _correlation = correlate(inner);
+                assert_eq!(request(), Some(inner));
+            }
+            assert_eq!(request(), Some(outer));
+        }
+        assert_eq!(request(), None);
+    }
+}
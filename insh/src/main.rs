@@ -16,52 +16,108 @@ mod ansi_escaped_text;
 mod args;
 mod auto_completer;
 mod auto_completers;
+mod choose_mode;
+mod chords;
 mod clipboard;
+mod collation;
 mod color;
 mod components;
 mod config;
+mod count;
 mod current_dir;
 mod data;
+mod devices;
+mod escalation;
+mod expand;
+mod external;
+mod file_metadata;
+mod hits;
+mod hooks;
+mod image_preview;
+mod inshd_requests;
+mod inspect;
+mod jobs;
+mod list_filter;
+mod list_viewport;
 #[cfg(feature = "logging")]
 mod logging;
-mod phrase_searcher;
+mod markdown;
+mod open_policy;
+mod path_format;
+mod pattern;
+mod plugins;
 mod programs;
+mod recording;
+mod registers;
+mod relative_time;
+mod request_registry;
 mod requester;
 mod response_handler;
+mod rpc;
+mod script;
+mod scrolloff;
+mod shell_init;
+mod sort_mode;
 mod stateful;
 mod string;
+mod symlink;
+mod toasts;
 
 use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
 use std::process::exit;
+use std::rc::Rc;
+use std::sync::Arc;
 
 use clap::Parser;
 #[cfg(feature = "logging")]
 use flexi_logger::LoggerHandle;
 use uuid::Uuid;
 
-use common::paths::INSHD_SOCKET;
 use insh_api::{GetFilesRequestParams, Request, RequestParams, Response};
+use path_finder::Entry;
 use term::TermEvent;
-use til::{App, AppRunOptions, Component, Requester, ResponseHandler, Stopper, SystemEffect};
+use til::{
+    App, AppExit, AppRunOptions, Component, Recorder, Replayer, Requester, ResponseHandler,
+    Stopper, SystemEffect,
+};
 
-use crate::args::Args;
-use crate::components::{Insh, InshProps};
+use crate::args::{Args, Command};
+use crate::components::{Insh, InshProps, Start};
 use crate::config::Config;
+use crate::inshd_requests::find_files;
 #[cfg(feature = "logging")]
 use crate::logging::{configure_logging, ConfigureLoggingResult};
+use crate::open_policy::open;
+use crate::programs::VimArgsBuilder;
+use crate::recording::{FileRecorder, FileReplayer, ReplayRequester};
 use crate::requester::InshdRequester;
 use crate::response_handler::{InshdResponseHandler, InshdResponseHandlerStopper};
+use crate::script::Script;
 use crate::stateful::Stateful;
 
 fn main() {
     let args: Args = Args::parse();
 
+    if let Some(Command::ShellInit { shell }) = args.command() {
+        print!("{}", shell.init_script());
+        exit(0);
+    }
+
+    if let Some(Command::PreviewImage { path }) = args.command() {
+        image_preview::preview_image(path);
+        exit(0);
+    }
+
     #[cfg(feature = "logging")]
     let _logger_handle: LoggerHandle;
     #[cfg(feature = "logging")]
     if let Some(log_file_path) = args.log_file_path() {
-        let configure_logging_result: ConfigureLoggingResult =
-            configure_logging(log_file_path.to_path_buf(), args.log_specification());
+        let configure_logging_result: ConfigureLoggingResult = configure_logging(
+            log_file_path.to_path_buf(),
+            args.log_specification(),
+            args.log_format(),
+        );
         _logger_handle = match configure_logging_result {
             Ok(_logger_handle) => _logger_handle,
             Err(error) => {
@@ -71,13 +127,87 @@ fn main() {
         }
     }
 
+    let mut config: Config = match Config::load(args.profile()) {
+        Ok(config) => config,
+        Err(error) => {
+            println!("{}", error);
+            exit(1);
+        }
+    };
+    if args.read_only() {
+        config.set_read_only();
+    }
+
+    if args.rpc() {
+        rpc::run(config.general());
+        exit(0);
+    }
+
+    let script: Option<Rc<Script>> = match config.browser().script() {
+        Some(path) => match Script::load(&path) {
+            Ok(script) => Some(Rc::new(script)),
+            Err(error) => {
+                println!("{}", error);
+                exit(1);
+            }
+        },
+        None => None,
+    };
+
+    // When replaying a recording, start in the directory that the recording started in.
+    let mut replay: Option<(FileReplayer, ReplayRequester)> = None;
+    let mut dir: Option<PathBuf> = args.dir().or_else(|| config.general().dir());
+    if let Some(path) = args.replay() {
+        match FileReplayer::open(path) {
+            Ok((replayer, recorded_dir, requester)) => {
+                replay = Some((replayer, requester));
+                dir = Some(recorded_dir);
+            }
+            Err(error) => {
+                println!("{}", error);
+                exit(1);
+            }
+        }
+    }
+
+    // If opening a file, then find the files matching the pattern. If exactly one file matches,
+    // then it is edited directly instead of starting the finder.
+    let file_to_open: Option<PathBuf> = match args.command() {
+        Some(Command::Open { pattern }) => {
+            let dir: PathBuf = dir.clone().unwrap_or_else(current_dir::current_dir);
+            let mut entries: Vec<Entry> = Vec::new();
+            match find_files(
+                &config.general().inshd_socket(),
+                dir,
+                pattern,
+                config.general().same_file_system(),
+                config.general().unicode_matching(),
+                |found| {
+                    entries.extend(found);
+                    Ok(())
+                },
+            ) {
+                Ok(()) => match entries.as_slice() {
+                    [entry] => Some(entry.path().to_path_buf()),
+                    _ => None,
+                },
+                Err(error) => {
+                    println!("{}", error);
+                    exit(1);
+                }
+            }
+        }
+        _ => None,
+    };
+
     // Determine the starting effects.
-    let mut starting_effects: Option<Vec<SystemEffect<Request>>> = args.starting_effects();
+    let mut starting_effects: Option<Vec<SystemEffect<Request>>> =
+        args.starting_effects(config.general().read_only());
     let pending_browser_request: Option<Uuid> = if args.browse() {
         let request = Request::builder()
             .params(RequestParams::GetFiles(
                 GetFilesRequestParams::builder()
-                    .dir(args.dir().clone().unwrap_or_else(current_dir::current_dir))
+                    .dir(dir.clone().unwrap_or_else(current_dir::current_dir))
                     .build(),
             ))
             .build();
@@ -98,28 +228,69 @@ fn main() {
     log::info!("{:?}", pending_browser_request);
 
     // Determine the starting term events.
-    let starting_term_events: Option<Vec<TermEvent>> = args.starting_term_events();
+    let mut starting_term_events: Option<Vec<TermEvent>> = args.starting_term_events();
 
-    let config: Config = match Config::load() {
-        Ok(config) => config,
-        Err(error) => {
-            println!("{}", error);
-            exit(1);
-        }
-    };
+    let mut start: Start = args.command().clone().into();
+    if let Some(file) = file_to_open {
+        let vim_args = VimArgsBuilder::new().path(&file).build();
+        let program = open(vim_args, &config);
+        starting_effects = Some(vec![
+            SystemEffect::RunProgram { program },
+            SystemEffect::Exit,
+        ]);
+        starting_term_events = None;
+        start = Start::Nothing;
+    }
 
     let mut app: App = App::builder().build();
 
+    let inshd_socket: PathBuf = config.general().inshd_socket();
+    let report_dir: bool = config.general().report_dir();
+    let terminal_notifications: bool = config.general().terminal_notifications();
+
     let insh_props: InshProps = InshProps::builder()
-        .dir(args.dir().clone())
-        .start(args.command().clone().into())
+        .dir(dir.clone())
+        .start(start)
         .pending_browser_request(pending_browser_request)
-        .config(config)
+        .config(Arc::new(config))
+        .script(script)
+        .cd_file(args.cd_file().clone())
+        .choose_mode(args.choose_mode())
+        .choose_out(args.out().clone())
         .build();
     let root = Insh::new(insh_props);
 
+    let recorder: Option<Box<dyn Recorder<Request, Response>>> = match args.record() {
+        Some(path) => {
+            let dir: PathBuf = dir.unwrap_or_else(current_dir::current_dir);
+            match FileRecorder::create(path, &dir) {
+                Ok(recorder) => Some(Box::new(recorder)),
+                Err(error) => {
+                    println!("{}", error);
+                    exit(1);
+                }
+            }
+        }
+        None => None,
+    };
+
+    // A replay does not connect to inshd (the responses are replayed instead).
+    if let Some((replayer, requester)) = replay {
+        let requester: Box<dyn Requester<Request>> = Box::new(requester);
+        let replayer: Box<dyn Replayer<Response>> = Box::new(replayer);
+        let run_options: AppRunOptions<InshProps, Request, Response> = AppRunOptions::builder()
+            .root(Box::new(root))
+            .starting_effects(starting_effects)
+            .requester(requester)
+            .replayer(replayer)
+            .report_working_dir(report_dir)
+            .terminal_notifications(terminal_notifications)
+            .build();
+        finish(app.run(run_options));
+    }
+
     // Connect to the Unix socket.
-    let socket = match UnixStream::connect(&*INSHD_SOCKET) {
+    let socket = match UnixStream::connect(&inshd_socket) {
         Ok(socket) => socket,
         Err(error) => {
             println!("Failed to connect to the inshd socket: {}", error);
@@ -153,6 +324,25 @@ fn main() {
         .requester(requester)
         .response_handler(response_handler)
         .response_handler_stopper(response_handler_stopper)
+        .recorder(recorder)
+        .report_working_dir(report_dir)
+        .terminal_notifications(terminal_notifications)
         .build();
-    app.run(run_options);
+    finish(app.run(run_options));
+}
+
+/// Exit with the code for why the app stopped, printing why (after the terminal was restored) if
+/// it didn't exit cleanly.
+fn finish(app_exit: AppExit) -> ! {
+    match app_exit {
+        AppExit::Clean => exit(0),
+        AppExit::Fatal(error) => {
+            println!("Insh stopped because of an error: {}", error);
+            exit(1);
+        }
+        AppExit::DaemonDisconnected => {
+            println!("Insh stopped because it was disconnected from inshd.");
+            exit(2);
+        }
+    }
 }
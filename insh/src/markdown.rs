@@ -0,0 +1,187 @@
+/*!
+This module contains the function [`markdown_to_yarns`] which converts markdown into styled yarns
+so that markdown files are readable in the terminal.
+
+Only a lightweight subset of markdown is supported: headings, lists, block quotes, code fences,
+inline code, emphasis, and strong emphasis.
+*/
+use rend::Yarn;
+
+use crate::color::Color;
+
+/// The character that is shown in place of the marker of an unordered list item.
+const BULLET: char = '•';
+
+/// The prefix that is shown in place of the marker of a block quote.
+const QUOTE_PREFIX: &str = "│ ";
+
+/// Return the styled yarns (one per line) for the markdown `text`.
+pub fn markdown_to_yarns(text: &str) -> Vec<Yarn> {
+    let mut yarns: Vec<Yarn> = Vec::new();
+    let mut in_code_fence: bool = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_fence = !in_code_fence;
+            // Show the language of the code fence (if there is one) in place of the fence.
+            let mut yarn = Yarn::from(line.trim_start().trim_start_matches('`'));
+            yarn.color(Color::GrayedText.into());
+            yarns.push(yarn);
+            continue;
+        }
+
+        if in_code_fence {
+            let mut yarn = Yarn::from(line);
+            yarn.color(Color::MarkdownCode.into());
+            yarns.push(yarn);
+            continue;
+        }
+
+        yarns.push(block_to_yarn(line));
+    }
+    yarns
+}
+
+/// Return the styled yarn for a line which is not in a code fence.
+fn block_to_yarn(line: &str) -> Yarn {
+    if let Some((level, heading)) = heading(line) {
+        let mut yarn = Yarn::from(heading);
+        yarn.color(Color::MarkdownHeading { level }.into());
+        return yarn;
+    }
+
+    if let Some(quote) = line.strip_prefix('>') {
+        let mut prefix = Yarn::from(QUOTE_PREFIX);
+        prefix.color(Color::GrayedText.into());
+        let mut quote: Yarn = inline_to_yarn(quote.strip_prefix(' ').unwrap_or(quote));
+        quote.color(Color::LightGrayedText.into());
+        return prefix.concat(quote);
+    }
+
+    let content: &str = line.trim_start();
+    let indent: &str = &line[..line.len() - content.len()];
+    for marker in ["- ", "* ", "+ "] {
+        if let Some(item) = content.strip_prefix(marker) {
+            let mut prefix = Yarn::from(format!("{}{} ", indent, BULLET));
+            prefix.color_after(Color::MarkdownListMarker.into(), indent.len());
+            return prefix.concat(inline_to_yarn(item));
+        }
+    }
+
+    inline_to_yarn(line)
+}
+
+/// Return the level and the text of the line if it is a heading.
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level: usize = line
+        .chars()
+        .take_while(|character| *character == '#')
+        .count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    match &line[level..] {
+        "" => Some((level, "")),
+        rest => rest.strip_prefix(' ').map(|text| (level, text.trim())),
+    }
+}
+
+/// The style of a span of inline text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InlineStyle {
+    /// Plain text.
+    Plain,
+    /// Emphasized text (`*text*` or `_text_`).
+    Emphasis,
+    /// Strongly emphasized text (`**text**` or `__text__`).
+    Strong,
+    /// Inline code (`` `text` ``).
+    Code,
+}
+
+/// Return the styled yarn for inline markdown text.
+fn inline_to_yarn(text: &str) -> Yarn {
+    let mut yarn = Yarn::new();
+    for (span, style) in parse_inline(text) {
+        let mut span_yarn = Yarn::from(span);
+        match style {
+            InlineStyle::Plain => {}
+            InlineStyle::Emphasis => span_yarn.color(Color::MarkdownEmphasis.into()),
+            InlineStyle::Strong => span_yarn.color(Color::MarkdownStrong.into()),
+            InlineStyle::Code => span_yarn.color(Color::MarkdownCode.into()),
+        }
+        yarn = yarn.concat(span_yarn);
+    }
+    yarn
+}
+
+/// Split inline markdown text into spans of text (with the markers removed) and their styles.
+///
+/// Markers that are not closed are kept as plain text.
+fn parse_inline(text: &str) -> Vec<(String, InlineStyle)> {
+    let mut spans: Vec<(String, InlineStyle)> = Vec::new();
+    let mut plain = String::new();
+    let mut rest: &str = text;
+    while let Some(character) = rest.chars().next() {
+        let styled: Option<(&str, InlineStyle)> = match character {
+            '`' => Some(("`", InlineStyle::Code)),
+            '*' if rest.starts_with("**") => Some(("**", InlineStyle::Strong)),
+            '_' if rest.starts_with("__") => Some(("__", InlineStyle::Strong)),
+            '*' => Some(("*", InlineStyle::Emphasis)),
+            '_' => Some(("_", InlineStyle::Emphasis)),
+            _ => None,
+        };
+
+        if let Some((marker, style)) = styled {
+            let after: &str = &rest[marker.len()..];
+            if let Some(end) = after.find(marker).filter(|end| *end > 0) {
+                if !plain.is_empty() {
+                    spans.push((std::mem::take(&mut plain), InlineStyle::Plain));
+                }
+                spans.push((after[..end].to_string(), style));
+                rest = &after[end + marker.len()..];
+                continue;
+            }
+        }
+
+        plain.push(character);
+        rest = &rest[character.len_utf8()..];
+    }
+    if !plain.is_empty() {
+        spans.push((plain, InlineStyle::Plain));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("# Insh", Some((1, "Insh")); "level one")]
+    #[test_case("### Options ", Some((3, "Options")); "level three")]
+    #[test_case("#", Some((1, "")); "empty heading")]
+    #[test_case("#hashtag", None; "no space")]
+    #[test_case("####### Too deep", None; "too deep")]
+    #[test_case("Plain", None; "plain")]
+    fn test_heading(line: &str, expected_heading: Option<(usize, &str)>) {
+        let heading: Option<(usize, &str)> = heading(line);
+
+        assert_eq!(heading, expected_heading);
+    }
+
+    #[test_case("plain", vec![("plain", InlineStyle::Plain)]; "plain")]
+    #[test_case("a *b* c", vec![("a ", InlineStyle::Plain), ("b", InlineStyle::Emphasis), (" c", InlineStyle::Plain)]; "emphasis")]
+    #[test_case("**b**_c_", vec![("b", InlineStyle::Strong), ("c", InlineStyle::Emphasis)]; "strong and emphasis")]
+    #[test_case("run `cargo *build*`", vec![("run ", InlineStyle::Plain), ("cargo *build*", InlineStyle::Code)]; "code is not styled further")]
+    #[test_case("2 * 3 = 6", vec![("2 * 3 = 6", InlineStyle::Plain)]; "unclosed marker")]
+    #[test_case("**", vec![("**", InlineStyle::Plain)]; "empty marker")]
+    fn test_parse_inline(text: &str, expected_spans: Vec<(&str, InlineStyle)>) {
+        let spans: Vec<(String, InlineStyle)> = parse_inline(text);
+
+        let expected_spans: Vec<(String, InlineStyle)> = expected_spans
+            .into_iter()
+            .map(|(span, style)| (span.to_string(), style))
+            .collect();
+        assert_eq!(spans, expected_spans);
+    }
+}
@@ -0,0 +1,119 @@
+/*!
+This module contains the function [`opener`] which decides how a file should be opened (in the
+editor or in the viewer) before it is opened, and the function [`open`] which opens a file that way
+(everywhere a file is opened from, so that the open is recorded the same way).
+*/
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+use til::Program;
+
+use crate::config::{Config, EditorConfig};
+use crate::data::Data;
+use crate::hooks::{run_hook, HookEvent};
+use crate::programs::{Viewer, Vim, VimArgs};
+
+/// The most files whose open counts are stored (the least opened files are forgotten first).
+const MAX_OPEN_COUNTS: usize = 1000;
+
+/// The number of bytes at the start of a file that are checked when detecting binary files.
+const BINARY_DETECTION_LENGTH: u64 = 8192;
+
+/// How a file should be opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Opener {
+    /// Open the file in the editor.
+    Editor,
+    /// Open the file in the viewer.
+    Viewer,
+}
+
+/// Return how the file at `path` should be opened.
+///
+/// Files that are larger than the max file size and binary files (if configured) are opened in the
+/// viewer. All other files (and files whose metadata cannot be read) are opened in the editor.
+pub fn opener(path: &Path, config: &EditorConfig) -> Opener {
+    let size: u64 = match fs::metadata(path) {
+        Ok(metadata) => metadata.len(),
+        Err(_) => return Opener::Editor,
+    };
+
+    if size > config.max_file_size() {
+        return Opener::Viewer;
+    }
+
+    if config.view_binary_files() && is_binary(path) {
+        return Opener::Viewer;
+    }
+
+    Opener::Editor
+}
+
+/// Record that the file is opened and return the program for opening it in vim (or in the viewer if
+/// the file is too large or is binary).
+pub fn open(mut vim_args: VimArgs, config: &Config) -> Box<dyn Program> {
+    if config.general().read_only() {
+        vim_args = vim_args.into_read_only();
+    }
+
+    if let Some(path) = vim_args.path() {
+        let mut data: Data = Data::read();
+        data.files.record_open(path, MAX_OPEN_COUNTS);
+        data.write();
+        data.release();
+        run_hook(config.hooks(), HookEvent::FileOpened { path });
+
+        let editor_config = config.editor();
+        if opener(path, editor_config) == Opener::Viewer {
+            return Box::new(Viewer::new(
+                editor_config.viewer().to_string(),
+                path.clone(),
+            ));
+        }
+    }
+
+    Box::new(Vim::new(vim_args))
+}
+
+/// Return if the file at `path` looks like a binary file.
+fn is_binary(path: &Path) -> bool {
+    let file: File = match File::open(path) {
+        Ok(file) => file,
+        Err(_) => return false,
+    };
+
+    let mut bytes: Vec<u8> = Vec::new();
+    if file
+        .take(BINARY_DETECTION_LENGTH)
+        .read_to_end(&mut bytes)
+        .is_err()
+    {
+        return false;
+    }
+
+    is_binary_bytes(&bytes)
+}
+
+/// Return if the bytes (from the start of a file) look like they are from a binary file.
+///
+/// Like git and grep, bytes containing a null byte are considered binary.
+fn is_binary_bytes(bytes: &[u8]) -> bool {
+    bytes.contains(&0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(b"", false; "empty")]
+    #[test_case(b"fn main() {}\n", false; "text")]
+    #[test_case("caf\u{e9}\n".as_bytes(), false; "utf-8 text")]
+    #[test_case(b"\x7fELF\x02\x01\x01\x00", true; "null byte")]
+    fn test_is_binary_bytes(bytes: &[u8], expected_binary: bool) {
+        let binary: bool = is_binary_bytes(bytes);
+
+        assert_eq!(binary, expected_binary);
+    }
+}
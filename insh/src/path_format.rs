@@ -0,0 +1,149 @@
+/*!
+This module contains [`PathFormat`] which are the formats that the path of an entry can be copied
+in (chosen with a second key after `y` in the browser).
+*/
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+/// A format of a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathFormat {
+    /// The name of the file.
+    Name,
+    Absolute,
+    /// The path relative to the root of the project (the closest directory with a `.git`).
+    Project,
+    /// The path relative to the home directory (like `~/foo`).
+    Home,
+    /// A `file://` URL.
+    Url,
+    /// The absolute path quoted so that it can be pasted into a shell.
+    Shell,
+}
+
+impl PathFormat {
+    /// All of the formats (in the order that they are listed in the menu).
+    pub const ALL: [PathFormat; 6] = [
+        Self::Name,
+        Self::Absolute,
+        Self::Project,
+        Self::Home,
+        Self::Url,
+        Self::Shell,
+    ];
+
+    /// Return the key which chooses the format in the menu.
+    pub fn key(&self) -> char {
+        match self {
+            Self::Name => 'y',
+            Self::Absolute => 'a',
+            Self::Project => 'p',
+            Self::Home => '~',
+            Self::Url => 'u',
+            Self::Shell => 's',
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Absolute => "absolute",
+            Self::Project => "project",
+            Self::Home => "home",
+            Self::Url => "URL",
+            Self::Shell => "shell",
+        }
+    }
+
+    /// Return the format chosen by the key.
+    pub fn from_key(key: char) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.key() == key)
+    }
+
+    /// Return the path in the format (with a trailing slash if it is a directory), or `None` if
+    /// it can't be written in the format (such as when it is not in a project).
+    pub fn format(&self, path: &Path, is_dir: bool, home: Option<&Path>) -> Option<String> {
+        let mut string: String = match self {
+            Self::Name => path.file_name()?.to_string_lossy().to_string(),
+            Self::Absolute | Self::Shell => path.to_string_lossy().to_string(),
+            Self::Project => {
+                let root: PathBuf = project_root(path)?;
+                relative(path, &root)?
+            }
+            Self::Home => match home.and_then(|home| relative(path, home)) {
+                Some(relative) => format!("~/{}", relative),
+                None => path.to_string_lossy().to_string(),
+            },
+            Self::Url => format!("file://{}", percent_encode(path)),
+        };
+        if is_dir && !string.ends_with('/') {
+            string.push('/');
+        }
+        if *self == Self::Shell {
+            string = shell_quote(&string);
+        }
+        Some(string)
+    }
+}
+
+/// Return the path relative to the directory (if it is under it).
+fn relative(path: &Path, dir: &Path) -> Option<String> {
+    let relative: &Path = path.strip_prefix(dir).ok()?;
+    Some(relative.to_string_lossy().to_string())
+}
+
+/// Return the closest ancestor of the path (or the path itself) which contains a `.git` (the root
+/// of a repository or of a worktree).
+fn project_root(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .find(|ancestor| ancestor.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+/// Return the path with the bytes that are not allowed in the path of a URL percent-encoded.
+fn percent_encode(path: &Path) -> String {
+    let mut encoded = String::new();
+    for byte in path.as_os_str().as_bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                encoded.push(*byte as char)
+            }
+            byte => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Return the string in single quotes (which a shell doesn't expand anything in).
+fn shell_quote(string: &str) -> String {
+    format!("'{}'", string.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(PathFormat::Name, "/home/me/foo.rs", false, Some("foo.rs"); "name")]
+    #[test_case(PathFormat::Absolute, "/home/me/src", true, Some("/home/me/src/"); "absolute dir")]
+    #[test_case(PathFormat::Home, "/home/me/foo.rs", false, Some("~/foo.rs"); "home")]
+    #[test_case(PathFormat::Home, "/etc/hosts", false, Some("/etc/hosts"); "outside of home")]
+    #[test_case(PathFormat::Url, "/home/me/a b%.rs", false, Some("file:///home/me/a%20b%25.rs"); "url")]
+    #[test_case(PathFormat::Shell, "/home/me/it's", false, Some(r"'/home/me/it'\''s'"); "shell")]
+    fn test_format(format: PathFormat, path: &str, is_dir: bool, expected: Option<&str>) {
+        let home = Path::new("/home/me");
+        assert_eq!(
+            format
+                .format(Path::new(path), is_dir, Some(home))
+                .as_deref(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_keys_are_unique() {
+        for format in PathFormat::ALL {
+            assert_eq!(PathFormat::from_key(format.key()), Some(format));
+        }
+    }
+}
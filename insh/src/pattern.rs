@@ -0,0 +1,89 @@
+/*!
+This module contains the [`PatternState`] enum which is used to validate patterns as they are typed
+and the function [`glob_to_regex`] for converting simple glob patterns to regexes.
+*/
+use regex::Regex;
+
+/// The state of a pattern that is being typed.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum PatternState {
+    /// The pattern has not been compiled yet.
+    #[default]
+    NotCompiled,
+    /// The pattern is a valid regex.
+    Valid,
+    /// The pattern is not a valid regex.
+    Invalid {
+        /// A message describing why the pattern is invalid.
+        error: String,
+    },
+}
+
+impl PatternState {
+    /// Return the state of the `regex` pattern.
+    pub fn new(regex: &str) -> Self {
+        match Regex::new(regex) {
+            Ok(_) => Self::Valid,
+            Err(error) => {
+                // The error messages from the regex crate repeat the pattern and point to the
+                // problem over multiple lines, so only the last line (the description) is kept.
+                let error: String = error.to_string();
+                let description: &str = error
+                    .lines()
+                    .rev()
+                    .find(|line| !line.trim().is_empty())
+                    .unwrap_or_default();
+                Self::Invalid {
+                    error: description.trim().to_string(),
+                }
+            }
+        }
+    }
+
+    /// Return if the pattern is invalid.
+    pub fn is_invalid(&self) -> bool {
+        matches!(self, Self::Invalid { .. })
+    }
+}
+
+/// Return the regex for a simple glob pattern.
+///
+/// A `*` matches any number of characters, a `?` matches a single character, and all other
+/// characters match themselves.
+pub fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    for character in glob.chars() {
+        match character {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            _ => regex.push_str(&regex::escape(&character.to_string())),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("", PatternState::Valid; "empty pattern")]
+    #[test_case("foo.*", PatternState::Valid; "valid pattern")]
+    #[test_case("(foo", PatternState::Invalid { error: String::from("error: unclosed group") }; "unclosed group")]
+    fn test_pattern_state_new(regex: &str, expected_pattern_state: PatternState) {
+        let pattern_state = PatternState::new(regex);
+
+        assert_eq!(pattern_state, expected_pattern_state);
+    }
+
+    #[test_case("", "^$"; "empty glob")]
+    #[test_case("*.rs", r"^.*\.rs$"; "star")]
+    #[test_case("foo?.txt", r"^foo.\.txt$"; "question mark")]
+    #[test_case("a+b(c)", r"^a\+b\(c\)$"; "special characters are escaped")]
+    fn test_glob_to_regex(glob: &str, expected_regex: &str) {
+        let regex: String = glob_to_regex(glob);
+
+        assert_eq!(regex, expected_regex);
+    }
+}
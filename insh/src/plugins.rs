@@ -0,0 +1,250 @@
+/*!
+This module contains [`discover`] for finding the commands that plugins advertise.
+
+A plugin is an executable that is listed in the `plugins` option of the config. Running it with the
+argument `describe` prints a JSON object advertising its commands, for example:
+
+```json
+{"commands": [{"name": "compress", "description": "Compress the selected file"}]}
+```
+
+Running it with the arguments `run <name>` runs a command (in the terminal) with the context (the
+current directory and the selection) in the environment variables `INSH_CONTEXT` (as JSON),
+`INSH_DIR`, and `INSH_SELECTION`. A command which is advertised with `"background": true` (like
+`git fetch` or a build) is run in the background instead, without a terminal and without leaving
+Insh, and a message is shown when it finishes.
+*/
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// How long a plugin has to advertise its commands before it is killed.
+const DESCRIBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A command that a plugin advertises.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PluginCommand {
+    /// The path of the plugin.
+    #[serde(skip)]
+    plugin: PathBuf,
+    /// The name of the command (which is passed to the plugin to run the command).
+    name: String,
+    /// A description of the command.
+    #[serde(default)]
+    description: String,
+    /// Whether the command is run in the background (instead of in the terminal).
+    #[serde(default)]
+    background: bool,
+}
+
+impl PluginCommand {
+    /// Return a command that is built into Insh (which has no plugin).
+    pub fn builtin(name: &str, description: &str) -> Self {
+        Self {
+            plugin: PathBuf::new(),
+            name: name.to_string(),
+            description: description.to_string(),
+            background: false,
+        }
+    }
+
+    /// Return whether the command is built into Insh (rather than advertised by a plugin).
+    pub fn is_builtin(&self) -> bool {
+        self.plugin.as_os_str().is_empty()
+    }
+
+    /// Return the path of the plugin.
+    pub fn plugin(&self) -> &Path {
+        &self.plugin
+    }
+
+    /// Return the name of the command.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return the description of the command.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Return whether the command is run in the background.
+    pub fn background(&self) -> bool {
+        self.background
+    }
+}
+
+/// The output of a plugin that is run with the argument `describe`.
+#[derive(Deserialize)]
+struct Advertisement {
+    commands: Vec<PluginCommand>,
+}
+
+/// The context that a command is run in.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct PluginContext {
+    /// The directory being browsed.
+    dir: PathBuf,
+    /// The selected entry (if there is one).
+    selection: Option<PathBuf>,
+}
+
+impl PluginContext {
+    /// Return a new context.
+    pub fn new(dir: PathBuf, selection: Option<PathBuf>) -> Self {
+        Self { dir, selection }
+    }
+
+    /// Return the directory being browsed.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Return the selected entry (if there is one).
+    pub fn selection(&self) -> Option<&Path> {
+        self.selection.as_deref()
+    }
+
+    /// Return the context as JSON.
+    pub fn to_json(&self) -> String {
+        // NOTE: Serializing the context can't fail because it only contains paths and options.
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+/// Return the commands that the plugins advertise along with an error message for each plugin that
+/// failed to advertise its commands.
+pub fn discover(plugins: &[PathBuf]) -> (Vec<PluginCommand>, Vec<String>) {
+    let mut commands: Vec<PluginCommand> = Vec::new();
+    let mut errors: Vec<String> = Vec::new();
+    for plugin in plugins {
+        match describe(plugin) {
+            Ok(plugin_commands) => commands.extend(plugin_commands),
+            Err(error) => errors.push(format!("{}: {}", plugin.to_string_lossy(), error)),
+        }
+    }
+    (commands, errors)
+}
+
+/// Run the plugin with the argument `describe` and return the commands it advertises.
+fn describe(plugin: &Path) -> Result<Vec<PluginCommand>, String> {
+    let mut child = Command::new(plugin)
+        .arg("describe")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|error| error.to_string())?;
+
+    // Read the output in another thread so that the plugin can't block on a full pipe.
+    let mut stdout = child.stdout.take().unwrap();
+    let reader = thread::spawn(move || {
+        let mut output = String::new();
+        stdout.read_to_string(&mut output).map(|_| output)
+    });
+
+    let start = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if start.elapsed() < DESCRIBE_TIMEOUT => {
+                thread::sleep(Duration::from_millis(10));
+            }
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(String::from("timed out advertising its commands"));
+            }
+            Err(error) => return Err(error.to_string()),
+        }
+    };
+    if !status.success() {
+        return Err(format!("exited with {}", status));
+    }
+
+    let output: String = match reader.join() {
+        Ok(Ok(output)) => output,
+        Ok(Err(error)) => return Err(error.to_string()),
+        Err(_) => return Err(String::from("failed to read its output")),
+    };
+    parse_advertisement(plugin, &output).map_err(|error| error.to_string())
+}
+
+/// Return the commands in the advertisement that the plugin printed.
+fn parse_advertisement(
+    plugin: &Path,
+    output: &str,
+) -> Result<Vec<PluginCommand>, serde_json::Error> {
+    let advertisement: Advertisement = serde_json::from_str(output)?;
+    Ok(advertisement
+        .commands
+        .into_iter()
+        .map(|command| PluginCommand {
+            plugin: plugin.to_path_buf(),
+            ..command
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    #[test_case(r#"{"commands": []}"#, Some(vec![]); "no commands")]
+    #[test_case(
+        r#"{"commands": [{"name": "a", "description": "Do a."}, {"name": "b"}]}"#,
+        Some(vec![("a", "Do a.", false), ("b", "", false)]);
+        "commands"
+    )]
+    #[test_case(
+        r#"{"commands": [{"name": "fetch", "background": true}]}"#,
+        Some(vec![("fetch", "", true)]);
+        "background command"
+    )]
+    #[test_case(r#"{"commands": [{"description": "Do a."}]}"#, None; "no name")]
+    #[test_case("nope", None; "not json")]
+    fn test_parse_advertisement(output: &str, expected: Option<Vec<(&str, &str, bool)>>) {
+        let plugin = Path::new("/plugins/foo");
+        let expected: Option<Vec<PluginCommand>> = expected.map(|commands| {
+            commands
+                .into_iter()
+                .map(|(name, description, background)| PluginCommand {
+                    plugin: plugin.to_path_buf(),
+                    name: name.to_string(),
+                    description: description.to_string(),
+                    background,
+                })
+                .collect()
+        });
+
+        assert_eq!(parse_advertisement(plugin, output).ok(), expected);
+    }
+
+    #[test]
+    fn test_discover_missing_plugin() {
+        let (commands, errors) = discover(&[PathBuf::from("/nonexistent/plugin")]);
+
+        assert!(commands.is_empty());
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test_case(
+        PluginContext::new(PathBuf::from("/foo"), None),
+        r#"{"dir":"/foo","selection":null}"#;
+        "no selection"
+    )]
+    #[test_case(
+        PluginContext::new(PathBuf::from("/foo"), Some(PathBuf::from("/foo/bar"))),
+        r#"{"dir":"/foo","selection":"/foo/bar"}"#;
+        "selection"
+    )]
+    fn test_context_to_json(context: PluginContext, expected: &str) {
+        assert_eq!(context.to_json(), expected);
+    }
+}
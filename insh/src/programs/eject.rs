@@ -0,0 +1,74 @@
+/*!
+Contains the [`Program`] [`Eject`].
+*/
+use std::ffi::OsString;
+
+use til::{Program, ProgramCleanup, ProgramSetup};
+
+use crate::devices::Device;
+
+/// The shell command for ejecting a device (where `$1` is the device and `$2` is its mount point).
+///
+/// The drive is powered off after it is unmounted if it can be (so that it can be unplugged).
+#[cfg(not(target_os = "macos"))]
+const EJECT: &str =
+    r#"udisksctl unmount -b "$1" && { udisksctl power-off -b "$1" 2>/dev/null || true; }"#;
+
+/// The shell command for ejecting a device (where `$1` is the device and `$2` is its mount point).
+#[cfg(target_os = "macos")]
+const EJECT: &str = r#"diskutil eject "$2""#;
+
+/// A program for ejecting removable media.
+///
+/// If ejecting fails, then its output is kept on the screen until enter is pressed.
+pub struct Eject {
+    /// The device to eject.
+    device: Device,
+}
+
+impl Eject {
+    /// Return a new program for ejecting the device.
+    pub fn new(device: Device) -> Self {
+        Self { device }
+    }
+}
+
+impl Program for Eject {
+    fn setup(&self) -> ProgramSetup {
+        ProgramSetup {
+            clear_screen: true,
+            cursor_home: true,
+            cursor_visible: Some(true),
+        }
+    }
+
+    fn cleanup(&self) -> ProgramCleanup {
+        ProgramCleanup {
+            hide_cursor: true,
+            enable_raw_terminal: true,
+        }
+    }
+
+    fn filename(&self) -> OsString {
+        "sh".into()
+    }
+
+    fn args(&self) -> Vec<OsString> {
+        vec![
+            "-c".into(),
+            format!(
+                "{} || {{ status=$?; printf '\\nPress enter to continue.'; read _; exit $status; }}",
+                EJECT
+            )
+            .into(),
+            // NOTE: This is `$0` for the command.
+            "sh".into(),
+            self.device.source().into(),
+            self.device.mount_point().into(),
+        ]
+    }
+
+    fn capture_stderr(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,65 @@
+/*!
+Contains the [`Program`] [`Escalated`].
+*/
+use std::ffi::OsString;
+
+use til::{Program, ProgramCleanup, ProgramSetup};
+
+use crate::escalation::Escalation;
+
+/// A program for retrying an operation with the escalation command (such as `sudo`).
+///
+/// The escalation command is a shell command which is passed the command of the operation as its
+/// arguments (`"$@"`).
+pub struct Escalated {
+    /// The shell command for escalating privileges.
+    command: String,
+    /// The operation to retry.
+    escalation: Escalation,
+}
+
+impl Escalated {
+    /// Return a new escalated program.
+    pub fn new(command: String, escalation: Escalation) -> Self {
+        Self {
+            command,
+            escalation,
+        }
+    }
+}
+
+impl Program for Escalated {
+    fn setup(&self) -> ProgramSetup {
+        ProgramSetup {
+            clear_screen: true,
+            cursor_home: true,
+            cursor_visible: Some(true),
+        }
+    }
+
+    fn cleanup(&self) -> ProgramCleanup {
+        ProgramCleanup {
+            hide_cursor: true,
+            enable_raw_terminal: true,
+        }
+    }
+
+    fn filename(&self) -> OsString {
+        "sh".into()
+    }
+
+    fn args(&self) -> Vec<OsString> {
+        let mut args: Vec<OsString> = vec![
+            "-c".into(),
+            format!("{} \"$@\"", self.command).into(),
+            // NOTE: This is `$0` for the command.
+            "sh".into(),
+        ];
+        args.extend(self.escalation.args().iter().cloned());
+        args
+    }
+
+    fn capture_stderr(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,49 @@
+/*!
+Contains the [`Program`] [`ImagePreview`].
+*/
+use std::env;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use til::{Program, ProgramCleanup, ProgramSetup};
+
+/// A program for previewing an image (by running the hidden `preview-image` command of Insh).
+pub struct ImagePreview {
+    /// The path of the image.
+    path: PathBuf,
+}
+
+impl ImagePreview {
+    /// Return a new image preview program.
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl Program for ImagePreview {
+    fn setup(&self) -> ProgramSetup {
+        ProgramSetup {
+            clear_screen: true,
+            cursor_home: true,
+            ..Default::default()
+        }
+    }
+
+    fn cleanup(&self) -> ProgramCleanup {
+        ProgramCleanup {
+            hide_cursor: true,
+            ..Default::default()
+        }
+    }
+
+    fn filename(&self) -> OsString {
+        match env::current_exe() {
+            Ok(path) => path.into(),
+            Err(_) => "insh".into(),
+        }
+    }
+
+    fn args(&self) -> Vec<OsString> {
+        vec!["preview-image".into(), self.path.clone().into()]
+    }
+}
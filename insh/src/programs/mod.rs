@@ -2,7 +2,19 @@
 [`Program`](super::program::Program) that can be run.
 */
 mod bash;
+mod eject;
+mod escalated;
+mod image_preview;
+mod mount;
+mod plugin;
+mod viewer;
 mod vim;
 
 pub use bash::Bash;
+pub use eject::Eject;
+pub use escalated::Escalated;
+pub use image_preview::ImagePreview;
+pub use mount::Mount;
+pub use plugin::Plugin;
+pub use viewer::Viewer;
 pub use vim::{Args as VimArgs, ArgsBuilder as VimArgsBuilder, Vim};
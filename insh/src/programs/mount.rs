@@ -0,0 +1,99 @@
+/*!
+Contains the [`Program`] [`Mount`].
+*/
+use std::ffi::OsString;
+
+use til::{Program, ProgramCleanup, ProgramSetup};
+
+use crate::config::{MountConfig, MountKind};
+
+/// A program for mounting (or unmounting) a network location.
+///
+/// The program runs in the terminal (so that passwords can be entered) and it returns once the
+/// location is mounted. If the command fails, then its output is kept on the screen until enter is
+/// pressed.
+pub struct Mount {
+    /// The location to mount.
+    mount: MountConfig,
+    /// Whether the location is unmounted instead.
+    unmount: bool,
+    /// The shell command for escalating privileges (which SMB shares are mounted with).
+    escalation_command: String,
+}
+
+impl Mount {
+    /// Return a new program for mounting the location.
+    pub fn new(mount: MountConfig, escalation_command: String) -> Self {
+        Self {
+            mount,
+            unmount: false,
+            escalation_command,
+        }
+    }
+
+    /// Return a new program for unmounting the location.
+    pub fn unmount(mount: MountConfig, escalation_command: String) -> Self {
+        Self {
+            mount,
+            unmount: true,
+            escalation_command,
+        }
+    }
+
+    /// Return the shell command (where `$1` is the source, `$2` is the mount point, and `$3` is
+    /// the extra options).
+    fn command(&self) -> String {
+        let command: String = match (self.mount.kind(), self.unmount) {
+            (MountKind::Sshfs, false) => String::from(r#"mkdir -p "$2" && sshfs "$1" "$2" $3"#),
+            (MountKind::Sshfs, true) => {
+                String::from(r#"fusermount -u "$2" 2>/dev/null || umount "$2""#)
+            }
+            (MountKind::Smb, false) => format!(
+                r#"mkdir -p "$2" && {} mount -t cifs "$1" "$2" $3"#,
+                self.escalation_command
+            ),
+            (MountKind::Smb, true) => format!(r#"{} umount "$2""#, self.escalation_command),
+        };
+        format!(
+            "{} || {{ status=$?; printf '\\nPress enter to continue.'; read _; exit $status; }}",
+            command
+        )
+    }
+}
+
+impl Program for Mount {
+    fn setup(&self) -> ProgramSetup {
+        ProgramSetup {
+            clear_screen: true,
+            cursor_home: true,
+            cursor_visible: Some(true),
+        }
+    }
+
+    fn cleanup(&self) -> ProgramCleanup {
+        ProgramCleanup {
+            hide_cursor: true,
+            enable_raw_terminal: true,
+        }
+    }
+
+    fn filename(&self) -> OsString {
+        "sh".into()
+    }
+
+    fn args(&self) -> Vec<OsString> {
+        vec![
+            "-c".into(),
+            self.command().into(),
+            // NOTE: This is `$0` for the command.
+            "sh".into(),
+            self.mount.source().into(),
+            self.mount.mount_point().into(),
+            self.mount.options().into(),
+        ]
+    }
+
+    fn capture_stderr(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,101 @@
+/*!
+Contains the [`Program`] [`Plugin`].
+*/
+use std::ffi::{CString, OsStr, OsString};
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+use til::{EnvVar, Program, ProgramCleanup, ProgramSetup};
+
+use crate::plugins::{PluginCommand, PluginContext};
+
+/// A program for running a command of a plugin.
+///
+/// The command runs in the terminal in the current directory. If the command fails, then its output
+/// is kept on the screen until enter is pressed. A command that runs in the background is run
+/// without the terminal (so there is nothing to keep on the screen).
+pub struct Plugin {
+    /// The command to run.
+    command: PluginCommand,
+    /// The context that the command is run in.
+    context: PluginContext,
+}
+
+impl Plugin {
+    /// Return a new program for running the command of a plugin.
+    pub fn new(command: PluginCommand, context: PluginContext) -> Self {
+        Self { command, context }
+    }
+}
+
+/// Return the environment variable (or nothing if the value contains a nul byte).
+fn env_var(name: &str, value: &OsStr) -> Option<EnvVar> {
+    Some(EnvVar {
+        name: CString::new(name).ok()?,
+        value: CString::new(value.as_bytes()).ok()?,
+    })
+}
+
+impl Program for Plugin {
+    fn setup(&self) -> ProgramSetup {
+        ProgramSetup {
+            clear_screen: true,
+            cursor_home: true,
+            cursor_visible: Some(true),
+        }
+    }
+
+    fn cleanup(&self) -> ProgramCleanup {
+        ProgramCleanup {
+            hide_cursor: true,
+            enable_raw_terminal: true,
+        }
+    }
+
+    fn filename(&self) -> OsString {
+        "sh".into()
+    }
+
+    fn args(&self) -> Vec<OsString> {
+        let script: &str = match self.command.background() {
+            true => r#"exec "$1" run "$2""#,
+            false => {
+                r#""$1" run "$2" || { status=$?; printf '\nPress enter to continue.'; read _; exit $status; }"#
+            }
+        };
+        vec![
+            "-c".into(),
+            script.into(),
+            // NOTE: This is `$0` for the command.
+            "sh".into(),
+            self.command.plugin().into(),
+            self.command.name().into(),
+        ]
+    }
+
+    fn cwd(&self) -> Option<PathBuf> {
+        Some(self.context.dir().to_path_buf())
+    }
+
+    fn env(&self) -> Vec<EnvVar> {
+        let context: String = self.context.to_json();
+        [
+            env_var("INSH_CONTEXT", OsStr::new(&context)),
+            env_var("INSH_DIR", self.context.dir().as_os_str()),
+            env_var(
+                "INSH_SELECTION",
+                self.context
+                    .selection()
+                    .map(Path::as_os_str)
+                    .unwrap_or_default(),
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    fn capture_stderr(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,56 @@
+/*!
+Contains the [`Program`] [`Viewer`].
+*/
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use til::{Program, ProgramCleanup, ProgramSetup};
+
+/// A program for viewing files that should not be opened in the editor (such as large or binary
+/// files).
+///
+/// The viewer is a shell command which is passed the path of the file as `$1`.
+pub struct Viewer {
+    /// The shell command for viewing the file.
+    command: String,
+    /// The path of the file to view.
+    path: PathBuf,
+}
+
+impl Viewer {
+    /// Return a new viewer program.
+    pub fn new(command: String, path: PathBuf) -> Self {
+        Self { command, path }
+    }
+}
+
+impl Program for Viewer {
+    fn setup(&self) -> ProgramSetup {
+        ProgramSetup {
+            clear_screen: true,
+            cursor_home: true,
+            ..Default::default()
+        }
+    }
+
+    fn cleanup(&self) -> ProgramCleanup {
+        ProgramCleanup {
+            hide_cursor: true,
+            ..Default::default()
+        }
+    }
+
+    fn filename(&self) -> OsString {
+        "sh".into()
+    }
+
+    fn args(&self) -> Vec<OsString> {
+        vec![
+            "-c".into(),
+            self.command.clone().into(),
+            // NOTE: This is `$0` for the command.
+            "sh".into(),
+            self.path.clone().into(),
+        ]
+    }
+}
@@ -46,6 +46,15 @@ impl Program for Vim {
             args.push(path.clone().into());
         }
 
+        if let Some(quickfix) = self.args.quickfix() {
+            args.push("-q".into());
+            args.push(quickfix.clone().into());
+        }
+
+        if self.args.read_only() {
+            args.push("-R".into());
+        }
+
         if let Some(line) = self.args.line() {
             args.push(format!("+{}", line).into());
         }
@@ -77,6 +86,10 @@ pub struct Args {
     line: Option<usize>,
     /// The starting column number.
     column: Option<usize>,
+    /// The path of a file with locations to load as the quickfix list.
+    quickfix: Option<PathBuf>,
+    /// Whether the file is opened read-only.
+    read_only: bool,
 }
 
 impl Args {
@@ -94,6 +107,24 @@ impl Args {
     pub fn column(&self) -> Option<usize> {
         self.column
     }
+
+    /// Return the path of the file with locations to load as the quickfix list.
+    pub fn quickfix(&self) -> &Option<PathBuf> {
+        &self.quickfix
+    }
+
+    /// Return whether the file is opened read-only.
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Return the arguments with the file opened read-only.
+    pub fn into_read_only(self) -> Self {
+        Self {
+            read_only: true,
+            ..self
+        }
+    }
 }
 
 /// A builder for `vim` [`Args`].
@@ -105,6 +136,10 @@ pub struct ArgsBuilder {
     line: Option<usize>,
     /// The starting column number.
     column: Option<usize>,
+    /// The path of a file with locations to load as the quickfix list.
+    quickfix: Option<PathBuf>,
+    /// Whether the file is opened read-only.
+    read_only: bool,
 }
 
 impl ArgsBuilder {
@@ -133,12 +168,27 @@ impl ArgsBuilder {
         self
     }
 
+    /// Set the path of a file with locations (in the form `file:line:column:text`) to load as the
+    /// quickfix list (`vim` starts at the first location).
+    pub fn quickfix(mut self, quickfix: &Path) -> Self {
+        self.quickfix = Some(quickfix.to_path_buf());
+        self
+    }
+
+    /// Set whether the file is opened read-only.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
     /// Return arguments for running `vim`.
     pub fn build(&self) -> Args {
         Args {
             path: self.path.clone(),
             line: self.line,
             column: self.column,
+            quickfix: self.quickfix.clone(),
+            read_only: self.read_only,
         }
     }
 }
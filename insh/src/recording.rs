@@ -0,0 +1,324 @@
+/*!
+This module contains the [`FileRecorder`] which records the events handled by insh to a file and the
+[`FileReplayer`] which replays them (so that bugs can be reproduced).
+
+A recording starts with the directory that insh started in followed by the terminal events, the
+responses, and the requests in the order that they happened (each with the time since the start of
+the recording).
+
+The uuids of requests are random, so a recorded response is replayed as the response to the request
+made at the same point of the replay (the replay waits for the request to be made).
+*/
+use std::collections::HashMap;
+use std::fmt::{Display, Error as FmtError, Formatter};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam::channel::{Receiver, RecvTimeoutError, Sender};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use insh_api::{Request, Response};
+use rend::Size;
+use term::TermEvent;
+use til::{Event, Recorder, Replayer, Requester};
+
+/// How long to wait for the request that a recorded response responds to be made (after which the
+/// replay continues anyway).
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// An entry of a recording.
+#[derive(Debug, Serialize, Deserialize)]
+enum Record<R> {
+    /// The start of the recording.
+    Start { dir: PathBuf },
+    /// A key was pressed (stored as the bytes that were read from the terminal).
+    Key { elapsed: Duration, bytes: Vec<u8> },
+    /// The terminal was resized.
+    Resize {
+        elapsed: Duration,
+        rows: usize,
+        columns: usize,
+    },
+    /// A response was received.
+    Response { elapsed: Duration, response: R },
+    /// A request was made.
+    Request { elapsed: Duration, uuid: Uuid },
+}
+
+/// Records the events handled by insh to a file.
+pub struct FileRecorder {
+    writer: BufWriter<File>,
+    start: Instant,
+}
+
+impl FileRecorder {
+    /// Create the recording file at `path` for a session that starts in the directory `dir`.
+    pub fn create(path: &Path, dir: &Path) -> Result<Self, RecordingError> {
+        let file: File = File::create(path).map_err(|error| RecordingError::Open {
+            path: path.to_path_buf(),
+            error: error.to_string(),
+        })?;
+        let mut recorder = Self {
+            writer: BufWriter::new(file),
+            start: Instant::now(),
+        };
+        recorder.write(&Record::<&Response>::Start {
+            dir: dir.to_path_buf(),
+        });
+        Ok(recorder)
+    }
+
+    /// Write a record (and flush it so that it is not lost if insh crashes).
+    fn write(&mut self, record: &Record<&Response>) {
+        #[allow(unused_variables)]
+        if let Err(error) = bincode::serialize_into(&mut self.writer, record) {
+            #[cfg(feature = "logging")]
+            log::error!("Failed to record: {}", error);
+        }
+        let _ = self.writer.flush();
+    }
+}
+
+impl Recorder<Request, Response> for FileRecorder {
+    fn record_event(&mut self, event: &Event<Response>) {
+        let elapsed: Duration = self.start.elapsed();
+        let record: Record<&Response> = match event {
+            Event::TermEvent(TermEvent::KeyEvent(key_event)) => {
+                let bytes: Vec<u8> = match key_event.try_into() {
+                    Ok(bytes) => bytes,
+                    #[allow(unused_variables)]
+                    Err(error) => {
+                        #[cfg(feature = "logging")]
+                        log::warn!("Failed to record a key event: {}", error);
+                        return;
+                    }
+                };
+                Record::Key { elapsed, bytes }
+            }
+            Event::TermEvent(TermEvent::Resize(size)) => Record::Resize {
+                elapsed,
+                rows: size.rows,
+                columns: size.columns,
+            },
+            Event::Response(response) => Record::Response { elapsed, response },
+            // NOTE: Focus changes are not recorded.
+            Event::TermEvent(TermEvent::FocusGained | TermEvent::FocusLost) => return,
+        };
+        self.write(&record);
+    }
+
+    fn record_request(&mut self, request: &Request) {
+        let elapsed: Duration = self.start.elapsed();
+        self.write(&Record::Request {
+            elapsed,
+            uuid: *request.uuid(),
+        });
+    }
+}
+
+/// Replays the events of a recording.
+pub struct FileReplayer {
+    reader: BufReader<File>,
+    /// The uuids of the requests made during the replay (in order).
+    request_rx: Receiver<Uuid>,
+}
+
+impl FileReplayer {
+    /// Open the recording at `path` and return the replayer, the directory that the recording
+    /// started in, and the requester that tells the replayer which requests are made.
+    pub fn open(path: &Path) -> Result<(Self, PathBuf, ReplayRequester), RecordingError> {
+        let file: File = File::open(path).map_err(|error| RecordingError::Open {
+            path: path.to_path_buf(),
+            error: error.to_string(),
+        })?;
+        let mut reader = BufReader::new(file);
+        let dir: PathBuf = match bincode::deserialize_from(&mut reader) {
+            Ok(Record::<Response>::Start { dir }) => dir,
+            _ => {
+                return Err(RecordingError::Invalid {
+                    path: path.to_path_buf(),
+                })
+            }
+        };
+
+        let (request_tx, request_rx) = crossbeam::channel::unbounded();
+        let replayer = Self { reader, request_rx };
+        Ok((replayer, dir, ReplayRequester { request_tx }))
+    }
+}
+
+impl Replayer<Response> for FileReplayer {
+    fn run(&mut self, event_tx: Sender<Event<Response>>) {
+        let start = Instant::now();
+        // The uuids of the recorded requests and the requests made during the replay.
+        let mut uuids: HashMap<Uuid, Uuid> = HashMap::new();
+
+        while let Ok(record) = bincode::deserialize_from(&mut self.reader) {
+            let record: Record<Response> = record;
+            let elapsed: Duration = match &record {
+                Record::Start { .. } => continue,
+                Record::Key { elapsed, .. }
+                | Record::Resize { elapsed, .. }
+                | Record::Response { elapsed, .. }
+                | Record::Request { elapsed, .. } => *elapsed,
+            };
+            if let Some(wait) = elapsed.checked_sub(start.elapsed()) {
+                thread::sleep(wait);
+            }
+
+            let event: Event<Response> = match record {
+                Record::Start { .. } => continue,
+                Record::Key { bytes, .. } => match TermEvent::try_from(&bytes[..]) {
+                    Ok(term_event) => Event::TermEvent(term_event),
+                    Err(_) => continue,
+                },
+                Record::Resize { rows, columns, .. } => {
+                    Event::TermEvent(TermEvent::Resize(Size::new(rows, columns)))
+                }
+                Record::Response { mut response, .. } => {
+                    if let Some(uuid) = uuids.get(response.uuid()) {
+                        response.set_uuid(*uuid);
+                    }
+                    Event::Response(response)
+                }
+                Record::Request { uuid, .. } => {
+                    match self.request_rx.recv_timeout(REQUEST_TIMEOUT) {
+                        Ok(replayed_uuid) => {
+                            uuids.insert(uuid, replayed_uuid);
+                        }
+                        Err(RecvTimeoutError::Timeout) => {
+                            #[cfg(feature = "logging")]
+                            log::warn!("Request {} was not made during the replay.", uuid);
+                        }
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    }
+                    continue;
+                }
+            };
+
+            if event_tx.send(event).is_err() {
+                break;
+            }
+        }
+
+        #[cfg(feature = "logging")]
+        log::info!("Replayer stopping...");
+    }
+}
+
+/// Tells the replayer about the requests made during a replay (instead of sending them to inshd).
+pub struct ReplayRequester {
+    request_tx: Sender<Uuid>,
+}
+
+impl Requester<Request> for ReplayRequester {
+    fn run(&mut self, request_rx: Receiver<Request>) {
+        while let Ok(request) = request_rx.recv() {
+            if self.request_tx.send(*request.uuid()).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+/// An error opening a recording.
+#[derive(Debug)]
+pub enum RecordingError {
+    Open { path: PathBuf, error: String },
+    Invalid { path: PathBuf },
+}
+
+impl Display for RecordingError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::Open { path, error } => {
+                write!(formatter, "Failed to open {}: {}", path.display(), error)
+            }
+            Self::Invalid { path } => {
+                write!(formatter, "{} is not a recording.", path.display())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insh_api::{ResponseParams, TouchFileResponseParams};
+    use std::time::SystemTime;
+    use term::{Key, KeyEvent, KeyMods};
+
+    #[test]
+    fn test_record_and_replay() {
+        let path: PathBuf =
+            std::env::temp_dir().join(format!("insh-test-{}.recording", Uuid::new_v4()));
+        let request = Request::builder()
+            .params(insh_api::RequestParams::GetFiles(
+                insh_api::GetFilesRequestParams::builder()
+                    .dir(PathBuf::from("/"))
+                    .build(),
+            ))
+            .build();
+        let response = Response::builder()
+            .uuid(*request.uuid())
+            .params(ResponseParams::TouchFile(
+                TouchFileResponseParams::builder()
+                    .path(PathBuf::from("/foo"))
+                    .result(Ok(SystemTime::UNIX_EPOCH))
+                    .build(),
+            ))
+            .build();
+
+        let mut recorder = FileRecorder::create(&path, Path::new("/tmp")).unwrap();
+        recorder.record_event(&Event::TermEvent(TermEvent::KeyEvent(KeyEvent {
+            key: Key::Char('j'),
+            mods: KeyMods::NONE,
+        })));
+        recorder.record_request(&request);
+        recorder.record_event(&Event::Response(response));
+        drop(recorder);
+
+        let (mut replayer, dir, mut requester) = FileReplayer::open(&path).unwrap();
+        assert_eq!(dir, PathBuf::from("/tmp"));
+
+        // The request is made again during the replay (with a new uuid).
+        let replayed_uuid: Uuid = Uuid::new_v4();
+        let (request_tx, request_rx) = crossbeam::channel::unbounded();
+        request_tx
+            .send(
+                Request::builder()
+                    .uuid(replayed_uuid)
+                    .params(insh_api::RequestParams::GetFiles(
+                        insh_api::GetFilesRequestParams::builder()
+                            .dir(PathBuf::from("/"))
+                            .build(),
+                    ))
+                    .build(),
+            )
+            .unwrap();
+        drop(request_tx);
+        requester.run(request_rx);
+
+        let (event_tx, event_rx) = crossbeam::channel::unbounded();
+        replayer.run(event_tx);
+        let events: Vec<Event<Response>> = event_rx.iter().collect();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            &events[0],
+            Event::TermEvent(TermEvent::KeyEvent(KeyEvent {
+                key: Key::Char('j'),
+                mods: KeyMods::NONE,
+            }))
+        ));
+        match &events[1] {
+            Event::Response(response) => assert_eq!(response.uuid(), &replayed_uuid),
+            Event::TermEvent(_) => panic!("Expected a response."),
+        }
+    }
+}
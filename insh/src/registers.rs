@@ -0,0 +1,95 @@
+/*!
+This module contains [`Registers`] which hold text that was yanked within Insh (like the registers
+of vim), independently of the system clipboard.
+
+Text that is yanked without naming a register goes to the clipboard and to the default register
+(`"`). Text that is yanked into a named register (`a` to `z`, chosen by pressing `"` and the name
+before yanking) only goes to that register and to the default register.
+*/
+use std::collections::BTreeMap;
+
+/// The name of the default register.
+pub const DEFAULT: char = '"';
+
+/// The maximum number of characters of the text of a register that is shown in a preview.
+const PREVIEW_LENGTH: usize = 24;
+
+/// The registers (keyed by their names).
+#[derive(Debug, Default)]
+pub struct Registers {
+    contents: BTreeMap<char, String>,
+}
+
+impl Registers {
+    /// Return whether the character is the name of a register.
+    pub fn is_name(name: char) -> bool {
+        name == DEFAULT || name.is_ascii_lowercase()
+    }
+
+    /// Return the text in the register.
+    pub fn get(&self, name: char) -> Option<&str> {
+        self.contents.get(&name).map(String::as_str)
+    }
+
+    /// Put the text in the register (and in the default register).
+    pub fn set(&mut self, name: char, text: String) {
+        if name != DEFAULT {
+            self.contents.insert(DEFAULT, text.clone());
+        }
+        self.contents.insert(name, text);
+    }
+
+    /// Return a line which lists the registers that hold text (with the start of their text).
+    pub fn preview(&self) -> String {
+        let previews: Vec<String> = self
+            .contents
+            .iter()
+            .map(|(name, text)| {
+                let mut preview: String = text.lines().next().unwrap_or_default().to_string();
+                if let Some((index, _)) = preview.char_indices().nth(PREVIEW_LENGTH) {
+                    preview.truncate(index);
+                    preview.push('…');
+                }
+                format!("{}:{}", name, preview)
+            })
+            .collect();
+        previews.join("  ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case('"', true; "default")]
+    #[test_case('a', true; "named")]
+    #[test_case('A', false; "upper case")]
+    #[test_case('1', false; "digit")]
+    fn test_is_name(name: char, expected: bool) {
+        assert_eq!(Registers::is_name(name), expected);
+    }
+
+    #[test]
+    fn test_set() {
+        let mut registers = Registers::default();
+        registers.set('a', "foo".to_string());
+        registers.set(DEFAULT, "bar".to_string());
+
+        assert_eq!(registers.get('a'), Some("foo"));
+        assert_eq!(registers.get(DEFAULT), Some("bar"));
+        assert_eq!(registers.get('b'), None);
+    }
+
+    #[test]
+    fn test_preview() {
+        let mut registers = Registers::default();
+        registers.set('b', "a long line of text to yank\nand another".to_string());
+        registers.set('a', "foo".to_string());
+
+        assert_eq!(
+            registers.preview(),
+            "\":foo  a:foo  b:a long line of text to y…"
+        );
+    }
+}
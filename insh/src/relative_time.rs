@@ -0,0 +1,67 @@
+/*!
+This module contains the function [`relative_time`] which formats a time relative to now (such as
+`5 minutes ago`) for showing when files were modified.
+*/
+use std::time::{Duration, SystemTime};
+
+/// The units of time (with their length in seconds) from largest to smallest.
+const UNITS: [(&str, u64); 6] = [
+    ("year", 365 * 24 * 60 * 60),
+    ("month", 30 * 24 * 60 * 60),
+    ("week", 7 * 24 * 60 * 60),
+    ("day", 24 * 60 * 60),
+    ("hour", 60 * 60),
+    ("minute", 60),
+];
+
+/// Return the time relative to `now` in the largest whole unit (for example `3 days ago`).
+///
+/// Times less than a minute ago are `just now` and times after `now` are `in the future`.
+pub fn relative_time(time: SystemTime, now: SystemTime) -> String {
+    let elapsed: Duration = match now.duration_since(time) {
+        Ok(elapsed) => elapsed,
+        // Allow for a little clock skew between the daemon and the client.
+        Err(error) if error.duration() < Duration::from_secs(1) => Duration::ZERO,
+        Err(_) => return "in the future".to_string(),
+    };
+
+    let seconds: u64 = elapsed.as_secs();
+    for (unit, unit_seconds) in UNITS {
+        let count: u64 = seconds / unit_seconds;
+        if count > 0 {
+            let plural: &str = if count == 1 { "" } else { "s" };
+            return format!("{} {}{} ago", count, unit, plural);
+        }
+    }
+    "just now".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(0, "just now"; "now")]
+    #[test_case(59, "just now"; "seconds")]
+    #[test_case(60, "1 minute ago"; "one minute")]
+    #[test_case(2 * 60 * 60 + 5, "2 hours ago"; "hours")]
+    #[test_case(8 * 24 * 60 * 60, "1 week ago"; "week")]
+    #[test_case(400 * 24 * 60 * 60, "1 year ago"; "year")]
+    fn test_relative_time(seconds_ago: u64, expected_string: &str) {
+        let now: SystemTime = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        let time: SystemTime = now - Duration::from_secs(seconds_ago);
+
+        let string: String = relative_time(time, now);
+
+        assert_eq!(string, expected_string);
+    }
+
+    #[test]
+    fn test_relative_time_in_the_future() {
+        let now: SystemTime = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+
+        let string: String = relative_time(now + Duration::from_secs(60), now);
+
+        assert_eq!(string, "in the future");
+    }
+}
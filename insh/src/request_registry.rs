@@ -0,0 +1,377 @@
+/*!
+This module contains [`RequestRegistry`] which keeps track of the requests that were sent to the
+daemon and haven't gotten their last response yet: which component sent each request (so that the
+responses to it go to that component) and what kind of request it is.
+
+A response is only routed if it is of the kind of its request, and the responses that come after
+the last response to a request are dropped. A request can replace the requests of the same kind
+that its target sent before (so that only the responses to the latest one are routed, like for the
+listing of a directory). A request that hasn't been responded to for too long is assumed to be lost
+and is swept (so the responses that might still come are dropped too, and its target is told).
+
+The requests are swept on the ticks of the app (not when another response arrives).
+*/
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use insh_api::{RequestKind, Response, MAX_WAIT_FOR_DEVICES};
+use uuid::Uuid;
+
+/// How long a request can go without a response before it is assumed to be lost.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The number of finished requests that are remembered (so that a duplicate response to one is
+/// recognized).
+const FINISHED_CAPACITY: usize = 256;
+
+/// A request that was sent and hasn't gotten its last response yet.
+#[derive(Debug, Clone, Copy)]
+struct Pending<Target> {
+    /// What sent the request.
+    target: Target,
+    kind: RequestKind,
+    /// When the request was sent or last responded to.
+    heard: Instant,
+}
+
+/// What to do with a response.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Resolution<Target> {
+    /// The response goes to the target (which sent the request).
+    Route(Target),
+    /// The request already got its last response.
+    Duplicate,
+    /// The response is of another kind than the request.
+    Mismatched,
+    /// The request isn't pending (it was cancelled, replaced, or swept).
+    Unknown,
+}
+
+/// The requests that are pending (keyed by their uuids).
+pub struct RequestRegistry<Target> {
+    pending: HashMap<Uuid, Pending<Target>>,
+    /// The requests that got their last response (the oldest first).
+    finished: VecDeque<Uuid>,
+}
+
+impl<Target> Default for RequestRegistry<Target> {
+    fn default() -> Self {
+        Self {
+            pending: HashMap::new(),
+            finished: VecDeque::new(),
+        }
+    }
+}
+
+impl<Target: Copy + PartialEq> RequestRegistry<Target> {
+    /// Register the request (of the kind) that the target sent at `now`.
+    pub fn register(&mut self, uuid: Uuid, kind: RequestKind, target: Target, now: Instant) {
+        let pending = Pending {
+            target,
+            kind,
+            heard: now,
+        };
+        self.pending.insert(uuid, pending);
+    }
+
+    /// Register the request (of the kind) that the target sent at `now` in place of the pending
+    /// requests of the same kind that the target sent before. Return the uuids of the requests that
+    /// were replaced (whose responses are dropped from now on).
+    pub fn replace(
+        &mut self,
+        uuid: Uuid,
+        kind: RequestKind,
+        target: Target,
+        now: Instant,
+    ) -> Vec<Uuid> {
+        let replaced: Vec<Uuid> = self
+            .pending
+            .iter()
+            .filter(|(_, pending)| pending.target == target && pending.kind == kind)
+            .map(|(uuid, _)| *uuid)
+            .collect();
+        for uuid in &replaced {
+            self.pending.remove(uuid);
+        }
+        self.register(uuid, kind, target, now);
+        replaced
+    }
+
+    /// Return what to do with the response that came at `now` (forgetting the request if it is the
+    /// last response to it).
+    pub fn resolve(&mut self, response: &Response, now: Instant) -> Resolution<Target> {
+        let uuid: Uuid = *response.uuid();
+        let pending: &mut Pending<Target> = match self.pending.get_mut(&uuid) {
+            Some(pending) => pending,
+            None if self.finished.contains(&uuid) => return Resolution::Duplicate,
+            None => return Resolution::Unknown,
+        };
+        if response
+            .params()
+            .kind()
+            .is_some_and(|kind| kind != pending.kind)
+        {
+            return Resolution::Mismatched;
+        }
+
+        pending.heard = now;
+        let target: Target = pending.target;
+        if response.last() {
+            self.pending.remove(&uuid);
+            if self.finished.len() == FINISHED_CAPACITY {
+                self.finished.pop_front();
+            }
+            self.finished.push_back(uuid);
+        }
+        Resolution::Route(target)
+    }
+
+    /// Forget the request (when its responses are no longer wanted, so that they are dropped).
+    pub fn remove(&mut self, uuid: &Uuid) {
+        self.pending.remove(uuid);
+    }
+
+    /// Forget the requests that the target sent (when they are cancelled). Return their uuids.
+    pub fn remove_target(&mut self, target: Target) -> Vec<Uuid> {
+        let removed: Vec<Uuid> = self
+            .pending
+            .iter()
+            .filter(|(_, pending)| pending.target == target)
+            .map(|(uuid, _)| *uuid)
+            .collect();
+        for uuid in &removed {
+            self.pending.remove(uuid);
+        }
+        removed
+    }
+
+    /// Forget the requests that haven't been responded to for longer than their timeout at `now`.
+    /// Return the uuids of the requests that were swept, their kinds, and their targets (which are
+    /// told that the requests timed out).
+    pub fn sweep(&mut self, now: Instant) -> Vec<(Uuid, RequestKind, Target)> {
+        let swept: Vec<(Uuid, RequestKind, Target)> = self
+            .pending
+            .iter()
+            .filter(|(_, pending)| {
+                timeout(pending.kind)
+                    .is_some_and(|timeout| now.saturating_duration_since(pending.heard) > timeout)
+            })
+            .map(|(uuid, pending)| (*uuid, pending.kind, pending.target))
+            .collect();
+        for (uuid, _, _) in &swept {
+            self.pending.remove(uuid);
+        }
+        swept
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Return the pending requests which are file operations or searches (the requests that are
+    /// worth waiting for before quitting) and their kinds.
+    pub fn operations(&self) -> Vec<(Uuid, RequestKind)> {
+        self.pending
+            .iter()
+            .filter(|(_, pending)| is_operation(pending.kind))
+            .map(|(uuid, pending)| (*uuid, pending.kind))
+            .collect()
+    }
+}
+
+/// Return whether a request of the kind changes files or searches them.
+fn is_operation(kind: RequestKind) -> bool {
+    matches!(
+        kind,
+        RequestKind::CreateFile
+            | RequestKind::WriteFile
+            | RequestKind::TouchFile
+            | RequestKind::DeleteFile
+            | RequestKind::RenameFile
+            | RequestKind::CopyFile
+            | RequestKind::MoveFile
+            | RequestKind::FindFiles
+            | RequestKind::SearchPhrase
+    )
+}
+
+/// Return how long a request of the kind can go without a response before it is assumed to be lost
+/// (or `None` if it can take any amount of time, like finding files).
+fn timeout(kind: RequestKind) -> Option<Duration> {
+    match kind {
+        RequestKind::FindFiles
+        | RequestKind::SearchPhrase
+        | RequestKind::CopyFile
+        | RequestKind::MoveFile => None,
+        RequestKind::WaitForDevices => Some(MAX_WAIT_FOR_DEVICES + REQUEST_TIMEOUT),
+        _ => Some(REQUEST_TIMEOUT),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use insh_api::{
+        BadRequestResponseParams, GetFilesRequestParams, GetFilesResponseParams, Request,
+        RequestParams, ResponseParams, TouchFileRequestParams,
+    };
+    use test_case::test_case;
+
+    fn register(registry: &mut RequestRegistry<u8>, request: &Request, target: u8, now: Instant) {
+        registry.register(*request.uuid(), request.params().kind(), target, now);
+    }
+
+    fn replace(
+        registry: &mut RequestRegistry<u8>,
+        request: &Request,
+        target: u8,
+        now: Instant,
+    ) -> Vec<Uuid> {
+        registry.replace(*request.uuid(), request.params().kind(), target, now)
+    }
+
+    fn get_files_request() -> Request {
+        Request::builder()
+            .params(RequestParams::GetFiles(
+                GetFilesRequestParams::builder().dir("/r".into()).build(),
+            ))
+            .build()
+    }
+
+    fn touch_file_request() -> Request {
+        Request::builder()
+            .params(RequestParams::TouchFile(
+                TouchFileRequestParams::builder()
+                    .path("/r/a".into())
+                    .build(),
+            ))
+            .build()
+    }
+
+    fn get_files_response(uuid: Uuid, last: bool) -> Response {
+        Response::builder()
+            .uuid(uuid)
+            .last(last)
+            .params(ResponseParams::GetFiles(
+                GetFilesResponseParams::builder()
+                    .result(Ok(Vec::new()))
+                    .build(),
+            ))
+            .build()
+    }
+
+    #[test]
+    fn test_resolve() {
+        let mut registry: RequestRegistry<u8> = RequestRegistry::default();
+        let now = Instant::now();
+        let request: Request = get_files_request();
+        let uuid: Uuid = *request.uuid();
+        register(&mut registry, &request, 1, now);
+
+        assert_eq!(
+            registry.resolve(&get_files_response(uuid, false), now),
+            Resolution::Route(1)
+        );
+        assert_eq!(
+            registry.resolve(&get_files_response(uuid, true), now),
+            Resolution::Route(1)
+        );
+        assert_eq!(
+            registry.resolve(&get_files_response(uuid, true), now),
+            Resolution::Duplicate
+        );
+        assert_eq!(
+            registry.resolve(&get_files_response(Uuid::new_v4(), true), now),
+            Resolution::Unknown
+        );
+        assert_eq!(registry.len(), 0);
+    }
+
+    #[test]
+    fn test_resolve_mismatched() {
+        let mut registry: RequestRegistry<u8> = RequestRegistry::default();
+        let now = Instant::now();
+        let request: Request = touch_file_request();
+        let uuid: Uuid = *request.uuid();
+        register(&mut registry, &request, 1, now);
+
+        assert_eq!(
+            registry.resolve(&get_files_response(uuid, true), now),
+            Resolution::Mismatched
+        );
+        let bad_request = Response::builder()
+            .uuid(uuid)
+            .last(true)
+            .params(ResponseParams::BadRequest(
+                BadRequestResponseParams::builder()
+                    .error("bad".to_string())
+                    .build(),
+            ))
+            .build();
+        assert_eq!(registry.resolve(&bad_request, now), Resolution::Route(1));
+    }
+
+    #[test_case(1, true; "same target")]
+    #[test_case(2, false; "other target")]
+    fn test_replace(target: u8, expected_replaced: bool) {
+        let mut registry: RequestRegistry<u8> = RequestRegistry::default();
+        let now = Instant::now();
+        let first: Request = get_files_request();
+        register(&mut registry, &first, 1, now);
+        register(&mut registry, &touch_file_request(), 1, now);
+
+        let replaced: Vec<Uuid> = replace(&mut registry, &get_files_request(), target, now);
+
+        assert_eq!(replaced.contains(first.uuid()), expected_replaced);
+        assert_eq!(replaced.len(), expected_replaced as usize);
+        let resolution = registry.resolve(&get_files_response(*first.uuid(), true), now);
+        assert_eq!(resolution == Resolution::Unknown, expected_replaced);
+    }
+
+    #[test_case(60, false; "in time")]
+    #[test_case(121, true; "timed out")]
+    fn test_sweep(seconds: u64, expected_swept: bool) {
+        let mut registry: RequestRegistry<u8> = RequestRegistry::default();
+        let now = Instant::now();
+        let request: Request = get_files_request();
+        register(&mut registry, &request, 1, now);
+
+        let swept: Vec<(Uuid, RequestKind, u8)> =
+            registry.sweep(now + Duration::from_secs(seconds));
+
+        let expected: (Uuid, RequestKind, u8) = (*request.uuid(), RequestKind::GetFiles, 1);
+        assert_eq!(swept.contains(&expected), expected_swept);
+        assert_eq!(registry.len(), (!expected_swept) as usize);
+    }
+
+    #[test]
+    fn test_remove_target() {
+        let mut registry: RequestRegistry<u8> = RequestRegistry::default();
+        let now = Instant::now();
+        let request: Request = get_files_request();
+        register(&mut registry, &request, 1, now);
+        register(&mut registry, &touch_file_request(), 2, now);
+
+        assert_eq!(registry.remove_target(1), vec![*request.uuid()]);
+        assert_eq!(registry.len(), 1);
+        assert_eq!(
+            registry.resolve(&get_files_response(*request.uuid(), true), now),
+            Resolution::Unknown
+        );
+    }
+
+    #[test]
+    fn test_operations() {
+        let mut registry: RequestRegistry<u8> = RequestRegistry::default();
+        let now = Instant::now();
+        let touch_file: Request = touch_file_request();
+        register(&mut registry, &get_files_request(), 1, now);
+        register(&mut registry, &touch_file, 2, now);
+
+        assert_eq!(
+            registry.operations(),
+            vec![(*touch_file.uuid(), RequestKind::TouchFile)]
+        );
+    }
+}
@@ -7,6 +7,9 @@ use std::os::unix::net::UnixStream;
 use crossbeam::channel::Receiver;
 use typed_builder::TypedBuilder;
 
+#[cfg(feature = "logging")]
+use crate::logging::correlate;
+
 #[derive(TypedBuilder)]
 pub struct InshdRequester {
     socket: UnixStream,
@@ -30,6 +33,8 @@ impl Requester<Request> for InshdRequester {
                 }
             };
             #[cfg(feature = "logging")]
+            let _correlation = correlate(*request.uuid());
+            #[cfg(feature = "logging")]
             log::debug!("Received request {}.", request.uuid());
 
             // Serialize the request.
@@ -72,3 +77,37 @@ impl Requester<Request> for InshdRequester {
         log::info!("Requester stopping...");
     }
 }
+
+/// Send the requests with an inshd requester over a socket and return the requests that are read
+/// from the other end of the socket (like inshd reads them), so that the requests made by the
+/// components can be checked without a daemon. A request that can't make it over the socket fails
+/// the test.
+///
+/// NOTE: The requester is run on this thread (the channel is closed once the requests are queued),
+/// so the requests must fit in the buffer of the socket.
+#[cfg(test)]
+pub fn send_over_socket(requests: Vec<Request>) -> Vec<Request> {
+    use std::io::Read;
+
+    use insh_api::wire::{decode, decode_length, LENGTH_LEN};
+
+    let (socket, mut inshd) = UnixStream::pair().unwrap();
+    let (request_tx, request_rx) = crossbeam::channel::unbounded::<Request>();
+    for request in requests {
+        request_tx.send(request).unwrap();
+    }
+    drop(request_tx);
+    InshdRequester::builder()
+        .socket(socket)
+        .build()
+        .run(request_rx);
+
+    let mut received: Vec<Request> = Vec::new();
+    let mut length_buffer: [u8; LENGTH_LEN] = [0; LENGTH_LEN];
+    while inshd.read_exact(&mut length_buffer).is_ok() {
+        let mut request_buffer: Vec<u8> = vec![0; decode_length(length_buffer).unwrap()];
+        inshd.read_exact(&mut request_buffer).unwrap();
+        received.push(decode(&request_buffer).unwrap());
+    }
+    received
+}
@@ -1,3 +1,4 @@
+use insh_api::wire::{decode, decode_length};
 use insh_api::Response;
 use til::{ResponseHandler, Stopper};
 
@@ -7,8 +8,9 @@ use std::os::unix::net::UnixStream;
 
 use crossbeam::channel::Sender;
 use typed_builder::TypedBuilder;
+
 #[cfg(feature = "logging")]
-use uuid::Uuid;
+use crate::logging::correlate;
 
 #[derive(TypedBuilder)]
 pub struct InshdResponseHandler {
@@ -44,12 +46,22 @@ impl ResponseHandler<Response> for InshdResponseHandler {
                     }
                 }
             }
-            let length: u64 = u64::from_be_bytes(length_buffer);
+            let length: usize = match decode_length(length_buffer) {
+                Ok(length) => length,
+                #[allow(unused_variables)]
+                Err(error) => {
+                    #[cfg(feature = "logging")]
+                    log::error!(
+                        "Encountered an error decoding the response length: {}",
+                        error
+                    );
+                    break;
+                }
+            };
             #[cfg(feature = "logging")]
             log::debug!("The response is {} bytes long.", length);
 
             // Reserve more space in the response buffer if necessary.
-            let length: usize = length.try_into().unwrap();
             #[cfg(feature = "logging")]
             log::debug!("Checking the capacity of the response buffer...");
             let capacity: usize = response_buffer.capacity();
@@ -92,12 +104,19 @@ impl ResponseHandler<Response> for InshdResponseHandler {
             log::debug!("Read the response.");
 
             // Deserialize the response.
-            let response: Response = bincode::deserialize(&response_buffer[..length]).unwrap();
+            let response: Response = match decode(&response_buffer[..length]) {
+                Ok(response) => response,
+                #[allow(unused_variables)]
+                Err(error) => {
+                    #[cfg(feature = "logging")]
+                    log::error!("Encountered an error decoding a response: {}", error);
+                    continue;
+                }
+            };
             #[cfg(feature = "logging")]
-            {
-                let response_uuid: Uuid = response.uuid().clone();
-                log::debug!("Received response {:?}.", response_uuid);
-            }
+            let _correlation = correlate(*response.uuid());
+            #[cfg(feature = "logging")]
+            log::debug!("Received response {}.", response.uuid());
 
             // Send the response to TIL.
             if response_tx.send(response).is_err() {
@@ -0,0 +1,300 @@
+/*!
+This module contains the function [`run`] which speaks a simple line-based RPC protocol over stdin
+and stdout (used by the `--rpc` mode so that editor plugins can find files and search file
+contents).
+
+Each request is a single line of tab separated fields:
+- `find<TAB><dir><TAB><pattern>` finds the files in the directory whose name matches the pattern.
+- `search<TAB><dir><TAB><phrase>` searches the contents of the files in the directory for the
+  phrase.
+- `quit` stops the RPC mode.
+
+The responses to a request are written one per line (as soon as they are received from inshd):
+- `file<TAB><path>` for each found file.
+- `hit<TAB><path><TAB><line number><TAB><line>` for each line containing the phrase (the line
+  numbers are 1-based).
+- `error<TAB><message>` if the request could not be handled.
+- `done` after all of the other responses to the request.
+
+A tab, a newline, a carriage return, or a backslash in a directory, a path, a line, or a message is
+escaped as `\t`, `\n`, `\r`, or `\\` (any other backslash is kept as is). The pattern and the phrase
+are the last field of a request, so they are not escaped.
+*/
+use std::fmt::{Display, Error as FmtError, Formatter};
+use std::io::{self, BufRead, Error as IOError, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use path_finder::Entry;
+use phrase_searcher::FileHit;
+
+use crate::config::GeneralConfig;
+use crate::current_dir;
+use crate::expand::{expand_path, ExpandError};
+use crate::inshd_requests::{find_files, search_phrase, InshdRequestError};
+
+/// Handle requests from stdin until stdin is closed, a `quit` request is received, or stdout is
+/// closed.
+pub fn run(config: &GeneralConfig) {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout().lock();
+    for line in stdin.lock().lines() {
+        let line: String = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+        if line.is_empty() {
+            continue;
+        }
+
+        let handled: Result<(), IOError> = match RpcRequest::from_str(&line) {
+            Ok(RpcRequest::Quit) => break,
+            Ok(request) => request.handle(config, &mut stdout),
+            Err(error) => writeln!(stdout, "{}", error_response(&error)),
+        };
+        if handled.is_err() || writeln!(stdout, "done").is_err() || stdout.flush().is_err() {
+            return;
+        }
+    }
+}
+
+/// A request received over RPC.
+#[derive(Debug, PartialEq, Eq)]
+enum RpcRequest {
+    /// Find the files in a directory whose name matches a pattern.
+    Find { dir: PathBuf, pattern: String },
+    /// Search the contents of the files in a directory for a phrase.
+    Search { dir: PathBuf, phrase: String },
+    /// Stop the RPC mode.
+    Quit,
+}
+
+impl RpcRequest {
+    /// Handle the request by writing the lines of its responses to `output`. The lines for each
+    /// batch of results are written (and flushed) as soon as the batch is received from inshd, and
+    /// the request is stopped if writing them fails.
+    fn handle(self, config: &GeneralConfig, output: &mut impl Write) -> Result<(), IOError> {
+        let result: Result<(), InshdRequestError> = match self {
+            Self::Find { dir, pattern } => {
+                let dir: PathBuf = match absolute(&dir) {
+                    Ok(dir) => dir,
+                    Err(error) => return writeln!(output, "{}", error_response(&error)),
+                };
+                find_files(
+                    &config.inshd_socket(),
+                    dir,
+                    &pattern,
+                    config.same_file_system(),
+                    config.unicode_matching(),
+                    |entries| write_files(output, &entries),
+                )
+            }
+            Self::Search { dir, phrase } => {
+                let dir: PathBuf = match absolute(&dir) {
+                    Ok(dir) => dir,
+                    Err(error) => return writeln!(output, "{}", error_response(&error)),
+                };
+                search_phrase(
+                    &config.inshd_socket(),
+                    dir,
+                    &phrase,
+                    config.same_file_system(),
+                    config.unicode_matching(),
+                    |file_hits| write_hits(output, &file_hits),
+                )
+            }
+            Self::Quit => Ok(()),
+        };
+        match result {
+            Ok(()) => Ok(()),
+            Err(InshdRequestError::Handle(error)) => Err(error),
+            Err(error) => writeln!(output, "{}", error_response(&error)),
+        }
+    }
+}
+
+/// Write and flush the lines of the responses for found files.
+fn write_files(output: &mut impl Write, entries: &[Entry]) -> Result<(), IOError> {
+    for entry in entries {
+        writeln!(output, "file\t{}", escape(&entry.path().to_string_lossy()))?;
+    }
+    output.flush()
+}
+
+/// Write and flush the lines of the responses for the hits of files.
+fn write_hits(output: &mut impl Write, file_hits: &[FileHit]) -> Result<(), IOError> {
+    for file_hit in file_hits {
+        let path: String = escape(&file_hit.path().to_string_lossy());
+        for line_hit in file_hit.line_hits() {
+            writeln!(
+                output,
+                "hit\t{}\t{}\t{}",
+                path,
+                line_hit.line_number(),
+                escape(line_hit.line())
+            )?;
+        }
+    }
+    output.flush()
+}
+
+/// Return the line of the response for an error.
+fn error_response(error: &impl Display) -> String {
+    format!("error\t{}", escape(&error.to_string()))
+}
+
+/// Return the field with its tabs, newlines, carriage returns, and backslashes escaped.
+fn escape(field: &str) -> String {
+    let mut escaped = String::with_capacity(field.len());
+    for character in field.chars() {
+        match character {
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\\' => escaped.push_str("\\\\"),
+            character => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+/// Return the field with its escaped tabs, newlines, carriage returns, and backslashes unescaped.
+fn unescape(field: &str) -> String {
+    let mut unescaped = String::with_capacity(field.len());
+    let mut characters = field.chars();
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            unescaped.push(character);
+            continue;
+        }
+        match characters.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('\\') => unescaped.push('\\'),
+            Some(other) => {
+                unescaped.push('\\');
+                unescaped.push(other);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Return the directory expanded and made absolute (relative to the current directory).
+fn absolute(dir: &Path) -> Result<PathBuf, ExpandError> {
+    let dir: PathBuf = expand_path(&dir.to_string_lossy())?;
+    Ok(match dir.is_relative() {
+        true => current_dir::current_dir().join(dir),
+        false => dir,
+    })
+}
+
+impl FromStr for RpcRequest {
+    type Err = RpcRequestParseError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let mut fields = string.splitn(3, '\t');
+        let method: &str = fields.next().unwrap_or_default();
+        match method {
+            "find" | "search" => {
+                let (dir, argument) = match (fields.next(), fields.next()) {
+                    (Some(dir), Some(argument)) => {
+                        (PathBuf::from(unescape(dir)), argument.to_string())
+                    }
+                    _ => return Err(RpcRequestParseError::MissingFields(method.to_string())),
+                };
+                match method {
+                    "find" => Ok(Self::Find {
+                        dir,
+                        pattern: argument,
+                    }),
+                    _ => Ok(Self::Search {
+                        dir,
+                        phrase: argument,
+                    }),
+                }
+            }
+            "quit" => Ok(Self::Quit),
+            _ => Err(RpcRequestParseError::UnknownMethod(method.to_string())),
+        }
+    }
+}
+
+/// An error parsing a request received over RPC.
+#[derive(Debug, PartialEq, Eq)]
+enum RpcRequestParseError {
+    /// The method of the request is not known.
+    UnknownMethod(String),
+    /// The request is missing fields required by the method.
+    MissingFields(String),
+}
+
+impl Display for RpcRequestParseError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::UnknownMethod(method) => write!(formatter, "Unknown method \"{}\".", method),
+            Self::MissingFields(method) => write!(
+                formatter,
+                "The \"{}\" method requires a directory and a pattern or phrase.",
+                method
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use phrase_searcher::LineHit;
+    use test_case::test_case;
+
+    #[test_case("find\t/foo\tmain.rs", Ok(RpcRequest::Find { dir: "/foo".into(), pattern: "main.rs".into() }); "find")]
+    #[test_case("search\tfoo\tbar\tbaz", Ok(RpcRequest::Search { dir: "foo".into(), phrase: "bar\tbaz".into() }); "search with a tab in the phrase")]
+    #[test_case("find\t/a\\tb\tmain.rs", Ok(RpcRequest::Find { dir: "/a\tb".into(), pattern: "main.rs".into() }); "find in a directory with a tab")]
+    #[test_case("quit", Ok(RpcRequest::Quit); "quit")]
+    #[test_case("find\t/foo", Err(RpcRequestParseError::MissingFields("find".into())); "missing pattern")]
+    #[test_case("open\t/foo", Err(RpcRequestParseError::UnknownMethod("open".into())); "unknown method")]
+    fn test_from_str(string: &str, expected_result: Result<RpcRequest, RpcRequestParseError>) {
+        let result: Result<RpcRequest, RpcRequestParseError> = RpcRequest::from_str(string);
+
+        assert_eq!(result, expected_result);
+    }
+
+    #[test_case("/foo/bar.rs", "/foo/bar.rs"; "plain")]
+    #[test_case("/foo/a\tb", "/foo/a\\tb"; "tab")]
+    #[test_case("/foo/a\nb\r", "/foo/a\\nb\\r"; "newline and carriage return")]
+    #[test_case("C:\\foo", "C:\\\\foo"; "backslash")]
+    fn test_escape(field: &str, expected_escaped: &str) {
+        let escaped: String = escape(field);
+
+        assert_eq!(escaped, expected_escaped);
+        assert_eq!(unescape(&escaped), field);
+    }
+
+    #[test]
+    fn test_write_hits() {
+        let file_hits: Vec<FileHit> = vec![
+            FileHit::new(
+                Path::new("/r/a\tb"),
+                vec![LineHit::new(1, "foo"), LineHit::new(3, "foo\\")],
+            ),
+            FileHit::new(Path::new("/r/c"), vec![LineHit::new(2, "foo")]),
+        ];
+        let mut output: Vec<u8> = Vec::new();
+
+        write_hits(&mut output, &file_hits).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "hit\t/r/a\\tb\t1\tfoo\nhit\t/r/a\\tb\t3\tfoo\\\\\nhit\t/r/c\t2\tfoo\n"
+        );
+    }
+
+    #[test_case("/foo\\x", "/foo\\x"; "unknown escape")]
+    #[test_case("/foo\\", "/foo\\"; "trailing backslash")]
+    fn test_unescape(field: &str, expected_unescaped: &str) {
+        assert_eq!(unescape(field), expected_unescaped);
+    }
+}
@@ -0,0 +1,373 @@
+/*!
+This module contains [`Script`] for custom actions of the browser that are defined by a
+[Rhai](https://rhai.rs) script and bound to keys.
+
+A script binds keys to the names of its functions with `bind`. When a bound key is pressed, the
+function is called with the context of the browser (a map with the `dir` being browsed, the
+`selection`, its `name`, whether it `is_dir`, and the `home` directory) and returns an effect or an
+array of effects made by `copy(text)`, `browse(dir)`, `bash(dir)`, `edit(path)`, `find(dir)`, and
+`search(dir)`, for example:
+
+```rhai
+bind("g", "yank_and_terminal");
+
+fn yank_and_terminal(context) {
+    [copy(relative(context.selection, context.home)), bash(context.dir)]
+}
+```
+
+The script can only use the context and the effects (it can't read files or run commands), and it
+is stopped if it runs for too long.
+*/
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter, Result as FormatResult};
+use std::fs;
+use std::io::Error as IOError;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use rhai::{Array, CallFnOptions, Dynamic, Engine, EvalAltResult, Map, Scope, AST};
+
+/// The maximum number of operations that loading the script or running an action can take.
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// The maximum depth of function calls in the script.
+const MAX_CALL_LEVELS: usize = 32;
+
+/// An effect of a custom action.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptEffect {
+    /// Copy the text to the clipboard.
+    Copy { text: String },
+    /// Browse the directory.
+    Browse { dir: PathBuf },
+    /// Run bash in the directory.
+    Bash { dir: PathBuf },
+    /// Edit the file.
+    Edit { path: PathBuf },
+    /// Find files in the directory.
+    Find { dir: PathBuf },
+    /// Search the files in the directory.
+    Search { dir: PathBuf },
+}
+
+/// A script defining custom actions which are bound to keys.
+pub struct Script {
+    engine: Engine,
+    ast: AST,
+    /// The names of the functions that are bound to keys.
+    bindings: HashMap<char, String>,
+}
+
+impl Script {
+    /// Load the script from a file (which runs its top level statements to bind keys).
+    pub fn load(path: &Path) -> Result<Self, ScriptLoadError> {
+        let source: String = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(error) => {
+                return Err(ScriptLoadError::ReadError {
+                    path: path.to_path_buf(),
+                    error,
+                })
+            }
+        };
+        Self::compile(&source).map_err(|error| ScriptLoadError::ScriptError {
+            path: path.to_path_buf(),
+            error,
+        })
+    }
+
+    /// Compile the source of the script and run its top level statements.
+    fn compile(source: &str) -> Result<Self, String> {
+        let bindings: Rc<RefCell<HashMap<char, String>>> = Rc::new(RefCell::new(HashMap::new()));
+        let engine: Engine = new_engine(bindings.clone());
+        let ast: AST = engine.compile(source).map_err(|error| error.to_string())?;
+        engine
+            .run_ast_with_scope(&mut Scope::new(), &ast)
+            .map_err(|error| error.to_string())?;
+
+        let bindings: HashMap<char, String> = bindings.take();
+        for name in bindings.values() {
+            if !ast.iter_functions().any(|function| function.name == name) {
+                return Err(format!("The function `{}` is bound but not defined.", name));
+            }
+        }
+        Ok(Self {
+            engine,
+            ast,
+            bindings,
+        })
+    }
+
+    /// Return whether a custom action is bound to the key.
+    pub fn binds(&self, key: char) -> bool {
+        self.bindings.contains_key(&key)
+    }
+
+    /// Run the custom action bound to the key and return its effects.
+    pub fn run(
+        &self,
+        key: char,
+        dir: &Path,
+        selection: Option<&Path>,
+    ) -> Result<Vec<ScriptEffect>, String> {
+        let name: &str = match self.bindings.get(&key) {
+            Some(name) => name,
+            None => return Ok(Vec::new()),
+        };
+        let options = CallFnOptions::new().eval_ast(false);
+        let value: Dynamic = self
+            .engine
+            .call_fn_with_options(
+                options,
+                &mut Scope::new(),
+                &self.ast,
+                name,
+                (context(dir, selection),),
+            )
+            .map_err(|error| format!("The action `{}` failed: {}", name, error))?;
+        effects(value).map_err(|error| format!("The action `{}` failed: {}", name, error))
+    }
+}
+
+/// Return a new engine with the functions available to scripts (where `bind` adds to the
+/// bindings).
+fn new_engine(bindings: Rc<RefCell<HashMap<char, String>>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_call_levels(MAX_CALL_LEVELS);
+    // NOTE: Printing would draw over insh.
+    engine.on_print(|_| {});
+    engine.on_debug(|_, _, _| {});
+
+    engine.register_type_with_name::<ScriptEffect>("Effect");
+    engine.register_fn(
+        "bind",
+        move |key: &str, name: &str| -> Result<(), Box<EvalAltResult>> {
+            let mut characters = key.chars();
+            match (characters.next(), characters.next()) {
+                (Some(key), None) => {
+                    bindings.borrow_mut().insert(key, name.to_string());
+                    Ok(())
+                }
+                _ => Err(format!("The key `{}` is not a single character.", key).into()),
+            }
+        },
+    );
+    engine.register_fn("relative", relative);
+    engine.register_fn("copy", |text: &str| ScriptEffect::Copy {
+        text: text.to_string(),
+    });
+    engine.register_fn("browse", |dir: &str| ScriptEffect::Browse {
+        dir: PathBuf::from(dir),
+    });
+    engine.register_fn("bash", |dir: &str| ScriptEffect::Bash {
+        dir: PathBuf::from(dir),
+    });
+    engine.register_fn("edit", |path: &str| ScriptEffect::Edit {
+        path: PathBuf::from(path),
+    });
+    engine.register_fn("find", |dir: &str| ScriptEffect::Find {
+        dir: PathBuf::from(dir),
+    });
+    engine.register_fn("search", |dir: &str| ScriptEffect::Search {
+        dir: PathBuf::from(dir),
+    });
+    engine
+}
+
+/// Return the path relative to the base (or the path as is if it is not in the base).
+fn relative(path: &str, base: &str) -> String {
+    match Path::new(path).strip_prefix(base) {
+        Ok(relative) if relative.as_os_str().is_empty() => String::from("."),
+        Ok(relative) => relative.to_string_lossy().to_string(),
+        Err(_) => path.to_string(),
+    }
+}
+
+/// Return the context of the browser that is passed to an action.
+fn context(dir: &Path, selection: Option<&Path>) -> Map {
+    let mut context = Map::new();
+    context.insert("dir".into(), dir.to_string_lossy().to_string().into());
+    context.insert(
+        "selection".into(),
+        match selection {
+            Some(selection) => selection.to_string_lossy().to_string().into(),
+            None => Dynamic::UNIT,
+        },
+    );
+    context.insert(
+        "name".into(),
+        match selection.and_then(Path::file_name) {
+            Some(name) => name.to_string_lossy().to_string().into(),
+            None => Dynamic::UNIT,
+        },
+    );
+    context.insert("is_dir".into(), selection.is_some_and(Path::is_dir).into());
+    context.insert(
+        "home".into(),
+        match dirs::home_dir() {
+            Some(home) => home.to_string_lossy().to_string().into(),
+            None => Dynamic::UNIT,
+        },
+    );
+    context
+}
+
+/// Return the effects that an action returned (nothing, an effect, or an array of effects).
+///
+/// An action can copy any number of times but can only have one other effect (because it leaves
+/// the browser or changes its directory).
+fn effects(value: Dynamic) -> Result<Vec<ScriptEffect>, String> {
+    let values: Array = if value.is_unit() {
+        Array::new()
+    } else if value.is_array() {
+        value.cast::<Array>()
+    } else {
+        vec![value]
+    };
+
+    let effects: Vec<ScriptEffect> = values
+        .into_iter()
+        .map(|value| {
+            let type_name: String = value.type_name().to_string();
+            value
+                .try_cast::<ScriptEffect>()
+                .ok_or_else(|| format!("Expected an effect but got {}.", type_name))
+        })
+        .collect::<Result<_, _>>()?;
+    let others: usize = effects
+        .iter()
+        .filter(|effect| !matches!(effect, ScriptEffect::Copy { .. }))
+        .count();
+    if others > 1 {
+        return Err(String::from(
+            "Only one effect other than copying can be returned.",
+        ));
+    }
+    Ok(effects)
+}
+
+/// An error loading a script.
+pub enum ScriptLoadError {
+    /// An error reading the script.
+    ReadError {
+        /// The path of the script.
+        path: PathBuf,
+        /// The IO error that was encountered while reading the script.
+        error: IOError,
+    },
+    /// An error compiling the script or running its top level statements.
+    ScriptError {
+        /// The path of the script.
+        path: PathBuf,
+        /// A description of the error.
+        error: String,
+    },
+}
+
+impl Display for ScriptLoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        match self {
+            Self::ReadError { path, error } => {
+                write!(
+                    f,
+                    "Failed to read the script \"{}\": {}",
+                    path.display(),
+                    error
+                )
+            }
+            Self::ScriptError { path, error } => {
+                write!(
+                    f,
+                    "Failed to load the script \"{}\": {}",
+                    path.display(),
+                    error
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    const SCRIPT: &str = r#"
+        bind("g", "yank_and_terminal");
+        bind("G", "nothing");
+        bind("x", "too_much");
+        bind("y", "not_an_effect");
+        bind("z", "forever");
+
+        fn yank_and_terminal(context) {
+            [copy(relative(context.selection, context.dir)), bash(context.dir)]
+        }
+
+        fn nothing(context) {}
+
+        fn too_much(context) {
+            [browse(context.dir), edit(context.selection)]
+        }
+
+        fn not_an_effect(context) {
+            42
+        }
+
+        fn forever(context) {
+            loop {}
+        }
+    "#;
+
+    #[test]
+    fn test_bindings() {
+        let script = Script::compile(SCRIPT).ok().unwrap();
+
+        assert!(script.binds('g'));
+        assert!(script.binds('G'));
+        assert!(!script.binds('q'));
+    }
+
+    #[test_case('g', Ok(vec![
+        ScriptEffect::Copy { text: String::from("bar") },
+        ScriptEffect::Bash { dir: PathBuf::from("/foo") },
+    ]); "composed effects")]
+    #[test_case('G', Ok(vec![]); "no effects")]
+    #[test_case('q', Ok(vec![]); "unbound")]
+    fn test_run(key: char, expected: Result<Vec<ScriptEffect>, String>) {
+        let script = Script::compile(SCRIPT).ok().unwrap();
+
+        assert_eq!(
+            script.run(key, Path::new("/foo"), Some(Path::new("/foo/bar"))),
+            expected
+        );
+    }
+
+    #[test_case('x'; "too many effects")]
+    #[test_case('y'; "not an effect")]
+    #[test_case('z'; "too many operations")]
+    fn test_run_fails(key: char) {
+        let script = Script::compile(SCRIPT).ok().unwrap();
+
+        assert!(script
+            .run(key, Path::new("/foo"), Some(Path::new("/foo/bar")))
+            .is_err());
+    }
+
+    #[test_case(r#"bind("gg", "foo"); fn foo(context) {}"#; "key is not a character")]
+    #[test_case(r#"bind("g", "foo");"#; "function is not defined")]
+    #[test_case(r#"bind("g", "#; "syntax error")]
+    fn test_compile_fails(source: &str) {
+        assert!(Script::compile(source).is_err());
+    }
+
+    #[test_case("/foo/bar", "/foo", "bar"; "in the base")]
+    #[test_case("/foo", "/foo", "."; "the base")]
+    #[test_case("/baz", "/foo", "/baz"; "not in the base")]
+    fn test_relative(path: &str, base: &str, expected: &str) {
+        assert_eq!(relative(path, base), expected);
+    }
+}
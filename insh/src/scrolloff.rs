@@ -0,0 +1,71 @@
+/*!
+This module contains the function [`offset_for`] which is the shared offset math for lists that
+keep a number of rows of context (the `scrolloff`) above and below the selected entry.
+*/
+
+/// Return the number of rows of context to keep above and below the selection in a list with
+/// `rows` visible rows.
+///
+/// The context is limited so that the selection can still move within the visible rows.
+pub fn margin(scrolloff: usize, rows: usize) -> usize {
+    scrolloff.min(rows.saturating_sub(1) / 2)
+}
+
+/// Return the offset (the number of the first visible entry) for showing the entry at `index`
+/// with `scrolloff` rows of context, moving the current `offset` as little as possible.
+///
+/// The offset never scrolls past the first or the last entry.
+pub fn offset_for(
+    index: usize,
+    offset: usize,
+    entry_count: usize,
+    rows: usize,
+    scrolloff: usize,
+) -> usize {
+    if rows == 0 {
+        return offset;
+    }
+    let margin: usize = margin(scrolloff, rows);
+    let min_offset: usize = (index + margin + 1).saturating_sub(rows);
+    let max_offset: usize = index
+        .saturating_sub(margin)
+        .min(entry_count.saturating_sub(rows));
+    offset.max(min_offset).min(max_offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(3, 10, 3; "scrolloff")]
+    #[test_case(3, 4, 1; "limited by rows")]
+    #[test_case(3, 1, 0; "one row")]
+    #[test_case(3, 0, 0; "no rows")]
+    #[test_case(0, 10, 0; "no scrolloff")]
+    fn test_margin(scrolloff: usize, rows: usize, expected_margin: usize) {
+        let margin: usize = margin(scrolloff, rows);
+
+        assert_eq!(margin, expected_margin);
+    }
+
+    #[test_case(5, 0, 100, 10, 0, 0; "visible without scrolloff")]
+    #[test_case(10, 0, 100, 10, 0, 1; "below without scrolloff")]
+    #[test_case(7, 0, 100, 10, 3, 1; "within the bottom margin")]
+    #[test_case(4, 5, 100, 10, 3, 1; "within the top margin")]
+    #[test_case(1, 5, 100, 10, 3, 0; "near the first entry")]
+    #[test_case(98, 80, 100, 10, 3, 90; "near the last entry")]
+    #[test_case(3, 0, 5, 10, 3, 0; "fewer entries than rows")]
+    fn test_offset_for(
+        index: usize,
+        offset: usize,
+        entry_count: usize,
+        rows: usize,
+        scrolloff: usize,
+        expected_offset: usize,
+    ) {
+        let new_offset: usize = offset_for(index, offset, entry_count, rows, scrolloff);
+
+        assert_eq!(new_offset, expected_offset);
+    }
+}
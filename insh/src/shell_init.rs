@@ -0,0 +1,52 @@
+/*!
+This module contains the [`Shell`] enum and the shell code for integrating Insh with shells (used by
+the `shell-init` command).
+*/
+use clap::ValueEnum;
+
+/// The shell code for defining the `insh-cd` function in bash.
+const BASH: &str = r#"insh-cd() {
+    local cd_file
+    cd_file="$(mktemp)" || return
+    command insh --cd-file "$cd_file" "$@"
+    local dir
+    dir="$(cat "$cd_file")"
+    rm -f "$cd_file"
+    if [ -n "$dir" ] && [ "$dir" != "$PWD" ]; then
+        cd "$dir" || return
+    fi
+}
+"#;
+
+/// The shell code for defining the `insh-cd` function in fish.
+const FISH: &str = r#"function insh-cd
+    set -l cd_file (mktemp); or return
+    command insh --cd-file $cd_file $argv
+    set -l dir (cat $cd_file)
+    rm -f $cd_file
+    if test -n "$dir" -a "$dir" != "$PWD"
+        cd $dir
+    end
+end
+"#;
+
+/// A shell that Insh can integrate with.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    /// Return the shell code which defines the `insh-cd` function. The function runs Insh and
+    /// then changes the working directory of the shell to the directory that was being browsed
+    /// when Insh exited.
+    pub fn init_script(&self) -> &'static str {
+        match self {
+            // NOTE: The bash function also works in zsh.
+            Self::Bash | Self::Zsh => BASH,
+            Self::Fish => FISH,
+        }
+    }
+}
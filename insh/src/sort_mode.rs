@@ -0,0 +1,134 @@
+/*!
+This module contains the enum [`SortMode`] (the `sort.mode` configuration option) which chooses what
+the entries of the browser are ordered by before their names (which are ordered by the
+[`Collation`](crate::collation::Collation)).
+*/
+use std::cmp::Ordering;
+use std::ffi::OsStr;
+
+use file_info::FileInfo;
+use serde::Deserialize;
+
+/// What the entries of the browser are ordered by.
+#[derive(Deserialize, Debug, Default, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortMode {
+    /// Only by name.
+    #[default]
+    Name,
+    /// By the time the files were last modified (the newest first).
+    Modified,
+    /// By the sizes of the files (the largest first).
+    Size,
+    /// By type (directories first) and then by extension.
+    Type,
+}
+
+impl SortMode {
+    /// Return the mode after this one (which the modes are cycled through in).
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Name => Self::Modified,
+            Self::Modified => Self::Size,
+            Self::Size => Self::Type,
+            Self::Type => Self::Name,
+        }
+    }
+
+    /// Return the name of the mode (as it is written in the configuration).
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Modified => "modified",
+            Self::Size => "size",
+            Self::Type => "type",
+        }
+    }
+
+    /// Compare the entries by the mode (the entries which are equal are then ordered by name). The
+    /// entries whose modification time or size isn't known come last.
+    pub fn compare(&self, a: &FileInfo, b: &FileInfo) -> Ordering {
+        match self {
+            Self::Name => Ordering::Equal,
+            Self::Modified => descending(a.modified(), b.modified()),
+            Self::Size => descending(a.size(), b.size()),
+            Self::Type => b
+                .is_dir()
+                .cmp(&a.is_dir())
+                .then_with(|| extension(a).cmp(extension(b))),
+        }
+    }
+}
+
+/// Compare the values from the largest to the smallest (with the unknown values last).
+fn descending<T: Ord>(a: Option<T>, b: Option<T>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => b.cmp(&a),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Return the extension of the file (empty if it has none).
+fn extension(file_info: &FileInfo) -> &OsStr {
+    file_info.path().extension().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    use file_type::FileType;
+    use test_case::test_case;
+
+    /// Return the info of a file with the name, the type, the number of seconds after the epoch it
+    /// was modified at, and the size.
+    fn file_info(
+        name: &str,
+        file_type: FileType,
+        modified: Option<u64>,
+        size: Option<u64>,
+    ) -> FileInfo {
+        FileInfo::builder()
+            .path(PathBuf::from("/r").join(name))
+            .r#type(Ok(file_type))
+            .modified(modified.map(|seconds| SystemTime::UNIX_EPOCH + Duration::from_secs(seconds)))
+            .size(size)
+            .build()
+    }
+
+    #[test_case(SortMode::Name, &["a.txt", "b", "c.rs", "d"]; "name")]
+    #[test_case(SortMode::Modified, &["c.rs", "a.txt", "d", "b"]; "modified")]
+    #[test_case(SortMode::Size, &["a.txt", "c.rs", "b", "d"]; "size")]
+    #[test_case(SortMode::Type, &["b", "d", "c.rs", "a.txt"]; "by type")]
+    fn test_compare(mode: SortMode, expected: &[&str]) {
+        let mut file_infos: Vec<FileInfo> = vec![
+            file_info("a.txt", FileType::File, Some(2), Some(10)),
+            file_info("b", FileType::Dir, None, None),
+            file_info("c.rs", FileType::File, Some(3), Some(5)),
+            file_info("d", FileType::Dir, Some(1), None),
+        ];
+        file_infos.sort_by(|a, b| mode.compare(a, b).then_with(|| a.path().cmp(b.path())));
+
+        let names: Vec<String> = file_infos
+            .iter()
+            .map(|file_info| file_info.name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, expected);
+    }
+
+    #[test]
+    fn test_next() {
+        let mut mode = SortMode::Name;
+        let mut names: Vec<&str> = Vec::new();
+        for _ in 0..4 {
+            mode = mode.next();
+            names.push(mode.name());
+        }
+        assert_eq!(names, ["modified", "size", "type", "name"]);
+    }
+}
@@ -23,7 +23,7 @@ mod conjoin {
                         + ", "
                         + conjunction
                         + " "
-                        + &self[self.len() - 1]
+                        + self[self.len() - 1].as_str()
                 }
             }
         }
@@ -94,51 +94,82 @@ pub use capitalize_first_letter::CapitalizeFirstLetterExt;
 
 /// Contains functionality for removing tabs from strings.
 mod detab {
+    /// The character that spaces are shown as when whitespace is shown.
+    const SPACE_MARKER: char = '·';
+    /// The character that the start of tabs is shown as when whitespace is shown.
+    const TAB_MARKER: char = '→';
+
     /// An extension trait for strings to remove tabs.
     pub trait DetabExt {
         /// Remove tab characters.
         fn detab(&self, tab_width: usize) -> String;
+
+        /// Remove tab characters and show whitespace (tabs start with `→` and spaces are `·`). The
+        /// result has as many characters as the result of `detab`.
+        fn detab_showing_whitespace(&self, tab_width: usize) -> String;
     }
 
     impl DetabExt for String {
         fn detab(&self, tab_width: usize) -> String {
             self.as_str().detab(tab_width)
         }
+
+        fn detab_showing_whitespace(&self, tab_width: usize) -> String {
+            self.as_str().detab_showing_whitespace(tab_width)
+        }
     }
 
     impl DetabExt for &str {
         fn detab(&self, tab_width: usize) -> String {
-            // If the string is empty, then the result is also empty.
-            if self.is_empty() {
-                return String::new();
-            }
+            detab(self, tab_width, false)
+        }
 
-            // The resuling string will be at least as long as the input string, so reserve the
-            // capacity for that many characters.
-            let mut result: String = String::with_capacity(self.len());
+        fn detab_showing_whitespace(&self, tab_width: usize) -> String {
+            detab(self, tab_width, true)
+        }
+    }
 
-            let mut counter: usize = 0;
-            for character in self.chars() {
-                match character {
-                    '\t' => {
-                        let number_of_spaces = tab_width - counter;
-                        result.push_str(&(" ".repeat(number_of_spaces)));
+    /// Remove the tab characters of the string (showing whitespace if `show_whitespace` is true).
+    fn detab(string: &str, tab_width: usize, show_whitespace: bool) -> String {
+        // If the string is empty, then the result is also empty.
+        if string.is_empty() {
+            return String::new();
+        }
 
-                        counter = 0;
+        // The resuling string will be at least as long as the input string, so reserve the
+        // capacity for that many characters.
+        let mut result: String = String::with_capacity(string.len());
+
+        let mut counter: usize = 0;
+        for character in string.chars() {
+            match character {
+                '\t' => {
+                    let number_of_spaces = tab_width - counter;
+                    match (show_whitespace, number_of_spaces.checked_sub(1)) {
+                        (true, Some(rest)) => {
+                            result.push(TAB_MARKER);
+                            result.push_str(&(" ".repeat(rest)));
+                        }
+                        _ => result.push_str(&(" ".repeat(number_of_spaces))),
                     }
-                    _ => {
-                        result.push(character);
 
-                        counter += 1;
-                        if counter == tab_width {
-                            counter = 0;
-                        }
+                    counter = 0;
+                }
+                _ => {
+                    match (show_whitespace, character) {
+                        (true, ' ') => result.push(SPACE_MARKER),
+                        _ => result.push(character),
+                    }
+
+                    counter += 1;
+                    if counter == tab_width {
+                        counter = 0;
                     }
                 }
             }
-
-            result
         }
+
+        result
     }
 
     #[cfg(test)]
@@ -159,6 +190,17 @@ mod detab {
 
             assert_eq!(result, expected)
         }
+
+        #[test_case("", 4, ""; "an empty string")]
+        #[test_case("\t", 4, "→   "; "just one tab")]
+        #[test_case("a b", 4, "a·b"; "a space")]
+        #[test_case("\tf\t", 4, "→   f→  "; "a tab, a character, then a tab")]
+        #[test_case("ab \tc", 4, "ab·→c"; "a space before a tab")]
+        fn test_detab_showing_whitespace(string: &str, tab_width: usize, expected: &str) {
+            let result: String = string.detab_showing_whitespace(tab_width);
+
+            assert_eq!(result, expected)
+        }
     }
 }
 pub use detab::DetabExt;
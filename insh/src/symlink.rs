@@ -0,0 +1,27 @@
+/*!
+This module contains functions for following symbolic links to directories. The browser keeps the
+logical path (the path through the links) and the daemon finds the physical location.
+*/
+use std::path::Path;
+
+/// Return if a link from the directory `dir` to `target` (both physical locations) points back to
+/// the directory or one of its ancestors, so that following it again and again would never end.
+pub fn is_cycle(target: &Path, dir: &Path) -> bool {
+    dir.starts_with(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("/foo/bar", "/foo/bar", true; "same directory")]
+    #[test_case("/foo", "/foo/bar", true; "parent")]
+    #[test_case("/", "/foo/bar", true; "root")]
+    #[test_case("/foo/bar/baz", "/foo/bar", false; "child")]
+    #[test_case("/foo/ba", "/foo/bar", false; "prefix of a component")]
+    #[test_case("/qux", "/foo/bar", false; "elsewhere")]
+    fn test_is_cycle(target: &str, dir: &str, expected_cycle: bool) {
+        assert_eq!(is_cycle(Path::new(target), Path::new(dir)), expected_cycle);
+    }
+}
@@ -0,0 +1,194 @@
+/*!
+This module contains [`Toasts`] which are short messages (like errors) that are shown stacked in the
+bottom right corner for a few seconds and are kept in a history (which is listed by the `messages`
+command of the command palette).
+*/
+use std::collections::VecDeque;
+use std::time::{Duration, SystemTime};
+
+use rend::{Fabric, Location, Yarn};
+
+use crate::color::Color;
+
+/// How long a toast is shown for (it is hidden on the next tick after that).
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+/// The maximum number of toasts that are shown at once.
+const MAX_VISIBLE: usize = 3;
+
+/// The maximum number of toasts that are kept in the history.
+const MAX_HISTORY: usize = 200;
+
+/// How important a toast is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastLevel {
+    Info,
+    Error,
+}
+
+impl ToastLevel {
+    /// Return the background color of toasts of the level.
+    pub fn color(&self) -> Color {
+        match self {
+            Self::Info => Color::Highlight,
+            Self::Error => Color::Danger,
+        }
+    }
+}
+
+/// A short message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Toast {
+    message: String,
+    level: ToastLevel,
+    /// When the toast was made.
+    time: SystemTime,
+}
+
+impl Toast {
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn level(&self) -> ToastLevel {
+        self.level
+    }
+
+    pub fn time(&self) -> SystemTime {
+        self.time
+    }
+}
+
+/// The toasts that were made (from oldest to newest).
+#[derive(Default)]
+pub struct Toasts {
+    history: VecDeque<Toast>,
+    /// Whether any toasts were shown at the last tick.
+    shown: bool,
+}
+
+impl Toasts {
+    /// Make a toast.
+    pub fn push(&mut self, message: String, level: ToastLevel) {
+        self.push_at(message, level, SystemTime::now());
+    }
+
+    fn push_at(&mut self, message: String, level: ToastLevel, time: SystemTime) {
+        self.history.push_back(Toast {
+            message,
+            level,
+            time,
+        });
+        if self.history.len() > MAX_HISTORY {
+            self.history.pop_front();
+        }
+    }
+
+    /// Return the toasts that were made (from oldest to newest).
+    pub fn history(&self) -> &VecDeque<Toast> {
+        &self.history
+    }
+
+    /// Return the toasts that are shown at the time (from oldest to newest).
+    fn visible(&self, now: SystemTime) -> Vec<&Toast> {
+        let mut visible: Vec<&Toast> = self
+            .history
+            .iter()
+            .rev()
+            .take_while(|toast| {
+                now.duration_since(toast.time)
+                    .is_ok_and(|age| age < TOAST_DURATION)
+            })
+            .take(MAX_VISIBLE)
+            .collect();
+        visible.reverse();
+        visible
+    }
+
+    /// Return whether a frame should be drawn at the time because toasts are shown (or were just
+    /// hidden), so that toasts which are made or expire without any input are drawn.
+    pub fn tick(&mut self, now: SystemTime) -> bool {
+        let shown: bool = !self.visible(now).is_empty();
+        let redraw: bool = shown || self.shown;
+        self.shown = shown;
+        redraw
+    }
+
+    /// Draw the toasts that are shown at the time over the fabric, stacked in the bottom right
+    /// corner above the last row (which is usually a status line).
+    pub fn render(&self, fabric: &mut Fabric, now: SystemTime) {
+        let size = fabric.size();
+        let visible: Vec<&Toast> = self.visible(now);
+        if size.rows < visible.len() + 1 {
+            return;
+        }
+
+        let width: usize = size.columns / 2;
+        for (index, toast) in visible.iter().rev().enumerate() {
+            let mut yarn = Yarn::from(format!(" {} ", toast.message));
+            yarn.truncate(width);
+            yarn.color(Color::InvertedText.into());
+            yarn.background(toast.level.color().into());
+            let location = Location::new(size.rows - 2 - index, size.columns - yarn.len());
+            fabric.overlay(&Fabric::from(yarn), location);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rend::Size;
+    use test_case::test_case;
+
+    #[test_case(&[10, 3, 1], &["3", "1"]; "old toasts are hidden")]
+    #[test_case(&[3, 3, 2, 1], &["3", "2", "1"]; "at most a few toasts are shown")]
+    #[test_case(&[], &[]; "no toasts")]
+    fn test_visible(seconds_ago: &[u64], expected: &[&str]) {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
+        let mut toasts = Toasts::default();
+        for seconds in seconds_ago {
+            let time: SystemTime = now - Duration::from_secs(*seconds);
+            toasts.push_at(seconds.to_string(), ToastLevel::Info, time);
+        }
+
+        let messages: Vec<&str> = toasts
+            .visible(now)
+            .iter()
+            .map(|toast| toast.message())
+            .collect();
+        assert_eq!(messages, expected);
+    }
+
+    #[test]
+    fn test_toast_disappears_without_input() {
+        let made = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
+        let mut toasts = Toasts::default();
+        toasts.push_at(String::from("hi"), ToastLevel::Info, made);
+        // Draw the toasts on a tick (like the app does when a tick has an effect).
+        let mut draw_on_tick = |seconds: u64| {
+            let now: SystemTime = made + Duration::from_secs(seconds);
+            let mut fabric = Fabric::new(Size::new(3, 10));
+            let redraw: bool = toasts.tick(now);
+            toasts.render(&mut fabric, now);
+            let shown: bool = fabric.characters()[1].contains(&'h');
+            (redraw, shown)
+        };
+
+        assert_eq!(draw_on_tick(1), (true, true));
+        assert_eq!(draw_on_tick(TOAST_DURATION.as_secs()), (true, false));
+        assert_eq!(draw_on_tick(TOAST_DURATION.as_secs() + 1), (false, false));
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut toasts = Toasts::default();
+        for number in 0..MAX_HISTORY + 1 {
+            toasts.push(number.to_string(), ToastLevel::Error);
+        }
+
+        assert_eq!(toasts.history().len(), MAX_HISTORY);
+        assert_eq!(toasts.history()[0].message(), "1");
+    }
+}
@@ -0,0 +1,47 @@
+//! A token for cancelling work.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// A token for cancelling work (clones of a token share whether it was cancelled).
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    /// If the work was cancelled.
+    cancelled: Arc<AtomicBool>,
+    /// If the work that this work is part of was cancelled (which cancels this work too).
+    parent: Option<Arc<AtomicBool>>,
+}
+
+impl CancellationToken {
+    /// Return a new token that has not been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return a token for part of the work (which is cancelled when this token is cancelled, but
+    /// can also be cancelled on its own).
+    pub fn child(&self) -> Self {
+        Self {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            parent: Some(self.cancelled.clone()),
+        }
+    }
+
+    /// Cancel the work.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Return if the work was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+            || self
+                .parent
+                .as_ref()
+                .is_some_and(|parent| parent.load(Ordering::Relaxed))
+    }
+
+    /// Return if there are other clones of the token (so the work may still be going on).
+    pub fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.cancelled) > 1
+    }
+}
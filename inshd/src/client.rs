@@ -1,5 +1,4 @@
 //! Information about a client.
-use std::io::Error as IOError;
 use std::os::unix::net::UnixStream;
 
 use typed_builder::TypedBuilder;
@@ -25,12 +24,4 @@ impl Client {
     pub fn stream(&mut self) -> &mut UnixStream {
         &mut self.stream
     }
-
-    /// Try returning a clone of the client information.
-    pub fn try_clone(&self) -> Result<Self, IOError> {
-        Ok(Self {
-            uuid: self.uuid,
-            stream: self.stream.try_clone()?,
-        })
-    }
 }
@@ -1,10 +1,20 @@
 //! Handles a client.
+//!
+//! Each request from the client is framed by its length, so a request that can't be decoded is
+//! skipped and the connection keeps serving the requests after it. If the uuid of the bad request
+//! can be decoded, then the client is sent a bad request response for it. Otherwise, no response
+//! can be matched to the request by the client, so the request is dropped (and only logged). The
+//! connection is only closed if a length is invalid, since then where the next request starts is
+//! unknown.
+use crate::cancellation_token::CancellationToken;
 use crate::client::Client;
-use crate::client_request::ClientRequest;
 use crate::disconnected_client::DisconnectedClient;
+use crate::incoming_request::IncomingRequest;
 
-use insh_api::Request;
+use insh_api::wire::{self, DecodeError, LENGTH_LEN};
+use insh_api::{BadRequestResponseParams, Request, RequestParams, Response, ResponseParams};
 
+use std::collections::HashMap;
 use std::io::{ErrorKind as IOErrorKind, Read};
 use std::os::fd::{AsRawFd, RawFd};
 use std::os::unix::net::UnixStream;
@@ -22,9 +32,9 @@ pub struct ClientHandler {
     /// Information about the client.
     client: Client,
     /// A sender for requests the from the client.
-    requests: Sender<Request>,
-    /// A sender of information about requests from the client.
-    client_requests_tx: Sender<ClientRequest>,
+    requests: Sender<IncomingRequest>,
+    /// A sender of responses to the client.
+    responses_tx: Sender<Response>,
     /// Senders of information about the client disconnecting.
     disconnected_clients_txs: Vec<Sender<DisconnectedClient>>,
     /// The read side of a pipe for a stop sentinel.
@@ -38,8 +48,13 @@ impl ClientHandler {
         log::info!("Client handler running for client {}.", client_uuid);
 
         let mut num_requests: usize = 0;
+        // Cancelled when the client disconnects so that work for the client stops.
+        let cancellation_token = CancellationToken::new();
+        // The tokens of the requests that may still be in flight (which are cancelled when the
+        // client cancels the requests).
+        let mut in_flight: HashMap<Uuid, CancellationToken> = HashMap::new();
 
-        let mut length_buffer: [u8; 8] = [0; 8];
+        let mut length_buffer: [u8; LENGTH_LEN] = [0; LENGTH_LEN];
         let mut request_buffer: Vec<u8> = vec![];
 
         let stream: &mut UnixStream = self.client.stream();
@@ -72,11 +87,22 @@ impl ClientHandler {
                     }
                 }
             }
-            let length: u64 = u64::from_be_bytes(length_buffer);
+            // NOTE: If the length is bogus, then where the next request starts is unknown so the
+            // client can not be served anymore.
+            let length: usize = match wire::decode_length(length_buffer) {
+                Ok(length) => length,
+                Err(error) => {
+                    log::error!(
+                        "Invalid request length from client {}: {}",
+                        client_uuid,
+                        error
+                    );
+                    break;
+                }
+            };
             log::debug!("The request is {} bytes long.", length);
 
             // Reserve more space in the request buffer if necessary.
-            let length: usize = length.try_into().unwrap();
             log::debug!("Checking the capacity of the request buffer...");
             let capacity: usize = request_buffer.capacity();
             log::debug!("The request buffer has a capacity of {}.", capacity);
@@ -106,24 +132,65 @@ impl ClientHandler {
             log::debug!("Read the request.");
 
             // Deserialize the request.
-            let request: Request = bincode::deserialize(&request_buffer[..length]).unwrap();
+            let request: Request = match wire::decode(&request_buffer[..length]) {
+                Ok(request) => request,
+                Err(error) => {
+                    log::warn!(
+                        "Received a bad request from client {}: {}",
+                        client_uuid,
+                        error
+                    );
+                    let response: Response =
+                        match bad_request_response(&request_buffer[..length], error) {
+                            Some(response) => response,
+                            None => {
+                                log::warn!("The uuid of the bad request could not be decoded.");
+                                continue;
+                            }
+                        };
+
+                    num_requests += 1;
+                    if self.responses_tx.send(response).is_err() {
+                        log::info!("Client {} stopped reading responses.", client_uuid);
+                        break;
+                    }
+                    continue;
+                }
+            };
             let request_uuid: Uuid = *request.uuid();
             log::debug!("Received request {:?}.", request_uuid);
 
-            // Send the request to the scheduler.
-            self.requests.send(request).unwrap();
+            if let RequestParams::CancelRequest(params) = request.params() {
+                match in_flight.remove(params.request()) {
+                    Some(request_cancellation_token) => {
+                        log::info!("Cancelling request {}.", params.request());
+                        request_cancellation_token.cancel();
+                    }
+                    None => log::debug!("Request {} is not in flight.", params.request()),
+                }
+                num_requests += 1;
+                continue;
+            }
 
-            num_requests += 1;
+            // Forget the requests that are done (whose tokens are only held here).
+            in_flight
+                .retain(|_, request_cancellation_token| request_cancellation_token.is_shared());
+            let request_cancellation_token: CancellationToken = cancellation_token.child();
+            in_flight.insert(request_uuid, request_cancellation_token.clone());
 
-            // Inform the response handler that the request is for this client.
-            let client_request: ClientRequest = ClientRequest::builder()
-                .client_uuid(client_uuid)
-                .request_uuid(request_uuid)
+            // Send the request to the scheduler.
+            let incoming_request: IncomingRequest = IncomingRequest::builder()
+                .request(request)
+                .cancellation_token(request_cancellation_token)
+                .responses_tx(self.responses_tx.clone())
                 .build();
-            self.client_requests_tx.send(client_request).unwrap();
+            self.requests.send(incoming_request).unwrap();
+
+            num_requests += 1;
         }
 
         log::info!("Client handler stopping for client {}...", client_uuid);
+        cancellation_token.cancel();
         let disconnected_client = DisconnectedClient::builder()
             .client_uuid(client_uuid)
             .num_requests(num_requests)
@@ -135,3 +202,182 @@ impl ClientHandler {
         }
     }
 }
+
+/// Return the response to the malformed request `bytes` (if the uuid of the request is known).
+fn bad_request_response(bytes: &[u8], error: DecodeError) -> Option<Response> {
+    let uuid: Uuid = wire::decode_request_uuid(bytes)?;
+    let params = BadRequestResponseParams::builder()
+        .error(error.to_string())
+        .build();
+    let response = Response::builder()
+        .uuid(uuid)
+        .last(true)
+        .params(ResponseParams::BadRequest(params))
+        .build();
+    Some(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossbeam::channel;
+    use insh_api::{CancelRequestRequestParams, GetFilesRequestParams};
+    use std::io::Write;
+    use std::path::PathBuf;
+    use std::thread::{self, JoinHandle};
+    use std::time::{Duration, Instant};
+    use test_case::test_case;
+
+    /// Return a request to get files.
+    fn get_files_request() -> Request {
+        Request::builder()
+            .params(RequestParams::GetFiles(
+                GetFilesRequestParams::builder()
+                    .dir(PathBuf::from("/tmp"))
+                    .build(),
+            ))
+            .build()
+    }
+
+    #[test_case(false, 1; "after a request")]
+    #[test_case(true, 0; "in the middle of a request")]
+    fn test_client_drops_socket(partial: bool, expected_num_requests: usize) {
+        let (mut socket, stream) = UnixStream::pair().unwrap();
+        let (requests_tx, requests_rx) = channel::unbounded();
+        let (responses_tx, _responses_rx) = channel::unbounded();
+        let (disconnected_clients_tx, disconnected_clients_rx) = channel::unbounded();
+        let (stop_rx, _stop_tx) = os_pipe::pipe().unwrap();
+        let mut client_handler = ClientHandler::builder()
+            .client(Client::builder().stream(stream).build())
+            .requests(requests_tx)
+            .responses_tx(responses_tx)
+            .disconnected_clients_txs(vec![disconnected_clients_tx])
+            .stop_rx(stop_rx)
+            .build();
+        let handle: JoinHandle<()> = thread::spawn(move || client_handler.run());
+
+        let request: Request = get_files_request();
+        let bytes: Vec<u8> = bincode::serialize(&request).unwrap();
+        let length: u64 = bytes.len() as u64;
+        socket.write_all(&length.to_be_bytes()).unwrap();
+        match partial {
+            true => socket.write_all(&bytes[..bytes.len() / 2]).unwrap(),
+            false => socket.write_all(&bytes).unwrap(),
+        }
+        drop(socket);
+        handle.join().unwrap();
+
+        let disconnected_client: DisconnectedClient = disconnected_clients_rx.recv().unwrap();
+        assert_eq!(disconnected_client.num_requests, expected_num_requests);
+        let incoming_requests: Vec<IncomingRequest> = requests_rx.try_iter().collect();
+        assert_eq!(incoming_requests.len(), expected_num_requests);
+        for incoming_request in incoming_requests {
+            assert_eq!(incoming_request.request.uuid(), request.uuid());
+            assert!(incoming_request.cancellation_token.is_cancelled());
+        }
+    }
+
+    /// Write a request to the socket.
+    fn write_request(socket: &mut UnixStream, request: &Request) {
+        let bytes: Vec<u8> = bincode::serialize(request).unwrap();
+        let length: u64 = bytes.len() as u64;
+        socket.write_all(&length.to_be_bytes()).unwrap();
+        socket.write_all(&bytes).unwrap();
+    }
+
+    #[test]
+    fn test_cancel_request() {
+        let (mut socket, stream) = UnixStream::pair().unwrap();
+        let (requests_tx, requests_rx) = channel::unbounded();
+        let (responses_tx, _responses_rx) = channel::unbounded();
+        let (disconnected_clients_tx, disconnected_clients_rx) = channel::unbounded();
+        let (stop_rx, _stop_tx) = os_pipe::pipe().unwrap();
+        let mut client_handler = ClientHandler::builder()
+            .client(Client::builder().stream(stream).build())
+            .requests(requests_tx)
+            .responses_tx(responses_tx)
+            .disconnected_clients_txs(vec![disconnected_clients_tx])
+            .stop_rx(stop_rx)
+            .build();
+        let handle: JoinHandle<()> = thread::spawn(move || client_handler.run());
+
+        let request: Request = get_files_request();
+        write_request(&mut socket, &request);
+        let incoming_request: IncomingRequest = requests_rx.recv().unwrap();
+        assert!(!incoming_request.cancellation_token.is_cancelled());
+
+        let cancel_request: Request = Request::builder()
+            .params(RequestParams::CancelRequest(
+                CancelRequestRequestParams::builder()
+                    .request(*request.uuid())
+                    .build(),
+            ))
+            .build();
+        write_request(&mut socket, &cancel_request);
+        let start = Instant::now();
+        while !incoming_request.cancellation_token.is_cancelled() {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        drop(socket);
+        handle.join().unwrap();
+
+        let disconnected_client: DisconnectedClient = disconnected_clients_rx.recv().unwrap();
+        assert_eq!(disconnected_client.num_requests, 2);
+        assert!(requests_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_undecodable_uuid_keeps_serving() {
+        let (mut socket, stream) = UnixStream::pair().unwrap();
+        let (requests_tx, requests_rx) = channel::unbounded();
+        let (responses_tx, responses_rx) = channel::unbounded();
+        let (disconnected_clients_tx, disconnected_clients_rx) = channel::unbounded();
+        let (stop_rx, _stop_tx) = os_pipe::pipe().unwrap();
+        let mut client_handler = ClientHandler::builder()
+            .client(Client::builder().stream(stream).build())
+            .requests(requests_tx)
+            .responses_tx(responses_tx)
+            .disconnected_clients_txs(vec![disconnected_clients_tx])
+            .stop_rx(stop_rx)
+            .build();
+        let handle: JoinHandle<()> = thread::spawn(move || client_handler.run());
+
+        let bad_bytes: Vec<u8> = vec![0xff; 10];
+        assert!(wire::decode_request_uuid(&bad_bytes).is_none());
+        let length: u64 = bad_bytes.len() as u64;
+        socket.write_all(&length.to_be_bytes()).unwrap();
+        socket.write_all(&bad_bytes).unwrap();
+        let request: Request = get_files_request();
+        write_request(&mut socket, &request);
+        let incoming_request: IncomingRequest = requests_rx.recv().unwrap();
+        assert_eq!(incoming_request.request.uuid(), request.uuid());
+
+        drop(socket);
+        handle.join().unwrap();
+
+        let disconnected_client: DisconnectedClient = disconnected_clients_rx.recv().unwrap();
+        assert_eq!(disconnected_client.num_requests, 1);
+        assert!(responses_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_bad_request_response() {
+        let request: Request = get_files_request();
+        let bytes: Vec<u8> = bincode::serialize(&request).unwrap();
+        let error: DecodeError = wire::decode::<Request>(&bytes[..30]).unwrap_err();
+
+        let response: Response = bad_request_response(&bytes[..30], error).unwrap();
+
+        assert_eq!(response.uuid(), request.uuid());
+        assert!(response.last());
+        assert!(matches!(
+            response.params(),
+            ResponseParams::BadRequest(params) if params.error().starts_with("The message is malformed")
+        ));
+        assert!(
+            bad_request_response(&bytes[..10], DecodeError::Malformed(String::new())).is_none()
+        );
+    }
+}
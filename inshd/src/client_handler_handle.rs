@@ -12,6 +12,8 @@ pub struct ClientHandlerHandle {
     pub client: Uuid,
     /// The handle to the client handler thread.
     pub handle: JoinHandle<()>,
+    /// The handle to the thread writing responses to the client.
+    pub writer_handle: JoinHandle<()>,
     /// The write side of a pipe for telling the client handler to stop.
     pub stop_tx: PipeWriter,
 }
@@ -5,7 +5,6 @@ use crate::stop::Stop;
 
 use std::collections::{HashMap, HashSet};
 use std::io::Write;
-use std::thread::JoinHandle;
 
 use crossbeam::channel::Receiver;
 use crossbeam::select;
@@ -46,7 +45,7 @@ impl ClientHandlerMonitor {
                     };
 
                     if pending_disconnected_clients.remove(&client_handler_handle.client) {
-                        ClientHandlerMonitor::join_client_handler(&client_handler_handle.client, client_handler_handle.handle);
+                        ClientHandlerMonitor::join_client_handler(client_handler_handle);
                         continue;
                     }
 
@@ -61,9 +60,14 @@ impl ClientHandlerMonitor {
                         }
                     };
 
+                    log::info!(
+                        "Client {} disconnected after making {} requests.",
+                        disconnected_client.client_uuid,
+                        disconnected_client.num_requests
+                    );
                     match client_uuid_to_handle.remove(&disconnected_client.client_uuid) {
                         Some(handle) => {
-                            ClientHandlerMonitor::join_client_handler(&disconnected_client.client_uuid, handle.handle);
+                            ClientHandlerMonitor::join_client_handler(handle);
                         },
                         None => {
                             pending_disconnected_clients.insert(disconnected_client.client_uuid);
@@ -84,29 +88,33 @@ impl ClientHandlerMonitor {
         while !pending_disconnected_clients.is_empty() {
             let client_handler_handle: ClientHandlerHandle =
                 self.client_handler_handles_rx.recv().unwrap();
-            ClientHandlerMonitor::join_client_handler(
-                &client_handler_handle.client,
-                client_handler_handle.handle,
-            );
             pending_disconnected_clients.remove(&client_handler_handle.client);
+            ClientHandlerMonitor::join_client_handler(client_handler_handle);
         }
 
         // Join all the client handlers.
-        for (client, mut handle) in client_uuid_to_handle.into_iter() {
+        for (_client, mut handle) in client_uuid_to_handle.into_iter() {
             let _ = handle.stop_tx.write(&[1; 1]).unwrap();
-            ClientHandlerMonitor::join_client_handler(&client, handle.handle);
+            ClientHandlerMonitor::join_client_handler(handle);
         }
 
         log::info!("Client handler monitor stopping...");
     }
 
-    /// Join a client handler thread.
-    fn join_client_handler(disconnected_client: &Uuid, handle: JoinHandle<()>) {
+    /// Join a client handler thread and the thread writing responses to the client.
+    ///
+    /// The writer stops once the requests of the client are done (they are cancelled when the
+    /// client disconnects).
+    fn join_client_handler(handle: ClientHandlerHandle) {
+        let disconnected_client: Uuid = handle.client;
         log::info!(
             "Waiting for client handler for client {} to stop...",
             disconnected_client
         );
-        let _ = handle.join();
+        let _ = handle.handle.join();
         log::info!("Client handler for client {} stopped.", disconnected_client);
+
+        let _ = handle.writer_handle.join();
+        log::info!("Client writer for client {} stopped.", disconnected_client);
     }
 }
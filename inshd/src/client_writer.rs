@@ -0,0 +1,182 @@
+//! Writes responses to a client.
+use insh_api::Response;
+
+use std::io::Write;
+use std::os::unix::net::UnixStream;
+
+use crossbeam::channel::Receiver;
+use typed_builder::TypedBuilder;
+use uuid::Uuid;
+
+/// The number of responses that can be queued for a client before the requests of the client wait
+/// for the client to read them.
+pub const OUTGOING_QUEUE_CAPACITY: usize = 64;
+
+/// Writes responses to a client.
+///
+/// The responses are queued in a bounded channel so that a client which reads slowly makes the
+/// requests producing the responses wait (instead of the responses buffering without bound).
+#[derive(TypedBuilder)]
+pub struct ClientWriter {
+    /// The UUID of the client.
+    client_uuid: Uuid,
+    /// The stream of the client.
+    stream: UnixStream,
+    /// A receiver of the responses for the client.
+    responses_rx: Receiver<Response>,
+}
+
+impl ClientWriter {
+    /// Run the client writer (until the client disconnects or there are no more responses).
+    pub fn run(&mut self) {
+        log::info!("Client writer running for client {}.", self.client_uuid);
+
+        let mut response_buffer: Vec<u8> = Vec::new();
+        for response in self.responses_rx.iter() {
+            let response_uuid: Uuid = *response.uuid();
+            log::debug!("Writing response {}...", response_uuid);
+
+            response_buffer.clear();
+            if let Err(error) = bincode::serialize_into(&mut response_buffer, &response) {
+                log::error!("Failed to serialize response {}: {}", response_uuid, error);
+                continue;
+            }
+            let length: u64 = response_buffer.len() as u64;
+
+            if let Err(error) = self.stream.write_all(&length.to_be_bytes()) {
+                log::error!("Failed to write length: {}", error);
+                break;
+            }
+            if let Err(error) = self.stream.write_all(&response_buffer) {
+                log::error!("Failed to write response: {}", error);
+                break;
+            }
+            log::debug!("Wrote response {}.", response_uuid);
+        }
+
+        // NOTE: Dropping the receiver makes the requests of the client stop sending responses.
+        log::info!("Client writer stopping for client {}...", self.client_uuid);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossbeam::channel::{self, SendTimeoutError, Sender};
+    use insh_api::{ResponseParams, TouchFileResponseParams};
+    use std::io::Read;
+    use std::path::PathBuf;
+    use std::thread::{self, JoinHandle};
+    use std::time::{Duration, Instant, SystemTime};
+
+    /// Return a response to the request `uuid`.
+    fn response(uuid: Uuid) -> Response {
+        Response::builder()
+            .uuid(uuid)
+            .last(true)
+            .params(ResponseParams::TouchFile(
+                TouchFileResponseParams::builder()
+                    .path(PathBuf::from("/tmp/foo"))
+                    .result(Ok(SystemTime::UNIX_EPOCH))
+                    .build(),
+            ))
+            .build()
+    }
+
+    #[test]
+    fn test_writes_responses() {
+        let (mut socket, stream) = UnixStream::pair().unwrap();
+        let (responses_tx, responses_rx) = channel::bounded(OUTGOING_QUEUE_CAPACITY);
+        let mut client_writer = ClientWriter::builder()
+            .client_uuid(Uuid::new_v4())
+            .stream(stream)
+            .responses_rx(responses_rx)
+            .build();
+        let handle: JoinHandle<()> = thread::spawn(move || client_writer.run());
+
+        let uuids: Vec<Uuid> = vec![Uuid::new_v4(), Uuid::new_v4()];
+        for uuid in &uuids {
+            responses_tx.send(response(*uuid)).unwrap();
+        }
+        drop(responses_tx);
+        handle.join().unwrap();
+
+        for uuid in &uuids {
+            let mut length_buffer: [u8; 8] = [0; 8];
+            socket.read_exact(&mut length_buffer).unwrap();
+            let mut response_buffer: Vec<u8> = vec![0; u64::from_be_bytes(length_buffer) as usize];
+            socket.read_exact(&mut response_buffer).unwrap();
+            let response: Response = bincode::deserialize(&response_buffer).unwrap();
+            assert_eq!(response.uuid(), uuid);
+        }
+    }
+
+    /// Start a client writer and return its sender of responses, the socket of the client, and the
+    /// handle of the writer.
+    fn start() -> (Sender<Response>, UnixStream, JoinHandle<()>) {
+        let (socket, stream) = UnixStream::pair().unwrap();
+        let (responses_tx, responses_rx) = channel::bounded(OUTGOING_QUEUE_CAPACITY);
+        let mut client_writer = ClientWriter::builder()
+            .client_uuid(Uuid::new_v4())
+            .stream(stream)
+            .responses_rx(responses_rx)
+            .build();
+        let handle: JoinHandle<()> = thread::spawn(move || client_writer.run());
+        (responses_tx, socket, handle)
+    }
+
+    #[test]
+    fn test_responses_for_disconnected_clients_are_dropped() {
+        // The first client disconnects abruptly before its request is responded to.
+        let (responses_tx, socket, handle) = start();
+        drop(socket);
+        let (other_responses_tx, mut other_socket, other_handle) = start();
+
+        // Once its writer notices, the responses to the disconnected client can't be queued (so
+        // its requests stop), while the responses to the other client are still written.
+        let start = Instant::now();
+        while responses_tx.send(response(Uuid::new_v4())).is_ok() {
+            assert!(start.elapsed() < Duration::from_secs(5));
+        }
+        handle.join().unwrap();
+        let other_request_uuid: Uuid = Uuid::new_v4();
+        other_responses_tx
+            .send(response(other_request_uuid))
+            .unwrap();
+        let mut length_buffer: [u8; 8] = [0; 8];
+        other_socket.read_exact(&mut length_buffer).unwrap();
+        let mut response_buffer: Vec<u8> = vec![0; u64::from_be_bytes(length_buffer) as usize];
+        other_socket.read_exact(&mut response_buffer).unwrap();
+        let other_response: Response = bincode::deserialize(&response_buffer).unwrap();
+        assert_eq!(other_response.uuid(), &other_request_uuid);
+
+        drop(other_responses_tx);
+        other_handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_slow_client() {
+        let (socket, stream) = UnixStream::pair().unwrap();
+        let (responses_tx, responses_rx) = channel::bounded(1);
+        let mut client_writer = ClientWriter::builder()
+            .client_uuid(Uuid::new_v4())
+            .stream(stream)
+            .responses_rx(responses_rx)
+            .build();
+        let handle: JoinHandle<()> = thread::spawn(move || client_writer.run());
+
+        // The client never reads, so sending waits once the socket buffer and the queue are full.
+        let waited: bool = (0..1_000_000).any(|_| {
+            matches!(
+                responses_tx.send_timeout(response(Uuid::new_v4()), Duration::from_millis(100)),
+                Err(SendTimeoutError::Timeout(_))
+            )
+        });
+        assert!(waited);
+
+        // When the client disconnects, the writer stops and responses can not be sent anymore.
+        drop(socket);
+        handle.join().unwrap();
+        assert!(responses_tx.send(response(Uuid::new_v4())).is_err());
+    }
+}
@@ -2,9 +2,10 @@
 use crate::client::Client;
 use crate::client_handler::ClientHandler;
 use crate::client_handler_handle::ClientHandlerHandle;
-use crate::client_request::ClientRequest;
+use crate::client_writer::{ClientWriter, OUTGOING_QUEUE_CAPACITY};
 use crate::disconnected_client::DisconnectedClient;
-use insh_api::Request;
+use crate::incoming_request::IncomingRequest;
+use insh_api::Response;
 
 use std::io::Result as IOResult;
 use std::os::fd::AsRawFd;
@@ -15,7 +16,7 @@ use std::os::unix::net::UnixStream;
 use std::thread;
 use std::thread::JoinHandle;
 
-use crossbeam::channel::Sender;
+use crossbeam::channel::{self, Receiver, Sender};
 use nix::sys::select::select;
 use nix::sys::select::FdSet;
 use os_pipe::PipeReader;
@@ -26,12 +27,8 @@ use typed_builder::TypedBuilder;
 pub struct ConnHandler {
     /// A unix socket listener.
     listener: UnixListener,
-    /// A sender of information about a client.
-    new_clients_tx: Sender<Client>,
     /// A sender of incoming requests (from clients).
-    incoming_requests_tx: Sender<Request>,
-    /// A Sender of client requests.
-    client_requests_tx: Sender<ClientRequest>,
+    incoming_requests_tx: Sender<IncomingRequest>,
     /// Senders of disconnected client uuids.
     disconnected_clients_txs: Vec<Sender<DisconnectedClient>>,
     /// A sender of client handler thread handles.
@@ -85,12 +82,29 @@ impl ConnHandler {
 
                 let client: Client = Client::builder().stream(stream).build();
                 log::info!("New client {}.", client.uuid());
-                let requests: Sender<Request> = self.incoming_requests_tx.clone();
+
+                // Create and spawn a thread to write responses to the client from a bounded queue
+                // (so that a client reading slowly slows down its requests).
+                let (responses_tx, responses_rx): (Sender<Response>, Receiver<Response>) =
+                    channel::bounded(OUTGOING_QUEUE_CAPACITY);
+                let mut client_writer: ClientWriter = ClientWriter::builder()
+                    .client_uuid(*client.uuid())
+                    .stream(client.stream.try_clone().unwrap())
+                    .responses_rx(responses_rx)
+                    .build();
+                let name: String = format!("client-writer-{}", client_num).to_string();
+                let writer_handle: JoinHandle<()> = thread::Builder::new()
+                    .name(name)
+                    .spawn(move || client_writer.run())
+                    .unwrap();
+
+                let requests: Sender<IncomingRequest> = self.incoming_requests_tx.clone();
                 let (stop_rx, stop_tx) = os_pipe::pipe().unwrap();
+                let client_uuid = *client.uuid();
                 let mut client_handler: ClientHandler = ClientHandler::builder()
-                    .client(client.try_clone().unwrap())
+                    .client(client)
                     .requests(requests)
-                    .client_requests_tx(self.client_requests_tx.clone())
+                    .responses_tx(responses_tx)
                     .disconnected_clients_txs(self.disconnected_clients_txs.clone())
                     .stop_rx(stop_rx)
                     .build();
@@ -102,16 +116,14 @@ impl ConnHandler {
 
                 // Send the client handler handle to the client handler monitor thread.
                 let client_handler_handle: ClientHandlerHandle = ClientHandlerHandle::builder()
-                    .client(*client.uuid())
+                    .client(client_uuid)
                     .handle(handle)
+                    .writer_handle(writer_handle)
                     .stop_tx(stop_tx)
                     .build();
                 self.client_handler_handles_tx
                     .send(client_handler_handle)
                     .unwrap();
-
-                // Inform the response handler thread of the new client.
-                self.new_clients_tx.send(client).unwrap();
                 client_num += 1;
             }
         }
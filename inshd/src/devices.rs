@@ -0,0 +1,87 @@
+//! Waits for devices (and network shares) to be mounted or unmounted.
+use std::fs;
+use std::time::{Duration, Instant};
+
+use insh_api::MAX_WAIT_FOR_DEVICES;
+
+/// How often the mounted devices are checked for changes while waiting for them to change.
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Waits for a device to be mounted or unmounted without blocking (by being polled).
+pub struct DevicesWait {
+    /// The mount table from when waiting started.
+    before: Vec<String>,
+    /// When to stop waiting.
+    deadline: Instant,
+    /// When the mount table was last checked.
+    polled: Instant,
+}
+
+impl DevicesWait {
+    /// Start waiting up to `wait` (at most [`MAX_WAIT_FOR_DEVICES`]) for a device to be mounted or
+    /// unmounted.
+    pub fn new(wait: Duration) -> Self {
+        let now: Instant = Instant::now();
+        Self {
+            before: mounts(),
+            deadline: now + wait.min(MAX_WAIT_FOR_DEVICES),
+            polled: now,
+        }
+    }
+
+    /// Return whether a device was mounted or unmounted once one was (or once waiting times out),
+    /// or `None` if waiting should be polled again later.
+    pub fn poll(&mut self) -> Option<bool> {
+        let now: Instant = Instant::now();
+        let timed_out: bool = now >= self.deadline;
+        if !timed_out && now.duration_since(self.polled) < WAIT_POLL_INTERVAL {
+            return None;
+        }
+        self.polled = now;
+
+        if mounts() != self.before {
+            return Some(true);
+        }
+        match timed_out {
+            true => Some(false),
+            false => None,
+        }
+    }
+}
+
+/// Return the lines of the mount table.
+#[cfg(target_os = "linux")]
+fn mounts() -> Vec<String> {
+    match fs::read_to_string("/proc/self/mounts") {
+        Ok(mounts) => mounts.lines().map(String::from).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Return the names of the mounted volumes.
+#[cfg(not(target_os = "linux"))]
+fn mounts() -> Vec<String> {
+    let mut volumes: Vec<String> = match fs::read_dir("/Volumes") {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    volumes.sort();
+    volumes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_devices_wait_times_out() {
+        let mut wait: DevicesWait = DevicesWait::new(Duration::from_millis(10));
+
+        assert_eq!(wait.poll(), None);
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(wait.poll(), Some(false));
+    }
+}
@@ -0,0 +1,96 @@
+//! Reads data derived from the extended attributes of files.
+use std::ffi::OsString;
+use std::path::Path;
+
+use file_info::{ExtendedAttributes, Tag};
+
+/// The name of the extended attribute that stores the Finder tags on macOS.
+#[cfg(target_os = "macos")]
+const TAGS_ATTRIBUTE: &str = "com.apple.metadata:_kMDItemUserTags";
+
+/// The name of the extended attribute that stores tags on Linux (used by KDE for example).
+#[cfg(not(target_os = "macos"))]
+const TAGS_ATTRIBUTE: &str = "user.xdg.tags";
+
+/// The prefix of the names of user extended attributes.
+const USER_ATTRIBUTE_PREFIX: &str = "user.";
+
+/// Return the data derived from the extended attributes of a file.
+pub fn read_extended_attributes(path: &Path) -> ExtendedAttributes {
+    let names: Vec<OsString> = match xattr::list(path) {
+        Ok(names) => names.collect(),
+        Err(error) => {
+            log::warn!("Error listing extended attributes of {:?}: {}", path, error);
+            return ExtendedAttributes::default();
+        }
+    };
+
+    let mut tags: Vec<Tag> = Vec::new();
+    let mut attributes: Vec<(String, String)> = Vec::new();
+    for name in names {
+        let name: String = name.to_string_lossy().to_string();
+        let value: Vec<u8> = match xattr::get(path, &name) {
+            Ok(Some(value)) => value,
+            Ok(None) => continue,
+            Err(error) => {
+                log::warn!(
+                    "Error getting extended attribute {} of {:?}: {}",
+                    name,
+                    path,
+                    error
+                );
+                continue;
+            }
+        };
+
+        if name == TAGS_ATTRIBUTE {
+            tags = parse_tags(&value);
+        } else if name.starts_with(USER_ATTRIBUTE_PREFIX) {
+            attributes.push((name, String::from_utf8_lossy(&value).to_string()));
+        }
+    }
+
+    ExtendedAttributes::builder()
+        .tags(tags)
+        .attributes(attributes)
+        .build()
+}
+
+/// Parse the Finder tags of a file.
+///
+/// The tags are stored as a binary property list of strings where each string is the name of the
+/// tag optionally followed by a newline and the number of the color of the tag.
+#[cfg(target_os = "macos")]
+fn parse_tags(value: &[u8]) -> Vec<Tag> {
+    let strings: Vec<String> = match plist::from_bytes(value) {
+        Ok(strings) => strings,
+        Err(error) => {
+            log::warn!("Error parsing tags: {}", error);
+            return Vec::new();
+        }
+    };
+
+    strings
+        .iter()
+        .map(|string| match string.split_once('\n') {
+            Some((name, color)) => Tag::builder()
+                .name(name)
+                .color(color.parse::<u8>().unwrap_or_default().into())
+                .build(),
+            None => Tag::builder().name(string).build(),
+        })
+        .collect()
+}
+
+/// Parse the tags of a file.
+///
+/// The tags are stored as a comma separated list of tag names.
+#[cfg(not(target_os = "macos"))]
+fn parse_tags(value: &[u8]) -> Vec<Tag> {
+    String::from_utf8_lossy(value)
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| Tag::builder().name(name).build())
+        .collect()
+}
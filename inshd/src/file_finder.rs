@@ -1,4 +1,6 @@
 //! Finds files.
+use crate::cancellation_token::CancellationToken;
+use crate::file_system_health::FileSystemHealth;
 use path_finder::Entry;
 use path_finder::NewPathFinderError;
 use path_finder::PathFinder;
@@ -14,6 +16,10 @@ use typed_builder::TypedBuilder;
 pub struct FileFinder {
     /// A sender of results of finding files.
     results_tx: Sender<FindFilesResult>,
+    /// A token for stopping finding files early.
+    cancellation_token: CancellationToken,
+    /// The health of file systems (the directories on slow file systems are not walked).
+    file_system_health: FileSystemHealth,
 }
 
 impl FileFinder {
@@ -21,23 +27,35 @@ impl FileFinder {
     pub fn run(&mut self, options: FileFinderOptions) {
         log::info!("File finder running...");
 
-        let mut path_finder = match PathFinder::new(&options.dir, &options.pattern) {
+        let mut path_finder = match PathFinder::new(
+            &options.dir,
+            &options.pattern,
+            options.same_file_system,
+            options.unicode_matching,
+            options.respect_ignore,
+            self.file_system_health.skip_slow_dirs(&options.dir),
+        ) {
             Ok(path_finder) => path_finder,
             Err(error) => {
-                self.results_tx
-                    .send(Err(FindFilesError::FailedToConstructPathFinder(error)))
-                    .unwrap();
+                let _ = self
+                    .results_tx
+                    .send(Err(FindFilesError::FailedToConstructPathFinder(error)));
                 return;
             }
         };
 
         loop {
+            if self.cancellation_token.is_cancelled() {
+                log::info!("Finding files was cancelled.");
+                break;
+            }
+
             let entry: Option<Entry> = path_finder.next();
             let entry: Entry = match entry {
                 Some(entry) => entry,
                 None => {
                     log::info!("No more entries.");
-                    self.results_tx.send(Ok(None)).unwrap();
+                    let _ = self.results_tx.send(Ok(None));
                     break;
                 }
             };
@@ -63,6 +81,12 @@ pub struct FileFinderOptions {
     /// A pattern to look for.
     #[builder(setter(into))]
     pub pattern: String,
+    /// Whether to stay on the file system of the directory.
+    pub same_file_system: bool,
+    /// Whether to case fold and normalize the pattern and the file names.
+    pub unicode_matching: bool,
+    /// Whether to skip hidden files and the files ignored by ignore files.
+    pub respect_ignore: bool,
 }
 
 /// An error finding files.
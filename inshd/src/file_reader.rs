@@ -0,0 +1,184 @@
+//! Reads chunks of files.
+use std::fs::{self, File, Metadata};
+use std::io::{Error as IOError, ErrorKind as IOErrorKind, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use insh_api::{FileChunk, ReadFileError, ReadFileResult, MAX_READ_FILE_LEN, MAX_READ_FILE_WAIT};
+
+/// How often a file is checked for growing while waiting for it to grow.
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Reads a chunk like [`read_chunk`], except that if the offset is the end of the file then waits
+/// for the file to grow without blocking (by being polled).
+pub struct ChunkWait {
+    /// The path of the file to read.
+    path: PathBuf,
+    /// The offset (in bytes) of the start of the chunk.
+    offset: u64,
+    /// The length (in bytes) of the chunk.
+    len: u64,
+    /// When to stop waiting (and return an empty chunk).
+    deadline: Instant,
+    /// When the file was last read.
+    polled: Option<Instant>,
+}
+
+impl ChunkWait {
+    /// Start waiting up to `wait` (at most [`MAX_READ_FILE_WAIT`]) for the file at `path` to grow
+    /// past `offset`.
+    pub fn new(path: &Path, offset: u64, len: u64, wait: Duration) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            offset,
+            len,
+            deadline: Instant::now() + wait.min(MAX_READ_FILE_WAIT),
+            polled: None,
+        }
+    }
+
+    /// Return the chunk once the file grows past the offset (or once waiting times out or reading
+    /// fails), or `None` if waiting should be polled again later.
+    pub fn poll(&mut self) -> Option<ReadFileResult> {
+        let now: Instant = Instant::now();
+        let timed_out: bool = now >= self.deadline;
+        if let Some(polled) = self.polled {
+            if !timed_out && now.duration_since(polled) < WAIT_POLL_INTERVAL {
+                return None;
+            }
+        }
+        self.polled = Some(now);
+
+        match read_chunk(&self.path, self.offset, self.len) {
+            Ok(chunk) if chunk.bytes().is_empty() && !timed_out => None,
+            result => Some(result),
+        }
+    }
+}
+
+/// Read the chunk of the file at `path` which starts at `offset` and is at most `len` bytes long
+/// (or at most [`MAX_READ_FILE_LEN`] bytes long if `len` is larger).
+///
+/// Only regular files are read so that reading a named pipe or a device never blocks or never
+/// ends. The offset may be the size of the file (in which case the chunk is empty) but not past it.
+pub fn read_chunk(path: &Path, offset: u64, len: u64) -> ReadFileResult {
+    let metadata: Metadata = fs::metadata(path).map_err(read_file_error)?;
+    if !metadata.is_file() {
+        return Err(ReadFileError::NotAFile);
+    }
+
+    let mut file: File = File::open(path).map_err(read_file_error)?;
+    // NOTE: Use the size from the opened file in case the path was replaced after it was checked.
+    let metadata: Metadata = file.metadata().map_err(read_file_error)?;
+    if !metadata.is_file() {
+        return Err(ReadFileError::NotAFile);
+    }
+    let file_size: u64 = metadata.len();
+
+    if offset > file_size {
+        return Err(ReadFileError::OffsetPastEnd { offset, file_size });
+    }
+
+    file.seek(SeekFrom::Start(offset))
+        .map_err(read_file_error)?;
+
+    let len: u64 = len.min(MAX_READ_FILE_LEN);
+    let mut bytes: Vec<u8> = Vec::with_capacity(len.min(file_size - offset) as usize);
+    file.take(len)
+        .read_to_end(&mut bytes)
+        .map_err(read_file_error)?;
+
+    Ok(FileChunk::builder()
+        .offset(offset)
+        .bytes(bytes)
+        .file_size(file_size)
+        .build())
+}
+
+/// Return the error for an IO error that occurred while reading a file.
+fn read_file_error(error: IOError) -> ReadFileError {
+    match error.kind() {
+        IOErrorKind::NotFound => ReadFileError::FileDoesNotExist,
+        IOErrorKind::PermissionDenied => ReadFileError::PermissionDenied,
+        _ => ReadFileError::Other(error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::path::PathBuf;
+    use test_case::test_case;
+
+    /// Return the path of a new temporary file with the contents `bytes`.
+    fn temp_file(name: &str, bytes: &[u8]) -> PathBuf {
+        let path: PathBuf =
+            env::temp_dir().join(format!("inshd-file-reader-{}-{}", std::process::id(), name));
+        fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test_case(0, 5, Ok((b"01234".to_vec(), false)); "start")]
+    #[test_case(5, 100, Ok((b"56789".to_vec(), true)); "end")]
+    #[test_case(10, 5, Ok((vec![], true)); "offset at end")]
+    #[test_case(11, 5, Err(ReadFileError::OffsetPastEnd { offset: 11, file_size: 10 }); "offset past end")]
+    fn test_read_chunk(offset: u64, len: u64, expected: Result<(Vec<u8>, bool), ReadFileError>) {
+        let path: PathBuf = temp_file(&format!("{}-{}", offset, len), b"0123456789");
+
+        let result: Result<(Vec<u8>, bool), ReadFileError> =
+            read_chunk(&path, offset, len).map(|chunk| (chunk.bytes().to_vec(), chunk.eof()));
+
+        fs::remove_file(&path).unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_read_chunk_limits_len() {
+        let bytes: Vec<u8> = vec![b'a'; MAX_READ_FILE_LEN as usize + 1];
+        let path: PathBuf = temp_file("large", &bytes);
+
+        let chunk: FileChunk = read_chunk(&path, 0, u64::MAX).unwrap();
+
+        fs::remove_file(&path).unwrap();
+        assert_eq!(chunk.bytes().len() as u64, MAX_READ_FILE_LEN);
+        assert!(!chunk.eof());
+    }
+
+    #[test]
+    fn test_chunk_wait() {
+        let path: PathBuf = temp_file("wait", b"01234");
+
+        let timed_out: FileChunk = ChunkWait::new(&path, 5, 5, Duration::ZERO)
+            .poll()
+            .unwrap()
+            .unwrap();
+        let mut wait: ChunkWait = ChunkWait::new(&path, 5, 5, Duration::from_secs(5));
+        let waiting: Option<ReadFileResult> = wait.poll();
+        fs::write(&path, b"0123456789").unwrap();
+        std::thread::sleep(WAIT_POLL_INTERVAL);
+        let grown: FileChunk = wait.poll().unwrap().unwrap();
+
+        fs::remove_file(&path).unwrap();
+        assert!(timed_out.bytes().is_empty());
+        assert!(waiting.is_none());
+        assert_eq!(grown.bytes(), b"56789");
+    }
+
+    #[test]
+    fn test_read_chunk_of_dir() {
+        let result: ReadFileResult = read_chunk(&env::temp_dir(), 0, 1);
+
+        assert_eq!(result.map(|_| ()), Err(ReadFileError::NotAFile));
+    }
+
+    #[test]
+    fn test_read_chunk_of_missing_file() {
+        let path: PathBuf = env::temp_dir().join("inshd-file-reader-missing");
+
+        let result: ReadFileResult = read_chunk(&path, 0, 1);
+
+        assert_eq!(result.map(|_| ()), Err(ReadFileError::FileDoesNotExist));
+    }
+}
@@ -0,0 +1,139 @@
+//! Tracks the health of directories on network file systems.
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use nix::sys::statfs::{self, Statfs};
+
+/// The latency of getting the metadata of a directory above which the file system of the directory
+/// is considered slow.
+const SLOW_THRESHOLD: Duration = Duration::from_millis(200);
+
+/// The magic numbers of network file systems (as reported by `statfs`).
+#[cfg(target_os = "linux")]
+const NETWORK_FILE_SYSTEM_TYPES: [u64; 7] = [
+    0x6969,     // NFS
+    0x517B,     // SMB
+    0xFF534D42, // CIFS
+    0xFE534D42, // SMB2
+    0x73757245, // Coda
+    0x5346414F, // AFS
+    0x65735546, // FUSE (for example, sshfs)
+];
+
+/// The names of network file systems (as reported by `statfs`).
+#[cfg(not(target_os = "linux"))]
+const NETWORK_FILE_SYSTEM_NAMES: [&str; 5] = ["nfs", "smbfs", "cifs", "afpfs", "webdav"];
+
+/// Tracks which directories are on slow network file systems.
+///
+/// The health is shared between all of the request handlers.
+#[derive(Clone, Default)]
+pub struct FileSystemHealth {
+    /// The directories that were found to be slow. Everything under these directories is also
+    /// considered to be slow.
+    slow_dirs: Arc<Mutex<HashSet<PathBuf>>>,
+}
+
+impl FileSystemHealth {
+    /// Check the health of the file system of a directory and return if the directory is slow.
+    ///
+    /// Only directories on network file systems are checked. A directory is slow if getting its
+    /// metadata takes longer than a threshold or if it is under a directory that was slow.
+    pub fn check(&self, dir: &Path) -> bool {
+        if !is_on_network_file_system(dir) {
+            return false;
+        }
+
+        let start: Instant = Instant::now();
+        let _ = fs::metadata(dir);
+        self.record(dir, start.elapsed())
+    }
+
+    /// Return a predicate of which directories a walk from `root` skips (the slow ones). If `root`
+    /// is slow itself, then nothing is skipped (because the slow tree is what the walk is for).
+    pub fn skip_slow_dirs(&self, root: &Path) -> impl Fn(&Path) -> bool + Send + Sync + 'static {
+        let root_is_slow: bool = self.check(root);
+        let file_system_health: FileSystemHealth = self.clone();
+        move |dir: &Path| {
+            if root_is_slow || !file_system_health.check(dir) {
+                return false;
+            }
+            log::info!("Not walking {:?} (it is on a slow file system).", dir);
+            true
+        }
+    }
+
+    /// Record the latency of getting the metadata of a directory (on a network file system) and
+    /// return if the directory is slow.
+    fn record(&self, dir: &Path, latency: Duration) -> bool {
+        let mut slow_dirs = self.slow_dirs.lock().unwrap();
+        if latency > SLOW_THRESHOLD {
+            log::warn!(
+                "Slow file system for {:?} (latency of {} ms).",
+                dir,
+                latency.as_millis()
+            );
+            slow_dirs.insert(dir.to_path_buf());
+            return true;
+        }
+
+        if slow_dirs.remove(dir) {
+            log::info!("File system for {:?} is no longer slow.", dir);
+        }
+        return dir.ancestors().any(|ancestor| slow_dirs.contains(ancestor));
+    }
+}
+
+/// Return if a path is on a network file system.
+fn is_on_network_file_system(path: &Path) -> bool {
+    let stats: Statfs = match statfs::statfs(path) {
+        Ok(stats) => stats,
+        Err(error) => {
+            log::warn!("Error getting file system stats for {:?}: {}", path, error);
+            return false;
+        }
+    };
+
+    #[cfg(target_os = "linux")]
+    return NETWORK_FILE_SYSTEM_TYPES.contains(&(stats.filesystem_type().0 as u64));
+
+    #[cfg(not(target_os = "linux"))]
+    return NETWORK_FILE_SYSTEM_NAMES.contains(&stats.filesystem_type_name());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(Duration::from_millis(1), false; "fast")]
+    #[test_case(SLOW_THRESHOLD, false; "at the threshold")]
+    #[test_case(SLOW_THRESHOLD + Duration::from_millis(1), true; "above the threshold")]
+    fn test_record_threshold(latency: Duration, expected: bool) {
+        let file_system_health = FileSystemHealth::default();
+        assert_eq!(
+            file_system_health.record(Path::new("/mnt/nfs"), latency),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_record_slow_dirs() {
+        let file_system_health = FileSystemHealth::default();
+        let fast: Duration = Duration::from_millis(1);
+        let slow: Duration = SLOW_THRESHOLD * 2;
+
+        assert!(file_system_health.record(Path::new("/mnt/nfs"), slow));
+        // The directories under a slow directory are slow even if they are fast themselves.
+        assert!(file_system_health.record(Path::new("/mnt/nfs/a"), fast));
+        assert!(!file_system_health.record(Path::new("/mnt/other"), fast));
+        assert!(!file_system_health.record(Path::new("/mnt/nfsother"), fast));
+
+        // A slow directory that becomes fast again is forgotten (and so are the ones under it).
+        assert!(!file_system_health.record(Path::new("/mnt/nfs"), fast));
+        assert!(!file_system_health.record(Path::new("/mnt/nfs/a"), fast));
+    }
+}
@@ -0,0 +1,282 @@
+//! Copies and moves files (and the files in directories).
+use crate::cancellation_token::CancellationToken;
+
+use std::fs::{self, File, Metadata, OpenOptions};
+use std::io::{Error as IOError, ErrorKind as IOErrorKind, Read, Write};
+use std::os::unix::fs::{symlink, OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crossbeam::channel::Sender;
+use nix::errno::Errno;
+use typed_builder::TypedBuilder;
+
+/// The size of the chunks that files are copied in (the copy can be cancelled between chunks).
+const CHUNK_SIZE: usize = 1 << 20;
+
+/// How often the progress of a copy is reported (so only the copies that take longer than this
+/// report their progress).
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// What happened while copying or moving a file.
+pub enum TransferEvent {
+    /// The number of bytes that were copied so far (out of the total).
+    Progress {
+        /// The number of bytes that were copied.
+        copied: u64,
+        /// The number of bytes that are copied in total.
+        total: u64,
+    },
+    /// Copying or moving the file is done (and the number of bytes that were copied).
+    Done(Result<u64, TransferError>),
+}
+
+/// An error copying or moving a file.
+pub enum TransferError {
+    /// A file already exists at the new path.
+    AlreadyExists,
+    /// The new path is inside of the directory that is copied or moved.
+    IntoItself,
+    /// An error reading or writing the files.
+    IO(IOError),
+}
+
+impl From<IOError> for TransferError {
+    fn from(error: IOError) -> Self {
+        Self::IO(error)
+    }
+}
+
+/// Copies or moves a file.
+#[derive(TypedBuilder)]
+pub struct FileTransfer {
+    /// A sender of what happened.
+    events_tx: Sender<TransferEvent>,
+    /// A token for stopping the copy early.
+    cancellation_token: CancellationToken,
+    /// When the progress was last reported.
+    #[builder(default, setter(skip))]
+    reported_at: Option<Instant>,
+}
+
+impl FileTransfer {
+    /// Copy (or move) the file at `from` to `to`.
+    pub fn run(&mut self, from: &Path, to: &Path, moving: bool) {
+        log::info!("File transfer running...");
+        let result: Result<u64, TransferError> = self.transfer(from, to, moving);
+        let _ = self.events_tx.send(TransferEvent::Done(result));
+        log::info!("File transfer done.");
+    }
+
+    /// Copy (or move) the file and return the number of bytes that were copied.
+    fn transfer(&mut self, from: &Path, to: &Path, moving: bool) -> Result<u64, TransferError> {
+        let metadata: Metadata = fs::symlink_metadata(from)?;
+        // NOTE: A file that is created at the new path after it is checked is still replaced, but
+        // this keeps pasting from replacing the files that can be seen in the browser.
+        if fs::symlink_metadata(to).is_ok() {
+            return Err(TransferError::AlreadyExists);
+        }
+        if metadata.is_dir() && to.starts_with(from) {
+            return Err(TransferError::IntoItself);
+        }
+
+        if moving {
+            match fs::rename(from, to) {
+                Ok(()) => return Ok(0),
+                // A file can only be renamed within a file system, so it is copied to another one
+                // (and then removed).
+                Err(error) if error.raw_os_error() == Some(Errno::EXDEV as i32) => {}
+                Err(error) => return Err(error.into()),
+            }
+        }
+
+        let total: u64 = size(from, &metadata)?;
+        let mut copied: u64 = 0;
+        self.reported_at = Some(Instant::now());
+        if let Err(error) = self.copy(from, to, &metadata, total, &mut copied) {
+            // Don't leave a partial copy behind.
+            let _ = remove(to);
+            return Err(error.into());
+        }
+
+        if moving {
+            remove(from)?;
+        }
+        Ok(copied)
+    }
+
+    /// Copy the file at `from` (with the metadata) to `to`, adding the number of bytes that are
+    /// copied to `copied`.
+    fn copy(
+        &mut self,
+        from: &Path,
+        to: &Path,
+        metadata: &Metadata,
+        total: u64,
+        copied: &mut u64,
+    ) -> Result<(), IOError> {
+        if metadata.is_symlink() {
+            return symlink(fs::read_link(from)?, to);
+        }
+
+        if metadata.is_dir() {
+            fs::create_dir(to)?;
+            for entry in fs::read_dir(from)? {
+                let entry = entry?;
+                let entry_metadata: Metadata = entry.metadata()?;
+                let entry_to: PathBuf = to.join(entry.file_name());
+                self.copy(&entry.path(), &entry_to, &entry_metadata, total, copied)?;
+            }
+            return fs::set_permissions(to, metadata.permissions());
+        }
+
+        let mut reader: File = File::open(from)?;
+        let mut writer: File = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(metadata.permissions().mode())
+            .open(to)?;
+        let mut buffer: Vec<u8> = vec![0; CHUNK_SIZE];
+        loop {
+            if self.cancellation_token.is_cancelled() {
+                return Err(IOError::new(
+                    IOErrorKind::Interrupted,
+                    "the copy was cancelled",
+                ));
+            }
+            let len: usize = match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(len) => len,
+                Err(error) if error.kind() == IOErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            writer.write_all(&buffer[..len])?;
+            *copied += len as u64;
+            self.report(*copied, total);
+        }
+        Ok(())
+    }
+
+    /// Report the progress (if it wasn't reported recently).
+    fn report(&mut self, copied: u64, total: u64) {
+        let now = Instant::now();
+        if self
+            .reported_at
+            .is_some_and(|reported_at| now.duration_since(reported_at) < PROGRESS_INTERVAL)
+        {
+            return;
+        }
+        self.reported_at = Some(now);
+        let _ = self
+            .events_tx
+            .send(TransferEvent::Progress { copied, total });
+    }
+}
+
+/// Return the number of bytes in the file (or in the files in it if it is a directory).
+fn size(path: &Path, metadata: &Metadata) -> Result<u64, IOError> {
+    if !metadata.is_dir() {
+        return Ok(match metadata.is_file() {
+            true => metadata.len(),
+            false => 0,
+        });
+    }
+    let mut size: u64 = 0;
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        size += self::size(&entry.path(), &entry.metadata()?)?;
+    }
+    Ok(size)
+}
+
+/// Remove the file (and the files in it if it is a directory).
+fn remove(path: &Path) -> Result<(), IOError> {
+    match fs::symlink_metadata(path)?.is_dir() {
+        true => fs::remove_dir_all(path),
+        false => fs::remove_file(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::env;
+
+    use crossbeam::channel::{self, Receiver};
+    use test_case::test_case;
+
+    /// Transfer `from` to `to` in a directory with a file `a`, a directory `d` (with a file `f` and
+    /// a link `l` to it), and a file `b`, and return the files of the directory afterwards.
+    fn transfer(from: &str, to: &str, moving: bool) -> (Vec<String>, Vec<TransferEvent>) {
+        let dir: PathBuf = env::temp_dir().join(format!(
+            "inshd-file-transfer-{}-{}-{}-{}",
+            from,
+            to.replace('/', "-"),
+            moving,
+            std::process::id()
+        ));
+        fs::create_dir_all(dir.join("d")).unwrap();
+        fs::write(dir.join("a"), b"a").unwrap();
+        fs::write(dir.join("b"), b"b").unwrap();
+        fs::write(dir.join("d").join("f"), b"ff").unwrap();
+        symlink("f", dir.join("d").join("l")).unwrap();
+
+        let (events_tx, events_rx): (Sender<TransferEvent>, Receiver<TransferEvent>) =
+            channel::unbounded();
+        FileTransfer::builder()
+            .events_tx(events_tx)
+            .cancellation_token(CancellationToken::new())
+            .build()
+            .run(&dir.join(from), &dir.join(to), moving);
+        let events: Vec<TransferEvent> = events_rx.try_iter().collect();
+
+        let mut files: Vec<String> = Vec::new();
+        let mut dirs: Vec<PathBuf> = vec![dir.clone()];
+        while let Some(next) = dirs.pop() {
+            for entry in fs::read_dir(&next).unwrap() {
+                let path: PathBuf = entry.unwrap().path();
+                let relative: String = path.strip_prefix(&dir).unwrap().display().to_string();
+                match fs::symlink_metadata(&path).unwrap() {
+                    metadata if metadata.is_dir() => dirs.push(path),
+                    metadata if metadata.is_symlink() => files.push(format!(
+                        "{} -> {}",
+                        relative,
+                        fs::read_link(&path).unwrap().display()
+                    )),
+                    _ => files.push(format!(
+                        "{}: {}",
+                        relative,
+                        fs::read_to_string(&path).unwrap()
+                    )),
+                }
+            }
+        }
+        files.sort();
+
+        fs::remove_dir_all(&dir).unwrap();
+        (files, events)
+    }
+
+    #[test_case("a", "c", false, &["a: a", "b: b", "c: a", "d/f: ff", "d/l -> f"], Some(1); "copy file")]
+    #[test_case("d", "e", false, &["a: a", "b: b", "d/f: ff", "d/l -> f", "e/f: ff", "e/l -> f"], Some(2); "copy directory")]
+    #[test_case("a", "d/a", true, &["b: b", "d/a: a", "d/f: ff", "d/l -> f"], Some(0); "move file")]
+    #[test_case("a", "b", false, &["a: a", "b: b", "d/f: ff", "d/l -> f"], None; "already exists")]
+    #[test_case("d", "d/e", false, &["a: a", "b: b", "d/f: ff", "d/l -> f"], None; "into itself")]
+    fn test_transfer(
+        from: &str,
+        to: &str,
+        moving: bool,
+        expected_files: &[&str],
+        expected_copied: Option<u64>,
+    ) {
+        let (files, events) = transfer(from, to, moving);
+
+        assert_eq!(files, expected_files);
+        let copied: Option<u64> = match events.last() {
+            Some(TransferEvent::Done(result)) => result.as_ref().ok().copied(),
+            _ => panic!("The transfer wasn't done."),
+        };
+        assert_eq!(copied, expected_copied);
+    }
+}
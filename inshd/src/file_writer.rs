@@ -0,0 +1,296 @@
+//! Writes files.
+use std::fs::{self, File, Metadata, OpenOptions};
+use std::io::{Error as IOError, ErrorKind as IOErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use uuid::Uuid;
+
+use insh_api::{
+    FilePatch, FormatNotPreserved, WriteFileContent, WriteFileError, WriteFileResult, WrittenFile,
+};
+
+/// The byte order mark of UTF-8.
+const UTF8_BOM: &[u8] = b"\xef\xbb\xbf";
+
+/// Write the content to the file at `path`.
+///
+/// The content is written to a temporary file in the same directory which is then renamed over the
+/// file so that the file is never left partially written. If `expected_mtime` is given and the file
+/// was modified at a different time (or does not exist), then nothing is written and a conflict is
+/// returned. If the path is a symbolic link, then the file that it points to is written.
+///
+/// If `preserve_format` is true and the file exists, then the line endings and the byte order mark
+/// of the content are converted to those of the file (or the reason they could not be is returned).
+pub fn write_file(
+    path: &Path,
+    content: &WriteFileContent,
+    expected_mtime: Option<SystemTime>,
+    preserve_format: bool,
+) -> WriteFileResult {
+    let path: PathBuf = match fs::canonicalize(path) {
+        Ok(path) => path,
+        Err(error) if error.kind() == IOErrorKind::NotFound => path.to_path_buf(),
+        Err(error) => return Err(write_file_error(error)),
+    };
+
+    let metadata: Option<Metadata> = match fs::metadata(&path) {
+        Ok(metadata) => Some(metadata),
+        Err(error) if error.kind() == IOErrorKind::NotFound => None,
+        Err(error) => return Err(write_file_error(error)),
+    };
+    if let Some(metadata) = &metadata {
+        if !metadata.is_file() {
+            return Err(WriteFileError::NotAFile);
+        }
+    }
+
+    if let Some(expected_mtime) = expected_mtime {
+        let actual_mtime: Option<SystemTime> = match &metadata {
+            Some(metadata) => Some(metadata.modified().map_err(write_file_error)?),
+            None => None,
+        };
+        if actual_mtime != Some(expected_mtime) {
+            return Err(WriteFileError::Conflict { actual_mtime });
+        }
+    }
+
+    let is_patches: bool = matches!(content, WriteFileContent::Patches(_));
+    let original: Option<Vec<u8>> = if metadata.is_some() && (is_patches || preserve_format) {
+        Some(fs::read(&path).map_err(write_file_error)?)
+    } else {
+        None
+    };
+    let mut bytes: Vec<u8> = match content {
+        WriteFileContent::Full(bytes) => bytes.clone(),
+        WriteFileContent::Patches(patches) => match &original {
+            Some(original) => apply_patches(original, patches)?,
+            None => return Err(WriteFileError::FileDoesNotExist),
+        },
+    };
+    let mut format_not_preserved: Option<FormatNotPreserved> = None;
+    if let (true, Some(original)) = (preserve_format, &original) {
+        match preserve(original, bytes) {
+            Ok(preserved) => bytes = preserved,
+            Err((given, reason)) => {
+                bytes = given;
+                format_not_preserved = Some(reason);
+            }
+        }
+    }
+
+    let temp_path: PathBuf = temp_path(&path)?;
+    if let Err(error) = write_temp_file(&temp_path, &bytes, metadata.as_ref()) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(write_file_error(error));
+    }
+    if let Err(error) = fs::rename(&temp_path, &path) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(write_file_error(error));
+    }
+
+    let metadata: Metadata = fs::metadata(&path).map_err(write_file_error)?;
+    Ok(WrittenFile::builder()
+        .mtime(metadata.modified().map_err(write_file_error)?)
+        .file_size(metadata.len())
+        .format_not_preserved(format_not_preserved)
+        .build())
+}
+
+/// The line endings of a file.
+#[derive(Debug, PartialEq, Eq)]
+enum LineEndings {
+    /// The file has no line endings.
+    None,
+    /// The lines end with a line feed.
+    Lf,
+    /// The lines end with a carriage return and a line feed.
+    CrLf,
+    /// Some lines end with a line feed and some with a carriage return and a line feed.
+    Mixed,
+}
+
+impl LineEndings {
+    /// Return the line endings of the bytes.
+    fn of(bytes: &[u8]) -> Self {
+        let newlines: usize = bytes.iter().filter(|byte| **byte == b'\n').count();
+        let crlfs: usize = bytes.windows(2).filter(|pair| pair == b"\r\n").count();
+        match (newlines, crlfs) {
+            (0, _) => Self::None,
+            (_, 0) => Self::Lf,
+            (newlines, crlfs) if newlines == crlfs => Self::CrLf,
+            _ => Self::Mixed,
+        }
+    }
+}
+
+/// Return the bytes with the line endings and the byte order mark of the original content of the
+/// file. If that is not possible, then return the bytes as they are and the reason.
+fn preserve(original: &[u8], bytes: Vec<u8>) -> Result<Vec<u8>, (Vec<u8>, FormatNotPreserved)> {
+    if original.starts_with(b"\xff\xfe") || original.starts_with(b"\xfe\xff") {
+        return Err((bytes, FormatNotPreserved::Utf16));
+    }
+
+    let line_endings: LineEndings = LineEndings::of(original);
+    if line_endings == LineEndings::Mixed {
+        return Err((bytes, FormatNotPreserved::MixedLineEndings));
+    }
+
+    let body: &[u8] = bytes.strip_prefix(UTF8_BOM).unwrap_or(&bytes);
+    let mut preserved: Vec<u8> = Vec::with_capacity(bytes.len() + UTF8_BOM.len());
+    if original.starts_with(UTF8_BOM) {
+        preserved.extend_from_slice(UTF8_BOM);
+    }
+    match line_endings {
+        LineEndings::CrLf => {
+            for (index, byte) in body.iter().enumerate() {
+                if *byte == b'\n' && (index == 0 || body[index - 1] != b'\r') {
+                    preserved.push(b'\r');
+                }
+                preserved.push(*byte);
+            }
+        }
+        LineEndings::Lf => {
+            for (index, byte) in body.iter().enumerate() {
+                if *byte == b'\r' && body.get(index + 1) == Some(&b'\n') {
+                    continue;
+                }
+                preserved.push(*byte);
+            }
+        }
+        LineEndings::None | LineEndings::Mixed => preserved.extend_from_slice(body),
+    }
+    Ok(preserved)
+}
+
+/// Return the path of the temporary file that is written before being renamed to `path`.
+fn temp_path(path: &Path) -> Result<PathBuf, WriteFileError> {
+    let file_name: String = match path.file_name() {
+        Some(file_name) => file_name.to_string_lossy().to_string(),
+        None => return Err(WriteFileError::NotAFile),
+    };
+    Ok(path.with_file_name(format!(".{}.{}.insh-tmp", file_name, Uuid::new_v4())))
+}
+
+/// Write the bytes to the temporary file (with the permissions of the original file if there is
+/// one) and flush them to the disk.
+fn write_temp_file(
+    temp_path: &Path,
+    bytes: &[u8],
+    metadata: Option<&Metadata>,
+) -> Result<(), IOError> {
+    let mut file: File = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(temp_path)?;
+    file.write_all(bytes)?;
+    if let Some(metadata) = metadata {
+        file.set_permissions(metadata.permissions())?;
+    }
+    file.sync_all()
+}
+
+/// Return the bytes of the original content with the patches applied.
+///
+/// The offsets of the patches are offsets in the original content. The patches must be sorted by
+/// offset and must not overlap.
+fn apply_patches(original: &[u8], patches: &[FilePatch]) -> Result<Vec<u8>, WriteFileError> {
+    let mut bytes: Vec<u8> = Vec::with_capacity(original.len());
+    let mut position: u64 = 0;
+    for patch in patches {
+        if patch.offset() < position {
+            return Err(WriteFileError::InvalidPatch(format!(
+                "The patch at offset {} overlaps the previous patch or is out of order.",
+                patch.offset()
+            )));
+        }
+        let end: u64 = patch.offset().saturating_add(patch.len());
+        if end > original.len() as u64 {
+            return Err(WriteFileError::InvalidPatch(format!(
+                "The patch at offset {} ends past the end of the file ({} bytes).",
+                patch.offset(),
+                original.len()
+            )));
+        }
+        bytes.extend_from_slice(&original[position as usize..patch.offset() as usize]);
+        bytes.extend_from_slice(patch.bytes());
+        position = end;
+    }
+    bytes.extend_from_slice(&original[position as usize..]);
+    Ok(bytes)
+}
+
+/// Return the error for an IO error that occurred while writing a file.
+fn write_file_error(error: IOError) -> WriteFileError {
+    match error.kind() {
+        IOErrorKind::NotFound => WriteFileError::FileDoesNotExist,
+        IOErrorKind::PermissionDenied => WriteFileError::PermissionDenied,
+        _ => WriteFileError::Other(error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use test_case::test_case;
+
+    /// Return a patch replacing `len` bytes at `offset` with `bytes`.
+    fn patch(offset: u64, len: u64, bytes: &[u8]) -> FilePatch {
+        FilePatch::builder()
+            .offset(offset)
+            .len(len)
+            .bytes(bytes.to_vec())
+            .build()
+    }
+
+    #[test_case(vec![], Ok(b"hello world".to_vec()); "no patches")]
+    #[test_case(vec![patch(0, 5, b"goodbye")], Ok(b"goodbye world".to_vec()); "replace")]
+    #[test_case(vec![patch(5, 0, b",")], Ok(b"hello, world".to_vec()); "insert")]
+    #[test_case(vec![patch(5, 6, b"")], Ok(b"hello".to_vec()); "delete")]
+    #[test_case(vec![patch(0, 1, b"H"), patch(6, 1, b"W")], Ok(b"Hello World".to_vec()); "multiple")]
+    #[test_case(vec![patch(6, 1, b"W"), patch(0, 1, b"H")], Err(()); "out of order")]
+    #[test_case(vec![patch(0, 3, b""), patch(2, 1, b"")], Err(()); "overlapping")]
+    #[test_case(vec![patch(10, 2, b"")], Err(()); "past the end")]
+    fn test_apply_patches(patches: Vec<FilePatch>, expected: Result<Vec<u8>, ()>) {
+        let result: Result<Vec<u8>, ()> = apply_patches(b"hello world", &patches).map_err(|_| ());
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_write_file_conflict() {
+        let path: PathBuf =
+            env::temp_dir().join(format!("inshd-file-writer-{}", std::process::id()));
+        fs::write(&path, b"original").unwrap();
+        let mtime: SystemTime = fs::metadata(&path).unwrap().modified().unwrap();
+
+        let content = WriteFileContent::Full(b"new".to_vec());
+        let stale_mtime: SystemTime = mtime - std::time::Duration::from_secs(1);
+        let conflict: WriteFileResult = write_file(&path, &content, Some(stale_mtime), false);
+        let written: WriteFileResult = write_file(&path, &content, Some(mtime), false);
+
+        let bytes: Vec<u8> = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(conflict, Err(WriteFileError::Conflict { .. })));
+        assert!(written.is_ok());
+        assert_eq!(bytes, b"new");
+    }
+
+    #[test_case(b"a\nb\n", b"a\r\nc\n", Ok(b"a\nc\n".to_vec()); "lf")]
+    #[test_case(b"a\r\nb\r\n", b"a\nc\r\n", Ok(b"a\r\nc\r\n".to_vec()); "crlf")]
+    #[test_case(b"a", b"b\nc", Ok(b"b\nc".to_vec()); "no line endings")]
+    #[test_case(b"\xef\xbb\xbfa\n", b"b\n", Ok(b"\xef\xbb\xbfb\n".to_vec()); "add bom")]
+    #[test_case(b"a\n", b"\xef\xbb\xbfb\n", Ok(b"b\n".to_vec()); "remove bom")]
+    #[test_case(b"a\r\nb\n", b"c\n", Err(FormatNotPreserved::MixedLineEndings); "mixed")]
+    #[test_case(b"\xff\xfea\x00", b"b", Err(FormatNotPreserved::Utf16); "utf16")]
+    fn test_preserve(original: &[u8], bytes: &[u8], expected: Result<Vec<u8>, FormatNotPreserved>) {
+        let result: Result<Vec<u8>, FormatNotPreserved> = preserve(original, bytes.to_vec())
+            .map_err(|(given, reason)| {
+                assert_eq!(given, bytes);
+                reason
+            });
+
+        assert_eq!(result, expected);
+    }
+}
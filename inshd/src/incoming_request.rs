@@ -0,0 +1,18 @@
+//! A request from a client.
+use crate::cancellation_token::CancellationToken;
+use insh_api::{Request, Response};
+
+use crossbeam::channel::Sender;
+
+use typed_builder::TypedBuilder;
+
+/// A request from a client.
+#[derive(TypedBuilder)]
+pub struct IncomingRequest {
+    /// The request.
+    pub request: Request,
+    /// A token which is cancelled when the client cancels the request or disconnects.
+    pub cancellation_token: CancellationToken,
+    /// A sender of responses to the client (which waits when the client is reading slowly).
+    pub responses_tx: Sender<Response>,
+}
@@ -6,20 +6,30 @@ The insh daemon.
 #![allow(clippy::needless_return)]
 
 mod args;
+mod cancellation_token;
 mod client;
 mod client_handler;
 mod client_handler_handle;
 mod client_handler_monitor;
-mod client_request;
+mod client_writer;
 mod conn_handler;
+mod devices;
 mod disconnected_client;
+mod extended_attributes;
 mod file_finder;
+mod file_reader;
+mod file_system_health;
+mod file_transfer;
+mod file_writer;
+mod incoming_request;
 mod logging;
 mod paths;
+mod permission;
+mod phrase_search;
+mod processes;
 mod request_handler;
 mod request_handler_died;
 mod request_handler_manager;
-mod response_handler;
 mod scheduler;
 mod server;
 mod signal_handler;
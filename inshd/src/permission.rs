@@ -0,0 +1,120 @@
+//! Classifies why permission to read a directory was denied.
+use std::fs::{self, Metadata};
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+
+use nix::unistd::{getegid, geteuid, Gid};
+
+use insh_api::GetFilesError;
+
+/// The permission bit for reading (the bits of the other classes are shifted from it).
+const READ: u32 = 0o4;
+
+/// The permission bit for searching a directory (the execute bit).
+const SEARCH: u32 = 0o1;
+
+/// Return the error for reading the directory `dir` being denied.
+///
+/// Missing search permission on the directory or one of its ancestors and missing read permission
+/// on the directory are found from their modes. If the modes allow reading the directory, then it
+/// must have been denied by a security policy (such as SELinux, AppArmor, or an ACL).
+pub fn classify_permission_denied(dir: &Path) -> GetFilesError {
+    let uid: u32 = geteuid().as_raw();
+    let gids: Vec<u32> = groups().into_iter().map(Gid::as_raw).collect();
+
+    let mut ancestors: Vec<&Path> = dir.ancestors().skip(1).collect();
+    ancestors.reverse();
+    for ancestor in ancestors {
+        match fs::metadata(ancestor) {
+            Ok(metadata) if !allows(&metadata, uid, &gids, SEARCH) => {
+                return GetFilesError::NoSearchPermission {
+                    dir: ancestor.to_path_buf(),
+                };
+            }
+            Ok(_) => {}
+            Err(error) => {
+                log::warn!("Error getting metadata for {:?}: {}", ancestor, error);
+                return GetFilesError::PermissionDenied;
+            }
+        }
+    }
+
+    match fs::metadata(dir) {
+        Ok(metadata) if !allows(&metadata, uid, &gids, READ) => GetFilesError::NoReadPermission,
+        Ok(_) => GetFilesError::SecurityPolicy,
+        Err(error) => {
+            log::warn!("Error getting metadata for {:?}: {}", dir, error);
+            GetFilesError::PermissionDenied
+        }
+    }
+}
+
+/// Return the groups of the process.
+fn groups() -> Vec<Gid> {
+    let mut groups: Vec<Gid> = vec![getegid()];
+    #[cfg(not(target_os = "macos"))]
+    match nix::unistd::getgroups() {
+        Ok(supplementary_groups) => groups.extend(supplementary_groups),
+        Err(error) => log::warn!("Error getting the supplementary groups: {}", error),
+    }
+    groups
+}
+
+/// Return if the mode of a file allows the user `uid` in the groups `gids` the `permission`.
+fn allows(metadata: &Metadata, uid: u32, gids: &[u32], permission: u32) -> bool {
+    mode_allows(
+        metadata.mode(),
+        metadata.uid(),
+        metadata.gid(),
+        uid,
+        gids,
+        permission,
+    )
+}
+
+/// Return if the `mode` of a file owned by `owner` and `group` allows the user `uid` in the groups
+/// `gids` the `permission`.
+///
+/// Only the bits of the most specific class that the user is in apply (like the kernel).
+fn mode_allows(mode: u32, owner: u32, group: u32, uid: u32, gids: &[u32], permission: u32) -> bool {
+    // The superuser is only denied executing files without any execute bits (which does not
+    // apply to searching directories).
+    if uid == 0 {
+        return true;
+    }
+
+    let shift: u32 = if uid == owner {
+        6
+    } else if gids.contains(&group) {
+        3
+    } else {
+        0
+    };
+    mode & (permission << shift) != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(0o700, 1000, READ, true; "owner")]
+    #[test_case(0o070, 1000, READ, false; "owner without owner bits")]
+    #[test_case(0o050, 1001, SEARCH, true; "group")]
+    #[test_case(0o705, 1001, READ, false; "group without group bits")]
+    #[test_case(0o004, 1002, READ, true; "other")]
+    #[test_case(0o644, 1002, SEARCH, false; "other without search")]
+    #[test_case(0o000, 0, READ, true; "superuser")]
+    fn test_mode_allows(mode: u32, uid: u32, permission: u32, expected_allows: bool) {
+        let owner: u32 = 1000;
+        let group: u32 = 100;
+        let gids: Vec<u32> = match uid {
+            1001 => vec![1001, 100],
+            _ => vec![uid],
+        };
+        assert_eq!(
+            mode_allows(mode, owner, group, uid, &gids, permission),
+            expected_allows
+        );
+    }
+}
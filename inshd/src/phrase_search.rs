@@ -0,0 +1,79 @@
+//! Searches the contents of files for a phrase.
+use crate::cancellation_token::CancellationToken;
+use crate::file_system_health::FileSystemHealth;
+use phrase_searcher::{FileHit, PhraseSearcher};
+
+use std::path::PathBuf;
+
+use crossbeam::channel::Sender;
+use typed_builder::TypedBuilder;
+
+/// Searches the contents of files for a phrase.
+#[derive(TypedBuilder)]
+pub struct PhraseSearch {
+    /// A sender of results of searching files.
+    results_tx: Sender<SearchPhraseResult>,
+    /// A token for stopping searching early.
+    cancellation_token: CancellationToken,
+    /// The health of file systems (the directories on slow file systems are not searched).
+    file_system_health: FileSystemHealth,
+}
+
+impl PhraseSearch {
+    /// Run the phrase search.
+    pub fn run(&mut self, options: PhraseSearchOptions) {
+        log::info!("Phrase search running...");
+
+        // NOTE: The search stops once the phrase searcher is dropped.
+        let mut phrase_searcher = PhraseSearcher::new(
+            &options.dir,
+            &options.phrase,
+            options.same_file_system,
+            options.unicode_matching,
+            self.file_system_health.skip_slow_dirs(&options.dir),
+        );
+
+        loop {
+            if self.cancellation_token.is_cancelled() {
+                log::info!("Searching for the phrase was cancelled.");
+                break;
+            }
+
+            let file_hit: FileHit = match phrase_searcher.next() {
+                Some(file_hit) => file_hit,
+                None => {
+                    log::info!("No more file hits.");
+                    let _ = self.results_tx.send(None);
+                    break;
+                }
+            };
+
+            log::debug!("Found file hit {:?}.", file_hit.path());
+
+            if let Err(error) = self.results_tx.send(Some(file_hit)) {
+                log::error!("Error sending file hit: {}", error);
+                break;
+            }
+        }
+
+        log::info!("Phrase search stopping...");
+    }
+}
+
+/// Options for searching for a phrase.
+#[derive(TypedBuilder)]
+pub struct PhraseSearchOptions {
+    /// The directory to search the files in.
+    #[builder(setter(into))]
+    pub dir: PathBuf,
+    /// The phrase to search for.
+    #[builder(setter(into))]
+    pub phrase: String,
+    /// Whether to stay on the file system of the directory.
+    pub same_file_system: bool,
+    /// Whether to case fold and normalize the phrase and the lines.
+    pub unicode_matching: bool,
+}
+
+/// A result of searching for a phrase (a file hit, or nothing once all of the files were searched).
+pub type SearchPhraseResult = Option<FileHit>;
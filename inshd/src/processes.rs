@@ -0,0 +1,192 @@
+//! Lists processes and the files they have open (from `/proc`) and sends signals to them.
+use std::ffi::OsStr;
+use std::fs;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+use nix::errno::Errno;
+use nix::sys::signal::{self, Signal as NixSignal};
+use nix::unistd::Pid;
+
+use insh_api::{GetProcessesError, OpenFile, ProcessInfo, Signal, SignalProcessError};
+
+/// The directory with a directory for each process.
+const PROC: &str = "/proc";
+
+/// Return the processes whose working directory is `dir` or one of its descendants (ordered by
+/// their pids).
+///
+/// Processes whose working directory can't be read (because they belong to another user or exited)
+/// are skipped.
+#[cfg(target_os = "linux")]
+pub fn processes_in(dir: &Path) -> Result<Vec<ProcessInfo>, GetProcessesError> {
+    let mut processes: Vec<ProcessInfo> = Vec::new();
+    for (pid, proc_dir) in pids()? {
+        let cwd: PathBuf = match fs::read_link(proc_dir.join("cwd")) {
+            Ok(cwd) => cwd,
+            Err(_) => continue,
+        };
+        if !cwd.starts_with(dir) {
+            continue;
+        }
+        processes.push(
+            ProcessInfo::builder()
+                .pid(pid)
+                .command(command(&proc_dir))
+                .cwd(cwd)
+                .build(),
+        );
+    }
+    processes.sort_by_key(ProcessInfo::pid);
+    Ok(processes)
+}
+
+/// Return the processes whose working directory is `dir` or one of its descendants.
+#[cfg(not(target_os = "linux"))]
+pub fn processes_in(_dir: &Path) -> Result<Vec<ProcessInfo>, GetProcessesError> {
+    Err(GetProcessesError::Unsupported)
+}
+
+/// Return the files in `dir` (or one of its descendants) that are open by processes (ordered by
+/// their paths and then by their pids).
+///
+/// Only the files of the processes whose open files can be read (usually the processes of the same
+/// user) are found.
+#[cfg(target_os = "linux")]
+pub fn open_files_in(dir: &Path) -> Result<Vec<OpenFile>, GetProcessesError> {
+    let mut open_files: Vec<OpenFile> = Vec::new();
+    for (pid, proc_dir) in pids()? {
+        let fds = match fs::read_dir(proc_dir.join("fd")) {
+            Ok(fds) => fds,
+            Err(_) => continue,
+        };
+        let mut paths: Vec<PathBuf> = fds
+            .filter_map(Result::ok)
+            .filter_map(|fd| fs::read_link(fd.path()).ok())
+            .filter(|path| path.starts_with(dir))
+            .collect();
+        if paths.is_empty() {
+            continue;
+        }
+        paths.sort();
+        paths.dedup();
+
+        let command: String = command(&proc_dir);
+        open_files.extend(paths.into_iter().map(|path| {
+            OpenFile::builder()
+                .path(path)
+                .pid(pid)
+                .command(command.clone())
+                .build()
+        }));
+    }
+    open_files.sort_by(|a, b| a.path().cmp(b.path()).then(a.pid().cmp(&b.pid())));
+    Ok(open_files)
+}
+
+/// Return the files in `dir` (or one of its descendants) that are open by processes.
+#[cfg(not(target_os = "linux"))]
+pub fn open_files_in(_dir: &Path) -> Result<Vec<OpenFile>, GetProcessesError> {
+    Err(GetProcessesError::Unsupported)
+}
+
+/// Return the pid and the directory in `/proc` of each process.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn pids() -> Result<Vec<(u32, PathBuf)>, GetProcessesError> {
+    let entries =
+        fs::read_dir(PROC).map_err(|error| GetProcessesError::Other(error.to_string()))?;
+    Ok(entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+            Some((pid, entry.path()))
+        })
+        .collect())
+}
+
+/// Return the command line of the process with the directory `proc_dir` in `/proc` (or its name if
+/// it has no command line, like kernel threads).
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn command(proc_dir: &Path) -> String {
+    // NOTE: The arguments of the command line are separated (and ended) by NUL bytes.
+    let cmdline: Vec<u8> = fs::read(proc_dir.join("cmdline")).unwrap_or_default();
+    let args: Vec<String> = cmdline
+        .split(|byte| *byte == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| OsStr::from_bytes(arg).to_string_lossy().to_string())
+        .collect();
+    if !args.is_empty() {
+        return args.join(" ");
+    }
+
+    match fs::read_to_string(proc_dir.join("comm")) {
+        Ok(comm) => format!("[{}]", comm.trim_end()),
+        Err(_) => String::new(),
+    }
+}
+
+/// Send the signal `signal` to the process with the pid `pid`.
+pub fn signal_process(pid: u32, signal: Signal) -> Result<(), SignalProcessError> {
+    let nix_signal: NixSignal = match signal {
+        Signal::Interrupt => NixSignal::SIGINT,
+        Signal::Terminate => NixSignal::SIGTERM,
+        Signal::Kill => NixSignal::SIGKILL,
+    };
+    // NOTE: A pid of zero (or a negative pid) would signal a whole process group.
+    let pid: i32 = i32::try_from(pid)
+        .ok()
+        .filter(|pid| *pid > 0)
+        .ok_or(SignalProcessError::NoSuchProcess)?;
+    signal::kill(Pid::from_raw(pid), nix_signal).map_err(|errno| match errno {
+        Errno::ESRCH => SignalProcessError::NoSuchProcess,
+        Errno::EPERM => SignalProcessError::PermissionDenied,
+        errno => SignalProcessError::Other(errno.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::process;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_processes_in() {
+        let cwd: PathBuf = env::current_dir().unwrap();
+
+        let processes: Vec<ProcessInfo> = processes_in(&cwd).unwrap();
+
+        let process: &ProcessInfo = processes
+            .iter()
+            .find(|process| process.pid() == process::id())
+            .unwrap();
+        assert_eq!(process.cwd(), cwd);
+        assert!(!process.command().is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_open_files_in() {
+        let dir: PathBuf = env::temp_dir().join(format!("inshd-open-files-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path: PathBuf = dir.join("open.txt");
+        let file = fs::File::create(&path).unwrap();
+
+        let open_files: Vec<OpenFile> = open_files_in(&dir).unwrap();
+
+        drop(file);
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(open_files
+            .iter()
+            .any(|open_file| open_file.pid() == process::id() && open_file.path() == path));
+    }
+
+    #[test]
+    fn test_signal_missing_process() {
+        assert_eq!(
+            signal_process(u32::MAX, Signal::Terminate),
+            Err(SignalProcessError::NoSuchProcess)
+        );
+    }
+}
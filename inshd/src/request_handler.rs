@@ -1,26 +1,84 @@
 //! Handles requests from clients.
-use std::fs::{self, DirBuilder, DirEntry, File, ReadDir};
-use std::io::{Error as IOError, ErrorKind as IOErrorKind};
+use std::collections::VecDeque;
+use std::fs::{self, DirBuilder, DirEntry, File, Metadata, OpenOptions, ReadDir};
+use std::io::{Error as IOError, ErrorKind as IOErrorKind, Read};
+use std::mem;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
 use std::path::PathBuf;
 use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
 
-use crossbeam::channel::{self, select, Receiver, Sender};
+use crossbeam::channel::{self, select, Receiver, SendTimeoutError, Sender, TryRecvError};
 use typed_builder::TypedBuilder;
+use uuid::Uuid;
 
-use file_info::FileInfo;
+use file_info::{FileInfo, LinkTarget};
 use file_type::FileType;
 use insh_api::{
     CreateFileError, CreateFileRequestParams, CreateFileResponseParams, CreateFileResult,
-    FindFilesRequestParams, FindFilesResponseParams, GetFilesError, GetFilesRequestParams,
-    GetFilesResponseParams, GetFilesResult, Request, RequestParams, Response, ResponseParams,
-    ResponseParamsAndLast,
+    DeleteFileError, DeleteFileRequestParams, DeleteFileResponseParams, DeleteFileResult,
+    FilesSummary, FindFilesRequestParams, FindFilesResponseParams, GetFileDetailsRequestParams,
+    GetFileDetailsResponseParams, GetFilesError, GetFilesRequestParams, GetFilesResponseParams,
+    GetFilesResult, GetOpenFilesRequestParams, GetOpenFilesResponseParams, GetOpenFilesResult,
+    GetProcessesRequestParams, GetProcessesResponseParams, GetProcessesResult,
+    ReadFileRequestParams, ReadFileResponseParams, ReadFileResult, RenameFileError,
+    RenameFileRequestParams, RenameFileResponseParams, RenameFileResult, RequestParams, Response,
+    ResponseParams, ResponseParamsAndLast, SearchPhraseRequestParams, SearchPhraseResponseParams,
+    SignalProcessRequestParams, SignalProcessResponseParams, SignalProcessResult, TouchFileError,
+    TouchFileRequestParams, TouchFileResponseParams, TouchFileResult, TransferFileError,
+    TransferFileRequestParams, TransferFileResponseParams, TransferFileResult, TransferProgress,
+    WaitForDevicesRequestParams, WaitForDevicesResponseParams, WriteFileRequestParams,
+    WriteFileResponseParams, WriteFileResult, MAX_GET_FILES_DIRS,
 };
 use path_finder::Entry;
+use phrase_searcher::FileHit;
+use text_encoding::Encoding;
 
+use crate::cancellation_token::CancellationToken;
+use crate::devices::DevicesWait;
+use crate::extended_attributes::read_extended_attributes;
 use crate::file_finder::FindFilesResult;
 use crate::file_finder::{FileFinder, FileFinderOptions};
+use crate::file_reader::{read_chunk, ChunkWait};
+use crate::file_system_health::FileSystemHealth;
+use crate::file_transfer::{FileTransfer, TransferError, TransferEvent};
+use crate::file_writer::write_file;
+use crate::incoming_request::IncomingRequest;
+use crate::permission::classify_permission_denied;
+use crate::phrase_search::{PhraseSearch, PhraseSearchOptions, SearchPhraseResult};
+use crate::processes::{open_files_in, processes_in, signal_process};
 use crate::stop::Stop;
 
+/// How long to wait to send a response before the request is parked (so that a client which reads
+/// slowly doesn't hold the request handler).
+const SEND_RESPONSE_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// How often the parked requests try to send their responses again.
+const PARKED_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How often the pending requests are polled for responses (which is often so that the results of
+/// finding files, searching, and copying stream without holding the request handler).
+const PENDING_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The number of found files that are buffered before finding files waits for them to be sent.
+const FIND_FILES_RESULTS_CAPACITY: usize = 256;
+
+/// The most found files that are sent in a single response.
+const FIND_FILES_BATCH_LEN: usize = 64;
+
+/// The number of file hits that are buffered before searching for a phrase waits for them to be
+/// sent.
+const SEARCH_PHRASE_RESULTS_CAPACITY: usize = 64;
+
+/// The most file hits that are sent in a single response.
+const SEARCH_PHRASE_BATCH_LEN: usize = 16;
+
+/// The longest that found files (or file hits) are held back to fill a batch before the batch is
+/// sent anyway.
+const BATCH_INTERVAL: Duration = Duration::from_millis(50);
+
 /// Handles requests from clients.
 #[derive(TypedBuilder)]
 pub struct RequestHandler {
@@ -28,66 +86,233 @@ pub struct RequestHandler {
     #[allow(dead_code)]
     number: usize,
     /// A receiver for requests.
-    requests: Receiver<Request>,
-    /// A sender for responses.
-    responses: Sender<Response>,
+    requests: Receiver<IncomingRequest>,
     /// A receiver for a stop sentinel.
     stop_rx: Receiver<Stop>,
+    /// The health of file systems.
+    file_system_health: FileSystemHealth,
 }
 
 impl RequestHandler {
     /// Run the request handler.
+    ///
+    /// A request whose client doesn't read its responses in time is parked (and retried
+    /// periodically), and a request which is waiting for something to happen before it responds
+    /// (like a file finder thread finding files) is pending (and polled periodically). Neither
+    /// blocks the request handler, so the requests of other clients are handled in the meantime.
     pub fn run(&mut self) {
         log::info!("Request handler running.");
 
+        // The requests that are parked or pending (with which one they are).
+        let mut waiting: VecDeque<(Handling, Progress)> = VecDeque::new();
         loop {
+            let any_pending: bool = waiting
+                .iter()
+                .any(|(_, progress)| matches!(progress, Progress::Pending));
+            let retry_rx: Receiver<Instant> = match (any_pending, waiting.is_empty()) {
+                (true, _) => channel::after(PENDING_POLL_INTERVAL),
+                (false, false) => channel::after(PARKED_RETRY_INTERVAL),
+                (false, true) => channel::never(),
+            };
             select! {
                 recv(self.stop_rx) -> _stop => {
                     break;
                 }
                 recv(self.requests) -> request => {
-                    let request: Request = request.unwrap();
-                    log::info!("Handling request {}.", request.uuid());
-
-                    let response_params_and_last_iter: Box<dyn Iterator<Item = ResponseParamsAndLast>> = match request.params() {
-                        RequestParams::GetFiles(params) => Box::new(GetFiles::new(params)),
-                        RequestParams::FindFiles(params) => Box::new(FindFiles::run(params)),
-                        RequestParams::CreateFile(params) => Box::new(CreateFile::new(params)),
-                    };
-
-                    let mut sent_last: bool = false;
-                    let mut send_error: bool = false;
-                    for response_params_and_last in response_params_and_last_iter {
-                        let response = Response::builder()
-                            .uuid(*request.uuid())
-                            .last(response_params_and_last.last)
-                            .params(response_params_and_last.response_params)
-                            .build();
+                    if let Some(mut handling) = self.start(request.unwrap()) {
+                        match handling.advance(SEND_RESPONSE_TIMEOUT) {
+                            Progress::Done => {}
+                            Progress::Parked => {
+                                log::debug!("Parking request {} until the client reads responses.", handling.uuid);
+                                waiting.push_back((handling, Progress::Parked));
+                            }
+                            Progress::Pending => waiting.push_back((handling, Progress::Pending)),
+                        }
+                    }
+                }
+                recv(retry_rx) -> _instant => {}
+            }
+
+            for _ in 0..waiting.len() {
+                let (mut handling, _) = waiting.pop_front().unwrap();
+                match handling.advance(Duration::ZERO) {
+                    Progress::Done => {}
+                    progress => waiting.push_back((handling, progress)),
+                }
+            }
+        }
+
+        log::info!("Request handler stopping...");
+    }
+
+    /// Start handling the request (unless it was cancelled).
+    fn start(&self, incoming_request: IncomingRequest) -> Option<Handling> {
+        let IncomingRequest {
+            request,
+            cancellation_token,
+            responses_tx,
+        } = incoming_request;
+        if cancellation_token.is_cancelled() {
+            log::info!(
+                "Skipping request {} because it was cancelled.",
+                request.uuid()
+            );
+            return None;
+        }
+        log::info!("Handling request {}.", request.uuid());
+
+        let uuid: Uuid = *request.uuid();
+        let responses: Box<dyn Iterator<Item = Step>> = match request.into_params() {
+            RequestParams::GetFiles(params) => {
+                Box::new(GetFiles::new(&params, &self.file_system_health).map(Step::Ready))
+            }
+            RequestParams::FindFiles(params) => Box::new(FindFiles::run(
+                &params,
+                &cancellation_token,
+                &self.file_system_health,
+            )),
+            RequestParams::SearchPhrase(params) => Box::new(SearchPhrase::run(
+                &params,
+                &cancellation_token,
+                &self.file_system_health,
+            )),
+            RequestParams::DeleteFile(params) => {
+                Box::new(DeleteFile::new(&params).map(Step::Ready))
+            }
+            RequestParams::RenameFile(params) => {
+                Box::new(RenameFile::new(&params).map(Step::Ready))
+            }
+            RequestParams::CopyFile(params) => {
+                Box::new(TransferFile::run(&params, false, &cancellation_token))
+            }
+            RequestParams::MoveFile(params) => {
+                Box::new(TransferFile::run(&params, true, &cancellation_token))
+            }
+            RequestParams::CreateFile(params) => {
+                Box::new(CreateFile::new(&params).map(Step::Ready))
+            }
+            RequestParams::GetFileDetails(params) => {
+                Box::new(GetFileDetails::new(&params).map(Step::Ready))
+            }
+            RequestParams::ReadFile(params) => Box::new(ReadFile::new(&params)),
+            RequestParams::WriteFile(params) => Box::new(WriteFile::new(params).map(Step::Ready)),
+            RequestParams::TouchFile(params) => Box::new(TouchFile::new(&params).map(Step::Ready)),
+            RequestParams::GetProcesses(params) => {
+                Box::new(GetProcesses::new(&params).map(Step::Ready))
+            }
+            RequestParams::SignalProcess(params) => {
+                Box::new(SignalProcess::new(params).map(Step::Ready))
+            }
+            RequestParams::GetOpenFiles(params) => {
+                Box::new(GetOpenFiles::new(&params).map(Step::Ready))
+            }
+            RequestParams::WaitForDevices(params) => Box::new(WaitForDevices::new(&params)),
+            RequestParams::CancelRequest(_) => {
+                log::warn!(
+                    "Request {} should have been handled by the client handler.",
+                    uuid
+                );
+                return None;
+            }
+        };
+
+        Some(Handling {
+            uuid,
+            responses,
+            unsent: None,
+            sent_last: false,
+            cancellation_token,
+            responses_tx,
+        })
+    }
+}
+
+/// A request whose responses are being sent.
+struct Handling {
+    /// The UUID of the request.
+    uuid: Uuid,
+    /// The responses to the request (which are produced as they are sent).
+    responses: Box<dyn Iterator<Item = Step>>,
+    /// The response that the client wasn't ready for (while the request is parked).
+    unsent: Option<Response>,
+    /// Whether the last response was sent.
+    sent_last: bool,
+    /// A token which is cancelled when the client cancels the request or disconnects.
+    cancellation_token: CancellationToken,
+    /// A sender of responses to the client.
+    responses_tx: Sender<Response>,
+}
+
+/// The next step of handling a request.
+enum Step {
+    /// A response is ready to be sent.
+    Ready(ResponseParamsAndLast),
+    /// The request is waiting for something to happen before it responds, so it is polled again
+    /// later (instead of holding the request handler).
+    Pending,
+}
+
+/// How far sending the responses to a request got.
+enum Progress {
+    /// All of the responses were sent (or the request was cancelled or the client disconnected).
+    Done,
+    /// The client isn't reading the responses, so the request waits to be retried.
+    Parked,
+    /// The request doesn't have a response yet, so it waits to be polled again.
+    Pending,
+}
+
+impl Handling {
+    /// Send the responses to the request until they are done, until the request is pending, or
+    /// until sending one waits longer than `wait` because the queue of responses to the client is
+    /// full (which pauses producing more responses).
+    fn advance(&mut self, wait: Duration) -> Progress {
+        loop {
+            if self.cancellation_token.is_cancelled() {
+                log::info!("Request {} was cancelled.", self.uuid);
+                return Progress::Done;
+            }
 
+            let response: Response = match self.unsent.take() {
+                Some(response) => response,
+                None => match self.responses.next() {
+                    Some(Step::Pending) => return Progress::Pending,
+                    Some(Step::Ready(response_params_and_last)) => {
                         if response_params_and_last.last {
-                            if sent_last {
+                            if self.sent_last {
                                 log::error!("Multiple last responses.");
-                                break;
+                                return Progress::Done;
                             }
-                            sent_last = true;
-                        }
-
-                        if let Err(error) = self.responses.send(response) {
-                            log::error!("Error sending response: {}", error);
-                            send_error = true;
-                            break;
+                            self.sent_last = true;
                         }
+                        Response::builder()
+                            .uuid(self.uuid)
+                            .last(response_params_and_last.last)
+                            .params(response_params_and_last.response_params)
+                            .build()
                     }
-                    if !sent_last && !send_error {
-                        log::warn!("Never received last response.");
+                    None => {
+                        if !self.sent_last {
+                            log::warn!("Never received last response.");
+                        }
+                        log::info!("Done handling request {}.", self.uuid);
+                        return Progress::Done;
                     }
+                },
+            };
 
-                    log::info!("Done handling request {}.", request.uuid());
+            match self.responses_tx.send_timeout(response, wait) {
+                Ok(()) => {}
+                Err(SendTimeoutError::Timeout(unsent_response)) => {
+                    self.unsent = Some(unsent_response);
+                    return Progress::Parked;
+                }
+                Err(SendTimeoutError::Disconnected(_)) => {
+                    log::info!("The client stopped reading responses.");
+                    return Progress::Done;
                 }
             }
         }
-
-        log::info!("Request handler stopping...");
     }
 }
 
@@ -96,19 +321,45 @@ impl RequestHandler {
 pub struct Context {}
 
 /// Handles a request to get files.
+///
+/// The files of the directory are gotten first and then the files of its subdirectories (breadth
+/// first) down to the depth of the request, with one response per directory.
 struct GetFiles {
-    /// The directory to get files for.
-    dir: PathBuf,
-    /// If getting files is done.
-    done: bool,
+    /// The directories to get files for (with the number of levels of their subdirectories whose
+    /// files are also gotten).
+    dirs: VecDeque<(PathBuf, usize)>,
+    /// The number of directories that have been queued (which is limited to
+    /// [`MAX_GET_FILES_DIRS`]).
+    queued: usize,
+    /// The health of file systems.
+    file_system_health: FileSystemHealth,
 }
 
 impl GetFiles {
     /// Return a new handler for getting files.
-    pub fn new(params: &GetFilesRequestParams) -> Self {
+    pub fn new(params: &GetFilesRequestParams, file_system_health: &FileSystemHealth) -> Self {
         Self {
-            dir: params.dir().to_path_buf(),
-            done: false,
+            dirs: VecDeque::from([(params.dir().to_path_buf(), params.depth())]),
+            queued: 1,
+            file_system_health: file_system_health.clone(),
+        }
+    }
+
+    /// Queue the subdirectories of a directory (but not symbolic links to directories or mount
+    /// points, so that the walk neither loops nor crosses into other file systems).
+    fn queue_subdirs(&mut self, file_infos: &[FileInfo], depth: usize) {
+        for file_info in file_infos {
+            if self.queued >= MAX_GET_FILES_DIRS {
+                log::warn!(
+                    "Not getting the files of more than {} directories.",
+                    MAX_GET_FILES_DIRS
+                );
+                return;
+            }
+            if matches!(file_info.r#type(), Ok(FileType::Dir)) && !file_info.is_mount_point() {
+                self.dirs.push_back((file_info.path().to_path_buf(), depth));
+                self.queued += 1;
+            }
         }
     }
 }
@@ -117,81 +368,268 @@ impl Iterator for GetFiles {
     type Item = ResponseParamsAndLast;
 
     fn next(&mut self) -> Option<ResponseParamsAndLast> {
-        if self.done {
-            return None;
-        }
+        let (dir, depth): (PathBuf, usize) = self.dirs.pop_front()?;
 
-        let read_dir: Result<ReadDir, IOError> = fs::read_dir(&self.dir);
-        let get_files_result: GetFilesResult = match read_dir {
-            Ok(dir_entries) => {
-                let mut file_infos: Vec<FileInfo> = Vec::new();
+        // If the file system is slow, then avoid getting the metadata of each entry.
+        let slow_file_system: bool = self.file_system_health.check(&dir);
 
-                for dir_entry in dir_entries {
-                    let dir_entry: DirEntry = match dir_entry {
-                        Ok(dir_entry) => dir_entry,
-                        Err(error) => {
-                            log::warn!("Error for dir entry: {}", error);
-                            continue;
-                        }
-                    };
-
-                    let file_type: Result<FileType, String> = match dir_entry.file_type() {
-                        Ok(std_file_type) => Ok(FileType::from(std_file_type)),
-                        Err(io_error) => Err(io_error.to_string()),
-                    };
-
-                    let file_info: FileInfo = FileInfo::builder()
-                        .path(dir_entry.path().to_path_buf())
-                        .r#type(file_type)
-                        .build();
-                    file_infos.push(file_info);
-                }
-                Ok(file_infos)
-            }
-            Err(error) => match error.kind() {
-                IOErrorKind::NotFound => Err(GetFilesError::DirDoesNotExist),
-                IOErrorKind::PermissionDenied => Err(GetFilesError::PermissionDenied),
-                _ => Err(GetFilesError::OtherErrorReading(error.to_string())),
-            },
-        };
+        let get_files_result: GetFilesResult = get_files(&dir, slow_file_system);
+        if let (Ok(file_infos), true) = (&get_files_result, depth > 0) {
+            self.queue_subdirs(file_infos, depth - 1);
+        }
 
+        let summary: Option<FilesSummary> = get_files_result
+            .as_ref()
+            .ok()
+            .map(|file_infos| summarize(file_infos));
+        let physical_dir: Option<PathBuf> = fs::canonicalize(&dir)
+            .ok()
+            .filter(|physical_dir| physical_dir != &dir);
         let response_params = ResponseParams::GetFiles(
             GetFilesResponseParams::builder()
+                .dir(dir)
                 .result(get_files_result)
+                .slow_file_system(slow_file_system)
+                .summary(summary)
+                .physical_dir(physical_dir)
                 .build(),
         );
 
-        self.done = true;
-
         Some(
             ResponseParamsAndLast::builder()
                 .response_params(response_params)
-                .last(true)
+                .last(self.dirs.is_empty())
                 .build(),
         )
     }
 }
 
+/// Return the numbers of files, directories, and hidden entries (whose names start with a `.`) of
+/// the files in a directory.
+fn summarize(file_infos: &[FileInfo]) -> FilesSummary {
+    let dirs: usize = file_infos
+        .iter()
+        .filter(|file_info| matches!(file_info.r#type(), Ok(FileType::Dir)))
+        .count();
+    let hidden: usize = file_infos
+        .iter()
+        .filter(|file_info| {
+            file_info
+                .path()
+                .file_name()
+                .is_some_and(|name| name.as_bytes().starts_with(b"."))
+        })
+        .count();
+    FilesSummary::builder()
+        .files(file_infos.len() - dirs)
+        .dirs(dirs)
+        .hidden(hidden)
+        .build()
+}
+
+/// Return the files in a directory.
+fn get_files(dir: &Path, slow_file_system: bool) -> GetFilesResult {
+    let read_dir: Result<ReadDir, IOError> = fs::read_dir(dir);
+    match read_dir {
+        Ok(dir_entries) => {
+            let mut file_infos: Vec<FileInfo> = Vec::new();
+
+            // The device of the directory is used to determine which entries are mount points.
+            let dir_device: Option<u64> = match slow_file_system {
+                true => None,
+                false => match fs::metadata(dir) {
+                    Ok(metadata) => Some(metadata.dev()),
+                    Err(error) => {
+                        log::warn!("Error getting metadata for dir: {}", error);
+                        None
+                    }
+                },
+            };
+
+            for dir_entry in dir_entries {
+                let dir_entry: DirEntry = match dir_entry {
+                    Ok(dir_entry) => dir_entry,
+                    Err(error) => {
+                        log::warn!("Error for dir entry: {}", error);
+                        continue;
+                    }
+                };
+
+                let file_type: Result<FileType, String> = match dir_entry.file_type() {
+                    Ok(std_file_type) => Ok(FileType::from(std_file_type)),
+                    Err(io_error) => Err(io_error.to_string()),
+                };
+
+                let metadata: Option<Metadata> = match slow_file_system {
+                    true => None,
+                    false => match dir_entry.metadata() {
+                        Ok(metadata) => Some(metadata),
+                        Err(error) => {
+                            log::warn!("Error getting metadata for dir entry: {}", error);
+                            None
+                        }
+                    },
+                };
+                let device: Option<u64> = metadata.as_ref().map(|metadata| metadata.dev());
+                let modified: Option<SystemTime> = metadata
+                    .as_ref()
+                    .and_then(|metadata| metadata.modified().ok());
+                let size: Option<u64> = metadata
+                    .as_ref()
+                    .filter(|metadata| metadata.is_file())
+                    .map(Metadata::len);
+                let permissions: Option<u32> =
+                    metadata.as_ref().map(|metadata| metadata.mode() & 0o7777);
+
+                let is_dir: bool = matches!(file_type, Ok(FileType::Dir));
+                let mount_point: bool = is_dir
+                    && matches!((dir_device, device), (Some(dir_device), Some(device)) if dir_device != device);
+
+                // Links are followed here (rather than by the client) so that checking what they
+                // point to never blocks the interface, even on a slow file system.
+                let link_target: Option<LinkTarget> = match file_type {
+                    Ok(FileType::Symlink) => link_target(&dir_entry.path()),
+                    _ => None,
+                };
+
+                let file_info: FileInfo = FileInfo::builder()
+                    .path(dir_entry.path().to_path_buf())
+                    .r#type(file_type)
+                    .device(device)
+                    .mount_point(mount_point)
+                    .modified(modified)
+                    .size(size)
+                    .permissions(permissions)
+                    .link_target(link_target)
+                    .build();
+                file_infos.push(file_info);
+            }
+            Ok(file_infos)
+        }
+        Err(error) => match error.kind() {
+            IOErrorKind::NotFound => Err(GetFilesError::DirDoesNotExist),
+            IOErrorKind::PermissionDenied => Err(classify_permission_denied(dir)),
+            _ => Err(GetFilesError::OtherErrorReading(error.to_string())),
+        },
+    }
+}
+
+/// Return what the symbolic link at `path` points to (if it exists).
+fn link_target(path: &Path) -> Option<LinkTarget> {
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(error) => {
+            log::debug!("Error following link {:?}: {}", path, error);
+            return None;
+        }
+    };
+    let target: PathBuf = fs::canonicalize(path).ok()?;
+    Some(
+        LinkTarget::builder()
+            .path(target)
+            .r#type(FileType::from(metadata.file_type()))
+            .build(),
+    )
+}
+
+/// A batch of results which are received from a thread without blocking.
+struct Batch<T> {
+    /// The items of the batch.
+    items: Vec<T>,
+    /// The most items in a batch.
+    len: usize,
+    /// When the first item of the batch was received.
+    started: Option<Instant>,
+}
+
+impl<T> Batch<T> {
+    /// Return a new empty batch of at most `len` items.
+    fn new(len: usize) -> Self {
+        Self {
+            items: Vec::with_capacity(len),
+            len,
+            started: None,
+        }
+    }
+
+    /// Receive the results that are ready (without waiting) into the batch until it is full, where
+    /// `item` returns the item of a result or `None` if the result ends the results. Return
+    /// whether the results ended.
+    fn fill<R>(&mut self, results_rx: &Receiver<R>, mut item: impl FnMut(R) -> Option<T>) -> bool {
+        while self.items.len() < self.len {
+            match results_rx.try_recv() {
+                Ok(result) => match item(result) {
+                    Some(item) => {
+                        self.started.get_or_insert_with(Instant::now);
+                        self.items.push(item);
+                    }
+                    None => return true,
+                },
+                Err(TryRecvError::Empty) => return false,
+                Err(TryRecvError::Disconnected) => {
+                    log::error!("The thread sending results stopped before ending them.");
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Return whether the batch should be sent, because it is full or because its first item was
+    /// held back for [`BATCH_INTERVAL`] (so that a huge tree streams to the client without flooding
+    /// it with tiny responses).
+    fn is_ready(&self) -> bool {
+        self.items.len() == self.len
+            || self
+                .started
+                .is_some_and(|started| started.elapsed() >= BATCH_INTERVAL)
+    }
+
+    /// Return the items of the batch and start a new batch.
+    fn take(&mut self) -> Vec<T> {
+        self.started = None;
+        mem::replace(&mut self.items, Vec::with_capacity(self.len))
+    }
+}
+
 /// Handles a request to find files.
+///
+/// The request is pending (and polled by the request handler) while the file finder thread hasn't
+/// found enough files for a batch.
 struct FindFiles {
     /// A receiver for results of finding files.
     results_rx: Receiver<FindFilesResult>,
     /// A handle to the thread for finding files.
     file_finder_handle: Option<JoinHandle<()>>,
+    /// The found files which haven't been sent yet.
+    batch: Batch<Entry>,
     /// If finding files is done.
     done: bool,
 }
 
 impl FindFiles {
-    /// Find files.
-    pub fn run(params: &FindFilesRequestParams) -> FindFiles {
+    /// Find files (until done or the `cancellation_token` is cancelled).
+    pub fn run(
+        params: &FindFilesRequestParams,
+        cancellation_token: &CancellationToken,
+        file_system_health: &FileSystemHealth,
+    ) -> FindFiles {
         // Create and start a thread to perform the finding of files.
+        // NOTE: The channel is bounded so that the walk pauses while the responses are not sent
+        // (because the client is reading slowly).
         let (results_tx, results_rx): (Sender<FindFilesResult>, Receiver<FindFilesResult>) =
-            channel::unbounded();
-        let mut file_finder: FileFinder = FileFinder::builder().results_tx(results_tx).build();
+            channel::bounded(FIND_FILES_RESULTS_CAPACITY);
+        let mut file_finder: FileFinder = FileFinder::builder()
+            .results_tx(results_tx)
+            .cancellation_token(cancellation_token.clone())
+            .file_system_health(file_system_health.clone())
+            .build();
         let file_finder_options: FileFinderOptions = FileFinderOptions::builder()
             .dir(params.dir())
             .pattern(params.pattern())
+            .same_file_system(params.same_file_system())
+            .unicode_matching(params.unicode_matching())
+            .respect_ignore(params.respect_ignore())
             .build();
         let file_finder_handle: JoinHandle<()> = thread::Builder::new()
             .name("file-finder".to_string())
@@ -201,68 +639,129 @@ impl FindFiles {
         FindFiles {
             results_rx,
             file_finder_handle: Some(file_finder_handle),
+            batch: Batch::new(FIND_FILES_BATCH_LEN),
             done: false,
         }
     }
 }
 
 impl Iterator for FindFiles {
-    type Item = ResponseParamsAndLast;
+    type Item = Step;
 
-    fn next(&mut self) -> Option<ResponseParamsAndLast> {
+    fn next(&mut self) -> Option<Step> {
         if self.done {
             return None;
         }
 
-        select! {
-            recv(self.results_rx) -> result => {
-                let result: FindFilesResult = match result {
-                    Ok(result) => result,
-                    Err(error) => {
-                        log::error!("Error receiving find files result from file finder thread: {}", error);
-                        todo!();
-                    }
-                };
+        // Finding files failing ends the responses like finding files finishing does.
+        let ended: bool = self.batch.fill(&self.results_rx, |result| match result {
+            Ok(entry) => entry,
+            Err(error) => {
+                log::error!("Error finding files: {}", error);
+                None
+            }
+        });
+        if ended {
+            self.done = true;
+            if let Some(file_finder_handle) = self.file_finder_handle.take() {
+                let _ = file_finder_handle.join();
+            }
+        } else if !self.batch.is_ready() {
+            return Some(Step::Pending);
+        }
 
-                let entry: Option<Entry> = match result {
-                    Ok(entry) => entry,
-                    Err(error) => {
-                        log::error!("Error finding files: {}", error);
-                        todo!();
-                    }
-                };
+        Some(Step::Ready(
+            ResponseParamsAndLast::builder()
+                .response_params(ResponseParams::FindFiles(
+                    FindFilesResponseParams::builder()
+                        .entries(self.batch.take())
+                        .build(),
+                ))
+                .last(self.done)
+                .build(),
+        ))
+    }
+}
 
-                let entry: Entry = match entry {
-                    Some(entry) => entry,
-                    None => {
-                        self.done = true;
-                        let file_finder_handle: JoinHandle<()> = self.file_finder_handle.take().unwrap();
-                        let _ = file_finder_handle.join();
-                        return Some(ResponseParamsAndLast::builder()
-                            .response_params(
-                                ResponseParams::FindFiles(
-                                    FindFilesResponseParams::builder()
-                                        .entries(vec![])
-                                        .build()
-                                )
-                            )
-                            .last(true)
-                            .build());
-                    }
-                };
+/// Handles a request to search the contents of files for a phrase.
+///
+/// The request is pending (and polled by the request handler) while the phrase search thread
+/// hasn't found enough file hits for a batch.
+struct SearchPhrase {
+    /// A receiver for results of searching for the phrase.
+    results_rx: Receiver<SearchPhraseResult>,
+    /// A handle to the thread for searching for the phrase.
+    phrase_search_handle: Option<JoinHandle<()>>,
+    /// The file hits which haven't been sent yet.
+    batch: Batch<FileHit>,
+    /// If searching for the phrase is done.
+    done: bool,
+}
+
+impl SearchPhrase {
+    /// Search for the phrase (until done or the `cancellation_token` is cancelled).
+    pub fn run(
+        params: &SearchPhraseRequestParams,
+        cancellation_token: &CancellationToken,
+        file_system_health: &FileSystemHealth,
+    ) -> SearchPhrase {
+        // NOTE: The channel is bounded so that the search pauses while the responses are not sent
+        // (because the client is reading slowly).
+        let (results_tx, results_rx): (Sender<SearchPhraseResult>, Receiver<SearchPhraseResult>) =
+            channel::bounded(SEARCH_PHRASE_RESULTS_CAPACITY);
+        let mut phrase_search: PhraseSearch = PhraseSearch::builder()
+            .results_tx(results_tx)
+            .cancellation_token(cancellation_token.clone())
+            .file_system_health(file_system_health.clone())
+            .build();
+        let phrase_search_options: PhraseSearchOptions = PhraseSearchOptions::builder()
+            .dir(params.dir())
+            .phrase(params.phrase())
+            .same_file_system(params.same_file_system())
+            .unicode_matching(params.unicode_matching())
+            .build();
+        let phrase_search_handle: JoinHandle<()> = thread::Builder::new()
+            .name("phrase-search".to_string())
+            .spawn(move || phrase_search.run(phrase_search_options))
+            .unwrap();
+
+        SearchPhrase {
+            results_rx,
+            phrase_search_handle: Some(phrase_search_handle),
+            batch: Batch::new(SEARCH_PHRASE_BATCH_LEN),
+            done: false,
+        }
+    }
+}
+
+impl Iterator for SearchPhrase {
+    type Item = Step;
 
-                return Some(ResponseParamsAndLast::builder()
-                    .response_params(
-                        ResponseParams::FindFiles(
-                            FindFilesResponseParams::builder()
-                                .entries(vec![entry])
-                                .build()
-                        )
-                    )
-                    .last(false)
-                    .build());
+    fn next(&mut self) -> Option<Step> {
+        if self.done {
+            return None;
+        }
+
+        let ended: bool = self.batch.fill(&self.results_rx, |file_hit| file_hit);
+        if ended {
+            self.done = true;
+            if let Some(phrase_search_handle) = self.phrase_search_handle.take() {
+                let _ = phrase_search_handle.join();
             }
+        } else if !self.batch.is_ready() {
+            return Some(Step::Pending);
         }
+
+        Some(Step::Ready(
+            ResponseParamsAndLast::builder()
+                .response_params(ResponseParams::SearchPhrase(
+                    SearchPhraseResponseParams::builder()
+                        .file_hits(self.batch.take())
+                        .build(),
+                ))
+                .last(self.done)
+                .build(),
+        ))
     }
 }
 
@@ -272,6 +771,8 @@ struct CreateFile {
     path: PathBuf,
     /// The type of file to create.
     file_type: FileType,
+    /// Whether missing parent directories should be created.
+    parents: bool,
     /// Whether or not created the file is done.
     done: bool,
 }
@@ -282,9 +783,26 @@ impl CreateFile {
         Self {
             path: params.path().to_path_buf(),
             file_type: params.file_type(),
+            parents: params.parents(),
             done: false,
         }
     }
+
+    /// Create the missing parent directories of the file (if they should be created).
+    fn create_parents(&self) -> Result<(), IOError> {
+        match (self.parents, self.path.parent()) {
+            (true, Some(parent)) => DirBuilder::new().recursive(true).create(parent),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Return the error for an IO error encountered while creating a file.
+fn create_file_error(io_error: IOError) -> CreateFileError {
+    match io_error.kind() {
+        IOErrorKind::PermissionDenied => CreateFileError::PermissionDenied,
+        _ => CreateFileError::Other(format!("{}", io_error)),
+    }
 }
 
 impl Iterator for CreateFile {
@@ -301,27 +819,27 @@ impl Iterator for CreateFile {
             match self.file_type {
                 FileType::File => {
                     log::info!("Creating file {:?}...", self.path);
-                    match File::create(&self.path) {
+                    match self.create_parents().and_then(|_| File::create(&self.path)) {
                         Ok(_) => {
                             log::info!("Created file {:?}.", self.path);
-                            Ok(())
+                            Ok(self.path.clone())
                         }
                         Err(io_error) => {
                             log::error!("Error creating file: {}", io_error);
-                            Err(CreateFileError::Other(format!("{}", io_error)))
+                            Err(create_file_error(io_error))
                         }
                     }
                 }
                 FileType::Dir => {
                     log::info!("Creating directory {:?}...", self.path);
-                    match DirBuilder::new().create(&self.path) {
+                    match DirBuilder::new().recursive(self.parents).create(&self.path) {
                         Ok(_) => {
                             log::info!("Created directory {:?}.", self.path);
-                            Ok(())
+                            Ok(self.path.clone())
                         }
                         Err(io_error) => {
                             log::error!("Error creating directory: {}", io_error);
-                            Err(CreateFileError::Other(format!("{}", io_error)))
+                            Err(create_file_error(io_error))
                         }
                     }
                 }
@@ -344,3 +862,1129 @@ impl Iterator for CreateFile {
         )
     }
 }
+
+/// Handles getting the details of files.
+struct GetFileDetails {
+    /// The paths of the files to get the details of.
+    paths: Vec<PathBuf>,
+    /// Whether or not getting the details is done.
+    done: bool,
+}
+
+impl GetFileDetails {
+    /// Return a new handler for getting the details of files.
+    fn new(params: &GetFileDetailsRequestParams) -> Self {
+        Self {
+            paths: params.paths().to_vec(),
+            done: false,
+        }
+    }
+}
+
+impl Iterator for GetFileDetails {
+    type Item = ResponseParamsAndLast;
+
+    fn next(&mut self) -> Option<ResponseParamsAndLast> {
+        if self.done {
+            return None;
+        }
+
+        let mut file_infos: Vec<FileInfo> = Vec::with_capacity(self.paths.len());
+        for path in &self.paths {
+            let (file_type, modified): (Result<FileType, String>, Option<SystemTime>) =
+                match fs::symlink_metadata(path) {
+                    Ok(metadata) => (
+                        Ok(FileType::from(metadata.file_type())),
+                        metadata.modified().ok(),
+                    ),
+                    Err(io_error) => (Err(io_error.to_string()), None),
+                };
+            let encoding: Option<Encoding> = match file_type {
+                Ok(FileType::File) => detect_encoding(path),
+                _ => None,
+            };
+
+            let file_info: FileInfo = FileInfo::builder()
+                .path(path.clone())
+                .r#type(file_type)
+                .extended_attributes(Some(read_extended_attributes(path)))
+                .modified(modified)
+                .encoding(encoding)
+                .build();
+            file_infos.push(file_info);
+        }
+
+        let response_params: ResponseParams = ResponseParams::GetFileDetails(
+            GetFileDetailsResponseParams::builder()
+                .file_infos(file_infos)
+                .build(),
+        );
+
+        self.done = true;
+
+        Some(
+            ResponseParamsAndLast::builder()
+                .response_params(response_params)
+                .last(true)
+                .build(),
+        )
+    }
+}
+
+/// Return the encoding of the file at `path` if it is a text file (detected from its start).
+fn detect_encoding(path: &Path) -> Option<Encoding> {
+    let file: File = File::open(path).ok()?;
+    let mut sample: Vec<u8> = Vec::with_capacity(text_encoding::SAMPLE_LEN);
+    file.take(text_encoding::SAMPLE_LEN as u64)
+        .read_to_end(&mut sample)
+        .ok()?;
+    text_encoding::detect(&sample)
+}
+
+/// Handles reading a chunk of a file.
+///
+/// If the request waits for the file to grow, then the request is pending (and polled by the
+/// request handler) until the file grows or waiting times out.
+struct ReadFile {
+    /// The path of the file to read.
+    path: PathBuf,
+    /// The offset (in bytes) of the start of the chunk.
+    offset: u64,
+    /// The length (in bytes) of the chunk.
+    len: u64,
+    /// Waiting for the file to grow if the offset is the end of the file.
+    wait: Option<ChunkWait>,
+    /// Whether or not reading the chunk is done.
+    done: bool,
+}
+
+impl ReadFile {
+    /// Return a new handler for reading a chunk of a file.
+    fn new(params: &ReadFileRequestParams) -> Self {
+        Self {
+            path: params.path().to_path_buf(),
+            offset: params.offset(),
+            len: params.len(),
+            wait: params
+                .wait()
+                .map(|wait| ChunkWait::new(params.path(), params.offset(), params.len(), wait)),
+            done: false,
+        }
+    }
+}
+
+impl Iterator for ReadFile {
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Step> {
+        if self.done {
+            return None;
+        }
+
+        let read_file_result: ReadFileResult = match &mut self.wait {
+            Some(wait) => match wait.poll() {
+                Some(read_file_result) => read_file_result,
+                None => return Some(Step::Pending),
+            },
+            None => read_chunk(&self.path, self.offset, self.len),
+        };
+        log::info!(
+            "Read up to {} bytes at offset {} of {:?}.",
+            self.len,
+            self.offset,
+            self.path
+        );
+        if let Err(error) = &read_file_result {
+            log::error!("Error reading file: {}", error);
+        }
+
+        let response_params: ResponseParams = ResponseParams::ReadFile(
+            ReadFileResponseParams::builder()
+                .result(read_file_result)
+                .build(),
+        );
+
+        self.done = true;
+
+        Some(Step::Ready(
+            ResponseParamsAndLast::builder()
+                .response_params(response_params)
+                .last(true)
+                .build(),
+        ))
+    }
+}
+
+/// Handles writing a file.
+struct WriteFile {
+    /// The parameters of the request.
+    params: WriteFileRequestParams,
+    /// Whether or not writing the file is done.
+    done: bool,
+}
+
+impl WriteFile {
+    /// Return a new handler for writing a file.
+    fn new(params: WriteFileRequestParams) -> Self {
+        Self {
+            params,
+            done: false,
+        }
+    }
+}
+
+impl Iterator for WriteFile {
+    type Item = ResponseParamsAndLast;
+
+    fn next(&mut self) -> Option<ResponseParamsAndLast> {
+        if self.done {
+            return None;
+        }
+
+        log::info!("Writing file {:?}...", self.params.path());
+        let write_file_result: WriteFileResult = write_file(
+            self.params.path(),
+            self.params.content(),
+            self.params.expected_mtime(),
+            self.params.preserve_format(),
+        );
+        match &write_file_result {
+            Ok(_) => log::info!("Wrote file {:?}.", self.params.path()),
+            Err(error) => log::error!("Error writing file: {}", error),
+        }
+
+        let response_params: ResponseParams = ResponseParams::WriteFile(
+            WriteFileResponseParams::builder()
+                .result(write_file_result)
+                .build(),
+        );
+
+        self.done = true;
+
+        Some(
+            ResponseParamsAndLast::builder()
+                .response_params(response_params)
+                .last(true)
+                .build(),
+        )
+    }
+}
+
+/// Handles touching a file.
+struct TouchFile {
+    /// The path of the file to touch.
+    path: PathBuf,
+    /// Whether or not touching the file is done.
+    done: bool,
+}
+
+impl TouchFile {
+    /// Return a new handler for touching a file.
+    fn new(params: &TouchFileRequestParams) -> Self {
+        Self {
+            path: params.path().to_path_buf(),
+            done: false,
+        }
+    }
+
+    /// Create the file if it does not exist or else update the time it was last modified at and
+    /// return the new time it was last modified at.
+    fn touch(&self) -> Result<SystemTime, IOError> {
+        // NOTE: Directories can't be opened for writing but their times can still be set.
+        let file: File = match self.path.is_dir() {
+            true => File::open(&self.path)?,
+            false => OpenOptions::new()
+                .append(true)
+                .create(true)
+                .open(&self.path)?,
+        };
+        file.set_modified(SystemTime::now())?;
+        file.metadata()?.modified()
+    }
+}
+
+impl Iterator for TouchFile {
+    type Item = ResponseParamsAndLast;
+
+    fn next(&mut self) -> Option<ResponseParamsAndLast> {
+        if self.done {
+            return None;
+        }
+
+        log::info!("Touching file {:?}...", self.path);
+        let touch_file_result: TouchFileResult = self.touch().map_err(|error| {
+            log::error!("Error touching file: {}", error);
+            match error.kind() {
+                IOErrorKind::PermissionDenied => TouchFileError::PermissionDenied,
+                _ => TouchFileError::Other(error.to_string()),
+            }
+        });
+
+        let response_params: ResponseParams = ResponseParams::TouchFile(
+            TouchFileResponseParams::builder()
+                .path(self.path.clone())
+                .result(touch_file_result)
+                .build(),
+        );
+
+        self.done = true;
+
+        Some(
+            ResponseParamsAndLast::builder()
+                .response_params(response_params)
+                .last(true)
+                .build(),
+        )
+    }
+}
+
+/// Handles deleting a file.
+struct DeleteFile {
+    /// The path of the file to delete.
+    path: PathBuf,
+    /// Whether a directory is deleted with everything in it.
+    recursive: bool,
+    /// Whether or not deleting the file is done.
+    done: bool,
+}
+
+impl DeleteFile {
+    /// Return a new handler for deleting a file.
+    fn new(params: &DeleteFileRequestParams) -> Self {
+        Self {
+            path: params.path().to_path_buf(),
+            recursive: params.recursive(),
+            done: false,
+        }
+    }
+
+    /// Delete the file (or the directory).
+    fn delete(&self) -> Result<(), IOError> {
+        // NOTE: The metadata of a symbolic link is used so that a link to a directory is deleted
+        // instead of the directory.
+        match fs::symlink_metadata(&self.path)?.is_dir() {
+            true if self.recursive => fs::remove_dir_all(&self.path),
+            true => fs::remove_dir(&self.path),
+            false => fs::remove_file(&self.path),
+        }
+    }
+}
+
+impl Iterator for DeleteFile {
+    type Item = ResponseParamsAndLast;
+
+    fn next(&mut self) -> Option<ResponseParamsAndLast> {
+        if self.done {
+            return None;
+        }
+
+        log::info!(
+            "Deleting file {:?} (recursive: {})...",
+            self.path,
+            self.recursive
+        );
+        let delete_file_result: DeleteFileResult = self.delete().map_err(|error| {
+            log::error!("Error deleting file: {}", error);
+            match error.kind() {
+                IOErrorKind::NotFound => DeleteFileError::FileDoesNotExist,
+                IOErrorKind::PermissionDenied => DeleteFileError::PermissionDenied,
+                IOErrorKind::DirectoryNotEmpty => DeleteFileError::DirectoryNotEmpty,
+                _ => DeleteFileError::Other(error.to_string()),
+            }
+        });
+
+        let response_params: ResponseParams = ResponseParams::DeleteFile(
+            DeleteFileResponseParams::builder()
+                .path(self.path.clone())
+                .recursive(self.recursive)
+                .result(delete_file_result)
+                .build(),
+        );
+
+        self.done = true;
+
+        Some(
+            ResponseParamsAndLast::builder()
+                .response_params(response_params)
+                .last(true)
+                .build(),
+        )
+    }
+}
+
+/// Handles renaming a file.
+struct RenameFile {
+    /// The path of the file to rename.
+    from: PathBuf,
+    /// The new path of the file.
+    to: PathBuf,
+    /// Whether or not renaming the file is done.
+    done: bool,
+}
+
+impl RenameFile {
+    /// Return a new handler for renaming a file.
+    fn new(params: &RenameFileRequestParams) -> Self {
+        Self {
+            from: params.from().to_path_buf(),
+            to: params.to().to_path_buf(),
+            done: false,
+        }
+    }
+
+    /// Rename the file (unless a file already exists at the new path).
+    fn rename(&self) -> Result<(), IOError> {
+        // NOTE: A file that is created at the new path after it is checked is still replaced, but
+        // this keeps renaming from replacing the files that can be seen in the browser.
+        if fs::symlink_metadata(&self.to).is_ok() {
+            return Err(IOError::from(IOErrorKind::AlreadyExists));
+        }
+        fs::rename(&self.from, &self.to)
+    }
+}
+
+impl Iterator for RenameFile {
+    type Item = ResponseParamsAndLast;
+
+    fn next(&mut self) -> Option<ResponseParamsAndLast> {
+        if self.done {
+            return None;
+        }
+
+        log::info!("Renaming file {:?} to {:?}...", self.from, self.to);
+        let rename_file_result: RenameFileResult = self.rename().map_err(|error| {
+            log::error!("Error renaming file: {}", error);
+            match error.kind() {
+                IOErrorKind::NotFound => RenameFileError::FileDoesNotExist,
+                IOErrorKind::PermissionDenied => RenameFileError::PermissionDenied,
+                IOErrorKind::AlreadyExists => RenameFileError::AlreadyExists,
+                _ => RenameFileError::Other(error.to_string()),
+            }
+        });
+
+        let response_params: ResponseParams = ResponseParams::RenameFile(
+            RenameFileResponseParams::builder()
+                .from(self.from.clone())
+                .to(self.to.clone())
+                .result(rename_file_result)
+                .build(),
+        );
+
+        self.done = true;
+
+        Some(
+            ResponseParamsAndLast::builder()
+                .response_params(response_params)
+                .last(true)
+                .build(),
+        )
+    }
+}
+
+/// Handles copying or moving a file.
+///
+/// The request is pending (and polled by the request handler) between the events of the file
+/// transfer thread.
+struct TransferFile {
+    /// The path of the file to copy or move.
+    from: PathBuf,
+    /// The new path of the file.
+    to: PathBuf,
+    /// Whether the file is moved (rather than copied).
+    moving: bool,
+    /// A receiver for what happens while transferring the file.
+    events_rx: Receiver<TransferEvent>,
+    /// A handle to the thread for transferring the file.
+    file_transfer_handle: Option<JoinHandle<()>>,
+    /// If transferring the file is done.
+    done: bool,
+}
+
+impl TransferFile {
+    /// Copy or move the file (until done or the `cancellation_token` is cancelled).
+    fn run(
+        params: &TransferFileRequestParams,
+        moving: bool,
+        cancellation_token: &CancellationToken,
+    ) -> Self {
+        let (events_tx, events_rx): (Sender<TransferEvent>, Receiver<TransferEvent>) =
+            channel::unbounded();
+        let mut file_transfer: FileTransfer = FileTransfer::builder()
+            .events_tx(events_tx)
+            .cancellation_token(cancellation_token.clone())
+            .build();
+        let from: PathBuf = params.from().to_path_buf();
+        let to: PathBuf = params.to().to_path_buf();
+        let file_transfer_handle: JoinHandle<()> = {
+            let (from, to) = (from.clone(), to.clone());
+            thread::Builder::new()
+                .name("file-transfer".to_string())
+                .spawn(move || file_transfer.run(&from, &to, moving))
+                .unwrap()
+        };
+
+        Self {
+            from,
+            to,
+            moving,
+            events_rx,
+            file_transfer_handle: Some(file_transfer_handle),
+            done: false,
+        }
+    }
+}
+
+impl Iterator for TransferFile {
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Step> {
+        if self.done {
+            return None;
+        }
+
+        let result: TransferFileResult = match self.events_rx.try_recv() {
+            Ok(TransferEvent::Progress { copied, total }) => Ok(TransferProgress::builder()
+                .copied(copied)
+                .total(total)
+                .build()),
+            Ok(TransferEvent::Done(result)) => {
+                self.done = true;
+                result
+                    .map(|copied| {
+                        TransferProgress::builder()
+                            .copied(copied)
+                            .total(copied)
+                            .build()
+                    })
+                    .map_err(|error| {
+                        let error: TransferFileError = match error {
+                            TransferError::AlreadyExists => TransferFileError::AlreadyExists,
+                            TransferError::IntoItself => TransferFileError::IntoItself,
+                            TransferError::IO(error) => {
+                                log::error!("Error transferring file: {}", error);
+                                match error.kind() {
+                                    IOErrorKind::NotFound => TransferFileError::FileDoesNotExist,
+                                    IOErrorKind::PermissionDenied => {
+                                        TransferFileError::PermissionDenied
+                                    }
+                                    _ => TransferFileError::Other(error.to_string()),
+                                }
+                            }
+                        };
+                        error
+                    })
+            }
+            Err(TryRecvError::Empty) => return Some(Step::Pending),
+            Err(error) => {
+                log::error!("Error receiving file transfer event: {}", error);
+                self.done = true;
+                Err(TransferFileError::Other(error.to_string()))
+            }
+        };
+        if self.done {
+            if let Some(file_transfer_handle) = self.file_transfer_handle.take() {
+                let _ = file_transfer_handle.join();
+            }
+        }
+
+        let response_params = TransferFileResponseParams::builder()
+            .from(self.from.clone())
+            .to(self.to.clone())
+            .result(result)
+            .build();
+        let response_params: ResponseParams = match self.moving {
+            true => ResponseParams::MoveFile(response_params),
+            false => ResponseParams::CopyFile(response_params),
+        };
+
+        Some(Step::Ready(
+            ResponseParamsAndLast::builder()
+                .response_params(response_params)
+                .last(self.done)
+                .build(),
+        ))
+    }
+}
+
+/// Handles getting the processes whose working directory is in a directory.
+struct GetProcesses {
+    /// The directory to get the processes in.
+    dir: PathBuf,
+    /// Whether or not getting the processes is done.
+    done: bool,
+}
+
+impl GetProcesses {
+    /// Return a new handler for getting processes.
+    fn new(params: &GetProcessesRequestParams) -> Self {
+        Self {
+            dir: params.dir().to_path_buf(),
+            done: false,
+        }
+    }
+}
+
+impl Iterator for GetProcesses {
+    type Item = ResponseParamsAndLast;
+
+    fn next(&mut self) -> Option<ResponseParamsAndLast> {
+        if self.done {
+            return None;
+        }
+
+        log::info!("Getting the processes in {:?}...", self.dir);
+        let get_processes_result: GetProcessesResult = processes_in(&self.dir);
+        if let Err(error) = &get_processes_result {
+            log::error!("Error getting processes: {}", error);
+        }
+
+        let response_params: ResponseParams = ResponseParams::GetProcesses(
+            GetProcessesResponseParams::builder()
+                .result(get_processes_result)
+                .build(),
+        );
+
+        self.done = true;
+
+        Some(
+            ResponseParamsAndLast::builder()
+                .response_params(response_params)
+                .last(true)
+                .build(),
+        )
+    }
+}
+
+/// Handles sending a signal to a process.
+struct SignalProcess {
+    /// The parameters of the request.
+    params: SignalProcessRequestParams,
+    /// Whether or not sending the signal is done.
+    done: bool,
+}
+
+impl SignalProcess {
+    /// Return a new handler for sending a signal to a process.
+    fn new(params: SignalProcessRequestParams) -> Self {
+        Self {
+            params,
+            done: false,
+        }
+    }
+}
+
+impl Iterator for SignalProcess {
+    type Item = ResponseParamsAndLast;
+
+    fn next(&mut self) -> Option<ResponseParamsAndLast> {
+        if self.done {
+            return None;
+        }
+
+        log::info!(
+            "Sending {} to process {}...",
+            self.params.signal(),
+            self.params.pid()
+        );
+        let signal_process_result: SignalProcessResult =
+            signal_process(self.params.pid(), self.params.signal());
+        if let Err(error) = &signal_process_result {
+            log::error!("Error sending signal: {}", error);
+        }
+
+        let response_params: ResponseParams = ResponseParams::SignalProcess(
+            SignalProcessResponseParams::builder()
+                .pid(self.params.pid())
+                .signal(self.params.signal())
+                .result(signal_process_result)
+                .build(),
+        );
+
+        self.done = true;
+
+        Some(
+            ResponseParamsAndLast::builder()
+                .response_params(response_params)
+                .last(true)
+                .build(),
+        )
+    }
+}
+
+/// Handles getting the files in a directory that are open by processes.
+struct GetOpenFiles {
+    /// The directory to get the open files in.
+    dir: PathBuf,
+    /// Whether or not getting the open files is done.
+    done: bool,
+}
+
+impl GetOpenFiles {
+    /// Return a new handler for getting open files.
+    fn new(params: &GetOpenFilesRequestParams) -> Self {
+        Self {
+            dir: params.dir().to_path_buf(),
+            done: false,
+        }
+    }
+}
+
+impl Iterator for GetOpenFiles {
+    type Item = ResponseParamsAndLast;
+
+    fn next(&mut self) -> Option<ResponseParamsAndLast> {
+        if self.done {
+            return None;
+        }
+
+        log::info!("Getting the open files in {:?}...", self.dir);
+        let get_open_files_result: GetOpenFilesResult = open_files_in(&self.dir);
+        if let Err(error) = &get_open_files_result {
+            log::error!("Error getting open files: {}", error);
+        }
+
+        let response_params: ResponseParams = ResponseParams::GetOpenFiles(
+            GetOpenFilesResponseParams::builder()
+                .result(get_open_files_result)
+                .build(),
+        );
+
+        self.done = true;
+
+        Some(
+            ResponseParamsAndLast::builder()
+                .response_params(response_params)
+                .last(true)
+                .build(),
+        )
+    }
+}
+
+/// Handles waiting for a device to be mounted or unmounted.
+///
+/// The request is pending (and polled by the request handler) until a device is mounted or
+/// unmounted or waiting times out.
+struct WaitForDevices {
+    /// Waiting for the devices to change.
+    wait: DevicesWait,
+    /// Whether or not waiting is done.
+    done: bool,
+}
+
+impl WaitForDevices {
+    /// Return a new handler for waiting for devices.
+    fn new(params: &WaitForDevicesRequestParams) -> Self {
+        Self {
+            wait: DevicesWait::new(params.wait()),
+            done: false,
+        }
+    }
+}
+
+impl Iterator for WaitForDevices {
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Step> {
+        if self.done {
+            return None;
+        }
+
+        let changed: bool = match self.wait.poll() {
+            Some(changed) => changed,
+            None => return Some(Step::Pending),
+        };
+        if changed {
+            log::info!("A device was mounted or unmounted.");
+        }
+
+        let response_params: ResponseParams = ResponseParams::WaitForDevices(
+            WaitForDevicesResponseParams::builder()
+                .changed(changed)
+                .build(),
+        );
+
+        self.done = true;
+
+        Some(Step::Ready(
+            ResponseParamsAndLast::builder()
+                .response_params(response_params)
+                .last(true)
+                .build(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    use test_case::test_case;
+
+    /// Return a handling of a request with `count` responses to touch files (sent to `responses_tx`).
+    fn touch_file_handling(count: usize, responses_tx: Sender<Response>) -> Handling {
+        let responses = (0..count).map(move |number| ResponseParamsAndLast {
+            response_params: ResponseParams::TouchFile(
+                TouchFileResponseParams::builder()
+                    .path(PathBuf::from(format!("/tmp/{}", number)))
+                    .result(Ok(SystemTime::UNIX_EPOCH))
+                    .build(),
+            ),
+            last: number + 1 == count,
+        });
+        Handling {
+            uuid: Uuid::new_v4(),
+            responses: Box::new(responses.map(Step::Ready)),
+            unsent: None,
+            sent_last: false,
+            cancellation_token: CancellationToken::new(),
+            responses_tx,
+        }
+    }
+
+    /// Return the response of a step if it is ready (for polling a request until it is done).
+    fn ready(step: Step) -> Option<ResponseParamsAndLast> {
+        match step {
+            Step::Ready(response) => Some(response),
+            Step::Pending => None,
+        }
+    }
+
+    #[test]
+    fn test_blocked_find_does_not_delay_other_requests() {
+        let (results_tx, results_rx) = channel::bounded(1);
+        let (responses_tx, responses_rx) = channel::unbounded();
+        let find_files = FindFiles {
+            results_rx,
+            file_finder_handle: None,
+            batch: Batch::new(FIND_FILES_BATCH_LEN),
+            done: false,
+        };
+        let mut find: Handling = touch_file_handling(0, responses_tx.clone());
+        find.responses = Box::new(find_files);
+        let mut touch: Handling = touch_file_handling(1, responses_tx);
+
+        // The file finder hasn't found anything, so the find is pending and the touch is handled.
+        let start: Instant = Instant::now();
+        assert!(matches!(find.advance(Duration::ZERO), Progress::Pending));
+        assert!(matches!(touch.advance(Duration::ZERO), Progress::Done));
+        assert!(start.elapsed() < BATCH_INTERVAL);
+        assert!(matches!(
+            responses_rx.try_recv().unwrap().params(),
+            ResponseParams::TouchFile(_)
+        ));
+
+        // A found file is held back for the batch interval and then sent.
+        results_tx
+            .send(Ok(Some(Entry::from(PathBuf::from("/r/a")))))
+            .unwrap();
+        assert!(matches!(find.advance(Duration::ZERO), Progress::Pending));
+        assert!(responses_rx.is_empty());
+        thread::sleep(BATCH_INTERVAL);
+        assert!(matches!(find.advance(Duration::ZERO), Progress::Pending));
+        assert!(!responses_rx.try_recv().unwrap().last());
+
+        results_tx.send(Ok(None)).unwrap();
+        assert!(matches!(find.advance(Duration::ZERO), Progress::Done));
+        assert!(responses_rx.try_recv().unwrap().last());
+    }
+
+    #[test]
+    fn test_slow_client_parks_request() {
+        let (responses_tx, responses_rx) = channel::bounded(1);
+        let mut handling: Handling = touch_file_handling(3, responses_tx);
+
+        // The client doesn't read, so the request is parked once the queue is full.
+        assert!(matches!(handling.advance(Duration::ZERO), Progress::Parked));
+        assert!(handling.unsent.is_some());
+
+        // Each time the client reads, the request gets further.
+        assert!(!responses_rx.recv().unwrap().last());
+        assert!(matches!(handling.advance(Duration::ZERO), Progress::Parked));
+        assert!(!responses_rx.recv().unwrap().last());
+        assert!(matches!(handling.advance(Duration::ZERO), Progress::Done));
+        assert!(responses_rx.recv().unwrap().last());
+    }
+
+    #[test_case(true; "cancelled")]
+    #[test_case(false; "disconnected")]
+    fn test_parked_request_is_dropped(cancelled: bool) {
+        let (responses_tx, responses_rx) = channel::bounded(1);
+        let mut handling: Handling = touch_file_handling(3, responses_tx);
+        assert!(matches!(handling.advance(Duration::ZERO), Progress::Parked));
+
+        match cancelled {
+            true => handling.cancellation_token.cancel(),
+            false => drop(responses_rx),
+        }
+        assert!(matches!(handling.advance(Duration::ZERO), Progress::Done));
+    }
+
+    #[test_case(false; "responds")]
+    #[test_case(true; "cancelled")]
+    fn test_pending_request_is_polled(cancelled: bool) {
+        let (responses_tx, responses_rx) = channel::bounded(1);
+        let mut handling: Handling = touch_file_handling(1, responses_tx);
+        let ready: Box<dyn Iterator<Item = Step>> = handling.responses;
+        handling.responses = Box::new(std::iter::once(Step::Pending).chain(ready));
+
+        assert!(matches!(
+            handling.advance(Duration::ZERO),
+            Progress::Pending
+        ));
+        assert!(responses_rx.is_empty());
+
+        if cancelled {
+            handling.cancellation_token.cancel();
+        }
+        assert!(matches!(handling.advance(Duration::ZERO), Progress::Done));
+        assert_eq!(responses_rx.len(), usize::from(!cancelled));
+    }
+
+    #[test]
+    fn test_get_files_depth() {
+        let dir: PathBuf = env::temp_dir().join(format!("inshd-get-files-{}", std::process::id()));
+        fs::create_dir_all(dir.join("a/b/c")).unwrap();
+        fs::create_dir_all(dir.join("d")).unwrap();
+        fs::write(dir.join("a/file"), b"").unwrap();
+
+        let params = GetFilesRequestParams::builder()
+            .dir(dir.clone())
+            .depth(2)
+            .build();
+        let responses: Vec<(PathBuf, usize, bool)> =
+            GetFiles::new(&params, &FileSystemHealth::default())
+                .map(|response| match response.response_params {
+                    ResponseParams::GetFiles(params) => (
+                        params.dir().strip_prefix(&dir).unwrap().to_path_buf(),
+                        params.result().as_ref().unwrap().len(),
+                        response.last,
+                    ),
+                    _ => panic!("Unexpected response parameters."),
+                })
+                .collect();
+
+        fs::remove_dir_all(&dir).unwrap();
+        let mut subdirs: Vec<(PathBuf, usize, bool)> = responses[1..3].to_vec();
+        subdirs.sort();
+        assert_eq!(responses.len(), 4);
+        assert_eq!(responses[0], (PathBuf::new(), 2, false));
+        assert_eq!(
+            subdirs,
+            vec![
+                (PathBuf::from("a"), 2, false),
+                (PathBuf::from("d"), 0, false)
+            ]
+        );
+        assert_eq!(responses[3], (PathBuf::from("a/b"), 1, true));
+    }
+
+    #[test]
+    fn test_find_files_batches() {
+        let dir: PathBuf = env::temp_dir().join(format!("inshd-find-files-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for number in 0..150 {
+            fs::write(dir.join(format!("file{}", number)), b"").unwrap();
+        }
+
+        let params = FindFilesRequestParams::builder()
+            .dir(dir.clone())
+            .pattern("file".to_string())
+            .build();
+        let responses: Vec<(usize, bool)> = FindFiles::run(
+            &params,
+            &CancellationToken::new(),
+            &FileSystemHealth::default(),
+        )
+        .filter_map(ready)
+        .map(|response| match response.response_params {
+            ResponseParams::FindFiles(params) => (params.entries().len(), response.last),
+            _ => panic!("Unexpected response parameters."),
+        })
+        .collect();
+
+        fs::remove_dir_all(&dir).unwrap();
+        let found: usize = responses.iter().map(|(len, _)| len).sum();
+        assert_eq!(found, 150);
+        assert!(responses.len() >= 3);
+        assert!(responses
+            .iter()
+            .all(|(len, _)| *len <= FIND_FILES_BATCH_LEN));
+        let lasts: Vec<bool> = responses.iter().map(|(_, last)| *last).collect();
+        assert_eq!(lasts.iter().filter(|last| **last).count(), 1);
+        assert_eq!(lasts.last(), Some(&true));
+    }
+
+    #[test_case("file", false, Ok(()); "file")]
+    #[test_case("empty", false, Ok(()); "empty directory")]
+    #[test_case("full", false, Err(DeleteFileError::DirectoryNotEmpty); "full directory")]
+    #[test_case("full", true, Ok(()); "recursive")]
+    #[test_case("missing", false, Err(DeleteFileError::FileDoesNotExist); "missing")]
+    fn test_delete_file(name: &str, recursive: bool, expected: DeleteFileResult) {
+        let dir: PathBuf = env::temp_dir().join(format!(
+            "inshd-delete-file-{}-{}-{}",
+            name,
+            recursive,
+            std::process::id()
+        ));
+        fs::create_dir_all(dir.join("empty")).unwrap();
+        fs::create_dir_all(dir.join("full")).unwrap();
+        fs::write(dir.join("full").join("file"), b"").unwrap();
+        fs::write(dir.join("file"), b"").unwrap();
+
+        let path: PathBuf = dir.join(name);
+        let params = DeleteFileRequestParams::builder()
+            .path(path.clone())
+            .recursive(recursive)
+            .build();
+        let responses: Vec<ResponseParamsAndLast> = DeleteFile::new(&params).collect();
+        let deleted: bool = !path.exists();
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(responses.len(), 1);
+        match &responses[0].response_params {
+            ResponseParams::DeleteFile(params) => assert_eq!(params.result(), &expected),
+            _ => panic!("Unexpected response parameters."),
+        }
+        assert_eq!(deleted, name == "missing" || expected.is_ok());
+    }
+
+    #[test_case("a", "c", Ok(()); "rename")]
+    #[test_case("a", "d/a", Ok(()); "into a directory")]
+    #[test_case("a", "b", Err(RenameFileError::AlreadyExists); "already exists")]
+    #[test_case("missing", "c", Err(RenameFileError::FileDoesNotExist); "missing")]
+    fn test_rename_file(from: &str, to: &str, expected: RenameFileResult) {
+        let dir: PathBuf = env::temp_dir().join(format!(
+            "inshd-rename-file-{}-{}-{}",
+            from,
+            to.replace('/', "-"),
+            std::process::id()
+        ));
+        fs::create_dir_all(dir.join("d")).unwrap();
+        fs::write(dir.join("a"), b"a").unwrap();
+        fs::write(dir.join("b"), b"b").unwrap();
+
+        let params = RenameFileRequestParams::builder()
+            .from(dir.join(from))
+            .to(dir.join(to))
+            .build();
+        let responses: Vec<ResponseParamsAndLast> = RenameFile::new(&params).collect();
+        let contents: Option<Vec<u8>> = fs::read(dir.join(to)).ok();
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(responses.len(), 1);
+        match &responses[0].response_params {
+            ResponseParams::RenameFile(params) => assert_eq!(params.result(), &expected),
+            _ => panic!("Unexpected response parameters."),
+        }
+        let expected_contents: Option<&[u8]> = match (&expected, to) {
+            (Ok(()), _) => Some(b"a"),
+            (_, "b") => Some(b"b"),
+            _ => None,
+        };
+        assert_eq!(contents.as_deref(), expected_contents);
+    }
+
+    #[test]
+    fn test_search_phrase() {
+        let dir: PathBuf =
+            env::temp_dir().join(format!("inshd-search-phrase-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for number in 0..40 {
+            fs::write(dir.join(format!("file{}", number)), b"foo\nbar\nfoo bar\n").unwrap();
+        }
+        fs::write(dir.join("other"), b"baz\n").unwrap();
+
+        let params = SearchPhraseRequestParams::builder()
+            .dir(dir.clone())
+            .phrase("foo".to_string())
+            .build();
+        let responses: Vec<(Vec<FileHit>, bool)> = SearchPhrase::run(
+            &params,
+            &CancellationToken::new(),
+            &FileSystemHealth::default(),
+        )
+        .filter_map(ready)
+        .map(|response| match response.response_params {
+            ResponseParams::SearchPhrase(params) => (params.into_file_hits(), response.last),
+            _ => panic!("Unexpected response parameters."),
+        })
+        .collect();
+
+        fs::remove_dir_all(&dir).unwrap();
+        let file_hits: Vec<&FileHit> = responses.iter().flat_map(|(hits, _)| hits).collect();
+        assert_eq!(file_hits.len(), 40);
+        assert!(file_hits
+            .iter()
+            .all(|file_hit| file_hit.line_hits().len() == 2));
+        assert!(responses
+            .iter()
+            .all(|(hits, _)| hits.len() <= SEARCH_PHRASE_BATCH_LEN));
+        let lasts: Vec<bool> = responses.iter().map(|(_, last)| *last).collect();
+        assert_eq!(lasts.iter().filter(|last| **last).count(), 1);
+        assert_eq!(lasts.last(), Some(&true));
+    }
+
+    #[test]
+    fn test_get_files_link_targets() {
+        let dir: PathBuf =
+            env::temp_dir().join(format!("inshd-link-targets-{}", std::process::id()));
+        fs::create_dir_all(dir.join("d")).unwrap();
+        fs::write(dir.join("f"), b"").unwrap();
+        std::os::unix::fs::symlink(dir.join("d"), dir.join("to_d")).unwrap();
+        std::os::unix::fs::symlink(dir.join("f"), dir.join("to_f")).unwrap();
+        std::os::unix::fs::symlink(dir.join("missing"), dir.join("broken")).unwrap();
+
+        let mut file_infos: Vec<FileInfo> = get_files(&dir, false).unwrap();
+        file_infos.sort_by(|a, b| a.path().cmp(b.path()));
+        let types: Vec<(String, bool, bool)> = file_infos
+            .iter()
+            .map(|file_info| {
+                (
+                    file_info.name().unwrap().to_string_lossy().to_string(),
+                    file_info.is_dir(),
+                    file_info.is_file(),
+                )
+            })
+            .collect();
+        let target: Option<PathBuf> = file_infos[3]
+            .link_target()
+            .map(|link_target| link_target.path().to_path_buf());
+        let expected_target: Option<PathBuf> = fs::canonicalize(dir.join("d")).ok();
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            types,
+            vec![
+                ("broken".to_string(), false, false),
+                ("d".to_string(), true, false),
+                ("f".to_string(), false, true),
+                ("to_d".to_string(), true, false),
+                ("to_f".to_string(), false, true),
+            ]
+        );
+        assert_eq!(target, expected_target);
+    }
+
+    #[test]
+    fn test_summarize() {
+        let file_infos: Vec<FileInfo> = [
+            ("/r/a", FileType::Dir),
+            ("/r/.git", FileType::Dir),
+            ("/r/b", FileType::File),
+            ("/r/.bashrc", FileType::File),
+            ("/r/c", FileType::Symlink),
+        ]
+        .into_iter()
+        .map(|(path, file_type)| {
+            FileInfo::builder()
+                .path(PathBuf::from(path))
+                .r#type(Ok(file_type))
+                .build()
+        })
+        .collect();
+
+        let expected = FilesSummary::builder().files(3).dirs(2).hidden(2).build();
+        assert_eq!(summarize(&file_infos), expected);
+    }
+}
@@ -2,10 +2,11 @@
 use std::thread;
 use std::thread::JoinHandle;
 
+use crate::file_system_health::FileSystemHealth;
+use crate::incoming_request::IncomingRequest;
 use crate::request_handler::RequestHandler;
 use crate::request_handler_died::RequestHandlerDied;
 use crate::stop::Stop;
-use insh_api::{Request, Response};
 
 use crossbeam::channel::{self, select, Receiver, Sender};
 use typed_builder::TypedBuilder;
@@ -18,11 +19,12 @@ pub struct RequestHandlerManager {
     /// A receiver of request handler dying information.
     died_rx: Receiver<RequestHandlerDied>,
     /// Receivers of requests for each request handler.
-    requests_rxs: Vec<Receiver<Request>>,
-    /// A senders of responses.
-    responses_tx: Sender<Response>,
+    requests_rxs: Vec<Receiver<IncomingRequest>>,
     /// A receiver of a stop sentinel.
     stop_rx: Receiver<Stop>,
+    /// The health of file systems (shared by the request handlers).
+    #[builder(default)]
+    file_system_health: FileSystemHealth,
 }
 
 impl RequestHandlerManager {
@@ -50,8 +52,8 @@ impl RequestHandlerManager {
             let mut request_handler = RequestHandler::builder()
                 .number(request_handler_num)
                 .requests(requests_rx)
-                .responses(self.responses_tx.clone())
                 .stop_rx(request_handler_stop_rx)
+                .file_system_health(self.file_system_health.clone())
                 .build();
             let name: String = format!("request-handler-{}", request_handler_num).to_string();
             let request_handler_handle: JoinHandle<()> = thread::Builder::new()
@@ -78,8 +80,8 @@ impl RequestHandlerManager {
                     let mut request_handler = RequestHandler::builder()
                         .number(number)
                         .requests(self.requests_rxs[number].clone())
-                        .responses(self.responses_tx.clone())
                         .stop_rx(request_handler_stop_rxs[number].clone())
+                        .file_system_health(self.file_system_health.clone())
                         .build();
                     let name: String = format!("request-handler-{}", number).to_string();
                     let request_handler_handle: JoinHandle<()> = thread::Builder::new()
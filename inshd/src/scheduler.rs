@@ -1,6 +1,6 @@
 //! Schedules requests.
+use crate::incoming_request::IncomingRequest;
 use crate::stop::Stop;
-use insh_api::Request;
 
 use crossbeam::channel::{Receiver, Sender};
 use crossbeam::select;
@@ -12,9 +12,9 @@ pub struct Scheduler {
     /// The number of request handlers.
     num_request_handlers: usize,
     /// Channels for sending requests to each request handler.
-    requests_txs: Vec<Sender<Request>>,
+    requests_txs: Vec<Sender<IncomingRequest>>,
     /// Incoming requests from client handlers.
-    incoming_requests_rx: Receiver<Request>,
+    incoming_requests_rx: Receiver<IncomingRequest>,
     /// A receiver for a stop sentinel.
     stop: Receiver<Stop>,
 }
@@ -33,7 +33,7 @@ impl Scheduler {
                     break;
                 }
                 recv(self.incoming_requests_rx) -> request => {
-                    let request: Request = match request {
+                    let request: IncomingRequest = match request {
                         Ok(request) => request,
                         Err(_) => {
                             log::warn!("Error receiving incoming request.");
@@ -45,7 +45,7 @@ impl Scheduler {
                         "Scheduling request with request handler {}.",
                         current_request_handler
                     );
-                    let requests_tx: &Sender<Request> = &self.requests_txs[current_request_handler];
+                    let requests_tx: &Sender<IncomingRequest> = &self.requests_txs[current_request_handler];
                     requests_tx.send(request).unwrap();
                     current_request_handler = (current_request_handler + 1) % self.num_request_handlers;
                 }
@@ -1,25 +1,21 @@
 //! The inshd server.
-use crate::client::Client;
 use crate::client_handler_handle::ClientHandlerHandle;
 use crate::client_handler_monitor::ClientHandlerMonitor;
-use crate::client_request::ClientRequest;
 use crate::conn_handler::ConnHandler;
 use crate::disconnected_client::DisconnectedClient;
+use crate::incoming_request::IncomingRequest;
 use crate::request_handler_died::RequestHandlerDied;
 use crate::request_handler_manager::RequestHandlerManager;
-use crate::response_handler::ResponseHandler;
 use crate::scheduler::Scheduler;
 use crate::signal_handler::SignalHandler;
 use crate::stop::Stop;
 use crate::INSHD_PID_FILE;
 use common::paths::INSHD_SOCKET;
-use insh_api::{Request, Response};
 
 use std::fs::remove_file;
 use std::io::Write;
 use std::os::unix::net::UnixListener;
 use std::panic;
-use std::panic::PanicInfo;
 use std::process::exit;
 use std::thread;
 use std::thread::JoinHandle;
@@ -74,42 +70,15 @@ impl Server {
             .spawn(move || signal_handler.run())
             .unwrap();
 
-        // Crate and spawn a response handler thread.
-        let (responses_tx, responses_rx): (Sender<Response>, Receiver<Response>) =
-            channel::unbounded();
-        let (new_clients_tx, new_clients_rx): (Sender<Client>, Receiver<Client>) =
-            channel::unbounded();
-        let (client_requests_tx, client_requests_rx): (
-            Sender<ClientRequest>,
-            Receiver<ClientRequest>,
-        ) = channel::unbounded();
-        let (disconnected_clients_tx, disconnected_clients_rx): (
-            Sender<DisconnectedClient>,
-            Receiver<DisconnectedClient>,
-        ) = channel::unbounded();
-        let mut disconnected_clients_txs: Vec<Sender<DisconnectedClient>> =
-            vec![disconnected_clients_tx.clone()];
-        let (response_handler_stop_tx, response_handler_stop_rx): (Sender<Stop>, Receiver<Stop>) =
-            channel::unbounded();
-        let mut response_handler = ResponseHandler::builder()
-            .responses_rx(responses_rx)
-            .new_clients_rx(new_clients_rx)
-            .client_requests_rx(client_requests_rx)
-            .disconnected_clients_rx(disconnected_clients_rx.clone())
-            .stop_rx(response_handler_stop_rx)
-            .build();
-        let response_handler_handle: JoinHandle<()> = thread::Builder::new()
-            .name("response-handler".to_string())
-            .spawn(move || response_handler.run())
-            .unwrap();
-
         // Create and spawn a request handler manager thread. The request handler manager starts
         // the request handler threads, restarts them if they die, and stops when it is time.
-        let mut requests_rxs: Vec<Receiver<Request>> = Vec::with_capacity(num_request_handlers);
-        let mut requests_txs: Vec<Sender<Request>> = Vec::with_capacity(num_request_handlers);
+        let mut requests_rxs: Vec<Receiver<IncomingRequest>> =
+            Vec::with_capacity(num_request_handlers);
+        let mut requests_txs: Vec<Sender<IncomingRequest>> =
+            Vec::with_capacity(num_request_handlers);
         for _ in 0..num_request_handlers {
             // Create the channels.
-            let (requests_tx, requests_rx): (Sender<Request>, Receiver<Request>) =
+            let (requests_tx, requests_rx): (Sender<IncomingRequest>, Receiver<IncomingRequest>) =
                 channel::unbounded();
             requests_rxs.push(requests_rx.clone());
             requests_txs.push(requests_tx);
@@ -122,7 +91,6 @@ impl Server {
             .num_request_handlers(num_request_handlers)
             .died_rx(died_rx)
             .requests_rxs(requests_rxs)
-            .responses_tx(responses_tx.clone())
             .stop_rx(request_handler_manager_stop_rx)
             .build();
         let request_handler_manager_handle: JoinHandle<()> = thread::Builder::new()
@@ -131,8 +99,10 @@ impl Server {
             .unwrap();
 
         // Create and spawn a scheduler to schedule the execution of requests with request handlers.
-        let (incoming_requests_tx, incoming_requests_rx): (Sender<Request>, Receiver<Request>) =
-            channel::unbounded();
+        let (incoming_requests_tx, incoming_requests_rx): (
+            Sender<IncomingRequest>,
+            Receiver<IncomingRequest>,
+        ) = channel::unbounded();
         let (scheduler_stop_tx, scheduler_stop_rx): (Sender<Stop>, Receiver<Stop>) =
             channel::unbounded();
         let mut scheduler: Scheduler = Scheduler::builder()
@@ -152,7 +122,8 @@ impl Server {
             Sender<DisconnectedClient>,
             Receiver<DisconnectedClient>,
         ) = channel::unbounded();
-        disconnected_clients_txs.push(disconnected_clients_tx.clone());
+        let disconnected_clients_txs: Vec<Sender<DisconnectedClient>> =
+            vec![disconnected_clients_tx.clone()];
         let (client_handler_handles_tx, client_handler_handles_rx): (
             Sender<ClientHandlerHandle>,
             Receiver<ClientHandlerHandle>,
@@ -175,9 +146,7 @@ impl Server {
         let (conn_handler_stop_rx, mut conn_handler_stop_tx) = os_pipe::pipe().unwrap();
         let mut conn_handler: ConnHandler = ConnHandler::builder()
             .listener(listener)
-            .new_clients_tx(new_clients_tx.clone())
             .incoming_requests_tx(incoming_requests_tx.clone())
-            .client_requests_tx(client_requests_tx.clone())
             .disconnected_clients_txs(disconnected_clients_txs)
             .client_handler_handles_tx(client_handler_handles_tx.clone())
             .stop_rx(conn_handler_stop_rx)
@@ -212,10 +181,6 @@ impl Server {
         let _ = signal_handler_handle.join();
         log::info!("Signal handler stopped.");
 
-        response_handler_stop_tx.send(Stop::new()).unwrap();
-        let _ = response_handler_handle.join();
-        log::info!("Response handler stopped.");
-
         log::info!("All threads stopped.");
 
         Server::cleanup();
@@ -224,7 +189,7 @@ impl Server {
 
     /// Set the panic hook.
     fn set_panic_hook(died_tx: Sender<RequestHandlerDied>) {
-        panic::set_hook(Box::new(move |panic_info: &PanicInfo| {
+        panic::set_hook(Box::new(move |panic_info| {
             let thread_handle = thread::current();
             let thread_name: &str = match thread_handle.name() {
                 Some(thread_name) => thread_name,
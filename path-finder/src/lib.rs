@@ -2,38 +2,146 @@
 The module contains the [`PathFinder`] struct which is used to find files with file names matching a
 pattern.
 */
+use std::borrow::Cow;
 use std::ffi::OsStr;
 use std::fmt::{Display, Error as FmtError, Formatter};
 use std::path::{Path, PathBuf};
 
+use ignore::{DirEntry as IgnoreEntry, Walk as IgnoreWalker, WalkBuilder as IgnoreWalkerBuilder};
 use regex::Error as RegexError;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use walkdir::{
-    DirEntry as WalkdirEntry, Error as WalkerEntryError, IntoIter as Walker,
-    WalkDir as WalkerBuilder,
+    DirEntry as WalkdirEntry, FilterEntry, IntoIter as WalkdirWalker, WalkDir as WalkerBuilder,
 };
 
 /// Used to find files with file names matching a pattern.
 pub struct PathFinder {
     /// The pattern to match file names against.
     regex: Regex,
+    /// Whether file names are case folded and normalized before they are matched (the pattern is
+    /// folded too).
+    unicode_matching: bool,
     /// An iterator over the files in a given directory (recursive).
     walker: Walker,
 }
 
+/// A predicate of which walked entries are kept (the directories that aren't kept are not descended
+/// into).
+type WalkdirFilter = Box<dyn FnMut(&WalkdirEntry) -> bool + Send>;
+
+/// An iterator over the paths of the files in a directory (recursive), which yields `None` for the
+/// entries that could not be read.
+enum Walker {
+    /// Walks all of the files.
+    All(FilterEntry<WalkdirWalker, WalkdirFilter>),
+    /// Walks the files that are not hidden or ignored by ignore files.
+    Unignored(IgnoreWalker),
+}
+
+impl Iterator for Walker {
+    type Item = Option<PathBuf>;
+
+    fn next(&mut self) -> Option<Option<PathBuf>> {
+        match self {
+            Self::All(walker) => walker
+                .next()
+                .map(|entry| entry.ok().map(WalkdirEntry::into_path)),
+            Self::Unignored(walker) => walker
+                .next()
+                .map(|entry| entry.ok().map(IgnoreEntry::into_path)),
+        }
+    }
+}
+
 impl PathFinder {
     /// Return a new path finder that can be used to find the files in the given `directory` with
-    /// file names that match the regex `pattern`.
-    pub fn new(directory: &Path, pattern: &str) -> Result<Self, NewPathFinderError> {
-        let regex: Regex = match Regex::new(pattern) {
+    /// file names that match the regex `pattern`. If `same_file_system` is true, then directories
+    /// on a different file system than `directory` are not descended into. If `unicode_matching`
+    /// is true, then the pattern and the file names are case folded and normalized (so that for
+    /// example `cafe\u{301}` and `CAFÉ` both match `café`). If `respect_ignore` is true, then
+    /// hidden files and the files ignored by ignore files (such as `.gitignore` and `.ignore`) are
+    /// skipped. The directories for which `skip_dir` returns true are not descended into.
+    pub fn new(
+        directory: &Path,
+        pattern: &str,
+        same_file_system: bool,
+        unicode_matching: bool,
+        respect_ignore: bool,
+        skip_dir: impl Fn(&Path) -> bool + Send + Sync + 'static,
+    ) -> Result<Self, NewPathFinderError> {
+        let pattern: Cow<str> = match unicode_matching {
+            true => Cow::Owned(fold_pattern(pattern)),
+            false => Cow::Borrowed(pattern),
+        };
+        let regex: Regex = match Regex::new(&pattern) {
             Ok(regex) => regex,
             Err(error) => return Err(NewPathFinderError::RegexError(error)),
         };
-        let walker = WalkerBuilder::new(directory).min_depth(1).into_iter();
+        let walker: Walker = match respect_ignore {
+            true => Walker::Unignored(
+                IgnoreWalkerBuilder::new(directory)
+                    .same_file_system(same_file_system)
+                    .filter_entry(move |entry| {
+                        !(entry
+                            .file_type()
+                            .is_some_and(|file_type| file_type.is_dir())
+                            && skip_dir(entry.path()))
+                    })
+                    .build(),
+            ),
+            false => Walker::All(
+                WalkerBuilder::new(directory)
+                    .min_depth(1)
+                    .same_file_system(same_file_system)
+                    .into_iter()
+                    .filter_entry(Box::new(move |entry| {
+                        !(entry.file_type().is_dir() && skip_dir(entry.path()))
+                    })),
+            ),
+        };
 
-        Ok(PathFinder { regex, walker })
+        Ok(PathFinder {
+            regex,
+            unicode_matching,
+            walker,
+        })
+    }
+}
+
+/// Return the regex `pattern` with the characters outside of escapes case folded and normalized
+/// (escapes such as `\W` or `\p{Lu}` are kept as they are because folding would change them).
+fn fold_pattern(pattern: &str) -> String {
+    let mut folded: String = String::with_capacity(pattern.len());
+    // The characters since the last escape (which are folded together so that combining
+    // characters are normalized with the characters before them).
+    let mut literal: String = String::new();
+    let mut characters = pattern.chars().peekable();
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            literal.push(character);
+            continue;
+        }
+
+        folded.push_str(&unicode_fold::fold(&literal));
+        literal.clear();
+
+        folded.push(character);
+        if let Some(escaped) = characters.next() {
+            folded.push(escaped);
+            // Escapes such as `\p{Letter}` and `\x{e9}` continue until the closing brace.
+            if characters.peek() == Some(&'{') {
+                for character in characters.by_ref() {
+                    folded.push(character);
+                    if character == '}' {
+                        break;
+                    }
+                }
+            }
+        }
     }
+    folded.push_str(&unicode_fold::fold(&literal));
+    folded
 }
 
 pub enum NewPathFinderError {
@@ -55,21 +163,27 @@ impl Iterator for PathFinder {
 
     fn next(&mut self) -> Option<Entry> {
         loop {
-            let entry: Option<Result<WalkdirEntry, WalkerEntryError>> = self.walker.next();
+            let path: Option<Option<PathBuf>> = self.walker.next();
 
-            match entry {
+            match path {
                 None => {
                     return None;
                 }
-                Some(entry) => match entry {
-                    Err(_) => continue,
-                    Ok(entry) => {
-                        if entry.path().is_dir() {
+                Some(path) => match path {
+                    None => continue,
+                    Some(path) => {
+                        if path.is_dir() {
                             continue;
                         }
 
-                        if self.regex.is_match(&entry.file_name().to_string_lossy()) {
-                            return Some(entry.into());
+                        let file_name: Cow<str> =
+                            path.file_name().unwrap_or_default().to_string_lossy();
+                        let file_name: Cow<str> = match self.unicode_matching {
+                            true => Cow::Owned(unicode_fold::fold(&file_name)),
+                            false => file_name,
+                        };
+                        if self.regex.is_match(&file_name) {
+                            return Some(path.into());
                         }
                         continue;
                     }
@@ -79,28 +193,37 @@ impl Iterator for PathFinder {
     }
 }
 
-impl From<Walker> for PathFinder {
-    fn from(walker: Walker) -> Self {
+impl From<WalkdirWalker> for PathFinder {
+    fn from(walker: WalkdirWalker) -> Self {
         PathFinder {
             regex: Regex::new(".*").unwrap(),
-            walker,
+            unicode_matching: false,
+            walker: Walker::All(walker.filter_entry(Box::new(|_| true))),
         }
     }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Entry {
+    #[serde(with = "path_serde")]
     path: PathBuf,
 }
 
 impl From<WalkdirEntry> for Entry {
     fn from(walkdir_entry: WalkdirEntry) -> Self {
+        // NOTE: The path of the walkdir entry is moved (instead of copied).
         Self {
-            path: walkdir_entry.path().to_path_buf(),
+            path: walkdir_entry.into_path(),
         }
     }
 }
 
+impl From<PathBuf> for Entry {
+    fn from(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
 impl Entry {
     pub fn path(&self) -> &Path {
         &self.path
@@ -110,3 +233,81 @@ impl Entry {
         self.path.file_name()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("foo", "foo"; "lowercase")]
+    #[test_case("FOO", "foo"; "uppercase")]
+    #[test_case("Stra\u{df}e", "strasse"; "sharp s")]
+    #[test_case("cafe\u{301}", "caf\u{e9}"; "decomposed")]
+    #[test_case(r"\W\D", r"\W\D"; "escapes")]
+    #[test_case(r"\p{Lu}X", r"\p{Lu}x"; "escape with braces")]
+    #[test_case(r"A\", r"a\"; "trailing backslash")]
+    fn test_fold_pattern(pattern: &str, expected: &str) {
+        assert_eq!(fold_pattern(pattern), expected);
+    }
+
+    #[test_case(false, &[".hidden", ".ignore", "a/ignored", "a/kept", "kept"]; "all files")]
+    #[test_case(true, &["a/kept", "kept"]; "respecting ignore files")]
+    fn test_respect_ignore(respect_ignore: bool, expected: &[&str]) {
+        let dir: PathBuf = std::env::temp_dir().join(format!(
+            "path-finder-{}-{}",
+            std::process::id(),
+            respect_ignore
+        ));
+        std::fs::create_dir_all(dir.join("a")).unwrap();
+        for file in ["kept", "a/kept", "a/ignored", ".hidden"] {
+            std::fs::write(dir.join(file), b"").unwrap();
+        }
+        std::fs::write(dir.join(".ignore"), b"ignored\n").unwrap();
+
+        let path_finder = match PathFinder::new(&dir, "", false, false, respect_ignore, |_| false) {
+            Ok(path_finder) => path_finder,
+            Err(_) => panic!("Failed to construct the path finder."),
+        };
+        let mut paths: Vec<String> = path_finder
+            .map(|entry| {
+                let path: &Path = entry.path().strip_prefix(&dir).unwrap();
+                path.to_string_lossy().to_string()
+            })
+            .collect();
+        paths.sort();
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(paths, expected);
+    }
+
+    #[test_case(false; "all files")]
+    #[test_case(true; "respecting ignore files")]
+    fn test_skip_dir(respect_ignore: bool) {
+        let dir: PathBuf = std::env::temp_dir().join(format!(
+            "path-finder-skip-{}-{}",
+            std::process::id(),
+            respect_ignore
+        ));
+        for file in ["kept", "a/kept", "slow/skipped", "slow/b/skipped"] {
+            let path: PathBuf = dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"").unwrap();
+        }
+
+        let skip_dir = |path: &Path| path.ends_with("slow");
+        let path_finder = match PathFinder::new(&dir, "", false, false, respect_ignore, skip_dir) {
+            Ok(path_finder) => path_finder,
+            Err(_) => panic!("Failed to construct the path finder."),
+        };
+        let mut paths: Vec<String> = path_finder
+            .map(|entry| {
+                let path: &Path = entry.path().strip_prefix(&dir).unwrap();
+                path.to_string_lossy().to_string()
+            })
+            .collect();
+        paths.sort();
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(paths, ["a/kept", "kept"]);
+    }
+}
@@ -0,0 +1,178 @@
+/*!
+Serialization of paths as the bytes of the path (for use with `#[serde(with = "path_serde")]`).
+
+By default serde serializes a path as a string, which copies nothing but fails for paths that are
+not valid UTF-8 and has to validate the path when it is deserialized. On unix a path is just bytes,
+so serializing the bytes borrows them from the path and deserializing them moves them into the path.
+
+With bincode the bytes are encoded the same as a string, so paths that are valid UTF-8 are encoded
+the same as before.
+
+The [`cow`] module deserializes a `Cow<Path>` instead, which borrows the bytes of the path from the
+serialized data (when the deserializer can lend them, like bincode decoding a slice) so that a path
+which is only looked at is never copied.
+*/
+use std::borrow::Cow;
+use std::ffi::{OsStr, OsString};
+use std::fmt::{Error as FmtError, Formatter};
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::path::{Path, PathBuf};
+
+use serde::de::{Error as DeError, SeqAccess, Visitor};
+use serde::{Deserializer, Serializer};
+
+/// Serialize a path as its bytes.
+pub fn serialize<S: Serializer>(path: &Path, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_bytes(path.as_os_str().as_bytes())
+}
+
+/// Deserialize a path from its bytes.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<PathBuf, D::Error> {
+    deserializer.deserialize_byte_buf(PathVisitor)
+}
+
+/// Visits the bytes of a path.
+struct PathVisitor;
+
+impl<'de> Visitor<'de> for PathVisitor {
+    type Value = PathBuf;
+
+    fn expecting(&self, formatter: &mut Formatter) -> Result<(), FmtError> {
+        write!(formatter, "the bytes of a path")
+    }
+
+    fn visit_byte_buf<E: DeError>(self, bytes: Vec<u8>) -> Result<PathBuf, E> {
+        Ok(PathBuf::from(OsString::from_vec(bytes)))
+    }
+
+    fn visit_bytes<E: DeError>(self, bytes: &[u8]) -> Result<PathBuf, E> {
+        self.visit_byte_buf(bytes.to_vec())
+    }
+
+    fn visit_str<E: DeError>(self, string: &str) -> Result<PathBuf, E> {
+        Ok(PathBuf::from(string))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<PathBuf, A::Error> {
+        let mut bytes: Vec<u8> = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        self.visit_byte_buf(bytes)
+    }
+}
+
+/// Serialization of borrowed paths as their bytes (for use with
+/// `#[serde(borrow, with = "path_serde::cow")]`).
+pub mod cow {
+    use super::*;
+
+    pub use super::serialize;
+
+    /// Deserialize a path from its bytes (borrowing them if the deserializer can lend them).
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Cow<'de, Path>, D::Error> {
+        deserializer.deserialize_bytes(CowPathVisitor)
+    }
+
+    /// Visits the bytes of a path (which are borrowed if they can be).
+    struct CowPathVisitor;
+
+    impl<'de> Visitor<'de> for CowPathVisitor {
+        type Value = Cow<'de, Path>;
+
+        fn expecting(&self, formatter: &mut Formatter) -> Result<(), FmtError> {
+            write!(formatter, "the bytes of a path")
+        }
+
+        fn visit_borrowed_bytes<E: DeError>(self, bytes: &'de [u8]) -> Result<Cow<'de, Path>, E> {
+            Ok(Cow::Borrowed(Path::new(OsStr::from_bytes(bytes))))
+        }
+
+        fn visit_borrowed_str<E: DeError>(self, string: &'de str) -> Result<Cow<'de, Path>, E> {
+            Ok(Cow::Borrowed(Path::new(string)))
+        }
+
+        fn visit_byte_buf<E: DeError>(self, bytes: Vec<u8>) -> Result<Cow<'de, Path>, E> {
+            PathVisitor.visit_byte_buf(bytes).map(Cow::Owned)
+        }
+
+        fn visit_bytes<E: DeError>(self, bytes: &[u8]) -> Result<Cow<'de, Path>, E> {
+            PathVisitor.visit_bytes(bytes).map(Cow::Owned)
+        }
+
+        fn visit_str<E: DeError>(self, string: &str) -> Result<Cow<'de, Path>, E> {
+            PathVisitor.visit_str(string).map(Cow::Owned)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Cow<'de, Path>, A::Error> {
+            PathVisitor.visit_seq(seq).map(Cow::Owned)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::value::{BytesDeserializer, Error as ValueError};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct File {
+        #[serde(with = "crate")]
+        path: PathBuf,
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let file = File {
+            path: PathBuf::from("/tmp/foo.txt"),
+        };
+
+        let bytes: Vec<u8> = bincode::serialize(&file).unwrap();
+
+        assert_eq!(bincode::deserialize::<File>(&bytes).unwrap(), file);
+    }
+
+    #[test]
+    fn test_not_utf8() {
+        let file = File {
+            path: PathBuf::from(OsStr::from_bytes(b"/tmp/\xff.txt")),
+        };
+
+        let bytes: Vec<u8> = bincode::serialize(&file).unwrap();
+
+        assert_eq!(bincode::deserialize::<File>(&bytes).unwrap(), file);
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct BorrowedFile<'a> {
+        #[serde(borrow, with = "crate::cow")]
+        path: Cow<'a, Path>,
+    }
+
+    #[test]
+    fn test_cow_borrows() {
+        let file = BorrowedFile {
+            path: Cow::Borrowed(Path::new(OsStr::from_bytes(b"/tmp/\xff.txt"))),
+        };
+
+        let bytes: Vec<u8> = bincode::serialize(&file).unwrap();
+
+        let decoded: BorrowedFile = bincode::deserialize(&bytes).unwrap();
+        assert!(matches!(decoded.path, Cow::Borrowed(_)));
+        assert_eq!(decoded, file);
+    }
+
+    #[test]
+    fn test_cow_copies() {
+        // The bytes are copied if the deserializer can't lend them.
+        let deserializer = BytesDeserializer::<ValueError>::new(b"/tmp/foo.txt");
+
+        let path: Cow<Path> = cow::deserialize(deserializer).unwrap();
+
+        assert!(matches!(path, Cow::Owned(_)));
+        assert_eq!(path, Path::new("/tmp/foo.txt"));
+    }
+}
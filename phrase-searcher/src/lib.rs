@@ -0,0 +1,445 @@
+/*!
+This crate contains the struct [`PhraseSearcher`] which can be used to search for a given phrase in
+the files in a directory (and all sub-directories).
+
+The files are searched by a pool of threads (one per cpu) while another thread walks the directory.
+Each file is searched for the phrase as a whole (using a SIMD accelerated substring search) instead
+of line by line, so only the lines that contain hits are ever split out and counted. Large files are
+memory mapped instead of read.
+
+The file hits are returned in the order that the files are walked in (the same order as searching
+the files one at a time).
+
+Files in other encodings than UTF-8 (such as UTF-16 and Latin-1) are decoded before they are
+searched and binary files are skipped (see [`text_encoding`]).
+
+With unicode matching, the phrase and the files are case folded and normalized before they are
+searched (see [`unicode_fold`]).
+*/
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::str;
+use std::thread;
+
+use crossbeam::channel::{Receiver, Sender};
+use memchr::memmem::Finder;
+use memchr::{memchr, memchr_iter, memrchr};
+use memmap2::Mmap;
+use serde::{Deserialize, Serialize};
+use text_encoding::{Encoding, SAMPLE_LEN};
+use walkdir::{DirEntry as Entry, Result as WalkdirResult, WalkDir as WalkerBuilder};
+
+/// Files at least this large are memory mapped instead of read.
+const MMAP_THRESHOLD: u64 = 1 << 20;
+
+/// The number of walked paths that can wait to be searched.
+const PATHS_CAPACITY: usize = 256;
+
+/// Used to search for phrases in files.
+pub struct PhraseSearcher {
+    /// The results of searching the files (numbered in the order that the files were walked in).
+    results_rx: Receiver<(usize, Option<FileHit>)>,
+    /// The results that were received before the results of files walked earlier.
+    pending: HashMap<usize, Option<FileHit>>,
+    /// The number of the file whose result is returned next.
+    next_number: usize,
+}
+
+impl PhraseSearcher {
+    /// Return a new phrase searcher. If `same_file_system` is true, then directories on a
+    /// different file system than `directory` are not searched. If `unicode_matching` is true,
+    /// then the phrase matches lines regardless of case and of how accents are encoded. The
+    /// directories for which `skip_dir` returns true are not searched.
+    ///
+    /// The search starts right away in the background (and stops when the searcher is dropped).
+    pub fn new(
+        directory: &Path,
+        phrase: &str,
+        same_file_system: bool,
+        unicode_matching: bool,
+        skip_dir: impl Fn(&Path) -> bool + Send + 'static,
+    ) -> Self {
+        let walker = WalkerBuilder::new(directory)
+            .min_depth(1)
+            .same_file_system(same_file_system)
+            .into_iter()
+            .filter_entry(move |entry| !(entry.file_type().is_dir() && skip_dir(entry.path())));
+
+        let (paths_tx, paths_rx) = crossbeam::channel::bounded(PATHS_CAPACITY);
+        let (results_tx, results_rx) = crossbeam::channel::bounded(PATHS_CAPACITY);
+
+        thread::Builder::new()
+            .name("phrase-searcher-walker".to_string())
+            .spawn(move || walk(walker, paths_tx))
+            .unwrap();
+
+        let number_of_searchers: usize = thread::available_parallelism()
+            .map(NonZeroUsize::get)
+            .unwrap_or(1);
+        for searcher_number in 0..number_of_searchers {
+            let paths_rx: Receiver<(usize, PathBuf)> = paths_rx.clone();
+            let results_tx: Sender<(usize, Option<FileHit>)> = results_tx.clone();
+            let phrase: String = match unicode_matching {
+                true => unicode_fold::fold(phrase),
+                false => phrase.to_string(),
+            };
+            thread::Builder::new()
+                .name(format!("phrase-searcher-{}", searcher_number))
+                .spawn(move || search(&phrase, unicode_matching, paths_rx, results_tx))
+                .unwrap();
+        }
+
+        Self {
+            results_rx,
+            pending: HashMap::new(),
+            next_number: 0,
+        }
+    }
+}
+
+impl Iterator for PhraseSearcher {
+    type Item = FileHit;
+
+    fn next(&mut self) -> Option<FileHit> {
+        loop {
+            if let Some(result) = self.pending.remove(&self.next_number) {
+                self.next_number += 1;
+                match result {
+                    Some(file_hit) => return Some(file_hit),
+                    None => continue,
+                }
+            }
+
+            // Every walked file has a result, so the searchers are only done once all of the
+            // results have been received.
+            match self.results_rx.recv() {
+                Ok((number, result)) => {
+                    self.pending.insert(number, result);
+                }
+                Err(_) => return None,
+            }
+        }
+    }
+}
+
+/// Send the paths of the files to search (numbered in the order that they are walked in).
+fn walk(walker: impl Iterator<Item = WalkdirResult<Entry>>, paths_tx: Sender<(usize, PathBuf)>) {
+    let mut number: usize = 0;
+    for entry in walker {
+        let entry: Entry = match entry {
+            Ok(entry) => entry,
+            Err(_) => continue,
+        };
+        if entry.path().is_dir() {
+            continue;
+        }
+
+        if paths_tx.send((number, entry.into_path())).is_err() {
+            // The phrase searcher was dropped.
+            return;
+        }
+        number += 1;
+    }
+}
+
+/// Search the files for the phrase and send the results.
+fn search(
+    phrase: &str,
+    unicode_matching: bool,
+    paths_rx: Receiver<(usize, PathBuf)>,
+    results_tx: Sender<(usize, Option<FileHit>)>,
+) {
+    let finder: Finder = Finder::new(phrase);
+    while let Ok((number, path)) = paths_rx.recv() {
+        let result: Option<FileHit> = search_file(&path, &finder, unicode_matching);
+        if results_tx.send((number, result)).is_err() {
+            // The phrase searcher was dropped.
+            return;
+        }
+    }
+}
+
+/// Search a file for the phrase. Return nothing if there are no hits or if the file could not be
+/// read as UTF-8.
+fn search_file(path: &Path, finder: &Finder, unicode_matching: bool) -> Option<FileHit> {
+    let mut file: File = File::open(path).ok()?;
+    let len: u64 = file.metadata().ok()?.len();
+
+    let line_hits: Vec<LineHit> = if len >= MMAP_THRESHOLD {
+        // SAFETY: The map is only read while the file is searched. If the file is truncated by
+        // another process in the meantime then reading the map can fault, which is the same
+        // trade-off that other search tools make for the speed of not copying large files.
+        let mmap: Mmap = unsafe { Mmap::map(&file) }.ok()?;
+        search_bytes(&mmap, finder, unicode_matching)?
+    } else {
+        let mut bytes: Vec<u8> = Vec::with_capacity(len as usize);
+        file.read_to_end(&mut bytes).ok()?;
+        search_bytes(&bytes, finder, unicode_matching)?
+    };
+
+    Some(FileHit::new(path, line_hits))
+}
+
+/// Return the lines of the contents of a file that contain the phrase. Return nothing if there are
+/// no hits or if the file looks binary.
+fn search_bytes(bytes: &[u8], finder: &Finder, unicode_matching: bool) -> Option<Vec<LineHit>> {
+    match text_encoding::detect(&bytes[..bytes.len().min(SAMPLE_LEN)])? {
+        Encoding::Utf8 => {
+            let bytes: &[u8] = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+            search_text(bytes, finder, unicode_matching)
+        }
+        encoding => {
+            let text: Cow<str> = text_encoding::decode(bytes, encoding);
+            search_text(text.as_bytes(), finder, unicode_matching)
+        }
+    }
+}
+
+/// Return the lines of `bytes` that contain the phrase. Return nothing if there are no hits or if
+/// the bytes are not UTF-8.
+///
+/// Lines are split like [`std::io::BufRead::lines`] (on `\n`, without a trailing `\r`) and hits
+/// are only counted when the whole phrase is within one line.
+fn search_text(bytes: &[u8], finder: &Finder, unicode_matching: bool) -> Option<Vec<LineHit>> {
+    if unicode_matching {
+        return search_folded(bytes, finder);
+    }
+
+    let lines: Vec<(usize, usize, usize)> = find_lines(bytes, finder);
+    if lines.is_empty() {
+        return None;
+    }
+
+    // Files that are not UTF-8 are not searched (like reading them line by line would fail).
+    let text: &str = str::from_utf8(bytes).ok()?;
+    Some(
+        lines
+            .into_iter()
+            .map(|(line_number, start, end)| LineHit::new(line_number, &text[start..end]))
+            .collect(),
+    )
+}
+
+/// Return the lines of `bytes` whose folded text contains the (folded) phrase.
+fn search_folded(bytes: &[u8], finder: &Finder) -> Option<Vec<LineHit>> {
+    let text: &str = str::from_utf8(bytes).ok()?;
+    let folded: String = unicode_fold::fold(text);
+
+    // NOTE: Folding can change the length of lines, so only the numbers of the folded lines are
+    // used (folding keeps the newlines, so the line numbers are the same).
+    let line_numbers: Vec<usize> = find_lines(folded.as_bytes(), finder)
+        .into_iter()
+        .map(|(line_number, _, _)| line_number)
+        .collect();
+    if line_numbers.is_empty() {
+        return None;
+    }
+
+    Some(
+        text.split('\n')
+            .zip(1..)
+            .filter(|(_, line_number)| line_numbers.binary_search(line_number).is_ok())
+            .map(|(line, line_number)| {
+                LineHit::new(line_number, line.strip_suffix('\r').unwrap_or(line))
+            })
+            .collect(),
+    )
+}
+
+/// Return the line number, start, and end of the lines of `bytes` that contain the phrase.
+fn find_lines(bytes: &[u8], finder: &Finder) -> Vec<(usize, usize, usize)> {
+    let phrase_len: usize = finder.needle().len();
+
+    // The line number, start, and end of the lines that contain the phrase.
+    let mut lines: Vec<(usize, usize, usize)> = Vec::new();
+    let mut line_number: usize = 1;
+    // The line numbers are counted up to here.
+    let mut counted: usize = 0;
+    // The start of the line to search from.
+    let mut position: usize = 0;
+    while position < bytes.len() {
+        let hit: usize = match finder.find(&bytes[position..]) {
+            Some(index) => position + index,
+            None => break,
+        };
+        let start: usize =
+            position + memrchr(b'\n', &bytes[position..hit]).map_or(0, |index| index + 1);
+        let newline: Option<usize> = memchr(b'\n', &bytes[hit..]).map(|index| hit + index);
+        let mut end: usize = newline.unwrap_or(bytes.len());
+        if end > start && bytes[end - 1] == b'\r' {
+            end -= 1;
+        }
+
+        line_number += memchr_iter(b'\n', &bytes[counted..start]).count();
+        counted = start;
+        if hit + phrase_len <= end {
+            lines.push((line_number, start, end));
+        }
+
+        position = match newline {
+            Some(newline) => newline + 1,
+            None => bytes.len(),
+        };
+    }
+
+    lines
+}
+
+/// A file contains lines which have hits for a phrase.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileHit {
+    /// The path of the file.
+    #[serde(with = "path_serde")]
+    path: PathBuf,
+    /// The lines containing hits.
+    line_hits: Vec<LineHit>,
+}
+
+impl FileHit {
+    /// Return a new file hit.
+    pub fn new(path: &Path, line_hits: Vec<LineHit>) -> Self {
+        let path: PathBuf = path.to_path_buf();
+        Self { path, line_hits }
+    }
+
+    /// Return the path of the file containing line hits.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Return all the lint hits.
+    pub fn line_hits(&self) -> &Vec<LineHit> {
+        &self.line_hits
+    }
+}
+
+/// Represents a line contains a hit for a phrase in a file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineHit {
+    /// The line number in the file.
+    line_number: usize,
+    /// The contents of the line.
+    line: String,
+}
+
+impl LineHit {
+    /// Return a new line hit.
+    pub fn new(line_number: usize, line: &str) -> Self {
+        Self {
+            line_number,
+            line: line.to_string(),
+        }
+    }
+
+    /// Return the line number of the line hit.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// Return the contents of the line hit.
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+    use test_case::test_case;
+    use uuid::Uuid;
+
+    #[test_case(b"", "foo", None; "empty file")]
+    #[test_case(b"bar\nbaz\n", "foo", None; "no hits")]
+    #[test_case(b"foo", "foo", Some(vec![(1, "foo")]); "no trailing newline")]
+    #[test_case(
+        b"foo\nbar\nfoo foo\n\nbaz foo",
+        "foo",
+        Some(vec![(1, "foo"), (3, "foo foo"), (5, "baz foo")]);
+        "hits on multiple lines"
+    )]
+    #[test_case(b"bar\r\nfoo\r\n", "foo", Some(vec![(2, "foo")]); "carriage returns")]
+    #[test_case(b"fo\no\n", "fo\no", None; "phrase across lines")]
+    #[test_case(b"foo\r\n", "foo\r", None; "phrase includes carriage return")]
+    #[test_case(b"foo\n\xff\n", "foo", Some(vec![(1, "foo")]); "latin1")]
+    #[test_case(b"\xff\xfef\x00o\x00o\x00\n\x00", "foo", Some(vec![(1, "foo")]); "utf16")]
+    #[test_case(b"\xef\xbb\xbffoo", "foo", Some(vec![(1, "foo")]); "utf8 bom")]
+    #[test_case(b"foo\x00\x01\x02\x00", "foo", None; "binary")]
+    #[test_case(b"a\nb\n", "", Some(vec![(1, "a"), (2, "b")]); "empty phrase")]
+    fn test_search_bytes(bytes: &[u8], phrase: &str, expected: Option<Vec<(usize, &str)>>) {
+        assert_eq!(
+            search_bytes(bytes, &Finder::new(phrase), false),
+            line_hits(expected)
+        );
+    }
+
+    #[test_case(b"Caf\xc3\xa9\nbar\n", "CAFE\u{301}", Some(vec![(1, "Caf\u{e9}")]); "accent")]
+    #[test_case(b"bar\r\nSTRASSE\r\n", "stra\u{df}e", Some(vec![(2, "STRASSE")]); "sharp s")]
+    #[test_case(b"foo\n", "bar", None; "no hits")]
+    fn test_search_bytes_unicode(bytes: &[u8], phrase: &str, expected: Option<Vec<(usize, &str)>>) {
+        let phrase: String = unicode_fold::fold(phrase);
+
+        assert_eq!(
+            search_bytes(bytes, &Finder::new(&phrase), true),
+            line_hits(expected)
+        );
+    }
+
+    /// Return the line hits for the line numbers and lines.
+    fn line_hits(expected: Option<Vec<(usize, &str)>>) -> Option<Vec<LineHit>> {
+        expected.map(|lines| {
+            lines
+                .into_iter()
+                .map(|(line_number, line)| LineHit::new(line_number, line))
+                .collect()
+        })
+    }
+
+    #[test]
+    fn test_phrase_searcher_order() {
+        let dir: PathBuf = env::temp_dir().join(format!("insh-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        for number in 0..50 {
+            let contents: &str = if number % 3 == 0 { "foo\n" } else { "bar\n" };
+            fs::write(dir.join(format!("{}.txt", number)), contents).unwrap();
+            fs::write(dir.join("sub").join(format!("{}.txt", number)), contents).unwrap();
+        }
+
+        let paths: Vec<PathBuf> = PhraseSearcher::new(&dir, "foo", false, false, |_| false)
+            .map(|file_hit| file_hit.path().to_path_buf())
+            .collect();
+        // The same order as walking the directory and searching the files one at a time.
+        let expected: Vec<PathBuf> = WalkerBuilder::new(&dir)
+            .min_depth(1)
+            .into_iter()
+            .map(|entry| entry.unwrap().into_path())
+            .filter(|path| path.is_file() && fs::read_to_string(path).unwrap() == "foo\n")
+            .collect();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(paths.len(), 34);
+        assert_eq!(paths, expected);
+    }
+
+    #[test]
+    fn test_phrase_searcher_skip_dir() {
+        let dir: PathBuf = env::temp_dir().join(format!("insh-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(dir.join("slow").join("sub")).unwrap();
+        for file in ["kept.txt", "slow/skipped.txt", "slow/sub/skipped.txt"] {
+            fs::write(dir.join(file), "foo\n").unwrap();
+        }
+
+        let paths: Vec<PathBuf> =
+            PhraseSearcher::new(&dir, "foo", false, false, |path| path.ends_with("slow"))
+                .map(|file_hit| file_hit.path().to_path_buf())
+                .collect();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(paths, [dir.join("kept.txt")]);
+    }
+}
@@ -2,7 +2,7 @@
 This module contains the [`Fabric`] struct which is used for representing a 2D rectangle of styled
 text.
 */
-use super::{Size, Yarn};
+use super::{Location, Size, Yarn};
 
 use std::cmp::Ordering;
 
@@ -138,6 +138,19 @@ impl Fabric {
         }
     }
 
+    /// Remove the rows of the fabric below the first `new_rows` rows.
+    ///
+    /// If the fabric has fewer rows, then it is left as is.
+    pub fn truncate_bottom(&mut self, new_rows: usize) {
+        if new_rows >= self.size.rows {
+            return;
+        }
+        self.size.rows = new_rows;
+        self.characters.truncate(new_rows);
+        self.colors.truncate(new_rows);
+        self.backgrounds.truncate(new_rows);
+    }
+
     /// Combine this fabric with another adding the contents of the other fabric to the bottom of
     /// this one.
     pub fn quilt_bottom(mut self, other: Fabric) -> Fabric {
@@ -153,6 +166,82 @@ impl Fabric {
 
         self
     }
+
+    /// Combine this fabric with another adding the contents of the other fabric to the right of
+    /// this one.
+    ///
+    /// Panic if the fabrics have different numbers of rows.
+    pub fn quilt_right(mut self, other: Fabric) -> Fabric {
+        if self.size.rows != other.size.rows {
+            panic!("Cannot quilt fabrics with different numbers of rows side by side.")
+        }
+
+        let columns: usize = self.size.columns;
+        for (
+            row,
+            row_colors,
+            row_backgrounds,
+            other_row,
+            other_row_colors,
+            other_row_backgrounds,
+        ) in izip!(
+            self.characters.iter_mut(),
+            self.colors.iter_mut(),
+            self.backgrounds.iter_mut(),
+            other.characters,
+            other.colors,
+            other.backgrounds
+        ) {
+            // NOTE: The rows (and especially their colors) may be shorter than the fabric.
+            row.resize(columns, ' ');
+            row.extend(other_row);
+            row_colors.resize(columns, None);
+            row_colors.extend(other_row_colors);
+            row_backgrounds.resize(columns, None);
+            row_backgrounds.extend(other_row_backgrounds);
+        }
+
+        self.size.columns += other.size.columns;
+
+        self
+    }
+
+    /// Draw the other fabric over this one with its top left corner at the location (cutting off
+    /// what doesn't fit).
+    pub fn overlay(&mut self, other: &Fabric, location: Location) {
+        let columns: usize = self.size.columns;
+        for (row, other_row, other_row_colors, other_row_backgrounds) in izip!(
+            location.row..self.size.rows,
+            &other.characters,
+            &other.colors,
+            &other.backgrounds
+        ) {
+            // NOTE: The colors of the rows may be shorter than the fabric.
+            self.colors[row].resize(columns, None);
+            self.backgrounds[row].resize(columns, None);
+            for (offset, character) in other_row.iter().enumerate() {
+                let column: usize = location.column + offset;
+                if column >= columns {
+                    break;
+                }
+                self.characters[row][column] = *character;
+                self.colors[row][column] = other_row_colors.get(offset).copied().flatten();
+                self.backgrounds[row][column] =
+                    other_row_backgrounds.get(offset).copied().flatten();
+            }
+        }
+    }
+
+    /// Dim the fabric by coloring all of its text with the color and removing its backgrounds.
+    pub fn dim(&mut self, color: Color) {
+        let columns: usize = self.size.columns;
+        for row_colors in self.colors.iter_mut() {
+            *row_colors = vec![Some(color); columns];
+        }
+        for row_backgrounds in self.backgrounds.iter_mut() {
+            row_backgrounds.clear();
+        }
+    }
 }
 
 impl From<Vec<&str>> for Fabric {
@@ -243,6 +332,14 @@ mod tests {
         assert_eq!(fabric, expected);
     }
 
+    #[test_case(Fabric::from(vec!["foo", "bar"]), 1, Fabric::from(vec!["foo"]); "fewer rows")]
+    #[test_case(Fabric::from(vec!["foo"]), 2, Fabric::from(vec!["foo"]); "more rows")]
+    fn test_truncate_bottom(mut fabric: Fabric, new_rows: usize, expected: Fabric) {
+        fabric.truncate_bottom(new_rows);
+
+        assert_eq!(fabric, expected);
+    }
+
     #[test_case(
         Fabric::new(Size::new(2, 3)),
         Fabric::new(Size::new(1, 3)),
@@ -253,4 +350,56 @@ mod tests {
 
         assert_eq!(result, expected);
     }
+
+    #[test_case(
+        Fabric::from(vec!["foo", "ba"]),
+        Fabric::from(vec!["x", "y"]),
+        Fabric::from(vec!["foox", "ba y"]);
+        "rows that are shorter than the fabric are padded"
+    )]
+    #[test_case(
+        Fabric::new(Size::new(2, 0)),
+        Fabric::from(vec!["x", "y"]),
+        Fabric::from(vec!["x", "y"]);
+        "empty fabric"
+    )]
+    fn test_quilt_right(fabric: Fabric, other: Fabric, expected: Fabric) {
+        let result = fabric.quilt_right(other);
+
+        assert_eq!(result.characters(), expected.characters());
+        assert_eq!(result.size(), expected.size());
+    }
+
+    #[test_case(
+        Fabric::from(vec!["....", "....", "...."]),
+        Fabric::from(vec!["ab", "cd"]),
+        Location::new(1, 1),
+        Fabric::from(vec!["....", ".ab.", ".cd."]);
+        "inside"
+    )]
+    #[test_case(
+        Fabric::from(vec!["....", "...."]),
+        Fabric::from(vec!["abc", "def"]),
+        Location::new(1, 2),
+        Fabric::from(vec!["....", "..ab"]);
+        "cut off"
+    )]
+    fn test_overlay(mut fabric: Fabric, other: Fabric, location: Location, expected: Fabric) {
+        fabric.overlay(&other, location);
+
+        assert_eq!(fabric.characters(), expected.characters());
+        assert_eq!(fabric.size(), expected.size());
+    }
+
+    #[test]
+    fn test_dim() {
+        let mut yarn = Yarn::from("ab");
+        yarn.background(Color::Red);
+        let mut fabric = Fabric::from(yarn);
+
+        fabric.dim(Color::DarkGrey);
+
+        assert_eq!(fabric.colors(), &vec![vec![Some(Color::DarkGrey); 2]]);
+        assert!(fabric.backgrounds()[0].iter().all(Option::is_none));
+    }
 }
@@ -3,7 +3,7 @@ This module contains the [`Location`] struct which is used to represent 2D locat
 */
 
 /// A 2D location.
-#[derive(Default)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct Location {
     /// The vertical component of the location.
     pub row: usize,
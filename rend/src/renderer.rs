@@ -1,5 +1,9 @@
 /*!
 This module contains the [`Renderer`] struct which is used for terminal rendering.
+
+The renderer degrades the output to the [`Capabilities`] of the terminal: colors are approximated
+with the colors that the terminal has, and characters other than ASCII are drawn with ASCII look
+alikes if the terminal can't draw them.
 */
 use super::fabric::Fabric;
 
@@ -9,22 +13,65 @@ use crossterm::cursor::MoveTo as MoveCursorTo;
 use crossterm::style::{Color, Print, SetBackgroundColor, SetForegroundColor};
 use crossterm::terminal::{Clear as ClearTerminal, ClearType as TerminalClearType};
 use crossterm::QueueableCommand;
+use term::{Capabilities, ColorSupport};
+
+/// The sequence that begins a synchronized update (the terminal holds off drawing until it ends).
+const BEGIN_SYNCHRONIZED_UPDATE: &str = "\x1b[?2026h";
+
+/// The sequence that ends a synchronized update (and draws what was sent since it began).
+const END_SYNCHRONIZED_UPDATE: &str = "\x1b[?2026l";
+
+/// The RGB values of the 16 ANSI colors (as xterm draws them) and the colors themselves.
+const ANSI_COLORS: [((u8, u8, u8), Color); 16] = [
+    ((0, 0, 0), Color::Black),
+    ((205, 0, 0), Color::DarkRed),
+    ((0, 205, 0), Color::DarkGreen),
+    ((205, 205, 0), Color::DarkYellow),
+    ((0, 0, 238), Color::DarkBlue),
+    ((205, 0, 205), Color::DarkMagenta),
+    ((0, 205, 205), Color::DarkCyan),
+    ((229, 229, 229), Color::Grey),
+    ((127, 127, 127), Color::DarkGrey),
+    ((255, 0, 0), Color::Red),
+    ((0, 255, 0), Color::Green),
+    ((255, 255, 0), Color::Yellow),
+    ((92, 92, 255), Color::Blue),
+    ((255, 0, 255), Color::Magenta),
+    ((0, 255, 255), Color::Cyan),
+    ((255, 255, 255), Color::White),
+];
+
+/// The levels of the red, green, and blue of the colors of the 6×6×6 cube of the xterm palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
 
 /// Renders [`Fabric`]s on the standard output.
 pub struct Renderer {
     /// The standard output.
     stdout: Stdout,
+    /// What the terminal can draw.
+    capabilities: Capabilities,
 }
 
 impl Renderer {
-    /// Return a new renderer.
+    /// Return a new renderer for the terminal (whose capabilities are detected).
     pub fn new() -> Self {
         let stdout = io::stdout();
-        Renderer { stdout }
+        Renderer {
+            stdout,
+            capabilities: *term::capabilities(),
+        }
     }
 
     /// Render the fabric on the terminal.
+    ///
+    /// If the terminal supports synchronized output, then the frame is wrapped in a synchronized
+    /// update so that it is drawn at once (instead of tearing while a large frame is sent).
     pub fn render(&mut self, fabric: Fabric) {
+        let synchronized: bool = self.capabilities.synchronized_output();
+        if synchronized {
+            self.lazy_print_string(BEGIN_SYNCHRONIZED_UPDATE);
+        }
+
         let attributes = itertools::izip!(
             0..,
             fabric.characters(),
@@ -46,15 +93,19 @@ impl Renderer {
                         let character_background: Option<&Option<Color>> =
                             row_backgrounds_iter.next();
 
-                        match character_color {
-                            Some(Some(color)) => self.lazy_start_text_color(*color),
+                        let colors: ColorSupport = self.capabilities.colors();
+                        match character_color.and_then(|color| degrade((*color)?, colors)) {
+                            Some(color) => self.lazy_start_text_color(color),
                             _ => self.lazy_reset_text_color(),
                         }
-                        match character_background {
-                            Some(Some(color)) => self.lazy_start_background_color(*color),
+                        match character_background.and_then(|color| degrade((*color)?, colors)) {
+                            Some(color) => self.lazy_start_background_color(color),
                             _ => self.lazy_reset_background_color(),
                         }
-                        self.lazy_print_character(character);
+                        match self.capabilities.unicode() {
+                            true => self.lazy_print_character(character),
+                            false => self.lazy_print_character(&ascii_fallback(*character)),
+                        }
                     }
                     None => break,
                 }
@@ -63,6 +114,9 @@ impl Renderer {
             self.lazy_reset_background_color();
         }
 
+        if synchronized {
+            self.lazy_print_string(END_SYNCHRONIZED_UPDATE);
+        }
         self.update_terminal();
     }
 
@@ -90,7 +144,6 @@ impl Renderer {
     }
 
     /// Queue the string to be sent the terminal, but don't send it.
-    #[allow(dead_code)]
     fn lazy_print_string(&mut self, string: &str) {
         self.stdout.queue(Print(string)).unwrap();
     }
@@ -128,3 +181,99 @@ impl Default for Renderer {
         Self::new()
     }
 }
+
+/// Return the color that is shown in place of the color with the colors that the terminal has (or
+/// `None` if it has no colors).
+fn degrade(color: Color, colors: ColorSupport) -> Option<Color> {
+    let rgb: (u8, u8, u8) = match (color, colors) {
+        (_, ColorSupport::None) => return None,
+        (_, ColorSupport::TrueColor) => return Some(color),
+        (Color::Rgb { r, g, b }, ColorSupport::Ansi256) => {
+            return Some(Color::AnsiValue(nearest_ansi_value((r, g, b))));
+        }
+        (Color::Rgb { r, g, b }, ColorSupport::Ansi16) => (r, g, b),
+        (Color::AnsiValue(value), ColorSupport::Ansi16) => ansi_value_rgb(value),
+        _ => return Some(color),
+    };
+    ANSI_COLORS
+        .iter()
+        .min_by_key(|(ansi_rgb, _)| distance(*ansi_rgb, rgb))
+        .map(|(_, color)| *color)
+}
+
+/// Return the value of the color of the 256 color palette which is nearest to the RGB color (out of
+/// the colors of the cube and the grays).
+fn nearest_ansi_value(rgb: (u8, u8, u8)) -> u8 {
+    (16..=255)
+        .min_by_key(|value| distance(ansi_value_rgb(*value), rgb))
+        .unwrap_or(16)
+}
+
+/// Return the RGB values of the color of the 256 color palette.
+fn ansi_value_rgb(value: u8) -> (u8, u8, u8) {
+    match value {
+        0..=15 => ANSI_COLORS[value as usize].0,
+        16..=231 => {
+            let index: usize = (value - 16) as usize;
+            (
+                CUBE_LEVELS[index / 36],
+                CUBE_LEVELS[index / 6 % 6],
+                CUBE_LEVELS[index % 6],
+            )
+        }
+        232..=255 => {
+            let level: u8 = 8 + (value - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+/// Return the square of the distance between the colors.
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let square = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    square(a.0, b.0) + square(a.1, b.1) + square(a.2, b.2)
+}
+
+/// Return the ASCII character which looks like the character (`?` if there isn't one).
+fn ascii_fallback(character: char) -> char {
+    match character {
+        character if character.is_ascii() => character,
+        '│' | '┃' | '║' => '|',
+        '─' | '━' | '═' => '-',
+        '┌' | '┐' | '└' | '┘' | '├' | '┤' | '┬' | '┴' | '┼' => '+',
+        '→' | '▶' | '›' => '>',
+        '←' | '◀' | '‹' => '<',
+        '…' | '·' => '.',
+        '●' | '•' | '×' => '*',
+        '○' => 'o',
+        '⏏' => '^',
+        '✓' => 'v',
+        _ => '?',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    #[test_case(Color::Rgb { r: 52, g: 70, b: 120 }, ColorSupport::TrueColor, Some(Color::Rgb { r: 52, g: 70, b: 120 }); "true color")]
+    #[test_case(Color::Rgb { r: 200, g: 30, b: 40 }, ColorSupport::Ansi256, Some(Color::AnsiValue(160)); "rgb with 256 colors")]
+    #[test_case(Color::Rgb { r: 128, g: 128, b: 128 }, ColorSupport::Ansi256, Some(Color::AnsiValue(244)); "gray with 256 colors")]
+    #[test_case(Color::Rgb { r: 250, g: 10, b: 10 }, ColorSupport::Ansi16, Some(Color::Red); "rgb with 16 colors")]
+    #[test_case(Color::AnsiValue(21), ColorSupport::Ansi16, Some(Color::DarkBlue); "ansi value with 16 colors")]
+    #[test_case(Color::Red, ColorSupport::Ansi16, Some(Color::Red); "ansi color")]
+    #[test_case(Color::Red, ColorSupport::None, None; "no colors")]
+    fn test_degrade(color: Color, colors: ColorSupport, expected: Option<Color>) {
+        assert_eq!(degrade(color, colors), expected);
+    }
+
+    #[test_case('a', 'a'; "ascii")]
+    #[test_case('│', '|'; "box drawing")]
+    #[test_case('→', '>'; "arrow")]
+    #[test_case('é', '?'; "no look alike")]
+    fn test_ascii_fallback(character: char, expected: char) {
+        assert_eq!(ascii_fallback(character), expected);
+    }
+}
@@ -102,6 +102,71 @@ impl Yarn {
         self
     }
 
+    /// Return the part of the yarn from `start` up to `end` (with its styles).
+    pub fn slice(&self, start: usize, end: usize) -> Self {
+        let end: usize = end.min(self.len());
+        let start: usize = start.min(end);
+        let styles = |styles: &Vec<Option<CrosstermColor>>| {
+            styles[start.min(styles.len())..end.min(styles.len())].to_vec()
+        };
+        Self {
+            characters: self.characters[start..end].to_vec(),
+            colors: styles(&self.colors),
+            backgrounds: styles(&self.backgrounds),
+        }
+    }
+
+    /// Split the yarn into rows of at most `width` characters. The rows after the first start with
+    /// `indent` spaces (a hanging indent, ignored if it leaves no room for the characters).
+    pub fn wrap(&self, width: usize, indent: usize) -> Vec<Self> {
+        if width == 0 {
+            return Vec::new();
+        }
+        let indent: usize = if indent < width { indent } else { 0 };
+
+        let mut rows: Vec<Self> = Vec::with_capacity(Self::wrapped_rows(self.len(), width, indent));
+        let mut start: usize = 0;
+        let mut row_width: usize = width;
+        loop {
+            let end: usize = (start + row_width).min(self.len());
+            rows.push(Self::blank(width - row_width).concat(self.slice(start, end)));
+            start = end;
+            if start >= self.len() {
+                return rows;
+            }
+            row_width = width - indent;
+        }
+    }
+
+    /// Return the `width` columns of the yarn starting at the column `offset` (padded with spaces).
+    /// The first and last columns are replaced by `<` and `>` if the yarn extends past them.
+    pub fn window(&self, offset: usize, width: usize) -> Self {
+        let mut window: Self = self.slice(offset, offset + width);
+        window.resize(width);
+        if width == 0 {
+            return window;
+        }
+        if offset > 0 && offset < self.len() {
+            window.characters[0] = '<';
+        }
+        if self.len() > offset + width {
+            window.characters[width - 1] = '>';
+        }
+        window
+    }
+
+    /// Return the number of rows that a yarn of the length `len` is split into by `wrap`.
+    pub fn wrapped_rows(len: usize, width: usize, indent: usize) -> usize {
+        if width == 0 {
+            return 0;
+        }
+        let indent: usize = if indent < width { indent } else { 0 };
+        match len.checked_sub(width) {
+            None | Some(0) => 1,
+            Some(rest) => 1 + (rest + width - indent - 1) / (width - indent),
+        }
+    }
+
     /// Change the length of the yarn to the `new_size`.
     pub fn resize(&mut self, new_len: usize) {
         let len = self.len();
@@ -262,4 +327,56 @@ mod tests {
 
         assert_eq!(result, expected_yarn);
     }
+
+    #[test_case("", 4, 0, vec![""]; "an empty yarn")]
+    #[test_case("abcd", 4, 2, vec!["abcd"]; "a yarn that just fits")]
+    #[test_case("abcdefgh", 4, 0, vec!["abcd", "efgh"]; "no indent")]
+    #[test_case("abcdefgh", 4, 2, vec!["abcd", "  ef", "  gh"]; "a hanging indent")]
+    #[test_case("abcdef", 4, 4, vec!["abcd", "ef"]; "an indent that is too wide")]
+    fn test_wrap(string: &str, width: usize, indent: usize, expected: Vec<&str>) {
+        let yarn = Yarn::from(string);
+
+        let rows: Vec<Yarn> = yarn.wrap(width, indent);
+
+        assert_eq!(
+            rows,
+            expected.into_iter().map(Yarn::from).collect::<Vec<Yarn>>()
+        );
+        assert_eq!(Yarn::wrapped_rows(yarn.len(), width, indent), rows.len());
+    }
+
+    #[test_case("abcdef", 0, 6, "abcdef"; "the whole yarn")]
+    #[test_case("abcdef", 0, 4, "abc>"; "more on the right")]
+    #[test_case("abcdef", 2, 4, "<def"; "more on the left")]
+    #[test_case("abcdefgh", 2, 4, "<de>"; "more on both sides")]
+    #[test_case("abc", 1, 4, "<c  "; "padded")]
+    #[test_case("abc", 5, 2, "  "; "past the end")]
+    fn test_window(string: &str, offset: usize, width: usize, expected: &str) {
+        assert_eq!(
+            Yarn::from(string).window(offset, width),
+            Yarn::from(expected)
+        );
+    }
+
+    #[test]
+    fn test_wrap_keeps_styles() {
+        let mut yarn = Yarn::from("ab").concat({
+            let mut highlighted = Yarn::from("cd");
+            highlighted.background(CrosstermColor::Yellow);
+            highlighted
+        });
+        yarn = yarn.concat(Yarn::from("e"));
+
+        let rows: Vec<Yarn> = yarn.wrap(3, 1);
+
+        assert_eq!(
+            rows[0].backgrounds(),
+            &vec![None, None, Some(CrosstermColor::Yellow)]
+        );
+        // NOTE: The backgrounds end at the last styled character.
+        assert_eq!(
+            rows[1].backgrounds(),
+            &vec![None, Some(CrosstermColor::Yellow)]
+        );
+    }
 }
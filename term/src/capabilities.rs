@@ -0,0 +1,213 @@
+/*!
+This module contains [`Capabilities`] which describes what the terminal can draw and which
+protocols it supports, so that the output degrades gracefully on dumb terminals and is fancier where
+it can be.
+
+The capabilities are detected once from the environment (`TERM`, `COLORTERM`, `TERM_PROGRAM`, the
+locale, and the variables set by particular terminals) because querying the terminal would race
+with reading its input.
+*/
+use std::env;
+use std::io::{self, IsTerminal};
+use std::sync::OnceLock;
+
+/// The colors that a terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorSupport {
+    /// No colors (a dumb terminal, or `NO_COLOR` is set).
+    None,
+    /// The 16 ANSI colors.
+    Ansi16,
+    /// The 256 colors of the xterm palette.
+    Ansi256,
+    /// Any RGB color.
+    TrueColor,
+}
+
+/// What a terminal can do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    colors: ColorSupport,
+    /// Whether characters other than ASCII can be drawn (they can't on the Linux console or with a
+    /// locale of another encoding than UTF-8).
+    unicode: bool,
+    /// Whether the kitty protocols (for the keyboard and for graphics) are supported.
+    kitty: bool,
+    /// Whether the terminal can hold off drawing until a frame is done (mode 2026), so that a frame
+    /// is never drawn half way.
+    synchronized_output: bool,
+}
+
+/// The terminals (by `TERM_PROGRAM`) which support the kitty protocols.
+const KITTY_PROGRAMS: [&str; 1] = ["ghostty"];
+
+/// The terminals (by `TERM_PROGRAM`) which support synchronized output.
+const SYNCHRONIZED_PROGRAMS: [&str; 4] = ["ghostty", "WezTerm", "iTerm.app", "contour"];
+
+/// The terminals (by `TERM`) which support synchronized output.
+const SYNCHRONIZED_TERMS: [&str; 3] = ["xterm-kitty", "foot", "alacritty"];
+
+/// The terminals (by `TERM_PROGRAM`) which show any RGB color (even if `COLORTERM` isn't passed on,
+/// like over SSH).
+const TRUE_COLOR_PROGRAMS: [&str; 4] = ["ghostty", "WezTerm", "iTerm.app", "vscode"];
+
+impl Capabilities {
+    /// Return the capabilities of a dumb terminal (which can only draw ASCII characters).
+    pub fn dumb() -> Self {
+        Self {
+            colors: ColorSupport::None,
+            unicode: false,
+            kitty: false,
+            synchronized_output: false,
+        }
+    }
+
+    /// Detect the capabilities of the terminal from the environment.
+    pub fn detect() -> Self {
+        if !io::stdout().is_terminal() {
+            return Self::dumb();
+        }
+        Self::from_env(|name| env::var(name).ok().filter(|value| !value.is_empty()))
+    }
+
+    /// Return the capabilities of the terminal described by the environment variables (which are
+    /// gotten by name, and are `None` if they aren't set or are empty).
+    fn from_env(var: impl Fn(&str) -> Option<String>) -> Self {
+        let term: String = match var("TERM") {
+            Some(term) if term != "dumb" => term,
+            _ => return Self::dumb(),
+        };
+        let term_program: String = var("TERM_PROGRAM").unwrap_or_default();
+        let kitty: bool = term == "xterm-kitty"
+            || var("KITTY_WINDOW_ID").is_some()
+            || KITTY_PROGRAMS.contains(&term_program.as_str());
+
+        let colors: ColorSupport = if var("NO_COLOR").is_some() {
+            ColorSupport::None
+        } else if kitty
+            || matches!(var("COLORTERM").as_deref(), Some("truecolor" | "24bit"))
+            || term.ends_with("-direct")
+            || TRUE_COLOR_PROGRAMS.contains(&term_program.as_str())
+        {
+            ColorSupport::TrueColor
+        } else if term.contains("256color") {
+            ColorSupport::Ansi256
+        } else {
+            ColorSupport::Ansi16
+        };
+
+        // NOTE: The first of the locale variables which is set is the one that is used.
+        let locale: Option<String> = ["LC_ALL", "LC_CTYPE", "LANG"].into_iter().find_map(&var);
+        let unicode: bool = term != "linux" && locale.as_deref().is_none_or(is_unicode_locale);
+
+        // NOTE: Multiplexers only pass synchronized output on in some versions (so it is only used
+        // outside of them).
+        let multiplexed: bool = var("TMUX").is_some() || term.starts_with("screen");
+        let synchronized_output: bool = !multiplexed
+            && (SYNCHRONIZED_TERMS.contains(&term.as_str())
+                || SYNCHRONIZED_PROGRAMS.contains(&term_program.as_str()));
+
+        Self {
+            colors,
+            unicode,
+            kitty: kitty && !multiplexed,
+            synchronized_output,
+        }
+    }
+
+    pub fn colors(&self) -> ColorSupport {
+        self.colors
+    }
+
+    /// Return whether characters other than ASCII can be drawn.
+    pub fn unicode(&self) -> bool {
+        self.unicode
+    }
+
+    /// Return whether the kitty protocols (for the keyboard and for graphics) are supported.
+    pub fn kitty(&self) -> bool {
+        self.kitty
+    }
+
+    /// Return whether the terminal can hold off drawing until a frame is done.
+    pub fn synchronized_output(&self) -> bool {
+        self.synchronized_output
+    }
+}
+
+/// Return the capabilities of the terminal (which are detected the first time).
+pub fn capabilities() -> &'static Capabilities {
+    static CAPABILITIES: OnceLock<Capabilities> = OnceLock::new();
+    CAPABILITIES.get_or_init(Capabilities::detect)
+}
+
+/// Return whether the locale (like `en_US.UTF-8`) uses UTF-8. A locale without an encoding (like
+/// `C`) is assumed to be used in a terminal which draws unicode anyway.
+fn is_unicode_locale(locale: &str) -> bool {
+    let encoding: &str = match locale.split_once('.') {
+        Some((_, encoding)) => encoding.split('@').next().unwrap_or_default(),
+        None => return true,
+    };
+    encoding.eq_ignore_ascii_case("utf-8") || encoding.eq_ignore_ascii_case("utf8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Return the capabilities of the terminal with the environment variables.
+    fn capabilities(vars: &[(&str, &str)]) -> Capabilities {
+        Capabilities::from_env(|name| {
+            vars.iter()
+                .find(|(var, _)| *var == name)
+                .map(|(_, value)| value.to_string())
+        })
+    }
+
+    #[test]
+    fn test_from_env() {
+        assert_eq!(capabilities(&[]), Capabilities::dumb());
+        assert_eq!(capabilities(&[("TERM", "dumb")]), Capabilities::dumb());
+
+        let xterm: Capabilities = capabilities(&[("TERM", "xterm"), ("LANG", "en_US.UTF-8")]);
+        assert_eq!(xterm.colors(), ColorSupport::Ansi16);
+        assert!(xterm.unicode());
+        assert!(!xterm.synchronized_output());
+
+        let xterm: Capabilities = capabilities(&[("TERM", "xterm-256color"), ("NO_COLOR", "1")]);
+        assert_eq!(xterm.colors(), ColorSupport::None);
+
+        let xterm: Capabilities = capabilities(&[("TERM", "xterm-256color")]);
+        assert_eq!(xterm.colors(), ColorSupport::Ansi256);
+
+        let console: Capabilities = capabilities(&[("TERM", "linux"), ("LANG", "en_US.UTF-8")]);
+        assert!(!console.unicode());
+
+        let latin: Capabilities =
+            capabilities(&[("TERM", "xterm"), ("LC_ALL", "de_DE.ISO-8859-1")]);
+        assert!(!latin.unicode());
+
+        let kitty: Capabilities = capabilities(&[("TERM", "xterm-kitty")]);
+        assert_eq!(kitty.colors(), ColorSupport::TrueColor);
+        assert!(kitty.kitty());
+        assert!(kitty.synchronized_output());
+
+        let tmux: Capabilities = capabilities(&[
+            ("TERM", "tmux-256color"),
+            ("TMUX", "/tmp/tmux-1000/default,1,0"),
+            ("COLORTERM", "truecolor"),
+            ("TERM_PROGRAM", "WezTerm"),
+        ]);
+        assert_eq!(tmux.colors(), ColorSupport::TrueColor);
+        assert!(!tmux.kitty());
+        assert!(!tmux.synchronized_output());
+    }
+
+    #[test]
+    fn test_is_unicode_locale() {
+        assert!(is_unicode_locale("C"));
+        assert!(is_unicode_locale("en_US.UTF-8"));
+        assert!(is_unicode_locale("sv_SE.utf8@euro"));
+        assert!(!is_unicode_locale("de_DE.ISO-8859-1"));
+    }
+}
@@ -4,13 +4,72 @@ use std::fmt::{Display, Error as FmtError, Formatter};
 
 use bitflags::bitflags;
 
+/// The sequence that the terminal sends when it gains focus (if focus reporting is enabled).
+const FOCUS_GAINED: &[u8] = b"\x1b[I";
+
+/// The sequence that the terminal sends when it loses focus (if focus reporting is enabled).
+const FOCUS_LOST: &[u8] = b"\x1b[O";
+
+/// The sequences that the terminal sends for the arrow keys (in the normal and in the application
+/// mode of the cursor keys) and the keys they are for.
+const ARROWS: [(&[u8], Key); 8] = [
+    (b"\x1b[A", Key::Up),
+    (b"\x1b[B", Key::Down),
+    (b"\x1b[C", Key::Right),
+    (b"\x1b[D", Key::Left),
+    (b"\x1bOA", Key::Up),
+    (b"\x1bOB", Key::Down),
+    (b"\x1bOC", Key::Right),
+    (b"\x1bOD", Key::Left),
+];
+
+/// The sequence that enables reporting when the terminal gains and loses focus.
+pub const ENABLE_FOCUS_REPORTING: &str = "\x1b[?1004h";
+
+/// The sequence that disables reporting when the terminal gains and loses focus.
+pub const DISABLE_FOCUS_REPORTING: &str = "\x1b[?1004l";
+
 #[derive(Debug, Clone)]
 pub enum TermEvent {
     KeyEvent(KeyEvent),
     Resize(Size),
+    /// The terminal gained focus (if focus reporting is enabled).
+    FocusGained,
+    /// The terminal lost focus (if focus reporting is enabled).
+    FocusLost,
 }
 
-#[derive(Debug, Clone)]
+/// Return the events for the bytes that were read from the terminal at once. Each byte is a key
+/// event except for the sequences of the arrow keys and the ones reporting that the terminal gained
+/// or lost focus.
+pub fn parse_events(bytes: &[u8]) -> Vec<TermEvent> {
+    let mut events: Vec<TermEvent> = Vec::new();
+    let mut rest: &[u8] = bytes;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix(FOCUS_GAINED) {
+            events.push(TermEvent::FocusGained);
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix(FOCUS_LOST) {
+            events.push(TermEvent::FocusLost);
+            rest = after;
+        } else if let Some((sequence, key)) = ARROWS
+            .iter()
+            .find(|(sequence, _)| rest.starts_with(sequence))
+        {
+            events.push(TermEvent::KeyEvent(KeyEvent {
+                key: key.clone(),
+                mods: KeyMods::NONE,
+            }));
+            rest = &rest[sequence.len()..];
+        } else {
+            events.push(TermEvent::try_from(&rest[..1]).unwrap());
+            rest = &rest[1..];
+        }
+    }
+    events
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct KeyEvent {
     pub key: Key,
     pub mods: KeyMods,
@@ -1140,6 +1199,12 @@ impl TryInto<Vec<u8>> for &KeyEvent {
             KeyEvent {
                 key: Key::Delete, ..
             } => Ok(vec![127]),
+            KeyEvent { key: Key::Up, .. } => Ok(b"\x1b[A".to_vec()),
+            KeyEvent { key: Key::Down, .. } => Ok(b"\x1b[B".to_vec()),
+            KeyEvent {
+                key: Key::Right, ..
+            } => Ok(b"\x1b[C".to_vec()),
+            KeyEvent { key: Key::Left, .. } => Ok(b"\x1b[D".to_vec()),
             KeyEvent {
                 key: Key::Unknown(value),
                 ..
@@ -1166,7 +1231,7 @@ impl Display for KeyEventToBytesError {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Key {
     Null,
     /// Start of text (same as <Ctrl>-a)
@@ -1228,6 +1293,10 @@ pub enum Key {
     UnitSep,
     Char(char),
     Delete,
+    Up,
+    Down,
+    Right,
+    Left,
     Unknown(u8),
 }
 
@@ -1240,3 +1309,35 @@ bitflags! {
         const CONTROL = 0b0000_0010;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_events() {
+        let events: Vec<TermEvent> = parse_events(b"a\x1b[I\x1b[O\x1b[A\x1bOB\x1b[");
+
+        assert!(matches!(
+            events.as_slice(),
+            [
+                TermEvent::KeyEvent(KeyEvent {
+                    key: Key::Char('a'),
+                    ..
+                }),
+                TermEvent::FocusGained,
+                TermEvent::FocusLost,
+                TermEvent::KeyEvent(KeyEvent { key: Key::Up, .. }),
+                TermEvent::KeyEvent(KeyEvent { key: Key::Down, .. }),
+                TermEvent::KeyEvent(KeyEvent {
+                    key: Key::Escape,
+                    ..
+                }),
+                TermEvent::KeyEvent(KeyEvent {
+                    key: Key::Char('['),
+                    ..
+                }),
+            ]
+        ));
+    }
+}
@@ -1,8 +1,12 @@
 #![allow(clippy::enum_variant_names)]
 #![allow(clippy::needless_return)]
 
+mod capabilities;
 mod event;
 mod term;
 
-pub use crate::event::{Key, KeyEvent, KeyMods, TermEvent};
+pub use crate::capabilities::{capabilities, Capabilities, ColorSupport};
+pub use crate::event::{
+    Key, KeyEvent, KeyMods, TermEvent, DISABLE_FOCUS_REPORTING, ENABLE_FOCUS_REPORTING,
+};
 pub use crate::term::Term;
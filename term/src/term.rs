@@ -15,7 +15,7 @@ use nix::unistd::{pipe, read, write};
 use nix::Result as NixResult;
 use termios::*;
 
-use crate::event::TermEvent;
+use crate::event::{parse_events, TermEvent};
 use size::Size;
 
 // TODO: Make sure we close these?
@@ -86,29 +86,34 @@ impl Term {
             let stdin_events: Option<PollFlags> = stdin_events.revents();
             if let Some(stdin_events) = stdin_events {
                 if stdin_events.contains(PollFlags::POLLIN) {
-                    let result = match self.stdin.read_exact(&mut self.buffer) {
-                        Ok(_) => Ok(TermEvent::try_from(&self.buffer[..]).unwrap()),
-                        Err(error) => Err(ReadError::IOError(error)),
-                    };
+                    if let Err(error) = self.stdin.read_exact(&mut self.buffer) {
+                        return Err(ReadError::IOError(error));
+                    }
+                    let mut bytes: Vec<u8> = vec![self.buffer[0]];
 
-                    // Buffer an other events.
+                    // Read the rest of the available bytes (so that sequences such as focus
+                    // reports are parsed as one event).
+                    let mut read_error: Option<ReadError> = None;
                     loop {
                         match self.stdin.read(&mut self.buffer) {
-                            Ok(read) => {
-                                if read == 0 {
-                                    break;
-                                }
-                            }
+                            Ok(0) => break,
+                            Ok(_) => bytes.push(self.buffer[0]),
                             Err(error) => {
-                                self.buffered_reads
-                                    .push_back(Err(ReadError::IOError(error)));
+                                read_error = Some(ReadError::IOError(error));
+                                break;
                             }
                         }
-                        self.buffered_reads
-                            .push_back(Ok(TermEvent::try_from(&self.buffer[..]).unwrap()));
                     }
 
-                    return result;
+                    // Buffer the other events.
+                    let mut events = parse_events(&bytes).into_iter();
+                    let event: TermEvent = events.next().unwrap();
+                    self.buffered_reads.extend(events.map(Ok));
+                    if let Some(read_error) = read_error {
+                        self.buffered_reads.push_back(Err(read_error));
+                    }
+
+                    return Ok(event);
                 }
             }
 
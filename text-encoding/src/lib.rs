@@ -0,0 +1,165 @@
+/*!
+Detection of the encoding of text files and decoding of them to UTF-8 (used for searching files and
+for showing them in the pager).
+
+The encoding is detected from the start of a file: a byte order mark is used if there is one,
+otherwise text that is valid UTF-8 is UTF-8, text with many zero bytes in every other position is
+UTF-16, text with other zero bytes is binary, and anything else is Latin-1 (which decodes any
+bytes).
+*/
+use std::borrow::Cow;
+use std::fmt::{Display, Error as FmtError, Formatter};
+use std::str;
+
+use encoding_rs::{Decoder, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+use serde::{Deserialize, Serialize};
+
+/// The number of bytes at the start of a file that are used to detect its encoding.
+pub const SAMPLE_LEN: usize = 4096;
+
+/// The encoding of a text file.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// ISO-8859-1 (decoded as its superset Windows-1252, like web browsers do).
+    Latin1,
+}
+
+impl Encoding {
+    /// Return the encoding of the text encoding library.
+    fn encoding_rs(self) -> &'static encoding_rs::Encoding {
+        match self {
+            Self::Utf8 => UTF_8,
+            Self::Utf16Le => UTF_16LE,
+            Self::Utf16Be => UTF_16BE,
+            Self::Latin1 => WINDOWS_1252,
+        }
+    }
+}
+
+impl Display for Encoding {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let name: &str = match self {
+            Self::Utf8 => "UTF-8",
+            Self::Utf16Le => "UTF-16LE",
+            Self::Utf16Be => "UTF-16BE",
+            Self::Latin1 => "Latin-1",
+        };
+        write!(formatter, "{}", name)
+    }
+}
+
+/// Return the encoding of the text that starts with `sample` (nothing if it looks binary).
+///
+/// The sample may end in the middle of a character.
+pub fn detect(sample: &[u8]) -> Option<Encoding> {
+    if sample.starts_with(b"\xef\xbb\xbf") {
+        return Some(Encoding::Utf8);
+    }
+    if sample.starts_with(b"\xff\xfe") {
+        return Some(Encoding::Utf16Le);
+    }
+    if sample.starts_with(b"\xfe\xff") {
+        return Some(Encoding::Utf16Be);
+    }
+
+    let zeros = |parity: usize| {
+        sample
+            .iter()
+            .skip(parity)
+            .step_by(2)
+            .filter(|byte| **byte == 0)
+            .count()
+    };
+    let (even_zeros, odd_zeros): (usize, usize) = (zeros(0), zeros(1));
+    if even_zeros + odd_zeros > 0 {
+        // NOTE: Mostly ASCII text encoded as UTF-16 has a zero in (about) every other byte.
+        let half: usize = sample.len() / 2;
+        return match (even_zeros, odd_zeros) {
+            (even_zeros, 0) if even_zeros * 3 >= half => Some(Encoding::Utf16Be),
+            (0, odd_zeros) if odd_zeros * 3 >= half => Some(Encoding::Utf16Le),
+            _ => None,
+        };
+    }
+
+    match str::from_utf8(sample) {
+        Ok(_) => Some(Encoding::Utf8),
+        // The sample may end in the middle of a character.
+        Err(error) if error.error_len().is_none() => Some(Encoding::Utf8),
+        Err(_) => Some(Encoding::Latin1),
+    }
+}
+
+/// Return the text decoded from the encoding (without a byte order mark). Bytes that are not valid
+/// in the encoding are replaced.
+pub fn decode(bytes: &[u8], encoding: Encoding) -> Cow<'_, str> {
+    let (text, _) = encoding.encoding_rs().decode_with_bom_removal(bytes);
+    text
+}
+
+/// Decodes the chunks of a file as they are read (characters may span chunks).
+pub struct Transcoder {
+    decoder: Decoder,
+}
+
+impl Transcoder {
+    /// Return a new transcoder for text in the encoding.
+    pub fn new(encoding: Encoding) -> Self {
+        Self {
+            decoder: encoding.encoding_rs().new_decoder_with_bom_removal(),
+        }
+    }
+
+    /// Return the next chunk decoded to UTF-8. If `last` is true, then the chunk is the end of the
+    /// text (so an incomplete character at the end is replaced instead of kept).
+    pub fn transcode(&mut self, bytes: &[u8], last: bool) -> String {
+        let capacity: usize = self
+            .decoder
+            .max_utf8_buffer_length(bytes.len())
+            .unwrap_or(bytes.len() * 3);
+        let mut text: String = String::with_capacity(capacity);
+        let _ = self.decoder.decode_to_string(bytes, &mut text, last);
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(b"hello\n", Some(Encoding::Utf8); "ascii")]
+    #[test_case(b"", Some(Encoding::Utf8); "empty")]
+    #[test_case(b"caf\xc3\xa9", Some(Encoding::Utf8); "utf8")]
+    #[test_case(b"caf\xc3", Some(Encoding::Utf8); "utf8 cut off")]
+    #[test_case(b"\xef\xbb\xbfhi", Some(Encoding::Utf8); "utf8 bom")]
+    #[test_case(b"\xff\xfeh\x00i\x00", Some(Encoding::Utf16Le); "utf16le bom")]
+    #[test_case(b"\xfe\xff\x00h\x00i", Some(Encoding::Utf16Be); "utf16be bom")]
+    #[test_case(b"h\x00i\x00\n\x00", Some(Encoding::Utf16Le); "utf16le")]
+    #[test_case(b"\x00h\x00i\x00\n", Some(Encoding::Utf16Be); "utf16be")]
+    #[test_case(b"caf\xe9 cr\xe8me", Some(Encoding::Latin1); "latin1")]
+    #[test_case(b"\x7fELF\x02\x01\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00", None; "binary")]
+    fn test_detect(sample: &[u8], expected: Option<Encoding>) {
+        assert_eq!(detect(sample), expected);
+    }
+
+    #[test_case(b"\xef\xbb\xbfcaf\xc3\xa9", Encoding::Utf8, "caf\u{e9}"; "utf8")]
+    #[test_case(b"\xff\xfec\x00a\x00f\x00\xe9\x00", Encoding::Utf16Le, "caf\u{e9}"; "utf16le")]
+    #[test_case(b"\x00c\x00a\x00f\x00\xe9", Encoding::Utf16Be, "caf\u{e9}"; "utf16be")]
+    #[test_case(b"caf\xe9", Encoding::Latin1, "caf\u{e9}"; "latin1")]
+    fn test_decode(bytes: &[u8], encoding: Encoding, expected: &str) {
+        assert_eq!(decode(bytes, encoding), expected);
+    }
+
+    #[test]
+    fn test_transcode_chunks() {
+        let mut transcoder = Transcoder::new(Encoding::Utf16Le);
+
+        let mut text: String = transcoder.transcode(b"\xff\xfeh\x00i", false);
+        text.push_str(&transcoder.transcode(b"\x00\n\x00", true));
+
+        assert_eq!(text, "hi\n");
+    }
+}
@@ -1,20 +1,26 @@
+use crate::app_exit::AppExit;
 use crate::ascii::ASCII;
+use crate::background::{self, BackgroundDone};
 use crate::component::Component;
 use crate::event::Event;
+use crate::osc::{notification_sequence, working_dir_sequence};
 use crate::output_forwarder::OutputForwarder;
 use crate::program::{Program, ProgramCleanup, ProgramSetup};
 use crate::program_monitor::{ProgramEvent, ProgramMonitor};
+use crate::recorder::Recorder;
+use crate::replayer::Replayer;
 use crate::requester::Requester;
 use crate::response_handler::ResponseHandler;
+use crate::stderr_capture::StderrCapture;
 use crate::stopper::Stopper;
-use crate::system_effect::SystemEffect;
+use crate::system_effect::{SystemEffect, Urgency};
 use crate::term_event_forwarder::TermEventForwarder;
 use crate::StdoutPipe;
 
 use rend::{Fabric, Renderer, Size};
-use term::{Term, TermEvent};
+use term::{KeyEvent, Term, TermEvent, DISABLE_FOCUS_REPORTING, ENABLE_FOCUS_REPORTING};
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::ffi::{c_int, CString, OsString};
 use std::fs::File;
 use std::io::{self, Error as IOError, Stdout, Write};
@@ -22,7 +28,9 @@ use std::os::fd::FromRawFd;
 use std::os::fd::RawFd;
 use std::os::unix::ffi::OsStringExt;
 use std::panic;
+use std::path::{Path, PathBuf};
 use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
 use crossbeam::channel::{self, Receiver, Sender};
 use crossbeam::select;
@@ -34,10 +42,20 @@ use crossterm::{ExecutableCommand, QueueableCommand};
 use nix::libc::{ioctl, setenv, winsize as WindowSize, TIOCSWINSZ};
 use nix::pty::{forkpty, ForkptyResult, Winsize};
 use nix::unistd::Pid;
-use nix::unistd::{chdir, execvp, ForkResult};
+use nix::unistd::{chdir, close, dup2, execvp, pipe, ForkResult};
+use notify_rust::Notification;
 use typed_builder::TypedBuilder;
 use uuid::Uuid;
 
+/// The most bytes of the stderr of a program that are captured (the last bytes are kept).
+const MAX_CAPTURED_STDERR: usize = 64 * 1024;
+
+/// How long to wait for the rest of the stderr of a program after it exits.
+const STDERR_DRAIN_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// How often the root component is told that time passed.
+pub const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
 #[derive(TypedBuilder)]
 pub struct App {
     #[builder(setter(skip), default=Term::new())]
@@ -52,13 +70,21 @@ pub struct App {
 
     #[builder(setter(skip), default)]
     size: Size,
+
+    /// Whether notifications are shown by the terminal (with OSC 9) instead of as desktop
+    /// notifications.
+    #[builder(setter(skip), default)]
+    terminal_notifications: bool,
 }
 
 impl App {
+    /// Run the root component until it exits (or the app can't keep running). Return why the app
+    /// stopped (once the terminal is restored).
     pub fn run<Props, Request, Response>(
         &mut self,
         options: AppRunOptions<Props, Request, Response>,
-    ) where
+    ) -> AppExit
+    where
         Request: Send + 'static,
         Response: Send + 'static,
     {
@@ -70,10 +96,18 @@ impl App {
             requester_stopper,
             response_handler,
             response_handler_stopper,
+            mut recorder,
+            replayer,
+            report_working_dir,
+            terminal_notifications,
         } = options;
+        self.terminal_notifications = terminal_notifications;
+        // The working directory that was last reported to the terminal.
+        let mut reported_working_dir: Option<PathBuf> = None;
 
         self.set_up();
 
+        let mut app_exit: AppExit = AppExit::Clean;
         let requester_handle: Option<JoinHandle<_>>;
         let response_handler_handle: Option<JoinHandle<_>>;
         // TODO: Join the term even forwarder thread when we are done.
@@ -86,6 +120,12 @@ impl App {
                 channel::unbounded();
             let (term_event_tx, term_event_rx): (Sender<TermEvent>, Receiver<TermEvent>) =
                 channel::unbounded();
+            let mut replay_rx: Receiver<Event<Response>> = channel::never();
+            let (background_tx, background_rx): (Sender<BackgroundDone>, Receiver<BackgroundDone>) =
+                channel::unbounded();
+            // The process ids of the programs that are running in the background (keyed by the
+            // uuids that they were spawned with).
+            let mut background_pids: HashMap<Uuid, Pid> = HashMap::new();
 
             if let Some(starting_term_events) = starting_term_events {
                 for term_event in starting_term_events {
@@ -116,6 +156,17 @@ impl App {
                 None => None,
             };
 
+            // Spawn the replayer.
+            if let Some(mut replayer) = replayer {
+                let (replay_tx, rx): (Sender<Event<Response>>, Receiver<Event<Response>>) =
+                    channel::unbounded();
+                replay_rx = rx;
+                thread::Builder::new()
+                    .name("replayer".to_string())
+                    .spawn(move || replayer.run(replay_tx))
+                    .unwrap();
+            }
+
             // Spawn the terminal event forwarder.
             let mut term_event_forwarder = TermEventForwarder::builder()
                 .term_event_tx(term_event_tx)
@@ -131,41 +182,109 @@ impl App {
             self.size = Size::from(terminal::size().unwrap());
 
             if let Some(effects) = starting_effects {
-                for effect in effects {
+                let mut effects: VecDeque<SystemEffect<Request>> = VecDeque::from(effects);
+                while let Some(effect) = effects.pop_front() {
                     match effect {
                         SystemEffect::RunProgram { program } => {
-                            let size_before = self.size;
-                            self.run_program::<Response>(program, &term_event_rx);
-                            if self.size != size_before {
-                                // NOTE: We don't handle the effect if one is generated from the resize.
-                                let event = Event::TermEvent(TermEvent::Resize(self.size));
-                                let _effect: Option<SystemEffect<Request>> = root.handle(event);
+                            let stderr: Option<String> = self.run_program(program, &term_event_rx);
+                            let event = Event::TermEvent(TermEvent::Resize(self.size));
+                            let effects = [
+                                root.handle(event),
+                                stderr.and_then(|stderr| root.handle_program_stderr(stderr)),
+                            ];
+                            for effect in effects {
+                                if let Some(SystemEffect::Request(request)) = effect {
+                                    if !send_request(request, &request_tx, &mut recorder) {
+                                        self.teardown();
+                                        return AppExit::DaemonDisconnected;
+                                    }
+                                }
+                            }
+                        }
+                        SystemEffect::SpawnBackground { uuid, program } => {
+                            if let Some(pid) =
+                                background::spawn(uuid, program, background_tx.clone())
+                            {
+                                background_pids.insert(uuid, pid);
+                            }
+                        }
+                        SystemEffect::KillBackground { uuid } => {
+                            if let Some(pid) = background_pids.get(&uuid) {
+                                background::kill(*pid);
                             }
                         }
                         SystemEffect::Request(request) => {
-                            request_tx.send(request).unwrap();
+                            if !send_request(request, &request_tx, &mut recorder) {
+                                self.teardown();
+                                return AppExit::DaemonDisconnected;
+                            }
+                        }
+                        SystemEffect::Requests(requests) => {
+                            for request in requests {
+                                if !send_request(request, &request_tx, &mut recorder) {
+                                    self.teardown();
+                                    return AppExit::DaemonDisconnected;
+                                }
+                            }
                         }
                         SystemEffect::Bell => {
                             self.make_bell_sound();
                         }
+                        SystemEffect::Redraw => {}
+                        SystemEffect::Notify {
+                            title,
+                            body,
+                            urgency,
+                        } => {
+                            self.notify(title, body, urgency);
+                        }
                         SystemEffect::Exit => {
                             #[cfg(feature = "logging")]
                             log::info!("Exiting.");
                             self.teardown();
-                            return;
+                            return AppExit::Clean;
+                        }
+                        SystemEffect::Batch(batch) => {
+                            for effect in batch.into_iter().rev() {
+                                effects.push_front(effect);
+                            }
                         }
                     }
                 }
             }
 
-            loop {
-                let fabric: Fabric = root.render(self.size);
+            // Whether the rest of a run of repeats of a key (like when a key is held) is still to be
+            // handled, or nothing happened on the last tick.
+            let mut repeating: bool = false;
+            let ticks: Receiver<Instant> = channel::tick(TICK_INTERVAL);
+            // Whether the bell was rung since the last frame was drawn.
+            let mut bell_rung: bool = false;
+
+            'frames: loop {
+                if let Some(working_dir) = root.working_dir().filter(|_| report_working_dir) {
+                    if reported_working_dir.as_ref() != Some(&working_dir) {
+                        self.report_working_dir(&working_dir);
+                        reported_working_dir = Some(working_dir);
+                    }
+                }
+
+                // NOTE: A frame is not drawn between the repeats of a key that were already sent,
+                // so that holding a key (like `j` in a huge directory) doesn't queue up frames
+                // which lag behind releasing it. The run of repeats is handled at once and the
+                // latest position is drawn once instead.
+                if !repeating {
+                    let fabric: Fabric = root.render(self.size);
 
-                self.renderer.render(fabric);
+                    self.renderer.render(fabric);
+                    bell_rung = false;
+                }
 
-                let mut event: Event<Response>;
+                let input: LoopInput<Response>;
                 if let Some(term_event) = self.unused_term_events.pop_front() {
-                    event = Event::TermEvent(term_event);
+                    if let TermEvent::Resize(size) = term_event {
+                        self.size = size;
+                    }
+                    input = LoopInput::Event(Event::TermEvent(term_event));
                 } else {
                     select! {
                         recv(term_event_rx) -> term_event => {
@@ -175,13 +294,14 @@ impl App {
                                 Err(error) => {
                                     #[cfg(feature = "logging")]
                                     log::error!("Error receiving terminal event from channel: {}", error);
+                                    app_exit = AppExit::Fatal(String::from("The terminal stopped sending input."));
                                     break;
                                 }
                             };
                             if let TermEvent::Resize(size) = term_event {
                                 self.size = size;
                             }
-                            event = Event::TermEvent(term_event);
+                            input = LoopInput::Event(Event::TermEvent(term_event));
                         },
                         recv(response_rx) -> response => {
                             let response: Response = match response {
@@ -190,37 +310,160 @@ impl App {
                                 Err(error) => {
                                     #[cfg(feature = "logging")]
                                     log::error!("Error receiving response from channel: {}", error);
+                                    app_exit = AppExit::DaemonDisconnected;
                                     break;
                                 }
                             };
-                            event = Event::Response(response);
+                            input = LoopInput::Event(Event::Response(response));
+                        }
+                        recv(replay_rx) -> replayed_event => {
+                            let event: Event<Response> = match replayed_event {
+                                Ok(replayed_event) => replayed_event,
+                                Err(_) => {
+                                    #[cfg(feature = "logging")]
+                                    log::info!("Done replaying events.");
+                                    replay_rx = channel::never();
+                                    continue;
+                                }
+                            };
+                            if let Event::TermEvent(TermEvent::Resize(size)) = &event {
+                                self.size = *size;
+                            }
+                            input = LoopInput::Event(event);
+                        }
+                        recv(background_rx) -> done => {
+                            // NOTE: The channel can't disconnect because a sender is kept here.
+                            input = LoopInput::BackgroundDone(done.unwrap());
+                        }
+                        recv(ticks) -> _ => {
+                            input = LoopInput::Tick;
                         }
                     }
                 }
 
-                let effect: Option<SystemEffect<Request>> = root.handle(event);
-                match effect {
-                    Some(SystemEffect::RunProgram { program }) => {
-                        let size_before = self.size;
-                        self.run_program::<Response>(program, &term_event_rx);
-                        if self.size != size_before {
-                            // NOTE: We don't handle the effect if one is generated from the resize.
-                            event = Event::TermEvent(TermEvent::Resize(self.size));
-                            let _effect: Option<SystemEffect<Request>> = root.handle(event);
+                let effect: Option<SystemEffect<Request>> = match input {
+                    LoopInput::Event(Event::TermEvent(TermEvent::KeyEvent(key_event))) => {
+                        let mut count: usize = 1 + take_repeats(
+                            &key_event,
+                            &mut self.unused_term_events,
+                            &term_event_rx,
+                        );
+                        // NOTE: The repeats are handled until one of them has an effect, and the
+                        // rest are handled after the effect is.
+                        let mut effect: Option<SystemEffect<Request>> = None;
+                        while count > 0 && effect.is_none() {
+                            let event = Event::TermEvent(TermEvent::KeyEvent(key_event.clone()));
+                            if let Some(recorder) = &mut recorder {
+                                recorder.record_event(&event);
+                            }
+                            effect = root.handle(event);
+                            count -= 1;
                         }
+                        for _ in 0..count {
+                            let term_event = TermEvent::KeyEvent(key_event.clone());
+                            self.unused_term_events.push_front(term_event);
+                        }
+                        repeating = count > 0;
+                        effect
                     }
-                    Some(SystemEffect::Request(request)) => {
-                        request_tx.send(request).unwrap();
+                    LoopInput::Event(event) => {
+                        if let Some(recorder) = &mut recorder {
+                            recorder.record_event(&event);
+                        }
+                        repeating = false;
+                        root.handle(event)
                     }
-                    Some(SystemEffect::Bell) => {
-                        self.make_bell_sound();
+                    LoopInput::BackgroundDone(done) => {
+                        background_pids.remove(&done.uuid);
+                        repeating = false;
+                        root.handle_background_done(done)
                     }
-                    Some(SystemEffect::Exit) => {
-                        #[cfg(feature = "logging")]
-                        log::info!("Exiting.");
-                        break;
+                    LoopInput::Tick => {
+                        let effect: Option<SystemEffect<Request>> = root.handle_tick();
+                        repeating = effect.is_none();
+                        effect
+                    }
+                };
+                // NOTE: The effects of a batch are handled in order (before any others).
+                let mut effects: VecDeque<SystemEffect<Request>> = effect.into_iter().collect();
+                while let Some(effect) = effects.pop_front() {
+                    match effect {
+                        SystemEffect::RunProgram { program } => {
+                            let stderr: Option<String> = self.run_program(program, &term_event_rx);
+                            // NOTE: The root is always told the size after a program is run (even if it
+                            // did not change) so that it can react to what the program did (for
+                            // example mounting a file system), and then given the stderr of the
+                            // program if it was captured. Only a request is handled from the effects.
+                            let event = Event::TermEvent(TermEvent::Resize(self.size));
+                            let effects = [
+                                root.handle(event),
+                                stderr.and_then(|stderr| root.handle_program_stderr(stderr)),
+                            ];
+                            let sent: bool = effects.into_iter().all(|effect| match effect {
+                                Some(SystemEffect::Request(request)) => {
+                                    send_request(request, &request_tx, &mut recorder)
+                                }
+                                _ => true,
+                            });
+                            if !sent {
+                                app_exit = AppExit::DaemonDisconnected;
+                                break 'frames;
+                            }
+                        }
+                        SystemEffect::SpawnBackground { uuid, program } => {
+                            if let Some(pid) =
+                                background::spawn(uuid, program, background_tx.clone())
+                            {
+                                background_pids.insert(uuid, pid);
+                            }
+                        }
+                        SystemEffect::KillBackground { uuid } => {
+                            if let Some(pid) = background_pids.get(&uuid) {
+                                background::kill(*pid);
+                            }
+                        }
+                        SystemEffect::Request(request) => {
+                            let sent: bool = send_request(request, &request_tx, &mut recorder);
+                            if !sent {
+                                app_exit = AppExit::DaemonDisconnected;
+                                break 'frames;
+                            }
+                        }
+                        SystemEffect::Requests(requests) => {
+                            let sent: bool = requests
+                                .into_iter()
+                                .all(|request| send_request(request, &request_tx, &mut recorder));
+                            if !sent {
+                                app_exit = AppExit::DaemonDisconnected;
+                                break 'frames;
+                            }
+                        }
+                        // NOTE: The bell is rung at most once per frame (so that a held key doesn't
+                        // ring it over and over again).
+                        SystemEffect::Bell if !bell_rung => {
+                            self.make_bell_sound();
+                            bell_rung = true;
+                        }
+                        SystemEffect::Bell => {}
+                        SystemEffect::Redraw => {}
+                        SystemEffect::Notify {
+                            title,
+                            body,
+                            urgency,
+                        } => {
+                            self.notify(title, body, urgency);
+                        }
+                        SystemEffect::Exit => {
+                            #[cfg(feature = "logging")]
+                            log::info!("Exiting.");
+                            break 'frames;
+                        }
+                        SystemEffect::Batch(batch) => {
+                            for effect in batch.into_iter().rev() {
+                                effects.push_front(effect);
+                            }
+                        }
                     }
-                    None => {}
                 }
             }
         }
@@ -260,6 +503,7 @@ impl App {
         }
 
         self.teardown();
+        app_exit
     }
 
     fn set_up(&mut self) {
@@ -268,23 +512,26 @@ impl App {
         self.term.enable_raw().unwrap();
         self.lazy_hide_cursor();
         self.lazy_clear_screen();
+        self.lazy_enable_focus_reporting();
 
         self.change_panic_hook();
     }
 
     fn teardown(&mut self) {
+        self.lazy_disable_focus_reporting();
         self.lazy_disable_alternate_terminal();
         self.term.restore_attrs().unwrap();
         self.lazy_show_cursor();
     }
 
+    /// Run the program. Return its stderr if it is captured and the program wrote any.
     // NOTE: clippy gets confused by the fork and complains some code is unreachable b/c of it.
     #[allow(unreachable_code)]
-    fn run_program<Response>(
+    fn run_program(
         &mut self,
         program: Box<dyn Program>,
         term_event_rx: &Receiver<TermEvent>,
-    ) {
+    ) -> Option<String> {
         let program_uuid: Uuid = Uuid::new_v4();
 
         #[cfg(feature = "logging")]
@@ -297,6 +544,22 @@ impl App {
 
         let stdout_pipe: Option<Box<dyn StdoutPipe>> = program.stdout_pipe();
 
+        // The read and write sides of a pipe for the stderr of the program (if it is captured).
+        let mut stderr_pipe: Option<(RawFd, RawFd)> = None;
+        if program.capture_stderr() {
+            match pipe() {
+                Ok(pipe) => stderr_pipe = Some(pipe),
+                #[allow(unused_variables)]
+                Err(error) => {
+                    #[cfg(feature = "logging")]
+                    log::warn!(
+                        "Failed to make a pipe for the stderr of the program: {}",
+                        error
+                    );
+                }
+            }
+        }
+
         let filename: OsString = program.filename();
         let mut args: Vec<OsString> = vec![filename.clone()];
         args.extend(program.args());
@@ -353,6 +616,12 @@ impl App {
                     }
                 }
 
+                if let Some((stderr_read, stderr_write)) = stderr_pipe {
+                    let _ = close(stderr_read);
+                    let _ = dup2(stderr_write, 2);
+                    let _ = close(stderr_write);
+                }
+
                 // Execute the program.
                 execvp(&filename, &args).unwrap();
             }
@@ -360,7 +629,11 @@ impl App {
             Err(error) => {
                 #[cfg(feature = "logging")]
                 log::error!("Failed to fork program: {}", error);
-                return;
+                if let Some((stderr_read, stderr_write)) = stderr_pipe {
+                    let _ = close(stderr_read);
+                    let _ = close(stderr_write);
+                }
+                return None;
             }
         }
 
@@ -379,6 +652,26 @@ impl App {
             .spawn(move || program_monitor.run())
             .unwrap();
 
+        // Spawn a thread that reads the stderr of the program (if it is captured).
+        let mut stderr_rx: Receiver<Vec<u8>> = channel::never();
+        let mut stderr: Option<Vec<u8>> = None;
+        if let Some((stderr_read, stderr_write)) = stderr_pipe {
+            let _ = close(stderr_write);
+            let (chunk_tx, chunk_rx): (Sender<Vec<u8>>, Receiver<Vec<u8>>) = channel::unbounded();
+            let mut stderr_capture = StderrCapture::builder()
+                .stderr(unsafe { File::from_raw_fd(stderr_read) })
+                .chunk_tx(chunk_tx)
+                .build();
+            // NOTE: The thread is not joined because a child of the program (like a daemon) may
+            // keep the pipe open after the program exits.
+            thread::Builder::new()
+                .name("program-stderr-capture".to_string())
+                .spawn(move || stderr_capture.run())
+                .unwrap();
+            stderr_rx = chunk_rx;
+            stderr = Some(Vec::new());
+        }
+
         let mut master_stdin: File;
         let mut master_stdout: File;
         unsafe {
@@ -449,6 +742,15 @@ impl App {
                         };
                         ProgramLoopEvent::ProgramEvent(program_event)
                     }
+                    recv(stderr_rx) -> chunk => {
+                        match chunk {
+                            Ok(chunk) => ProgramLoopEvent::Stderr(chunk),
+                            Err(_) => {
+                                stderr_rx = channel::never();
+                                continue;
+                            }
+                        }
+                    }
                 }
             };
 
@@ -494,6 +796,8 @@ impl App {
                             log::debug!("Signaled terminal resize to program.");
                         };
                     }
+                    // NOTE: Programs are not told about focus changes.
+                    TermEvent::FocusGained | TermEvent::FocusLost => {}
                 },
                 ProgramLoopEvent::ProgramEvent(program_event) => match program_event {
                     ProgramEvent::Done => {
@@ -502,6 +806,20 @@ impl App {
                         break;
                     }
                 },
+                ProgramLoopEvent::Stderr(chunk) => {
+                    self.tee_stderr(&chunk, &mut stderr);
+                }
+            }
+        }
+
+        // Read the rest of the stderr of the program.
+        loop {
+            select! {
+                recv(stderr_rx) -> chunk => match chunk {
+                    Ok(chunk) => self.tee_stderr(&chunk, &mut stderr),
+                    Err(_) => break,
+                },
+                default(STDERR_DRAIN_TIMEOUT) => break,
             }
         }
 
@@ -521,6 +839,31 @@ impl App {
 
         #[cfg(feature = "logging")]
         log::info!("Done running program.");
+
+        stderr
+            .filter(|stderr| !stderr.is_empty())
+            .map(|stderr| String::from_utf8_lossy(&stderr).into_owned())
+    }
+
+    /// Show a chunk of the stderr of a program in the terminal and add it to the captured stderr.
+    fn tee_stderr(&mut self, chunk: &[u8], stderr: &mut Option<Vec<u8>>) {
+        // NOTE: The terminal is raw so it doesn't return the carriage at the end of a line.
+        let mut output: Vec<u8> = Vec::with_capacity(chunk.len());
+        for byte in chunk {
+            if *byte == b'\n' {
+                output.push(b'\r');
+            }
+            output.push(*byte);
+        }
+        let _ = self.stdout.write_all(&output);
+        self.update_terminal();
+
+        if let Some(stderr) = stderr {
+            stderr.extend_from_slice(chunk);
+            if stderr.len() > MAX_CAPTURED_STDERR {
+                stderr.drain(..stderr.len() - MAX_CAPTURED_STDERR);
+            }
+        }
     }
 
     /// Run set up for a program.
@@ -572,6 +915,14 @@ impl App {
         self.stdout.queue(LeaveAlternateScreen).unwrap();
     }
 
+    fn lazy_enable_focus_reporting(&mut self) {
+        self.stdout.queue(Print(ENABLE_FOCUS_REPORTING)).unwrap();
+    }
+
+    fn lazy_disable_focus_reporting(&mut self) {
+        self.stdout.queue(Print(DISABLE_FOCUS_REPORTING)).unwrap();
+    }
+
     fn lazy_clear_screen(&mut self) {
         self.stdout
             .queue(ClearTerminal(TerminalClearType::All))
@@ -594,6 +945,41 @@ impl App {
         self.stdout.execute(Print(ASCII::Bell)).unwrap();
     }
 
+    /// Show a desktop notification (in another thread so that waiting for the notification server
+    /// doesn't block rendering).
+    fn notify(&mut self, title: String, body: String, urgency: Urgency) {
+        if self.terminal_notifications {
+            self.stdout
+                .queue(Print(notification_sequence(&title, &body)))
+                .unwrap();
+            self.update_terminal();
+            return;
+        }
+
+        thread::spawn(move || {
+            let mut notification = Notification::new();
+            notification.summary(&title).body(&body);
+            #[cfg(all(unix, not(target_os = "macos")))]
+            notification.urgency(match urgency {
+                Urgency::Low => notify_rust::Urgency::Low,
+                Urgency::Normal => notify_rust::Urgency::Normal,
+                Urgency::Critical => notify_rust::Urgency::Critical,
+            });
+            #[cfg(target_os = "macos")]
+            let _ = urgency;
+            #[allow(unused_variables)]
+            if let Err(error) = notification.show() {
+                #[cfg(feature = "logging")]
+                log::warn!("Failed to show a notification: {}", error);
+            }
+        });
+    }
+
+    fn report_working_dir(&mut self, dir: &Path) {
+        self.stdout.queue(Print(working_dir_sequence(dir))).unwrap();
+        self.update_terminal();
+    }
+
     fn update_terminal(&mut self) {
         self.stdout.flush().unwrap();
     }
@@ -602,6 +988,7 @@ impl App {
         let hook_before = panic::take_hook();
         panic::set_hook(Box::new(move |info| {
             let mut stdout = io::stdout();
+            stdout.queue(Print(DISABLE_FOCUS_REPORTING)).unwrap();
             stdout.queue(LeaveAlternateScreen).unwrap();
             stdout.queue(ShowCursor).unwrap();
             stdout.flush().unwrap();
@@ -611,6 +998,45 @@ impl App {
     }
 }
 
+/// Take the repeats of the key event (like when a key is held) that were already sent from the
+/// unused terminal events and then from the channel, and return how many were taken. The first event
+/// which isn't a repeat is left at the front of the unused terminal events.
+fn take_repeats(
+    key_event: &KeyEvent,
+    unused_term_events: &mut VecDeque<TermEvent>,
+    term_event_rx: &Receiver<TermEvent>,
+) -> usize {
+    let mut count: usize = 0;
+    loop {
+        if unused_term_events.is_empty() {
+            match term_event_rx.try_recv() {
+                Ok(term_event) => unused_term_events.push_back(term_event),
+                Err(_) => return count,
+            }
+        }
+        match unused_term_events.front() {
+            Some(TermEvent::KeyEvent(next)) if next == key_event => {
+                unused_term_events.pop_front();
+                count += 1;
+            }
+            _ => return count,
+        }
+    }
+}
+
+/// Record the request (if the events are being recorded) and send it to the requester. Return
+/// whether it was sent (it isn't if the requester stopped, like when the daemon disconnected).
+fn send_request<Request, Response>(
+    request: Request,
+    request_tx: &Sender<Request>,
+    recorder: &mut Option<Box<dyn Recorder<Request, Response>>>,
+) -> bool {
+    if let Some(recorder) = recorder {
+        recorder.record_request(&request);
+    }
+    request_tx.send(request).is_ok()
+}
+
 #[derive(TypedBuilder)]
 pub struct AppRunOptions<Props, Request, Response>
 where
@@ -640,9 +1066,80 @@ where
     /// Stops the responses handler.
     #[builder(default, setter(into))]
     response_handler_stopper: Option<Box<dyn Stopper>>,
+
+    /// Records the events and requests (for replaying them later).
+    #[builder(default, setter(into))]
+    recorder: Option<Box<dyn Recorder<Request, Response>>>,
+
+    /// Replays recorded events.
+    #[builder(default, setter(into))]
+    replayer: Option<Box<dyn Replayer<Response>>>,
+
+    /// Whether the working directory of the root is reported to the terminal (with OSC 7).
+    #[builder(default)]
+    report_working_dir: bool,
+
+    /// Whether notifications are shown by the terminal (with OSC 9) instead of as desktop
+    /// notifications.
+    #[builder(default)]
+    terminal_notifications: bool,
 }
 
 enum ProgramLoopEvent {
     TermEvent(TermEvent),
     ProgramEvent(ProgramEvent),
+    /// A chunk of the stderr of the program (if it is captured).
+    Stderr(Vec<u8>),
+}
+
+/// What the main loop of the app handles next.
+enum LoopInput<Response> {
+    Event(Event<Response>),
+    /// A program that was spawned in the background exited.
+    BackgroundDone(BackgroundDone),
+    /// The tick interval passed.
+    Tick,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use term::{Key, KeyMods};
+
+    fn key(character: char) -> TermEvent {
+        TermEvent::KeyEvent(KeyEvent {
+            key: Key::Char(character),
+            mods: KeyMods::NONE,
+        })
+    }
+
+    #[test]
+    fn test_take_repeats() {
+        let j = KeyEvent {
+            key: Key::Char('j'),
+            mods: KeyMods::NONE,
+        };
+        let (term_event_tx, term_event_rx): (Sender<TermEvent>, Receiver<TermEvent>) =
+            channel::unbounded();
+        let mut unused_term_events: VecDeque<TermEvent> = VecDeque::from([key('j')]);
+        for character in ['j', 'j', 'k', 'j'] {
+            term_event_tx.send(key(character)).unwrap();
+        }
+
+        assert_eq!(take_repeats(&j, &mut unused_term_events, &term_event_rx), 3);
+        assert!(matches!(
+            unused_term_events.front(),
+            Some(TermEvent::KeyEvent(KeyEvent {
+                key: Key::Char('k'),
+                ..
+            }))
+        ));
+        assert_eq!(unused_term_events.len(), 1);
+
+        unused_term_events.clear();
+        assert_eq!(take_repeats(&j, &mut unused_term_events, &term_event_rx), 1);
+        assert_eq!(take_repeats(&j, &mut unused_term_events, &term_event_rx), 0);
+        assert!(unused_term_events.is_empty());
+    }
 }
@@ -0,0 +1,11 @@
+/// Why the app stopped running.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppExit {
+    /// The root component asked to exit.
+    Clean,
+    /// The connection to the daemon was lost (so no more requests can be sent or responses
+    /// received).
+    DaemonDisconnected,
+    /// Something went wrong that the app can't recover from.
+    Fatal(String),
+}
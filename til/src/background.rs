@@ -0,0 +1,131 @@
+/*!
+This module contains [`spawn`] which runs a [`Program`] in the background (without a terminal and
+without leaving the app), [`kill`] which stops it early, and [`BackgroundDone`] which the root
+component is given when the program exits.
+
+A program in the background runs in its own process group so that it (and the programs that it runs)
+can be killed together.
+*/
+use crate::program::Program;
+
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::thread;
+
+use crossbeam::channel::Sender;
+use nix::sys::signal::{killpg, Signal};
+use nix::unistd::Pid;
+use uuid::Uuid;
+
+/// The most bytes of the output of a program that are kept (the last bytes are kept).
+const MAX_OUTPUT: usize = 64 * 1024;
+
+/// What a program that ran in the background did.
+pub struct BackgroundDone {
+    /// The uuid that the program was spawned with.
+    pub uuid: Uuid,
+    /// The status that the program exited with, or the error that kept it from running.
+    pub status: Result<ExitStatus, String>,
+    /// The end of the stdout of the program.
+    pub stdout: String,
+    /// The end of the stderr of the program.
+    pub stderr: String,
+}
+
+/// Start the program (with no input) and send what it did once it exits. Return the process id of
+/// the program (which is also the id of its process group) if it started.
+pub fn spawn(
+    uuid: Uuid,
+    program: Box<dyn Program>,
+    done_tx: Sender<BackgroundDone>,
+) -> Option<Pid> {
+    #[cfg(feature = "logging")]
+    log::info!("Running program {} in the background...", uuid);
+
+    let child: Child = match command(program.as_ref()).spawn() {
+        Ok(child) => child,
+        Err(error) => {
+            let _ = done_tx.send(BackgroundDone {
+                uuid,
+                status: Err(error.to_string()),
+                stdout: String::new(),
+                stderr: String::new(),
+            });
+            return None;
+        }
+    };
+    let pid = Pid::from_raw(child.id() as i32);
+
+    thread::Builder::new()
+        .name("background-program".to_string())
+        .spawn(move || {
+            let done: BackgroundDone = wait(uuid, child);
+            // NOTE: The app may have exited while the program was running.
+            let _ = done_tx.send(done);
+        })
+        .unwrap();
+
+    Some(pid)
+}
+
+/// Ask the program with the process id (and the programs that it ran) to terminate.
+pub fn kill(pid: Pid) {
+    #[allow(unused_variables)]
+    if let Err(error) = killpg(pid, Signal::SIGTERM) {
+        #[cfg(feature = "logging")]
+        log::warn!("Failed to kill background program {}: {}", pid, error);
+    }
+}
+
+/// Return the command for running the program in its own process group.
+fn command(program: &dyn Program) -> Command {
+    let mut command = Command::new(program.filename());
+    command
+        .args(program.args())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0);
+    if let Some(cwd) = program.cwd() {
+        command.current_dir(cwd);
+    }
+    for env_var in program.env() {
+        command.env(
+            OsStr::from_bytes(env_var.name.as_bytes()),
+            OsStr::from_bytes(env_var.value.as_bytes()),
+        );
+    }
+    command
+}
+
+/// Wait for the program to exit (reading its output).
+fn wait(uuid: Uuid, child: Child) -> BackgroundDone {
+    let output: Result<Output, _> = child.wait_with_output();
+    let done = match output {
+        Ok(output) => BackgroundDone {
+            uuid,
+            status: Ok(output.status),
+            stdout: tail(&output.stdout),
+            stderr: tail(&output.stderr),
+        },
+        Err(error) => BackgroundDone {
+            uuid,
+            status: Err(error.to_string()),
+            stdout: String::new(),
+            stderr: String::new(),
+        },
+    };
+
+    #[cfg(feature = "logging")]
+    log::info!("Program {} finished running in the background.", uuid);
+
+    done
+}
+
+/// Return the last bytes of the output (as text).
+fn tail(output: &[u8]) -> String {
+    let start: usize = output.len().saturating_sub(MAX_OUTPUT);
+    String::from_utf8_lossy(&output[start..]).into_owned()
+}
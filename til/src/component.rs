@@ -1,5 +1,9 @@
+use std::path::PathBuf;
+
 use rend::{Fabric, Size};
 
+use crate::background::BackgroundDone;
+
 pub trait Component<Props, Event, Effect> {
     fn new(props: Props) -> Self
     where
@@ -12,4 +16,29 @@ pub trait Component<Props, Event, Effect> {
     fn handle(&mut self, event: Event) -> Option<Effect>;
 
     fn render(&self, size: Size) -> Fabric;
+
+    /// Return the directory that the component is in (which is reported to the terminal if
+    /// reporting the working directory is enabled).
+    fn working_dir(&self) -> Option<PathBuf> {
+        None
+    }
+
+    /// Handle the stderr of a program that captures it (which is given to the component after the
+    /// program exits if it wrote anything).
+    fn handle_program_stderr(&mut self, _stderr: String) -> Option<Effect> {
+        None
+    }
+
+    /// Handle a program that was spawned in the background exiting.
+    fn handle_background_done(&mut self, _done: BackgroundDone) -> Option<Effect> {
+        None
+    }
+
+    /// Handle time passing (the component is told every [`TICK_INTERVAL`](crate::TICK_INTERVAL)
+    /// so that it can time things out). A frame is only drawn after a tick if there is an effect
+    /// (which can be [`SystemEffect::Redraw`](crate::SystemEffect::Redraw) when what is shown
+    /// changed with time).
+    fn handle_tick(&mut self) -> Option<Effect> {
+        None
+    }
 }
@@ -0,0 +1,110 @@
+/*!
+Component ids identify the instances of components, so that the events for a component (like the
+responses to the requests that it sent) can be routed straight to it instead of being handed down
+through every parent.
+
+Til only gives the ids out. What each id is routed from (like the requests that each component
+sent and when they time out) is kept by the app, which knows what its requests and responses are.
+*/
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The id that is given to the next component.
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// The id of an instance of a component of the type `C` (so that it can't be mixed up with the id
+/// of a component of another type).
+pub struct ComponentId<C> {
+    id: u64,
+    component: PhantomData<fn() -> C>,
+}
+
+impl<C> Default for ComponentId<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> ComponentId<C> {
+    /// Return a new id (which differs from the ids of all of the other components).
+    pub fn new() -> Self {
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            component: PhantomData,
+        }
+    }
+}
+
+// NOTE: These are implemented by hand because deriving them would require the component to
+// implement them too.
+impl<C> Clone for ComponentId<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C> Copy for ComponentId<C> {}
+
+impl<C> PartialEq for ComponentId<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<C> Eq for ComponentId<C> {}
+
+impl<C> Hash for ComponentId<C> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<C> Debug for ComponentId<C> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "ComponentId({})", self.id)
+    }
+}
+
+/// A component and its id.
+pub struct Identified<C> {
+    id: ComponentId<C>,
+    component: C,
+}
+
+impl<C> Identified<C> {
+    /// Give the component a new id.
+    pub fn new(component: C) -> Self {
+        Self {
+            id: ComponentId::new(),
+            component,
+        }
+    }
+
+    pub fn id(&self) -> ComponentId<C> {
+        self.id
+    }
+}
+
+impl<C> Deref for Identified<C> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        &self.component
+    }
+}
+
+impl<C> DerefMut for Identified<C> {
+    fn deref_mut(&mut self) -> &mut C {
+        &mut self.component
+    }
+}
+
+/// Return whether the component (if there is one) has the id.
+pub fn has_id<C>(component: &Option<Identified<C>>, id: ComponentId<C>) -> bool {
+    component
+        .as_ref()
+        .is_some_and(|component| component.id() == id)
+}
@@ -2,27 +2,41 @@
 #![allow(clippy::manual_map)]
 
 mod app;
+mod app_exit;
 mod ascii;
+mod background;
 mod component;
+mod component_id;
 mod event;
+mod modal;
+mod osc;
 mod output_forwarder;
 mod paths;
 mod program;
 mod program_monitor;
+mod recorder;
+mod replayer;
 mod requester;
 mod response_handler;
+mod stderr_capture;
 mod stopper;
 mod system_effect;
 mod term_event_forwarder;
 
-pub use app::{App, AppRunOptions};
+pub use app::{App, AppRunOptions, TICK_INTERVAL};
+pub use app_exit::AppExit;
+pub use background::BackgroundDone;
 pub use component::Component;
+pub use component_id::{has_id, ComponentId, Identified};
 pub use event::Event;
+pub use modal::{Modal, ModalHandling, ModalStack};
 pub use program::{EnvVar, Program, ProgramCleanup, ProgramSetup, StdoutPipe};
+pub use recorder::Recorder;
+pub use replayer::Replayer;
 pub use requester::Requester;
 pub use response_handler::ResponseHandler;
 pub use stopper::Stopper;
-pub use system_effect::SystemEffect;
+pub use system_effect::{SystemEffect, Urgency};
 
 #[macro_use]
 extern crate lazy_static;
@@ -0,0 +1,84 @@
+/*!
+Modals are overlays (like prompts, help, and palettes) which capture the input and are rendered above
+the rest of the application (which is dimmed) until they are closed.
+*/
+use rend::{Fabric, Location, Size};
+
+use crossterm::style::Color;
+
+/// The color of the text under the modals.
+const DIM_COLOR: Color = Color::DarkGrey;
+
+/// An overlay which captures the input while it is open.
+pub trait Modal<Event, Effect> {
+    fn handle(&mut self, event: Event) -> Option<Effect>;
+
+    fn render(&self, size: Size) -> Fabric;
+
+    /// Return the size of the modal on a screen of the size (the whole screen by default).
+    fn size(&self, screen: Size) -> Size {
+        screen
+    }
+}
+
+/// What happened to an event that was given to a [`ModalStack`].
+pub enum ModalHandling<Event, Effect> {
+    /// The top modal captured the event (and this is its effect).
+    Captured(Option<Effect>),
+    /// There are no modals so the event is passed back (to be handled by what is under them).
+    Passed(Event),
+}
+
+/// A stack of modals where the top modal captures the input.
+pub struct ModalStack<Event, Effect> {
+    modals: Vec<Box<dyn Modal<Event, Effect>>>,
+}
+
+impl<Event, Effect> Default for ModalStack<Event, Effect> {
+    fn default() -> Self {
+        Self { modals: Vec::new() }
+    }
+}
+
+impl<Event, Effect> ModalStack<Event, Effect> {
+    /// Open a modal on top of the others.
+    pub fn push(&mut self, modal: Box<dyn Modal<Event, Effect>>) {
+        self.modals.push(modal);
+    }
+
+    /// Close the top modal.
+    pub fn pop(&mut self) -> Option<Box<dyn Modal<Event, Effect>>> {
+        self.modals.pop()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modals.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.modals.len()
+    }
+
+    /// Give the event to the top modal (or pass it back if there are no modals).
+    pub fn handle(&mut self, event: Event) -> ModalHandling<Event, Effect> {
+        match self.modals.last_mut() {
+            Some(modal) => ModalHandling::Captured(modal.handle(event)),
+            None => ModalHandling::Passed(event),
+        }
+    }
+
+    /// Render the modals centered over the base, dimming what is under each modal.
+    pub fn render(&self, mut base: Fabric, screen: Size) -> Fabric {
+        for modal in &self.modals {
+            base.dim(DIM_COLOR);
+            let size: Size = modal.size(screen);
+            let size = Size::new(size.rows.min(screen.rows), size.columns.min(screen.columns));
+            let location = Location::new(
+                (screen.rows - size.rows) / 2,
+                (screen.columns - size.columns) / 2,
+            );
+            base.overlay(&modal.render(size), location);
+        }
+        base
+    }
+}
@@ -0,0 +1,46 @@
+/*!
+Operating system command (OSC) escape sequences which tell the terminal emulator about the
+application.
+*/
+use std::ffi::OsString;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+use nix::unistd::gethostname;
+
+/// Return the OSC 7 escape sequence which tells the terminal the current working directory (so that
+/// for example new tabs can be opened in it).
+pub fn working_dir_sequence(dir: &Path) -> String {
+    let host: String = gethostname()
+        .map(|host: OsString| host.to_string_lossy().to_string())
+        .unwrap_or_default();
+    format!(
+        "\x1b]7;file://{}{}\x1b\\",
+        percent_encode(host.as_bytes()),
+        percent_encode(dir.as_os_str().as_bytes())
+    )
+}
+
+/// Return the OSC 9 escape sequence which asks the terminal to show a notification.
+pub fn notification_sequence(title: &str, body: &str) -> String {
+    let message: String = format!("{}: {}", title, body)
+        .chars()
+        .filter(|character| !character.is_control())
+        .collect();
+    format!("\x1b]9;{}\x07", message)
+}
+
+/// Return the bytes percent encoded for a file URL (where only unreserved characters and `/` are
+/// kept as is).
+fn percent_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len());
+    for byte in bytes {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                encoded.push(*byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
@@ -37,6 +37,13 @@ pub trait Program: Send {
     fn stdout_pipe(&self) -> Option<Box<dyn StdoutPipe>> {
         None
     }
+
+    /// Return whether the stderr of the program is captured. It is still shown in the terminal,
+    /// but is also given to the root component after the program exits (so that errors aren't
+    /// lost when the screen of the app is restored).
+    fn capture_stderr(&self) -> bool {
+        false
+    }
 }
 
 /**
@@ -0,0 +1,8 @@
+use crate::event::Event;
+
+/// Records the events handled by the app and the requests made by it (so that they can be
+/// replayed later).
+pub trait Recorder<Request, Response> {
+    fn record_event(&mut self, event: &Event<Response>);
+    fn record_request(&mut self, request: &Request);
+}
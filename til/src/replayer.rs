@@ -0,0 +1,9 @@
+use crate::event::Event;
+
+use crossbeam::channel::Sender;
+
+/// Replays recorded events to the app (instead of or in addition to the terminal and the
+/// responses).
+pub trait Replayer<Response>: Send {
+    fn run(&mut self, event_tx: Sender<Event<Response>>);
+}
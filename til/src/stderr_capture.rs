@@ -0,0 +1,33 @@
+use std::fs::File;
+use std::io::Read;
+
+use crossbeam::channel::Sender;
+use typed_builder::TypedBuilder;
+
+/// Reads the stderr of a program (from the read side of a pipe) and sends it in chunks until the
+/// program and its children close the pipe.
+#[derive(TypedBuilder)]
+pub struct StderrCapture {
+    stderr: File,
+    chunk_tx: Sender<Vec<u8>>,
+}
+
+impl StderrCapture {
+    pub fn run(&mut self) {
+        #[cfg(feature = "logging")]
+        log::debug!("Stderr capture running...");
+        let mut buffer: [u8; 4096] = [0; 4096];
+        while let Ok(length) = self.stderr.read(&mut buffer) {
+            if length == 0 {
+                break;
+            }
+            // NOTE: The receiver is dropped if a child of the program keeps the pipe open after the
+            // program exits.
+            if self.chunk_tx.send(buffer[..length].to_vec()).is_err() {
+                break;
+            }
+        }
+        #[cfg(feature = "logging")]
+        log::debug!("Stderr capture stopping...");
+    }
+}
@@ -3,6 +3,8 @@ System effects are side-effect that components can emit which the application fr
 */
 use crate::program::Program;
 
+use uuid::Uuid;
+
 /// A side-effect that components can emit which the application framework will handle.
 pub enum SystemEffect<Request> {
     /// Run a program.
@@ -11,12 +13,57 @@ pub enum SystemEffect<Request> {
         program: Box<dyn Program>,
     },
 
+    /// Run a program in the background (without a terminal), and give the root component what it
+    /// did once it exits.
+    SpawnBackground {
+        /// The uuid that the root component is given back along with what the program did.
+        uuid: Uuid,
+        /// The program to run.
+        program: Box<dyn Program>,
+    },
+
+    /// Kill a program that was spawned in the background (if it is still running). The root
+    /// component is still given what it did once it exits.
+    KillBackground {
+        /// The uuid that the program was spawned with.
+        uuid: Uuid,
+    },
+
     /// A request to the backend.
     Request(Request),
 
+    /// Several requests to the backend (which are sent in order).
+    Requests(Vec<Request>),
+
     /// Make the bell sound.
     Bell,
 
+    /// Draw a frame (when what is shown changed without anything else happening, like a message
+    /// that is hidden after a while).
+    Redraw,
+
+    /// Show a desktop notification.
+    Notify {
+        /// The title of the notification.
+        title: String,
+        /// The body of the notification.
+        body: String,
+        /// How urgent the notification is.
+        urgency: Urgency,
+    },
+
     /// Exit Insh.
     Exit,
+
+    /// Several effects (which are handled in order).
+    Batch(Vec<SystemEffect<Request>>),
+}
+
+/// How urgent a desktop notification is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Urgency {
+    Low,
+    #[default]
+    Normal,
+    Critical,
 }
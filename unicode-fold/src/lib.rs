@@ -0,0 +1,53 @@
+/*!
+Unicode aware normalization of text for matching (used by the finder and the searcher).
+
+The same text can be encoded in different ways, for example `é` is either a single code point or an
+`e` followed by a combining accent (which is what some file systems store). Normalizing both the
+pattern and the text to NFC makes them compare equal.
+
+Folding also applies full case folding, so that for example `STRASSE` matches `straße`.
+*/
+use std::borrow::Cow;
+
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
+/// Return the text normalized to NFC (borrowed if it already is).
+pub fn normalize(text: &str) -> Cow<'_, str> {
+    match is_nfc_quick(text.chars()) {
+        IsNormalized::Yes => Cow::Borrowed(text),
+        IsNormalized::No | IsNormalized::Maybe => Cow::Owned(text.nfc().collect()),
+    }
+}
+
+/// Return the text case folded and normalized to NFC (for caseless matching).
+///
+/// Folding never adds or removes newlines, so the lines of folded text correspond to the lines of
+/// the text.
+pub fn fold(text: &str) -> String {
+    // NOTE: The text is decomposed first (like Unicode canonical caseless matching) so that
+    // characters whose folding is only defined for their decomposed form are folded too.
+    let decomposed: String = text.nfd().collect();
+    caseless::default_case_fold_str(&decomposed).nfc().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("cafe", "cafe"; "ascii")]
+    #[test_case("caf\u{e9}", "caf\u{e9}"; "composed")]
+    #[test_case("cafe\u{301}", "caf\u{e9}"; "decomposed")]
+    fn test_normalize(text: &str, expected: &str) {
+        assert_eq!(normalize(text), expected);
+    }
+
+    #[test_case("Cargo.TOML", "cargo.toml"; "ascii")]
+    #[test_case("CAFE\u{301}", "caf\u{e9}"; "decomposed accent")]
+    #[test_case("Stra\u{df}e", "strasse"; "sharp s")]
+    #[test_case("\u{3a3}\u{399}\u{3a3}", "\u{3c3}\u{3b9}\u{3c3}"; "greek")]
+    #[test_case("a\nB\r\n", "a\nb\r\n"; "newlines")]
+    fn test_fold(text: &str, expected: &str) {
+        assert_eq!(fold(text), expected);
+    }
+}